@@ -15,7 +15,7 @@ fn main() {
     println!("HP modifier: {:+}", human.hp_modifier());
     println!("Evasion modifier: {:+}", human.evasion_modifier());
     println!("Can fly: {}", human.has_flight());
-    println!("Foundation abilities: {:?}", human.foundation_abilities());
+    println!("Foundation abilities: {:?}", feature_names(human));
 
     // Special ancestries
     println!("\n2. Special ancestries with modifiers:");
@@ -27,7 +27,7 @@ fn main() {
         giant.hp_modifier()
     );
     println!("  Evasion: {:+}", giant.evasion_modifier());
-    println!("  Abilities: {:?}", giant.foundation_abilities());
+    println!("  Abilities: {:?}", feature_names(giant));
 
     let simiah = Ancestry::Simiah;
     println!("\n{} - The nimble climbers", simiah);
@@ -36,14 +36,14 @@ fn main() {
         "  Evasion: {:+} (nimble and agile)",
         simiah.evasion_modifier()
     );
-    println!("  Abilities: {:?}", simiah.foundation_abilities());
+    println!("  Abilities: {:?}", feature_names(simiah));
 
     let faerie = Ancestry::Faerie;
     println!("\n{} - The fey folk", faerie);
     println!("  HP modifier: {:+}", faerie.hp_modifier());
     println!("  Evasion: {:+}", faerie.evasion_modifier());
     println!("  Can fly: {} (innate flight!)", faerie.has_flight());
-    println!("  Abilities: {:?}", faerie.foundation_abilities());
+    println!("  Abilities: {:?}", feature_names(faerie));
 
     // All ancestries
     println!("\n3. All {} ancestries:", Ancestry::iter().count());
@@ -51,7 +51,7 @@ fn main() {
         let hp_mod = ancestry.hp_modifier();
         let ev_mod = ancestry.evasion_modifier();
         let flight = if ancestry.has_flight() { "✈" } else { " " };
-        let abilities = ancestry.foundation_abilities();
+        let abilities = feature_names(ancestry);
 
         println!(
             "  {:12} {} | HP:{:+2} Evasion:{:+2} | {}",
@@ -138,7 +138,7 @@ fn main() {
     for (ancestry, description) in highlights {
         println!("\n  {}:", ancestry);
         println!("    → {}", description);
-        println!("    Abilities: {:?}", ancestry.foundation_abilities());
+        println!("    Abilities: {:?}", feature_names(ancestry));
     }
 
     // Character concept examples
@@ -166,3 +166,7 @@ fn main() {
 
     println!("\n=== Ancestry System Demonstration Complete ===");
 }
+
+fn feature_names(ancestry: Ancestry) -> Vec<&'static str> {
+    ancestry.features().iter().map(|f| f.name).collect()
+}
@@ -55,8 +55,8 @@ fn main() {
     println!("  Domains: {} & {}", d1, d2);
 
     println!("\nAncestry Abilities:");
-    for ability in elara_ancestry.foundation_abilities() {
-        println!("  • {}", ability);
+    for feature in elara_ancestry.features() {
+        println!("  • {}", feature.name);
     }
 
     println!("\nConcept: A charismatic human bard who uses music and stories");
@@ -113,8 +113,8 @@ fn main() {
     println!("  Domains: {} & {}", d1, d2);
 
     println!("\nAncestry Abilities:");
-    for ability in grunk_ancestry.foundation_abilities() {
-        println!("  • {}", ability);
+    for feature in grunk_ancestry.features() {
+        println!("  • {}", feature.name);
     }
 
     println!("\nConcept: A towering giant who serves as an unbreakable wall");
@@ -171,8 +171,8 @@ fn main() {
     println!("  Domains: {} & {}", d1, d2);
 
     println!("\nAncestry Abilities:");
-    for ability in whisper_ancestry.foundation_abilities() {
-        println!("  • {}", ability);
+    for feature in whisper_ancestry.features() {
+        println!("  • {}", feature.name);
     }
 
     println!("\nConcept: A monkey-like rogue who uses tail and climbing ability");
@@ -230,8 +230,8 @@ fn main() {
     println!("  Flight: {} (innate!)", spark_ancestry.has_flight());
 
     println!("\nAncestry Abilities:");
-    for ability in spark_ancestry.foundation_abilities() {
-        println!("  • {}", ability);
+    for feature in spark_ancestry.features() {
+        println!("  • {}", feature.name);
     }
 
     println!("\nConcept: A tiny faerie wizard who combines arcane study with");
@@ -7,7 +7,7 @@
 //! - Working with JSON save files
 
 use daggerheart_engine::character::{Ancestry, Attributes, CharacterProgress, Class};
-use daggerheart_engine::combat::simulation::{CombatEncounter, Combatant};
+use daggerheart_engine::combat::simulation::{CombatEncounter, Combatant, CombatantBuilder};
 
 fn main() {
     println!("=== Daggerheart Save/Load System ===\n");
@@ -32,19 +32,17 @@ fn example_save_character() {
     println!("EXAMPLE 1: Save and Load Character\n");
 
     // Create a character
-    let warrior = Combatant::player(
-        "Grom the Mighty",
-        5,
-        Class::Warrior,
-        Ancestry::Orc,
-        Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap(),
-    )
-    .with_armor(3);
+    let warrior = CombatantBuilder::player("Grom the Mighty", Class::Warrior, Ancestry::Orc)
+        .with_level(5)
+        .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+        .with_armor(3)
+        .build()
+        .unwrap();
 
     println!("Created character: {}", warrior.name);
     println!("  Level: {}", warrior.level);
     println!("  HP: {}/{}", warrior.hp.current, warrior.hp.maximum);
-    println!("  Class: {}", warrior.class);
+    println!("  Class: {}", warrior.class().unwrap());
 
     // Save to file
     let save_path = "grom_character.json";
@@ -60,7 +58,7 @@ fn example_save_character() {
         "  HP: {}/{}",
         loaded_warrior.hp.current, loaded_warrior.hp.maximum
     );
-    println!("  Class: {}", loaded_warrior.class);
+    println!("  Class: {}", loaded_warrior.class().unwrap());
 
     // Clean up
     std::fs::remove_file(save_path).ok();
@@ -105,16 +103,23 @@ fn example_save_encounter() {
     let mut encounter = CombatEncounter::new(5);
 
     // Add combatants
-    let warrior = Combatant::player(
-        "Elara",
-        3,
-        Class::Bard,
-        Ancestry::Human,
-        Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap(),
-    );
-
-    let goblin1 = Combatant::enemy("Goblin Scout", 1, 4, 13, 1);
-    let goblin2 = Combatant::enemy("Goblin Archer", 1, 3, 14, 0);
+    let warrior = CombatantBuilder::player("Elara", Class::Bard, Ancestry::Human)
+        .with_level(3)
+        .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+        .build()
+        .unwrap();
+
+    let goblin1 = CombatantBuilder::adversary("Goblin Scout")
+        .with_hp(4)
+        .with_evasion(13)
+        .with_armor(1)
+        .build()
+        .unwrap();
+    let goblin2 = CombatantBuilder::adversary("Goblin Archer")
+        .with_hp(3)
+        .with_evasion(14)
+        .build()
+        .unwrap();
 
     encounter.add_combatant(warrior);
     encounter.add_combatant(goblin1);
@@ -149,7 +154,7 @@ fn example_save_encounter() {
         println!(
             "  - {} ({}): HP {}/{}, Initiative {}",
             combatant.name,
-            if combatant.is_player {
+            if combatant.is_player() {
                 "Player"
             } else {
                 "Enemy"
@@ -171,13 +176,10 @@ fn example_persistence() {
 
     // Session 1: Create character and gain some XP
     println!("📖 Session 1:");
-    let mut warrior = Combatant::player(
-        "Theron",
-        1,
-        Class::Ranger,
-        Ancestry::Faerie,
-        Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap(),
-    );
+    let mut warrior = CombatantBuilder::player("Theron", Class::Ranger, Ancestry::Faerie)
+        .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+        .build()
+        .unwrap();
 
     let mut progress = CharacterProgress::new();
     progress.add_experience(120);
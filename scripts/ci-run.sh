@@ -106,6 +106,13 @@ if [ "$RUN_TESTS" = "true" ]; then
     else
         print_error "Unit tests failed"
     fi
+
+    print_step "Testing feature subsets..."
+    for features in "" std persistence content solo parallel; do
+        if ! cargo test --no-default-features --features "$features"; then
+            print_error "Tests with only '$features' failed"
+        fi
+    done
 fi
 
 # Doc tests
@@ -159,6 +166,20 @@ if [ "$RUN_BUILD" = "true" ]; then
     else
         print_error "Build with all features failed"
     fi
+
+    print_step "Building no_std core..."
+    if cargo build --lib --no-default-features --verbose; then
+        print_success "no_std build succeeded"
+    else
+        print_error "no_std build failed"
+    fi
+
+    print_step "Building feature subsets..."
+    for features in std persistence content; do
+        if ! cargo build --lib --no-default-features --features "$features" --verbose; then
+            print_error "Build with only '$features' failed"
+        fi
+    done
 fi
 
 # Exit with failure if any step failed
@@ -0,0 +1,629 @@
+//! Session stats - the numbers a table shares at the end of a session
+//!
+//! [`SessionStats`] tallies the campaign roll log and the event logs of
+//! the encounters played: how often the dice came up critical, how Hope
+//! rolls stack up against Fear rolls, how hard each player hit, and which
+//! domain cards saw the most play. [`Campaign::session_stats`] gathers
+//! them for one session and [`Campaign::stats`] for the whole campaign.
+//!
+//! [`FairnessReport`] audits the dice themselves: it counts the faces each
+//! die type has landed on and runs a chi-square test against an even
+//! spread, so a table playing online can check a suspicious streak.
+//! Duality rolls keep the face of each die rolled, Hope, Fear and any
+//! advantage d6, and encounter attacks keep their damage dice too, so the
+//! roll log and encounters can be audited after the fact.
+
+use crate::campaign::{Campaign, EncounterRecord, RollRecord};
+use crate::combat::events::CombatEvent;
+use crate::combat::simulation::{CombatEncounter, CombatantId};
+use crate::core::dice::{ControllingDie, Die};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// One player's numbers
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PlayerStats {
+    pub name: String,
+    /// Duality rolls, attacks included
+    pub rolls: u32,
+    pub criticals: u32,
+    pub attacks: u32,
+    pub hits: u32,
+    /// Damage rolled on hits, before armor
+    pub damage: u32,
+}
+
+impl PlayerStats {
+    /// Damage per attack made, misses counting as none
+    pub fn average_damage(&self) -> f64 {
+        ratio(self.damage, self.attacks)
+    }
+}
+
+/// How often a domain card was played
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CardUse {
+    pub card: String,
+    pub uses: u32,
+}
+
+/// Totals over rolls and encounters
+///
+/// Rolls are the logged duality rolls plus the attack rolls players made
+/// in encounters; adversary attacks are left out.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::analytics::SessionStats;
+/// use daggerheart_engine::campaign::Campaign;
+/// use daggerheart_engine::core::dice::DualityRoll;
+///
+/// let mut campaign = Campaign::new("The Witherwild", 5);
+/// campaign.log_roll("Grom", "Leap the chasm", DualityRoll::from_values(7, 7).with_modifier(2));
+/// campaign.log_roll("Grom", "Search", DualityRoll::from_values(3, 9).with_modifier(0));
+/// campaign.log_roll("Lia", "Persuade", DualityRoll::from_values(10, 4).with_modifier(1));
+///
+/// let stats = campaign.session_stats(None).unwrap();
+/// assert_eq!(stats.rolls, 3);
+/// assert_eq!(stats.crit_rate(), 1.0 / 3.0);
+/// // The critical counts with Hope
+/// assert_eq!(stats.hope_fear_ratio(), Some(2.0));
+/// assert_eq!(stats.player("grom").unwrap().criticals, 1);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SessionStats {
+    pub rolls: u32,
+    pub criticals: u32,
+    /// Rolls where Hope was higher, criticals included
+    pub with_hope: u32,
+    /// Rolls where Fear was higher
+    pub with_fear: u32,
+    /// Each player who rolled or attacked, in order of first appearance
+    pub players: Vec<PlayerStats>,
+    /// Cards played, most used first
+    pub cards: Vec<CardUse>,
+}
+
+impl SessionStats {
+    /// Tally logged rolls
+    pub fn from_rolls(rolls: &[RollRecord]) -> Self {
+        let mut stats = Self::default();
+        stats.add_rolls(rolls);
+        stats
+    }
+
+    /// Add logged rolls to the tally
+    pub fn add_rolls(&mut self, rolls: &[RollRecord]) {
+        for record in rolls {
+            let result = &record.result;
+            let hope = result.is_critical || result.controlling == ControllingDie::Hope;
+            let fear = !result.is_critical && result.controlling == ControllingDie::Fear;
+            self.count_roll(&record.roller, result.is_critical, hope, fear);
+        }
+    }
+
+    /// Add an encounter's player attacks, damage and cards to the tally
+    ///
+    /// Damage is credited to whoever last hit the target, so a hit's
+    /// damage roll goes to the attacker even after reactions.
+    pub fn add_encounter(&mut self, encounter: &CombatEncounter) {
+        let mut names = HashMap::new();
+        let mut last_hit: HashMap<CombatantId, CombatantId> = HashMap::new();
+        let is_player = |id: CombatantId| encounter.get(id).is_some_and(|c| c.is_player());
+
+        for event in &encounter.events {
+            match event {
+                CombatEvent::CombatantAdded { combatant, name } => {
+                    names.insert(*combatant, name.clone());
+                }
+                CombatEvent::AttackResolved {
+                    attacker,
+                    target,
+                    result,
+                } => {
+                    if result.hit {
+                        last_hit.insert(*target, *attacker);
+                    } else {
+                        last_hit.remove(target);
+                    }
+                    let Some(name) = names.get(attacker).filter(|_| is_player(*attacker)) else {
+                        continue;
+                    };
+                    self.count_roll(name, result.critical, result.with_hope, result.with_fear);
+                    let player = self.player_entry(name);
+                    player.attacks += 1;
+                    player.hits += u32::from(result.hit);
+                }
+                CombatEvent::DamageApplied { target, damage } => {
+                    let attacker = last_hit.remove(target);
+                    if let Some(name) = attacker
+                        .filter(|&id| is_player(id))
+                        .and_then(|id| names.get(&id))
+                    {
+                        self.player_entry(name).damage += u32::from(damage.raw_damage);
+                    }
+                }
+                CombatEvent::CardPlayed { card, .. } => {
+                    match self.cards.iter_mut().find(|c| &c.card == card) {
+                        Some(used) => used.uses += 1,
+                        None => self.cards.push(CardUse {
+                            card: card.clone(),
+                            uses: 1,
+                        }),
+                    }
+                }
+                _ => {}
+            }
+        }
+        // Stable, so ties keep the order cards were first played
+        self.cards.sort_by_key(|used| std::cmp::Reverse(used.uses));
+    }
+
+    /// Share of rolls that were critical, 0 with no rolls
+    pub fn crit_rate(&self) -> f64 {
+        ratio(self.criticals, self.rolls)
+    }
+
+    /// Rolls with Hope for each roll with Fear, `None` with no Fear rolls
+    pub fn hope_fear_ratio(&self) -> Option<f64> {
+        (self.with_fear > 0).then(|| ratio(self.with_hope, self.with_fear))
+    }
+
+    /// Up to `count` cards, most used first
+    pub fn most_used_cards(&self, count: usize) -> &[CardUse] {
+        &self.cards[..count.min(self.cards.len())]
+    }
+
+    /// A player's numbers, by name ignoring case
+    pub fn player(&self, name: &str) -> Option<&PlayerStats> {
+        self.players
+            .iter()
+            .find(|player| player.name.eq_ignore_ascii_case(name))
+    }
+
+    fn count_roll(&mut self, roller: &str, critical: bool, hope: bool, fear: bool) {
+        self.rolls += 1;
+        self.criticals += u32::from(critical);
+        self.with_hope += u32::from(hope);
+        self.with_fear += u32::from(fear);
+        let player = self.player_entry(roller);
+        player.rolls += 1;
+        player.criticals += u32::from(critical);
+    }
+
+    fn player_entry(&mut self, name: &str) -> &mut PlayerStats {
+        let index = match self.players.iter().position(|p| p.name == name) {
+            Some(index) => index,
+            None => {
+                self.players.push(PlayerStats {
+                    name: name.to_string(),
+                    ..PlayerStats::default()
+                });
+                self.players.len() - 1
+            }
+        };
+        &mut self.players[index]
+    }
+}
+
+fn ratio(numerator: u32, denominator: u32) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        f64::from(numerator) / f64::from(denominator)
+    }
+}
+
+/// How evenly one die type has landed
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DieFairness {
+    pub die: Die,
+    /// How often each face came up, from 1 to the die's maximum
+    pub faces: Vec<u32>,
+}
+
+impl DieFairness {
+    /// An untouched tally for `die`
+    pub fn new(die: Die) -> Self {
+        Self {
+            die,
+            faces: vec![0; usize::from(die.max())],
+        }
+    }
+
+    /// Count a face; faces the die doesn't have are ignored
+    pub fn record(&mut self, face: u8) {
+        if let Some(count) = usize::from(face)
+            .checked_sub(1)
+            .and_then(|index| self.faces.get_mut(index))
+        {
+            *count += 1;
+        }
+    }
+
+    /// Rolls counted
+    pub fn rolls(&self) -> u32 {
+        self.faces.iter().sum()
+    }
+
+    /// How often a fair die would land on each face in as many rolls
+    pub fn expected(&self) -> f64 {
+        f64::from(self.rolls()) / self.faces.len() as f64
+    }
+
+    /// Pearson's chi-square statistic against a fair die, 0 with no rolls
+    pub fn chi_square(&self) -> f64 {
+        let expected = self.expected();
+        if expected == 0.0 {
+            return 0.0;
+        }
+        self.faces
+            .iter()
+            .map(|&observed| (f64::from(observed) - expected).powi(2) / expected)
+            .sum()
+    }
+
+    /// Degrees of freedom of the test, one fewer than the die's faces
+    pub fn degrees_of_freedom(&self) -> u8 {
+        self.die.max() - 1
+    }
+
+    /// Whether every face is expected at least five times, below which
+    /// the test says little
+    pub fn has_enough_rolls(&self) -> bool {
+        self.expected() >= 5.0
+    }
+
+    /// Whether the faces are further from even than a fair die would land
+    /// one time in a hundred
+    pub fn is_suspicious(&self) -> bool {
+        self.has_enough_rolls() && self.chi_square() > critical_value(self.die)
+    }
+}
+
+/// Chi-square value a fair die exceeds 1% of the time
+fn critical_value(die: Die) -> f64 {
+    match die {
+        Die::D4 => 11.345,
+        Die::D6 => 15.086,
+        Die::D8 => 18.475,
+        Die::D10 => 21.666,
+        Die::D12 => 24.725,
+        Die::D20 => 36.191,
+    }
+}
+
+/// Face counts for each die type rolled
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::analytics::FairnessReport;
+/// use daggerheart_engine::core::dice::Die;
+///
+/// let mut report = FairnessReport::default();
+/// for _ in 0..10 {
+///     for face in 1..=6 {
+///         report.record(Die::D6, face);
+///     }
+/// }
+/// let d6 = report.die(Die::D6).unwrap();
+/// assert_eq!(d6.rolls(), 60);
+/// assert_eq!(d6.chi_square(), 0.0);
+/// assert!(!report.is_suspicious());
+///
+/// // A die stuck on 6
+/// for _ in 0..30 {
+///     report.record(Die::D6, 6);
+/// }
+/// assert!(report.is_suspicious());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FairnessReport {
+    /// Each die type rolled, smallest first
+    pub dice: Vec<DieFairness>,
+}
+
+impl FairnessReport {
+    /// Count a face rolled on `die`
+    pub fn record(&mut self, die: Die, face: u8) {
+        let index = match self.dice.iter().position(|d| d.die == die) {
+            Some(index) => index,
+            None => {
+                let index = self.dice.partition_point(|d| d.die.max() < die.max());
+                self.dice.insert(index, DieFairness::new(die));
+                index
+            }
+        };
+        self.dice[index].record(face);
+    }
+
+    /// Count the faces of logged rolls, only `roller`'s if given
+    pub fn add_rolls(&mut self, rolls: &[RollRecord], roller: Option<&str>) {
+        for record in rolls {
+            if roller.is_some_and(|name| !record.roller.eq_ignore_ascii_case(name)) {
+                continue;
+            }
+            let result = &record.result;
+            self.record(Die::D12, result.roll.hope);
+            self.record(Die::D12, result.roll.fear);
+            if let Some(d6) = result.advantage_die {
+                self.record(Die::D6, d6);
+            }
+        }
+    }
+
+    /// Count the dice of an encounter's attacks, Hope, Fear, any advantage
+    /// d6 and the damage dice of each hit, only `roller`'s if given
+    pub fn add_encounter(&mut self, encounter: &CombatEncounter, roller: Option<&str>) {
+        let mut names = HashMap::new();
+        for event in &encounter.events {
+            match event {
+                CombatEvent::CombatantAdded { combatant, name } => {
+                    names.insert(*combatant, name.as_str());
+                }
+                CombatEvent::AttackResolved {
+                    attacker, result, ..
+                } => {
+                    let name = names.get(attacker).copied().unwrap_or_default();
+                    if roller.is_some_and(|roller| !name.eq_ignore_ascii_case(roller)) {
+                        continue;
+                    }
+                    for face in [result.hope, result.fear] {
+                        self.record(Die::D12, u8::try_from(face).unwrap_or(0));
+                    }
+                    if let Some(d6) = result.advantage_die {
+                        self.record(Die::D6, d6);
+                    }
+                    for &(die, face) in &result.damage_dice {
+                        self.record(die, face);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// The tally for one die type, if it was rolled
+    pub fn die(&self, die: Die) -> Option<&DieFairness> {
+        self.dice.iter().find(|d| d.die == die)
+    }
+
+    /// Whether any die type looks loaded
+    pub fn is_suspicious(&self) -> bool {
+        self.dice.iter().any(DieFairness::is_suspicious)
+    }
+}
+
+impl Campaign {
+    /// Audit every die in the roll log and encounters, only `roller`'s if
+    /// given
+    pub fn dice_fairness(&self, roller: Option<&str>) -> FairnessReport {
+        let mut report = FairnessReport::default();
+        report.add_rolls(&self.roll_log, roller);
+        let active = self.active.as_ref().map(|active| &active.encounter);
+        for encounter in self.completed.iter().map(|r| &r.encounter).chain(active) {
+            report.add_encounter(encounter, roller);
+        }
+        report
+    }
+
+    /// Stats for session `number`, or for the session being played with
+    /// `None`
+    ///
+    /// The session being played includes the encounter in progress.
+    /// Returns `None` for a session that hasn't been played.
+    pub fn session_stats(&self, number: Option<u32>) -> Option<SessionStats> {
+        let (rolls, encounters, active) = match number {
+            None => {
+                let last = self.sessions.last();
+                let rolls = last.map_or(0, |s| s.roll_log_end);
+                let encounters = last.map_or(0, |s| s.encounters_end);
+                (
+                    rolls..self.roll_log.len(),
+                    encounters..self.completed.len(),
+                    true,
+                )
+            }
+            Some(number) => {
+                let index = (number as usize).checked_sub(1)?;
+                let session = self.sessions.get(index)?;
+                let previous = index.checked_sub(1).and_then(|i| self.sessions.get(i));
+                let rolls = previous.map_or(0, |s| s.roll_log_end);
+                let encounters = previous.map_or(0, |s| s.encounters_end);
+                (
+                    rolls..session.roll_log_end,
+                    encounters..session.encounters_end,
+                    false,
+                )
+            }
+        };
+        let rolls = self.roll_log.get(rolls).unwrap_or_default();
+        let encounters = self.completed.get(encounters).unwrap_or_default();
+        let mut stats = SessionStats::from_rolls(rolls);
+        self.add_encounters(&mut stats, encounters, active);
+        Some(stats)
+    }
+
+    /// Stats for every roll and encounter in the campaign
+    pub fn stats(&self) -> SessionStats {
+        let mut stats = SessionStats::from_rolls(&self.roll_log);
+        self.add_encounters(&mut stats, &self.completed, true);
+        stats
+    }
+
+    fn add_encounters(&self, stats: &mut SessionStats, records: &[EncounterRecord], active: bool) {
+        for record in records {
+            stats.add_encounter(&record.encounter);
+        }
+        if let Some(current) = self.active.as_ref().filter(|_| active) {
+            stats.add_encounter(&current.encounter);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::attack::Attack;
+    use crate::combat::simulation::{Combatant, CombatantBuilder};
+    use crate::core::dice::{DamageDice, DualityRoll};
+    use crate::test_support::hero;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn goblin() -> Combatant {
+        CombatantBuilder::adversary("Goblin")
+            .with_hp(40)
+            .with_evasion(1)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_damage_is_credited_to_the_attacker() {
+        let mut encounter = CombatEncounter::new(5);
+        let grom = encounter.add_combatant(hero("Grom"));
+        let goblin = encounter.add_combatant(goblin());
+        let mut rng = StdRng::seed_from_u64(7);
+        for _ in 0..3 {
+            encounter
+                .resolve_attack_with_rng(
+                    grom,
+                    goblin,
+                    &Attack::new(2),
+                    &DamageDice::d8(1),
+                    &mut rng,
+                )
+                .unwrap();
+        }
+        encounter
+            .resolve_attack_with_rng(goblin, grom, &Attack::new(0), &DamageDice::d6(1), &mut rng)
+            .unwrap();
+
+        let mut stats = SessionStats::default();
+        stats.add_encounter(&encounter);
+        assert_eq!(stats.players.len(), 1);
+        let grom = stats.player("Grom").unwrap();
+        assert_eq!(grom.attacks, 3);
+        assert_eq!(grom.rolls, 3);
+        let dealt: u32 = encounter
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                CombatEvent::DamageApplied { target, damage } if *target == goblin => {
+                    Some(u32::from(damage.raw_damage))
+                }
+                _ => None,
+            })
+            .sum();
+        assert_eq!(grom.damage, dealt);
+        assert_eq!(grom.average_damage(), f64::from(dealt) / 3.0);
+    }
+
+    #[test]
+    fn test_fair_dice_pass_the_audit() {
+        let mut campaign = Campaign::new("The Witherwild", 5);
+        let mut rng = StdRng::seed_from_u64(11);
+        for _ in 0..600 {
+            let roll = DualityRoll::roll_with_rng(&mut rng);
+            campaign.log_roll("Grom", "Climb", roll.with_advantage_with_rng(&mut rng));
+        }
+        // Lia's Hope die always lands on 12
+        for fear in (1..=11).cycle().take(120) {
+            campaign.log_roll(
+                "Lia",
+                "Sneak",
+                DualityRoll::from_values(12, fear).with_modifier(0),
+            );
+        }
+
+        let grom = campaign.dice_fairness(Some("grom"));
+        assert_eq!(grom.die(Die::D12).unwrap().rolls(), 1200);
+        assert_eq!(grom.die(Die::D6).unwrap().rolls(), 600);
+        assert_eq!(grom.dice[0].die, Die::D6);
+        assert!(!grom.is_suspicious());
+
+        let lia = campaign.dice_fairness(Some("Lia"));
+        assert!(lia.die(Die::D12).unwrap().is_suspicious());
+        assert!(lia.die(Die::D6).is_none());
+    }
+
+    #[test]
+    fn test_encounter_attacks_audit_every_die() {
+        let mut encounter = CombatEncounter::new(5);
+        let grom = encounter.add_combatant(hero("Grom"));
+        let goblin = encounter.add_combatant(goblin());
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..3 {
+            encounter
+                .resolve_attack_with_rng(
+                    grom,
+                    goblin,
+                    &Attack::new(2).with_advantage(),
+                    &DamageDice::d8(2),
+                    &mut rng,
+                )
+                .unwrap();
+        }
+
+        let mut report = FairnessReport::default();
+        report.add_encounter(&encounter, Some("Grom"));
+        assert_eq!(report.die(Die::D12).unwrap().rolls(), 6);
+        assert_eq!(report.die(Die::D6).unwrap().rolls(), 3);
+        // Evasion 1 is always hit, so every attack rolled its 2d8
+        assert!(report.die(Die::D8).unwrap().rolls() >= 6);
+    }
+
+    #[test]
+    fn test_few_rolls_are_never_suspicious() {
+        let mut d20 = DieFairness::new(Die::D20);
+        for _ in 0..50 {
+            d20.record(20);
+        }
+        d20.record(0);
+        d20.record(21);
+        assert_eq!(d20.rolls(), 50);
+        assert!(!d20.has_enough_rolls());
+        assert!(!d20.is_suspicious());
+        assert_eq!(d20.degrees_of_freedom(), 19);
+    }
+
+    #[test]
+    fn test_sessions_are_tallied_separately() {
+        let mut campaign = Campaign::new("The Witherwild", 5);
+        campaign.log_roll(
+            "Grom",
+            "Climb",
+            DualityRoll::from_values(9, 2).with_modifier(0),
+        );
+        campaign.end_session("2025-03-14", 1, Vec::new());
+        campaign.log_roll(
+            "Lia",
+            "Sneak",
+            DualityRoll::from_values(2, 9).with_modifier(0),
+        );
+        campaign.log_roll(
+            "Lia",
+            "Parry",
+            DualityRoll::from_values(3, 3).with_modifier(0),
+        );
+
+        let first = campaign.session_stats(Some(1)).unwrap();
+        assert_eq!(first.rolls, 1);
+        assert_eq!(first.hope_fear_ratio(), None);
+        let current = campaign.session_stats(None).unwrap();
+        assert_eq!(current.rolls, 2);
+        assert_eq!(current.player("Lia").unwrap().criticals, 1);
+        assert_eq!(current.hope_fear_ratio(), Some(1.0));
+        assert_eq!(campaign.stats().rolls, 3);
+        assert!(campaign.session_stats(Some(2)).is_none());
+        assert!(campaign.session_stats(Some(0)).is_none());
+    }
+}
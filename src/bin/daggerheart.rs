@@ -5,21 +5,139 @@
 //! - Roll dice (basic, duality, damage)
 //! - Run combat simulations
 //! - Save and load game state
-
-use clap::{Parser, Subcommand};
-use daggerheart_engine::character::{Ancestry, Attributes, CharacterProgress, Class};
-use daggerheart_engine::combat::simulation::{CombatEncounter, Combatant};
-use daggerheart_engine::core::dice::{ControllingDie, DamageDice, Die, DualityResult, DualityRoll};
+//!
+//! `--output json` prints each command's result as JSON for scripts.
+
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::Shell;
+use daggerheart_engine::analytics::SessionStats;
+use daggerheart_engine::campaign::{Campaign, SessionLog};
+use daggerheart_engine::character::generator::{CharacterGenerator, NameGenerator};
+use daggerheart_engine::character::{
+    Ancestry, AttributeType, Attributes, CharacterProgress, CharacterSheet, Class, Domain,
+};
+#[cfg(feature = "solo")]
+use daggerheart_engine::combat::advisor::GmMove;
+use daggerheart_engine::combat::montecarlo::{MonteCarlo, TargetPolicy};
+use daggerheart_engine::combat::simulation::{CombatEncounter, Combatant, CombatantBuilder};
+use daggerheart_engine::combat::{
+    Adversary, AdversaryBuilder, AdversaryRole, Attack, CombatEvent, Condition, Countdown,
+    DamageResult, Hazard, HazardTrigger, HopeMode, Threat, TurnModel, DEFAULT_FEAR_MAXIMUM,
+};
+use daggerheart_engine::compendium::{self, CompendiumFormat};
+use daggerheart_engine::content::lint::{Severity, Subject};
+use daggerheart_engine::content::ContentRegistry;
+use daggerheart_engine::core::dice::{
+    ControllingDie, DamageDice, Die, DualityResult, DualityRoll, SuccessType,
+};
+use daggerheart_engine::core::Difficulty;
+use daggerheart_engine::downtime::{RestActivity, RestKind};
+#[cfg(feature = "solo")]
+use daggerheart_engine::gm::solo::SoloGm;
+use daggerheart_engine::gm::{Likelihood, Oracle};
+use daggerheart_engine::i18n::Catalog;
+use daggerheart_engine::interop::{foundry, import, macros};
+use daggerheart_engine::items::Weapon;
+use daggerheart_engine::party::{self, PartyOverview};
+use daggerheart_engine::render;
+use daggerheart_engine::scene::{Environment, Scene, SceneKind};
+use daggerheart_engine::social::SocialNpc;
+use daggerheart_engine::storage::{self, AutosavePolicy, Format, Persist};
+use daggerheart_engine::EngineError;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde_json::{json, Value};
+use std::io::Write;
+use strum::IntoEnumIterator;
+
+#[path = "daggerheart/adversary_wizard.rs"]
+mod adversary_wizard;
+#[cfg(feature = "tui")]
+#[path = "daggerheart/tui.rs"]
+mod tui;
+#[path = "daggerheart/wizard.rs"]
+mod wizard;
 
 #[derive(Parser)]
 #[command(name = "daggerheart")]
 #[command(about = "Daggerheart TTRPG Rules Engine CLI", long_about = None)]
 #[command(version)]
 struct Cli {
+    /// How to print results
+    #[arg(long, value_enum, default_value = "text", global = true)]
+    output: Output,
+
     #[command(subcommand)]
     command: Commands,
 }
 
+/// How command results are printed
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Output {
+    /// Human-readable summaries
+    Text,
+    /// One JSON document per command, for scripts
+    Json,
+    /// Nothing except errors
+    Quiet,
+}
+
+/// Where `char import` reads a character from
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ImportSource {
+    /// Foundry if the file looks like an actor, otherwise other
+    Auto,
+    /// Foundry VTT actor JSON
+    Foundry,
+    /// Other builders' JSON exports, such as Demiplane, mapped best-effort
+    Other,
+}
+
+/// What `char export` writes
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ExportFormat {
+    /// Foundry VTT actor JSON
+    Foundry,
+    /// Printable Markdown sheet
+    Md,
+    /// Printable HTML sheet
+    Html,
+    /// Roll20 macros for weapons and cards, one per line
+    Roll20,
+    /// The same rolls as generic roll template JSON, for other VTTs
+    Macros,
+}
+
+impl Output {
+    /// Print a command's result: `text` for people, `value` for scripts
+    fn emit(self, value: Value, text: impl FnOnce()) {
+        match self {
+            Output::Text => text(),
+            Output::Json => {
+                let mut stdout = std::io::stdout().lock();
+                if let Err(e) = writeln!(stdout, "{}", value) {
+                    // Whoever was reading (`head`, `jq -e`) has stopped
+                    if e.kind() == std::io::ErrorKind::BrokenPipe {
+                        std::process::exit(0);
+                    }
+                    eprintln!("{}", json!({ "error": e.to_string() }));
+                    std::process::exit(1);
+                }
+            }
+            Output::Quiet => {}
+        }
+    }
+
+    /// Warn about something that didn't stop the command, on stderr
+    fn warn(self, warning: &str) {
+        match self {
+            Output::Text => eprintln!("⚠️  {}", warning),
+            Output::Json => eprintln!("{}", json!({ "warning": warning })),
+            Output::Quiet => {}
+        }
+    }
+}
+
 #[derive(Subcommand)]
 enum Commands {
     /// Create and manage characters
@@ -34,11 +152,172 @@ enum Commands {
     #[command(subcommand)]
     Combat(CombatCommands),
 
+    /// Generate names and other table results
+    #[command(subcommand)]
+    Generate(GenerateCommands),
+
+    /// Create a campaign save and add the party to it
+    #[command(subcommand)]
+    Campaign(CampaignCommands),
+
+    /// Rest and work on long-term projects between sessions
+    #[command(subcommand)]
+    Downtime(DowntimeCommands),
+
+    /// Keep a journal of each session in the campaign file
+    #[command(subcommand)]
+    Session(SessionCommands),
+
+    /// Ask the oracle for quick rulings and complications
+    #[command(subcommand)]
+    Gm(GmCommands),
+
+    /// Play a campaign's encounter without a GM
+    #[cfg(feature = "solo")]
+    #[command(subcommand)]
+    Solo(SoloCommands),
+
+    /// Dump cards, adversaries and weapons for reference sites
+    #[command(subcommand)]
+    Compendium(CompendiumCommands),
+
+    /// Compare the party's characters
+    #[command(subcommand)]
+    Party(PartyCommands),
+
     /// List available classes
     Classes,
 
     /// List available ancestries
     Ancestries,
+
+    /// Build homebrew adversaries
+    #[command(subcommand)]
+    Adversary(AdversaryCommands),
+
+    /// Browse adversary stat blocks
+    Adversaries {
+        /// Only this tier (1-4)
+        #[arg(short, long)]
+        tier: Option<u8>,
+
+        /// Only this role (e.g., bruiser, solo, minion)
+        #[arg(short, long)]
+        role: Option<String>,
+
+        /// Content file to browse instead of the built-in set
+        #[arg(long)]
+        content: Option<String>,
+    },
+
+    /// Browse domain cards
+    Cards {
+        /// Only this domain (e.g., blade, arcana)
+        #[arg(short, long)]
+        domain: Option<String>,
+
+        /// Only cards usable at this character level
+        #[arg(short, long)]
+        level: Option<u8>,
+
+        /// Content file to browse instead of the built-in set
+        #[arg(long)]
+        content: Option<String>,
+
+        /// Translation catalog for card text
+        #[arg(long)]
+        catalog: Option<String>,
+    },
+
+    /// Write a translation catalog to fill in
+    ///
+    /// Lists every key sheets and content use, with its English text.
+    /// Translate the values, then pass the file to --catalog.
+    CatalogTemplate {
+        /// Language tag of the translation (e.g., de, pt-BR)
+        locale: String,
+
+        /// Output file (default: <locale>.json)
+        #[arg(short, long)]
+        out_file: Option<String>,
+
+        /// Content file to include instead of the built-in set
+        #[arg(long)]
+        content: Option<String>,
+    },
+
+    /// Check a content file for mistakes and balance red flags
+    Lint {
+        /// Content file
+        file: String,
+    },
+
+    /// Track an encounter live in the terminal
+    ///
+    /// Hotkeys step turns, roll attacks for the acting combatant against
+    /// the selected one, deal damage and adjust Hope and Fear. Changes
+    /// are saved on quit, and along the way under an autosave policy.
+    #[cfg(feature = "tui")]
+    Tui {
+        /// Encounter file
+        file: String,
+
+        /// Attack modifier for `a` attacks
+        #[arg(short, long, default_value = "0", allow_hyphen_values = true)]
+        modifier: i8,
+
+        /// Damage dice for `a` attacks
+        #[arg(short, long, default_value = "1d8")]
+        damage: String,
+
+        /// When to save on its own: command, quit, or seconds like 30s
+        /// (defaults to the campaign's policy, or quit)
+        #[arg(long)]
+        autosave: Option<AutosavePolicy>,
+
+        /// Campaign file whose autosave policy to follow
+        #[arg(long)]
+        campaign: Option<String>,
+    },
+
+    /// Print JSON Schema for save files
+    #[cfg(feature = "schema")]
+    Schema {
+        /// Type to print (lists available types if omitted)
+        name: Option<String>,
+
+        /// Write every schema to this directory instead
+        #[arg(long)]
+        out: Option<String>,
+    },
+
+    /// Print a shell completion script
+    ///
+    /// For example: `daggerheart completions bash > /etc/bash_completion.d/daggerheart`
+    Completions {
+        /// Shell to generate completions for
+        shell: Shell,
+    },
+
+    /// Print the man page, or write one per subcommand
+    #[cfg(feature = "man")]
+    Man {
+        /// Write every page to this directory instead
+        #[arg(long)]
+        out: Option<String>,
+    },
+
+    /// Run the REST API server
+    #[cfg(feature = "server")]
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:3000")]
+        addr: String,
+
+        /// Directory for characters and encounters
+        #[arg(long, default_value = "saves")]
+        dir: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -60,19 +339,68 @@ enum CharCommands {
         #[arg(short, long, default_value = "1")]
         level: u8,
 
-        /// Output file (default: <name>.json)
+        /// Output file (default: <name>_char.<format>)
         #[arg(short, long)]
-        output: Option<String>,
+        out_file: Option<String>,
 
         /// Attribute values (e.g., "2,1,1,0,0,-1")
         #[arg(long, default_value = "2,1,1,0,0,-1")]
         attributes: String,
+
+        /// File format: json, yaml, or toml (yaml/toml need those features)
+        #[arg(short, long, default_value = "json")]
+        format: Format,
+    },
+
+    /// Create a character step by step with interactive prompts
+    Wizard {
+        /// Character name (asked for if omitted)
+        name: Option<String>,
+
+        /// Content file to draw domain cards from instead of the built-in set
+        #[arg(long)]
+        content: Option<String>,
+
+        /// File format: json, yaml, or toml (yaml/toml need those features)
+        #[arg(short, long, default_value = "json")]
+        format: Format,
+    },
+
+    /// Generate random characters for NPCs or a playtest party
+    Random {
+        /// Class (random if omitted)
+        #[arg(short, long)]
+        class: Option<String>,
+
+        /// Ancestry (random if omitted)
+        #[arg(short, long)]
+        ancestry: Option<String>,
+
+        /// Level
+        #[arg(short, long, default_value = "1")]
+        level: u8,
+
+        /// How many characters to generate
+        #[arg(short = 'n', long, default_value = "1")]
+        count: usize,
+
+        /// Seed for reproducible results
+        #[arg(long)]
+        seed: Option<u64>,
+
+        /// Save each character like `char create` does
+        #[arg(long)]
+        save: bool,
     },
 
     /// Show character details
     Show {
         /// Character file
         file: String,
+
+        /// Print the raw character in this format instead of a summary
+        #[arg(short, long)]
+        format: Option<Format>,
     },
 
     /// Level up a character
@@ -93,282 +421,2279 @@ enum CharCommands {
         /// Amount of XP to add
         amount: u32,
     },
-}
-
-#[derive(Subcommand)]
-enum RollCommands {
-    /// Roll a basic die (d4, d6, d8, d10, d12, d20)
-    Die {
-        /// Die type (e.g., d20, d6, d8)
-        die: String,
 
-        /// Number of times to roll
-        #[arg(short, long, default_value = "1")]
-        count: usize,
-    },
+    /// Export a character as Foundry VTT actor JSON or a printable sheet
+    Export {
+        /// Character file
+        file: String,
 
-    /// Roll duality dice (2d12 Hope vs Fear)
-    Duality {
-        /// Modifier to add to the roll
-        #[arg(default_value = "0")]
-        modifier: i8,
+        /// Export format
+        #[arg(short, long, value_enum, default_value = "foundry")]
+        format: ExportFormat,
 
-        /// Roll with advantage (extra d6)
+        /// Output file (default: <name>_foundry.json, <name>.md or <name>.html)
         #[arg(short, long)]
-        advantage: bool,
+        out_file: Option<String>,
+
+        /// Content file to read card text from instead of the built-in set
+        #[arg(long)]
+        content: Option<String>,
+
+        /// Translation catalog for sheet and card text
+        #[arg(long)]
+        catalog: Option<String>,
     },
 
-    /// Roll damage dice
-    Damage {
-        /// Damage dice (e.g., 2d6, 1d8+3, 2d6+1d4+2)
-        dice: String,
+    /// Import a character from Foundry VTT or another builder's JSON export
+    Import {
+        /// Foundry actor or other character export file
+        file: String,
+
+        /// Where the file came from (auto detects Foundry actors)
+        #[arg(long, value_enum, default_value = "auto")]
+        from: ImportSource,
+
+        /// Content file to match cards against instead of the built-in set
+        #[arg(long)]
+        content: Option<String>,
+
+        /// File format for the imported character: json, yaml, or toml
+        #[arg(short, long, default_value = "json")]
+        format: Format,
     },
 }
 
 #[derive(Subcommand)]
-enum CombatCommands {
-    /// Create a new combat encounter
+enum CampaignCommands {
+    /// Create an empty campaign
     New {
-        /// Hope pool maximum
+        /// Campaign name
+        name: String,
+
+        /// Shared Hope pool maximum, used with --shared-hope
         #[arg(short = 'H', long, default_value = "5")]
         hope: u8,
 
+        /// Pool the party's Hope instead of tracking it per character
+        #[arg(long)]
+        shared_hope: bool,
+
+        /// How encounters decide turn order
+        #[arg(long, value_enum, default_value = "agility")]
+        initiative: Initiative,
+
+        /// Most Fear the GM can hold
+        #[arg(long, default_value_t = DEFAULT_FEAR_MAXIMUM)]
+        fear_max: u8,
+
+        /// When `tui` sessions save on their own: command, quit, or
+        /// seconds like 30s
+        #[arg(long, default_value = "quit")]
+        autosave: AutosavePolicy,
+
         /// Output file
         #[arg(short, long)]
-        output: String,
+        out_file: String,
     },
 
-    /// Add a combatant to an encounter
+    /// Add a character to the party
     Add {
-        /// Encounter file
+        /// Campaign file
         file: String,
 
-        /// Character file to add
-        #[arg(short, long)]
-        character: Option<String>,
+        /// Character file (its progress file is picked up too)
+        character: String,
+    },
 
-        /// Or create an enemy with name
-        #[arg(short = 'e', long)]
-        enemy: Option<String>,
+    /// Move the table into a new scene
+    Scene {
+        /// Campaign file
+        file: String,
 
-        /// Enemy level
-        #[arg(long, default_value = "1")]
-        level: u8,
+        /// Kind of scene
+        #[arg(value_enum)]
+        kind: SceneChoice,
 
-        /// Enemy HP
-        #[arg(long, default_value = "4")]
-        hp: u8,
+        /// Scene name
+        name: String,
 
-        /// Enemy evasion
-        #[arg(long, default_value = "13")]
-        evasion: u8,
+        /// Where the scene takes place
+        #[arg(short, long)]
+        environment: Option<String>,
 
-        /// Enemy armor
-        #[arg(long, default_value = "0")]
-        armor: u8,
+        /// Difficulty of rolls against the environment
+        #[arg(short, long, requires = "environment")]
+        difficulty: Option<u8>,
+
+        /// Party member taking part (repeatable; defaults to the whole party)
+        #[arg(short, long = "pc")]
+        pcs: Vec<String>,
+
+        /// Countdown as NAME=STEPS (repeatable)
+        #[arg(short, long = "countdown", value_parser = parse_countdown)]
+        countdowns: Vec<Countdown>,
+
+        /// NPC to win over as NAME=DIFFICULTY (repeatable)
+        #[arg(short, long = "npc", value_parser = parse_npc)]
+        npcs: Vec<SocialNpc>,
     },
 
-    /// Start combat (roll initiative)
-    Start {
-        /// Encounter file
+    /// Roll a party member's Presence against an NPC in the current scene
+    Sway {
+        /// Campaign file
         file: String,
+
+        /// Party member making the roll
+        character: String,
+
+        /// NPC to sway
+        npc: String,
+
+        /// Seed for a reproducible roll
+        #[arg(long)]
+        seed: Option<u64>,
     },
 
-    /// Show encounter status
-    Status {
-        /// Encounter file
+    /// Close the current scene
+    EndScene {
+        /// Campaign file
         file: String,
     },
 }
 
-fn main() {
-    let cli = Cli::parse();
+/// Scene kinds for `campaign scene`
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum SceneChoice {
+    /// Travel, investigation, and obstacles
+    Exploration,
+    /// Conversation and intrigue
+    Social,
+    /// Resting and projects
+    Downtime,
+    /// A fight being run outside the campaign's encounters
+    Combat,
+}
 
-    let result = match cli.command {
-        Commands::Char(cmd) => handle_char_command(cmd),
-        Commands::Roll(cmd) => handle_roll_command(cmd),
-        Commands::Combat(cmd) => handle_combat_command(cmd),
-        Commands::Classes => {
-            list_classes();
-            Ok(())
-        }
-        Commands::Ancestries => {
-            list_ancestries();
-            Ok(())
+impl From<SceneChoice> for SceneKind {
+    fn from(value: SceneChoice) -> Self {
+        match value {
+            SceneChoice::Exploration => SceneKind::Exploration,
+            SceneChoice::Social => SceneKind::Social,
+            SceneChoice::Downtime => SceneKind::Downtime,
+            SceneChoice::Combat => SceneKind::Combat,
         }
-    };
-
-    if let Err(e) = result {
-        eprintln!("❌ Error: {}", e);
-        std::process::exit(1);
     }
 }
 
-fn handle_char_command(cmd: CharCommands) -> Result<(), Box<dyn std::error::Error>> {
-    match cmd {
-        CharCommands::Create {
-            name,
-            class,
-            ancestry,
-            level,
-            output,
-            attributes,
-        } => {
-            // Parse class
-            let class = parse_class(&class)?;
+#[derive(Subcommand)]
+enum CompendiumCommands {
+    /// Write one file per entry, with an index per section, into a folder
+    Export {
+        /// Folder to write into
+        dir: String,
+
+        /// Entry format
+        #[arg(short, long, value_enum, default_value = "md")]
+        format: CompendiumOutput,
+
+        /// Content file to export instead of the built-in set
+        #[arg(long)]
+        content: Option<String>,
+    },
+}
 
-            // Parse ancestry
-            let ancestry = parse_ancestry(&ancestry)?;
+#[derive(Subcommand)]
+enum PartyCommands {
+    /// Report domain coverage, best traits, healing, damage and gaps
+    Analyze {
+        /// Character files (`*_progress.json` files are read alongside)
+        #[arg(required = true)]
+        files: Vec<String>,
+
+        /// Content file to look up cards in instead of the built-in set
+        #[arg(long)]
+        content: Option<String>,
+    },
+}
 
-            // Parse attributes
-            let attr_values: Vec<i8> = attributes
-                .split(',')
-                .map(|s| s.trim().parse())
-                .collect::<Result<Vec<_>, _>>()?;
+/// How `compendium export` writes entries
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum CompendiumOutput {
+    /// JSON, as the engine saves it
+    Json,
+    /// Markdown with YAML front matter, for static site generators
+    Md,
+}
 
-            if attr_values.len() != 6 {
-                return Err("Attributes must have exactly 6 values".into());
-            }
+/// How `combat screen` renders the GM screen
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum ScreenFormat {
+    /// Plain text for the terminal
+    Text,
+    /// Markdown for session notes
+    Md,
+    /// A standalone HTML page
+    Html,
+}
 
-            let mut attr_array = [0i8; 6];
-            attr_array.copy_from_slice(&attr_values);
-            let attributes = Attributes::from_array(attr_array)?;
+#[derive(Subcommand)]
+enum AdversaryCommands {
+    /// Build a stat block from its role's benchmark, answering prompts
+    New {
+        /// Adversary name (asked for if omitted)
+        name: Option<String>,
 
-            // Create character
-            let character = Combatant::player(name.clone(), level, class, ancestry, attributes);
+        /// Tier 1-4 (asked for if omitted)
+        #[arg(short, long)]
+        tier: Option<u8>,
 
-            // Create initial progress
-            let progress = CharacterProgress::new();
+        /// Role, e.g. bruiser or solo (asked for if omitted)
+        #[arg(short, long)]
+        role: Option<String>,
 
-            // Save files
-            let char_file = output.unwrap_or_else(|| format!("{}_char.json", name));
-            let progress_file = format!("{}_progress.json", name);
-
-            character.save_to_file(&char_file)?;
-            progress.save_to_file(&progress_file)?;
-
-            println!("✅ Character created!");
-            println!("  Name: {}", character.name);
-            println!("  Class: {}", character.class);
-            println!("  Ancestry: {}", character.ancestry);
-            println!("  Level: {}", character.level);
-            println!("  HP: {}/{}", character.hp.current, character.hp.maximum);
-            println!("  Evasion: {}", character.evasion);
-            println!("\n📁 Files saved:");
-            println!("  Character: {}", char_file);
-            println!("  Progress: {}", progress_file);
-        }
-
-        CharCommands::Show { file } => {
-            let character = Combatant::load_from_file(&file)?;
-
-            println!("=== {} ===", character.name);
-            println!("  Class: {}", character.class);
-            println!("  Ancestry: {}", character.ancestry);
-            println!("  Level: {}", character.level);
-            println!();
-            println!("  HP: {}/{}", character.hp.current, character.hp.maximum);
-            println!("  Stress: {}/5", character.stress.current);
-            println!("  Evasion: {}", character.evasion);
-            println!("  Armor: {}", character.armor);
-            println!();
-            println!("Attributes:");
-            println!("  Agility:   {:+}", character.attributes.agility);
-            println!("  Strength:  {:+}", character.attributes.strength);
-            println!("  Finesse:   {:+}", character.attributes.finesse);
-            println!("  Instinct:  {:+}", character.attributes.instinct);
-            println!("  Presence:  {:+}", character.attributes.presence);
-            println!("  Knowledge: {:+}", character.attributes.knowledge);
+        /// Add it to this content file (created if missing) instead of
+        /// saving it on its own
+        #[arg(long)]
+        content: Option<String>,
+    },
 
-            // Try to load progress file
-            let progress_file = file.replace("_char.json", "_progress.json");
-            if let Ok(progress) = CharacterProgress::load_from_file(&progress_file) {
-                println!();
-                println!("Progress:");
-                println!("  Level: {}", progress.level);
-                println!(
-                    "  XP: {} / {}",
-                    progress.experience,
-                    progress.xp_for_next_level()
-                );
-                println!("  Cards: {:?}", progress.available_cards);
-            }
-        }
+    /// Print a benchmark stat block for an NPC made up on the spot
+    Improvise {
+        /// Tier 1-4
+        #[arg(short, long, default_value = "1")]
+        tier: u8,
 
-        CharCommands::LevelUp { file, card } => {
-            let mut progress = CharacterProgress::load_from_file(&file)?;
+        /// Role, e.g. standard or bruiser
+        #[arg(short, long, default_value = "standard")]
+        role: String,
 
-            if !progress.can_level_up() {
-                println!(
-                    "❌ Not enough XP to level up (need {}, have {})",
-                    progress.xp_for_next_level(),
-                    progress.experience
-                );
-                return Ok(());
-            }
+        /// Name to give it (defaults to its role)
+        #[arg(short, long)]
+        name: Option<String>,
+    },
+}
 
-            let old_level = progress.level;
-            progress.level_up()?;
+#[derive(Subcommand)]
+enum SessionCommands {
+    /// End the session: award XP and write its journal entry
+    End {
+        /// Campaign file
+        file: String,
 
-            println!("🎉 LEVEL UP!");
-            println!("  {} → {}", old_level, progress.level);
-            println!("  Remaining XP: {}", progress.experience);
+        /// XP awarded to each party member
+        #[arg(long, default_value = "0")]
+        xp: u32,
 
-            if let Some(card_id) = card {
-                progress.add_card(&card_id);
-                println!("  ✨ Learned: {}", card_id);
-            }
+        /// Closing note for the journal (repeatable)
+        #[arg(short, long)]
+        note: Vec<String>,
 
-            progress.save_to_file(&file)?;
-            println!("\n✅ Progress saved to {}", file);
-        }
+        /// Date played (defaults to today, YYYY-MM-DD)
+        #[arg(long)]
+        date: Option<String>,
+    },
 
-        CharCommands::AddXp { file, amount } => {
-            let mut progress = CharacterProgress::load_from_file(&file)?;
+    /// Show the session journal
+    Log {
+        /// Campaign file
+        file: String,
 
-            progress.add_experience(amount);
-            println!("📈 Added {} XP", amount);
-            println!("  Total XP: {}", progress.experience);
-            println!("  Level: {}", progress.level);
-            println!("  XP for next level: {}", progress.xp_for_next_level());
-
-            if progress.can_level_up() {
-                println!("\n💡 You can now level up! Run:");
-                println!("   daggerheart char level-up {}", file);
-            }
+        /// Only this session number
+        #[arg(short, long)]
+        session: Option<u32>,
+    },
+
+    /// Show end-of-session stats: crits, Hope and Fear, damage, cards
+    Stats {
+        /// Campaign file
+        file: String,
+
+        /// A finished session's number (defaults to the session being played)
+        #[arg(short, long, conflicts_with = "all")]
+        session: Option<u32>,
+
+        /// The whole campaign so far
+        #[arg(long)]
+        all: bool,
+    },
+
+    /// Check the dice for fairness: each die type's faces against an even spread
+    Fairness {
+        /// Campaign file
+        file: String,
+
+        /// Only this player's rolls
+        #[arg(short, long)]
+        roller: Option<String>,
+    },
+}
+
+/// A downtime move for `downtime rest`
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum RestMove {
+    /// Clear Hit Points
+    Wounds,
+    /// Clear Stress
+    Stress,
+    /// Gain a Hope
+    Prepare,
+}
+
+/// How `--initiative` orders turns
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+enum Initiative {
+    /// Duality dice plus Agility
+    Agility,
+    /// A straight d20
+    D20,
+    /// Players first, then adversaries, without rolling
+    Role,
+    /// No initiative: characters spend action tokens to act
+    Tokens,
+}
+
+impl From<Initiative> for TurnModel {
+    fn from(value: Initiative) -> Self {
+        match value {
+            Initiative::Agility => TurnModel::Agility,
+            Initiative::D20 => TurnModel::D20,
+            Initiative::Role => TurnModel::ByRole,
+            Initiative::Tokens => TurnModel::ActionTokens,
+        }
+    }
+}
+
+impl From<RestMove> for RestActivity {
+    fn from(value: RestMove) -> Self {
+        match value {
+            RestMove::Wounds => RestActivity::TendToWounds,
+            RestMove::Stress => RestActivity::ClearStress,
+            RestMove::Prepare => RestActivity::Prepare,
+        }
+    }
+}
+
+#[derive(Subcommand)]
+enum DowntimeCommands {
+    /// Start a long-term project for a character
+    Project {
+        /// Campaign file
+        file: String,
+
+        /// Party member working on it
+        character: String,
+
+        /// Project name
+        name: String,
+
+        /// Segments on the project clock
+        #[arg(short, long, default_value = "6")]
+        segments: u8,
+    },
+
+    /// Roll to make progress on a project
+    Advance {
+        /// Campaign file
+        file: String,
+
+        /// Party member working on it
+        character: String,
+
+        /// Project name
+        project: String,
+
+        /// Trait to roll (e.g., knowledge, finesse)
+        #[arg(short, long)]
+        r#trait: String,
+
+        /// Difficulty to beat
+        #[arg(short, long, default_value_t = Difficulty::AVERAGE.value())]
+        difficulty: u16,
+
+        /// Seed for a reproducible roll
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// Make a character's downtime moves for a rest
+    Rest {
+        /// Campaign file
+        file: String,
+
+        /// Party member resting
+        character: String,
+
+        /// Moves to make (up to two, repeats allowed)
+        #[arg(value_enum, required = true)]
+        moves: Vec<RestMove>,
+
+        /// Take a long rest instead of a short one
+        #[arg(short, long)]
+        long: bool,
+
+        /// Seed for reproducible recovery rolls
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// The GM gains Fear for the party's rest
+    PartyRest {
+        /// Campaign file
+        file: String,
+
+        /// A long rest instead of a short one
+        #[arg(short, long)]
+        long: bool,
+
+        /// Seed for a reproducible roll
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// Show projects and downtime history
+    Status {
+        /// Campaign file
+        file: String,
+
+        /// Only this party member
+        character: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+enum GenerateCommands {
+    /// Generate names flavored by ancestry
+    Name {
+        /// Ancestry (random for each name if omitted)
+        #[arg(short, long)]
+        ancestry: Option<String>,
+
+        /// How many names to generate
+        #[arg(short = 'n', long, default_value = "1")]
+        count: usize,
+
+        /// Seed for reproducible results
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+enum GmCommands {
+    /// Ask a yes/no question, weighted by how likely a yes is
+    Ask {
+        /// impossible, very-unlikely, unlikely, even, likely, very-likely, or certain
+        #[arg(short, long, default_value = "even")]
+        likelihood: Likelihood,
+
+        /// Seed for reproducible results
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// Roll on the d12 complication table
+    Complication {
+        /// Seed for reproducible results
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+}
+
+#[cfg(feature = "solo")]
+#[derive(Subcommand)]
+enum SoloCommands {
+    /// Start an encounter between the party and an encounter file's adversaries
+    Begin {
+        /// Campaign file
+        file: String,
+
+        /// Encounter file to take the adversaries from
+        encounter: String,
+
+        /// Name for the encounter's record
+        #[arg(short, long, default_value = "Encounter")]
+        name: String,
+    },
+
+    /// Ask the oracle a scene question and journal the answer
+    Ask {
+        /// Campaign file
+        file: String,
+
+        /// The question, for the journal
+        question: String,
+
+        /// impossible, very-unlikely, unlikely, even, likely, very-likely, or certain
+        #[arg(short, long, default_value = "even")]
+        likelihood: Likelihood,
+
+        /// Seed for reproducible results
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// Roll a complication and journal it
+    Complication {
+        /// Campaign file
+        file: String,
+
+        /// Seed for reproducible results
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// Name an NPC and journal the meeting
+    Npc {
+        /// Campaign file
+        file: String,
+
+        /// Seed for reproducible results
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+
+    /// End the current turn and let adversaries act until a player is up
+    Advance {
+        /// Campaign file with an encounter in progress
+        file: String,
+
+        /// Targeting policy (random, weakest, strongest)
+        #[arg(short, long, default_value = "random")]
+        policy: String,
+
+        /// Seed for reproducible results
+        #[arg(long)]
+        seed: Option<u64>,
+    },
+}
+
+#[derive(Subcommand)]
+enum RollCommands {
+    /// Roll a basic die (d4, d6, d8, d10, d12, d20)
+    Die {
+        /// Die type (e.g., d20, d6, d8)
+        die: String,
+
+        /// Number of times to roll
+        #[arg(short, long, default_value = "1")]
+        count: usize,
+    },
+
+    /// Roll duality dice (2d12 Hope vs Fear)
+    Duality {
+        /// Modifier to add to the roll
+        #[arg(default_value = "0")]
+        modifier: i8,
+
+        /// Roll with advantage (extra d6)
+        #[arg(short, long)]
+        advantage: bool,
+    },
+
+    /// Roll damage dice
+    Damage {
+        /// Damage dice (e.g., 2d6, 1d8+3, 2d6+1d4+2)
+        dice: String,
+    },
+
+    /// Resolve an attack: roll to hit, roll damage and apply thresholds
+    Attack {
+        /// Attack modifier (trait plus bonuses)
+        #[arg(short, long, default_value = "0", allow_hyphen_values = true)]
+        modifier: i8,
+
+        /// Target's Evasion
+        #[arg(short, long)]
+        evasion: u8,
+
+        /// Weapon damage dice (e.g., d10+3, 2d6), or a weapon name with --character
+        #[arg(short, long)]
+        weapon: String,
+
+        /// Attack as this character, working out the modifier and damage
+        /// from their traits, Proficiency and the named weapon
+        #[arg(short, long, conflicts_with = "modifier")]
+        character: Option<String>,
+
+        /// Target's armor score
+        #[arg(long, default_value = "0")]
+        armor: u8,
+
+        /// Roll with advantage (extra d6)
+        #[arg(short, long)]
+        advantage: bool,
+    },
+
+    /// Roll Spellcast with the trait a character's subclass casts with
+    Spellcast {
+        /// Character file
+        character: String,
+
+        /// Difficulty to beat
+        #[arg(short, long, default_value_t = Difficulty::AVERAGE.value())]
+        difficulty: u16,
+    },
+}
+
+#[derive(Subcommand)]
+enum CombatCommands {
+    /// Create a new combat encounter
+    New {
+        /// Shared Hope pool maximum, used with --shared-hope
+        #[arg(short = 'H', long, default_value = "5")]
+        hope: u8,
+
+        /// Pool the party's Hope instead of tracking it per character
+        #[arg(long)]
+        shared_hope: bool,
+
+        /// How turn order is decided
+        #[arg(long, value_enum, default_value = "agility")]
+        initiative: Initiative,
+
+        /// Output file
+        #[arg(short, long)]
+        out_file: String,
+    },
+
+    /// Add a combatant to an encounter
+    Add {
+        /// Encounter file
+        file: String,
+
+        /// Character file to add
+        #[arg(short, long)]
+        character: Option<String>,
+
+        /// Or create an enemy with name
+        #[arg(short = 'e', long)]
+        enemy: Option<String>,
+
+        /// Enemy level
+        #[arg(long, default_value = "1")]
+        level: u8,
+
+        /// Enemy HP
+        #[arg(long, default_value = "4")]
+        hp: u8,
+
+        /// Enemy evasion
+        #[arg(long, default_value = "13")]
+        evasion: u8,
+
+        /// Enemy armor
+        #[arg(long, default_value = "0")]
+        armor: u8,
+
+        /// Enemy role, for encounter budgets (e.g., bruiser, minion)
+        #[arg(short, long)]
+        role: Option<String>,
+    },
+
+    /// Start combat (roll initiative)
+    Start {
+        /// Encounter file
+        file: String,
+    },
+
+    /// End the current turn and move to the next combatant
+    Next {
+        /// Encounter file
+        file: String,
+    },
+
+    /// End the round, skipping anyone who hasn't acted yet
+    EndRound {
+        /// Encounter file
+        file: String,
+    },
+
+    /// Start a countdown that ticks at the start of each round
+    Countdown {
+        /// Encounter file
+        file: String,
+
+        /// What happens when it runs out
+        name: String,
+
+        /// Rounds until it runs out
+        rounds: u8,
+    },
+
+    /// Add an environment hazard that strikes as turns advance
+    Hazard {
+        /// Encounter file
+        file: String,
+
+        /// Hazard name
+        name: String,
+
+        /// Trait rolled to avoid it
+        #[arg(short, long)]
+        save: String,
+
+        /// Difficulty of the reaction roll
+        #[arg(short, long)]
+        difficulty: u8,
+
+        /// Damage dealt on a failed roll (e.g., 2d6)
+        #[arg(long)]
+        damage: Option<String>,
+
+        /// Condition gained on a failed roll
+        #[arg(long)]
+        condition: Option<String>,
+
+        /// Only catch combatants in this zone
+        #[arg(short, long)]
+        zone: Option<i8>,
+
+        /// Strike whoever starts their turn, instead of everyone each round
+        #[arg(long, conflicts_with = "countdown")]
+        each_turn: bool,
+
+        /// Strike when this countdown runs out, instead of each round
+        #[arg(long)]
+        countdown: Option<String>,
+    },
+
+    /// Show encounter status
+    Status {
+        /// Encounter file
+        file: String,
+
+        /// Print the raw encounter in this format instead of a summary
+        #[arg(short, long)]
+        format: Option<Format>,
+    },
+
+    /// Show the GM screen: adversary thresholds, features, conditions,
+    /// countdowns and Fear
+    Screen {
+        /// Encounter file
+        file: String,
+
+        /// How to render it
+        #[arg(short, long, value_enum, default_value = "text")]
+        format: ScreenFormat,
+
+        /// Write it to this file instead of printing it
+        #[arg(short, long)]
+        out_file: Option<String>,
+    },
+
+    /// Run many automated playthroughs and report statistics
+    Simulate {
+        /// Encounter file (before combat starts)
+        file: String,
+
+        /// Number of playthroughs
+        #[arg(short, long, default_value = "1000")]
+        runs: u32,
+
+        /// Seed for reproducible results
+        #[arg(short, long, default_value = "0")]
+        seed: u64,
+
+        /// Targeting policy (random, weakest, strongest)
+        #[arg(short, long, default_value = "random")]
+        policy: String,
+
+        /// Round limit before a run counts as a stalemate
+        #[arg(long, default_value = "20")]
+        max_rounds: u32,
+
+        /// Run playthroughs in parallel (requires the `parallel` feature)
+        #[arg(long)]
+        parallel: bool,
+    },
+
+    /// Rate how dangerous an encounter is for a party
+    Analyze {
+        /// Encounter file
+        file: String,
+
+        /// Character files of the party (default: the encounter's own)
+        #[arg(short, long, num_args = 1..)]
+        party: Vec<String>,
+
+        /// Also run this many quick automated fights
+        #[arg(long)]
+        simulate: Option<u32>,
+
+        /// Seed for the automated fights
+        #[arg(short, long, default_value = "0")]
+        seed: u64,
+    },
+}
+
+fn main() {
+    // Text output is printed as it's built; a reader that stops early
+    // (`| head`) closes the pipe, which is a clean exit rather than a crash
+    let report_panic = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        let message = info
+            .payload()
+            .downcast_ref::<String>()
+            .map_or("", String::as_str);
+        if message.contains("Broken pipe") {
+            std::process::exit(0);
+        }
+        report_panic(info);
+    }));
+
+    let cli = Cli::parse();
+    let out = cli.output;
+
+    let result = match cli.command {
+        Commands::Char(cmd) => handle_char_command(cmd, out),
+        Commands::Roll(cmd) => handle_roll_command(cmd, out),
+        Commands::Combat(cmd) => handle_combat_command(cmd, out),
+        Commands::Generate(cmd) => handle_generate_command(cmd, out),
+        Commands::Campaign(cmd) => handle_campaign_command(cmd, out),
+        Commands::Downtime(cmd) => handle_downtime_command(cmd, out),
+        Commands::Session(cmd) => handle_session_command(cmd, out),
+        Commands::Gm(cmd) => handle_gm_command(cmd, out),
+        #[cfg(feature = "solo")]
+        Commands::Solo(cmd) => handle_solo_command(cmd, out),
+        Commands::Compendium(cmd) => handle_compendium_command(cmd, out),
+        Commands::Party(cmd) => handle_party_command(cmd, out),
+        Commands::Classes => {
+            list_classes(out);
+            Ok(())
+        }
+        Commands::Ancestries => {
+            list_ancestries(out);
+            Ok(())
+        }
+        Commands::Adversary(cmd) => handle_adversary_command(cmd, out),
+        Commands::Adversaries {
+            tier,
+            role,
+            content,
+        } => list_adversaries(tier, role, content, out),
+        Commands::Cards {
+            domain,
+            level,
+            content,
+            catalog,
+        } => list_cards(domain, level, content, catalog, out),
+        Commands::CatalogTemplate {
+            locale,
+            out_file,
+            content,
+        } => write_catalog_template(locale, out_file, content, out),
+        Commands::Lint { file } => lint_content(file, out),
+        Commands::Completions { shell } => {
+            clap_complete::generate(
+                shell,
+                &mut Cli::command(),
+                "daggerheart",
+                &mut std::io::stdout(),
+            );
+            Ok(())
+        }
+        #[cfg(feature = "man")]
+        Commands::Man { out: dir } => handle_man_command(dir, out),
+        #[cfg(feature = "tui")]
+        Commands::Tui {
+            file,
+            modifier,
+            damage,
+            autosave,
+            campaign,
+        } => handle_tui_command(file, modifier, damage, autosave, campaign),
+        #[cfg(feature = "schema")]
+        Commands::Schema { name, out: dir } => handle_schema_command(name, dir, out),
+        #[cfg(feature = "server")]
+        Commands::Serve { addr, dir } => handle_serve_command(addr, dir, out),
+    };
+
+    if let Err(e) = result {
+        if out == Output::Json {
+            eprintln!("{}", json!({ "error": e.to_string() }));
+        } else {
+            eprintln!("❌ Error: {}", e);
+        }
+        std::process::exit(1);
+    }
+}
+
+fn handle_char_command(cmd: CharCommands, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd {
+        CharCommands::Create {
+            name,
+            class,
+            ancestry,
+            level,
+            out_file,
+            attributes,
+            format,
+        } => {
+            // Parse class
+            let class = parse_class(&class)?;
+
+            // Parse ancestry
+            let ancestry = parse_ancestry(&ancestry)?;
+
+            // Parse attributes
+            let attr_values: Vec<i8> = attributes
+                .split(',')
+                .map(|s| s.trim().parse())
+                .collect::<Result<Vec<_>, _>>()?;
+
+            if attr_values.len() != 6 {
+                return Err("Attributes must have exactly 6 values".into());
+            }
+
+            let mut attr_array = [0i8; 6];
+            attr_array.copy_from_slice(&attr_values);
+            let attributes = Attributes::from_array(attr_array)?;
+
+            // Create character
+            let character = CombatantBuilder::player(name.clone(), class, ancestry)
+                .with_level(level)
+                .with_attributes(attributes)
+                .build()?;
+
+            // Create initial progress
+            let progress = CharacterProgress::new();
+
+            // Save files
+            let char_file =
+                out_file.unwrap_or_else(|| format!("{}_char.{}", name, format.extension()));
+            let progress_file = format!("{}_progress.{}", name, format.extension());
+
+            save(&character, &char_file)?;
+            save(&progress, &progress_file)?;
+
+            let value = json!({
+                "name": character.name,
+                "class": class,
+                "ancestry": ancestry,
+                "level": character.level,
+                "hp": character.hp.maximum,
+                "evasion": character.evasion,
+                "files": { "character": char_file, "progress": progress_file },
+            });
+            out.emit(value, || {
+                println!("✅ Character created!");
+                println!("  Name: {}", character.name);
+                println!("  Class: {}", class);
+                println!("  Ancestry: {}", ancestry);
+                println!("  Level: {}", character.level);
+                println!("  HP: {}/{}", character.hp.current, character.hp.maximum);
+                println!("  Evasion: {}", character.evasion);
+                println!("\n📁 Files saved:");
+                println!("  Character: {}", char_file);
+                println!("  Progress: {}", progress_file);
+            });
+        }
+
+        CharCommands::Wizard {
+            name,
+            content,
+            format,
+        } => {
+            let content = load_content(content)?;
+            let stdin = std::io::stdin().lock();
+            // Keep stdout clean for the JSON result
+            let sheet = if out == Output::Text {
+                wizard::Wizard::new(stdin, std::io::stdout()).run(name, &content)?
+            } else {
+                wizard::Wizard::new(stdin, std::io::stderr()).run(name, &content)?
+            };
+
+            let character = &sheet.combatant;
+            let char_file = format!("{}_char.{}", character.name, format.extension());
+            let progress_file = format!("{}_progress.{}", character.name, format.extension());
+            save(character, &char_file)?;
+            save(&sheet.progress, &progress_file)?;
+
+            let value = json!({
+                "character": character,
+                "progress": sheet.progress,
+                "files": { "character": char_file, "progress": progress_file },
+            });
+            out.emit(value, || {
+                let player = character.player_data().expect("the wizard builds players");
+                println!("\n✅ Character created!");
+                println!("  Name: {}", character.name);
+                if let Some(subclass) = player.subclass {
+                    println!("  Class: {} ({})", player.class, subclass);
+                }
+                if let Some(community) = player.community {
+                    println!("  Heritage: {} {}", community, player.ancestry);
+                }
+                println!("  HP: {}/{}", character.hp.current, character.hp.maximum);
+                println!("  Evasion: {}", character.evasion);
+                println!("  Cards: {}", sheet.progress.available_cards.join(", "));
+                println!("  Equipment: {}", character.equipment.join(", "));
+                println!("\n📁 Files saved:");
+                println!("  Character: {}", char_file);
+                println!("  Progress: {}", progress_file);
+            });
+        }
+
+        CharCommands::Random {
+            class,
+            ancestry,
+            level,
+            count,
+            seed,
+            save: save_files,
+        } => {
+            let mut generator = CharacterGenerator::new().with_level(level);
+            if let Some(class) = class {
+                generator = generator.with_class(parse_class(&class)?);
+            }
+            if let Some(ancestry) = ancestry {
+                generator = generator.with_ancestry(parse_ancestry(&ancestry)?);
+            }
+            let mut rng = match seed {
+                Some(seed) => StdRng::seed_from_u64(seed),
+                None => StdRng::from_entropy(),
+            };
+
+            let mut characters = Vec::new();
+            for _ in 0..count {
+                let sheet = generator.generate_with_rng(&mut rng)?;
+                if save_files {
+                    save(&sheet.combatant, &format!("{}_char.json", sheet.name()))?;
+                    save(&sheet.progress, &format!("{}_progress.json", sheet.name()))?;
+                }
+                characters.push(sheet);
+            }
+
+            out.emit(json!(characters), || {
+                for sheet in &characters {
+                    let character = &sheet.combatant;
+                    let player = character.player_data().expect("generated characters are players");
+                    let attributes = &player.attributes;
+                    println!(
+                        "🎲 {} - level {} {} {} ({})",
+                        character.name,
+                        character.level,
+                        player.ancestry,
+                        player.class,
+                        player.subclass.map(|s| s.to_string()).unwrap_or_default()
+                    );
+                    println!(
+                        "  HP {}  Evasion {}  Agi {:+} Str {:+} Fin {:+} Ins {:+} Pre {:+} Kno {:+}",
+                        character.hp.maximum,
+                        character.evasion,
+                        attributes.agility,
+                        attributes.strength,
+                        attributes.finesse,
+                        attributes.instinct,
+                        attributes.presence,
+                        attributes.knowledge
+                    );
+                    println!("  Cards: {}", sheet.progress.available_cards.join(", "));
+                    println!("  Equipment: {}", character.equipment.join(", "));
+                }
+                if save_files {
+                    println!("\n📁 Saved {} character(s)", characters.len());
+                }
+            });
+        }
+
+        CharCommands::Show { file, format } => {
+            let character = load::<Combatant>(&file)?;
+
+            if let Some(format) = format {
+                println!("{}", character.to_format(format)?);
+                return Ok(());
+            }
+
+            // Try to load progress file
+            let progress_file = file.replace("_char.", "_progress.");
+            let progress = load::<CharacterProgress>(&progress_file).ok();
+
+            let value = json!({ "character": character, "progress": progress });
+            out.emit(value, || {
+                println!("=== {} ===", character.name);
+                if let Some(player) = character.player_data() {
+                    match player.subclass {
+                        Some(subclass) => println!("  Class: {} ({})", player.class, subclass),
+                        None => println!("  Class: {}", player.class),
+                    }
+                    println!("  Ancestry: {}", player.ancestry);
+                    if let Some(community) = player.community {
+                        println!("  Community: {}", community);
+                    }
+                }
+                println!("  Level: {}", character.level);
+                println!();
+                println!("  HP: {}/{}", character.hp.current, character.hp.maximum);
+                println!("  Stress: {}/5", character.stress.current);
+                println!("  Evasion: {}", character.evasion);
+                println!("  Armor: {}", character.armor);
+                if let Some(attributes) = character.attributes() {
+                    println!();
+                    println!("Attributes:");
+                    println!("  Agility:   {:+}", attributes.agility);
+                    println!("  Strength:  {:+}", attributes.strength);
+                    println!("  Finesse:   {:+}", attributes.finesse);
+                    println!("  Instinct:  {:+}", attributes.instinct);
+                    println!("  Presence:  {:+}", attributes.presence);
+                    println!("  Knowledge: {:+}", attributes.knowledge);
+                }
+
+                if let Some(progress) = &progress {
+                    println!();
+                    println!("Progress:");
+                    println!("  Level: {}", progress.level);
+                    println!(
+                        "  XP: {} / {}",
+                        progress.experience,
+                        progress.xp_for_next_level()
+                    );
+                    println!("  Cards: {:?}", progress.available_cards);
+                }
+            });
+        }
+
+        CharCommands::LevelUp { file, card } => {
+            let mut progress = load::<CharacterProgress>(&file)?;
+
+            if !progress.can_level_up() {
+                let value = json!({
+                    "leveled_up": false,
+                    "experience": progress.experience,
+                    "needed": progress.xp_for_next_level(),
+                });
+                out.emit(value, || {
+                    println!(
+                        "❌ Not enough XP to level up (need {}, have {})",
+                        progress.xp_for_next_level(),
+                        progress.experience
+                    );
+                });
+                return Ok(());
+            }
+
+            let old_level = progress.level;
+            progress.level_up()?;
+            if let Some(card_id) = &card {
+                progress.add_card(card_id);
+            }
+            save(&progress, &file)?;
+
+            let value = json!({
+                "leveled_up": true,
+                "from": old_level,
+                "to": progress.level,
+                "experience": progress.experience,
+                "card": card,
+                "file": file,
+            });
+            out.emit(value, || {
+                println!("🎉 LEVEL UP!");
+                println!("  {} → {}", old_level, progress.level);
+                println!("  Remaining XP: {}", progress.experience);
+                if let Some(card_id) = &card {
+                    println!("  ✨ Learned: {}", card_id);
+                }
+                println!("\n✅ Progress saved to {}", file);
+            });
+        }
+
+        CharCommands::AddXp { file, amount } => {
+            let mut progress = load::<CharacterProgress>(&file)?;
+
+            progress.add_experience(amount);
+            save(&progress, &file)?;
+
+            let value = json!({
+                "added": amount,
+                "experience": progress.experience,
+                "level": progress.level,
+                "next_level": progress.xp_for_next_level(),
+                "can_level_up": progress.can_level_up(),
+                "file": file,
+            });
+            out.emit(value, || {
+                println!("📈 Added {} XP", amount);
+                println!("  Total XP: {}", progress.experience);
+                println!("  Level: {}", progress.level);
+                println!("  XP for next level: {}", progress.xp_for_next_level());
+
+                if progress.can_level_up() {
+                    println!("\n💡 You can now level up! Run:");
+                    println!("   daggerheart char level-up {}", file);
+                }
+
+                println!("\n✅ Progress saved");
+            });
+        }
+
+        CharCommands::Export {
+            file,
+            format,
+            out_file,
+            content,
+            catalog,
+        } => {
+            let sheet = load_sheet(&file)?;
+            let catalog = load_catalog(catalog)?;
+
+            let (rendered, default_output) = match format {
+                ExportFormat::Foundry => (
+                    foundry::export_character(&sheet)?,
+                    format!("{}_foundry.json", sheet.name()),
+                ),
+                ExportFormat::Md => (
+                    sheet.render_markdown_localized(&load_content(content)?, &catalog),
+                    format!("{}.md", sheet.name()),
+                ),
+                ExportFormat::Html => (
+                    sheet.render_html_localized(&load_content(content)?, &catalog),
+                    format!("{}.html", sheet.name()),
+                ),
+                ExportFormat::Roll20 => (
+                    macros::character_templates(
+                        &sheet,
+                        &load_content(content)?.localized(&catalog),
+                    )?
+                    .iter()
+                    .map(|template| template.to_roll20() + "\n")
+                    .collect(),
+                    format!("{}_roll20.txt", sheet.name()),
+                ),
+                ExportFormat::Macros => (
+                    serde_json::to_string_pretty(&macros::character_templates(
+                        &sheet,
+                        &load_content(content)?.localized(&catalog),
+                    )?)?,
+                    format!("{}_macros.json", sheet.name()),
+                ),
+            };
+            let out_file = out_file.unwrap_or(default_output);
+            std::fs::write(&out_file, rendered)?;
+
+            let value = json!({
+                "name": sheet.name(),
+                "format": format.to_possible_value().map(|v| v.get_name().to_string()),
+                "file": out_file,
+            });
+            out.emit(value, || match format {
+                ExportFormat::Foundry => {
+                    println!("📤 Exported {} for Foundry VTT", sheet.name());
+                    println!("  Actor: {}", out_file);
+                }
+                ExportFormat::Md | ExportFormat::Html => {
+                    println!("📤 Exported {}'s character sheet", sheet.name());
+                    println!("  Sheet: {}", out_file);
+                }
+                ExportFormat::Roll20 | ExportFormat::Macros => {
+                    println!("📤 Exported {}'s weapons and cards as macros", sheet.name());
+                    println!("  Macros: {}", out_file);
+                }
+            });
+        }
+
+        CharCommands::Import {
+            file,
+            from,
+            content,
+            format,
+        } => {
+            let json = std::fs::read_to_string(&file)?;
+            let from = match from {
+                ImportSource::Auto => {
+                    let value: Value = serde_json::from_str(&json)?;
+                    if value.get("system").is_some() && value.get("type").is_some() {
+                        ImportSource::Foundry
+                    } else {
+                        ImportSource::Other
+                    }
+                }
+                from => from,
+            };
+            let (sheet, warnings) = match from {
+                ImportSource::Other => {
+                    let report = import::from_json(&json, &load_content(content)?)?;
+                    (report.sheet, report.warnings)
+                }
+                _ => (foundry::import_character(&json)?, Vec::new()),
+            };
+
+            let char_file = format!("{}_char.{}", sheet.name(), format.extension());
+            let progress_file = format!("{}_progress.{}", sheet.name(), format.extension());
+            save(&sheet.combatant, &char_file)?;
+            save(&sheet.progress, &progress_file)?;
+
+            let value = json!({
+                "name": sheet.name(),
+                "class": sheet.combatant.class(),
+                "ancestry": sheet.combatant.ancestry(),
+                "level": sheet.progress.level,
+                "warnings": warnings,
+                "files": { "character": char_file, "progress": progress_file },
+            });
+            out.emit(value, || {
+                match from {
+                    ImportSource::Other => println!("📥 Imported {}", sheet.name()),
+                    _ => println!("📥 Imported {} from Foundry VTT", sheet.name()),
+                }
+                if let Some(player) = sheet.combatant.player_data() {
+                    println!("  Class: {}", player.class);
+                    println!("  Ancestry: {}", player.ancestry);
+                }
+                println!("  Level: {}", sheet.progress.level);
+                if !warnings.is_empty() {
+                    println!("\n⚠️  Not imported:");
+                    for warning in &warnings {
+                        println!("  {}", warning);
+                    }
+                }
+                println!("\n📁 Files saved:");
+                println!("  Character: {}", char_file);
+                println!("  Progress: {}", progress_file);
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_generate_command(
+    cmd: GenerateCommands,
+    out: Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd {
+        GenerateCommands::Name {
+            ancestry,
+            count,
+            seed,
+        } => {
+            let ancestry = ancestry.map(|a| parse_ancestry(&a)).transpose()?;
+            let mut names = match seed {
+                Some(seed) => NameGenerator::seeded(seed),
+                None => NameGenerator::new(),
+            };
 
-            progress.save_to_file(&file)?;
-            println!("\n✅ Progress saved");
+            let generated: Vec<(Ancestry, String)> = (0..count)
+                .map(|_| match ancestry {
+                    Some(ancestry) => (ancestry, names.name(ancestry)),
+                    None => names.any(),
+                })
+                .collect();
+
+            let value: Vec<Value> = generated
+                .iter()
+                .map(|(ancestry, name)| json!({ "name": name, "ancestry": ancestry }))
+                .collect();
+            out.emit(json!(value), || {
+                for (generated_ancestry, name) in &generated {
+                    if ancestry.is_some() {
+                        println!("{}", name);
+                    } else {
+                        println!("{} ({})", name, generated_ancestry);
+                    }
+                }
+            });
         }
     }
 
     Ok(())
 }
 
-fn handle_roll_command(cmd: RollCommands) -> Result<(), Box<dyn std::error::Error>> {
+fn handle_gm_command(cmd: GmCommands, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    let oracle = |seed: Option<u64>| seed.map_or_else(Oracle::new, Oracle::seeded);
     match cmd {
-        RollCommands::Die { die, count } => {
-            let die_type = parse_die(&die)?;
+        GmCommands::Ask { likelihood, seed } => {
+            let answer = oracle(seed).ask(likelihood);
+            out.emit(json!(answer), || {
+                println!("🔮 {}", answer.answer);
+                println!(
+                    "  {} ({}%), rolled {}",
+                    answer.likelihood,
+                    answer.likelihood.chance(),
+                    answer.roll
+                );
+            });
+        }
+
+        GmCommands::Complication { seed } => {
+            let complication = oracle(seed).complication();
+            out.emit(json!(complication), || {
+                println!(
+                    "⚠️  {} complication ({})",
+                    complication.severity, complication.roll
+                );
+                println!("  {}", complication.description);
+                println!("  Costs: {}", complication.severity.suggested_cost());
+            });
+        }
+    }
 
-            println!("🎲 Rolling {}x{}:", count, die);
-            let mut total = 0;
-            let mut rolls = Vec::new();
+    Ok(())
+}
 
-            for i in 1..=count {
-                let roll = die_type.roll();
-                total += roll;
-                rolls.push(roll);
+#[cfg(feature = "solo")]
+fn handle_solo_command(cmd: SoloCommands, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    let gm = |seed: Option<u64>| seed.map_or_else(SoloGm::new, SoloGm::seeded);
+    match cmd {
+        SoloCommands::Begin {
+            file,
+            encounter,
+            name,
+        } => {
+            let mut campaign = load::<Campaign>(&file)?;
+            let enemies: Vec<Combatant> = load_encounter(&encounter, out)?
+                .combatants
+                .into_iter()
+                .filter(|c| !c.is_player())
+                .collect();
+            let count = enemies.len();
+            campaign.begin_encounter(name.clone(), enemies)?;
+            save(&campaign, &file)?;
+
+            out.emit(
+                json!({ "file": file, "name": name, "adversaries": count }),
+                || {
+                    println!(
+                        "⚔️  {} begins: the party against {} adversaries",
+                        name, count
+                    );
+                    println!("\n💡 Roll initiative and let the adversaries act with:");
+                    println!("   daggerheart solo advance {}", file);
+                },
+            );
+        }
 
-                if count <= 10 {
-                    println!("  Roll {}: {}", i, roll);
+        SoloCommands::Ask {
+            file,
+            question,
+            likelihood,
+            seed,
+        } => {
+            let mut campaign = load::<Campaign>(&file)?;
+            let answer = gm(seed).ask(&mut campaign, &question, likelihood);
+            save(&campaign, &file)?;
+            out.emit(json!(answer), || {
+                println!("🔮 {}", answer.answer);
+                println!(
+                    "  {} ({}), rolled {}",
+                    question, answer.likelihood, answer.roll
+                );
+            });
+        }
+
+        SoloCommands::Complication { file, seed } => {
+            let mut campaign = load::<Campaign>(&file)?;
+            let complication = gm(seed).complication(&mut campaign);
+            save(&campaign, &file)?;
+            out.emit(json!(complication), || {
+                println!("⚠️  {} complication", complication.severity);
+                println!("  {}", complication.description);
+            });
+        }
+
+        SoloCommands::Npc { file, seed } => {
+            let mut campaign = load::<Campaign>(&file)?;
+            let (ancestry, name) = gm(seed).npc(&mut campaign);
+            save(&campaign, &file)?;
+            out.emit(json!({ "name": name, "ancestry": ancestry }), || {
+                println!("👤 {} ({})", name, ancestry);
+            });
+        }
+
+        SoloCommands::Advance { file, policy, seed } => {
+            let mut campaign = load::<Campaign>(&file)?;
+            let activations = gm(seed)
+                .with_policy(parse_target_policy(&policy)?)
+                .advance(&mut campaign)?;
+            save(&campaign, &file)?;
+
+            let encounter = campaign.encounter().ok_or(EngineError::NoActiveEncounter)?;
+            let up = encounter
+                .current_combatant()
+                .filter(|_| !encounter.is_over())
+                .map(|c| c.name.to_string());
+            let name_of = |id| {
+                encounter
+                    .get(id)
+                    .map_or_else(String::new, |c| c.name.to_string())
+            };
+            out.emit(json!({ "activations": activations, "up": up }), || {
+                for activation in &activations {
+                    let outcome = if activation.succeeded { "✅" } else { "❌" };
+                    match activation.action {
+                        GmMove::Attack { target, .. } => println!(
+                            "⚔️  {} attacks {} {}",
+                            activation.name,
+                            name_of(target),
+                            outcome
+                        ),
+                        GmMove::Search { target } => println!(
+                            "🔍 {} searches for {} {}",
+                            activation.name,
+                            name_of(target),
+                            outcome
+                        ),
+                        GmMove::Hold => println!("⏸️  {} holds", activation.name),
+                    }
+                }
+                match &up {
+                    Some(name) => println!("\n👉 {}'s turn (round {})", name, encounter.round),
+                    None => println!("\n🏁 The encounter is over"),
+                }
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_campaign_command(
+    cmd: CampaignCommands,
+    out: Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd {
+        CampaignCommands::New {
+            name,
+            hope,
+            shared_hope,
+            initiative,
+            fear_max,
+            autosave,
+            out_file,
+        } => {
+            let mode = hope_mode(shared_hope);
+            let campaign = Campaign::new(name.clone(), hope)
+                .with_hope_mode(mode)
+                .with_turn_model(initiative.into())
+                .with_fear_maximum(fear_max)
+                .with_autosave(autosave);
+            save(&campaign, &out_file)?;
+
+            let value = json!({
+                "file": out_file,
+                "name": name,
+                "hope": hope,
+                "hope_mode": mode,
+                "turn_model": campaign.turn_model,
+                "fear_max": fear_max,
+                "autosave": autosave,
+            });
+            out.emit(value, || {
+                println!("✅ Campaign '{}' created!", name);
+                print_hope_mode(mode, hope);
+                println!("  Initiative: {:?}", campaign.turn_model);
+                println!("  Fear cap: {}", fear_max);
+                println!("  Autosave: {}", autosave);
+                println!("  File: {}", out_file);
+                println!("\n💡 Add characters with:");
+                println!("   daggerheart campaign add {} <character file>", out_file);
+            });
+        }
+
+        CampaignCommands::Add { file, character } => {
+            let mut campaign = load::<Campaign>(&file)?;
+            let sheet = load_sheet(&character)?;
+            let name = sheet.name().to_string();
+            campaign.add_character(sheet)?;
+            save(&campaign, &file)?;
+
+            let value = json!({ "file": file, "name": name, "party": campaign.party.len() });
+            out.emit(value, || {
+                println!("➕ {} joined the party", name);
+                println!("  Party size: {}", campaign.party.len());
+            });
+        }
+
+        CampaignCommands::Scene {
+            file,
+            kind,
+            name,
+            environment,
+            difficulty,
+            pcs,
+            countdowns,
+            npcs,
+        } => {
+            let mut campaign = load::<Campaign>(&file)?;
+            let mut scene = Scene::new(name, kind.into());
+            if let Some(environment) = environment {
+                let mut environment = Environment::new(environment);
+                environment.difficulty = difficulty;
+                scene = scene.with_environment(environment);
+            }
+            let pcs = if pcs.is_empty() {
+                campaign
+                    .party
+                    .iter()
+                    .map(|s| s.name().to_string())
+                    .collect()
+            } else {
+                pcs
+            };
+            for pc in pcs {
+                scene.add_pc(pc);
+            }
+            scene.countdowns = countdowns;
+            scene.npcs = npcs;
+            let previous = campaign.begin_scene(scene.clone())?;
+            save(&campaign, &file)?;
+
+            let value = json!({ "file": file, "scene": scene, "previous": previous });
+            out.emit(value, || {
+                if let Some(previous) = &previous {
+                    println!("🏁 '{}' ends", previous.name);
+                }
+                println!("🎬 {} scene: {}", scene.kind, scene.name);
+                if let Some(environment) = &scene.environment {
+                    match environment.difficulty {
+                        Some(difficulty) => {
+                            println!(
+                                "  Environment: {} (Difficulty {})",
+                                environment.name, difficulty
+                            )
+                        }
+                        None => println!("  Environment: {}", environment.name),
+                    }
+                }
+                if !scene.active_pcs.is_empty() {
+                    println!("  PCs: {}", scene.active_pcs.join(", "));
+                }
+                for countdown in &scene.countdowns {
+                    println!("  ⏳ {}: {}", countdown.name, countdown.remaining);
+                }
+                for npc in &scene.npcs {
+                    println!(
+                        "  🗣️  {} (Difficulty {}, {})",
+                        npc.name, npc.difficulty, npc.attitude
+                    );
+                }
+            });
+        }
+
+        CampaignCommands::Sway {
+            file,
+            character,
+            npc,
+            seed,
+        } => {
+            let mut campaign = load::<Campaign>(&file)?;
+            let outcome = match seed {
+                Some(seed) => campaign.social_roll_with_rng(
+                    &character,
+                    &npc,
+                    &mut StdRng::seed_from_u64(seed),
+                )?,
+                None => campaign.social_roll(&character, &npc)?,
+            };
+            save(&campaign, &file)?;
+            let target = campaign
+                .scene
+                .as_ref()
+                .and_then(|scene| scene.npc(&npc))
+                .cloned()
+                .expect("the NPC was just rolled against");
+
+            let value =
+                json!({ "file": file, "character": character, "outcome": outcome, "npc": target });
+            out.emit(value, || {
+                println!("🗣️  {} works on {} (Presence)", character, target.name);
+                println!(
+                    "  Hope: {}, Fear: {}, Modifier: {:+}",
+                    outcome.result.roll.hope, outcome.result.roll.fear, outcome.result.modifier
+                );
+                println!(
+                    "  Total: {} vs Difficulty {}",
+                    outcome.result.total, outcome.difficulty
+                );
+                match outcome.outcome {
+                    SuccessType::Failure => println!("  ❌ Failure"),
+                    SuccessType::SuccessWithHope => println!("  ✨ Success with Hope"),
+                    SuccessType::SuccessWithFear => println!("  😰 Success with Fear"),
+                    SuccessType::CriticalSuccess => println!("  💥 Critical success!"),
+                }
+                println!(
+                    "  Resolve: {}/{} (+{})",
+                    target.resolve_marked, target.resolve, outcome.resolve_marked
+                );
+                println!(
+                    "  Pressure: {}/{} (+{})",
+                    target.pressure, target.pressure_maximum, outcome.pressure_gained
+                );
+                if outcome.swayed {
+                    println!("  ✨ {} gives way!", target.name);
+                }
+                if outcome.soured {
+                    println!("  💢 {} has had enough", target.name);
+                }
+                println!("  Attitude: {}", target.attitude);
+                if outcome.hope_gained > 0 {
+                    println!("  +{} Hope", outcome.hope_gained);
+                }
+                if outcome.fear_gained > 0 {
+                    println!("  +{} Fear for the GM", outcome.fear_gained);
+                }
+            });
+        }
+
+        CampaignCommands::EndScene { file } => {
+            let mut campaign = load::<Campaign>(&file)?;
+            let scene = campaign.end_scene().ok_or("No scene in progress")?;
+            save(&campaign, &file)?;
+
+            let value = json!({ "file": file, "scene": scene });
+            out.emit(value, || println!("🏁 '{}' ends", scene.name));
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_downtime_command(
+    cmd: DowntimeCommands,
+    out: Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd {
+        DowntimeCommands::Project {
+            file,
+            character,
+            name,
+            segments,
+        } => {
+            let mut campaign = load::<Campaign>(&file)?;
+            let project = campaign.start_project(&character, name, segments)?.clone();
+            save(&campaign, &file)?;
+
+            out.emit(
+                json!({ "character": character, "project": project }),
+                || {
+                    println!("🛠️  {} starts '{}'", character, project.name);
+                    println!("  Clock: {} segments", project.segments);
+                },
+            );
+        }
+
+        DowntimeCommands::Advance {
+            file,
+            character,
+            project,
+            r#trait,
+            difficulty,
+            seed,
+        } => {
+            let attribute = parse_trait(&r#trait)?;
+            let mut campaign = load::<Campaign>(&file)?;
+            let roll = match seed {
+                Some(seed) => campaign.advance_project_with_rng(
+                    &character,
+                    &project,
+                    attribute,
+                    difficulty,
+                    &mut StdRng::seed_from_u64(seed),
+                )?,
+                None => campaign.advance_project(&character, &project, attribute, difficulty)?,
+            };
+            let clock = campaign
+                .downtime(&character)
+                .and_then(|record| record.project(&project))
+                .cloned()
+                .expect("project was just advanced");
+            save(&campaign, &file)?;
+
+            out.emit(json!({ "roll": roll, "project": clock }), || {
+                println!("🎲 {} works on '{}' ({})", character, clock.name, attribute);
+                println!(
+                    "  Hope: {}, Fear: {}, Modifier: {:+}",
+                    roll.result.roll.hope, roll.result.roll.fear, roll.result.modifier
+                );
+                println!(
+                    "  Total: {} vs Difficulty {}",
+                    roll.result.total, difficulty
+                );
+                match roll.outcome {
+                    SuccessType::Failure => println!("  ❌ No progress"),
+                    SuccessType::SuccessWithHope => println!("  ✨ Success with Hope"),
+                    SuccessType::SuccessWithFear => println!("  😰 Success with Fear"),
+                    SuccessType::CriticalSuccess => println!("  💥 Critical success!"),
+                }
+                println!(
+                    "  Progress: {}/{} (+{})",
+                    clock.progress, clock.segments, roll.ticks
+                );
+                if roll.completed {
+                    println!("  🎉 Project complete!");
+                }
+            });
+        }
+
+        DowntimeCommands::Rest {
+            file,
+            character,
+            moves,
+            long,
+            seed,
+        } => {
+            let rest = if long {
+                RestKind::Long
+            } else {
+                RestKind::Short
+            };
+            let activities: Vec<RestActivity> = moves.into_iter().map(Into::into).collect();
+            let mut campaign = load::<Campaign>(&file)?;
+            let records = match seed {
+                Some(seed) => campaign.rest_with_rng(
+                    &character,
+                    rest,
+                    &activities,
+                    &mut StdRng::seed_from_u64(seed),
+                )?,
+                None => campaign.rest(&character, rest, &activities)?,
+            };
+            save(&campaign, &file)?;
+
+            let value = json!({ "character": character, "rest": rest, "moves": records });
+            out.emit(value, || {
+                println!(
+                    "🏕️  {} takes a {} rest",
+                    character,
+                    rest.to_string().to_lowercase()
+                );
+                for record in &records {
+                    match record.activity {
+                        RestActivity::TendToWounds => {
+                            println!("  {}: cleared {} HP", record.activity, record.amount)
+                        }
+                        RestActivity::ClearStress => {
+                            println!("  {}: cleared {} Stress", record.activity, record.amount)
+                        }
+                        RestActivity::Prepare => {
+                            println!("  {}: gained {} Hope", record.activity, record.amount)
+                        }
+                    }
+                }
+            });
+        }
+
+        DowntimeCommands::PartyRest { file, long, seed } => {
+            let rest = if long {
+                RestKind::Long
+            } else {
+                RestKind::Short
+            };
+            let mut campaign = load::<Campaign>(&file)?;
+            let gained = match seed {
+                Some(seed) => {
+                    campaign.gain_rest_fear_with_rng(rest, &mut StdRng::seed_from_u64(seed))
+                }
+                None => campaign.gain_rest_fear(rest),
+            };
+            save(&campaign, &file)?;
+
+            let value = json!({ "rest": rest, "fear_gained": gained, "fear": campaign.fear });
+            out.emit(value, || {
+                println!(
+                    "🏕️  The party takes a {} rest",
+                    rest.to_string().to_lowercase()
+                );
+                println!(
+                    "  💀 GM gains {} Fear ({}/{})",
+                    gained, campaign.fear.current, campaign.fear.maximum
+                );
+            });
+        }
+
+        DowntimeCommands::Status { file, character } => {
+            let campaign = load::<Campaign>(&file)?;
+            if let Some(name) = &character {
+                if campaign.character(name).is_none() {
+                    return Err(EngineError::UnknownCharacter { name: name.clone() }.into());
                 }
             }
+            let records: Vec<_> = campaign
+                .downtime
+                .iter()
+                .filter(|r| character.as_ref().is_none_or(|name| &r.character == name))
+                .collect();
+
+            out.emit(json!(records), || {
+                if records.is_empty() {
+                    println!("No downtime yet");
+                }
+                for record in &records {
+                    println!("📜 {}", record.character);
+                    for project in &record.projects {
+                        let mark = if project.is_complete() { "✅" } else { "⏳" };
+                        println!(
+                            "  {} {} [{}/{}]",
+                            mark, project.name, project.progress, project.segments
+                        );
+                    }
+                    if !record.activities.is_empty() {
+                        println!("  Downtime moves: {}", record.activities.len());
+                    }
+                }
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn handle_compendium_command(
+    cmd: CompendiumCommands,
+    out: Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd {
+        CompendiumCommands::Export {
+            dir,
+            format,
+            content,
+        } => {
+            let content = load_content(content)?;
+            let format = match format {
+                CompendiumOutput::Json => CompendiumFormat::Json,
+                CompendiumOutput::Md => CompendiumFormat::Markdown,
+            };
+            let written = compendium::export(&content, format, &dir)?;
+
+            let value = json!({
+                "dir": dir,
+                "cards": content.cards.len(),
+                "adversaries": content.adversaries.len(),
+                "files": written,
+            });
+            out.emit(value, || {
+                println!("📚 Exported the compendium to {}", dir);
+                println!("  Cards: {}", content.cards.len());
+                println!("  Adversaries: {}", content.adversaries.len());
+                println!("  Files written: {}", written.len());
+            });
+        }
+    }
+    Ok(())
+}
+
+fn handle_party_command(cmd: PartyCommands, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd {
+        PartyCommands::Analyze { files, content } => {
+            let content = load_content(content)?;
+            let sheets = files
+                .iter()
+                .filter(|file| !file.contains("_progress."))
+                .map(|file| load::<CharacterSheet>(file).or_else(|_| load_sheet(file)))
+                .collect::<Result<Vec<_>, _>>()?;
+            let report = party::overview_with(&sheets, &content);
+
+            out.emit(json!(report), || print_party_overview(&report));
+        }
+    }
+    Ok(())
+}
+
+fn print_party_overview(report: &PartyOverview) {
+    println!(
+        "🧭 Party of {} (tier {}, damage against Difficulty {})\n",
+        report.members.len(),
+        report.tier,
+        report.difficulty
+    );
+    for member in &report.members {
+        let class = member
+            .class
+            .map(|class| class.to_string())
+            .unwrap_or_else(|| String::from("-"));
+        println!(
+            "  {:<12} {:<9} L{}  HP {}  Evasion {}  {:+} for {}  {:.0}% to hit, {:.1} a round",
+            member.name,
+            class,
+            member.level,
+            member.hp,
+            member.evasion,
+            member.attack_modifier,
+            member.damage,
+            member.hit_chance * 100.0,
+            member.damage_per_round
+        );
+        if !member.healing.is_empty() {
+            println!("  {:<12} heals with {}", "", member.healing.join(", "));
+        }
+    }
+    println!("\n  Party damage a round: {:.1}", report.damage_per_round);
+
+    println!("\nDomains:");
+    for coverage in &report.domains {
+        let who = if coverage.members.is_empty() {
+            String::from("-")
+        } else {
+            coverage.members.join(", ")
+        };
+        println!("  {:<9} {}", coverage.domain, who);
+    }
+
+    println!("\nBest traits:");
+    for spread in &report.traits {
+        println!(
+            "  {:<9} {:+} ({})",
+            spread.attribute,
+            spread.best,
+            spread.members.join(", ")
+        );
+    }
+
+    if !report.gaps.is_empty() {
+        println!("\nGaps:");
+        for gap in &report.gaps {
+            println!("  ⚠️  {}", gap);
+        }
+    }
+}
+
+fn handle_session_command(
+    cmd: SessionCommands,
+    out: Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd {
+        SessionCommands::End {
+            file,
+            xp,
+            note,
+            date,
+        } => {
+            let mut campaign = load::<Campaign>(&file)?;
+            let session = campaign
+                .end_session(date.unwrap_or_else(today), xp, note)
+                .clone();
+            save(&campaign, &file)?;
+
+            out.emit(json!(session), || {
+                println!("📔 Session {} ended", session.number);
+                print_session(&session);
+            });
+        }
 
-            if count > 1 {
-                println!("\nRolls: {:?}", rolls);
-                println!("Total: {}", total);
-                println!("Average: {:.2}", total as f64 / count as f64);
+        SessionCommands::Log { file, session } => {
+            let campaign = load::<Campaign>(&file)?;
+            let sessions: Vec<&SessionLog> = campaign
+                .sessions
+                .iter()
+                .filter(|s| session.is_none_or(|number| s.number == number))
+                .collect();
+            if let (Some(number), true) = (session, sessions.is_empty()) {
+                return Err(format!("No session {} in {}", number, file).into());
             }
+
+            out.emit(json!(sessions), || {
+                if sessions.is_empty() {
+                    println!("No sessions logged yet");
+                }
+                for session in &sessions {
+                    println!("📔 Session {}", session.number);
+                    print_session(session);
+                    println!();
+                }
+            });
+        }
+
+        SessionCommands::Stats { file, session, all } => {
+            let campaign = load::<Campaign>(&file)?;
+            let stats = if all {
+                campaign.stats()
+            } else {
+                campaign
+                    .session_stats(session)
+                    .ok_or_else(|| format!("No session {} in {}", session.unwrap_or(0), file))?
+            };
+
+            out.emit(json!(stats), || {
+                match (all, session) {
+                    (true, _) => println!("📊 {} so far", campaign.name),
+                    (false, Some(number)) => println!("📊 Session {}", number),
+                    (false, None) => println!("📊 This session"),
+                }
+                print_stats(&stats);
+            });
+        }
+
+        SessionCommands::Fairness { file, roller } => {
+            let campaign = load::<Campaign>(&file)?;
+            let report = campaign.dice_fairness(roller.as_deref());
+
+            out.emit(json!(report), || {
+                match &roller {
+                    Some(name) => println!("🎲 {}'s dice", name),
+                    None => println!("🎲 Dice in {}", campaign.name),
+                }
+                if report.dice.is_empty() {
+                    println!("  No rolls yet");
+                }
+                for die in &report.dice {
+                    let verdict = if !die.has_enough_rolls() {
+                        "too few rolls to tell"
+                    } else if die.is_suspicious() {
+                        "⚠️  suspicious"
+                    } else {
+                        "looks fair"
+                    };
+                    println!(
+                        "  d{}: {} rolls, χ² {:.1} ({} df), {}",
+                        die.die.max(),
+                        die.rolls(),
+                        die.chi_square(),
+                        die.degrees_of_freedom(),
+                        verdict
+                    );
+                    let faces: Vec<String> = die
+                        .faces
+                        .iter()
+                        .enumerate()
+                        .map(|(index, count)| format!("{}:{}", index + 1, count))
+                        .collect();
+                    println!("    {}", faces.join(" "));
+                }
+            });
+        }
+    }
+
+    Ok(())
+}
+
+fn hope_mode(shared: bool) -> HopeMode {
+    if shared {
+        HopeMode::Shared
+    } else {
+        HopeMode::PerCharacter
+    }
+}
+
+fn print_hope_mode(mode: HopeMode, shared_maximum: u8) {
+    match mode {
+        HopeMode::Shared => println!("  Shared Hope pool: {}", shared_maximum),
+        HopeMode::PerCharacter => println!("  Hope: tracked per character"),
+    }
+}
+
+fn print_stats(stats: &SessionStats) {
+    if stats.rolls == 0 && stats.cards.is_empty() {
+        println!("  No rolls yet");
+        return;
+    }
+    println!(
+        "  Rolls: {}, critical: {} ({:.0}%)",
+        stats.rolls,
+        stats.criticals,
+        stats.crit_rate() * 100.0
+    );
+    match stats.hope_fear_ratio() {
+        Some(ratio) => println!(
+            "  With Hope: {}, with Fear: {} ({:.2} to 1)",
+            stats.with_hope, stats.with_fear, ratio
+        ),
+        None => println!("  With Hope: {}, never with Fear", stats.with_hope),
+    }
+    for player in &stats.players {
+        print!(
+            "  {}: {} rolls, {} critical",
+            player.name, player.rolls, player.criticals
+        );
+        if player.attacks > 0 {
+            print!(
+                ", {}/{} hits, {:.1} damage per attack",
+                player.hits,
+                player.attacks,
+                player.average_damage()
+            );
+        }
+        println!();
+    }
+    if !stats.cards.is_empty() {
+        println!("  Most used cards:");
+        for used in stats.most_used_cards(5) {
+            println!("    {} ×{}", used.card, used.uses);
+        }
+    }
+}
+
+fn print_session(session: &SessionLog) {
+    println!("  Date: {}", session.date);
+    println!("  XP awarded: {}", session.xp_awarded);
+    println!(
+        "  Hope: {}, Fear carried over: {}",
+        session.hope, session.fear_carried
+    );
+    for member in &session.party {
+        println!(
+            "  {} (level {}): HP {}/{}, Stress {}, Hope {}, XP {}",
+            member.name,
+            member.level,
+            member.hp.current,
+            member.hp.maximum,
+            member.stress,
+            member.hope,
+            member.experience
+        );
+    }
+    if !session.notable_rolls.is_empty() {
+        println!("  Notable rolls:");
+        for record in &session.notable_rolls {
+            println!(
+                "    💥 {}: {} ({})",
+                record.roller, record.description, record.result.total
+            );
+        }
+    }
+    if !session.notes.is_empty() {
+        println!("  Notes:");
+        for note in &session.notes {
+            println!("    - {}", note);
+        }
+    }
+}
+
+/// Today's date (UTC) as YYYY-MM-DD
+fn today() -> String {
+    let seconds = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs());
+    // Days since 1970-01-01 to a civil date (Howard Hinnant's algorithm)
+    let days = (seconds / 86_400) as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+fn handle_roll_command(cmd: RollCommands, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd {
+        RollCommands::Die { die, count } => {
+            let die_type = parse_die(&die)?;
+            let rolls: Vec<u8> = (0..count).map(|_| die_type.roll()).collect();
+            let total: u32 = rolls.iter().map(|&roll| roll as u32).sum();
+
+            let value = json!({ "die": die, "rolls": rolls, "total": total });
+            out.emit(value, || {
+                println!("🎲 Rolling {}x{}:", count, die);
+                if count <= 10 {
+                    for (i, roll) in rolls.iter().enumerate() {
+                        println!("  Roll {}: {}", i + 1, roll);
+                    }
+                }
+
+                if count > 1 {
+                    println!("\nRolls: {:?}", rolls);
+                    println!("Total: {}", total);
+                    println!("Average: {:.2}", total as f64 / count as f64);
+                }
+            });
         }
 
         RollCommands::Duality {
@@ -392,30 +2717,57 @@ fn handle_roll_command(cmd: RollCommands) -> Result<(), Box<dyn std::error::Erro
                 roll.with_modifier(modifier)
             };
 
-            println!("🎲 Duality Roll:");
-            println!("  Hope die: {}", roll.hope);
-            println!("  Fear die: {}", roll.fear);
+            out.emit(json!(result), || {
+                println!("🎲 Duality Roll:");
+                println!("  Hope die: {}", roll.hope);
+                println!("  Fear die: {}", roll.fear);
 
-            if let Some(d6) = result.advantage_die {
-                println!("  Advantage die: {}", d6);
-            }
+                if let Some(d6) = result.advantage_die {
+                    println!("  Advantage die: {}", d6);
+                }
 
-            if modifier != 0 {
-                println!("  Modifier: {:+}", modifier);
-            }
+                if modifier != 0 {
+                    println!("  Modifier: {:+}", modifier);
+                }
 
-            println!("  Total: {}", result.total);
+                println!("  Total: {}", result.total);
 
-            if result.is_critical {
-                println!("\n🌟 CRITICAL! (Doubles: {})", roll.hope);
-            }
+                if result.is_critical {
+                    println!("\n🌟 CRITICAL! (Doubles: {})", roll.hope);
+                }
 
-            // Determine success type (assuming difficulty is met)
-            match result.controlling {
-                ControllingDie::Hope => println!("\n✅ Hope controls! 🌟"),
-                ControllingDie::Fear => println!("\n⚠️ Fear controls... 💀"),
-                ControllingDie::Tied => println!("\n🔄 Tied"),
-            }
+                // Determine success type (assuming difficulty is met)
+                match result.controlling {
+                    ControllingDie::Hope => println!("\n✅ Hope controls! 🌟"),
+                    ControllingDie::Fear => println!("\n⚠️ Fear controls... 💀"),
+                    ControllingDie::Tied => println!("\n🔄 Tied"),
+                }
+            });
+        }
+
+        RollCommands::Spellcast {
+            character,
+            difficulty,
+        } => {
+            let caster = load::<Combatant>(&character)?;
+            let roll = caster.spellcast_roll(difficulty)?;
+
+            out.emit(json!(roll), || {
+                println!("✨ {} casts with {}:", caster.name, roll.spellcast_trait);
+                println!("  Hope die: {}", roll.result.roll.hope);
+                println!("  Fear die: {}", roll.result.roll.fear);
+                println!("  Modifier: {:+}", roll.result.modifier);
+                println!(
+                    "  Total: {} vs Difficulty {}",
+                    roll.result.total, difficulty
+                );
+                match roll.outcome {
+                    SuccessType::Failure => println!("\n❌ The spell fails"),
+                    SuccessType::SuccessWithHope => println!("\n✅ Success with Hope 🌟"),
+                    SuccessType::SuccessWithFear => println!("\n⚠️ Success with Fear 💀"),
+                    SuccessType::CriticalSuccess => println!("\n🌟 CRITICAL!"),
+                }
+            });
         }
 
         RollCommands::Damage { dice } => {
@@ -423,27 +2775,126 @@ fn handle_roll_command(cmd: RollCommands) -> Result<(), Box<dyn std::error::Erro
             let damage_dice = parse_damage_dice(&dice)?;
             let result = damage_dice.roll();
 
-            println!("🎲 Damage Roll: {}", dice);
-            println!("  Individual rolls: {:?}", result.rolls);
-            println!("  Total damage: {}", result.total);
+            let value = json!({
+                "dice": dice,
+                "rolls": result.rolls,
+                "bonus": result.bonus,
+                "total": result.total,
+            });
+            out.emit(value, || {
+                println!("🎲 Damage Roll: {}", dice);
+                println!("  Individual rolls: {:?}", result.rolls);
+                println!("  Total damage: {}", result.total);
+            });
+        }
+
+        RollCommands::Attack {
+            modifier,
+            evasion,
+            weapon,
+            character,
+            armor,
+            advantage,
+        } => {
+            let (mut attack, damage_dice) = match character {
+                Some(path) => {
+                    let character = load::<Combatant>(&path)?;
+                    let weapon = Weapon::named(&weapon)
+                        .ok_or_else(|| format!("Unknown weapon '{}'", weapon))?;
+                    let damage = weapon.damage(character.proficiency());
+                    (Attack::from_character(&character, &weapon)?, damage)
+                }
+                None => (Attack::new(modifier), parse_damage_dice(&weapon)?),
+            };
+            let modifier = attack.modifier;
+            if advantage {
+                attack = attack.with_advantage();
+            }
+            let result = attack.roll().against(evasion);
+            let hit = result.hit;
+            let damage = hit.then(|| {
+                let roll = damage_dice.roll();
+                let damage = DamageResult::calculate(roll.total, armor);
+                (roll, damage)
+            });
+
+            let value = json!({
+                "attack": result,
+                "evasion": evasion,
+                "hit": hit,
+                "damage_rolls": damage.as_ref().map(|(roll, _)| &roll.rolls),
+                "damage": damage.as_ref().map(|(_, damage)| damage),
+            });
+            out.emit(value, || {
+                println!("⚔️  Attack Roll:");
+                println!("  Hope die: {}", result.hope);
+                println!("  Fear die: {}", result.fear);
+                if modifier != 0 {
+                    println!("  Modifier: {:+}", modifier);
+                }
+                println!("  Total: {} vs Evasion {}", result.total, evasion);
+
+                if result.critical {
+                    println!("\n🌟 CRITICAL! (Doubles: {})", result.hope);
+                } else if result.with_hope {
+                    println!("\n✅ With Hope 🌟");
+                } else {
+                    println!("\n⚠️ With Fear 💀");
+                }
+
+                let Some((roll, damage)) = &damage else {
+                    println!("\n❌ MISS");
+                    return;
+                };
+
+                println!("\n🎯 HIT! Damage: {}", damage_dice);
+                println!("  Individual rolls: {:?}", roll.rolls);
+                println!("  Total damage: {}", roll.total);
+                if armor > 0 {
+                    println!("  After armor ({}): {}", armor, damage.after_armor);
+                }
+                println!("\n💔 HP lost: {}", damage.hp_lost);
+                if damage.stress_gained > 0 {
+                    println!("😰 Stress gained: {}", damage.stress_gained);
+                }
+            });
         }
     }
 
     Ok(())
 }
 
-fn handle_combat_command(cmd: CombatCommands) -> Result<(), Box<dyn std::error::Error>> {
+fn handle_combat_command(
+    cmd: CombatCommands,
+    out: Output,
+) -> Result<(), Box<dyn std::error::Error>> {
     match cmd {
-        CombatCommands::New { hope, output } => {
-            let encounter = CombatEncounter::new(hope);
-            encounter.save_session(&output)?;
-
-            println!("✅ Combat encounter created!");
-            println!("  Hope pool: {}", hope);
-            println!("  File: {}", output);
-            println!("\n💡 Add combatants with:");
-            println!("   daggerheart combat add {} --character <file>", output);
-            println!("   daggerheart combat add {} --enemy <name>", output);
+        CombatCommands::New {
+            hope,
+            shared_hope,
+            initiative,
+            out_file,
+        } => {
+            let mut encounter = CombatEncounter::new(hope);
+            encounter.hope_mode = hope_mode(shared_hope);
+            encounter.turn_model = initiative.into();
+            save(&encounter, &out_file)?;
+
+            let value = json!({
+                "file": out_file,
+                "hope": hope,
+                "hope_mode": encounter.hope_mode,
+                "turn_model": encounter.turn_model,
+            });
+            out.emit(value, || {
+                println!("✅ Combat encounter created!");
+                print_hope_mode(encounter.hope_mode, hope);
+                println!("  Initiative: {:?}", encounter.turn_model);
+                println!("  File: {}", out_file);
+                println!("\n💡 Add combatants with:");
+                println!("   daggerheart combat add {} --character <file>", out_file);
+                println!("   daggerheart combat add {} --enemy <name>", out_file);
+            });
         }
 
         CombatCommands::Add {
@@ -454,63 +2905,181 @@ fn handle_combat_command(cmd: CombatCommands) -> Result<(), Box<dyn std::error::
             hp,
             evasion,
             armor,
+            role,
         } => {
-            let mut encounter = CombatEncounter::load_session(&file)?;
+            let mut encounter = load_encounter(&file, out)?;
 
-            if let Some(char_file) = character {
-                let combatant = Combatant::load_from_file(&char_file)?;
-                println!("➕ Adding player: {}", combatant.name);
-                encounter.add_combatant(combatant);
+            let combatant = if let Some(char_file) = character {
+                load::<Combatant>(&char_file)?
             } else if let Some(enemy_name) = enemy {
-                let combatant = Combatant::enemy(enemy_name.clone(), level, hp, evasion, armor);
-                println!("➕ Adding enemy: {}", enemy_name);
-                println!("  HP: {}, Evasion: {}, Armor: {}", hp, evasion, armor);
-                encounter.add_combatant(combatant);
+                let mut builder = CombatantBuilder::adversary(enemy_name)
+                    .with_level(level)
+                    .with_hp(hp)
+                    .with_evasion(evasion)
+                    .with_armor(armor);
+                if let Some(role) = role {
+                    builder = builder.with_role(parse_role(&role)?);
+                }
+                builder.build()?
             } else {
                 return Err("Must specify either --character or --enemy".into());
-            }
-
-            encounter.save_session(&file)?;
-            println!(
-                "\n✅ Combatant added! Total: {}",
-                encounter.combatants.len()
-            );
+            };
+            let name = combatant.name.clone();
+            let is_player = combatant.is_player();
+            let id = encounter.add_combatant(combatant);
+
+            save(&encounter, &file)?;
+
+            let value = json!({
+                "file": file,
+                "id": id,
+                "name": name,
+                "total": encounter.combatants.len(),
+            });
+            out.emit(value, || {
+                if is_player {
+                    println!("➕ Adding player: {}", name);
+                } else {
+                    println!("➕ Adding enemy: {}", name);
+                    println!("  HP: {}, Evasion: {}, Armor: {}", hp, evasion, armor);
+                }
+                println!(
+                    "\n✅ Combatant added! Total: {}",
+                    encounter.combatants.len()
+                );
+            });
         }
 
         CombatCommands::Start { file } => {
-            let mut encounter = CombatEncounter::load_session(&file)?;
+            let mut encounter = load_encounter(&file, out)?;
 
             if encounter.combatants.is_empty() {
                 return Err("No combatants in encounter!".into());
             }
 
             encounter.start();
+            save(&encounter, &file)?;
+
+            let order: Vec<&Combatant> = encounter
+                .turn_order
+                .iter()
+                .filter_map(|&id| encounter.get(id))
+                .collect();
+            let value = json!({
+                "file": file,
+                "round": encounter.round,
+                "turn_order": order
+                    .iter()
+                    .map(|c| json!({ "id": c.id, "name": c.name, "initiative": c.initiative }))
+                    .collect::<Vec<_>>(),
+            });
+            out.emit(value, || {
+                println!("⚔️ Combat started!");
+                println!("\nInitiative order:");
+                for (i, combatant) in order.iter().enumerate() {
+                    println!(
+                        "  {}. {} (Initiative: {})",
+                        i + 1,
+                        combatant.name,
+                        combatant.initiative
+                    );
+                }
+                println!("\n✅ Encounter saved");
+            });
+        }
 
-            println!("⚔️ Combat started!");
-            println!("\nInitiative order:");
-            for (i, &idx) in encounter.turn_order.iter().enumerate() {
-                let combatant = &encounter.combatants[idx];
-                println!(
-                    "  {}. {} (Initiative: {})",
-                    i + 1,
-                    combatant.name,
-                    combatant.initiative
-                );
+        CombatCommands::Next { file } => {
+            let mut encounter = load_encounter(&file, out)?;
+            require_started(&encounter)?;
+
+            let events = encounter.events.len();
+            encounter.next_turn();
+            save(&encounter, &file)?;
+
+            print_turn(&encounter, events, out);
+        }
+
+        CombatCommands::EndRound { file } => {
+            let mut encounter = load_encounter(&file, out)?;
+            require_started(&encounter)?;
+
+            let events = encounter.events.len();
+            encounter.end_round();
+            save(&encounter, &file)?;
+
+            print_turn(&encounter, events, out);
+        }
+
+        CombatCommands::Countdown { file, name, rounds } => {
+            let mut encounter = load_encounter(&file, out)?;
+            encounter.add_countdown(Countdown::new(name.as_str(), rounds));
+            save(&encounter, &file)?;
+
+            let value = json!({ "file": file, "name": name, "rounds": rounds });
+            out.emit(value, || {
+                println!("⏳ Countdown '{}' started: {} rounds", name, rounds);
+            });
+        }
+
+        CombatCommands::Hazard {
+            file,
+            name,
+            save: save_trait,
+            difficulty,
+            damage,
+            condition,
+            zone,
+            each_turn,
+            countdown,
+        } => {
+            let trigger = match countdown {
+                Some(countdown) => HazardTrigger::Countdown(countdown),
+                None if each_turn => HazardTrigger::TurnStart,
+                None => HazardTrigger::EachRound,
+            };
+            let mut hazard = Hazard::new(name, trigger, parse_trait(&save_trait)?, difficulty);
+            if let Some(damage) = damage {
+                hazard = hazard.with_damage(parse_damage_dice(&damage)?);
+            }
+            if let Some(condition) = condition {
+                hazard = hazard.with_condition(parse_condition(&condition)?);
             }
+            if let Some(zone) = zone {
+                hazard = hazard.in_zone(zone);
+            }
+
+            let mut encounter = load_encounter(&file, out)?;
+            encounter.add_hazard(hazard.clone());
+            save(&encounter, &file)?;
 
-            encounter.save_session(&file)?;
-            println!("\n✅ Encounter saved");
+            out.emit(json!({ "file": file, "hazard": hazard }), || {
+                println!(
+                    "⚠️  Hazard '{}' added: {} reaction roll, Difficulty {}",
+                    hazard.name, hazard.save, hazard.difficulty
+                );
+            });
         }
 
-        CombatCommands::Status { file } => {
-            let encounter = CombatEncounter::load_session(&file)?;
+        CombatCommands::Status { file, format } => {
+            let encounter = load_encounter(&file, out)?;
+
+            if let Some(format) = format {
+                println!("{}", encounter.to_format(format)?);
+                return Ok(());
+            }
+            if out != Output::Text {
+                out.emit(json!(encounter), || {});
+                return Ok(());
+            }
 
             println!("=== Combat Status ===");
             println!("Round: {}", encounter.round);
-            println!(
-                "Hope: {}/{}",
-                encounter.hope.current, encounter.hope.maximum
-            );
+            if encounter.hope_mode == HopeMode::Shared {
+                println!(
+                    "Hope: {}/{}",
+                    encounter.hope.current, encounter.hope.maximum
+                );
+            }
             println!("Fear: {}", encounter.fear.current);
             println!();
 
@@ -545,15 +3114,259 @@ fn handle_combat_command(cmd: CombatCommands) -> Result<(), Box<dyn std::error::
                     combatant.evasion,
                     combatant.armor
                 );
+                if encounter.hope_mode == HopeMode::PerCharacter {
+                    if let Some(hope) = combatant.hope() {
+                        println!("      Hope: {}/{}", hope.current, hope.maximum);
+                    }
+                }
+            }
+        }
+
+        CombatCommands::Screen {
+            file,
+            format,
+            out_file,
+        } => {
+            let encounter = load_encounter(&file, out)?;
+            let screen = render::gm_screen(&encounter);
+            let rendered = match format {
+                ScreenFormat::Text => screen.to_text(),
+                ScreenFormat::Md => screen.to_markdown(),
+                ScreenFormat::Html => screen.to_html(),
+            };
+            match out_file {
+                Some(out_file) => {
+                    std::fs::write(&out_file, rendered)?;
+                    out.emit(json!({ "file": out_file, "screen": screen }), || {
+                        println!("📤 Exported the GM screen");
+                        println!("  Screen: {}", out_file);
+                    });
+                }
+                None => out.emit(json!(screen), || print!("{}", rendered)),
+            }
+        }
+
+        CombatCommands::Simulate {
+            file,
+            runs,
+            seed,
+            policy,
+            max_rounds,
+            parallel,
+        } => {
+            let encounter = load_encounter(&file, out)?;
+
+            if encounter.combatants.is_empty() {
+                return Err("No combatants in encounter!".into());
+            }
+
+            let policy = parse_target_policy(&policy)?;
+            let report = MonteCarlo::new(encounter)
+                .with_policies(policy, policy)
+                .with_max_rounds(max_rounds)
+                .with_parallel(parallel)
+                .run(runs, seed);
+
+            let value = json!({
+                "seed": seed,
+                "win_rate": report.win_rate(),
+                "report": report,
+            });
+            out.emit(value, || {
+                println!("🎲 Simulated {} playthroughs (seed {})", report.runs, seed);
+                println!();
+                println!("  Win rate: {:.1}%", report.win_rate() * 100.0);
+                println!(
+                    "  Wins / Losses / Stalemates: {} / {} / {}",
+                    report.player_wins, report.enemy_wins, report.stalemates
+                );
+                println!("  Average rounds: {:.2}", report.average_rounds);
+                println!();
+                println!("  Player HP lost: {:.2}", report.average_player_hp_lost);
+                println!(
+                    "  Player Stress gained: {:.2}",
+                    report.average_player_stress_gained
+                );
+                println!("  Enemy HP lost: {:.2}", report.average_enemy_hp_lost);
+                println!();
+                println!("  ✨ Hope generated: {:.2}", report.average_hope_gained);
+                println!("  💀 Fear generated: {:.2}", report.average_fear_gained);
+            });
+        }
+
+        CombatCommands::Analyze {
+            file,
+            party,
+            simulate,
+            seed,
+        } => {
+            let encounter = load_encounter(&file, out)?;
+            let party = party
+                .iter()
+                .map(|path| load::<Combatant>(path))
+                .collect::<Result<Vec<_>, _>>()?;
+            let estimate = match simulate {
+                Some(runs) => encounter.estimate_difficulty_simulated(&party, runs, seed),
+                None => encounter.estimate_difficulty(&party),
+            };
+
+            out.emit(json!(estimate), || {
+                let icon = match estimate.threat {
+                    Threat::Trivial => "🟢",
+                    Threat::Standard => "🟡",
+                    Threat::Hard => "🟠",
+                    Threat::Deadly => "🔴",
+                };
+                println!("{} {} encounter", icon, estimate.threat);
+                println!(
+                    "  Party: {} at tier {}",
+                    estimate.party_size, estimate.party_tier
+                );
+                println!(
+                    "  Battle points: {} of {} ({})",
+                    estimate.spent, estimate.budget, estimate.battle_point_threat
+                );
+                println!();
+                for enemy in &estimate.enemies {
+                    let assumed = if enemy.assumed_role { "?" } else { "" };
+                    println!(
+                        "  {}× {} - Tier {} {}{}: {} BP",
+                        enemy.count,
+                        enemy.name,
+                        enemy.tier,
+                        enemy.role,
+                        assumed,
+                        enemy.battle_points
+                    );
+                }
+                if estimate.enemies.iter().any(|enemy| enemy.assumed_role) {
+                    println!("  ? No role recorded, counted as Standard");
+                }
+                for name in &estimate.above_tier {
+                    println!("  ⚠️  {} is from a higher tier than the party", name);
+                }
+                if let Some(report) = &estimate.simulation {
+                    println!();
+                    println!(
+                        "  Simulated: {:.1}% won over {} fights, {:.2} HP lost on average",
+                        report.win_rate() * 100.0,
+                        report.runs,
+                        report.average_player_hp_lost
+                    );
+                }
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "schema")]
+fn handle_schema_command(
+    name: Option<String>,
+    dir: Option<String>,
+    out: Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use daggerheart_engine::schema;
+
+    if let Some(dir) = dir {
+        std::fs::create_dir_all(&dir)?;
+        let mut paths = Vec::new();
+        for (name, schema) in schema::all_schemas() {
+            let path = std::path::Path::new(&dir).join(format!("{}.schema.json", name));
+            std::fs::write(&path, serde_json::to_string_pretty(&schema)?)?;
+            paths.push(path);
+        }
+        out.emit(json!(paths), || {
+            for path in &paths {
+                println!("📄 {}", path.display());
             }
+        });
+        return Ok(());
+    }
+
+    match name {
+        Some(name) => {
+            let schema =
+                schema::schema_by_name(&name).ok_or_else(|| format!("Unknown schema: {}", name))?;
+            println!("{}", serde_json::to_string_pretty(&schema)?);
         }
+        None => out.emit(json!(schema::SCHEMA_NAMES), || {
+            println!("📐 Available Schemas:\n");
+            for name in schema::SCHEMA_NAMES {
+                println!("  • {}", name);
+            }
+        }),
     }
+    Ok(())
+}
+
+#[cfg(feature = "tui")]
+fn handle_tui_command(
+    file: String,
+    modifier: i8,
+    damage: String,
+    autosave: Option<AutosavePolicy>,
+    campaign: Option<String>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let encounter = load_encounter(&file, Output::Text)?;
+    let damage = parse_damage_dice(&damage)?;
+    let autosave = match (autosave, campaign) {
+        (Some(policy), _) => policy,
+        (None, Some(campaign)) => load::<Campaign>(&campaign)?.autosave,
+        (None, None) => AutosavePolicy::default(),
+    };
+    tui::run(tui::App::new(encounter, file, Attack::new(modifier), damage).with_autosave(autosave))
+}
+
+#[cfg(feature = "man")]
+fn handle_man_command(dir: Option<String>, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    let command = Cli::command();
 
+    let Some(dir) = dir else {
+        clap_mangen::Man::new(command).render(&mut std::io::stdout())?;
+        return Ok(());
+    };
+
+    std::fs::create_dir_all(&dir)?;
+    clap_mangen::generate_to(command, &dir)?;
+
+    let mut pages: Vec<_> = std::fs::read_dir(&dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "1")
+                && path
+                    .file_name()
+                    .is_some_and(|name| name.to_string_lossy().starts_with("daggerheart"))
+        })
+        .collect();
+    pages.sort();
+    out.emit(json!(pages), || {
+        for page in &pages {
+            println!("📄 {}", page.display());
+        }
+    });
+    Ok(())
+}
+
+#[cfg(feature = "server")]
+fn handle_serve_command(
+    addr: String,
+    dir: String,
+    out: Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    use daggerheart_engine::server;
+    use daggerheart_engine::storage::DirectoryStorage;
+
+    let storage = DirectoryStorage::new(&dir)?;
+    out.emit(json!({ "addr": addr, "dir": dir }), || {
+        println!("🌐 Serving on http://{} (saves in {})", addr, dir);
+    });
+    tokio::runtime::Runtime::new()?.block_on(server::serve(addr, storage))?;
     Ok(())
 }
 
-fn list_classes() {
-    println!("Available Classes:\n");
+fn list_classes(out: Output) {
     let classes = [
         ("Bard", "Charismatic performer, Presence + Knowledge"),
         ("Druid", "Nature shapeshifter, Instinct + Agility"),
@@ -566,25 +3379,315 @@ fn list_classes() {
         ("Wizard", "Scholarly mage, Knowledge + Finesse"),
     ];
 
-    for (name, desc) in classes {
-        println!("  {:<12} - {}", name, desc);
-    }
+    let value = Class::iter()
+        .zip(classes)
+        .map(|(class, (name, desc))| {
+            json!({ "name": name, "description": desc, "hope_feature": class.hope_feature() })
+        })
+        .collect();
+    out.emit(Value::Array(value), || {
+        println!("Available Classes:\n");
+        for (class, (name, desc)) in Class::iter().zip(classes) {
+            let feature = class.hope_feature();
+            println!("  {:<12} - {}", name, desc);
+            println!(
+                "  {:<12}   {} ({} Hope): {}",
+                "", feature.name, feature.hope_cost, feature.description
+            );
+        }
+    });
 }
 
-fn list_ancestries() {
-    println!("Available Ancestries:\n");
+fn list_ancestries(out: Output) {
     let ancestries = [
         "Clank", "Daemon", "Drakona", "Dwarf", "Faerie", "Faun", "Fungril", "Galapa", "Giant",
         "Goblin", "Halfling", "Human", "Inferis", "Katari", "Orc", "Ribbet", "Simiah",
     ];
 
-    for (i, ancestry) in ancestries.iter().enumerate() {
-        if i % 3 == 0 && i != 0 {
-            println!();
+    out.emit(json!(ancestries), || {
+        println!("Available Ancestries:\n");
+        for (i, ancestry) in ancestries.iter().enumerate() {
+            if i % 3 == 0 && i != 0 {
+                println!();
+            }
+            print!("  {:<12}", ancestry);
+        }
+        println!("\n");
+    });
+}
+
+fn load_content(path: Option<String>) -> Result<ContentRegistry, EngineError> {
+    match path {
+        Some(path) => load(&path),
+        None => Ok(ContentRegistry::builtin()),
+    }
+}
+
+fn write_catalog_template(
+    locale: String,
+    out_file: Option<String>,
+    content: Option<String>,
+    out: Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let template = Catalog::template(locale.clone(), &load_content(content)?);
+    let out_file = out_file.unwrap_or_else(|| format!("{}.json", locale));
+    save(&template, &out_file)?;
+
+    let value = json!({
+        "locale": locale,
+        "file": out_file,
+        "keys": template.messages.len(),
+    });
+    out.emit(value, || {
+        println!("🌐 Wrote a {} catalog to translate", locale);
+        println!("  Keys: {}", template.messages.len());
+        println!("  File: {}", out_file);
+        println!("\n💡 Use it with:");
+        println!(
+            "   daggerheart char export <file> --format md --catalog {}",
+            out_file
+        );
+    });
+    Ok(())
+}
+
+fn lint_content(file: String, out: Output) -> Result<(), Box<dyn std::error::Error>> {
+    let content: ContentRegistry = load(&file)?;
+    let diagnostics = content.lint();
+    let errors = diagnostics
+        .iter()
+        .filter(|d| d.severity == Severity::Error)
+        .count();
+
+    out.emit(json!({ "file": file, "diagnostics": diagnostics }), || {
+        if diagnostics.is_empty() {
+            println!("✅ {} looks good", file);
+        }
+        for diagnostic in &diagnostics {
+            let (icon, name) = match &diagnostic.subject {
+                Subject::Card(id) => ("🃏", id),
+                Subject::Adversary(name) => ("👹", name),
+                Subject::Weapon(name) => ("⚔️", name),
+            };
+            let marker = match diagnostic.severity {
+                Severity::Error => "❌",
+                Severity::Warning => "⚠️",
+            };
+            println!("{} {} {}: {}", marker, icon, name, diagnostic.message);
+        }
+    });
+    if errors > 0 {
+        return Err(format!("{} has {} error(s)", file, errors).into());
+    }
+    Ok(())
+}
+
+fn load_catalog(path: Option<String>) -> Result<Catalog, EngineError> {
+    match path {
+        Some(path) => load(&path),
+        None => Ok(Catalog::english()),
+    }
+}
+
+fn handle_adversary_command(
+    cmd: AdversaryCommands,
+    out: Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    match cmd {
+        AdversaryCommands::New {
+            name,
+            tier,
+            role,
+            content,
+        } => {
+            let role = role.map(|role| parse_role(&role)).transpose()?;
+            let stdin = std::io::stdin().lock();
+            // Keep stdout clean for the JSON result
+            let adversary = if out == Output::Text {
+                wizard::Wizard::new(stdin, std::io::stdout()).adversary(name, tier, role)?
+            } else {
+                wizard::Wizard::new(stdin, std::io::stderr()).adversary(name, tier, role)?
+            };
+            let deviations = AdversaryBuilder::from_adversary(adversary.clone()).deviations();
+
+            let file = match content {
+                Some(file) => {
+                    let mut registry = if std::path::Path::new(&file).exists() {
+                        load::<ContentRegistry>(&file)?
+                    } else {
+                        ContentRegistry::new()
+                    };
+                    registry.add_adversary(adversary.clone());
+                    save(&registry, &file)?;
+                    file
+                }
+                None => {
+                    let file = format!("{}_adversary.json", adversary.name);
+                    save(&adversary, &file)?;
+                    file
+                }
+            };
+
+            let value = json!({
+                "adversary": adversary,
+                "deviations": deviations,
+                "file": file,
+            });
+            out.emit(value, || {
+                println!();
+                print_adversaries(&[&adversary]);
+                if !deviations.is_empty() {
+                    println!("📏 Compared with the benchmark:");
+                    for deviation in &deviations {
+                        println!("  {}", adversary_wizard::describe(deviation));
+                    }
+                }
+                println!("\n📁 Saved to {}", file);
+            });
+            Ok(())
+        }
+
+        AdversaryCommands::Improvise { tier, role, name } => {
+            let mut adversary = Adversary::quick_stat(tier, parse_role(&role)?);
+            if let Some(name) = name {
+                adversary.name = name;
+            }
+            out.emit(json!(adversary), || print_adversaries(&[&adversary]));
+            Ok(())
+        }
+    }
+}
+
+fn list_adversaries(
+    tier: Option<u8>,
+    role: Option<String>,
+    content: Option<String>,
+    out: Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let role = role.map(|role| parse_role(&role)).transpose()?;
+    let registry = load_content(content)?;
+    let adversaries = registry.find_adversaries(tier, role);
+
+    out.emit(json!(adversaries), || print_adversaries(&adversaries));
+    Ok(())
+}
+
+fn print_adversaries(adversaries: &[&Adversary]) {
+    if adversaries.is_empty() {
+        println!("No adversaries match.");
+        return;
+    }
+
+    for adversary in adversaries {
+        println!(
+            "👹 {} - Tier {} {} ({} BP)",
+            adversary.name,
+            adversary.tier,
+            adversary.role,
+            adversary.battle_points()
+        );
+        println!(
+            "  Difficulty: {} | Thresholds: {}/{} | HP: {} | Stress: {}",
+            adversary.difficulty,
+            adversary.major_threshold,
+            adversary.severe_threshold,
+            adversary.hp,
+            adversary.stress
+        );
+        println!(
+            "  Attack: {:+} | Damage: {}",
+            adversary.attack_modifier, adversary.damage
+        );
+        for feature in &adversary.features {
+            println!("  ✨ {}: {}", feature.name, feature.description);
+        }
+        for feature in &adversary.triggers {
+            println!("  ⚡ {}", feature);
+        }
+        if !adversary.immunities.is_empty() {
+            let immunities: Vec<String> =
+                adversary.immunities.iter().map(|c| c.to_string()).collect();
+            println!("  🛡️  Immune to {}", immunities.join(", "));
+        }
+        if let Some(mount) = &adversary.mount {
+            println!("  🐎 Rides {} (HP {})", mount.name, mount.hp.maximum);
         }
-        print!("  {:<12}", ancestry);
+        println!();
     }
-    println!("\n");
+}
+
+fn list_cards(
+    domain: Option<String>,
+    level: Option<u8>,
+    content: Option<String>,
+    catalog: Option<String>,
+    out: Output,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let domain = domain.map(|domain| parse_domain(&domain)).transpose()?;
+    let registry = load_content(content)?.localized(&load_catalog(catalog)?);
+    let cards = registry.find_cards(domain, level);
+
+    out.emit(json!(cards), || {
+        if cards.is_empty() {
+            println!("No cards match.");
+        }
+
+        for card in &cards {
+            println!(
+                "🃏 {} - {} level {} ({:?})",
+                card.name, card.domain, card.level_requirement, card.action_cost
+            );
+            println!("  {}", card.description);
+            println!();
+        }
+    });
+    Ok(())
+}
+
+fn parse_countdown(s: &str) -> Result<Countdown, String> {
+    let (name, steps) = s
+        .rsplit_once('=')
+        .ok_or_else(|| format!("Expected NAME=STEPS, got '{}'", s))?;
+    let steps = steps
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid countdown length '{}'", steps))?;
+    Ok(Countdown::new(name.trim(), steps))
+}
+
+fn parse_npc(s: &str) -> Result<SocialNpc, String> {
+    let (name, difficulty) = s
+        .rsplit_once('=')
+        .ok_or_else(|| format!("Expected NAME=DIFFICULTY, got '{}'", s))?;
+    let difficulty = difficulty
+        .trim()
+        .parse()
+        .map_err(|_| format!("Invalid difficulty '{}'", difficulty))?;
+    Ok(SocialNpc::new(name.trim(), difficulty))
+}
+
+fn parse_role(s: &str) -> Result<AdversaryRole, Box<dyn std::error::Error>> {
+    AdversaryRole::iter()
+        .find(|role| role.to_string().eq_ignore_ascii_case(s))
+        .ok_or_else(|| format!("Unknown role: {}", s).into())
+}
+
+fn parse_condition(s: &str) -> Result<Condition, Box<dyn std::error::Error>> {
+    Condition::iter()
+        .find(|condition| condition.to_string().eq_ignore_ascii_case(s))
+        .ok_or_else(|| format!("Unknown condition: {}", s).into())
+}
+
+fn parse_domain(s: &str) -> Result<Domain, Box<dyn std::error::Error>> {
+    Domain::iter()
+        .find(|domain| domain.to_string().eq_ignore_ascii_case(s))
+        .ok_or_else(|| format!("Unknown domain: {}", s).into())
+}
+
+fn parse_trait(s: &str) -> Result<AttributeType, Box<dyn std::error::Error>> {
+    AttributeType::iter()
+        .find(|attribute| attribute.to_string().eq_ignore_ascii_case(s))
+        .ok_or_else(|| format!("Unknown trait: {}", s).into())
 }
 
 fn parse_class(s: &str) -> Result<Class, Box<dyn std::error::Error>> {
@@ -633,6 +3736,113 @@ fn parse_ancestry(s: &str) -> Result<Ancestry, Box<dyn std::error::Error>> {
     }
 }
 
+/// Load a saved file, picking the format from its extension
+fn load<T: Persist>(path: &str) -> Result<T, EngineError> {
+    storage::load_file_as(path, Format::from_path(path))
+}
+
+/// Load an encounter file, repairing anything inconsistent in it and
+/// warning about each fix
+fn load_encounter(path: &str, out: Output) -> Result<CombatEncounter, EngineError> {
+    let mut encounter = load::<CombatEncounter>(path)?;
+    for issue in encounter.repair() {
+        out.warn(&format!("Repaired {}: {}", path, issue));
+    }
+    Ok(encounter)
+}
+
+/// Load a character file along with its progress file, if there is one
+fn load_sheet(path: &str) -> Result<CharacterSheet, EngineError> {
+    let character = load::<Combatant>(path)?;
+    let progress_file = path.replace("_char.", "_progress.");
+    Ok(match load::<CharacterProgress>(&progress_file) {
+        Ok(progress) => CharacterSheet::from_parts(character, progress),
+        Err(_) => CharacterSheet::new(character),
+    })
+}
+
+fn require_started(encounter: &CombatEncounter) -> Result<(), Box<dyn std::error::Error>> {
+    if encounter.round == 0 {
+        return Err("Combat hasn't started! Run `daggerheart combat start` first.".into());
+    }
+    Ok(())
+}
+
+/// Print what happened since `since` events and whose turn it is now
+fn print_turn(encounter: &CombatEncounter, since: usize, out: Output) {
+    let events = &encounter.events[since..];
+    let current = encounter.current_combatant();
+
+    let value = json!({
+        "round": encounter.round,
+        "current": current.map(|c| json!({ "id": c.id, "name": c.name })),
+        "events": events,
+        "countdowns": encounter.countdowns,
+        "over": encounter.is_over(),
+        "player_victory": encounter.player_victory(),
+    });
+    out.emit(value, || {
+        for event in events {
+            match event {
+                CombatEvent::RoundStarted { round } => println!("🔔 Round {} begins", round),
+                CombatEvent::CountdownExpired { name } => {
+                    println!("⌛ Countdown '{}' has run out!", name)
+                }
+                CombatEvent::HazardTriggered {
+                    combatant,
+                    hazard,
+                    total,
+                    saved,
+                } => {
+                    let name = encounter
+                        .get(*combatant)
+                        .map_or_else(String::new, |c| c.name.to_string());
+                    if *saved {
+                        println!("⚠️  {}: {} avoids it ({})", hazard, name, total);
+                    } else {
+                        println!("⚠️  {}: {} is caught ({})", hazard, name, total);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        for countdown in &encounter.countdowns {
+            println!(
+                "⏳ {}: {} round(s) left",
+                countdown.name, countdown.remaining
+            );
+        }
+
+        if encounter.is_over() {
+            match encounter.player_victory() {
+                Some(true) => println!("\n🎉 VICTORY! Players won!"),
+                Some(false) => println!("\n💀 DEFEAT! Enemies won!"),
+                None => {}
+            }
+        } else if let Some(current) = current {
+            println!(
+                "\n▶️  Round {}: {}'s turn (HP: {}/{})",
+                encounter.round, current.name, current.hp.current, current.hp.maximum
+            );
+        }
+    });
+}
+
+/// Save to a file, picking the format from its extension
+fn save<T: Persist>(value: &T, path: &str) -> Result<(), EngineError> {
+    storage::save_file_as(value, path, Format::from_path(path))
+}
+
+fn parse_target_policy(s: &str) -> Result<TargetPolicy, Box<dyn std::error::Error>> {
+    match s.to_lowercase().as_str() {
+        "random" => Ok(TargetPolicy::Random),
+        "weakest" => Ok(TargetPolicy::Weakest),
+        "strongest" => Ok(TargetPolicy::Strongest),
+        _ => Err(format!("Unknown policy: {}. Use random, weakest, or strongest", s).into()),
+    }
+}
+
 fn parse_die(s: &str) -> Result<Die, Box<dyn std::error::Error>> {
     match s.to_lowercase().as_str() {
         "d4" => Ok(Die::D4),
@@ -672,3 +3882,27 @@ fn parse_damage_dice(s: &str) -> Result<DamageDice, Box<dyn std::error::Error>>
 
     Ok(DamageDice::new(dice).with_bonus(bonus))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cli_arguments_dont_clash() {
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn test_output_goes_anywhere() {
+        for args in [
+            ["daggerheart", "--output", "json", "roll", "duality"],
+            ["daggerheart", "roll", "duality", "--output", "json"],
+        ] {
+            assert!(Cli::try_parse_from(args).unwrap().output == Output::Json);
+        }
+
+        let cli =
+            Cli::try_parse_from(["daggerheart", "combat", "new", "-o", "battle.json"]).unwrap();
+        assert!(cli.output == Output::Text);
+    }
+}
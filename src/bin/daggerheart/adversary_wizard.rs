@@ -0,0 +1,177 @@
+//! `daggerheart adversary new` - interactive adversary building
+//!
+//! Starts from the benchmark stat block for a role and tier, then asks for
+//! each stat with the benchmark as the default, saying how far every
+//! change strays from it.
+
+use crate::wizard::Wizard;
+use daggerheart_engine::combat::{Adversary, AdversaryBuilder, AdversaryRole, Deviation, Stat};
+use std::fmt::Display;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+use strum::IntoEnumIterator;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+impl<R: BufRead, W: Write> Wizard<R, W> {
+    /// Ask for the adversary's stats and features and build it
+    pub fn adversary(
+        &mut self,
+        name: Option<String>,
+        tier: Option<u8>,
+        role: Option<AdversaryRole>,
+    ) -> Result<Adversary> {
+        let name = match name {
+            Some(name) => name,
+            None => loop {
+                let name = self.ask("Name: ")?;
+                if !name.is_empty() {
+                    break name;
+                }
+            },
+        };
+        let tier = match tier {
+            Some(tier) => tier,
+            None => self.choose("Tier", &[1u8, 2, 3, 4])?,
+        };
+        let role = match role {
+            Some(role) => role,
+            None => self.choose("Role", &AdversaryRole::iter().collect::<Vec<_>>())?,
+        };
+
+        let mut builder = AdversaryBuilder::new(name, tier, role);
+        self.say(&format!(
+            "\nStarting from the tier {} {} benchmark. Press Enter to keep a value.",
+            tier, role
+        ))?;
+
+        let stats = builder.adversary().clone();
+        let difficulty = self.ask_value("Difficulty", stats.difficulty)?;
+        builder = builder.with_difficulty(difficulty);
+        self.report(&builder, Stat::Difficulty)?;
+
+        let major = self.ask_value("Major threshold", stats.major_threshold)?;
+        let severe = self.ask_value("Severe threshold", stats.severe_threshold)?;
+        builder = builder.with_thresholds(major, severe);
+        self.report(&builder, Stat::MajorThreshold)?;
+        self.report(&builder, Stat::SevereThreshold)?;
+
+        let hp = self.ask_value("HP", stats.hp)?;
+        builder = builder.with_hp(hp);
+        self.report(&builder, Stat::Hp)?;
+
+        let stress = self.ask_value("Stress", stats.stress)?;
+        builder = builder.with_stress(stress);
+        self.report(&builder, Stat::Stress)?;
+
+        let modifier = self.ask_value("Attack modifier", stats.attack_modifier)?;
+        let damage = loop {
+            let answer = self.ask(&format!("  Damage [{}]: ", stats.damage))?;
+            if answer.is_empty() {
+                break stats.damage.clone();
+            }
+            match crate::parse_damage_dice(&answer) {
+                Ok(damage) => break damage,
+                Err(error) => self.say(&format!("  ❌ {}", error))?,
+            }
+        };
+        builder = builder.with_attack(modifier, damage);
+        self.report(&builder, Stat::AttackModifier)?;
+        self.report(&builder, Stat::Damage)?;
+
+        self.say("\nFeatures (leave the name blank to finish):")?;
+        loop {
+            let feature = self.ask("  Name: ")?;
+            if feature.is_empty() {
+                break;
+            }
+            let description = self.ask("  Description: ")?;
+            builder = builder.with_feature(feature, description);
+        }
+
+        Ok(builder.build())
+    }
+
+    /// Ask for a number, keeping `default` on an empty answer
+    fn ask_value<T: FromStr + Display + Copy>(&mut self, label: &str, default: T) -> Result<T> {
+        loop {
+            let answer = self.ask(&format!("  {} [{}]: ", label, default))?;
+            if answer.is_empty() {
+                return Ok(default);
+            }
+            match answer.parse() {
+                Ok(value) => return Ok(value),
+                Err(_) => self.say("  ❌ Enter a number")?,
+            }
+        }
+    }
+
+    /// Say how far a stat is from the benchmark, if it moved
+    fn report(&mut self, builder: &AdversaryBuilder, stat: Stat) -> Result<()> {
+        match builder.deviations().into_iter().find(|d| d.stat == stat) {
+            Some(deviation) => self.say(&format!("    ↳ {}", describe(&deviation))),
+            None => Ok(()),
+        }
+    }
+}
+
+/// A deviation as text, such as `HP 9 is 2 above the benchmark of 7 (+29%)`
+pub fn describe(deviation: &Deviation) -> String {
+    let difference = deviation.difference();
+    let direction = if difference > 0.0 { "above" } else { "below" };
+    let percent = deviation
+        .percent()
+        .map(|percent| format!(" ({:+.0}%)", percent))
+        .unwrap_or_default();
+    format!(
+        "{} {} is {} {} the benchmark of {}{}",
+        deviation.stat,
+        deviation.value,
+        difference.abs(),
+        direction,
+        deviation.benchmark,
+        percent
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keeps_benchmark_and_takes_changes() {
+        // Enter through every stat but HP, then add one feature
+        let answers = "\n\n\n9\n\n\n\nCave-in\nBring down the ceiling\n\n";
+        let mut output = Vec::new();
+        let adversary = Wizard::new(answers.as_bytes(), &mut output)
+            .adversary(
+                Some(String::from("Tunnel Brute")),
+                Some(1),
+                Some(AdversaryRole::Bruiser),
+            )
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        let benchmark = AdversaryBuilder::benchmark("Tunnel Brute", 1, AdversaryRole::Bruiser);
+        assert_eq!(adversary.hp, 9);
+        assert_eq!(adversary.difficulty, benchmark.difficulty);
+        assert_eq!(adversary.damage, benchmark.damage);
+        assert_eq!(adversary.features[0].name, "Cave-in");
+        assert!(output.contains("↳ HP 9 is 2 above the benchmark of 7 (+29%)"));
+    }
+
+    #[test]
+    fn test_asks_for_tier_and_role_and_rejects_bad_numbers() {
+        let answers = "2\nsolo\nlots\n\n\n\n\n\n\n2d10+4\n\n";
+        let mut output = Vec::new();
+        let adversary = Wizard::new(answers.as_bytes(), &mut output)
+            .adversary(Some(String::from("Wyrm")), None, None)
+            .unwrap();
+        let output = String::from_utf8(output).unwrap();
+
+        assert_eq!(adversary.tier, 2);
+        assert_eq!(adversary.role, AdversaryRole::Solo);
+        assert!(output.contains("❌ Enter a number"));
+        assert_eq!(adversary.damage.to_string(), "2d10+4");
+    }
+}
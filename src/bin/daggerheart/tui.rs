@@ -0,0 +1,863 @@
+//! `daggerheart tui` - a live encounter tracker for the GM screen
+//!
+//! Shows the turn order, the selected combatant's HP, Stress and Hope,
+//! the Fear pool and the event log. Every change goes through the
+//! engine, so the saved encounter and its log stay consistent with the
+//! other `combat` commands. Encounters with a shared Hope pool show the
+//! party's pool instead. Changes are saved under an [`AutosavePolicy`]
+//! as well as on `s` and on quit.
+
+use crate::save;
+use daggerheart_engine::combat::simulation::{CombatEncounter, Combatant, CombatantId};
+use daggerheart_engine::combat::{Attack, CombatEvent, HopeMode};
+use daggerheart_engine::core::dice::DamageDice;
+use daggerheart_engine::render;
+use daggerheart_engine::storage::{Autosave, AutosavePolicy};
+use daggerheart_engine::EngineError;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Gauge, List, ListItem, ListState, Paragraph};
+use ratatui::Frame;
+use std::time::Instant;
+
+/// Player characters start with six Stress slots
+const STRESS_SLOTS: u8 = 6;
+
+/// Log lines kept on screen
+const LOG_LINES: usize = 200;
+
+const HELP: &str = "n next turn · r end round · ↑↓ select · a attack · d damage · \
+                    h/H Hope · f/F Fear · g GM screen · s save · q quit";
+
+/// What keys currently do
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Mode {
+    /// Hotkeys act on the encounter
+    Normal,
+    /// Typing an amount of damage for the selected combatant
+    Damage(String),
+}
+
+/// The tracker's state, independent of the terminal
+pub struct App {
+    encounter: CombatEncounter,
+    file: String,
+    attack: Attack,
+    damage: DamageDice,
+    selected: usize,
+    mode: Mode,
+    /// Showing the GM screen instead of the turn order and details
+    screen: bool,
+    status: String,
+    quit: bool,
+    autosave: Autosave,
+}
+
+impl App {
+    pub fn new(
+        encounter: CombatEncounter,
+        file: String,
+        attack: Attack,
+        damage: DamageDice,
+    ) -> Self {
+        Self {
+            encounter,
+            file,
+            attack,
+            damage,
+            selected: 0,
+            mode: Mode::Normal,
+            screen: false,
+            status: String::from("Ready"),
+            quit: false,
+            autosave: Autosave::new(AutosavePolicy::default(), Instant::now()),
+        }
+    }
+
+    /// Save on its own under `policy`, not only on `s` and quit
+    pub fn with_autosave(mut self, policy: AutosavePolicy) -> Self {
+        self.autosave = Autosave::new(policy, Instant::now());
+        self
+    }
+
+    /// Write the encounter to its file
+    fn save(&mut self) -> Result<(), EngineError> {
+        save(&self.encounter, &self.file)?;
+        self.autosave.saved(Instant::now());
+        Ok(())
+    }
+
+    /// Save if the autosave policy says it's time, given whether the
+    /// last key changed the encounter
+    fn autosave(&mut self, changed: bool) {
+        let now = Instant::now();
+        let due = if changed {
+            self.autosave.changed(now)
+        } else {
+            self.autosave.is_due(now)
+        };
+        if !due {
+            return;
+        }
+        self.status = match self.save() {
+            Ok(()) => format!("{} · autosaved", self.status),
+            Err(error) => format!("❌ Autosave failed: {}", error),
+        };
+    }
+
+    /// Combatants in initiative order, then anyone not in the order yet
+    fn roster(&self) -> Vec<&Combatant> {
+        let mut roster: Vec<&Combatant> = self
+            .encounter
+            .turn_order
+            .iter()
+            .filter_map(|&id| self.encounter.get(id))
+            .collect();
+        for combatant in &self.encounter.combatants {
+            if !self.encounter.turn_order.contains(&combatant.id) {
+                roster.push(combatant);
+            }
+        }
+        roster
+    }
+
+    fn selected_id(&self) -> Option<CombatantId> {
+        self.roster().get(self.selected).map(|c| c.id)
+    }
+
+    fn require_selected(&self) -> Result<CombatantId, EngineError> {
+        self.selected_id()
+            .ok_or_else(|| EngineError::InvalidCombatAction(String::from("No one is selected")))
+    }
+
+    /// Handle one key press
+    fn handle_key(&mut self, key: KeyCode) {
+        let logged = self.encounter.events.len();
+        let result = match &mut self.mode {
+            Mode::Damage(amount) => match key {
+                KeyCode::Char(c) if c.is_ascii_digit() && amount.len() < 3 => {
+                    amount.push(c);
+                    Ok(())
+                }
+                KeyCode::Backspace => {
+                    amount.pop();
+                    Ok(())
+                }
+                KeyCode::Enter => {
+                    let amount = amount.parse().unwrap_or(0);
+                    self.mode = Mode::Normal;
+                    self.apply_damage(amount)
+                }
+                KeyCode::Esc => {
+                    self.mode = Mode::Normal;
+                    self.status = String::from("Cancelled");
+                    Ok(())
+                }
+                _ => Ok(()),
+            },
+            Mode::Normal => self.handle_hotkey(key),
+        };
+
+        if let Err(error) = result {
+            self.status = format!("❌ {}", error);
+        }
+        // Everything that changes the encounter goes in its log
+        self.autosave(self.encounter.events.len() != logged);
+    }
+
+    fn handle_hotkey(&mut self, key: KeyCode) -> Result<(), EngineError> {
+        match key {
+            KeyCode::Char('n') => {
+                if self.encounter.round == 0 {
+                    self.encounter.start();
+                    self.status = String::from("Initiative rolled");
+                } else {
+                    self.encounter.next_turn();
+                    self.status = String::from("Next turn");
+                }
+            }
+            KeyCode::Char('r') if self.encounter.round > 0 => {
+                self.encounter.end_round();
+                self.status = format!("Round {} begins", self.encounter.round);
+            }
+            KeyCode::Up | KeyCode::Char('k') => {
+                self.selected = self.selected.saturating_sub(1);
+            }
+            KeyCode::Down | KeyCode::Char('j') => {
+                let last = self.encounter.combatants.len().saturating_sub(1);
+                self.selected = (self.selected + 1).min(last);
+            }
+            KeyCode::Char('a') => self.attack_selected()?,
+            KeyCode::Char('d') if self.selected_id().is_some() => {
+                self.mode = Mode::Damage(String::new());
+            }
+            KeyCode::Char('h') => self.encounter.gain_hope(self.require_selected()?, 1)?,
+            KeyCode::Char('H') => self.encounter.spend_hope(self.require_selected()?, 1)?,
+            KeyCode::Char('f') => self.encounter.gain_fear(1),
+            KeyCode::Char('F') => self.encounter.spend_fear(1)?,
+            KeyCode::Char('g') => self.screen = !self.screen,
+            KeyCode::Char('s') => {
+                self.save()?;
+                self.status = format!("Saved to {}", self.file);
+            }
+            KeyCode::Char('q') | KeyCode::Esc => self.quit = true,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// The acting combatant attacks the selected one
+    fn attack_selected(&mut self) -> Result<(), EngineError> {
+        let (Some(attacker), Some(target)) =
+            (self.encounter.current_combatant_id(), self.selected_id())
+        else {
+            self.status = String::from("Start combat and select a target first");
+            return Ok(());
+        };
+
+        let hp_before = self.encounter.get(target).map_or(0, |c| c.hp.current);
+        let result = self
+            .encounter
+            .resolve_attack(attacker, target, &self.attack, &self.damage)?;
+        let hp_after = self.encounter.get(target).map_or(0, |c| c.hp.current);
+
+        self.status = if hp_after < hp_before {
+            format!(
+                "Hit! {} total, {} HP lost",
+                result.total,
+                hp_before - hp_after
+            )
+        } else if result.hit {
+            format!("Hit! {} total, no HP lost", result.total)
+        } else {
+            format!("Miss ({} total)", result.total)
+        };
+        Ok(())
+    }
+
+    fn apply_damage(&mut self, amount: u16) -> Result<(), EngineError> {
+        let Some(target) = self.selected_id() else {
+            return Ok(());
+        };
+        let result = self.encounter.apply_damage(target, amount)?;
+        self.status = format!(
+            "{} damage: {} HP lost, {} Stress",
+            amount, result.hp_lost, result.stress_gained
+        );
+        Ok(())
+    }
+
+    fn name(&self, id: CombatantId) -> String {
+        self.encounter
+            .get(id)
+            .map_or_else(|| id.to_string(), |c| c.name.to_string())
+    }
+
+    /// One readable line for a logged event
+    fn describe(&self, event: &CombatEvent) -> String {
+        match event {
+            CombatEvent::CombatantAdded { name, .. } => format!("{} joins", name),
+            CombatEvent::InitiativeRolled {
+                combatant,
+                initiative,
+            } => format!("{} rolls {} initiative", self.name(*combatant), initiative),
+            CombatEvent::RoundStarted { round } => format!("── Round {} ──", round),
+            CombatEvent::CombatantSummoned { combatant, .. } => {
+                format!("{} arrives", self.name(*combatant))
+            }
+            CombatEvent::CombatantRemoved { combatant } => {
+                format!("{} leaves", self.name(*combatant))
+            }
+            CombatEvent::TurnEnded { combatant, .. } => {
+                format!("{} ends their turn", self.name(*combatant))
+            }
+            CombatEvent::AttackResolved {
+                attacker,
+                target,
+                result,
+            } => format!(
+                "{} attacks {}: {} ({} Hope, {} Fear)",
+                self.name(*attacker),
+                self.name(*target),
+                result.total,
+                result.hope,
+                result.fear
+            ),
+            CombatEvent::DamageApplied { target, damage } => format!(
+                "{} takes {} damage, loses {} HP",
+                self.name(*target),
+                damage.raw_damage,
+                damage.hp_lost
+            ),
+            CombatEvent::StressGained { combatant, amount } => {
+                format!("{} marks {} Stress", self.name(*combatant), amount)
+            }
+            CombatEvent::CombatantDefeated { combatant } => {
+                format!("💀 {} is defeated", self.name(*combatant))
+            }
+            CombatEvent::HopeGained { combatant, amount } => {
+                format!("{} gains {} Hope", self.name(*combatant), amount)
+            }
+            CombatEvent::HopeSpent { combatant, amount } => {
+                format!("{} spends {} Hope", self.name(*combatant), amount)
+            }
+            CombatEvent::FearGained { amount } => format!("+{} Fear", amount),
+            CombatEvent::FearSpent { amount } => format!("-{} Fear", amount),
+            CombatEvent::ConditionApplied {
+                combatant,
+                condition,
+            } => format!("{} is {}", self.name(*combatant), condition),
+            CombatEvent::ConditionRemoved {
+                combatant,
+                condition,
+            } => format!("{} is no longer {}", self.name(*combatant), condition),
+            CombatEvent::ConditionResisted {
+                combatant,
+                condition,
+            } => format!("{} can't be {}", self.name(*combatant), condition),
+            CombatEvent::FeatureTriggered {
+                combatant,
+                feature,
+                opponent,
+            } => format!(
+                "⚡ {}'s {} fires on {}",
+                self.name(*combatant),
+                feature,
+                self.name(*opponent)
+            ),
+            CombatEvent::ClassFeatureUsed {
+                combatant,
+                feature,
+                target,
+            } => match target {
+                Some(target) if target != combatant => format!(
+                    "{} uses {} on {}",
+                    self.name(*combatant),
+                    feature,
+                    self.name(*target)
+                ),
+                _ => format!("{} uses {}", self.name(*combatant), feature),
+            },
+            CombatEvent::AbilityUsed {
+                combatant,
+                ability,
+                targets,
+            } => match targets.as_slice() {
+                [target] if target == combatant => {
+                    format!("{} uses {}", self.name(*combatant), ability)
+                }
+                _ => format!(
+                    "{} uses {} on {}",
+                    self.name(*combatant),
+                    ability,
+                    targets
+                        .iter()
+                        .map(|target| self.name(*target))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            },
+            CombatEvent::OngoingDamageStarted { combatant, name } => {
+                format!("🔥 {} is {}", self.name(*combatant), name)
+            }
+            CombatEvent::OngoingDamageTicked {
+                combatant,
+                name,
+                rolled,
+            } => format!(
+                "🔥 {} takes {} from {}",
+                self.name(*combatant),
+                rolled,
+                name
+            ),
+            CombatEvent::OngoingDamageEnded { combatant, name } => {
+                format!("{} is no longer {}", self.name(*combatant), name)
+            }
+            CombatEvent::CombatantMoved {
+                combatant,
+                from,
+                to,
+            } => {
+                format!(
+                    "{} moves from zone {} to {}",
+                    self.name(*combatant),
+                    from,
+                    to
+                )
+            }
+            CombatEvent::MountDamaged {
+                rider,
+                mount,
+                damage,
+            } => format!(
+                "{}'s {} takes {} damage",
+                self.name(*rider),
+                mount,
+                damage.after_armor
+            ),
+            CombatEvent::Dismounted { combatant, mount } => {
+                format!("{} is off {}", self.name(*combatant), mount)
+            }
+            CombatEvent::Mounted { combatant, mount } => {
+                format!("{} mounts {}", self.name(*combatant), mount)
+            }
+            CombatEvent::ReactionTaken {
+                combatant,
+                reaction,
+                attacker,
+            } => format!(
+                "↩ {} reacts to {} with {}",
+                self.name(*combatant),
+                self.name(*attacker),
+                reaction
+            ),
+            CombatEvent::CardPlayed { combatant, card } => {
+                format!("{} plays {}", self.name(*combatant), card)
+            }
+            CombatEvent::CountdownAdded { name, rounds } => {
+                format!("⏳ {} in {} rounds", name, rounds)
+            }
+            CombatEvent::CountdownExpired { name } => format!("⌛ {}!", name),
+            CombatEvent::ActionTokenSpent {
+                combatant,
+                remaining,
+            } => format!(
+                "🪙 {} takes the spotlight ({} tokens left)",
+                self.name(*combatant),
+                remaining
+            ),
+            CombatEvent::ActionTokensRefreshed { tokens } => {
+                format!("🪙 Action tokens refreshed to {}", tokens)
+            }
+            CombatEvent::GmSpotlight { actions } => {
+                format!("The GM takes the spotlight for {} actions", actions)
+            }
+            CombatEvent::HazardTriggered {
+                combatant,
+                hazard,
+                total,
+                saved,
+            } => format!(
+                "⚠ {} {} {} ({})",
+                hazard,
+                if *saved { "misses" } else { "catches" },
+                self.name(*combatant),
+                total
+            ),
+            CombatEvent::ContestResolved {
+                first,
+                second,
+                winner,
+                margin,
+            } => match winner {
+                Some(winner) => {
+                    let loser = if winner == first { second } else { first };
+                    format!(
+                        "{} wins the contest against {} by {}",
+                        self.name(*winner),
+                        self.name(*loser),
+                        margin
+                    )
+                }
+                None => format!(
+                    "{} and {} are locked in a tie",
+                    self.name(*first),
+                    self.name(*second)
+                ),
+            },
+            CombatEvent::ResourcesConverted {
+                combatant,
+                from,
+                to,
+                paid,
+                gained,
+            } => format!(
+                "{} trades {} {} for {} {}",
+                self.name(*combatant),
+                paid,
+                from,
+                gained,
+                to
+            ),
+        }
+    }
+}
+
+/// Run the tracker until the GM quits, then save any unsaved changes
+pub fn run(mut app: App) -> Result<(), Box<dyn std::error::Error>> {
+    let mut terminal = ratatui::init();
+    let result = (|| -> std::io::Result<()> {
+        while !app.quit {
+            terminal.draw(|frame| draw(frame, &app))?;
+            // Wake up for a pending autosave even if no key comes
+            let ready = match app.autosave.wait(Instant::now()) {
+                Some(wait) => event::poll(wait)?,
+                None => true,
+            };
+            if !ready {
+                app.autosave(false);
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    app.handle_key(key.code);
+                }
+            }
+        }
+        Ok(())
+    })();
+    ratatui::restore();
+
+    result?;
+    if app.autosave.on_quit() {
+        app.save()?;
+        println!("✅ Encounter saved to {}", app.file);
+    } else {
+        println!("✅ Nothing changed since {} was last saved", app.file);
+    }
+    Ok(())
+}
+
+fn draw(frame: &mut Frame, app: &App) {
+    let [header, body, log, footer] = Layout::vertical([
+        Constraint::Length(3),
+        Constraint::Min(8),
+        Constraint::Length(10),
+        Constraint::Length(1),
+    ])
+    .areas(frame.area());
+    let [order, details] =
+        Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(body);
+
+    draw_pools(frame, app, header);
+    if app.screen {
+        frame.render_widget(
+            Paragraph::new(render::gm_screen(&app.encounter).to_text())
+                .block(Block::default().borders(Borders::ALL).title("GM screen")),
+            body,
+        );
+    } else {
+        draw_order(frame, app, order);
+        draw_details(frame, app, details);
+    }
+    draw_log(frame, app, log);
+
+    let footer_text = match &app.mode {
+        Mode::Normal => format!("{}  │  {}", app.status, HELP),
+        Mode::Damage(amount) => format!(
+            "Damage to deal: {}▏ (Enter to apply, Esc to cancel)",
+            amount
+        ),
+    };
+    frame.render_widget(Paragraph::new(footer_text), footer);
+}
+
+fn draw_pools(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let encounter = &app.encounter;
+    let [round, hope, fear] = Layout::horizontal([
+        Constraint::Length(14),
+        Constraint::Fill(1),
+        Constraint::Fill(1),
+    ])
+    .areas(area);
+
+    let round_text = if encounter.round == 0 {
+        String::from("Not started")
+    } else {
+        format!("Round {}", encounter.round)
+    };
+    frame.render_widget(
+        Paragraph::new(round_text).block(Block::default().borders(Borders::ALL)),
+        round,
+    );
+    // Without a party pool, show whoever is selected
+    let (title, pool) = match encounter.hope_mode {
+        HopeMode::Shared => ("Party Hope", Some(&encounter.hope)),
+        HopeMode::PerCharacter => (
+            "Hope (selected)",
+            app.selected_id()
+                .and_then(|id| encounter.get(id))
+                .and_then(|c| c.hope()),
+        ),
+    };
+    let (current, maximum) = pool.map_or((0, 0), |hope| (hope.current, hope.maximum));
+    frame.render_widget(
+        Gauge::default()
+            .block(Block::default().borders(Borders::ALL).title(title))
+            .gauge_style(Style::default().fg(Color::Yellow))
+            .ratio(ratio(current, maximum))
+            .label(match pool {
+                Some(_) => format!("{}/{}", current, maximum),
+                None => String::from("—"),
+            }),
+        hope,
+    );
+    frame.render_widget(
+        Paragraph::new("●".repeat(encounter.fear.current as usize))
+            .style(Style::default().fg(Color::Magenta))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .title(format!("Fear {}", encounter.fear.current)),
+            ),
+        fear,
+    );
+}
+
+fn draw_order(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let current = app.encounter.current_combatant_id();
+    let items: Vec<ListItem> = app
+        .roster()
+        .iter()
+        .map(|combatant| {
+            let marker = if Some(combatant.id) == current {
+                "▶ "
+            } else {
+                "  "
+            };
+            let style = if !combatant.is_alive() {
+                Style::default().fg(Color::DarkGray)
+            } else if combatant.is_player() {
+                Style::default().fg(Color::Cyan)
+            } else {
+                Style::default().fg(Color::Red)
+            };
+            ListItem::new(Line::from(vec![
+                Span::raw(marker),
+                Span::styled(combatant.name.to_string(), style),
+                Span::raw(format!(
+                    "  HP {}/{}  Init {}",
+                    combatant.hp.current, combatant.hp.maximum, combatant.initiative
+                )),
+            ]))
+        })
+        .collect();
+
+    let mut state = ListState::default().with_selected(Some(app.selected));
+    frame.render_stateful_widget(
+        List::new(items)
+            .block(Block::default().borders(Borders::ALL).title("Turn order"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED)),
+        area,
+        &mut state,
+    );
+}
+
+fn draw_details(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let roster = app.roster();
+    let Some(combatant) = roster.get(app.selected) else {
+        frame.render_widget(
+            Paragraph::new("No combatants").block(Block::default().borders(Borders::ALL)),
+            area,
+        );
+        return;
+    };
+
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .title(combatant.name.to_string());
+    let inner = block.inner(area);
+    frame.render_widget(block, area);
+
+    let [hp, stress, stats] = Layout::vertical([
+        Constraint::Length(1),
+        Constraint::Length(1),
+        Constraint::Min(1),
+    ])
+    .spacing(1)
+    .areas(inner);
+
+    frame.render_widget(
+        Gauge::default()
+            .gauge_style(Style::default().fg(Color::Green))
+            .ratio(ratio(combatant.hp.current, combatant.hp.maximum))
+            .label(format!(
+                "HP {}/{}",
+                combatant.hp.current, combatant.hp.maximum
+            )),
+        hp,
+    );
+    let slots = STRESS_SLOTS.max(combatant.stress.current);
+    frame.render_widget(
+        Gauge::default()
+            .gauge_style(Style::default().fg(Color::LightRed))
+            .ratio(ratio(combatant.stress.current, slots))
+            .label(format!("Stress {}/{}", combatant.stress.current, slots)),
+        stress,
+    );
+
+    let conditions: Vec<String> = combatant.conditions.iter().map(|c| c.to_string()).collect();
+    let mut lines = vec![
+        Line::from(format!(
+            "Evasion {}   Armor {}   Level {}",
+            combatant.evasion, combatant.armor, combatant.level
+        )),
+        Line::from(format!(
+            "Conditions: {}",
+            if conditions.is_empty() {
+                String::from("none")
+            } else {
+                conditions.join(", ")
+            }
+        )),
+    ];
+    if let Some(player) = combatant.player_data() {
+        lines.insert(
+            0,
+            Line::from(format!("{} {}", player.ancestry, player.class)),
+        );
+    }
+    frame.render_widget(Paragraph::new(lines), stats);
+}
+
+fn draw_log(frame: &mut Frame, app: &App, area: ratatui::layout::Rect) {
+    let visible = area.height.saturating_sub(2) as usize;
+    let events = &app.encounter.events;
+    let start = events.len().saturating_sub(visible.min(LOG_LINES));
+    let lines: Vec<Line> = events[start..]
+        .iter()
+        .map(|event| Line::from(app.describe(event)))
+        .collect();
+
+    frame.render_widget(
+        Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Event log")),
+        area,
+    );
+}
+
+fn ratio(current: u8, maximum: u8) -> f64 {
+    if maximum == 0 {
+        0.0
+    } else {
+        (current as f64 / maximum as f64).clamp(0.0, 1.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use daggerheart_engine::combat::CombatantBuilder;
+
+    fn create_test_app() -> App {
+        let mut encounter = CombatEncounter::new(5);
+        for (name, hp) in [("Goblin", 4), ("Orc", 6)] {
+            encounter.add_combatant(
+                CombatantBuilder::adversary(name)
+                    .with_hp(hp)
+                    .with_evasion(10)
+                    .build()
+                    .unwrap(),
+            );
+        }
+        App::new(
+            encounter,
+            String::from("unused.json"),
+            Attack::new(2),
+            DamageDice::d6(1),
+        )
+    }
+
+    fn press(app: &mut App, keys: &str) {
+        for key in keys.chars() {
+            app.handle_key(KeyCode::Char(key));
+        }
+    }
+
+    #[test]
+    fn test_next_starts_then_advances() {
+        let mut app = create_test_app();
+        press(&mut app, "n");
+        assert_eq!(app.encounter.round, 1);
+
+        let first = app.encounter.current_combatant_id();
+        press(&mut app, "n");
+        assert_ne!(app.encounter.current_combatant_id(), first);
+
+        press(&mut app, "r");
+        assert_eq!(app.encounter.round, 2);
+    }
+
+    #[test]
+    fn test_autosave_after_each_change() {
+        let path = std::env::temp_dir().join("daggerheart_tui_autosave.json");
+        std::fs::remove_file(&path).ok();
+        let mut app = create_test_app().with_autosave(AutosavePolicy::EveryCommand);
+        app.file = path.to_string_lossy().into_owned();
+
+        press(&mut app, "j");
+        assert!(!path.exists());
+        press(&mut app, "n");
+        assert!(path.exists());
+        assert!(app.status.ends_with("autosaved"));
+        assert!(!app.autosave.on_quit());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_damage_prompt() {
+        let mut app = create_test_app();
+        app.handle_key(KeyCode::Down);
+        let target = app.selected_id().unwrap();
+
+        press(&mut app, "d12");
+        assert_eq!(app.mode, Mode::Damage(String::from("12")));
+        app.handle_key(KeyCode::Enter);
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.encounter.get(target).unwrap().hp.current < 6);
+    }
+
+    #[test]
+    fn test_pools_and_errors() {
+        let mut app = create_test_app();
+        press(&mut app, "H");
+        assert!(app.status.contains("Goblin has no Hope"));
+
+        app.encounter.hope_mode = HopeMode::Shared;
+        let hope = app.encounter.hope.current;
+        press(&mut app, "HfF");
+        assert_eq!(app.encounter.hope.current, hope - 1);
+        assert_eq!(app.encounter.fear.current, 0);
+
+        press(&mut app, "F");
+        assert!(app.status.contains("Not enough Fear"));
+    }
+
+    #[test]
+    fn test_attack_needs_started_combat() {
+        let mut app = create_test_app();
+        let attacks = |app: &App| {
+            app.encounter
+                .events
+                .iter()
+                .filter(|e| matches!(e, CombatEvent::AttackResolved { .. }))
+                .count()
+        };
+
+        press(&mut app, "a");
+        assert_eq!(attacks(&app), 0);
+
+        press(&mut app, "na");
+        assert_eq!(attacks(&app), 1);
+    }
+
+    #[test]
+    fn test_gm_screen_toggles() {
+        let mut app = create_test_app();
+        press(&mut app, "g");
+        assert!(app.screen);
+        press(&mut app, "g");
+        assert!(!app.screen);
+    }
+
+    #[test]
+    fn test_describe_uses_names() {
+        let app = create_test_app();
+        let event = CombatEvent::CombatantDefeated {
+            combatant: CombatantId(1),
+        };
+        assert_eq!(app.describe(&event), "💀 Orc is defeated");
+    }
+}
@@ -0,0 +1,257 @@
+//! `daggerheart char wizard` - interactive character creation
+//!
+//! Walks through each choice on a character sheet in the order of the
+//! rulebook, checking every answer before moving on, so the finished
+//! character is always valid.
+
+use daggerheart_engine::cards::DomainCard;
+use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class, Community};
+use daggerheart_engine::combat::CombatantBuilder;
+use daggerheart_engine::content::ContentRegistry;
+use std::fmt::Display;
+use std::io::{self, BufRead, Write};
+use strum::IntoEnumIterator;
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// Traits in the order `Attributes::from_array` takes them
+const TRAITS: [&str; 6] = [
+    "Agility",
+    "Strength",
+    "Finesse",
+    "Instinct",
+    "Presence",
+    "Knowledge",
+];
+
+/// Domain cards a new character starts with
+const STARTING_CARDS: usize = 2;
+
+/// Asks questions on `output` and reads answers from `input`
+pub struct Wizard<R, W> {
+    input: R,
+    output: W,
+}
+
+impl<R: BufRead, W: Write> Wizard<R, W> {
+    pub fn new(input: R, output: W) -> Self {
+        Self { input, output }
+    }
+
+    /// Ask every question and build the character
+    pub fn run(
+        &mut self,
+        name: Option<String>,
+        content: &ContentRegistry,
+    ) -> Result<CharacterSheet> {
+        let name = match name {
+            Some(name) => name,
+            None => loop {
+                let name = self.ask("Name: ")?;
+                if !name.is_empty() {
+                    break name;
+                }
+            },
+        };
+
+        let class = self.choose("Class", &Class::iter().collect::<Vec<_>>())?;
+        let subclass = self.choose("Subclass", &class.subclasses())?;
+        let ancestry = self.choose("Ancestry", &Ancestry::iter().collect::<Vec<_>>())?;
+        let community = self.choose("Community", &Community::iter().collect::<Vec<_>>())?;
+        let attributes = self.assign_attributes()?;
+        let cards = self.choose_cards(class, content)?;
+        let equipment = self.choose_equipment(class)?;
+
+        let mut builder = CombatantBuilder::player(name, class, ancestry)
+            .with_subclass(subclass)
+            .with_community(community)
+            .with_attributes(attributes);
+        for item in equipment {
+            builder = builder.with_equipment(item);
+        }
+
+        let mut sheet = CharacterSheet::new(builder.build()?);
+        for card in cards {
+            sheet.progress.add_card(&card.id);
+        }
+        Ok(sheet)
+    }
+
+    /// Read one trimmed line, failing if the input ends
+    pub(crate) fn ask(&mut self, prompt: &str) -> Result<String> {
+        write!(self.output, "{}", prompt)?;
+        self.output.flush()?;
+
+        let mut line = String::new();
+        if self.input.read_line(&mut line)? == 0 {
+            return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "wizard cancelled").into());
+        }
+        Ok(line.trim().to_string())
+    }
+
+    /// Print a line of the conversation
+    pub(crate) fn say(&mut self, line: &str) -> Result<()> {
+        writeln!(self.output, "{}", line)?;
+        Ok(())
+    }
+
+    /// Pick one option by number or name
+    pub(crate) fn choose<T: Copy + Display>(&mut self, title: &str, options: &[T]) -> Result<T> {
+        writeln!(self.output, "\n{}:", title)?;
+        for (i, option) in options.iter().enumerate() {
+            writeln!(self.output, "  {}. {}", i + 1, option)?;
+        }
+
+        loop {
+            let answer = self.ask("> ")?;
+            let picked = match answer.parse::<usize>() {
+                Ok(n) => n.checked_sub(1).and_then(|i| options.get(i)),
+                Err(_) => options
+                    .iter()
+                    .find(|option| option.to_string().eq_ignore_ascii_case(&answer)),
+            };
+            match picked {
+                Some(&option) => return Ok(option),
+                None => writeln!(self.output, "❌ Pick 1-{} or a name", options.len())?,
+            }
+        }
+    }
+
+    /// Assign the standard modifiers one trait at a time
+    ///
+    /// Each answer must come from the modifiers still unassigned, and the
+    /// last trait gets whatever is left.
+    fn assign_attributes(&mut self) -> Result<Attributes> {
+        let mut remaining = Attributes::STANDARD_MODIFIERS.to_vec();
+        let mut modifiers = [0i8; 6];
+
+        writeln!(
+            self.output,
+            "\nAssign +2, +1, +1, +0, +0, -1 to your traits:"
+        )?;
+        for (i, name) in TRAITS.iter().enumerate() {
+            if remaining.len() == 1 {
+                modifiers[i] = remaining[0];
+                writeln!(self.output, "  {}: {:+}", name, remaining[0])?;
+                break;
+            }
+
+            let left: Vec<String> = remaining.iter().map(|m| format!("{:+}", m)).collect();
+            loop {
+                let answer = self.ask(&format!("  {} [{}]: ", name, left.join(" ")))?;
+                let position = answer
+                    .parse::<i8>()
+                    .ok()
+                    .and_then(|m| remaining.iter().position(|&r| r == m));
+                match position {
+                    Some(position) => {
+                        modifiers[i] = remaining.remove(position);
+                        break;
+                    }
+                    None => writeln!(self.output, "  ❌ Choose one of: {}", left.join(" "))?,
+                }
+            }
+        }
+
+        Ok(Attributes::from_array(modifiers)?)
+    }
+
+    /// Pick the level 1 cards from the class's two domains
+    fn choose_cards<'a>(
+        &mut self,
+        class: Class,
+        content: &'a ContentRegistry,
+    ) -> Result<Vec<&'a DomainCard>> {
+        let (first, second) = class.domains();
+        let mut pool: Vec<&DomainCard> = content.find_cards(Some(first), Some(1));
+        pool.extend(content.find_cards(Some(second), Some(1)));
+
+        let mut chosen = Vec::new();
+        while chosen.len() < STARTING_CARDS && !pool.is_empty() {
+            let labels: Vec<CardLabel> = pool.iter().map(|card| CardLabel(card)).collect();
+            let title = format!("Domain card {} of {}", chosen.len() + 1, STARTING_CARDS);
+            let picked = self.choose(&title, &labels)?;
+            pool.retain(|card| card.id != picked.0.id);
+            chosen.push(picked.0);
+        }
+        Ok(chosen)
+    }
+
+    /// Take the class's suggested kit or a comma-separated list
+    fn choose_equipment(&mut self, class: Class) -> Result<Vec<String>> {
+        let suggested = class.suggested_equipment().join(", ");
+        let answer = self.ask(&format!("\nEquipment [{}]: ", suggested))?;
+        let items = if answer.is_empty() {
+            &suggested
+        } else {
+            &answer
+        };
+
+        Ok(items
+            .split(',')
+            .map(str::trim)
+            .filter(|item| !item.is_empty())
+            .map(String::from)
+            .collect())
+    }
+}
+
+/// Lists a card by name, so it can be picked by name too
+#[derive(Clone, Copy)]
+struct CardLabel<'a>(&'a DomainCard);
+
+impl Display for CardLabel<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0.name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use daggerheart_engine::character::Subclass;
+
+    fn run(answers: &str) -> (Result<CharacterSheet>, String) {
+        let mut output = Vec::new();
+        let result = Wizard::new(answers.as_bytes(), &mut output)
+            .run(Some(String::from("Grom")), &ContentRegistry::builtin());
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn test_full_walkthrough() {
+        let answers = "warrior\n2\norc\nridgeborne\n2\n1\n0\n1\n0\n1\n1\n\n";
+        let (sheet, _) = run(answers);
+        let sheet = sheet.unwrap();
+
+        let grom = &sheet.combatant;
+        assert_eq!(grom.class(), Some(Class::Warrior));
+        assert_eq!(grom.subclass(), Some(Subclass::CallOfTheSlayer));
+        assert_eq!(grom.community(), Some(Community::Ridgeborne));
+        assert_eq!(
+            grom.attributes(),
+            Some(&Attributes::from_array([2, 1, 0, 1, 0, -1]).unwrap())
+        );
+        assert_eq!(sheet.progress.available_cards.len(), STARTING_CARDS);
+        assert_eq!(grom.equipment, vec!["Longsword", "Chainmail Armor"]);
+    }
+
+    #[test]
+    fn test_rejects_used_modifiers() {
+        // A second +2 is refused, then +1 is accepted
+        let answers = "1\n1\n1\n1\n2\n2\n1\n1\n0\n0\n1\n1\nDagger, Rope\n";
+        let (sheet, output) = run(answers);
+
+        assert!(output.contains("❌ Choose one of: +1 +1 +0 +0 -1"));
+        let sheet = sheet.unwrap();
+        assert_eq!(sheet.combatant.attributes().unwrap().strength, 1);
+        assert_eq!(sheet.combatant.equipment, vec!["Dagger", "Rope"]);
+    }
+
+    #[test]
+    fn test_reprompts_bad_choice_and_stops_at_eof() {
+        let (result, output) = run("10\nbard\n");
+        assert!(output.contains("❌ Pick 1-9 or a name"));
+        assert!(result.is_err());
+    }
+}
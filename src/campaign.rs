@@ -0,0 +1,1450 @@
+//! Campaign state - everything that persists between encounters
+//!
+//! A `Campaign` is the single save file for a table: the party's character
+//! sheets, the shared Hope/Fear meta-state, the current scene, the
+//! encounter in progress,
+//! finished encounters, downtime, the roll log, GM notes, and a journal
+//! entry for each session played.
+
+use crate::character::{AttributeType, CharacterSheet};
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId, HopeMode, TurnModel};
+use crate::combat::{Fear, HitPoints, Hope};
+use crate::core::dice::{Die, DualityResult, DualityRoll};
+use crate::downtime::{
+    self, ActivityRecord, DowntimeRecord, ProjectClock, ProjectRoll, RestActivity, RestKind,
+    MOVES_PER_REST,
+};
+use crate::error::EngineError;
+use crate::items::RewardSuggestion;
+use crate::rules::RulesConfig;
+use crate::scene::{Scene, SceneKind};
+use crate::social::SocialOutcome;
+#[cfg(feature = "persistence")]
+use crate::storage::{self, AutosavePolicy, Persist};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "persistence")]
+use std::path::Path;
+
+/// Current version of the campaign save format
+pub const CAMPAIGN_FORMAT_VERSION: u32 = 1;
+
+/// A logged duality roll
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RollRecord {
+    /// Who rolled
+    pub roller: String,
+    /// What the roll was for
+    pub description: String,
+    pub result: DualityResult,
+}
+
+/// An encounter that is currently being played
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ActiveEncounter {
+    pub name: String,
+    pub encounter: CombatEncounter,
+    /// Encounter id of each party member, in `Campaign::party` order
+    pub party_ids: Vec<CombatantId>,
+}
+
+/// A finished encounter kept for the campaign history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EncounterRecord {
+    pub name: String,
+    pub encounter: CombatEncounter,
+}
+
+impl EncounterRecord {
+    /// Whether the party won (`None` if the encounter ended early)
+    pub fn player_victory(&self) -> Option<bool> {
+        self.encounter.player_victory()
+    }
+}
+
+/// A party member as they stood at the end of a session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SessionCharacter {
+    pub name: String,
+    pub level: u8,
+    pub hp: HitPoints,
+    pub stress: u8,
+    /// The character's own Hope (0 when the table shares a pool)
+    #[serde(default)]
+    pub hope: u8,
+    /// Total XP after this session's award
+    pub experience: u32,
+}
+
+/// The journal entry for one finished session
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SessionLog {
+    /// Session number, starting at 1
+    pub number: u32,
+    /// When the session was played, as given (e.g. `2025-03-14`)
+    pub date: String,
+    /// XP awarded to each party member
+    pub xp_awarded: u32,
+    /// Shared party Hope at the end of the session, for tables using one
+    pub hope: u8,
+    /// GM Fear carried into the next session
+    pub fear_carried: u8,
+    /// Critical rolls made during the session
+    pub notable_rolls: Vec<RollRecord>,
+    /// GM notes added during the session, then any closing notes
+    pub notes: Vec<String>,
+    pub party: Vec<SessionCharacter>,
+    /// Length of the roll log when the session ended
+    pub roll_log_end: usize,
+    /// Number of GM notes when the session ended
+    pub notes_end: usize,
+    /// Number of finished encounters when the session ended
+    #[serde(default)]
+    pub encounters_end: usize,
+}
+
+/// All persistent state for a campaign
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Campaign {
+    /// Save format version
+    pub version: u32,
+    pub name: String,
+    pub party: Vec<CharacterSheet>,
+    /// Shared party Hope carried between encounters, used when
+    /// `rules.hope_mode` is `Shared`
+    pub hope: Hope,
+    /// House rules, stored alongside the other top-level fields
+    #[serde(flatten)]
+    pub rules: RulesConfig,
+    /// How encounters order turns
+    #[serde(default)]
+    pub turn_model: TurnModel,
+    /// GM Fear carried between encounters and sessions, up to its cap
+    pub fear: Fear,
+    /// The scene being played, if any
+    #[serde(default)]
+    pub scene: Option<Scene>,
+    pub active: Option<ActiveEncounter>,
+    pub completed: Vec<EncounterRecord>,
+    /// Projects and rests of each party member
+    #[serde(default)]
+    pub downtime: Vec<DowntimeRecord>,
+    pub roll_log: Vec<RollRecord>,
+    pub notes: Vec<String>,
+    /// Journal of finished sessions, oldest first
+    #[serde(default)]
+    pub sessions: Vec<SessionLog>,
+    /// When interactive sessions on this campaign save on their own
+    #[cfg(feature = "persistence")]
+    #[serde(default)]
+    pub autosave: AutosavePolicy,
+}
+
+impl Campaign {
+    #[cfg(feature = "persistence")]
+    /// Save campaign to a JSON file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use daggerheart_engine::campaign::Campaign;
+    ///
+    /// let campaign = Campaign::new("The Witherwild", 5);
+    /// campaign.save_to_file("witherwild.json").unwrap();
+    /// ```
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), EngineError> {
+        storage::save_file(self, path)
+    }
+
+    #[cfg(feature = "persistence")]
+    /// Load campaign from a JSON file
+    ///
+    /// Files written by a newer version of the engine are rejected.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use daggerheart_engine::campaign::Campaign;
+    ///
+    /// let campaign = Campaign::load_from_file("witherwild.json").unwrap();
+    /// println!("Party size: {}", campaign.party.len());
+    /// ```
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, EngineError> {
+        storage::load_file(path)
+    }
+
+    /// Create an empty campaign
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::Campaign;
+    ///
+    /// let campaign = Campaign::new("The Witherwild", 5);
+    /// assert_eq!(campaign.hope.current, 5);
+    /// assert!(campaign.party.is_empty());
+    /// ```
+    pub fn new(name: impl Into<String>, hope_max: u8) -> Self {
+        Self {
+            version: CAMPAIGN_FORMAT_VERSION,
+            name: name.into(),
+            party: Vec::new(),
+            hope: Hope::new(hope_max),
+            rules: RulesConfig::default(),
+            turn_model: TurnModel::default(),
+            fear: Fear::new(),
+            scene: None,
+            active: None,
+            completed: Vec::new(),
+            downtime: Vec::new(),
+            roll_log: Vec::new(),
+            notes: Vec::new(),
+            sessions: Vec::new(),
+            #[cfg(feature = "persistence")]
+            autosave: AutosavePolicy::default(),
+        }
+    }
+
+    /// Choose whether characters keep their own Hope or share a pool
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::Campaign;
+    /// use daggerheart_engine::combat::HopeMode;
+    ///
+    /// let campaign = Campaign::new("The Witherwild", 5).with_hope_mode(HopeMode::Shared);
+    /// let mut campaign = campaign;
+    /// let encounter = campaign.begin_encounter("Ambush", vec![]).unwrap();
+    /// assert_eq!(encounter.hope_mode, HopeMode::Shared);
+    /// ```
+    pub fn with_hope_mode(mut self, mode: HopeMode) -> Self {
+        self.rules.hope_mode = mode;
+        self
+    }
+
+    /// Choose how encounters decide who acts first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::Campaign;
+    /// use daggerheart_engine::combat::TurnModel;
+    ///
+    /// let mut campaign = Campaign::new("The Witherwild", 5).with_turn_model(TurnModel::ByRole);
+    /// let encounter = campaign.begin_encounter("Ambush", vec![]).unwrap();
+    /// assert_eq!(encounter.turn_model, TurnModel::ByRole);
+    /// ```
+    pub fn with_turn_model(mut self, model: TurnModel) -> Self {
+        self.turn_model = model;
+        self
+    }
+
+    #[cfg(feature = "persistence")]
+    /// Choose when interactive sessions save on their own
+    pub fn with_autosave(mut self, policy: AutosavePolicy) -> Self {
+        self.autosave = policy;
+        self
+    }
+
+    /// Cap the GM's Fear at something other than 12
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::Campaign;
+    ///
+    /// let mut campaign = Campaign::new("The Witherwild", 5).with_fear_maximum(8);
+    /// campaign.fear.gain(10);
+    /// assert_eq!(campaign.fear.current, 8);
+    /// ```
+    pub fn with_fear_maximum(mut self, maximum: u8) -> Self {
+        self.rules.max_fear = maximum;
+        self.fear.set_maximum(maximum);
+        self
+    }
+
+    /// Play by a set of house rules
+    ///
+    /// The Fear cap and encumbrance take effect at once; the rest apply
+    /// from the next encounter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::Campaign;
+    /// use daggerheart_engine::rules::{CritRule, RulesConfig};
+    ///
+    /// let mut campaign = Campaign::new("The Witherwild", 5)
+    ///     .with_rules(RulesConfig::default().with_crit(CritRule::MaxDice).with_max_fear(6));
+    /// assert_eq!(campaign.fear.maximum, 6);
+    ///
+    /// let encounter = campaign.begin_encounter("Ambush", vec![]).unwrap();
+    /// assert_eq!(encounter.rules.crit, CritRule::MaxDice);
+    /// ```
+    pub fn with_rules(mut self, rules: RulesConfig) -> Self {
+        self.set_rules(rules);
+        self
+    }
+
+    /// Switch to a different set of house rules
+    pub fn set_rules(&mut self, rules: RulesConfig) {
+        self.fear.set_maximum(rules.max_fear);
+        let encumbrance = rules.encumbrance;
+        self.rules = rules;
+        self.set_encumbrance(encumbrance);
+    }
+
+    /// Add a character to the party (names must be unique)
+    pub fn add_character(&mut self, mut sheet: CharacterSheet) -> Result<(), EngineError> {
+        if self.character(sheet.name()).is_some() {
+            return Err(EngineError::DuplicateCharacter {
+                name: sheet.name().to_string(),
+            });
+        }
+        sheet.inventory.encumbrance = self.rules.encumbrance;
+        sheet.update_encumbrance();
+        self.party.push(sheet);
+        Ok(())
+    }
+
+    /// Turn encumbrance on or off for the whole party
+    ///
+    /// Characters joining later follow the same setting.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::Campaign;
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::{CombatantBuilder, Condition};
+    ///
+    /// let mut sheet = CharacterSheet::new(
+    ///     CombatantBuilder::player("Ash", Class::Ranger, Ancestry::Human)
+    ///         .with_attributes(Attributes::from_array([2, 0, 1, 1, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// sheet.carry("Boulder", 20, 1);
+    ///
+    /// let mut campaign = Campaign::new("The Witherwild", 5);
+    /// campaign.add_character(sheet).unwrap();
+    /// assert!(!campaign.party[0].combatant.has_condition(Condition::Encumbered));
+    ///
+    /// campaign.set_encumbrance(true);
+    /// assert!(campaign.party[0].combatant.has_condition(Condition::Encumbered));
+    /// ```
+    pub fn set_encumbrance(&mut self, enabled: bool) {
+        self.rules.encumbrance = enabled;
+        for sheet in &mut self.party {
+            sheet.inventory.encumbrance = enabled;
+            sheet.update_encumbrance();
+        }
+    }
+
+    /// Find a party member by name
+    pub fn character(&self, name: &str) -> Option<&CharacterSheet> {
+        self.party.iter().find(|s| s.name() == name)
+    }
+
+    /// Find a party member by name for mutation
+    pub fn character_mut(&mut self, name: &str) -> Option<&mut CharacterSheet> {
+        self.party.iter_mut().find(|s| s.name() == name)
+    }
+
+    /// Move the table into a new scene, returning the one it replaces
+    ///
+    /// Every PC in the scene must be in the party, and only a combat scene
+    /// can start while an encounter is in progress.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::Campaign;
+    /// use daggerheart_engine::scene::{Environment, Scene, SceneKind};
+    ///
+    /// let mut campaign = Campaign::new("The Witherwild", 5);
+    /// campaign
+    ///     .begin_scene(
+    ///         Scene::new("Into the Marsh", SceneKind::Exploration)
+    ///             .with_environment(Environment::new("Sunken Marsh")),
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let previous = campaign
+    ///     .begin_scene(Scene::new("Bargain with the Hag", SceneKind::Social))
+    ///     .unwrap();
+    /// assert_eq!(previous.unwrap().name, "Into the Marsh");
+    /// ```
+    pub fn begin_scene(&mut self, scene: Scene) -> Result<Option<Scene>, EngineError> {
+        if let Some(name) = scene
+            .active_pcs
+            .iter()
+            .find(|pc| self.character(pc).is_none())
+        {
+            return Err(EngineError::UnknownCharacter { name: name.clone() });
+        }
+        if let (Some(active), false) = (&self.active, scene.kind == SceneKind::Combat) {
+            return Err(EngineError::EncounterInProgress {
+                name: active.name.clone(),
+            });
+        }
+        Ok(self.scene.replace(scene))
+    }
+
+    /// Close the current scene, returning it
+    pub fn end_scene(&mut self) -> Option<Scene> {
+        self.scene.take()
+    }
+
+    /// Roll a party member's Presence against an NPC in the current scene
+    ///
+    /// The roll's Hope goes to the character (or the shared pool), its
+    /// Fear to the GM, and a critical clears a Stress. The roll is added
+    /// to the campaign log.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::Campaign;
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    /// use daggerheart_engine::scene::{Scene, SceneKind};
+    /// use daggerheart_engine::social::SocialNpc;
+    ///
+    /// let mut campaign = Campaign::new("The Witherwild", 5);
+    /// campaign
+    ///     .add_character(CharacterSheet::new(
+    ///         CombatantBuilder::player("Ash", Class::Bard, Ancestry::Human)
+    ///             .with_attributes(Attributes::from_array([0, 1, -1, 0, 2, 1]).unwrap())
+    ///             .build()
+    ///             .unwrap(),
+    ///     ))
+    ///     .unwrap();
+    /// campaign
+    ///     .begin_scene(
+    ///         Scene::new("Audience", SceneKind::Social).with_npc(SocialNpc::new("Baron", 13)),
+    ///     )
+    ///     .unwrap();
+    ///
+    /// let outcome = campaign.social_roll("Ash", "Baron").unwrap();
+    /// assert_eq!(outcome.result.modifier, 2);
+    /// assert_eq!(campaign.fear.current, outcome.fear_gained);
+    /// ```
+    pub fn social_roll(
+        &mut self,
+        character: &str,
+        npc: &str,
+    ) -> Result<SocialOutcome, EngineError> {
+        let mut rng = rand::thread_rng();
+        self.social_roll_with_rng(character, npc, &mut rng)
+    }
+
+    /// Roll against an NPC with a specific RNG (for testing)
+    pub fn social_roll_with_rng<R: Rng>(
+        &mut self,
+        character: &str,
+        npc: &str,
+        rng: &mut R,
+    ) -> Result<SocialOutcome, EngineError> {
+        let sheet = self
+            .party
+            .iter_mut()
+            .find(|s| s.name() == character)
+            .ok_or_else(|| EngineError::UnknownCharacter {
+                name: character.to_string(),
+            })?;
+        let presence = downtime::trait_modifier(&sheet.combatant, AttributeType::Presence)?;
+        let target = self
+            .scene
+            .as_mut()
+            .and_then(|scene| scene.npc_mut(npc))
+            .ok_or_else(|| EngineError::UnknownNpc {
+                name: npc.to_string(),
+            })?;
+        let outcome = target.persuade_with_rng(presence, rng);
+
+        match self.rules.hope_mode {
+            HopeMode::Shared => self.hope.gain(outcome.hope_gained),
+            HopeMode::PerCharacter => {
+                if let Ok(hope) = sheet.combatant.hope_mut() {
+                    hope.gain(outcome.hope_gained);
+                }
+            }
+        }
+        let stress = &mut sheet.combatant.stress.current;
+        *stress = stress.saturating_sub(outcome.stress_cleared);
+        self.fear.gain(outcome.fear_gained);
+
+        let description = format!("Sway {} (Presence)", outcome.npc);
+        self.log_roll(character, description, outcome.result.clone());
+        Ok(outcome)
+    }
+
+    /// Start a new encounter with the whole party against `enemies`
+    ///
+    /// The encounter inherits the campaign's current Hope and Fear, and
+    /// the table moves into a combat scene of the same name. That scene
+    /// keeps the environment and PCs of the scene it replaces, or has the
+    /// whole party if there wasn't one. The environment's hazards come
+    /// into the encounter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::Campaign;
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    ///
+    /// let mut campaign = Campaign::new("The Witherwild", 5);
+    /// campaign
+    ///     .add_character(CharacterSheet::new(
+    ///         CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///             .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///             .build()
+    ///             .unwrap(),
+    ///     ))
+    ///     .unwrap();
+    ///
+    /// let encounter = campaign
+    ///     .begin_encounter(
+    ///         "Ambush",
+    ///         vec![CombatantBuilder::adversary("Goblin")
+    ///             .with_hp(4)
+    ///             .with_evasion(10)
+    ///             .build()
+    ///             .unwrap()],
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(encounter.combatants.len(), 2);
+    /// ```
+    pub fn begin_encounter(
+        &mut self,
+        name: impl Into<String>,
+        enemies: Vec<Combatant>,
+    ) -> Result<&mut CombatEncounter, EngineError> {
+        if let Some(active) = &self.active {
+            return Err(EngineError::EncounterInProgress {
+                name: active.name.clone(),
+            });
+        }
+
+        let mut encounter = CombatEncounter::new(self.hope.maximum);
+        encounter.hope = self.hope;
+        encounter.hope_mode = self.rules.hope_mode;
+        encounter.rules = self.rules.clone();
+        encounter.turn_model = self.turn_model;
+        encounter.fear = self.fear.clone();
+
+        let party_ids = self
+            .party
+            .iter()
+            .map(|sheet| encounter.add_combatant(sheet.enter_encounter()))
+            .collect();
+        for enemy in enemies {
+            encounter.add_combatant(enemy);
+        }
+
+        let name = name.into();
+        let mut scene = Scene::new(name.clone(), SceneKind::Combat);
+        match self.scene.take() {
+            Some(previous) => {
+                scene.environment = previous.environment;
+                scene.active_pcs = previous.active_pcs;
+            }
+            None => scene.active_pcs = self.party.iter().map(|s| s.name().to_string()).collect(),
+        }
+        if let Some(environment) = &scene.environment {
+            for hazard in &environment.hazards {
+                encounter.add_hazard(hazard.clone());
+            }
+        }
+        self.scene = Some(scene);
+
+        let active = self.active.insert(ActiveEncounter {
+            name,
+            encounter,
+            party_ids,
+        });
+        Ok(&mut active.encounter)
+    }
+
+    /// The encounter in progress, if any
+    pub fn encounter(&self) -> Option<&CombatEncounter> {
+        self.active.as_ref().map(|a| &a.encounter)
+    }
+
+    /// The encounter in progress, for mutation
+    pub fn encounter_mut(&mut self) -> Option<&mut CombatEncounter> {
+        self.active.as_mut().map(|a| &mut a.encounter)
+    }
+
+    /// End the current encounter and fold its results into the campaign
+    ///
+    /// Party HP, Stress, Hope, lasting conditions and permanent modifiers
+    /// are copied back onto the character sheets (see
+    /// [`CharacterSheet::leave_encounter`]), and the shared Hope and Fear
+    /// carry over. Fear is held to the campaign's cap even if the
+    /// encounter's pool was changed. The combat scene ends with it.
+    pub fn finish_encounter(&mut self) -> Result<&EncounterRecord, EngineError> {
+        let active = self.active.take().ok_or(EngineError::NoActiveEncounter)?;
+        if self.scene.as_ref().map(|s| s.kind) == Some(SceneKind::Combat) {
+            self.scene = None;
+        }
+
+        self.hope = active.encounter.hope;
+        self.fear.current = active.encounter.fear.current.min(self.fear.maximum);
+
+        for (sheet, &id) in self.party.iter_mut().zip(&active.party_ids) {
+            if let Some(combatant) = active.encounter.get(id) {
+                sheet.leave_encounter(combatant);
+            }
+        }
+
+        self.completed.push(EncounterRecord {
+            name: active.name,
+            encounter: active.encounter,
+        });
+        Ok(self.completed.last().expect("record was just pushed"))
+    }
+
+    /// Add a roll to the campaign log
+    pub fn log_roll(
+        &mut self,
+        roller: impl Into<String>,
+        description: impl Into<String>,
+        result: DualityResult,
+    ) {
+        self.roll_log.push(RollRecord {
+            roller: roller.into(),
+            description: description.into(),
+            result,
+        });
+    }
+
+    /// Add a GM note
+    pub fn add_note(&mut self, note: impl Into<String>) {
+        self.notes.push(note.into());
+    }
+
+    /// Rolls logged since the last session ended
+    pub fn session_rolls(&self) -> &[RollRecord] {
+        let start = self.sessions.last().map_or(0, |s| s.roll_log_end);
+        &self.roll_log[start.min(self.roll_log.len())..]
+    }
+
+    /// End the current session and add its journal entry
+    ///
+    /// Every party member is awarded `xp_awarded`. The entry keeps the
+    /// session's critical rolls and the GM notes added since the last
+    /// session, followed by `closing_notes`, along with a snapshot of the
+    /// party and the Hope and Fear carried over.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::Campaign;
+    /// use daggerheart_engine::core::dice::DualityRoll;
+    ///
+    /// let mut campaign = Campaign::new("The Witherwild", 5);
+    /// campaign.log_roll("Grom", "Leap the chasm", DualityRoll::from_values(7, 7).with_modifier(2));
+    /// campaign.log_roll("Grom", "Search", DualityRoll::from_values(3, 9).with_modifier(0));
+    /// campaign.add_note("The bridge is out");
+    /// campaign.fear.gain(3);
+    ///
+    /// let session = campaign.end_session("2025-03-14", 2, vec![String::from("Grom found the key")]);
+    /// assert_eq!(session.number, 1);
+    /// assert_eq!(session.fear_carried, 3);
+    /// assert_eq!(session.notable_rolls.len(), 1);
+    /// assert_eq!(session.notes, vec!["The bridge is out", "Grom found the key"]);
+    /// assert!(campaign.session_rolls().is_empty());
+    /// ```
+    pub fn end_session(
+        &mut self,
+        date: impl Into<String>,
+        xp_awarded: u32,
+        closing_notes: Vec<String>,
+    ) -> &SessionLog {
+        let notes_start = self.sessions.last().map_or(0, |s| s.notes_end);
+        let mut notes: Vec<String> = self.notes.get(notes_start..).unwrap_or_default().to_vec();
+        notes.extend(closing_notes);
+
+        let notable_rolls = self
+            .session_rolls()
+            .iter()
+            .filter(|record| record.result.is_critical)
+            .cloned()
+            .collect();
+
+        let party = self
+            .party
+            .iter_mut()
+            .map(|sheet| {
+                sheet.progress.add_experience(xp_awarded);
+                SessionCharacter {
+                    name: sheet.name().to_string(),
+                    level: sheet.combatant.level,
+                    hp: sheet.combatant.hp.clone(),
+                    stress: sheet.combatant.stress.current,
+                    hope: match self.rules.hope_mode {
+                        HopeMode::PerCharacter => sheet.combatant.hope().map_or(0, |h| h.current),
+                        HopeMode::Shared => 0,
+                    },
+                    experience: sheet.progress.experience,
+                }
+            })
+            .collect();
+
+        self.sessions.push(SessionLog {
+            number: self.sessions.len() as u32 + 1,
+            date: date.into(),
+            xp_awarded,
+            hope: self.hope.current,
+            fear_carried: self.fear.current,
+            notable_rolls,
+            notes,
+            party,
+            roll_log_end: self.roll_log.len(),
+            notes_end: self.notes.len(),
+            encounters_end: self.completed.len(),
+        });
+        self.sessions.last().expect("session was just pushed")
+    }
+
+    /// Hand out rewards to the party
+    ///
+    /// Gold is split evenly, with leftover handfuls going to the first
+    /// party members, and items are dealt out in turn as placeholders
+    /// for the GM to roll and rename. Fails with no one to reward.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::Campaign;
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    /// use daggerheart_engine::items::RewardSuggestion;
+    ///
+    /// let mut campaign = Campaign::new("The Witherwild", 5);
+    /// for name in ["Grom", "Lia"] {
+    ///     campaign
+    ///         .add_character(CharacterSheet::new(
+    ///             CombatantBuilder::player(name, Class::Warrior, Ancestry::Orc)
+    ///                 .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///                 .build()
+    ///                 .unwrap(),
+    ///         ))
+    ///         .unwrap();
+    /// }
+    ///
+    /// campaign.award_rewards(&RewardSuggestion::for_play(1, 1, 3)).unwrap();
+    /// assert_eq!(campaign.party[0].inventory.gold, 3);
+    /// assert_eq!(campaign.party[1].inventory.gold, 2);
+    /// assert_eq!(campaign.party[1].inventory.quantity("Unrolled loot (1d12)"), 1);
+    /// ```
+    pub fn award_rewards(&mut self, rewards: &RewardSuggestion) -> Result<(), EngineError> {
+        let members = self.party.len() as u32;
+        if members == 0 {
+            return Err(EngineError::InvalidCharacterState(String::from(
+                "There is no party to reward",
+            )));
+        }
+        let (share, leftover) = (rewards.gold / members, rewards.gold % members);
+        for (index, sheet) in self.party.iter_mut().enumerate() {
+            let extra = u32::from((index as u32) < leftover);
+            sheet.inventory.gold = sheet.inventory.gold.saturating_add(share + extra);
+        }
+        for (index, item) in rewards.items.iter().enumerate() {
+            let sheet = &mut self.party[index % members as usize];
+            sheet.carry(item.to_string(), 0, 1);
+        }
+        Ok(())
+    }
+
+    /// The GM gains Fear as the party rests
+    ///
+    /// A short rest gives 1d4 Fear; a long rest gives 1d4 plus one per
+    /// party member. Returns the Fear actually gained, after the cap.
+    pub fn gain_rest_fear(&mut self, rest: RestKind) -> u8 {
+        let mut rng = rand::thread_rng();
+        self.gain_rest_fear_with_rng(rest, &mut rng)
+    }
+
+    /// Gain Fear for a rest with a specific RNG (for testing)
+    pub fn gain_rest_fear_with_rng<R: Rng>(&mut self, rest: RestKind, rng: &mut R) -> u8 {
+        let mut amount = Die::D4.roll_with_rng(rng);
+        if rest == RestKind::Long {
+            amount = amount.saturating_add(self.party.len().min(u8::MAX as usize) as u8);
+        }
+        let before = self.fear.current;
+        self.fear.gain(amount);
+        self.fear.current - before
+    }
+
+    /// A party member's downtime record, if they've had any downtime
+    pub fn downtime(&self, character: &str) -> Option<&DowntimeRecord> {
+        self.downtime.iter().find(|r| r.character == character)
+    }
+
+    /// A party member's downtime record, created on first use
+    fn downtime_mut(&mut self, character: &str) -> Result<&mut DowntimeRecord, EngineError> {
+        if self.character(character).is_none() {
+            return Err(EngineError::UnknownCharacter {
+                name: character.to_string(),
+            });
+        }
+        let index = match self.downtime.iter().position(|r| r.character == character) {
+            Some(index) => index,
+            None => {
+                self.downtime.push(DowntimeRecord::new(character));
+                self.downtime.len() - 1
+            }
+        };
+        Ok(&mut self.downtime[index])
+    }
+
+    /// Start a long-term project for a party member
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::Campaign;
+    /// use daggerheart_engine::character::{
+    ///     Ancestry, AttributeType, Attributes, CharacterSheet, Class,
+    /// };
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    ///
+    /// let mut campaign = Campaign::new("The Witherwild", 5);
+    /// campaign
+    ///     .add_character(CharacterSheet::new(
+    ///         CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///             .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///             .build()
+    ///             .unwrap(),
+    ///     ))
+    ///     .unwrap();
+    ///
+    /// campaign.start_project("Grom", "Reforge the blade", 6).unwrap();
+    /// let roll = campaign
+    ///     .advance_project("Grom", "reforge the blade", AttributeType::Strength, 12)
+    ///     .unwrap();
+    ///
+    /// let project = campaign.downtime("Grom").unwrap().project("Reforge the blade").unwrap();
+    /// assert_eq!(project.progress, roll.ticks);
+    /// assert_eq!(campaign.roll_log.len(), 1);
+    /// ```
+    pub fn start_project(
+        &mut self,
+        character: &str,
+        name: impl Into<String>,
+        segments: u8,
+    ) -> Result<&ProjectClock, EngineError> {
+        let name = name.into();
+        let record = self.downtime_mut(character)?;
+        if record.project(&name).is_some() {
+            return Err(EngineError::DuplicateProject {
+                character: character.to_string(),
+                project: name,
+            });
+        }
+        record.projects.push(ProjectClock::new(name, segments));
+        Ok(record.projects.last().expect("project was just pushed"))
+    }
+
+    /// Roll a party member's trait to make progress on a project
+    ///
+    /// The roll is added to the campaign log.
+    pub fn advance_project(
+        &mut self,
+        character: &str,
+        project: &str,
+        attribute: AttributeType,
+        difficulty: u16,
+    ) -> Result<ProjectRoll, EngineError> {
+        let mut rng = rand::thread_rng();
+        self.advance_project_with_rng(character, project, attribute, difficulty, &mut rng)
+    }
+
+    /// Advance a project with a specific RNG (for testing)
+    pub fn advance_project_with_rng<R: Rng>(
+        &mut self,
+        character: &str,
+        project: &str,
+        attribute: AttributeType,
+        difficulty: u16,
+        rng: &mut R,
+    ) -> Result<ProjectRoll, EngineError> {
+        let modifier = match self.character(character) {
+            Some(sheet) => downtime::trait_modifier(&sheet.combatant, attribute)?,
+            None => {
+                return Err(EngineError::UnknownCharacter {
+                    name: character.to_string(),
+                })
+            }
+        };
+
+        let clock = self
+            .downtime_mut(character)?
+            .project_mut(project)
+            .ok_or_else(|| EngineError::UnknownProject {
+                character: character.to_string(),
+                project: project.to_string(),
+            })?;
+        let roll = clock.resolve(
+            DualityRoll::roll_with_rng(rng).with_modifier(modifier),
+            difficulty,
+        );
+        let description = format!("Work on {} ({})", clock.name, attribute);
+
+        self.log_roll(character, description, roll.result.clone());
+        Ok(roll)
+    }
+
+    /// Make a party member's downtime moves for a rest
+    ///
+    /// Up to [`MOVES_PER_REST`] moves can be made, and the same move can
+    /// be chosen twice. `Prepare` adds to the character's Hope, or to the
+    /// party's when the table shares a pool. The character's magic items
+    /// recharge if the rest is long enough for them, and their innate
+    /// abilities can be used again.
+    pub fn rest(
+        &mut self,
+        character: &str,
+        rest: RestKind,
+        activities: &[RestActivity],
+    ) -> Result<Vec<ActivityRecord>, EngineError> {
+        let mut rng = rand::thread_rng();
+        self.rest_with_rng(character, rest, activities, &mut rng)
+    }
+
+    /// Make downtime moves with a specific RNG (for testing)
+    pub fn rest_with_rng<R: Rng>(
+        &mut self,
+        character: &str,
+        rest: RestKind,
+        activities: &[RestActivity],
+        rng: &mut R,
+    ) -> Result<Vec<ActivityRecord>, EngineError> {
+        if activities.len() > MOVES_PER_REST {
+            return Err(EngineError::InvalidCharacterState(format!(
+                "A rest allows {} downtime moves, got {}",
+                MOVES_PER_REST,
+                activities.len()
+            )));
+        }
+
+        let sheet = self
+            .party
+            .iter_mut()
+            .find(|s| s.name() == character)
+            .ok_or_else(|| EngineError::UnknownCharacter {
+                name: character.to_string(),
+            })?;
+        let records: Vec<ActivityRecord> = activities
+            .iter()
+            .map(|&activity| {
+                let shared_hope = match self.rules.hope_mode {
+                    HopeMode::Shared => Some(&mut self.hope),
+                    HopeMode::PerCharacter => None,
+                };
+                ActivityRecord {
+                    rest,
+                    activity,
+                    amount: activity.apply_with_rng(rest, &mut sheet.combatant, shared_hope, rng),
+                }
+            })
+            .collect();
+        sheet.recharge_items(rest);
+        sheet.combatant.refresh_abilities();
+
+        self.downtime_mut(character)?
+            .activities
+            .extend(records.iter().cloned());
+        Ok(records)
+    }
+}
+
+#[cfg(feature = "persistence")]
+impl Persist for Campaign {
+    fn after_load(&mut self) -> Result<(), std::io::Error> {
+        if self.version > CAMPAIGN_FORMAT_VERSION {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!(
+                    "Campaign format version {} is newer than supported version {}",
+                    self.version, CAMPAIGN_FORMAT_VERSION
+                ),
+            ));
+        }
+        if let Some(active) = self.active.as_mut() {
+            active.encounter.migrate_legacy_ids();
+        }
+        // Saves from before house rules kept the cap only on the pool
+        self.rules.max_fear = self.fear.maximum;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Class};
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::combat::{Condition, CHARACTER_HOPE_MAXIMUM};
+    use crate::items::{MagicItem, Recharge};
+    use crate::test_support::player;
+    use rand::SeedableRng;
+
+    fn create_test_sheet(name: &str) -> CharacterSheet {
+        CharacterSheet::new(
+            player(name, Class::Warrior, Ancestry::Human)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    fn create_test_campaign() -> Campaign {
+        let mut campaign = Campaign::new("Test", 5);
+        campaign.add_character(create_test_sheet("Alpha")).unwrap();
+        campaign.add_character(create_test_sheet("Beta")).unwrap();
+        campaign
+    }
+
+    #[test]
+    fn test_award_rewards_splits_among_the_party() {
+        let mut campaign = create_test_campaign();
+        campaign.add_character(create_test_sheet("Gamma")).unwrap();
+        let rewards = RewardSuggestion::for_play(2, 2, 4);
+        campaign.award_rewards(&rewards).unwrap();
+
+        let gold: Vec<u32> = campaign.party.iter().map(|s| s.inventory.gold).collect();
+        assert_eq!(gold, vec![11, 11, 10]);
+        let carried: Vec<usize> = campaign
+            .party
+            .iter()
+            .map(|s| s.inventory.items.len())
+            .collect();
+        assert_eq!(carried, vec![2, 1, 1]);
+        assert_eq!(
+            campaign.party[0]
+                .inventory
+                .quantity("Unrolled consumable (2d12)"),
+            1
+        );
+
+        assert!(Campaign::new("Empty", 5).award_rewards(&rewards).is_err());
+    }
+
+    #[test]
+    fn test_innate_ability_uses_last_until_a_rest() {
+        let mut campaign = Campaign::new("Test", 5);
+        campaign
+            .add_character(CharacterSheet::new(
+                player("Cog", Class::Guardian, Ancestry::Clank)
+                    .build()
+                    .unwrap(),
+            ))
+            .unwrap();
+        let encounter = campaign.begin_encounter("Ambush", vec![]).unwrap();
+        let cog = encounter.combatants[0].id;
+        encounter
+            .use_ability(cog, "Repair Protocol", &[], &crate::combat::Attack::new(0))
+            .unwrap();
+        campaign.finish_encounter().unwrap();
+        assert_eq!(campaign.party[0].combatant.actions()[0].uses_left, Some(0));
+
+        campaign.rest("Cog", RestKind::Short, &[]).unwrap();
+        assert_eq!(campaign.party[0].combatant.actions()[0].uses_left, Some(1));
+    }
+
+    #[test]
+    fn test_duplicate_character_rejected() {
+        let mut campaign = create_test_campaign();
+        assert!(campaign.add_character(create_test_sheet("Alpha")).is_err());
+        assert_eq!(campaign.party.len(), 2);
+    }
+
+    #[test]
+    fn test_begin_encounter_inherits_pools() {
+        let mut campaign = create_test_campaign();
+        campaign.hope.spend(2).unwrap();
+        campaign.fear.gain(3);
+
+        let encounter = campaign
+            .begin_encounter(
+                "Ambush",
+                vec![CombatantBuilder::adversary("Goblin")
+                    .with_hp(4)
+                    .with_evasion(10)
+                    .build()
+                    .unwrap()],
+            )
+            .unwrap();
+
+        assert_eq!(encounter.combatants.len(), 3);
+        assert_eq!(encounter.hope.current, 3);
+        assert_eq!(encounter.fear.current, 3);
+    }
+
+    #[test]
+    fn test_only_one_active_encounter() {
+        let mut campaign = create_test_campaign();
+        campaign.begin_encounter("First", vec![]).unwrap();
+        assert!(campaign.begin_encounter("Second", vec![]).is_err());
+    }
+
+    #[test]
+    fn test_finish_encounter_syncs_state() {
+        let mut campaign = create_test_campaign();
+        campaign
+            .begin_encounter(
+                "Ambush",
+                vec![CombatantBuilder::adversary("Goblin")
+                    .with_hp(4)
+                    .with_evasion(10)
+                    .build()
+                    .unwrap()],
+            )
+            .unwrap();
+
+        let party_id = campaign.active.as_ref().unwrap().party_ids[1];
+        let encounter = campaign.encounter_mut().unwrap();
+        encounter.apply_damage(party_id, 2).unwrap();
+        encounter.gain_fear(2);
+        encounter
+            .apply_condition(party_id, Condition::Vulnerable)
+            .unwrap();
+        encounter
+            .apply_condition(party_id, Condition::Hidden)
+            .unwrap();
+
+        let record = campaign.finish_encounter().unwrap();
+        assert_eq!(record.name, "Ambush");
+
+        assert!(campaign.active.is_none());
+        assert_eq!(campaign.completed.len(), 1);
+        assert_eq!(campaign.fear.current, 2);
+        assert_eq!(
+            campaign.character("Beta").unwrap().combatant.stress.current,
+            1
+        );
+        assert_eq!(
+            campaign.character("Beta").unwrap().combatant.conditions,
+            [Condition::Vulnerable]
+        );
+        assert_eq!(
+            campaign
+                .character("Alpha")
+                .unwrap()
+                .combatant
+                .stress
+                .current,
+            0
+        );
+    }
+
+    #[test]
+    fn test_fear_carries_over_within_cap() {
+        let mut campaign = create_test_campaign().with_fear_maximum(6);
+        campaign.fear.gain(4);
+
+        let encounter = campaign.begin_encounter("Ambush", vec![]).unwrap();
+        assert_eq!(encounter.fear.maximum, 6);
+        encounter.fear.maximum = 20;
+        encounter.gain_fear(10);
+        campaign.finish_encounter().unwrap();
+        assert_eq!(campaign.fear.current, 6);
+        assert_eq!(campaign.fear.maximum, 6);
+    }
+
+    #[test]
+    fn test_rest_fear() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        for _ in 0..20 {
+            let mut campaign = create_test_campaign();
+            let short = campaign.gain_rest_fear_with_rng(RestKind::Short, &mut rng);
+            assert!((1..=4).contains(&short));
+
+            let mut campaign = create_test_campaign();
+            let long = campaign.gain_rest_fear_with_rng(RestKind::Long, &mut rng);
+            // 1d4 plus two party members
+            assert!((3..=6).contains(&long));
+            assert_eq!(campaign.fear.current, long);
+        }
+
+        let mut campaign = create_test_campaign();
+        campaign.fear.gain(11);
+        assert_eq!(
+            campaign.gain_rest_fear_with_rng(RestKind::Long, &mut rng),
+            1
+        );
+    }
+
+    #[test]
+    fn test_finish_without_encounter() {
+        let mut campaign = create_test_campaign();
+        assert!(campaign.finish_encounter().is_err());
+    }
+
+    #[test]
+    fn test_scenes_follow_encounters() {
+        use crate::combat::{Hazard, HazardTrigger};
+        use crate::scene::Environment;
+
+        let mut campaign = create_test_campaign();
+        assert!(campaign
+            .begin_scene(Scene::new("Scouting", SceneKind::Exploration).with_pc("Gamma"))
+            .is_err());
+        let rubble = Hazard::new(
+            "Falling Rubble",
+            HazardTrigger::EachRound,
+            AttributeType::Agility,
+            12,
+        );
+        campaign
+            .begin_scene(
+                Scene::new("Scouting", SceneKind::Exploration)
+                    .with_environment(Environment::new("Ruined Keep").with_hazard(rubble))
+                    .with_pc("Alpha"),
+            )
+            .unwrap();
+
+        campaign.begin_encounter("Ambush", vec![]).unwrap();
+        let scene = campaign.scene.clone().unwrap();
+        assert_eq!(scene.kind, SceneKind::Combat);
+        assert_eq!(scene.environment.unwrap().name, "Ruined Keep");
+        let active = campaign.active.as_ref().unwrap();
+        assert_eq!(active.encounter.hazards[0].name, "Falling Rubble");
+        assert_eq!(scene.active_pcs, vec!["Alpha"]);
+        assert!(campaign
+            .begin_scene(Scene::new("Parley", SceneKind::Social))
+            .is_err());
+
+        campaign.finish_encounter().unwrap();
+        assert!(campaign.scene.is_none());
+
+        // Without a scene, the whole party joins the fight
+        campaign.begin_encounter("Rematch", vec![]).unwrap();
+        assert_eq!(campaign.scene.as_ref().unwrap().active_pcs.len(), 2);
+    }
+
+    #[test]
+    fn test_social_rolls_feed_hope_and_fear() {
+        use crate::social::SocialNpc;
+
+        let mut campaign = create_test_campaign();
+        assert!(matches!(
+            campaign.social_roll("Alpha", "Baron"),
+            Err(EngineError::UnknownNpc { .. })
+        ));
+        campaign
+            .begin_scene(
+                Scene::new("Audience", SceneKind::Social).with_npc(SocialNpc::new("Baron", 12)),
+            )
+            .unwrap();
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(11);
+        let mut hope = 0;
+        let mut fear = 0;
+        for _ in 0..6 {
+            let outcome = campaign
+                .social_roll_with_rng("Alpha", "baron", &mut rng)
+                .unwrap();
+            hope += outcome.hope_gained;
+            fear += outcome.fear_gained;
+        }
+
+        let starting = Hope::starting().current;
+        let alpha = campaign.character("Alpha").unwrap();
+        assert_eq!(
+            alpha.combatant.hope().unwrap().current,
+            (starting + hope).min(CHARACTER_HOPE_MAXIMUM)
+        );
+        assert_eq!(campaign.fear.current, fear);
+        assert_eq!(campaign.roll_log.len(), 6);
+        assert_eq!(campaign.roll_log[0].description, "Sway Baron (Presence)");
+    }
+
+    #[test]
+    fn test_roll_log_and_notes() {
+        let mut campaign = create_test_campaign();
+        let result = DualityRoll::from_values(9, 4).with_modifier(2);
+
+        campaign.log_roll("Alpha", "Climb the wall", result.clone());
+        campaign.add_note("The bridge is out");
+
+        assert_eq!(campaign.roll_log[0].result, result);
+        assert_eq!(campaign.notes, vec!["The bridge is out".to_string()]);
+    }
+
+    #[test]
+    fn test_projects_are_per_character() {
+        let mut campaign = create_test_campaign();
+        campaign.start_project("Alpha", "Map the marsh", 4).unwrap();
+        campaign.start_project("Beta", "Map the marsh", 4).unwrap();
+
+        assert!(matches!(
+            campaign.start_project("Alpha", "map the marsh", 2),
+            Err(EngineError::DuplicateProject { .. })
+        ));
+        assert!(matches!(
+            campaign.start_project("Gamma", "Anything", 2),
+            Err(EngineError::UnknownCharacter { .. })
+        ));
+
+        let mut rng = rand::rngs::StdRng::seed_from_u64(5);
+        for _ in 0..10 {
+            campaign
+                .advance_project_with_rng(
+                    "Alpha",
+                    "Map the marsh",
+                    AttributeType::Agility,
+                    10,
+                    &mut rng,
+                )
+                .unwrap();
+        }
+        assert!(campaign
+            .downtime("Alpha")
+            .unwrap()
+            .project("Map the marsh")
+            .unwrap()
+            .is_complete());
+        assert_eq!(campaign.downtime("Beta").unwrap().projects[0].progress, 0);
+        assert_eq!(campaign.roll_log.len(), 10);
+        assert_eq!(
+            campaign.roll_log[0].description,
+            "Work on Map the marsh (Agility)"
+        );
+
+        assert!(matches!(
+            campaign.advance_project("Alpha", "Sail", AttributeType::Agility, 10),
+            Err(EngineError::UnknownProject { .. })
+        ));
+    }
+
+    #[test]
+    fn test_rest_records_moves() {
+        let mut campaign = create_test_campaign();
+        campaign.party[0].combatant.stress.current = 3;
+        campaign.party[0]
+            .add_magic_item(MagicItem::new("Charm").with_charges(1, Recharge::LongRest));
+        campaign.party[0].use_item("Charm", 1).unwrap();
+
+        let records = campaign
+            .rest(
+                "Alpha",
+                RestKind::Long,
+                &[RestActivity::ClearStress, RestActivity::Prepare],
+            )
+            .unwrap();
+        assert_eq!(records[0].amount, 3);
+        assert_eq!(campaign.party[0].combatant.stress.current, 0);
+        assert_eq!(campaign.party[0].combatant.hope().unwrap().current, 3);
+        assert_eq!(campaign.downtime("Alpha").unwrap().activities, records);
+        assert!(campaign.party[0].use_item("Charm", 1).is_ok());
+
+        assert!(campaign
+            .rest("Beta", RestKind::Short, &[RestActivity::Prepare; 3])
+            .is_err());
+        assert!(campaign.downtime("Beta").is_none());
+    }
+
+    #[test]
+    fn test_sessions_only_cover_their_own_rolls_and_notes() {
+        let mut campaign = create_test_campaign();
+        let critical = DualityRoll::from_values(5, 5).with_modifier(1);
+        campaign.log_roll("Alpha", "Parry", critical.clone());
+        campaign.add_note("Met the ferryman");
+        campaign.end_session("2025-03-07", 1, vec![]);
+
+        campaign.log_roll(
+            "Beta",
+            "Climb",
+            DualityRoll::from_values(2, 9).with_modifier(0),
+        );
+        campaign.log_roll("Beta", "Strike", critical);
+        campaign.fear.gain(2);
+        let second = campaign
+            .end_session("2025-03-14", 2, vec![String::from("Cliffhanger")])
+            .clone();
+
+        assert_eq!(second.number, 2);
+        assert_eq!(second.notable_rolls.len(), 1);
+        assert_eq!(second.notable_rolls[0].roller, "Beta");
+        assert_eq!(second.notes, vec!["Cliffhanger"]);
+        assert_eq!(second.fear_carried, 2);
+        assert_eq!(second.party.len(), 2);
+        assert_eq!(second.party[0].experience, 3);
+        assert_eq!(campaign.party[1].progress.experience, 3);
+        assert_eq!(campaign.sessions[0].notes, vec!["Met the ferryman"]);
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_campaign_serialization() {
+        let mut campaign = create_test_campaign();
+        campaign.begin_encounter("Ambush", vec![]).unwrap();
+        campaign.log_roll(
+            "Beta",
+            "Sneak",
+            DualityRoll::from_values(3, 8).with_modifier(1),
+        );
+
+        let json = serde_json::to_string(&campaign).unwrap();
+        let loaded: Campaign = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.version, CAMPAIGN_FORMAT_VERSION);
+        assert_eq!(loaded.party, campaign.party);
+        assert_eq!(loaded.roll_log, campaign.roll_log);
+        assert!(loaded.encounter().is_some());
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_house_rules_keep_their_top_level_fields() {
+        use crate::rules::DeathRule;
+
+        let campaign = create_test_campaign()
+            .with_hope_mode(HopeMode::Shared)
+            .with_rules(RulesConfig::default().with_death(DeathRule::Permadeath));
+        let value = serde_json::to_value(&campaign).unwrap();
+        assert_eq!(value["hope_mode"], "PerCharacter");
+        assert_eq!(value["death"], "Permadeath");
+
+        // Older saves have neither `max_fear` nor the new rules
+        let mut value = value;
+        let fields = value.as_object_mut().unwrap();
+        fields.remove("max_fear");
+        fields.remove("death");
+        fields.insert(
+            "fear".into(),
+            serde_json::json!({"current": 2, "maximum": 8}),
+        );
+        let mut loaded: Campaign = serde_json::from_value(value).unwrap();
+        loaded.after_load().unwrap();
+        assert_eq!(loaded.rules.death, DeathRule::AnyMove);
+        assert_eq!(loaded.rules.max_fear, 8);
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_load_rejects_newer_version() {
+        let mut campaign = create_test_campaign();
+        campaign.version = CAMPAIGN_FORMAT_VERSION + 1;
+
+        let path = std::env::temp_dir().join("daggerheart_campaign_version_test.json");
+        let path = path.to_str().unwrap();
+        campaign.save_to_file(path).unwrap();
+
+        let result = Campaign::load_from_file(path);
+        std::fs::remove_file(path).ok();
+        assert!(result.is_err());
+    }
+}
@@ -1,12 +1,17 @@
 //! Card effects and abilities
 
+use crate::combat::damage::DamageOverTime;
+use crate::combat::resources::{ConversionRate, Pool};
 use crate::core::dice::DamageDice;
+use alloc::string::String;
 use serde::{Deserialize, Serialize};
+use strum_macros::Display;
 
 use super::{Range, Target};
 
 /// Duration of an effect
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Duration {
     /// Effect lasts until end of current turn
     EndOfTurn,
@@ -20,8 +25,21 @@ pub enum Duration {
     Instant,
 }
 
+/// Which way a movement effect moves its target, relative to whoever
+/// uses it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum MoveDirection {
+    /// Push the target away
+    #[default]
+    Away,
+    /// Pull the target closer
+    Toward,
+}
+
 /// Type of card effect
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum CardEffect {
     /// Deal damage to target(s)
     Attack {
@@ -31,6 +49,9 @@ pub enum CardEffect {
         range: Range,
         /// Who can be targeted
         target: Target,
+        /// Rolled as Spellcast rather than a weapon attack
+        #[serde(default)]
+        spell: bool,
     },
     /// Heal hit points
     Heal {
@@ -61,6 +82,27 @@ pub enum CardEffect {
         distance: Range,
         /// Who can be moved
         target: Target,
+        /// Push or pull
+        #[serde(default)]
+        direction: MoveDirection,
+    },
+    /// Damage that keeps coming each turn, like burning or bleeding
+    DamageOverTime {
+        effect: DamageOverTime,
+        /// Who suffers it
+        target: Target,
+    },
+    /// Trade out of one of the user's pools and into another, like
+    /// marking a Stress to gain a Hope
+    Convert {
+        from: Pool,
+        to: Pool,
+        rate: ConversionRate,
+    },
+    /// Lift a character into the air until they land
+    Flight {
+        /// Who takes flight
+        target: Target,
     },
     /// Complex effect (description only for now)
     Special {
@@ -69,6 +111,90 @@ pub enum CardEffect {
     },
 }
 
+/// What sort of thing granted a modifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum SourceKind {
+    /// A card, class feature, or other ability
+    #[default]
+    Effect,
+    /// A condition the combatant is under
+    Condition,
+    /// Something the combatant carries or wears
+    Equipment,
+}
+
+/// A modifier a combatant is under right now
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ActiveEffect {
+    /// What granted it, such as a card or class feature
+    pub source: String,
+    /// What sort of thing the source is
+    #[serde(default)]
+    pub kind: SourceKind,
+    pub bonus: i8,
+    /// What it modifies, e.g. `attack rolls` or `Evasion`
+    pub applies_to: String,
+    pub duration: Duration,
+}
+
+impl ActiveEffect {
+    /// A modifier that lasts until it is removed, such as from equipment
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::cards::effects::{ActiveEffect, SourceKind};
+    ///
+    /// let ring = ActiveEffect::permanent("Ring of Focus", SourceKind::Equipment, 1, "Spellcast rolls");
+    /// assert!(ring.modifies("spellcast rolls"));
+    /// ```
+    pub fn permanent(
+        source: impl Into<String>,
+        kind: SourceKind,
+        bonus: i8,
+        applies_to: impl Into<String>,
+    ) -> Self {
+        Self {
+            source: source.into(),
+            kind,
+            bonus,
+            applies_to: applies_to.into(),
+            duration: Duration::Permanent,
+        }
+    }
+
+    /// Whether this modifies `what`, ignoring case
+    pub fn modifies(&self, what: &str) -> bool {
+        self.applies_to.eq_ignore_ascii_case(what)
+    }
+
+    /// Age the effect as its owner's turn ends; false once it has worn off
+    pub fn end_turn(&mut self) -> bool {
+        match self.duration {
+            Duration::EndOfTurn | Duration::Instant => false,
+            Duration::EndOfNextTurn => {
+                self.duration = Duration::EndOfTurn;
+                true
+            }
+            Duration::Rounds(_) | Duration::Permanent => true,
+        }
+    }
+
+    /// Age the effect as a new round begins; false once it has worn off
+    pub fn new_round(&mut self) -> bool {
+        match self.duration {
+            Duration::Rounds(rounds) if rounds <= 1 => false,
+            Duration::Rounds(rounds) => {
+                self.duration = Duration::Rounds(rounds - 1);
+                true
+            }
+            _ => true,
+        }
+    }
+}
+
 impl CardEffect {
     /// Create an attack effect
     ///
@@ -92,6 +218,47 @@ impl CardEffect {
             damage,
             range,
             target,
+            spell: false,
+        }
+    }
+
+    /// Create a spell attack, rolled with the caster's Spellcast trait
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::cards::{Range, Target};
+    /// use daggerheart_engine::cards::effects::CardEffect;
+    /// use daggerheart_engine::core::dice::DamageDice;
+    ///
+    /// let effect = CardEffect::spell(DamageDice::d10(1), Range::Far, Target::Enemy);
+    /// assert!(effect.is_attack());
+    /// assert!(effect.is_spell());
+    /// ```
+    pub fn spell(damage: DamageDice, range: Range, target: Target) -> Self {
+        Self::Attack {
+            damage,
+            range,
+            target,
+            spell: true,
+        }
+    }
+
+    /// Create an effect that pushes its target up to `distance` away
+    pub fn push(distance: Range, target: Target) -> Self {
+        Self::Move {
+            distance,
+            target,
+            direction: MoveDirection::Away,
+        }
+    }
+
+    /// Create an effect that pulls its target up to `distance` closer
+    pub fn pull(distance: Range, target: Target) -> Self {
+        Self::Move {
+            distance,
+            target,
+            direction: MoveDirection::Toward,
         }
     }
 
@@ -141,11 +308,81 @@ impl CardEffect {
         }
     }
 
+    /// Create an effect trading out of one of the user's pools and into
+    /// another
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::cards::effects::CardEffect;
+    /// use daggerheart_engine::cards::Target;
+    /// use daggerheart_engine::combat::{ConversionRate, Pool};
+    ///
+    /// let rally = CardEffect::convert(Pool::Stress, Pool::Hope, ConversionRate::EVEN);
+    /// assert_eq!(rally.target(), Some(Target::SelfOnly));
+    /// ```
+    pub fn convert(from: Pool, to: Pool, rate: ConversionRate) -> Self {
+        Self::Convert { from, to, rate }
+    }
+
+    /// Create an effect lifting the target into the air
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::cards::effects::CardEffect;
+    /// use daggerheart_engine::cards::Target;
+    ///
+    /// let wings = CardEffect::flight(Target::Ally);
+    /// assert!(wings.targets_allies());
+    /// ```
+    pub fn flight(target: Target) -> Self {
+        Self::Flight { target }
+    }
+
     /// Check if this is an attack effect
     pub fn is_attack(&self) -> bool {
         matches!(self, Self::Attack { .. })
     }
 
+    /// The lasting modifier this effect puts on its target, if any
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::cards::Target;
+    /// use daggerheart_engine::cards::effects::{CardEffect, Duration};
+    ///
+    /// let effect = CardEffect::modifier(1, Target::SelfOnly, Duration::Permanent, "attack rolls");
+    /// let active = effect.active_effect("No Mercy").unwrap();
+    /// assert!(active.modifies("Attack Rolls"));
+    ///
+    /// let instant = CardEffect::modifier(1, Target::SelfOnly, Duration::Instant, "attack rolls");
+    /// assert!(instant.active_effect("Flash").is_none());
+    /// ```
+    pub fn active_effect(&self, source: &str) -> Option<ActiveEffect> {
+        match self {
+            Self::Modifier {
+                bonus,
+                duration,
+                applies_to,
+                ..
+            } if *duration != Duration::Instant => Some(ActiveEffect {
+                source: String::from(source),
+                kind: SourceKind::Effect,
+                bonus: *bonus,
+                applies_to: applies_to.clone(),
+                duration: *duration,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Check if this is an attack rolled as Spellcast
+    pub fn is_spell(&self) -> bool {
+        matches!(self, Self::Attack { spell: true, .. })
+    }
+
     /// Check if this is a heal effect
     pub fn is_heal(&self) -> bool {
         matches!(self, Self::Heal { .. })
@@ -161,22 +398,47 @@ impl CardEffect {
         matches!(self, Self::ClearStress { .. })
     }
 
+    /// Who the effect is aimed at, if it says
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::cards::Target;
+    /// use daggerheart_engine::cards::effects::CardEffect;
+    ///
+    /// assert_eq!(CardEffect::heal(1, Target::Ally).target(), Some(Target::Ally));
+    /// ```
+    pub fn target(&self) -> Option<Target> {
+        match self {
+            Self::Attack { target, .. }
+            | Self::Heal { target, .. }
+            | Self::Modifier { target, .. }
+            | Self::ClearStress { target }
+            | Self::Flight { target }
+            | Self::Move { target, .. }
+            | Self::DamageOverTime { target, .. } => Some(*target),
+            Self::Convert { .. } => Some(Target::SelfOnly),
+            Self::Special { .. } => None,
+        }
+    }
+
     /// Check if this effect targets enemies
     pub fn targets_enemies(&self) -> bool {
         match self {
-            Self::Attack { target, .. } => {
+            Self::Attack { target, .. } | Self::DamageOverTime { target, .. } => {
                 matches!(target, Target::Enemy | Target::AllEnemies | Target::Any)
             }
             Self::Heal { target, .. } => matches!(target, Target::Enemy | Target::Any),
             Self::Modifier { target, .. } => {
                 matches!(target, Target::Enemy | Target::AllEnemies | Target::Any)
             }
-            Self::ClearStress { target } => {
+            Self::ClearStress { target } | Self::Flight { target } => {
                 matches!(target, Target::Enemy | Target::AllEnemies | Target::Any)
             }
             Self::Move { target, .. } => {
                 matches!(target, Target::Enemy | Target::AllEnemies | Target::Any)
             }
+            Self::Convert { .. } => false,
             Self::Special { .. } => false, // Unknown
         }
     }
@@ -184,7 +446,7 @@ impl CardEffect {
     /// Check if this effect targets allies
     pub fn targets_allies(&self) -> bool {
         match self {
-            Self::Attack { target, .. } => {
+            Self::Attack { target, .. } | Self::DamageOverTime { target, .. } => {
                 matches!(target, Target::Ally | Target::AllAllies | Target::Any)
             }
             Self::Heal { target, .. } => {
@@ -199,7 +461,7 @@ impl CardEffect {
                     Target::SelfOnly | Target::Ally | Target::AllAllies | Target::Any
                 )
             }
-            Self::ClearStress { target } => {
+            Self::ClearStress { target } | Self::Flight { target } => {
                 matches!(
                     target,
                     Target::SelfOnly | Target::Ally | Target::AllAllies | Target::Any
@@ -211,6 +473,7 @@ impl CardEffect {
                     Target::SelfOnly | Target::Ally | Target::AllAllies | Target::Any
                 )
             }
+            Self::Convert { .. } => true,
             Self::Special { .. } => false, // Unknown
         }
     }
@@ -248,6 +511,38 @@ mod tests {
         assert!(!effect.targets_allies());
     }
 
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_spell_attack_effect() {
+        let effect = CardEffect::spell(DamageDice::d8(2), Range::Far, Target::Enemy);
+        assert!(effect.is_attack());
+        assert!(effect.is_spell());
+        assert!(!CardEffect::attack(DamageDice::d8(2), Range::Far, Target::Enemy).is_spell());
+
+        // Effects saved before spells existed are weapon attacks
+        let json =
+            r#"{"Attack":{"damage":{"dice":["D6"],"bonus":0},"range":"Close","target":"Enemy"}}"#;
+        let loaded: CardEffect = serde_json::from_str(json).unwrap();
+        assert!(loaded.is_attack());
+        assert!(!loaded.is_spell());
+    }
+
+    #[test]
+    fn test_active_effects_wear_off() {
+        let mut turn = CardEffect::modifier(2, Target::Ally, Duration::EndOfNextTurn, "Evasion")
+            .active_effect("Shield")
+            .unwrap();
+        assert!(turn.end_turn());
+        assert!(!turn.end_turn());
+
+        let mut rounds = CardEffect::modifier(-1, Target::Enemy, Duration::Rounds(2), "attacks")
+            .active_effect("Curse")
+            .unwrap();
+        assert!(rounds.end_turn());
+        assert!(rounds.new_round());
+        assert!(!rounds.new_round());
+    }
+
     #[test]
     fn test_create_heal_effect() {
         let effect = CardEffect::heal(5, Target::Ally);
@@ -344,6 +639,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "persistence")]
     #[test]
     fn test_effect_serialization() {
         let effect = CardEffect::attack(
@@ -358,6 +654,7 @@ mod tests {
         assert_eq!(effect, loaded);
     }
 
+    #[cfg(feature = "persistence")]
     #[test]
     fn test_duration_serialization() {
         let duration = Duration::Rounds(5);
@@ -439,6 +736,8 @@ mod property_tests {
                 Just(CardEffect::heal(5, Target::SelfOnly)),
                 Just(CardEffect::modifier(2, Target::SelfOnly, Duration::EndOfTurn, "test")),
                 Just(CardEffect::ClearStress { target: Target::SelfOnly }),
+                Just(CardEffect::flight(Target::SelfOnly)),
+                Just(CardEffect::convert(Pool::Stress, Pool::Hope, ConversionRate::EVEN)),
             ],
         ) {
             // SelfOnly should count as targeting allies
@@ -2,13 +2,18 @@
 
 pub mod effects;
 
-use crate::character::Domain;
+use crate::character::{Class, Domain};
+use crate::error::EngineError;
+#[cfg(feature = "persistence")]
+use crate::storage::Persist;
+use alloc::string::String;
 use serde::{Deserialize, Serialize};
 
-pub use effects::{CardEffect, Duration};
+pub use effects::{ActiveEffect, CardEffect, Duration, MoveDirection, SourceKind};
 
 /// Range categories for abilities
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Range {
     /// Very Close (melee, adjacent)
     VeryClose,
@@ -20,8 +25,31 @@ pub enum Range {
     Any,
 }
 
+impl Range {
+    /// How many range bands a movement of this range covers, or `None`
+    /// for no limit
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::cards::Range;
+    ///
+    /// assert_eq!(Range::Close.steps(), Some(2));
+    /// assert_eq!(Range::Any.steps(), None);
+    /// ```
+    pub fn steps(self) -> Option<u8> {
+        match self {
+            Range::VeryClose => Some(1),
+            Range::Close => Some(2),
+            Range::Far => Some(3),
+            Range::Any => None,
+        }
+    }
+}
+
 /// Target type for abilities
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Target {
     /// Self only
     SelfOnly,
@@ -39,6 +67,7 @@ pub enum Target {
 
 /// Action cost type
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum ActionCost {
     /// Major action (one per turn)
     Major,
@@ -52,6 +81,7 @@ pub enum ActionCost {
 
 /// Domain card representing an ability
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DomainCard {
     /// Unique identifier
     pub id: String,
@@ -60,11 +90,15 @@ pub struct DomainCard {
     /// Which domain this card belongs to
     pub domain: Domain,
     /// Minimum character level required
+    #[serde(default)]
     pub level_requirement: u8,
     /// Card description
     pub description: String,
     /// Action cost to use this card
     pub action_cost: ActionCost,
+    /// Stress marked to swap this card from the vault into the loadout
+    #[serde(default)]
+    pub recall_cost: u8,
 }
 
 impl DomainCard {
@@ -103,9 +137,16 @@ impl DomainCard {
             level_requirement,
             description: description.into(),
             action_cost,
+            recall_cost: 0,
         }
     }
 
+    /// Set the Stress it costs to recall this card from the vault
+    pub fn with_recall_cost(mut self, recall_cost: u8) -> Self {
+        self.recall_cost = recall_cost;
+        self
+    }
+
     /// Check if a character of given level can use this card
     ///
     /// # Examples
@@ -131,6 +172,51 @@ impl DomainCard {
         character_level >= self.level_requirement
     }
 
+    /// Check that a character of this class and level may play this card
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::cards::{ActionCost, DomainCard};
+    /// use daggerheart_engine::character::{Class, Domain};
+    /// use daggerheart_engine::EngineError;
+    ///
+    /// let card = DomainCard::new(
+    ///     "blade_strike",
+    ///     "Blade Strike",
+    ///     Domain::Blade,
+    ///     3,
+    ///     "A powerful strike",
+    ///     ActionCost::Major,
+    /// );
+    ///
+    /// assert!(card.check_allowed(Class::Warrior, 3).is_ok());
+    /// assert!(matches!(
+    ///     card.check_allowed(Class::Wizard, 3),
+    ///     Err(EngineError::CardNotAllowed { domain: Domain::Blade, .. })
+    /// ));
+    /// assert!(matches!(
+    ///     card.check_allowed(Class::Warrior, 1),
+    ///     Err(EngineError::CardLevelTooLow { required: 3, level: 1, .. })
+    /// ));
+    /// ```
+    pub fn check_allowed(&self, class: Class, character_level: u8) -> Result<(), EngineError> {
+        if !class.has_domain(self.domain) {
+            return Err(EngineError::CardNotAllowed {
+                card: self.name.clone(),
+                domain: self.domain,
+            });
+        }
+        if !self.can_use(character_level) {
+            return Err(EngineError::CardLevelTooLow {
+                card: self.name.clone(),
+                required: self.level_requirement,
+                level: character_level,
+            });
+        }
+        Ok(())
+    }
+
     /// Check if this is a major action card
     pub fn is_major_action(&self) -> bool {
         matches!(self.action_cost, ActionCost::Major)
@@ -147,6 +233,9 @@ impl DomainCard {
     }
 }
 
+#[cfg(feature = "persistence")]
+impl Persist for DomainCard {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -290,6 +379,7 @@ mod tests {
         assert!(card.is_reaction());
     }
 
+    #[cfg(feature = "persistence")]
     #[test]
     fn test_card_serialization() {
         let card = DomainCard::new(
@@ -307,6 +397,7 @@ mod tests {
         assert_eq!(card, loaded);
     }
 
+    #[cfg(feature = "persistence")]
     #[test]
     fn test_range_serialization() {
         let range = Range::Close;
@@ -315,6 +406,7 @@ mod tests {
         assert_eq!(range, loaded);
     }
 
+    #[cfg(feature = "persistence")]
     #[test]
     fn test_target_serialization() {
         let target = Target::Enemy;
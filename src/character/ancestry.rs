@@ -1,10 +1,17 @@
 //! Character ancestries (races)
 
+use crate::cards::effects::{ActiveEffect, CardEffect, Duration};
+use crate::cards::{Range, Target};
+use crate::combat::triggers::{Trigger, TriggerEffect, TriggeredFeature};
+use crate::core::dice::{DamageDice, Die};
+use alloc::vec;
+use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter};
 
 /// The 17 playable ancestries in Daggerheart
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Ancestry {
     Clank,
     Daemon,
@@ -80,47 +87,332 @@ impl Ancestry {
     /// assert!(!Ancestry::Human.has_flight());
     /// ```
     pub fn has_flight(&self) -> bool {
-        matches!(self, Ancestry::Faerie)
+        self.features()
+            .iter()
+            .any(|feature| feature.effect == AncestryEffect::Flight)
     }
 
-    /// Get the foundation abilities for this ancestry
+    /// The foundation features of this ancestry
     ///
-    /// Each ancestry provides unique foundation abilities that grant
-    /// special powers and traits. This is a simplified representation;
-    /// full ability implementations will be added later.
+    /// Each carries what it does as effect data: passive modifiers and
+    /// triggered features are put on a character as they're built, and
+    /// the rest are there for the encounter and the table to use.
     ///
     /// # Examples
     ///
     /// ```
-    /// use daggerheart_engine::character::Ancestry;
+    /// use daggerheart_engine::character::{Ancestry, AncestryEffect};
     ///
-    /// let abilities = Ancestry::Human.foundation_abilities();
-    /// assert!(abilities.contains(&"Adaptable"));
+    /// let features = Ancestry::Halfling.features();
+    /// assert_eq!(features[0].name, "Lucky");
+    /// assert_eq!(features[0].effect, AncestryEffect::Reroll);
     ///
-    /// let abilities = Ancestry::Giant.foundation_abilities();
-    /// assert!(abilities.contains(&"Mighty Grip"));
+    /// let breath = &Ancestry::Drakona.features()[1];
+    /// assert!(matches!(&breath.effect, AncestryEffect::Ability { effect, .. } if effect.is_attack()));
     /// ```
-    pub fn foundation_abilities(&self) -> Vec<&'static str> {
-        match self {
-            Ancestry::Clank => vec!["Constructed", "Repair Protocol"],
-            Ancestry::Daemon => vec!["Demon Ancestry", "Otherworldly"],
-            Ancestry::Drakona => vec!["Dragon Ancestry", "Breath Weapon"],
-            Ancestry::Dwarf => vec!["Stonecunning", "Dwarven Resilience"],
-            Ancestry::Faerie => vec!["Flight", "Fey Magic"],
-            Ancestry::Faun => vec!["Natural Athlete", "Forest Step"],
-            Ancestry::Fungril => vec!["Spore Cloud", "Fungal Network"],
-            Ancestry::Galapa => vec!["Shell Defense", "Aquatic"],
-            Ancestry::Giant => vec!["Mighty Grip", "Imposing Presence"],
-            Ancestry::Goblin => vec!["Nimble Escape", "Sneaky"],
-            Ancestry::Halfling => vec!["Lucky", "Brave"],
-            Ancestry::Human => vec!["Adaptable", "Versatile"],
-            Ancestry::Inferis => vec!["Fire Resistance", "Infernal Legacy"],
-            Ancestry::Katari => vec!["Cat's Grace", "Nine Lives"],
-            Ancestry::Orc => vec!["Relentless Endurance", "Savage Attacks"],
-            Ancestry::Ribbet => vec!["Amphibious", "Leap"],
-            Ancestry::Simiah => vec!["Prehensile Tail", "Climbing"],
+    pub fn features(&self) -> Vec<AncestryFeature> {
+        let features = match self {
+            Ancestry::Clank => [
+                (
+                    "Constructed",
+                    "Doesn't need to eat, sleep or breathe",
+                    AncestryEffect::Narrative,
+                ),
+                (
+                    "Repair Protocol",
+                    "Patch yourself up, clearing a Hit Point",
+                    AncestryEffect::Ability {
+                        effect: CardEffect::heal(1, Target::SelfOnly),
+                        uses_per_rest: Some(1),
+                    },
+                ),
+            ],
+            Ancestry::Daemon => [
+                (
+                    "Demon Ancestry",
+                    "Sense the presence of fiends and the infernal",
+                    AncestryEffect::Narrative,
+                ),
+                (
+                    "Otherworldly",
+                    "Sense magic and spirits nearby",
+                    AncestryEffect::Narrative,
+                ),
+            ],
+            Ancestry::Drakona => [
+                (
+                    "Dragon Ancestry",
+                    "Speak with and be understood by dragons",
+                    AncestryEffect::Narrative,
+                ),
+                (
+                    "Breath Weapon",
+                    "Breathe elemental energy over everyone in Very Close range for d8 damage",
+                    AncestryEffect::Ability {
+                        effect: CardEffect::attack(
+                            DamageDice::new(vec![Die::D8]),
+                            Range::VeryClose,
+                            Target::AllEnemies,
+                        ),
+                        uses_per_rest: Some(1),
+                    },
+                ),
+            ],
+            Ancestry::Dwarf => [
+                (
+                    "Stonecunning",
+                    "Read the age and make of stonework at a glance",
+                    AncestryEffect::Narrative,
+                ),
+                (
+                    "Dwarven Resilience",
+                    "Shrug off a blow, clearing a Stress when an attack damages you",
+                    AncestryEffect::Triggered {
+                        trigger: Trigger::Damaged,
+                        effect: TriggerEffect::ClearStress(1),
+                    },
+                ),
+            ],
+            Ancestry::Faerie => [
+                ("Flight", "Fly on your wings", AncestryEffect::Flight),
+                (
+                    "Fey Magic",
+                    "Cast small glamours and illusions",
+                    AncestryEffect::Narrative,
+                ),
+            ],
+            Ancestry::Faun => [
+                (
+                    "Natural Athlete",
+                    "Leap, climb and run with ease",
+                    AncestryEffect::Narrative,
+                ),
+                (
+                    "Forest Step",
+                    "Move through undergrowth without a trace",
+                    AncestryEffect::Narrative,
+                ),
+            ],
+            Ancestry::Fungril => [
+                (
+                    "Spore Cloud",
+                    "Release choking spores when struck up close; the attacker marks a Stress",
+                    AncestryEffect::Triggered {
+                        trigger: Trigger::DamagedByMelee,
+                        effect: TriggerEffect::OpponentMarksStress(1),
+                    },
+                ),
+                (
+                    "Fungal Network",
+                    "Speak with other Fungril through the mycelium",
+                    AncestryEffect::Narrative,
+                ),
+            ],
+            Ancestry::Galapa => [
+                (
+                    "Shell Defense",
+                    "Pull into your shell to weather a blow",
+                    AncestryEffect::Narrative,
+                ),
+                (
+                    "Aquatic",
+                    "Breathe and move naturally underwater",
+                    AncestryEffect::Narrative,
+                ),
+            ],
+            Ancestry::Giant => [
+                (
+                    "Mighty Grip",
+                    "Wield heavy things as if they were light",
+                    AncestryEffect::Narrative,
+                ),
+                (
+                    "Reach",
+                    "Your melee range extends to Very Close",
+                    AncestryEffect::Reach,
+                ),
+            ],
+            Ancestry::Goblin => [
+                (
+                    "Nimble Escape",
+                    "Slip out of a tight spot",
+                    AncestryEffect::Narrative,
+                ),
+                (
+                    "Sneaky",
+                    "Go unnoticed in a crowd",
+                    AncestryEffect::Narrative,
+                ),
+            ],
+            Ancestry::Halfling => [
+                (
+                    "Lucky",
+                    "Reroll your duality dice and take either result",
+                    AncestryEffect::Reroll,
+                ),
+                (
+                    "Brave",
+                    "Stand firm against fear",
+                    AncestryEffect::Narrative,
+                ),
+            ],
+            Ancestry::Human => [
+                (
+                    "Adaptable",
+                    "Pick up new skills quickly",
+                    AncestryEffect::Narrative,
+                ),
+                (
+                    "Versatile",
+                    "Turn your hand to anything",
+                    AncestryEffect::Narrative,
+                ),
+            ],
+            Ancestry::Inferis => [
+                (
+                    "Fire Resistance",
+                    "Endure heat and flame",
+                    AncestryEffect::Narrative,
+                ),
+                (
+                    "Infernal Legacy",
+                    "Draw on your fiendish heritage for +1 to Spellcast rolls",
+                    AncestryEffect::Passive(modifier(1, "Spellcast rolls")),
+                ),
+            ],
+            Ancestry::Katari => [
+                (
+                    "Cat's Grace",
+                    "Land on your feet from any fall",
+                    AncestryEffect::Narrative,
+                ),
+                (
+                    "Nine Lives",
+                    "Cheat death when it comes for you",
+                    AncestryEffect::Narrative,
+                ),
+            ],
+            Ancestry::Orc => [
+                (
+                    "Relentless Endurance",
+                    "Keep going long after others tire",
+                    AncestryEffect::Narrative,
+                ),
+                (
+                    "Savage Attacks",
+                    "Gore with your tusks when you land a hit; the target marks a Stress",
+                    AncestryEffect::Triggered {
+                        trigger: Trigger::Hits,
+                        effect: TriggerEffect::OpponentMarksStress(1),
+                    },
+                ),
+            ],
+            Ancestry::Ribbet => [
+                (
+                    "Amphibious",
+                    "Breathe and move naturally underwater",
+                    AncestryEffect::Narrative,
+                ),
+                (
+                    "Long Tongue",
+                    "Lash out with your tongue at a target in Close range for d12 damage",
+                    AncestryEffect::Ability {
+                        effect: CardEffect::attack(
+                            DamageDice::new(vec![Die::D12]),
+                            Range::Close,
+                            Target::Enemy,
+                        ),
+                        uses_per_rest: None,
+                    },
+                ),
+            ],
+            Ancestry::Simiah => [
+                (
+                    "Prehensile Tail",
+                    "Hold an item or a handhold with your tail",
+                    AncestryEffect::Narrative,
+                ),
+                (
+                    "Climbing",
+                    "Climb as easily as you walk",
+                    AncestryEffect::Narrative,
+                ),
+            ],
+        };
+
+        features
+            .into_iter()
+            .map(|(name, description, effect)| AncestryFeature {
+                ancestry: *self,
+                name,
+                description,
+                effect,
+            })
+            .collect()
+    }
+}
+
+/// What an ancestry feature does
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum AncestryEffect {
+    /// Always on, like a bonus to a kind of roll
+    Passive(CardEffect),
+    /// Fires on its own during attacks, like an adversary's triggered
+    /// feature
+    Triggered {
+        trigger: Trigger,
+        effect: TriggerEffect,
+    },
+    /// Used like a card, such as a breath weapon, as often as the
+    /// limit allows between rests
+    Ability {
+        effect: CardEffect,
+        /// Uses before the character rests; `None` for no limit
+        uses_per_rest: Option<u8>,
+    },
+    /// Reroll the duality dice and keep either result
+    Reroll,
+    /// Melee range extends to Very Close
+    Reach,
+    /// The character can fly
+    Flight,
+    /// Flavour for the table with nothing for the engine to do
+    Narrative,
+}
+
+/// One of an ancestry's foundation features
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AncestryFeature {
+    pub ancestry: Ancestry,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub effect: AncestryEffect,
+}
+
+impl AncestryFeature {
+    /// The triggered feature to put on a character, if it is one
+    pub fn trigger(&self) -> Option<TriggeredFeature> {
+        match &self.effect {
+            AncestryEffect::Triggered { trigger, effect } => {
+                Some(TriggeredFeature::new(self.name, *trigger, effect.clone()))
+            }
+            _ => None,
         }
     }
+
+    /// The lasting modifier to put on a character, if it is one
+    pub fn modifier(&self) -> Option<ActiveEffect> {
+        match &self.effect {
+            AncestryEffect::Passive(effect) => effect.active_effect(self.name),
+            _ => None,
+        }
+    }
+}
+
+fn modifier(bonus: i8, applies_to: &str) -> CardEffect {
+    CardEffect::modifier(bonus, Target::SelfOnly, Duration::Permanent, applies_to)
 }
 
 #[cfg(test)]
@@ -181,36 +473,49 @@ mod tests {
     }
 
     #[test]
-    fn test_all_ancestries_have_foundation_ability() {
-        // Every ancestry should have at least one foundation ability
+    fn test_all_ancestries_have_foundation_features() {
         for ancestry in Ancestry::iter() {
-            let abilities = ancestry.foundation_abilities();
-            assert!(
-                !abilities.is_empty(),
-                "{} should have foundation abilities",
-                ancestry
-            );
+            let features = ancestry.features();
+            assert_eq!(features.len(), 2, "{} should have two features", ancestry);
+            assert!(features.iter().all(|f| f.ancestry == ancestry));
         }
     }
 
     #[test]
-    fn test_human_foundation_abilities() {
-        let abilities = Ancestry::Human.foundation_abilities();
+    fn test_human_foundation_features() {
+        let features = Ancestry::Human.features();
         assert!(
-            abilities.contains(&"Adaptable"),
+            features.iter().any(|f| f.name == "Adaptable"),
             "Humans should have Adaptable trait"
         );
     }
 
     #[test]
-    fn test_giant_foundation_abilities() {
-        let abilities = Ancestry::Giant.foundation_abilities();
+    fn test_giant_foundation_features() {
+        let features = Ancestry::Giant.features();
         assert!(
-            abilities.contains(&"Mighty Grip"),
+            features.iter().any(|f| f.name == "Mighty Grip"),
             "Giants should have Mighty Grip"
         );
+        assert_eq!(features[1].effect, AncestryEffect::Reach);
+    }
+
+    #[test]
+    fn test_features_convert_to_triggers_and_modifiers() {
+        let tusks = &Ancestry::Orc.features()[1];
+        assert_eq!(
+            tusks.trigger().unwrap().to_string(),
+            "Savage Attacks: When it hits with an attack, the opponent marks 1 Stress"
+        );
+        assert!(tusks.modifier().is_none());
+
+        let legacy = &Ancestry::Inferis.features()[1];
+        let modifier = legacy.modifier().unwrap();
+        assert_eq!(modifier.source, "Infernal Legacy");
+        assert!(modifier.modifies("Spellcast rolls"));
     }
 
+    #[cfg(feature = "persistence")]
     #[test]
     fn test_all_ancestries_serializable() {
         for ancestry in Ancestry::iter() {
@@ -277,10 +582,10 @@ mod property_tests {
         }
 
         #[test]
-        fn prop_foundation_abilities_is_consistent(ancestry in any_ancestry()) {
-            let abilities1 = ancestry.foundation_abilities();
-            let abilities2 = ancestry.foundation_abilities();
-            prop_assert_eq!(abilities1, abilities2, "foundation_abilities() should be deterministic");
+        fn prop_features_are_consistent(ancestry in any_ancestry()) {
+            let features1 = ancestry.features();
+            let features2 = ancestry.features();
+            prop_assert_eq!(features1, features2, "features() should be deterministic");
         }
 
         #[test]
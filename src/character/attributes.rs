@@ -3,10 +3,13 @@
 //! Characters have six core traits with modifiers that must sum correctly.
 
 use crate::error::EngineError;
+use alloc::vec;
 use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
 
 /// Attribute types in Daggerheart
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum AttributeType {
     Agility,
     Strength,
@@ -21,6 +24,7 @@ pub enum AttributeType {
 /// Daggerheart characters have six core traits that modify their actions.
 /// The modifiers must be exactly: +2, +1, +1, +0, +0, -1 (in any order).
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Attributes {
     pub agility: i8,
     pub strength: i8,
@@ -109,10 +113,7 @@ impl Attributes {
         if mods == expected {
             Ok(())
         } else {
-            Err(EngineError::InvalidCharacterState(format!(
-                "Attributes must use standard modifiers {:?}, got {:?}",
-                expected, mods
-            )))
+            Err(EngineError::InvalidAttributes { modifiers: mods })
         }
     }
 }
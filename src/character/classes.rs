@@ -1,10 +1,14 @@
 //! Character classes and domains
 
+use crate::cards::{CardEffect, Duration, Target};
+use crate::character::AttributeType;
+use alloc::string::String;
 use serde::{Deserialize, Serialize};
 use strum_macros::{Display, EnumIter};
 
 /// The nine playable classes in Daggerheart
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Class {
     Bard,
     Druid,
@@ -17,8 +21,94 @@ pub enum Class {
     Wizard,
 }
 
+/// The two subclasses each class chooses between at character creation
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Subclass {
+    Troubadour,
+    Wordsmith,
+    #[strum(serialize = "Warden of the Elements")]
+    WardenOfTheElements,
+    #[strum(serialize = "Warden of Renewal")]
+    WardenOfRenewal,
+    Stalwart,
+    Vengeance,
+    Beastbound,
+    Wayfinder,
+    Nightwalker,
+    Syndicate,
+    #[strum(serialize = "Divine Wielder")]
+    DivineWielder,
+    #[strum(serialize = "Winged Sentinel")]
+    WingedSentinel,
+    #[strum(serialize = "Elemental Origin")]
+    ElementalOrigin,
+    #[strum(serialize = "Primal Origin")]
+    PrimalOrigin,
+    #[strum(serialize = "Call of the Brave")]
+    CallOfTheBrave,
+    #[strum(serialize = "Call of the Slayer")]
+    CallOfTheSlayer,
+    #[strum(serialize = "School of Knowledge")]
+    SchoolOfKnowledge,
+    #[strum(serialize = "School of War")]
+    SchoolOfWar,
+}
+
+impl Subclass {
+    /// The class this subclass belongs to
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Class, Subclass};
+    ///
+    /// assert_eq!(Subclass::Stalwart.class(), Class::Guardian);
+    /// assert_eq!(Subclass::SchoolOfWar.to_string(), "School of War");
+    /// ```
+    pub fn class(&self) -> Class {
+        match self {
+            Subclass::Troubadour | Subclass::Wordsmith => Class::Bard,
+            Subclass::WardenOfTheElements | Subclass::WardenOfRenewal => Class::Druid,
+            Subclass::Stalwart | Subclass::Vengeance => Class::Guardian,
+            Subclass::Beastbound | Subclass::Wayfinder => Class::Ranger,
+            Subclass::Nightwalker | Subclass::Syndicate => Class::Rogue,
+            Subclass::DivineWielder | Subclass::WingedSentinel => Class::Seraph,
+            Subclass::ElementalOrigin | Subclass::PrimalOrigin => Class::Sorcerer,
+            Subclass::CallOfTheBrave | Subclass::CallOfTheSlayer => Class::Warrior,
+            Subclass::SchoolOfKnowledge | Subclass::SchoolOfWar => Class::Wizard,
+        }
+    }
+
+    /// The trait this subclass rolls Spellcast with, if it casts at all
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{AttributeType, Subclass};
+    ///
+    /// assert_eq!(
+    ///     Subclass::SchoolOfWar.spellcast_trait(),
+    ///     Some(AttributeType::Knowledge)
+    /// );
+    /// assert_eq!(Subclass::CallOfTheBrave.spellcast_trait(), None);
+    /// ```
+    pub fn spellcast_trait(&self) -> Option<AttributeType> {
+        match self.class() {
+            Class::Bard => Some(AttributeType::Presence),
+            Class::Druid | Class::Sorcerer => Some(AttributeType::Instinct),
+            Class::Ranger => Some(AttributeType::Agility),
+            Class::Rogue => Some(AttributeType::Finesse),
+            Class::Seraph => Some(AttributeType::Strength),
+            Class::Wizard => Some(AttributeType::Knowledge),
+            Class::Guardian | Class::Warrior => None,
+        }
+    }
+}
+
 /// The nine domains that grant special abilities
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Domain {
     Arcana,
     Blade,
@@ -59,6 +149,21 @@ impl Class {
         }
     }
 
+    /// Check if this class has access to a domain
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Class, Domain};
+    ///
+    /// assert!(Class::Warrior.has_domain(Domain::Blade));
+    /// assert!(!Class::Warrior.has_domain(Domain::Arcana));
+    /// ```
+    pub fn has_domain(&self, domain: Domain) -> bool {
+        let (first, second) = self.domains();
+        first == domain || second == domain
+    }
+
     /// Get the starting HP for this class
     ///
     /// All classes start with 6 HP in Daggerheart.
@@ -104,6 +209,55 @@ impl Class {
         }
     }
 
+    /// The two subclasses this class can choose from
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Class, Subclass};
+    ///
+    /// assert_eq!(
+    ///     Class::Bard.subclasses(),
+    ///     [Subclass::Troubadour, Subclass::Wordsmith]
+    /// );
+    /// ```
+    pub fn subclasses(&self) -> [Subclass; 2] {
+        match self {
+            Class::Bard => [Subclass::Troubadour, Subclass::Wordsmith],
+            Class::Druid => [Subclass::WardenOfTheElements, Subclass::WardenOfRenewal],
+            Class::Guardian => [Subclass::Stalwart, Subclass::Vengeance],
+            Class::Ranger => [Subclass::Beastbound, Subclass::Wayfinder],
+            Class::Rogue => [Subclass::Nightwalker, Subclass::Syndicate],
+            Class::Seraph => [Subclass::DivineWielder, Subclass::WingedSentinel],
+            Class::Sorcerer => [Subclass::ElementalOrigin, Subclass::PrimalOrigin],
+            Class::Warrior => [Subclass::CallOfTheBrave, Subclass::CallOfTheSlayer],
+            Class::Wizard => [Subclass::SchoolOfKnowledge, Subclass::SchoolOfWar],
+        }
+    }
+
+    /// The starting weapons and armor suggested for this class
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::Class;
+    ///
+    /// assert!(Class::Warrior.suggested_equipment().contains(&"Longsword"));
+    /// ```
+    pub fn suggested_equipment(&self) -> &'static [&'static str] {
+        match self {
+            Class::Bard => &["Rapier", "Small Dagger", "Gambeson Armor"],
+            Class::Druid => &["Shortstaff", "Round Shield", "Leather Armor"],
+            Class::Guardian => &["Battleaxe", "Chainmail Armor"],
+            Class::Ranger => &["Shortbow", "Leather Armor"],
+            Class::Rogue => &["Dagger", "Small Dagger", "Gambeson Armor"],
+            Class::Seraph => &["Hallowed Axe", "Round Shield", "Chainmail Armor"],
+            Class::Sorcerer => &["Dualstaff", "Gambeson Armor"],
+            Class::Warrior => &["Longsword", "Chainmail Armor"],
+            Class::Wizard => &["Greatstaff", "Leather Armor"],
+        }
+    }
+
     /// Check if this class can use abilities from a specific domain
     ///
     /// # Examples
@@ -119,6 +273,101 @@ impl Class {
         let (d1, d2) = self.domains();
         domain == d1 || domain == d2
     }
+
+    /// The class's signature Hope feature
+    ///
+    /// Every class has one, and each costs 3 Hope.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::Class;
+    ///
+    /// let feature = Class::Warrior.hope_feature();
+    /// assert_eq!(feature.name, "No Mercy");
+    /// assert_eq!(feature.hope_cost, 3);
+    /// ```
+    pub fn hope_feature(&self) -> ClassHopeFeature {
+        let (name, description, effect) = match self {
+            Class::Bard => (
+                "Make a Scene",
+                "Distract a target within Close range, giving them -2 to their Difficulty",
+                CardEffect::modifier(-2, Target::Enemy, Duration::EndOfNextTurn, "Difficulty"),
+            ),
+            Class::Druid => (
+                "Evolution",
+                "Transform into a Beastform without marking Stress, and raise one trait by +1 while transformed",
+                special("Beastform without marking Stress; +1 to a trait while transformed"),
+            ),
+            Class::Guardian => (
+                "Frontline Tank",
+                "Clear 2 Armor Slots",
+                special("Clear 2 Armor Slots"),
+            ),
+            Class::Ranger => (
+                "Hold Them Off",
+                "After a successful weapon attack, use the same roll against two more adversaries",
+                special("Use the attack roll against two more adversaries"),
+            ),
+            Class::Rogue => (
+                "Rogue's Dodge",
+                "Gain +2 Evasion until an attack succeeds against you or your next rest",
+                CardEffect::modifier(2, Target::SelfOnly, Duration::Permanent, "Evasion"),
+            ),
+            Class::Seraph => (
+                "Life Support",
+                "Clear a Hit Point on an ally within Close range",
+                CardEffect::heal(1, Target::Ally),
+            ),
+            Class::Sorcerer => (
+                "Volatile Magic",
+                "Reroll any number of damage dice on an attack that deals magic damage",
+                special("Reroll any number of magic damage dice"),
+            ),
+            Class::Warrior => (
+                "No Mercy",
+                "Gain +1 to attack rolls until your next rest",
+                CardEffect::modifier(1, Target::SelfOnly, Duration::Permanent, "attack rolls"),
+            ),
+            Class::Wizard => (
+                "Not This Time",
+                "Force an adversary within Far range to reroll an attack or damage roll",
+                special("An adversary rerolls an attack or damage roll"),
+            ),
+        };
+
+        ClassHopeFeature {
+            class: *self,
+            name,
+            description,
+            hope_cost: CLASS_FEATURE_HOPE_COST,
+            effect,
+        }
+    }
+}
+
+/// What every class's Hope feature costs
+pub const CLASS_FEATURE_HOPE_COST: u8 = 3;
+
+/// A class's signature feature, paid for with Hope
+///
+/// Effects the engine can resolve, like Life Support's healing or No
+/// Mercy's attack bonus, are applied when the feature is used; the rest
+/// are logged for the GM.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ClassHopeFeature {
+    pub class: Class,
+    pub name: &'static str,
+    pub description: &'static str,
+    pub hope_cost: u8,
+    pub effect: CardEffect,
+}
+
+fn special(description: &str) -> CardEffect {
+    CardEffect::Special {
+        description: String::from(description),
+    }
 }
 
 #[cfg(test)]
@@ -131,6 +380,22 @@ mod tests {
         assert_eq!(Class::iter().count(), 9, "Should have exactly 9 classes");
     }
 
+    #[test]
+    fn test_subclasses_belong_to_their_class() {
+        assert_eq!(Subclass::iter().count(), 18);
+        for class in Class::iter() {
+            for subclass in class.subclasses() {
+                assert_eq!(
+                    subclass.class(),
+                    class,
+                    "{} should be a {}",
+                    subclass,
+                    class
+                );
+            }
+        }
+    }
+
     #[test]
     fn test_domain_count() {
         assert_eq!(Domain::iter().count(), 9, "Should have exactly 9 domains");
@@ -198,6 +463,31 @@ mod tests {
         assert!(!class.can_use_domain(Domain::Blade));
     }
 
+    #[test]
+    fn test_spellcast_traits() {
+        for subclass in Subclass::iter() {
+            let casts = !matches!(subclass.class(), Class::Guardian | Class::Warrior);
+            assert_eq!(subclass.spellcast_trait().is_some(), casts, "{}", subclass);
+        }
+        assert_eq!(
+            Subclass::Troubadour.spellcast_trait(),
+            Some(AttributeType::Presence)
+        );
+    }
+
+    #[test]
+    fn test_every_class_has_a_hope_feature() {
+        for class in Class::iter() {
+            let feature = class.hope_feature();
+            assert_eq!(feature.class, class);
+            assert_eq!(feature.hope_cost, CLASS_FEATURE_HOPE_COST);
+            assert!(!feature.name.is_empty());
+        }
+        assert!(Class::Seraph.hope_feature().effect.is_heal());
+        assert_eq!(Class::Rogue.hope_feature().name, "Rogue's Dodge");
+    }
+
+    #[cfg(feature = "persistence")]
     #[test]
     fn test_all_classes_serializable() {
         for class in Class::iter() {
@@ -207,6 +497,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "persistence")]
     #[test]
     fn test_all_domains_serializable() {
         for domain in Domain::iter() {
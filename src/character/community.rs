@@ -0,0 +1,62 @@
+//! Character communities - the culture a character grew up in
+
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// The nine communities in Daggerheart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Community {
+    Highborne,
+    Loreborne,
+    Orderborne,
+    Ridgeborne,
+    Seaborne,
+    Slyborne,
+    Underborne,
+    Wanderborne,
+    Wildborne,
+}
+
+impl Community {
+    /// The feature every member of this community gets
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::Community;
+    ///
+    /// assert_eq!(Community::Loreborne.feature(), "Well-Read");
+    /// ```
+    pub fn feature(&self) -> &'static str {
+        match self {
+            Community::Highborne => "Privilege",
+            Community::Loreborne => "Well-Read",
+            Community::Orderborne => "Dedicated",
+            Community::Ridgeborne => "Steady",
+            Community::Seaborne => "Know the Tide",
+            Community::Slyborne => "Scoundrel",
+            Community::Underborne => "Low-Light Living",
+            Community::Wanderborne => "Nomadic Pack",
+            Community::Wildborne => "Lightfoot",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_community_count() {
+        assert_eq!(Community::iter().count(), 9);
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_community_serialization() {
+        let json = serde_json::to_string(&Community::Seaborne).unwrap();
+        assert_eq!(json, r#""Seaborne""#);
+    }
+}
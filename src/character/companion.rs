@@ -0,0 +1,342 @@
+//! Companions - a Beastbound ranger's animal and similar familiars
+//!
+//! A companion keeps its own Hit Points, Stress and attack on its owner's
+//! sheet and levels up alongside them, taking one upgrade per level. In
+//! combat it joins on its owner's side, acts right after them, and rolls
+//! its attacks with their Spellcast trait.
+
+use crate::cards::Range;
+use crate::combat::attack::Attack;
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantBuilder, CombatantId};
+use crate::combat::summons::SummonSource;
+use crate::combat::{HitPoints, Stress};
+use crate::core::dice::{DamageDice, Die};
+use crate::error::EngineError;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// A companion's natural attack
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CompanionAttack {
+    pub name: String,
+    pub damage: Die,
+    pub range: Range,
+}
+
+/// What a companion gains when its owner levels up
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum CompanionUpgrade {
+    /// +2 Evasion
+    Aware,
+    /// +1 Stress slot
+    Resilient,
+    /// Its damage die steps up, d6 to d8 and so on
+    Vicious,
+    /// +1 armor
+    Armored,
+    /// +1 Hit Point
+    Hardy,
+}
+
+/// An animal companion or familiar
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::{Companion, CompanionUpgrade};
+/// use daggerheart_engine::core::dice::Die;
+///
+/// let mut wolf = Companion::new("Fang", "Wolf");
+/// assert_eq!(wolf.attack.damage, Die::D6);
+///
+/// // One upgrade per level past the first
+/// assert!(wolf.upgrade(CompanionUpgrade::Vicious).is_err());
+/// wolf.level_up(2);
+/// wolf.upgrade(CompanionUpgrade::Vicious).unwrap();
+/// assert_eq!(wolf.attack.damage, Die::D8);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Companion {
+    pub name: String,
+    /// What kind of creature it is, e.g. `Wolf`
+    pub species: String,
+    pub level: u8,
+    pub hp: HitPoints,
+    pub stress: Stress,
+    /// Stress it can mark before it's out of the fight
+    pub stress_slots: u8,
+    pub evasion: u8,
+    #[serde(default)]
+    pub armor: u8,
+    pub attack: CompanionAttack,
+    #[serde(default)]
+    pub upgrades: Vec<CompanionUpgrade>,
+}
+
+impl Companion {
+    /// A level 1 companion with 3 HP, 3 Stress slots, Evasion 10 and a d6
+    /// melee attack
+    pub fn new(name: impl Into<String>, species: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            species: species.into(),
+            level: 1,
+            hp: HitPoints::new(3),
+            stress: Stress::new(),
+            stress_slots: 3,
+            evasion: 10,
+            armor: 0,
+            attack: CompanionAttack {
+                name: String::from("Bite"),
+                damage: Die::D6,
+                range: Range::VeryClose,
+            },
+            upgrades: Vec::new(),
+        }
+    }
+
+    /// Set its attack
+    pub fn with_attack(mut self, name: impl Into<String>, damage: Die, range: Range) -> Self {
+        self.attack = CompanionAttack {
+            name: name.into(),
+            damage,
+            range,
+        };
+        self
+    }
+
+    /// Set its Hit Points
+    pub fn with_hp(mut self, hp: u8) -> Self {
+        self.hp = HitPoints::new(hp);
+        self
+    }
+
+    /// Set its Evasion
+    pub fn with_evasion(mut self, evasion: u8) -> Self {
+        self.evasion = evasion;
+        self
+    }
+
+    /// Follow its owner to a new level
+    pub fn level_up(&mut self, level: u8) {
+        self.level = self.level.max(level);
+    }
+
+    /// Upgrades it has earned but not yet taken
+    pub fn upgrades_available(&self) -> u8 {
+        let earned = self.level.saturating_sub(1) as usize;
+        earned.saturating_sub(self.upgrades.len()) as u8
+    }
+
+    /// Take an upgrade earned by levelling up
+    pub fn upgrade(&mut self, upgrade: CompanionUpgrade) -> Result<(), EngineError> {
+        if self.upgrades_available() == 0 {
+            return Err(EngineError::InvalidCharacterState(format!(
+                "{} has no upgrades left at level {}",
+                self.name, self.level
+            )));
+        }
+        self.apply(upgrade);
+        self.upgrades.push(upgrade);
+        Ok(())
+    }
+
+    /// Swap its upgrades for a different set, as when rebuilding its owner
+    ///
+    /// Doesn't check they were earned; see [`Companion::upgrades_available`].
+    pub fn respec(&mut self, upgrades: &[CompanionUpgrade]) {
+        for upgrade in core::mem::take(&mut self.upgrades) {
+            self.undo(upgrade);
+        }
+        for &upgrade in upgrades {
+            self.apply(upgrade);
+        }
+        self.upgrades = upgrades.to_vec();
+    }
+
+    fn apply(&mut self, upgrade: CompanionUpgrade) {
+        match upgrade {
+            CompanionUpgrade::Aware => self.evasion = self.evasion.saturating_add(2),
+            CompanionUpgrade::Resilient => self.stress_slots = self.stress_slots.saturating_add(1),
+            CompanionUpgrade::Vicious => self.attack.damage = step_up(self.attack.damage),
+            CompanionUpgrade::Armored => self.armor = self.armor.saturating_add(1),
+            CompanionUpgrade::Hardy => {
+                self.hp.maximum = self.hp.maximum.saturating_add(1);
+                self.hp.current = self.hp.current.saturating_add(1);
+            }
+        }
+    }
+
+    fn undo(&mut self, upgrade: CompanionUpgrade) {
+        match upgrade {
+            CompanionUpgrade::Aware => self.evasion = self.evasion.saturating_sub(2),
+            CompanionUpgrade::Resilient => self.stress_slots = self.stress_slots.saturating_sub(1),
+            CompanionUpgrade::Vicious => self.attack.damage = step_down(self.attack.damage),
+            CompanionUpgrade::Armored => self.armor = self.armor.saturating_sub(1),
+            CompanionUpgrade::Hardy => {
+                self.hp.maximum = self.hp.maximum.saturating_sub(1).max(1);
+                self.hp.current = self.hp.current.min(self.hp.maximum);
+            }
+        }
+    }
+
+    /// Whether it's out of the fight, from lost HP or a full Stress track
+    pub fn is_out(&self) -> bool {
+        !self.hp.is_alive() || self.stress.current >= self.stress_slots
+    }
+
+    /// Clear its Stress and heal it fully, as on a rest
+    pub fn recover(&mut self) {
+        self.stress.clear();
+        self.hp.current = self.hp.maximum;
+    }
+
+    /// Its attack, rolled with its owner's Spellcast trait
+    pub fn attack_roll(&self, owner: &Combatant) -> Result<Attack, EngineError> {
+        Ok(Attack::new(owner.spellcast_modifier()?).with_range(self.attack.range))
+    }
+
+    /// Its attack's damage dice
+    pub fn damage(&self) -> DamageDice {
+        DamageDice::new(vec![self.attack.damage])
+    }
+
+    /// A combatant for it, carrying its current HP and Stress
+    pub fn to_combatant(&self) -> Combatant {
+        let mut combatant = CombatantBuilder::adversary(self.name.clone())
+            .with_level(self.level)
+            .with_hp(self.hp.maximum)
+            .with_evasion(self.evasion)
+            .with_armor(self.armor)
+            .build()
+            .expect("companions always have HP and Evasion");
+        combatant.hp = self.hp.clone();
+        combatant.stress = self.stress.clone();
+        combatant
+    }
+
+    /// Carry HP and Stress back from the fight
+    pub fn update_from(&mut self, combatant: &Combatant) {
+        self.hp.current = combatant.hp.current.min(self.hp.maximum);
+        self.stress = combatant.stress.clone();
+    }
+}
+
+fn step_up(die: Die) -> Die {
+    match die {
+        Die::D4 => Die::D6,
+        Die::D6 => Die::D8,
+        Die::D8 => Die::D10,
+        Die::D10 => Die::D12,
+        Die::D12 | Die::D20 => die,
+    }
+}
+
+fn step_down(die: Die) -> Die {
+    match die {
+        Die::D6 => Die::D4,
+        Die::D8 => Die::D6,
+        Die::D10 => Die::D8,
+        Die::D12 => Die::D10,
+        Die::D4 | Die::D20 => die,
+    }
+}
+
+impl CombatEncounter {
+    /// Bring a character's companion into the fight under their control
+    ///
+    /// It fights on its owner's side and acts right after them. When it
+    /// drops it stays on the field, unlike a summon.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class, Companion};
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let ranger = encounter.add_combatant(
+    ///     CombatantBuilder::player("Ash", Class::Ranger, Ancestry::Human)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// let fang = encounter.add_companion(ranger, &Companion::new("Fang", "Wolf")).unwrap();
+    /// let wolf = encounter.get(fang).unwrap();
+    /// assert!(wolf.is_player());
+    /// assert_eq!(wolf.controller(), Some(ranger));
+    /// ```
+    pub fn add_companion(
+        &mut self,
+        owner: CombatantId,
+        companion: &Companion,
+    ) -> Result<CombatantId, EngineError> {
+        self.join_under(
+            owner,
+            companion.to_combatant(),
+            SummonSource::Companion { owner },
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Class, Subclass};
+    use crate::test_support::player;
+
+    #[test]
+    fn test_upgrades_follow_levels() {
+        let mut fang = Companion::new("Fang", "Wolf");
+        fang.level_up(3);
+        assert_eq!(fang.upgrades_available(), 2);
+        fang.upgrade(CompanionUpgrade::Aware).unwrap();
+        fang.upgrade(CompanionUpgrade::Hardy).unwrap();
+        assert!(fang.upgrade(CompanionUpgrade::Resilient).is_err());
+        assert_eq!(fang.evasion, 12);
+        assert_eq!(fang.hp.maximum, 4);
+
+        // Levels never go backwards
+        fang.level_up(2);
+        assert_eq!(fang.level, 3);
+    }
+
+    #[test]
+    fn test_companion_stays_down_and_carries_damage_back() {
+        let mut encounter = CombatEncounter::new(5);
+        let ranger = encounter.add_combatant(
+            player("Ash", Class::Ranger, Ancestry::Human)
+                .build()
+                .unwrap(),
+        );
+        let mut companion = Companion::new("Fang", "Wolf");
+        let fang = encounter.add_companion(ranger, &companion).unwrap();
+
+        encounter.apply_damage(fang, 30).unwrap();
+        let downed = encounter.get(fang).unwrap();
+        assert!(!downed.is_alive());
+        assert_eq!(encounter.summons_of(ranger), vec![fang]);
+
+        companion.update_from(downed);
+        assert!(companion.is_out());
+        companion.recover();
+        assert!(!companion.is_out());
+    }
+
+    #[test]
+    fn test_attacks_use_owner_spellcast() {
+        let ranger = player("Ash", Class::Ranger, Ancestry::Human)
+            .with_subclass(Subclass::Beastbound)
+            .build()
+            .unwrap();
+        let fang = Companion::new("Fang", "Wolf");
+        let attack = fang.attack_roll(&ranger).unwrap();
+        assert_eq!(attack.modifier, ranger.spellcast_modifier().unwrap());
+        assert!(attack.is_melee());
+        assert_eq!(fang.damage(), DamageDice::d6(1));
+    }
+}
@@ -0,0 +1,48 @@
+//! Experiences - a character's background specialties
+
+use alloc::string::String;
+use serde::{Deserialize, Serialize};
+
+/// A named specialty that adds to rolls it applies to
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::Experience;
+///
+/// let experience = Experience::new("Bounty Hunter", 2);
+/// assert_eq!(experience.name, "Bounty Hunter");
+/// assert_eq!(experience.bonus, 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Experience {
+    pub name: String,
+    pub bonus: i8,
+}
+
+impl Experience {
+    /// Create an experience
+    pub fn new(name: impl Into<String>, bonus: i8) -> Self {
+        Self {
+            name: name.into(),
+            bonus,
+        }
+    }
+}
+
+#[cfg(all(test, feature = "persistence"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_experience_serialization() {
+        let experience = Experience::new("Sailor", 2);
+        let json = serde_json::to_string(&experience).unwrap();
+        assert_eq!(json, r#"{"name":"Sailor","bonus":2}"#);
+        assert_eq!(
+            serde_json::from_str::<Experience>(&json).unwrap(),
+            experience
+        );
+    }
+}
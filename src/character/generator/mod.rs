@@ -0,0 +1,267 @@
+//! Random character generation for quick NPCs and playtest parties
+//!
+//! Generated characters are always valid: attributes use the standard
+//! spread arranged to suit the class, cards come from the class domains
+//! and equipment from the class's suggested kit. Names come from the
+//! ancestry tables in [`names`].
+
+pub mod names;
+
+pub use names::NameGenerator;
+
+use crate::character::{Ancestry, Attributes, CharacterSheet, Class, Community};
+use crate::combat::simulation::CombatantBuilder;
+use crate::content::ContentRegistry;
+use crate::error::EngineError;
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand::Rng;
+use strum::IntoEnumIterator;
+
+/// Domain cards every character starts with
+const STARTING_CARDS: usize = 2;
+
+/// Generates random, rules-valid characters
+///
+/// Anything not fixed with a `with_*` method is picked at random.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::generator::CharacterGenerator;
+/// use daggerheart_engine::character::Class;
+///
+/// let sheet = CharacterGenerator::new()
+///     .with_class(Class::Wizard)
+///     .with_level(3)
+///     .generate()
+///     .unwrap();
+///
+/// assert_eq!(sheet.combatant.class(), Some(Class::Wizard));
+/// assert_eq!(sheet.progress.level, 3);
+/// assert_eq!(sheet.combatant.attributes().unwrap().knowledge, 2);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CharacterGenerator {
+    class: Option<Class>,
+    ancestry: Option<Ancestry>,
+    level: u8,
+    content: ContentRegistry,
+}
+
+impl Default for CharacterGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CharacterGenerator {
+    /// Generate level 1 characters using the built-in cards
+    pub fn new() -> Self {
+        Self {
+            class: None,
+            ancestry: None,
+            level: 1,
+            content: ContentRegistry::builtin(),
+        }
+    }
+
+    /// Always generate this class
+    pub fn with_class(mut self, class: Class) -> Self {
+        self.class = Some(class);
+        self
+    }
+
+    /// Always generate this ancestry
+    pub fn with_ancestry(mut self, ancestry: Ancestry) -> Self {
+        self.ancestry = Some(ancestry);
+        self
+    }
+
+    /// Set the level (defaults to 1)
+    pub fn with_level(mut self, level: u8) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Draw domain cards from this registry instead of the built-in set
+    pub fn with_content(mut self, content: ContentRegistry) -> Self {
+        self.content = content;
+        self
+    }
+
+    /// Generate a character
+    pub fn generate(&self) -> Result<CharacterSheet, EngineError> {
+        let mut rng = rand::thread_rng();
+        self.generate_with_rng(&mut rng)
+    }
+
+    /// Generate a character with a specific RNG (for testing/seeding)
+    ///
+    /// Fails with `InvalidLevel` outside levels 1-10.
+    pub fn generate_with_rng<R: Rng>(&self, rng: &mut R) -> Result<CharacterSheet, EngineError> {
+        let class = self
+            .class
+            .unwrap_or_else(|| Class::iter().choose(rng).expect("there are classes"));
+        let ancestry = self
+            .ancestry
+            .unwrap_or_else(|| Ancestry::iter().choose(rng).expect("there are ancestries"));
+        let subclass = *class
+            .subclasses()
+            .choose(rng)
+            .expect("classes have subclasses");
+        let community = Community::iter()
+            .choose(rng)
+            .expect("there are communities");
+        let name = NameGenerator::from_rng(&mut *rng).name(ancestry);
+
+        let mut builder = CombatantBuilder::player(name, class, ancestry)
+            .with_level(self.level)
+            .with_subclass(subclass)
+            .with_community(community)
+            .with_attributes(random_attributes(class, rng));
+        for item in class.suggested_equipment() {
+            builder = builder.with_equipment(*item);
+        }
+
+        let mut sheet = CharacterSheet::new(builder.build()?);
+        for card in self.random_cards(class, rng) {
+            sheet.progress.add_card(card);
+        }
+        Ok(sheet)
+    }
+
+    /// Two level 1 cards, then one more for each level gained
+    fn random_cards<R: Rng>(&self, class: Class, rng: &mut R) -> Vec<String> {
+        let (first, second) = class.domains();
+        let mut cards: Vec<String> = Vec::new();
+
+        for level in 1..=self.level {
+            let picks = if level == 1 { STARTING_CARDS } else { 1 };
+            let mut pool: Vec<&str> = [first, second]
+                .into_iter()
+                .flat_map(|domain| self.content.find_cards(Some(domain), Some(level)))
+                .map(|card| card.id.as_str())
+                .filter(|id| !cards.iter().any(|owned| owned == id))
+                .collect();
+            pool.shuffle(rng);
+            cards.extend(pool.into_iter().take(picks).map(String::from));
+        }
+        cards
+    }
+}
+
+/// The class's suggested spread, with the non-primary traits shuffled
+///
+/// The +2 always lands on the trait the class relies on most.
+pub fn random_attributes<R: Rng>(class: Class, rng: &mut R) -> Attributes {
+    let mut modifiers = suggested_modifiers(class);
+    let primary = modifiers
+        .iter()
+        .position(|&m| m == 2)
+        .expect("suggested spreads have a +2");
+
+    let mut rest: Vec<i8> = modifiers
+        .iter()
+        .enumerate()
+        .filter(|&(i, _)| i != primary)
+        .map(|(_, &m)| m)
+        .collect();
+    rest.shuffle(rng);
+    for (i, modifier) in (0..6).filter(|&i| i != primary).zip(rest) {
+        modifiers[i] = modifier;
+    }
+
+    Attributes::from_array(modifiers).expect("shuffling keeps the standard spread")
+}
+
+/// The SRD's suggested trait spread for each class
+///
+/// In `Attributes::from_array` order: Agility, Strength, Finesse,
+/// Instinct, Presence, Knowledge.
+fn suggested_modifiers(class: Class) -> [i8; 6] {
+    match class {
+        Class::Bard => [0, -1, 1, 0, 2, 1],
+        Class::Druid => [1, 0, 1, 2, -1, 0],
+        Class::Guardian => [1, 2, -1, 0, 1, 0],
+        Class::Ranger => [2, 0, 1, 1, -1, 0],
+        Class::Rogue => [1, -1, 2, 0, 1, 0],
+        Class::Seraph => [0, 2, 0, 1, 1, -1],
+        Class::Sorcerer => [0, -1, 1, 2, 1, 0],
+        Class::Warrior => [2, 1, 0, 1, -1, 0],
+        Class::Wizard => [-1, 0, 0, 1, 1, 2],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_generated_characters_are_valid() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let generator = CharacterGenerator::new();
+
+        for _ in 0..50 {
+            let sheet = generator.generate_with_rng(&mut rng).unwrap();
+            let combatant = &sheet.combatant;
+            let class = combatant.class().unwrap();
+
+            assert!(combatant.attributes().unwrap().validate().is_ok());
+            assert_eq!(combatant.subclass().unwrap().class(), class);
+            assert!(combatant.community().is_some());
+            assert_eq!(sheet.progress.available_cards.len(), STARTING_CARDS);
+            for id in &sheet.progress.available_cards {
+                let card = ContentRegistry::builtin().card(id).cloned().unwrap();
+                assert!(class.can_use_domain(card.domain));
+            }
+        }
+    }
+
+    #[test]
+    fn test_primary_trait_is_kept() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for class in Class::iter() {
+            let primary = suggested_modifiers(class)
+                .iter()
+                .position(|&m| m == 2)
+                .unwrap();
+            for _ in 0..10 {
+                let attributes = random_attributes(class, &mut rng);
+                let modifiers = [
+                    attributes.agility,
+                    attributes.strength,
+                    attributes.finesse,
+                    attributes.instinct,
+                    attributes.presence,
+                    attributes.knowledge,
+                ];
+                assert_eq!(modifiers[primary], 2, "{}", class);
+            }
+        }
+    }
+
+    #[test]
+    fn test_seeded_generation_repeats() {
+        let generator = CharacterGenerator::new().with_ancestry(Ancestry::Dwarf);
+        let a = generator
+            .generate_with_rng(&mut StdRng::seed_from_u64(3))
+            .unwrap();
+        let b = generator
+            .generate_with_rng(&mut StdRng::seed_from_u64(3))
+            .unwrap();
+
+        assert_eq!(a, b);
+        assert_eq!(a.combatant.ancestry(), Some(Ancestry::Dwarf));
+    }
+
+    #[test]
+    fn test_invalid_level() {
+        let result = CharacterGenerator::new().with_level(11).generate();
+        assert!(matches!(
+            result,
+            Err(EngineError::InvalidLevel { level: 11 })
+        ));
+    }
+}
@@ -0,0 +1,243 @@
+//! Names flavored by ancestry
+//!
+//! Each ancestry has its own syllables and family names, so a dwarf
+//! sounds like a dwarf and a faerie like a faerie. Given names join a
+//! random prefix and suffix; most ancestries add a family name.
+
+use crate::character::Ancestry;
+use rand::rngs::StdRng;
+use rand::seq::{IteratorRandom, SliceRandom};
+use rand::{Rng, SeedableRng};
+use strum::IntoEnumIterator;
+
+/// The pieces names are built from for one ancestry
+struct NameTable {
+    prefixes: &'static [&'static str],
+    suffixes: &'static [&'static str],
+    /// Family names, or empty for ancestries that go by one name
+    family: &'static [&'static str],
+}
+
+fn table(ancestry: Ancestry) -> NameTable {
+    match ancestry {
+        Ancestry::Clank => NameTable {
+            prefixes: &["Cog", "Gear", "Bolt", "Ratch", "Tin", "Brass", "Pist"],
+            suffixes: &["ley", "wick", "on", "et", "sworth", "ix"],
+            family: &["Mk. II", "Mk. IV", "of the Foundry", "Unit Seven"],
+        },
+        Ancestry::Daemon => NameTable {
+            prefixes: &["Mal", "Zar", "Vex", "Ash", "Nix", "Kael", "Ruth"],
+            suffixes: &["ith", "ora", "ek", "iel", "azar", "is"],
+            family: &["Emberlash", "Nightbrand", "Vael", "Cinderveil"],
+        },
+        Ancestry::Drakona => NameTable {
+            prefixes: &["Vor", "Kryth", "Sar", "Dra", "Ith", "Bal", "Zyn"],
+            suffixes: &["athix", "ion", "ax", "essa", "or", "yth"],
+            family: &["Scaleborn", "Ashwing", "Stormclaw", "Goldhide"],
+        },
+        Ancestry::Dwarf => NameTable {
+            prefixes: &["Bal", "Dur", "Thor", "Grim", "Bro", "Kil", "Dag", "Hel"],
+            suffixes: &["in", "ek", "grim", "dal", "rin", "nar", "ga", "dis"],
+            family: &[
+                "Ironfist",
+                "Stonebeard",
+                "Deepdelver",
+                "Hammerfall",
+                "Coppervein",
+            ],
+        },
+        Ancestry::Faerie => NameTable {
+            prefixes: &["Lil", "Pip", "Fae", "Thistle", "Wisp", "Bri", "Dew"],
+            suffixes: &["wing", "ette", "lia", "bloom", "sy", "ara"],
+            family: &[],
+        },
+        Ancestry::Faun => NameTable {
+            prefixes: &["Bram", "Fen", "Hol", "Ash", "Lark", "Rue", "Cor"],
+            suffixes: &["ble", "wyn", "ly", "tor", "an", "ith"],
+            family: &["Mossback", "Greenhoof", "Thornfield", "Brookwalker"],
+        },
+        Ancestry::Fungril => NameTable {
+            prefixes: &["Spor", "Myc", "Lich", "Mor", "Truf", "Puff"],
+            suffixes: &["elle", "on", "ulus", "a", "ix", "ling"],
+            family: &["of the Deep Network", "Rootkin", "Undergrowth"],
+        },
+        Ancestry::Galapa => NameTable {
+            prefixes: &["Tor", "Shel", "Oka", "Mar", "Tam", "Bas"],
+            suffixes: &["tuga", "do", "lan", "ok", "wen", "ra"],
+            family: &["Slowtide", "Reefshell", "Deepcurrent", "Sandback"],
+        },
+        Ancestry::Giant => NameTable {
+            prefixes: &["Gor", "Ulf", "Hro", "Bjar", "Skal", "Tor", "Ymr"],
+            suffixes: &["mund", "ga", "thor", "dis", "vald", "rik"],
+            family: &["Mountainsong", "Cloudreach", "Stonestride", "Frostmantle"],
+        },
+        Ancestry::Goblin => NameTable {
+            prefixes: &["Snik", "Grib", "Nox", "Zik", "Mog", "Skrat", "Tup"],
+            suffixes: &["le", "bit", "zz", "nok", "ka", "wig"],
+            family: &["Quickfingers", "Mudwhistle", "Sparkpocket", "Rattlebones"],
+        },
+        Ancestry::Halfling => NameTable {
+            prefixes: &["Mer", "Pip", "Ros", "Bil", "Tam", "Cor", "Lil"],
+            suffixes: &["ry", "pin", "ie", "bo", "wise", "a"],
+            family: &[
+                "Goodbarrel",
+                "Underhill",
+                "Tealeaf",
+                "Applebrook",
+                "Brandyfoot",
+            ],
+        },
+        Ancestry::Human => NameTable {
+            prefixes: &["Al", "Bren", "Cor", "El", "Mar", "Ros", "Tam", "Wil"],
+            suffixes: &["dric", "na", "win", "ara", "cus", "alind", "sin", "la"],
+            family: &[
+                "Ashford",
+                "Blackwood",
+                "Marsh",
+                "Thatcher",
+                "Vance",
+                "Holloway",
+            ],
+        },
+        Ancestry::Inferis => NameTable {
+            prefixes: &["Ign", "Pyr", "Sol", "Cind", "Brim", "Scor"],
+            suffixes: &["is", "ra", "eus", "ix", "ana", "oth"],
+            family: &["Hellsworn", "Brimstone", "Ashenheart", "Flamecrown"],
+        },
+        Ancestry::Katari => NameTable {
+            prefixes: &["Mir", "Shar", "Pur", "Tash", "Zhi", "Raa", "Kit"],
+            suffixes: &["ra", "iel", "rin", "ka", "ro", "sha"],
+            family: &["Swiftpaw", "Silverwhisker", "Duskstalker", "Softstep"],
+        },
+        Ancestry::Orc => NameTable {
+            prefixes: &["Grom", "Thra", "Ursk", "Kar", "Mok", "Gash", "Vol"],
+            suffixes: &["ak", "ash", "ga", "nar", "uk", "osh"],
+            family: &["Bloodtusk", "Skullsplitter", "Ironhide", "Ashjaw"],
+        },
+        Ancestry::Ribbet => NameTable {
+            prefixes: &["Rib", "Crok", "Bul", "Pol", "Lil", "Gub"],
+            suffixes: &["bit", "ley", "bo", "pad", "wog", "ly"],
+            family: &["Lilypad", "Marshleaper", "Bogsinger", "Reedhopper"],
+        },
+        Ancestry::Simiah => NameTable {
+            prefixes: &["Oo", "Bon", "Kal", "Mak", "Ta", "Zuu"],
+            suffixes: &["ko", "go", "ri", "mba", "ki", "la"],
+            family: &["Treeswing", "Canopy", "Vinegrasp", "Highbranch"],
+        },
+    }
+}
+
+/// Generates ancestry-flavored names
+///
+/// Seed it to get the same names every time.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::generator::names::NameGenerator;
+/// use daggerheart_engine::character::Ancestry;
+///
+/// let mut names = NameGenerator::seeded(42);
+/// let dwarves = names.names(Ancestry::Dwarf, 3);
+/// assert_eq!(dwarves.len(), 3);
+///
+/// // Same seed, same names
+/// assert_eq!(NameGenerator::seeded(42).names(Ancestry::Dwarf, 3), dwarves);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NameGenerator<R = StdRng> {
+    rng: R,
+}
+
+impl NameGenerator {
+    /// A generator seeded from the system's entropy
+    pub fn new() -> Self {
+        Self::from_rng(StdRng::from_entropy())
+    }
+
+    /// A generator that always produces the same names for a seed
+    pub fn seeded(seed: u64) -> Self {
+        Self::from_rng(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Default for NameGenerator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: Rng> NameGenerator<R> {
+    /// Draw names from an existing RNG
+    pub fn from_rng(rng: R) -> Self {
+        Self { rng }
+    }
+
+    /// A name for a member of this ancestry
+    pub fn name(&mut self, ancestry: Ancestry) -> String {
+        let table = table(ancestry);
+        let prefix = table
+            .prefixes
+            .choose(&mut self.rng)
+            .expect("tables have prefixes");
+        let suffix = table
+            .suffixes
+            .choose(&mut self.rng)
+            .expect("tables have suffixes");
+
+        match table.family.choose(&mut self.rng) {
+            Some(family) => format!("{}{} {}", prefix, suffix, family),
+            None => format!("{}{}", prefix, suffix),
+        }
+    }
+
+    /// Several names for members of this ancestry
+    pub fn names(&mut self, ancestry: Ancestry, count: usize) -> Vec<String> {
+        (0..count).map(|_| self.name(ancestry)).collect()
+    }
+
+    /// A name for a random ancestry, with the ancestry picked
+    pub fn any(&mut self) -> (Ancestry, String) {
+        let ancestry = Ancestry::iter()
+            .choose(&mut self.rng)
+            .expect("there are ancestries");
+        (ancestry, self.name(ancestry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_ancestry_has_names() {
+        let mut names = NameGenerator::seeded(1);
+        for ancestry in Ancestry::iter() {
+            let name = names.name(ancestry);
+            assert!(!name.is_empty(), "{} has no names", ancestry);
+            assert!(name.chars().next().unwrap().is_uppercase());
+        }
+    }
+
+    #[test]
+    fn test_names_use_ancestry_tables() {
+        let mut names = NameGenerator::seeded(9);
+        let dwarf = table(Ancestry::Dwarf);
+        for name in names.names(Ancestry::Dwarf, 20) {
+            assert!(dwarf.prefixes.iter().any(|p| name.starts_with(p)));
+            assert!(dwarf.family.iter().any(|f| name.ends_with(f)));
+        }
+
+        // Faeries go by a single name
+        for name in names.names(Ancestry::Faerie, 10) {
+            assert!(!name.contains(' '));
+        }
+    }
+
+    #[test]
+    fn test_seeds_differ() {
+        let a = NameGenerator::seeded(1).names(Ancestry::Human, 5);
+        let b = NameGenerator::seeded(2).names(Ancestry::Human, 5);
+        assert_ne!(a, b);
+    }
+}
@@ -2,19 +2,39 @@
 //!
 //! This module handles:
 //! - Character attributes (Agility, Strength, Finesse, etc.)
-//! - Classes and ancestries
+//! - Classes, subclasses, ancestries and communities
+//! - Experiences
 //! - Character progression and leveling
 //! - Foundation abilities
+//! - Character sheets, printable as Markdown or HTML
+//! - Companions that level with their owner
+//! - Rebuilding a character between sessions
+//! - Random character generation
 
 pub mod ancestry;
 pub mod attributes;
 pub mod classes;
+pub mod community;
+#[cfg(feature = "std")]
+pub mod companion;
+pub mod experience;
+#[cfg(feature = "content")]
+pub mod generator;
 pub mod progression;
+#[cfg(feature = "std")]
+pub mod rebuild;
+#[cfg(feature = "content")]
+mod render;
+#[cfg(feature = "std")]
+pub mod sheet;
 
-pub use ancestry::Ancestry;
+pub use ancestry::{Ancestry, AncestryEffect, AncestryFeature};
 pub use attributes::{AttributeType, Attributes};
-pub use classes::{Class, Domain};
+pub use classes::{Class, ClassHopeFeature, Domain, Subclass, CLASS_FEATURE_HOPE_COST};
+pub use community::Community;
+#[cfg(feature = "std")]
+pub use companion::{Companion, CompanionAttack, CompanionUpgrade};
+pub use experience::Experience;
 pub use progression::CharacterProgress;
-
-// TODO: Add submodules
-// pub mod sheet;
+#[cfg(feature = "std")]
+pub use sheet::CharacterSheet;
@@ -1,10 +1,40 @@
 //! Character progression - leveling and advancement
 
-use crate::error::EngineError;
+use crate::error::{EngineError, Resource};
+#[cfg(feature = "persistence")]
+use crate::storage::{self, Persist};
+use alloc::string::String;
+use alloc::vec::Vec;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "persistence")]
+use std::path::Path;
+
+/// Get the tier (1-4) for a character level
+///
+/// Level 1 is tier 1, levels 2-4 are tier 2, levels 5-7 are tier 3,
+/// and levels 8-10 are tier 4.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::progression::tier_for_level;
+///
+/// assert_eq!(tier_for_level(1), 1);
+/// assert_eq!(tier_for_level(4), 2);
+/// assert_eq!(tier_for_level(10), 4);
+/// ```
+pub fn tier_for_level(level: u8) -> u8 {
+    match level {
+        0..=1 => 1,
+        2..=4 => 2,
+        5..=7 => 3,
+        _ => 4,
+    }
+}
 
 /// Character progression tracker
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CharacterProgress {
     pub level: u8,
     pub experience: u32,
@@ -12,6 +42,7 @@ pub struct CharacterProgress {
 }
 
 impl CharacterProgress {
+    #[cfg(feature = "persistence")]
     /// Save character progress to a JSON file
     ///
     /// # Examples
@@ -23,13 +54,11 @@ impl CharacterProgress {
     /// progress.add_experience(150);
     /// progress.save_to_file("progress.json").unwrap();
     /// ```
-    pub fn save_to_file(&self, path: &str) -> Result<(), std::io::Error> {
-        let json = serde_json::to_string_pretty(self)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-        std::fs::write(path, json)?;
-        Ok(())
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), EngineError> {
+        storage::save_file(self, path)
     }
 
+    #[cfg(feature = "persistence")]
     /// Load character progress from a JSON file
     ///
     /// # Examples
@@ -40,10 +69,8 @@ impl CharacterProgress {
     /// let progress = CharacterProgress::load_from_file("progress.json").unwrap();
     /// println!("Level: {}", progress.level);
     /// ```
-    pub fn load_from_file(path: &str) -> Result<Self, std::io::Error> {
-        let json = std::fs::read_to_string(path)?;
-        serde_json::from_str(&json)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, EngineError> {
+        storage::load_file(path)
     }
 
     /// Create a new character at level 1
@@ -80,6 +107,11 @@ impl CharacterProgress {
         (self.level as u32) * 100
     }
 
+    /// Get the character's current tier
+    pub fn tier(&self) -> u8 {
+        tier_for_level(self.level)
+    }
+
     /// Add experience points
     ///
     /// # Examples
@@ -127,13 +159,18 @@ impl CharacterProgress {
     /// assert_eq!(progress.experience, 50); // 150 - 100 = 50 left over
     /// ```
     pub fn level_up(&mut self) -> Result<(), EngineError> {
-        if !self.can_level_up() {
-            return Err(EngineError::InvalidCharacterState(
-                "Not enough XP to level up".to_string(),
-            ));
+        let cost = self.xp_for_next_level();
+        if self.level >= 10 {
+            return Err(EngineError::MaxLevel { level: self.level });
+        }
+        if self.experience < cost {
+            return Err(EngineError::InsufficientResource {
+                resource: Resource::Experience,
+                have: self.experience,
+                need: cost,
+            });
         }
 
-        let cost = self.xp_for_next_level();
         self.experience -= cost;
         self.level += 1;
 
@@ -157,6 +194,9 @@ impl Default for CharacterProgress {
     }
 }
 
+#[cfg(feature = "persistence")]
+impl Persist for CharacterProgress {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -183,6 +223,17 @@ mod tests {
         assert_eq!(progress.xp_for_next_level(), 500); // Level 5 -> 6
     }
 
+    #[test]
+    fn test_tier_for_level() {
+        assert_eq!(tier_for_level(1), 1);
+        assert_eq!(tier_for_level(2), 2);
+        assert_eq!(tier_for_level(4), 2);
+        assert_eq!(tier_for_level(5), 3);
+        assert_eq!(tier_for_level(7), 3);
+        assert_eq!(tier_for_level(8), 4);
+        assert_eq!(tier_for_level(10), 4);
+    }
+
     #[test]
     fn test_add_experience() {
         let mut progress = CharacterProgress::new();
@@ -222,7 +273,14 @@ mod tests {
         let mut progress = CharacterProgress::new();
         progress.add_experience(50);
 
-        assert!(progress.level_up().is_err());
+        assert!(matches!(
+            progress.level_up(),
+            Err(EngineError::InsufficientResource {
+                resource: Resource::Experience,
+                have: 50,
+                need: 100
+            })
+        ));
         assert_eq!(progress.level, 1); // Unchanged
     }
 
@@ -233,7 +291,10 @@ mod tests {
         progress.add_experience(1000);
 
         assert!(!progress.can_level_up());
-        assert!(progress.level_up().is_err());
+        assert!(matches!(
+            progress.level_up(),
+            Err(EngineError::MaxLevel { level: 10 })
+        ));
     }
 
     #[test]
@@ -274,6 +335,7 @@ mod tests {
         assert!(!progress.has_card("fireball"));
     }
 
+    #[cfg(feature = "persistence")]
     #[test]
     fn test_progression_serialization() {
         let mut progress = CharacterProgress::new();
@@ -0,0 +1,441 @@
+//! Rebuilding a character between sessions
+//!
+//! A player can re-pick their traits, domain cards, Experiences and
+//! companion upgrades, or swap to their class's other subclass. A
+//! [`Rebuild`] collects the new picks, lists what changed for the GM to
+//! approve, and checks the result is still a legal character of its
+//! level before handing back the new sheet.
+
+use crate::character::companion::CompanionUpgrade;
+use crate::character::progression::tier_for_level;
+use crate::character::{AttributeType, Attributes, CharacterSheet, Experience, Subclass};
+use crate::combat::simulation::CombatantKind;
+#[cfg(feature = "content")]
+use crate::content::ContentRegistry;
+use crate::error::EngineError;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use strum::IntoEnumIterator;
+
+/// One difference between a sheet and its rebuild
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum SheetChange {
+    Attribute {
+        attribute: AttributeType,
+        from: i8,
+        to: i8,
+    },
+    Subclass {
+        from: Option<Subclass>,
+        to: Option<Subclass>,
+    },
+    CardAdded {
+        card: String,
+    },
+    CardRemoved {
+        card: String,
+    },
+    ExperienceAdded {
+        experience: Experience,
+    },
+    ExperienceRemoved {
+        experience: Experience,
+    },
+    CompanionUpgrades {
+        from: Vec<CompanionUpgrade>,
+        to: Vec<CompanionUpgrade>,
+    },
+}
+
+impl fmt::Display for SheetChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let subclass = |subclass: &Option<Subclass>| match subclass {
+            Some(subclass) => subclass.to_string(),
+            None => String::from("none"),
+        };
+        let upgrades = |upgrades: &[CompanionUpgrade]| {
+            let names: Vec<String> = upgrades.iter().map(|u| u.to_string()).collect();
+            if names.is_empty() {
+                String::from("none")
+            } else {
+                names.join(", ")
+            }
+        };
+        match self {
+            SheetChange::Attribute {
+                attribute,
+                from,
+                to,
+            } => write!(f, "{} {:+} → {:+}", attribute, from, to),
+            SheetChange::Subclass { from, to } => {
+                write!(f, "Subclass {} → {}", subclass(from), subclass(to))
+            }
+            SheetChange::CardAdded { card } => write!(f, "+ card {}", card),
+            SheetChange::CardRemoved { card } => write!(f, "- card {}", card),
+            SheetChange::ExperienceAdded { experience } => {
+                write!(f, "+ {} {:+}", experience.name, experience.bonus)
+            }
+            SheetChange::ExperienceRemoved { experience } => {
+                write!(f, "- {} {:+}", experience.name, experience.bonus)
+            }
+            SheetChange::CompanionUpgrades { from, to } => write!(
+                f,
+                "Companion upgrades {} → {}",
+                upgrades(from),
+                upgrades(to)
+            ),
+        }
+    }
+}
+
+/// A rebuilt sheet and what changed, ready for the GM to approve
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rebuilt {
+    pub sheet: CharacterSheet,
+    pub changes: Vec<SheetChange>,
+}
+
+/// New picks for a character, checked before they're applied
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::rebuild::SheetChange;
+/// use daggerheart_engine::character::{
+///     Ancestry, Attributes, CharacterSheet, Class, Subclass,
+/// };
+/// use daggerheart_engine::combat::CombatantBuilder;
+///
+/// let sheet = CharacterSheet::new(
+///     CombatantBuilder::player("Ash", Class::Ranger, Ancestry::Human)
+///         .with_subclass(Subclass::Beastbound)
+///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+///         .build()
+///         .unwrap(),
+/// );
+///
+/// let rebuilt = sheet
+///     .rebuild()
+///     .with_subclass(Subclass::Wayfinder)
+///     .finish()
+///     .unwrap();
+/// assert_eq!(rebuilt.sheet.combatant.subclass(), Some(Subclass::Wayfinder));
+/// assert_eq!(
+///     rebuilt.changes[0].to_string(),
+///     "Subclass Beastbound → Wayfinder"
+/// );
+///
+/// // A Wizard subclass isn't a legal pick for a Ranger
+/// assert!(sheet.rebuild().with_subclass(Subclass::SchoolOfWar).finish().is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct Rebuild {
+    original: CharacterSheet,
+    sheet: CharacterSheet,
+}
+
+impl CharacterSheet {
+    /// Start re-picking this character's choices
+    pub fn rebuild(&self) -> Rebuild {
+        Rebuild {
+            original: self.clone(),
+            sheet: self.clone(),
+        }
+    }
+}
+
+impl Rebuild {
+    /// Reassign the character's traits
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        if let CombatantKind::Player(data) = &mut self.sheet.combatant.kind {
+            data.attributes = attributes;
+        }
+        self
+    }
+
+    /// Swap subclass
+    pub fn with_subclass(mut self, subclass: Subclass) -> Self {
+        if let CombatantKind::Player(data) = &mut self.sheet.combatant.kind {
+            data.subclass = Some(subclass);
+        }
+        self
+    }
+
+    /// Replace the character's domain cards
+    pub fn with_cards<I, S>(mut self, cards: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.sheet.progress.available_cards = cards.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Replace the character's Experiences
+    pub fn with_experiences(mut self, experiences: Vec<Experience>) -> Self {
+        self.sheet.combatant.experiences = experiences.into();
+        self
+    }
+
+    /// Re-pick the companion's upgrades
+    pub fn with_companion_upgrades(mut self, upgrades: &[CompanionUpgrade]) -> Self {
+        if let Some(companion) = &mut self.sheet.companion {
+            companion.respec(upgrades);
+        }
+        self
+    }
+
+    /// What would change, in sheet order
+    pub fn changes(&self) -> Vec<SheetChange> {
+        let (before, after) = (&self.original, &self.sheet);
+        let mut changes = Vec::new();
+
+        if let (Some(from), Some(to)) =
+            (before.combatant.attributes(), after.combatant.attributes())
+        {
+            for attribute in AttributeType::iter() {
+                let (from, to) = (from.get_modifier(attribute), to.get_modifier(attribute));
+                if from != to {
+                    changes.push(SheetChange::Attribute {
+                        attribute,
+                        from,
+                        to,
+                    });
+                }
+            }
+        }
+        let (from, to) = (before.combatant.subclass(), after.combatant.subclass());
+        if from != to {
+            changes.push(SheetChange::Subclass { from, to });
+        }
+
+        let (old, new) = (
+            &before.progress.available_cards,
+            &after.progress.available_cards,
+        );
+        for card in old.iter().filter(|card| !new.contains(card)) {
+            changes.push(SheetChange::CardRemoved { card: card.clone() });
+        }
+        for card in new.iter().filter(|card| !old.contains(card)) {
+            changes.push(SheetChange::CardAdded { card: card.clone() });
+        }
+
+        let (old, new) = (&before.combatant.experiences, &after.combatant.experiences);
+        for experience in old.iter().filter(|e| !new.contains(e)) {
+            changes.push(SheetChange::ExperienceRemoved {
+                experience: experience.clone(),
+            });
+        }
+        for experience in new.iter().filter(|e| !old.contains(e)) {
+            changes.push(SheetChange::ExperienceAdded {
+                experience: experience.clone(),
+            });
+        }
+
+        if let (Some(from), Some(to)) = (&before.companion, &after.companion) {
+            if from.upgrades != to.upgrades {
+                changes.push(SheetChange::CompanionUpgrades {
+                    from: from.upgrades.clone(),
+                    to: to.upgrades.clone(),
+                });
+            }
+        }
+        changes
+    }
+
+    /// Check the rebuilt character is legal for their level
+    ///
+    /// Traits must use the standard spread, the subclass must belong to
+    /// the class, and there can be no more than level + 1 domain cards,
+    /// tier + 1 Experiences and one companion upgrade per level past the
+    /// first.
+    pub fn validate(&self) -> Result<(), EngineError> {
+        let combatant = &self.sheet.combatant;
+        let class = combatant.require_class()?;
+        let level = self.sheet.progress.level;
+
+        if let Some(attributes) = combatant.attributes() {
+            attributes.validate()?;
+        }
+        if let Some(subclass) = combatant.subclass() {
+            if subclass.class() != class {
+                return Err(EngineError::WrongSubclass { subclass, class });
+            }
+        }
+
+        let cards = &self.sheet.progress.available_cards;
+        let too_many = |what: &str, count: usize, allowed: usize| {
+            EngineError::InvalidCharacterState(format!(
+                "{} has {} {}, but only {} are allowed at level {}",
+                combatant.name, count, what, allowed, level
+            ))
+        };
+        let allowed = level as usize + 1;
+        if cards.len() > allowed {
+            return Err(too_many("domain cards", cards.len(), allowed));
+        }
+        if let Some(card) = cards
+            .iter()
+            .enumerate()
+            .find_map(|(i, card)| cards[..i].contains(card).then_some(card))
+        {
+            return Err(EngineError::InvalidCharacterState(format!(
+                "{} has {} twice",
+                combatant.name, card
+            )));
+        }
+
+        let allowed = tier_for_level(level) as usize + 1;
+        if combatant.experiences.len() > allowed {
+            return Err(too_many(
+                "Experiences",
+                combatant.experiences.len(),
+                allowed,
+            ));
+        }
+
+        if let Some(companion) = &self.sheet.companion {
+            let allowed = level.saturating_sub(1) as usize;
+            if companion.upgrades.len() > allowed {
+                return Err(too_many(
+                    "companion upgrades",
+                    companion.upgrades.len(),
+                    allowed,
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Also check each domain card exists and is one the class can take at
+    /// this level
+    #[cfg(feature = "content")]
+    pub fn validate_with(&self, content: &ContentRegistry) -> Result<(), EngineError> {
+        self.validate()?;
+        let class = self.sheet.combatant.require_class()?;
+        for id in &self.sheet.progress.available_cards {
+            let card = content
+                .card(id)
+                .ok_or_else(|| EngineError::UnknownCard { id: id.clone() })?;
+            card.check_allowed(class, self.sheet.progress.level)?;
+        }
+        Ok(())
+    }
+
+    /// Validate and hand back the new sheet with its changes
+    pub fn finish(self) -> Result<Rebuilt, EngineError> {
+        self.validate()?;
+        let changes = self.changes();
+        Ok(Rebuilt {
+            sheet: self.sheet,
+            changes,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::companion::Companion;
+    use crate::character::{Ancestry, Class};
+    use crate::test_support::player;
+
+    fn create_test_sheet() -> CharacterSheet {
+        let mut sheet = CharacterSheet::new(
+            player("Ash", Class::Ranger, Ancestry::Human)
+                .with_level(2)
+                .with_subclass(Subclass::Beastbound)
+                .with_experience(Experience::new("Tracker", 2))
+                .build()
+                .unwrap(),
+        )
+        .with_companion(Companion::new("Fang", "Wolf"));
+        sheet.progress.add_card("bone_snap");
+        sheet
+    }
+
+    #[test]
+    fn test_changes_are_listed() {
+        let sheet = create_test_sheet();
+        let rebuilt = sheet
+            .rebuild()
+            .with_attributes(Attributes::from_array([1, 2, 1, 0, 0, -1]).unwrap())
+            .with_cards(["grace_strike"])
+            .with_experiences(vec![Experience::new("Sailor", 2)])
+            .with_companion_upgrades(&[CompanionUpgrade::Aware])
+            .finish()
+            .unwrap();
+
+        let lines: Vec<String> = rebuilt.changes.iter().map(|c| c.to_string()).collect();
+        assert_eq!(
+            lines,
+            vec![
+                "Agility +2 → +1",
+                "Strength +1 → +2",
+                "- card bone_snap",
+                "+ card grace_strike",
+                "- Tracker +2",
+                "+ Sailor +2",
+                "Companion upgrades none → Aware",
+            ]
+        );
+        assert_eq!(rebuilt.sheet.companion.unwrap().evasion, 12);
+        assert!(sheet.rebuild().changes().is_empty());
+    }
+
+    #[test]
+    fn test_illegal_rebuilds_are_rejected() {
+        let sheet = create_test_sheet();
+        assert!(sheet
+            .rebuild()
+            .with_cards(["a", "b", "c", "d"])
+            .finish()
+            .is_err());
+        assert!(sheet.rebuild().with_cards(["a", "a"]).finish().is_err());
+        assert!(sheet
+            .rebuild()
+            .with_companion_upgrades(&[CompanionUpgrade::Aware, CompanionUpgrade::Hardy])
+            .finish()
+            .is_err());
+        assert!(matches!(
+            sheet
+                .rebuild()
+                .with_attributes(Attributes {
+                    agility: 2,
+                    strength: 2,
+                    finesse: 1,
+                    instinct: 0,
+                    presence: 0,
+                    knowledge: -1,
+                })
+                .finish(),
+            Err(EngineError::InvalidAttributes { .. })
+        ));
+    }
+
+    #[cfg(feature = "content")]
+    #[test]
+    fn test_unknown_cards_are_rejected() {
+        let content = ContentRegistry::builtin();
+        assert!(matches!(
+            create_test_sheet()
+                .rebuild()
+                .with_cards(["no_such_card"])
+                .validate_with(&content),
+            Err(EngineError::UnknownCard { id }) if id == "no_such_card"
+        ));
+    }
+
+    #[test]
+    fn test_respec_restores_base_stats() {
+        let mut fang = Companion::new("Fang", "Wolf");
+        fang.level_up(3);
+        fang.upgrade(CompanionUpgrade::Vicious).unwrap();
+        fang.upgrade(CompanionUpgrade::Hardy).unwrap();
+        fang.respec(&[CompanionUpgrade::Armored]);
+        assert_eq!(fang.attack.damage, crate::core::dice::Die::D6);
+        assert_eq!(fang.hp.maximum, 3);
+        assert_eq!(fang.armor, 1);
+    }
+}
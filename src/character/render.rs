@@ -0,0 +1,506 @@
+//! Printable character sheets in Markdown and HTML
+//!
+//! Card text is looked up in a `ContentRegistry`, since a sheet only
+//! stores card ids. Cards missing from the registry are listed by id.
+//! The `_localized` renderers take their text from a [`Catalog`].
+
+use crate::cards::{ActionCost, DomainCard};
+use crate::character::{Attributes, CharacterSheet};
+use crate::content::ContentRegistry;
+use crate::i18n::Catalog;
+use std::fmt::Write;
+
+impl CharacterSheet {
+    /// Render a printable Markdown sheet, with card text from the built-in content
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    ///
+    /// let mut sheet = CharacterSheet::new(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// sheet.progress.add_card("get_back_up");
+    ///
+    /// let markdown = sheet.render_markdown();
+    /// assert!(markdown.starts_with("# Grom\n"));
+    /// assert!(markdown.contains("### Get Back Up"));
+    /// ```
+    pub fn render_markdown(&self) -> String {
+        self.render_markdown_with(&ContentRegistry::builtin())
+    }
+
+    /// Render a printable Markdown sheet, with card text from `content`
+    pub fn render_markdown_with(&self, content: &ContentRegistry) -> String {
+        self.render_markdown_localized(content, &Catalog::english())
+    }
+
+    /// Render a printable Markdown sheet in the catalog's language
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    /// use daggerheart_engine::content::ContentRegistry;
+    /// use daggerheart_engine::i18n::Catalog;
+    ///
+    /// let sheet = CharacterSheet::new(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// let german = Catalog::new("de")
+    ///     .with_message("sheet.Traits", "Merkmale")
+    ///     .with_message("class.Warrior", "Krieger")
+    ///     .with_message("sheet.level", "Stufe {level}");
+    ///
+    /// let markdown = sheet.render_markdown_localized(&ContentRegistry::builtin(), &german);
+    /// assert!(markdown.contains("*Stufe 1 Orc Krieger*"));
+    /// assert!(markdown.contains("## Merkmale"));
+    /// ```
+    pub fn render_markdown_localized(
+        &self,
+        content: &ContentRegistry,
+        catalog: &Catalog,
+    ) -> String {
+        let content = content.localized(catalog);
+        let label = |text| catalog.name("sheet", text);
+        let combatant = &self.combatant;
+        let mut md = String::new();
+
+        let _ = writeln!(md, "# {}\n", combatant.name);
+        let _ = writeln!(md, "*{}*\n", self.summary(catalog));
+
+        if let Some(attributes) = combatant.attributes() {
+            let _ = writeln!(md, "## {}\n", label("Traits"));
+            let (names, values): (Vec<_>, Vec<_>) = traits(attributes, catalog)
+                .into_iter()
+                .map(|(name, value)| (name, format!("{:+}", value)))
+                .unzip();
+            let _ = writeln!(md, "| {} |", names.join(" | "));
+            let _ = writeln!(md, "|{}", ":---:|".repeat(names.len()));
+            let _ = writeln!(md, "| {} |\n", values.join(" | "));
+        }
+
+        let _ = writeln!(md, "## {}\n", label("Stats"));
+        for (name, value) in self.stats(catalog) {
+            let _ = writeln!(md, "- **{}:** {}", name, value);
+        }
+
+        let features = self.features(catalog);
+        if !features.is_empty() {
+            let _ = writeln!(md, "\n## {}\n", label("Features"));
+            for feature in features {
+                let _ = writeln!(md, "- {}", feature);
+            }
+        }
+
+        if !combatant.experiences.is_empty() {
+            let _ = writeln!(md, "\n## {}\n", label("Experiences"));
+            for experience in &combatant.experiences {
+                let _ = writeln!(md, "- {} {:+}", experience.name, experience.bonus);
+            }
+        }
+
+        if !self.progress.available_cards.is_empty() {
+            let _ = writeln!(md, "\n## {}", label("Domain Cards"));
+            for id in &self.progress.available_cards {
+                match content.card(id) {
+                    Some(card) => {
+                        let _ = writeln!(
+                            md,
+                            "\n### {}\n\n*{}*\n",
+                            card.name,
+                            card_line(card, catalog)
+                        );
+                        let _ = writeln!(md, "{}", card.description);
+                    }
+                    None => {
+                        let _ = writeln!(md, "\n### {}", id);
+                    }
+                }
+            }
+        }
+
+        if !combatant.equipment.is_empty() {
+            let _ = writeln!(md, "\n## {}\n", label("Equipment"));
+            for item in &combatant.equipment {
+                let _ = writeln!(md, "- {}", item);
+            }
+        }
+
+        md
+    }
+
+    /// Render a standalone printable HTML page, with card text from the built-in content
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    ///
+    /// let sheet = CharacterSheet::new(
+    ///     CombatantBuilder::player("Grom <the Bold>", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// let html = sheet.render_html();
+    /// assert!(html.starts_with("<!DOCTYPE html>"));
+    /// assert!(html.contains("<h1>Grom &lt;the Bold&gt;</h1>"));
+    /// ```
+    pub fn render_html(&self) -> String {
+        self.render_html_with(&ContentRegistry::builtin())
+    }
+
+    /// Render a standalone printable HTML page, with card text from `content`
+    pub fn render_html_with(&self, content: &ContentRegistry) -> String {
+        self.render_html_localized(content, &Catalog::english())
+    }
+
+    /// Render a standalone printable HTML page in the catalog's language
+    pub fn render_html_localized(&self, content: &ContentRegistry, catalog: &Catalog) -> String {
+        let content = content.localized(catalog);
+        let heading = |text| format!("<h2>{}</h2>\n", escape(&catalog.name("sheet", text)));
+        let combatant = &self.combatant;
+        let name = escape(&combatant.name);
+        let mut html = String::new();
+
+        let _ = writeln!(
+            html,
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">",
+            escape(&catalog.locale)
+        );
+        let _ = writeln!(html, "<title>{}</title>", name);
+        html.push_str(STYLE);
+        html.push_str("</head>\n<body>\n");
+        let _ = writeln!(html, "<h1>{}</h1>", name);
+        let _ = writeln!(
+            html,
+            "<p class=\"summary\">{}</p>",
+            escape(&self.summary(catalog))
+        );
+
+        if let Some(attributes) = combatant.attributes() {
+            html.push_str(&heading("Traits"));
+            html.push_str("<table class=\"traits\">\n<tr>");
+            for (name, _) in traits(attributes, catalog) {
+                let _ = write!(html, "<th>{}</th>", escape(&name));
+            }
+            html.push_str("</tr>\n<tr>");
+            for (_, value) in traits(attributes, catalog) {
+                let _ = write!(html, "<td>{:+}</td>", value);
+            }
+            html.push_str("</tr>\n</table>\n");
+        }
+
+        html.push_str(&heading("Stats"));
+        html.push_str("<dl class=\"stats\">\n");
+        for (name, value) in self.stats(catalog) {
+            let _ = writeln!(
+                html,
+                "<dt>{}</dt><dd>{}</dd>",
+                escape(&name),
+                escape(&value)
+            );
+        }
+        html.push_str("</dl>\n");
+
+        let features = self.features(catalog);
+        if !features.is_empty() {
+            html.push_str(&heading("Features"));
+            html.push_str("<ul>\n");
+            for feature in features {
+                let _ = writeln!(html, "<li>{}</li>", escape(&feature));
+            }
+            html.push_str("</ul>\n");
+        }
+
+        if !combatant.experiences.is_empty() {
+            html.push_str(&heading("Experiences"));
+            html.push_str("<ul>\n");
+            for experience in &combatant.experiences {
+                let _ = writeln!(
+                    html,
+                    "<li>{} {:+}</li>",
+                    escape(&experience.name),
+                    experience.bonus
+                );
+            }
+            html.push_str("</ul>\n");
+        }
+
+        if !self.progress.available_cards.is_empty() {
+            html.push_str(&heading("Domain Cards"));
+            for id in &self.progress.available_cards {
+                html.push_str("<div class=\"card\">\n");
+                match content.card(id) {
+                    Some(card) => {
+                        let _ = writeln!(html, "<h3>{}</h3>", escape(&card.name));
+                        let _ = writeln!(
+                            html,
+                            "<p class=\"meta\">{}</p>",
+                            escape(&card_line(card, catalog))
+                        );
+                        let _ = writeln!(html, "<p>{}</p>", escape(&card.description));
+                    }
+                    None => {
+                        let _ = writeln!(html, "<h3>{}</h3>", escape(id));
+                    }
+                }
+                html.push_str("</div>\n");
+            }
+        }
+
+        if !combatant.equipment.is_empty() {
+            html.push_str(&heading("Equipment"));
+            html.push_str("<ul>\n");
+            for item in &combatant.equipment {
+                let _ = writeln!(html, "<li>{}</li>", escape(item));
+            }
+            html.push_str("</ul>\n");
+        }
+
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+
+    /// "Level 3 Orc Warrior (Call of the Slayer) · Ridgeborne"
+    fn summary(&self, catalog: &Catalog) -> String {
+        let combatant = &self.combatant;
+        let mut summary = catalog.format(
+            "sheet.level",
+            "Level {level}",
+            &[("level", &combatant.level.to_string())],
+        );
+        if let Some(player) = combatant.player_data() {
+            let _ = write!(
+                summary,
+                " {} {}",
+                catalog.name("ancestry", &player.ancestry.to_string()),
+                catalog.name("class", &player.class.to_string())
+            );
+            if let Some(subclass) = player.subclass {
+                let _ = write!(
+                    summary,
+                    " ({})",
+                    catalog.name("subclass", &subclass.to_string())
+                );
+            }
+            if let Some(community) = player.community {
+                let _ = write!(
+                    summary,
+                    " · {}",
+                    catalog.name("community", &community.to_string())
+                );
+            }
+        }
+        summary
+    }
+
+    /// Derived stats, as label and value
+    fn stats(&self, catalog: &Catalog) -> Vec<(String, String)> {
+        let combatant = &self.combatant;
+        let stats = vec![
+            (
+                "HP",
+                format!("{}/{}", combatant.hp.current, combatant.hp.maximum),
+            ),
+            (
+                "Stress",
+                catalog.format(
+                    "sheet.marked",
+                    "{count} marked",
+                    &[("count", &combatant.stress.current.to_string())],
+                ),
+            ),
+            ("Evasion", combatant.evasion.to_string()),
+            ("Armor", combatant.armor.to_string()),
+            ("Tier", self.progress.tier().to_string()),
+            (
+                "XP",
+                format!(
+                    "{}/{}",
+                    self.progress.experience,
+                    self.progress.xp_for_next_level()
+                ),
+            ),
+        ];
+        stats
+            .into_iter()
+            .map(|(label, value)| (catalog.name("sheet", label), value))
+            .collect()
+    }
+
+    /// Ancestry abilities and the community feature
+    fn features(&self, catalog: &Catalog) -> Vec<String> {
+        let combatant = &self.combatant;
+        let mut features = Vec::new();
+        if let Some(ancestry) = combatant.ancestry() {
+            let name = catalog.name("ancestry", &ancestry.to_string());
+            for feature in ancestry.features() {
+                features.push(format!(
+                    "{} ({})",
+                    catalog.name("ability", feature.name),
+                    name
+                ));
+            }
+        }
+        if let Some(community) = combatant.community() {
+            features.push(format!(
+                "{} ({})",
+                catalog.name("feature", community.feature()),
+                catalog.name("community", &community.to_string())
+            ));
+        }
+        features
+    }
+}
+
+/// Trait names and modifiers in sheet order
+fn traits(attributes: &Attributes, catalog: &Catalog) -> [(String, i8); 6] {
+    [
+        ("Agility", attributes.agility),
+        ("Strength", attributes.strength),
+        ("Finesse", attributes.finesse),
+        ("Instinct", attributes.instinct),
+        ("Presence", attributes.presence),
+        ("Knowledge", attributes.knowledge),
+    ]
+    .map(|(name, value)| (catalog.name("trait", name), value))
+}
+
+/// "Blade · level 1 · Major action"
+fn card_line(card: &DomainCard, catalog: &Catalog) -> String {
+    let cost = match card.action_cost {
+        ActionCost::Major => "Major action",
+        ActionCost::Minor => "Minor action",
+        ActionCost::Reaction => "Reaction",
+        ActionCost::Free => "Free action",
+    };
+    format!(
+        "{} · {} · {}",
+        catalog.name("domain", &card.domain.to_string()),
+        catalog.format(
+            "sheet.card_level",
+            "level {level}",
+            &[("level", &card.level_requirement.to_string())]
+        ),
+        catalog.name("cost", cost)
+    )
+}
+
+/// Escape text for HTML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "<style>
+body { font-family: Georgia, serif; max-width: 48em; margin: 2em auto; color: #222; }
+h1 { margin-bottom: 0; }
+.summary { font-style: italic; margin-top: 0.25em; }
+h2 { border-bottom: 2px solid #633; color: #633; }
+table.traits { border-collapse: collapse; width: 100%; }
+table.traits th, table.traits td { border: 1px solid #999; padding: 0.4em; text-align: center; }
+dl.stats { display: grid; grid-template-columns: max-content auto; gap: 0.25em 1em; }
+dt { font-weight: bold; }
+dd { margin: 0; }
+.card { border: 1px solid #999; border-radius: 6px; padding: 0 1em; margin: 0.5em 0; break-inside: avoid; }
+.card .meta { font-style: italic; color: #555; }
+@media print { body { margin: 0; } }
+</style>
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Class, Community, Experience, Subclass};
+    use crate::test_support::player;
+
+    fn create_test_sheet() -> CharacterSheet {
+        let mut sheet = CharacterSheet::new(
+            player("Grom", Class::Warrior, Ancestry::Orc)
+                .with_subclass(Subclass::CallOfTheSlayer)
+                .with_community(Community::Ridgeborne)
+                .with_experience(Experience::new("Mercenary", 2))
+                .with_equipment("Longsword")
+                .build()
+                .unwrap(),
+        );
+        sheet.progress.add_card("get_back_up");
+        sheet.progress.add_card("homebrew_card");
+        sheet
+    }
+
+    #[test]
+    fn test_markdown_sections() {
+        let md = create_test_sheet().render_markdown();
+
+        assert!(md.contains("*Level 1 Orc Warrior (Call of the Slayer) · Ridgeborne*"));
+        assert!(md.contains("| +2 | +1 | +1 | +0 | +0 | -1 |"));
+        assert!(md.contains("- **Evasion:** 10"));
+        assert!(md.contains("- Steady (Ridgeborne)"));
+        assert!(md.contains("- Mercenary +2"));
+        assert!(md.contains("### Get Back Up\n\n*Blade · level 1 · Reaction*"));
+        assert!(md.contains("### homebrew_card"));
+        assert!(md.contains("## Equipment\n\n- Longsword"));
+    }
+
+    #[test]
+    fn test_localized_sheet() {
+        let catalog = Catalog::new("fr")
+            .with_message("sheet.Equipment", "Équipement")
+            .with_message("sheet.card_level", "niveau {level}")
+            .with_message("trait.Agility", "Agilité")
+            .with_message("card.get_back_up.name", "Relève-toi");
+        let sheet = create_test_sheet();
+        let content = ContentRegistry::builtin();
+
+        let md = sheet.render_markdown_localized(&content, &catalog);
+        assert!(md.contains("## Équipement\n\n- Longsword"));
+        assert!(md.contains("### Relève-toi\n\n*Blade · niveau 1 · Reaction*"));
+        assert!(md.contains("## Stats"));
+
+        let html = sheet.render_html_localized(&content, &catalog);
+        assert!(html.contains("<html lang=\"fr\">"));
+        assert!(html.contains("<th>Agilité</th>"));
+
+        // The translators' template has every key the sheet looks up
+        let mut template = Catalog::template("xx", &content);
+        for text in template.messages.values_mut() {
+            *text = format!("[{}]", text);
+        }
+        let md = sheet.render_markdown_localized(&content, &template);
+        assert!(md.contains("*[Level 1] [Orc] [Warrior] ([Call of the Slayer]) · [Ridgeborne]*"));
+        assert!(md.contains("- [Steady] ([Ridgeborne])"));
+        assert!(md.contains("- **[Stress]:** [0 marked]"));
+        assert!(md.contains("*[Blade] · [level 1] · [Reaction]*"));
+        assert!(md
+            .lines()
+            .filter(|line| line.starts_with("## "))
+            .all(|line| line.starts_with("## [")));
+    }
+
+    #[test]
+    fn test_html_sections() {
+        let html = create_test_sheet().render_html();
+
+        assert!(html.contains("<td>+2</td>"));
+        assert!(html.contains("<dt>HP</dt><dd>6/6</dd>"));
+        assert!(html.contains("<h3>Get Back Up</h3>"));
+        assert!(html.contains("<li>Longsword</li>"));
+        assert!(html.trim_end().ends_with("</html>"));
+    }
+}
@@ -0,0 +1,756 @@
+//! Character sheets - a character's combat stats and progression together
+
+use crate::cards::effects::Duration;
+use crate::cards::DomainCard;
+use crate::character::companion::Companion;
+use crate::character::progression::CharacterProgress;
+use crate::character::AttributeType;
+use crate::combat::conditions::Condition;
+use crate::combat::simulation::Combatant;
+use crate::downtime::RestKind;
+use crate::error::EngineError;
+use crate::hooks::{EngineHooks, Hooks};
+use crate::items::inventory::Inventory;
+use crate::items::loadout::Loadout;
+use crate::items::magic::{MagicItem, DEFAULT_ATTUNEMENT_LIMIT};
+use crate::items::weapons::{Weapon, WeaponSlot};
+#[cfg(feature = "persistence")]
+use crate::storage::{self, Persist};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "persistence")]
+use std::path::Path;
+use std::sync::Arc;
+
+/// Everything needed to play a character across sessions
+///
+/// Bundles the combat-facing `Combatant` with its `CharacterProgress`, so
+/// tools don't have to keep `<name>_char.json` and `<name>_progress.json`
+/// in sync.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CharacterSheet {
+    pub combatant: Combatant,
+    pub progress: CharacterProgress,
+    /// An animal companion or familiar that levels up with the character
+    #[serde(default)]
+    pub companion: Option<Companion>,
+    /// The weapons the character has in hand
+    #[serde(default)]
+    pub loadout: Loadout,
+    /// Magic items carried, with their attunement and charges
+    #[serde(default)]
+    pub magic_items: Vec<MagicItem>,
+    /// Items the character can be attuned to at once
+    #[serde(default = "default_attunement_limit")]
+    pub attunement_limit: u8,
+    /// Everything else carried, and whether its weight counts
+    #[serde(default)]
+    pub inventory: Inventory,
+    /// Lasting marks from Avoiding Death, each costing a Hope slot
+    #[serde(default)]
+    pub scars: Vec<String>,
+    /// Observers notified of level-ups and card plays
+    #[serde(skip)]
+    hooks: Hooks,
+}
+
+impl CharacterSheet {
+    #[cfg(feature = "persistence")]
+    /// Save character sheet to a JSON file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    ///
+    /// let sheet = CharacterSheet::new(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// sheet.save_to_file("grom.json").unwrap();
+    /// ```
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), EngineError> {
+        storage::save_file(self, path)
+    }
+
+    #[cfg(feature = "persistence")]
+    /// Load character sheet from a JSON file
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, EngineError> {
+        storage::load_file(path)
+    }
+
+    /// Create a sheet for a new character
+    ///
+    /// Progression starts at the combatant's level with no XP or cards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    ///
+    /// let sheet = CharacterSheet::new(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_level(3)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// assert_eq!(sheet.name(), "Grom");
+    /// assert_eq!(sheet.progress.level, 3);
+    /// ```
+    pub fn new(combatant: Combatant) -> Self {
+        let mut progress = CharacterProgress::new();
+        progress.level = combatant.level;
+        Self {
+            combatant,
+            progress,
+            companion: None,
+            loadout: Loadout::default(),
+            magic_items: Vec::new(),
+            attunement_limit: DEFAULT_ATTUNEMENT_LIMIT,
+            inventory: Inventory::default(),
+            scars: Vec::new(),
+            hooks: Hooks::default(),
+        }
+    }
+
+    /// Create a sheet from an existing combatant and progress pair
+    pub fn from_parts(combatant: Combatant, progress: CharacterProgress) -> Self {
+        Self {
+            combatant,
+            progress,
+            companion: None,
+            loadout: Loadout::default(),
+            magic_items: Vec::new(),
+            attunement_limit: DEFAULT_ATTUNEMENT_LIMIT,
+            inventory: Inventory::default(),
+            scars: Vec::new(),
+            hooks: Hooks::default(),
+        }
+    }
+
+    /// Give the character a companion at their level
+    pub fn with_companion(mut self, mut companion: Companion) -> Self {
+        companion.level_up(self.progress.level);
+        self.companion = Some(companion);
+        self
+    }
+
+    /// Wield a weapon, returning the one it replaces
+    ///
+    /// The weapon is added to the combatant's equipment, with the primary
+    /// weapon first so attacks use it, and its features' Evasion and armor
+    /// changes are applied. Fails if it needs a hand that's already
+    /// holding something.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    /// use daggerheart_engine::items::Weapon;
+    ///
+    /// let mut sheet = CharacterSheet::new(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// sheet.equip(Weapon::named("Battleaxe").unwrap()).unwrap();
+    /// assert_eq!(sheet.free_hands(), 0);
+    /// assert!(sheet.equip(Weapon::named("Round Shield").unwrap()).is_err());
+    /// ```
+    pub fn equip(&mut self, weapon: Weapon) -> Result<Option<Weapon>, EngineError> {
+        let slot = weapon.slot;
+        let name = weapon.name.clone();
+        let modifiers = (weapon.evasion_modifier(), weapon.armor_modifier());
+        let replaced = self.loadout.equip(weapon)?;
+        if let Some(old) = &replaced {
+            self.adjust_defenses(-old.evasion_modifier(), -old.armor_modifier());
+            self.combatant.equipment.retain(|item| *item != old.name);
+        }
+        self.adjust_defenses(modifiers.0, modifiers.1);
+        let equipment = &mut self.combatant.equipment;
+        equipment.retain(|item| *item != name);
+        match slot {
+            WeaponSlot::Primary => equipment.insert(0, name),
+            WeaponSlot::Secondary => equipment.push(name),
+        }
+        Ok(replaced)
+    }
+
+    /// Put away the weapon in a slot
+    pub fn unequip(&mut self, slot: WeaponSlot) -> Option<Weapon> {
+        let weapon = self.loadout.unequip(slot)?;
+        self.adjust_defenses(-weapon.evasion_modifier(), -weapon.armor_modifier());
+        self.combatant.equipment.retain(|item| *item != weapon.name);
+        Some(weapon)
+    }
+
+    fn adjust_defenses(&mut self, evasion: i8, armor: i8) {
+        let combatant = &mut self.combatant;
+        combatant.evasion = combatant.evasion.saturating_add_signed(evasion);
+        combatant.armor = combatant.armor.saturating_add_signed(armor);
+    }
+
+    /// Hands not holding a weapon
+    pub fn free_hands(&self) -> u8 {
+        self.loadout.free_hands()
+    }
+
+    /// Carry a magic item, adding it to the combatant's equipment
+    pub fn add_magic_item(&mut self, item: MagicItem) {
+        if !self.combatant.equipment.contains(&item.name) {
+            self.combatant.equipment.push(item.name.clone());
+        }
+        self.magic_items.push(item);
+    }
+
+    /// A carried magic item, by name ignoring case
+    pub fn magic_item(&self, name: &str) -> Option<&MagicItem> {
+        self.magic_items
+            .iter()
+            .find(|item| item.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Items the character is attuned to
+    pub fn attuned_items(&self) -> impl Iterator<Item = &MagicItem> {
+        self.magic_items.iter().filter(|item| item.attuned)
+    }
+
+    /// Attune to a carried item, up to the character's attunement limit
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    /// use daggerheart_engine::items::MagicItem;
+    ///
+    /// let mut sheet = CharacterSheet::new(
+    ///     CombatantBuilder::player("Elowen", Class::Wizard, Ancestry::Human)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// sheet.attunement_limit = 1;
+    /// sheet.add_magic_item(MagicItem::new("Ring of Warding").requiring_attunement());
+    /// sheet.add_magic_item(MagicItem::new("Cloak of Stars").requiring_attunement());
+    ///
+    /// sheet.attune("Ring of Warding").unwrap();
+    /// assert!(sheet.attune("Cloak of Stars").is_err());
+    /// ```
+    pub fn attune(&mut self, name: &str) -> Result<(), EngineError> {
+        let attuned = self.attuned_items().count();
+        let limit = self.attunement_limit;
+        let character = self.combatant.name.clone();
+        let item = self.magic_item_mut(name)?;
+        if item.attuned {
+            return Ok(());
+        }
+        if attuned >= limit as usize {
+            return Err(EngineError::AttunementLimit {
+                character: character.to_string(),
+                limit,
+            });
+        }
+        item.attuned = true;
+        Ok(())
+    }
+
+    /// End attunement to an item, freeing the slot
+    pub fn unattune(&mut self, name: &str) -> Result<(), EngineError> {
+        self.magic_item_mut(name)?.attuned = false;
+        Ok(())
+    }
+
+    /// Use a carried item's charges
+    pub fn use_item(&mut self, name: &str, charges: u8) -> Result<(), EngineError> {
+        self.magic_item_mut(name)?.use_charges(charges)
+    }
+
+    /// Recharge magic items on a rest, returning how many got charges back
+    pub fn recharge_items(&mut self, rest: RestKind) -> usize {
+        self.magic_items
+            .iter_mut()
+            .filter_map(|item| item.charges.as_mut())
+            .map(|charges| charges.rest(rest))
+            .filter(|&restored| restored > 0)
+            .count()
+    }
+
+    /// Put an item in the inventory, marking the character Encumbered
+    /// if it overloads them
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::{CombatantBuilder, Condition};
+    ///
+    /// let mut sheet = CharacterSheet::new(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// sheet.inventory.encumbrance = true;
+    ///
+    /// // Strength +1 carries 13
+    /// sheet.carry("Iron Ingot", 4, 4);
+    /// assert!(sheet.combatant.has_condition(Condition::Encumbered));
+    /// sheet.drop_item("Iron Ingot", 1).unwrap();
+    /// assert!(!sheet.combatant.has_condition(Condition::Encumbered));
+    /// ```
+    pub fn carry(&mut self, name: impl Into<String>, weight: u16, quantity: u16) -> bool {
+        self.inventory.add(name, weight, quantity);
+        self.update_encumbrance()
+    }
+
+    /// Take an item out of the inventory, updating encumbrance
+    ///
+    /// Fails with [`EngineError::ItemNotCarried`] if none of the item is
+    /// carried, or [`EngineError::TooFewCarried`] if fewer are.
+    pub fn drop_item(&mut self, name: &str, quantity: u16) -> Result<bool, EngineError> {
+        self.inventory
+            .remove(name, quantity)
+            .map_err(|error| match error {
+                EngineError::TooFewCarried {
+                    carried: 0, item, ..
+                } => EngineError::ItemNotCarried {
+                    character: self.combatant.name.to_string(),
+                    item,
+                },
+                error => error,
+            })?;
+        Ok(self.update_encumbrance())
+    }
+
+    /// Whether the inventory is over what the character's Strength allows
+    pub fn is_overloaded(&self) -> bool {
+        let strength = self.combatant.attributes().map_or(0, |attributes| {
+            attributes.get_modifier(AttributeType::Strength)
+        });
+        self.inventory.is_overloaded(strength)
+    }
+
+    /// Mark or clear the Encumbered condition to match the load, returning
+    /// whether the character is now encumbered
+    pub fn update_encumbrance(&mut self) -> bool {
+        if self.is_overloaded() {
+            self.combatant.add_condition(Condition::Encumbered);
+        } else {
+            self.combatant.remove_condition(Condition::Encumbered);
+        }
+        self.combatant.has_condition(Condition::Encumbered)
+    }
+
+    /// The character as they enter an encounter
+    ///
+    /// Conditions that don't outlast a scene, such as Hidden, are left
+    /// behind.
+    pub fn enter_encounter(&self) -> Combatant {
+        let mut combatant = self.combatant.clone();
+        combatant.conditions.retain(|c| c.is_lasting());
+        combatant
+    }
+
+    /// Carry what an encounter left on the character back to the sheet
+    ///
+    /// Hit Points, Stress, Hope and innate ability uses are copied over,
+    /// along with lasting conditions and permanent modifiers such as No
+    /// Mercy. Modifiers
+    /// counted in turns or rounds wear off with the fight, and
+    /// Encumbered is checked against the load again.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::{CombatantBuilder, Condition};
+    ///
+    /// let mut sheet = CharacterSheet::new(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// let mut fighting = sheet.enter_encounter();
+    /// fighting.add_condition(Condition::Vulnerable);
+    /// fighting.add_condition(Condition::Hidden);
+    /// fighting.take_damage(2);
+    ///
+    /// sheet.leave_encounter(&fighting);
+    /// assert_eq!(sheet.combatant.hp.current, fighting.hp.current);
+    /// assert_eq!(sheet.combatant.conditions, vec![Condition::Vulnerable]);
+    /// ```
+    pub fn leave_encounter(&mut self, combatant: &Combatant) {
+        self.combatant.hp = combatant.hp.clone();
+        self.combatant.stress = combatant.stress.clone();
+        self.combatant.abilities_used = combatant.abilities_used.clone();
+        if let (Ok(hope), Some(&ended)) = (self.combatant.hope_mut(), combatant.hope()) {
+            *hope = ended;
+        }
+        self.combatant.conditions = combatant
+            .conditions
+            .iter()
+            .copied()
+            .filter(|c| c.is_lasting())
+            .collect();
+        self.combatant.modifiers.effects = combatant
+            .modifiers
+            .effects
+            .iter()
+            .filter(|effect| effect.duration == Duration::Permanent)
+            .cloned()
+            .collect();
+        self.update_encumbrance();
+    }
+
+    /// Mark the character with a scar, crossing out one of their Hope
+    /// slots for good
+    pub fn add_scar(&mut self, scar: impl Into<String>) {
+        if let Ok(hope) = self.combatant.hope_mut() {
+            hope.reduce_maximum(1);
+        }
+        self.scars.push(scar.into());
+    }
+
+    fn magic_item_mut(&mut self, name: &str) -> Result<&mut MagicItem, EngineError> {
+        let character = &self.combatant.name;
+        self.magic_items
+            .iter_mut()
+            .find(|item| item.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| EngineError::ItemNotCarried {
+                character: character.to_string(),
+                item: name.to_string(),
+            })
+    }
+
+    /// Character name
+    pub fn name(&self) -> &str {
+        &self.combatant.name
+    }
+
+    /// Register an observer for this character
+    ///
+    /// Hooks are not saved with the sheet; register them again after
+    /// loading.
+    pub fn add_hook(&mut self, hook: Arc<dyn EngineHooks>) {
+        self.hooks.add(hook);
+    }
+
+    /// Observers registered on this character
+    pub fn hooks(&self) -> &Hooks {
+        &self.hooks
+    }
+
+    /// Level up, spending XP, and keep the combatant's level in step
+    ///
+    /// A companion levels up too, earning an upgrade to pick with
+    /// [`Companion::upgrade`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    ///
+    /// let mut sheet = CharacterSheet::new(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// sheet.progress.add_experience(100);
+    ///
+    /// sheet.level_up().unwrap();
+    /// assert_eq!(sheet.combatant.level, 2);
+    /// ```
+    pub fn level_up(&mut self) -> Result<(), EngineError> {
+        self.progress.level_up()?;
+        self.combatant.level = self.progress.level;
+        self.hooks
+            .level_up(&self.combatant.name, self.progress.level);
+        if let Some(companion) = &mut self.companion {
+            companion.level_up(self.progress.level);
+            self.hooks
+                .companion_level_up(&self.combatant.name, &companion.name, companion.level);
+        }
+        Ok(())
+    }
+
+    /// Play one of this character's domain cards
+    ///
+    /// The card must be in the character's available cards, from one of
+    /// their class domains and within their level.
+    pub fn play_card(&self, card: &DomainCard) -> Result<(), EngineError> {
+        if !self.progress.has_card(&card.id) {
+            return Err(EngineError::CardNotOwned {
+                character: self.name().to_string(),
+                card: card.name.clone(),
+            });
+        }
+        card.check_allowed(self.combatant.require_class()?, self.progress.level)?;
+
+        self.hooks.card_played(self.name(), card);
+        Ok(())
+    }
+}
+
+fn default_attunement_limit() -> u8 {
+    DEFAULT_ATTUNEMENT_LIMIT
+}
+
+#[cfg(feature = "persistence")]
+impl Persist for CharacterSheet {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{ActionCost, ActiveEffect, SourceKind};
+    use crate::character::{Ancestry, Class, Domain};
+    use crate::hooks::tests::Recorder;
+    use crate::items::{MagicItem, Recharge, WeaponFeature};
+    use crate::test_support::player;
+
+    fn create_test_sheet() -> CharacterSheet {
+        CharacterSheet::new(
+            player("Test", Class::Ranger, Ancestry::Human)
+                .with_level(2)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_new_sheet_matches_level() {
+        let sheet = create_test_sheet();
+        assert_eq!(sheet.progress.level, 2);
+        assert_eq!(sheet.progress.experience, 0);
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_sheet_serialization() {
+        let mut sheet = create_test_sheet();
+        sheet.progress.add_card("blade_strike");
+
+        let json = serde_json::to_string(&sheet).unwrap();
+        let loaded: CharacterSheet = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded, sheet);
+    }
+
+    #[test]
+    fn test_level_up_notifies_hooks() {
+        let recorder = Arc::new(Recorder::default());
+        let mut sheet = create_test_sheet();
+        sheet.add_hook(recorder.clone());
+
+        assert!(sheet.level_up().is_err());
+        sheet.progress.add_experience(200);
+        sheet.level_up().unwrap();
+
+        assert_eq!(sheet.combatant.level, 3);
+        assert_eq!(recorder.calls(), vec!["level Test 3"]);
+    }
+
+    #[test]
+    fn test_companion_levels_with_owner() {
+        let recorder = Arc::new(Recorder::default());
+        let mut sheet = create_test_sheet().with_companion(Companion::new("Fang", "Wolf"));
+        sheet.add_hook(recorder.clone());
+        assert_eq!(sheet.companion.as_ref().unwrap().level, 2);
+
+        sheet.progress.add_experience(200);
+        sheet.level_up().unwrap();
+
+        let companion = sheet.companion.as_ref().unwrap();
+        assert_eq!(companion.level, 3);
+        assert_eq!(companion.upgrades_available(), 2);
+        assert_eq!(
+            recorder.calls(),
+            vec!["level Test 3", "companion Test Fang 3"]
+        );
+    }
+
+    #[test]
+    fn test_equip_keeps_primary_weapon_first() {
+        let mut sheet = create_test_sheet();
+        sheet.combatant.equipment.push(String::from("Rope"));
+        sheet.equip(Weapon::named("Small Dagger").unwrap()).unwrap();
+        sheet.equip(Weapon::named("Dagger").unwrap()).unwrap();
+        assert_eq!(
+            sheet.combatant.equipment,
+            ["Dagger", "Rope", "Small Dagger"]
+        );
+        assert_eq!(sheet.free_hands(), 0);
+
+        assert!(sheet.equip(Weapon::named("Shortbow").unwrap()).is_err());
+        sheet.unequip(WeaponSlot::Secondary);
+        sheet.equip(Weapon::named("Shortbow").unwrap()).unwrap();
+        assert_eq!(sheet.combatant.equipment, ["Shortbow", "Rope"]);
+    }
+
+    #[test]
+    fn test_weapon_features_change_defenses_while_wielded() {
+        let mut sheet = create_test_sheet();
+        let (evasion, armor) = (sheet.combatant.evasion, sheet.combatant.armor);
+        let heavy = Weapon::named("Battleaxe")
+            .unwrap()
+            .with_feature(WeaponFeature::heavy());
+
+        sheet.equip(heavy).unwrap();
+        assert_eq!(sheet.combatant.evasion, evasion - 1);
+        // Swapping it out gives the Evasion back
+        sheet.equip(Weapon::named("Broadsword").unwrap()).unwrap();
+        sheet.equip(Weapon::named("Round Shield").unwrap()).unwrap();
+        assert_eq!(sheet.combatant.evasion, evasion);
+        assert_eq!(sheet.combatant.armor, armor + 1);
+
+        sheet.unequip(WeaponSlot::Secondary);
+        assert_eq!(sheet.combatant.armor, armor);
+    }
+
+    #[test]
+    fn test_magic_items_attune_and_recharge() {
+        let mut sheet = create_test_sheet();
+        sheet.add_magic_item(
+            MagicItem::new("Wand of Sparks")
+                .requiring_attunement()
+                .with_charges(2, Recharge::LongRest),
+        );
+        assert!(sheet
+            .combatant
+            .equipment
+            .contains(&String::from("Wand of Sparks")));
+        assert!(sheet.use_item("Wand of Sparks", 1).is_err());
+        assert!(matches!(
+            sheet.attune("Boots"),
+            Err(EngineError::ItemNotCarried { .. })
+        ));
+
+        sheet.attune("wand of sparks").unwrap();
+        sheet.use_item("Wand of Sparks", 2).unwrap();
+        assert!(sheet.use_item("Wand of Sparks", 1).is_err());
+        assert_eq!(sheet.recharge_items(RestKind::Short), 0);
+        assert_eq!(sheet.recharge_items(RestKind::Long), 1);
+
+        // Unattuning frees the slot
+        sheet.attunement_limit = 1;
+        sheet.add_magic_item(MagicItem::new("Ring").requiring_attunement());
+        assert!(matches!(
+            sheet.attune("Ring"),
+            Err(EngineError::AttunementLimit { limit: 1, .. })
+        ));
+        sheet.unattune("Wand of Sparks").unwrap();
+        sheet.attune("Ring").unwrap();
+        assert_eq!(sheet.attuned_items().count(), 1);
+    }
+
+    #[test]
+    fn test_play_card_requires_owned_card() {
+        let recorder = Arc::new(Recorder::default());
+        let mut sheet = create_test_sheet();
+        sheet.add_hook(recorder.clone());
+        let card = DomainCard::new(
+            "bone_snap",
+            "Bone Snap",
+            Domain::Bone,
+            1,
+            "A swift sword strike",
+            ActionCost::Major,
+        );
+
+        assert!(matches!(
+            sheet.play_card(&card),
+            Err(EngineError::CardNotOwned { .. })
+        ));
+        sheet.progress.add_card("bone_snap");
+        sheet.play_card(&card).unwrap();
+
+        assert_eq!(recorder.calls(), vec!["card Test bone_snap"]);
+    }
+
+    #[test]
+    fn test_play_card_outside_class_domains() {
+        let mut sheet = create_test_sheet();
+        let card = DomainCard::new(
+            "blade_strike",
+            "Blade Strike",
+            Domain::Blade,
+            1,
+            "A swift sword strike",
+            ActionCost::Major,
+        );
+        sheet.progress.add_card("blade_strike");
+
+        assert!(matches!(
+            sheet.play_card(&card),
+            Err(EngineError::CardNotAllowed {
+                domain: Domain::Blade,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_encumbrance_is_opt_in() {
+        let mut sheet = create_test_sheet();
+        assert!(!sheet.carry("Anvil", 20, 1));
+        assert!(!sheet.combatant.has_condition(Condition::Encumbered));
+
+        sheet.inventory.encumbrance = true;
+        assert!(sheet.update_encumbrance());
+        assert!(!sheet.drop_item("Anvil", 1).unwrap());
+        assert!(matches!(
+            sheet.drop_item("Anvil", 1),
+            Err(EngineError::ItemNotCarried { .. })
+        ));
+
+        // Strength +1 carries exactly 13 without trouble
+        assert!(!sheet.carry("Stone", 1, 13));
+        assert!(sheet.carry("Stone", 1, 1));
+    }
+
+    #[test]
+    fn test_lasting_effects_carry_between_encounters() {
+        let mut sheet = create_test_sheet();
+        sheet.combatant.add_condition(Condition::Hidden);
+        let mut fighting = sheet.enter_encounter();
+        assert!(fighting.conditions.is_empty());
+
+        fighting.modifiers.add(ActiveEffect::permanent(
+            "No Mercy",
+            SourceKind::Effect,
+            1,
+            "attack rolls",
+        ));
+        let mut blessed = ActiveEffect::permanent("Bless", SourceKind::Effect, 1, "rolls");
+        blessed.duration = Duration::Rounds(2);
+        fighting.modifiers.add(blessed);
+        fighting.add_condition(Condition::Restrained);
+        fighting.hope_mut().unwrap().spend(1).unwrap();
+
+        sheet.leave_encounter(&fighting);
+        assert_eq!(sheet.combatant.conditions, [Condition::Restrained]);
+        assert_eq!(sheet.combatant.modifiers.effects.len(), 1);
+        assert_eq!(sheet.combatant.effect_bonus("attack rolls"), 1);
+        assert_eq!(sheet.combatant.hope().unwrap().current, 1);
+        assert!(sheet.enter_encounter().has_condition(Condition::Restrained));
+    }
+
+    #[test]
+    fn test_scars_cost_a_hope_slot() {
+        let mut sheet = create_test_sheet();
+        let maximum = sheet.combatant.hope().unwrap().maximum;
+        sheet.add_scar("Burned hand");
+        assert_eq!(sheet.scars, ["Burned hand"]);
+        assert_eq!(sheet.combatant.hope().unwrap().maximum, maximum - 1);
+    }
+}
@@ -0,0 +1,428 @@
+//! Adversaries - GM-controlled stat blocks
+
+use crate::combat::adversary_builder::AdversaryBuilder;
+use crate::combat::conditions::Condition;
+use crate::combat::mounts::Mount;
+use crate::combat::simulation::{Combatant, CombatantBuilder};
+use crate::combat::triggers::{Trigger, TriggerEffect, TriggeredFeature};
+use crate::core::dice::DamageDice;
+use crate::core::Difficulty;
+#[cfg(feature = "persistence")]
+use crate::storage::Persist;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// Adversary roles from the SRD
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum AdversaryRole {
+    Bruiser,
+    Horde,
+    Leader,
+    Minion,
+    Ranged,
+    Skulk,
+    Social,
+    Solo,
+    Standard,
+    Support,
+}
+
+impl AdversaryRole {
+    /// Battle point cost of an adversary with this role
+    ///
+    /// Minions are bought in groups: one battle point buys as many
+    /// Minions as there are PCs.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::AdversaryRole;
+    ///
+    /// assert_eq!(AdversaryRole::Minion.battle_points(), 1);
+    /// assert_eq!(AdversaryRole::Standard.battle_points(), 2);
+    /// assert_eq!(AdversaryRole::Solo.battle_points(), 5);
+    /// ```
+    pub fn battle_points(&self) -> u8 {
+        match self {
+            AdversaryRole::Minion | AdversaryRole::Social | AdversaryRole::Support => 1,
+            AdversaryRole::Horde
+            | AdversaryRole::Ranged
+            | AdversaryRole::Skulk
+            | AdversaryRole::Standard => 2,
+            AdversaryRole::Leader => 3,
+            AdversaryRole::Bruiser => 4,
+            AdversaryRole::Solo => 5,
+        }
+    }
+
+    /// Whether this role counts as a "heavy hitter" for budgeting
+    ///
+    /// Encounters without any Bruisers, Hordes, Leaders, or Solos
+    /// get an extra battle point.
+    pub fn is_heavy(&self) -> bool {
+        matches!(
+            self,
+            AdversaryRole::Bruiser
+                | AdversaryRole::Horde
+                | AdversaryRole::Leader
+                | AdversaryRole::Solo
+        )
+    }
+
+    /// A feature that captures how the role plays, if it needs one
+    fn signature_feature(&self) -> Option<(&'static str, &'static str)> {
+        match self {
+            AdversaryRole::Bruiser => Some((
+                "Momentum",
+                "When this adversary hits a PC, the GM gains a Fear",
+            )),
+            AdversaryRole::Horde => Some((
+                "Horde",
+                "Once it has marked half its HP or more, its attack deals half damage",
+            )),
+            AdversaryRole::Leader => Some((
+                "Rally",
+                "Spend a Fear to spotlight this adversary and up to two allies",
+            )),
+            AdversaryRole::Minion => Some(("Minion", "Defeated by any damage")),
+            AdversaryRole::Ranged => Some((
+                "Keep Away",
+                "Mark a Stress to move out of Melee range when a PC closes in",
+            )),
+            AdversaryRole::Skulk => Some((
+                "Cloaked",
+                "Becomes Hidden when out of sight; its next attack has advantage",
+            )),
+            AdversaryRole::Social => Some((
+                "Connected",
+                "Calls on allies, favors or rumors rather than fighting",
+            )),
+            AdversaryRole::Solo => Some((
+                "Relentless (2)",
+                "Can be spotlighted up to two times per GM turn",
+            )),
+            AdversaryRole::Standard => None,
+            AdversaryRole::Support => Some((
+                "Bolster",
+                "Mark a Stress to give an ally within Close range advantage on their next attack",
+            )),
+        }
+    }
+}
+
+/// Something special an adversary can do, as written on its stat block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AdversaryFeature {
+    pub name: String,
+    pub description: String,
+}
+
+/// An adversary stat block
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Adversary {
+    pub name: String,
+    pub tier: u8,
+    pub role: AdversaryRole,
+    /// Difficulty to hit (used as Evasion in combat)
+    pub difficulty: u8,
+    pub major_threshold: u8,
+    pub severe_threshold: u8,
+    pub hp: u8,
+    pub stress: u8,
+    pub attack_modifier: i8,
+    pub damage: DamageDice,
+    #[serde(default)]
+    pub features: Vec<AdversaryFeature>,
+    /// Features the encounter fires by itself
+    #[serde(default)]
+    pub triggers: Vec<TriggeredFeature>,
+    /// Conditions the adversary can't gain
+    #[serde(default)]
+    pub immunities: Vec<Condition>,
+    /// What it rides into battle
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mount: Option<Mount>,
+}
+
+impl Adversary {
+    /// Create an adversary with generic stats for its tier
+    ///
+    /// Use the `with_*` methods to fill in the real stat block.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::{Adversary, AdversaryRole};
+    ///
+    /// let bear = Adversary::new("Bear", 1, AdversaryRole::Bruiser)
+    ///     .with_difficulty(14)
+    ///     .with_hp(7);
+    ///
+    /// assert_eq!(bear.difficulty, 14);
+    /// assert_eq!(bear.hp, 7);
+    /// ```
+    pub fn new(name: impl Into<String>, tier: u8, role: AdversaryRole) -> Self {
+        let tier = tier.clamp(1, 4);
+        Self {
+            name: name.into(),
+            tier,
+            role,
+            difficulty: Difficulty::adversary(tier).value() as u8,
+            major_threshold: 7 * tier,
+            severe_threshold: 12 * tier,
+            hp: 5,
+            stress: 3,
+            attack_modifier: tier as i8,
+            damage: DamageDice::d8(1).with_bonus(tier as i16),
+            features: Vec::new(),
+            triggers: Vec::new(),
+            immunities: Vec::new(),
+            mount: None,
+        }
+    }
+
+    /// A ready-to-run stat block for an NPC improvised mid-scene
+    ///
+    /// The role's benchmark stats for the tier (see
+    /// [`AdversaryBuilder::benchmark`]), named for the role, with
+    /// a feature that captures how the role plays.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::{Adversary, AdversaryRole};
+    ///
+    /// let guard = Adversary::quick_stat(2, AdversaryRole::Standard);
+    /// assert_eq!(guard.name, "Improvised Standard");
+    /// assert_eq!(guard.difficulty, 14);
+    ///
+    /// let boss = Adversary::quick_stat(2, AdversaryRole::Solo);
+    /// assert!(boss.hp > guard.hp);
+    /// assert_eq!(boss.features[0].name, "Relentless (2)");
+    /// ```
+    ///
+    /// [`AdversaryBuilder::benchmark`]: crate::combat::AdversaryBuilder::benchmark
+    pub fn quick_stat(tier: u8, role: AdversaryRole) -> Self {
+        let tier = tier.clamp(1, 4);
+        let adversary = AdversaryBuilder::benchmark(format!("Improvised {}", role), tier, role);
+        match role.signature_feature() {
+            Some((name, description)) => adversary.with_feature(name, description),
+            None => adversary,
+        }
+    }
+
+    /// Set the difficulty
+    pub fn with_difficulty(mut self, difficulty: u8) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+
+    /// Set the damage thresholds
+    pub fn with_thresholds(mut self, major: u8, severe: u8) -> Self {
+        self.major_threshold = major;
+        self.severe_threshold = severe;
+        self
+    }
+
+    /// Set the HP
+    pub fn with_hp(mut self, hp: u8) -> Self {
+        self.hp = hp;
+        self
+    }
+
+    /// Set the Stress
+    pub fn with_stress(mut self, stress: u8) -> Self {
+        self.stress = stress;
+        self
+    }
+
+    /// Set the standard attack
+    pub fn with_attack(mut self, modifier: i8, damage: DamageDice) -> Self {
+        self.attack_modifier = modifier;
+        self.damage = damage;
+        self
+    }
+
+    /// Add a feature
+    pub fn with_feature(mut self, name: impl Into<String>, description: impl Into<String>) -> Self {
+        self.features.push(AdversaryFeature {
+            name: name.into(),
+            description: description.into(),
+        });
+        self
+    }
+
+    /// Add a passive feature that fires during attacks
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::{Adversary, AdversaryRole, Trigger, TriggerEffect};
+    ///
+    /// let urchin = Adversary::new("Giant Urchin", 1, AdversaryRole::Bruiser).with_trigger(
+    ///     "Spiny Hide",
+    ///     Trigger::DamagedByMelee,
+    ///     TriggerEffect::OpponentMarksStress(1),
+    /// );
+    /// assert_eq!(urchin.to_combatant().triggers.len(), 1);
+    /// ```
+    pub fn with_trigger(
+        mut self,
+        name: impl Into<String>,
+        trigger: Trigger,
+        effect: TriggerEffect,
+    ) -> Self {
+        self.triggers
+            .push(TriggeredFeature::new(name, trigger, effect));
+        self
+    }
+
+    /// Make the adversary immune to a condition
+    pub fn with_immunity(mut self, condition: Condition) -> Self {
+        if !self.immunities.contains(&condition) {
+            self.immunities.push(condition);
+        }
+        self
+    }
+
+    /// Set what it rides
+    pub fn with_mount(mut self, mount: Mount) -> Self {
+        self.mount = Some(mount);
+        self
+    }
+
+    /// Battle point cost of this adversary
+    pub fn battle_points(&self) -> u8 {
+        self.role.battle_points()
+    }
+
+    /// Create an enemy combatant from this stat block
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::{Adversary, AdversaryRole};
+    ///
+    /// let wolf = Adversary::new("Dire Wolf", 1, AdversaryRole::Skulk);
+    /// let combatant = wolf.to_combatant();
+    ///
+    /// assert_eq!(combatant.name, "Dire Wolf");
+    /// assert!(!combatant.is_player());
+    /// assert_eq!(combatant.evasion, wolf.difficulty);
+    /// ```
+    pub fn to_combatant(&self) -> Combatant {
+        let mut builder = CombatantBuilder::adversary(self.name.clone())
+            .with_level(self.tier)
+            .with_hp(self.hp)
+            .with_evasion(self.difficulty)
+            .with_role(self.role);
+        for feature in &self.triggers {
+            builder = builder.with_trigger(feature.clone());
+        }
+        for &condition in &self.immunities {
+            builder = builder.with_immunity(condition);
+        }
+        if let Some(mount) = &self.mount {
+            builder = builder.with_mount(mount.clone());
+        }
+        builder
+            .build()
+            .expect("adversaries always have HP and Difficulty")
+    }
+}
+
+#[cfg(feature = "persistence")]
+impl Persist for Adversary {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_role_count() {
+        assert_eq!(AdversaryRole::iter().count(), 10);
+    }
+
+    #[test]
+    fn test_role_battle_points() {
+        assert_eq!(AdversaryRole::Minion.battle_points(), 1);
+        assert_eq!(AdversaryRole::Social.battle_points(), 1);
+        assert_eq!(AdversaryRole::Support.battle_points(), 1);
+        assert_eq!(AdversaryRole::Horde.battle_points(), 2);
+        assert_eq!(AdversaryRole::Ranged.battle_points(), 2);
+        assert_eq!(AdversaryRole::Skulk.battle_points(), 2);
+        assert_eq!(AdversaryRole::Standard.battle_points(), 2);
+        assert_eq!(AdversaryRole::Leader.battle_points(), 3);
+        assert_eq!(AdversaryRole::Bruiser.battle_points(), 4);
+        assert_eq!(AdversaryRole::Solo.battle_points(), 5);
+    }
+
+    #[test]
+    fn test_heavy_roles() {
+        assert!(AdversaryRole::Bruiser.is_heavy());
+        assert!(AdversaryRole::Solo.is_heavy());
+        assert!(!AdversaryRole::Standard.is_heavy());
+        assert!(!AdversaryRole::Minion.is_heavy());
+    }
+
+    #[test]
+    fn test_quick_stats_follow_the_benchmarks() {
+        for role in AdversaryRole::iter() {
+            for tier in 1..=4 {
+                let quick = Adversary::quick_stat(tier, role);
+                assert!(AdversaryBuilder::from_adversary(quick.clone())
+                    .deviations()
+                    .is_empty());
+                assert_eq!(
+                    quick.features.len(),
+                    usize::from(role != AdversaryRole::Standard)
+                );
+            }
+        }
+        assert_eq!(Adversary::quick_stat(9, AdversaryRole::Minion).tier, 4);
+    }
+
+    #[test]
+    fn test_new_adversary_tier_defaults() {
+        let tier1 = Adversary::new("Bandit", 1, AdversaryRole::Standard);
+        let tier3 = Adversary::new("Knight", 3, AdversaryRole::Standard);
+
+        assert_eq!(tier1.difficulty, 11);
+        assert!(tier3.difficulty > tier1.difficulty);
+        assert!(tier3.major_threshold > tier1.major_threshold);
+        assert!(tier1.severe_threshold > tier1.major_threshold);
+    }
+
+    #[test]
+    fn test_tier_is_clamped() {
+        let adversary = Adversary::new("Thing", 9, AdversaryRole::Solo);
+        assert_eq!(adversary.tier, 4);
+    }
+
+    #[test]
+    fn test_to_combatant() {
+        let adversary = Adversary::new("Ogre", 1, AdversaryRole::Bruiser)
+            .with_hp(8)
+            .with_difficulty(13);
+        let combatant = adversary.to_combatant();
+
+        assert_eq!(combatant.hp.maximum, 8);
+        assert_eq!(combatant.evasion, 13);
+        assert!(!combatant.is_player());
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_adversary_serialization() {
+        let adversary = Adversary::new("Ogre", 1, AdversaryRole::Bruiser);
+
+        let json = serde_json::to_string(&adversary).unwrap();
+        let loaded: Adversary = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(adversary, loaded);
+    }
+}
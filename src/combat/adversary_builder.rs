@@ -0,0 +1,328 @@
+//! Adversary building from role benchmarks
+//!
+//! Homebrew adversaries start from the typical stat block for their role
+//! and tier, modelled on the SRD's benchmarks. GMs then change what makes
+//! the adversary their own, and the builder reports how far each stat has
+//! moved from where the role usually sits.
+
+use crate::combat::adversary::{Adversary, AdversaryRole};
+use crate::core::dice::{DamageDice, Die};
+use crate::core::Difficulty;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+use strum_macros::{Display, EnumIter};
+
+/// A stat compared against the benchmarks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Stat {
+    Difficulty,
+    #[strum(to_string = "Major threshold")]
+    MajorThreshold,
+    #[strum(to_string = "Severe threshold")]
+    SevereThreshold,
+    #[strum(to_string = "HP")]
+    Hp,
+    Stress,
+    #[strum(to_string = "Attack")]
+    AttackModifier,
+    /// Compared by average roll
+    Damage,
+}
+
+impl Stat {
+    /// The stat's value on a stat block, with damage as its average
+    pub fn value(&self, adversary: &Adversary) -> f32 {
+        match self {
+            Stat::Difficulty => adversary.difficulty as f32,
+            Stat::MajorThreshold => adversary.major_threshold as f32,
+            Stat::SevereThreshold => adversary.severe_threshold as f32,
+            Stat::Hp => adversary.hp as f32,
+            Stat::Stress => adversary.stress as f32,
+            Stat::AttackModifier => adversary.attack_modifier as f32,
+            Stat::Damage => adversary.damage.average(),
+        }
+    }
+}
+
+/// How one stat compares with its benchmark
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Deviation {
+    pub stat: Stat,
+    pub benchmark: f32,
+    pub value: f32,
+}
+
+impl Deviation {
+    /// How far above (positive) or below the benchmark the stat is
+    pub fn difference(&self) -> f32 {
+        self.value - self.benchmark
+    }
+
+    /// The difference as a percentage of the benchmark, if it isn't zero
+    pub fn percent(&self) -> Option<f32> {
+        (self.benchmark != 0.0).then(|| self.difference() / self.benchmark.abs() * 100.0)
+    }
+}
+
+/// Stat changes a role makes to its tier's standard adversary
+struct RoleAdjustment {
+    difficulty: i16,
+    major: i16,
+    severe: i16,
+    hp: i16,
+    stress: i16,
+    attack: i8,
+    dice: i16,
+    bonus: i16,
+}
+
+impl RoleAdjustment {
+    fn for_role(role: AdversaryRole) -> Self {
+        let (difficulty, major, severe, hp, stress, attack, dice, bonus) = match role {
+            AdversaryRole::Bruiser => (1, 2, 5, 2, -1, 0, 0, 2),
+            AdversaryRole::Horde => (-1, -2, -3, 1, 0, -2, 0, 0),
+            AdversaryRole::Leader => (2, 1, 2, 1, 1, 1, 0, 1),
+            AdversaryRole::Minion => (-2, 0, 0, 0, 0, -2, 0, 0),
+            AdversaryRole::Ranged => (-1, -1, -2, -1, 0, 1, 0, 0),
+            AdversaryRole::Skulk => (1, -2, -3, -1, 0, 1, 0, 0),
+            AdversaryRole::Social => (0, -2, -4, -2, 0, -3, 0, -2),
+            AdversaryRole::Solo => (2, 3, 6, 3, 1, 2, 1, 0),
+            AdversaryRole::Standard => (0, 0, 0, 0, 0, 0, 0, 0),
+            AdversaryRole::Support => (0, -1, -2, -1, 1, -1, 0, -1),
+        };
+        Self {
+            difficulty,
+            major,
+            severe,
+            hp,
+            stress,
+            attack,
+            dice,
+            bonus,
+        }
+    }
+}
+
+/// Builds an adversary from its role's benchmark, tracking the changes
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::combat::{AdversaryBuilder, AdversaryRole, Stat};
+///
+/// let builder = AdversaryBuilder::new("Tunnel Brute", 2, AdversaryRole::Bruiser)
+///     .with_hp(12)
+///     .with_feature("Cave-in", "Spend a Fear to bring down the ceiling");
+///
+/// let deviations = builder.deviations();
+/// assert_eq!(deviations.len(), 1);
+/// assert_eq!(deviations[0].stat, Stat::Hp);
+/// assert!(deviations[0].difference() > 0.0);
+///
+/// let brute = builder.build();
+/// assert_eq!(brute.features[0].name, "Cave-in");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct AdversaryBuilder {
+    adversary: Adversary,
+    benchmark: Adversary,
+}
+
+impl AdversaryBuilder {
+    /// Start from the benchmark stat block for a role at a tier (1-4)
+    pub fn new(name: impl Into<String>, tier: u8, role: AdversaryRole) -> Self {
+        let benchmark = Self::benchmark(name, tier, role);
+        Self {
+            adversary: benchmark.clone(),
+            benchmark,
+        }
+    }
+
+    /// Start from an existing stat block, compared with its role's
+    /// benchmark
+    pub fn from_adversary(adversary: Adversary) -> Self {
+        Self {
+            benchmark: Self::benchmark(adversary.name.clone(), adversary.tier, adversary.role),
+            adversary,
+        }
+    }
+
+    /// The typical stat block for a role at a tier (1-4)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::{AdversaryBuilder, AdversaryRole};
+    ///
+    /// let standard = AdversaryBuilder::benchmark("Guard", 1, AdversaryRole::Standard);
+    /// let solo = AdversaryBuilder::benchmark("Dragon", 1, AdversaryRole::Solo);
+    /// assert!(solo.hp > standard.hp);
+    /// assert_eq!(standard.damage.to_string(), "1d8+2");
+    /// ```
+    pub fn benchmark(name: impl Into<String>, tier: u8, role: AdversaryRole) -> Adversary {
+        let tier = tier.clamp(1, 4);
+        let step = tier as i16;
+        let role_change = RoleAdjustment::for_role(role);
+        let stat = |base: i16, change: i16| (base + change).clamp(1, u8::MAX as i16) as u8;
+
+        let difficulty = Difficulty::adversary(tier).value() as i16;
+        let (major, severe, hp, stress, damage) = if role == AdversaryRole::Minion {
+            // Minions go down to any damage and hit for a flat amount
+            let flat = [2, 4, 6, 10][tier as usize - 1];
+            (1, 1, 1, 1, DamageDice::new(vec![]).with_bonus(flat))
+        } else {
+            let die = if tier == 4 { Die::D10 } else { Die::D8 };
+            let dice = (step + role_change.dice).max(1) as usize;
+            let bonus = [2, 3, 4, 6][tier as usize - 1] + role_change.bonus;
+            (
+                stat(7 * step, role_change.major),
+                stat(12 * step, role_change.severe),
+                stat(4 + step, role_change.hp),
+                stat([3, 3, 4, 5][tier as usize - 1], role_change.stress),
+                DamageDice::new(vec![die; dice]).with_bonus(bonus.max(0)),
+            )
+        };
+
+        Adversary::new(name, tier, role)
+            .with_difficulty(stat(difficulty, role_change.difficulty))
+            .with_thresholds(major, severe)
+            .with_hp(hp)
+            .with_stress(stress)
+            .with_attack(step as i8 + role_change.attack, damage)
+    }
+
+    /// Set the Difficulty
+    pub fn with_difficulty(mut self, difficulty: u8) -> Self {
+        self.adversary.difficulty = difficulty;
+        self
+    }
+
+    /// Set the damage thresholds
+    pub fn with_thresholds(mut self, major: u8, severe: u8) -> Self {
+        self.adversary.major_threshold = major;
+        self.adversary.severe_threshold = severe;
+        self
+    }
+
+    /// Set the HP
+    pub fn with_hp(mut self, hp: u8) -> Self {
+        self.adversary.hp = hp;
+        self
+    }
+
+    /// Set the Stress
+    pub fn with_stress(mut self, stress: u8) -> Self {
+        self.adversary.stress = stress;
+        self
+    }
+
+    /// Set the standard attack
+    pub fn with_attack(mut self, modifier: i8, damage: DamageDice) -> Self {
+        self.adversary.attack_modifier = modifier;
+        self.adversary.damage = damage;
+        self
+    }
+
+    /// Add a feature
+    pub fn with_feature(mut self, name: impl Into<String>, description: impl Into<String>) -> Self {
+        self.adversary = self.adversary.with_feature(name, description);
+        self
+    }
+
+    /// The stat block so far
+    pub fn adversary(&self) -> &Adversary {
+        &self.adversary
+    }
+
+    /// The stat block it started from
+    pub fn benchmark_stats(&self) -> &Adversary {
+        &self.benchmark
+    }
+
+    /// Every stat compared with the benchmark, in stat block order
+    pub fn compare(&self) -> Vec<Deviation> {
+        Stat::iter()
+            .map(|stat| Deviation {
+                stat,
+                benchmark: stat.value(&self.benchmark),
+                value: stat.value(&self.adversary),
+            })
+            .collect()
+    }
+
+    /// Only the stats that differ from the benchmark
+    pub fn deviations(&self) -> Vec<Deviation> {
+        self.compare()
+            .into_iter()
+            .filter(|deviation| deviation.difference() != 0.0)
+            .collect()
+    }
+
+    /// Finish the stat block
+    pub fn build(self) -> Adversary {
+        self.adversary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_benchmarks_grow_with_tier() {
+        for role in AdversaryRole::iter().filter(|&r| r != AdversaryRole::Minion) {
+            for tier in 1..4 {
+                let lower = AdversaryBuilder::benchmark("A", tier, role);
+                let higher = AdversaryBuilder::benchmark("B", tier + 1, role);
+                assert!(higher.difficulty > lower.difficulty, "{} difficulty", role);
+                assert!(higher.severe_threshold > lower.severe_threshold);
+                assert!(higher.damage.average() > lower.damage.average());
+                assert!(lower.major_threshold < lower.severe_threshold);
+            }
+        }
+    }
+
+    #[test]
+    fn test_minions_go_down_in_one_hit() {
+        let minion = AdversaryBuilder::benchmark("Lackey", 3, AdversaryRole::Minion);
+        assert_eq!(minion.hp, 1);
+        assert_eq!(minion.damage.to_string(), "6");
+    }
+
+    #[test]
+    fn test_unchanged_builder_matches_benchmark() {
+        let builder = AdversaryBuilder::new("Guard", 2, AdversaryRole::Standard);
+        assert!(builder.deviations().is_empty());
+        assert_eq!(builder.compare().len(), Stat::iter().count());
+        assert_eq!(
+            builder.build(),
+            AdversaryBuilder::benchmark("Guard", 2, AdversaryRole::Standard)
+        );
+    }
+
+    #[test]
+    fn test_deviations() {
+        let builder = AdversaryBuilder::new("Glass Cannon", 1, AdversaryRole::Ranged)
+            .with_hp(2)
+            .with_attack(3, DamageDice::d8(2).with_bonus(2));
+        let deviations = builder.deviations();
+        let stats: Vec<Stat> = deviations.iter().map(|d| d.stat).collect();
+        assert_eq!(stats, vec![Stat::Hp, Stat::AttackModifier, Stat::Damage]);
+
+        let hp = deviations[0];
+        assert_eq!(hp.benchmark, 4.0);
+        assert_eq!(hp.difference(), -2.0);
+        assert_eq!(hp.percent(), Some(-50.0));
+    }
+
+    #[test]
+    fn test_from_adversary() {
+        let bear = AdversaryBuilder::from_adversary(
+            Adversary::new("Bear", 1, AdversaryRole::Bruiser).with_hp(10),
+        );
+        assert_eq!(bear.benchmark_stats().role, AdversaryRole::Bruiser);
+        assert!(bear.deviations().iter().any(|d| d.stat == Stat::Hp));
+    }
+}
@@ -0,0 +1,284 @@
+//! GM move advisor - what the GM could do next
+//!
+//! Looks at an encounter and lists the GM's legal options: which
+//! adversaries can act, who each would go after under a [`TargetPolicy`],
+//! and which Fear moves the pool can pay for. Nothing is rolled or
+//! spent; the same policies the Monte Carlo simulator plays with are
+//! only asked for a suggestion.
+
+use crate::combat::conditions::Condition;
+use crate::combat::montecarlo::TargetPolicy;
+use crate::combat::movement::RangeBand;
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId, TurnModel};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Fear it costs to spotlight an adversary out of turn
+pub const SPOTLIGHT_FEAR: u8 = 1;
+
+/// What an adversary would do with the spotlight
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum GmMove {
+    /// Attack the target the policy picked
+    Attack {
+        target: CombatantId,
+        range: RangeBand,
+    },
+    /// Everyone on the other side is Hidden; look for one of them
+    Search { target: CombatantId },
+    /// Nobody left to go after
+    Hold,
+}
+
+/// An adversary that can take the spotlight
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AdversaryOption {
+    pub combatant: CombatantId,
+    pub name: String,
+    /// False while Restrained; it can still act where it stands
+    pub can_move: bool,
+    pub suggestion: GmMove,
+}
+
+/// Something the GM can spend Fear on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FearOption {
+    /// The adversary whose feature it is, if any
+    pub combatant: Option<CombatantId>,
+    pub name: String,
+    pub fear: u8,
+    /// Whether the pool covers the cost right now
+    pub affordable: bool,
+}
+
+/// The GM's options at this point in the encounter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GmAdvice {
+    pub fear: u8,
+    /// Adversary actions waiting on the tracker, under action tokens
+    pub tracked_actions: Option<u8>,
+    pub adversaries: Vec<AdversaryOption>,
+    pub fear_options: Vec<FearOption>,
+}
+
+impl GmAdvice {
+    /// Fear options the pool can pay for
+    pub fn affordable(&self) -> impl Iterator<Item = &FearOption> {
+        self.fear_options.iter().filter(|option| option.affordable)
+    }
+}
+
+impl CombatEncounter {
+    /// List the GM's options, picking targets with `policy`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::advisor::GmMove;
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder, TargetPolicy};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let grom = encounter.add_combatant(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Goblin")
+    ///         .with_hp(4)
+    ///         .with_evasion(10)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// let advice = encounter.gm_advice(TargetPolicy::Weakest);
+    /// assert_eq!(advice.adversaries.len(), 1);
+    /// assert!(matches!(
+    ///     advice.adversaries[0].suggestion,
+    ///     GmMove::Attack { target, .. } if target == grom
+    /// ));
+    /// ```
+    pub fn gm_advice(&self, policy: TargetPolicy) -> GmAdvice {
+        let mut rng = rand::thread_rng();
+        self.gm_advice_with_rng(policy, &mut rng)
+    }
+
+    /// List the GM's options with a specific RNG for random targeting
+    pub fn gm_advice_with_rng<R: Rng>(&self, policy: TargetPolicy, rng: &mut R) -> GmAdvice {
+        let (hidden, opponents): (Vec<&Combatant>, Vec<&Combatant>) = self
+            .combatants
+            .iter()
+            .filter(|c| c.is_player() && c.is_alive())
+            .partition(|c| c.has_condition(Condition::Hidden));
+
+        let acting: Vec<&Combatant> = self
+            .combatants
+            .iter()
+            .filter(|c| !c.is_player() && c.is_alive())
+            .collect();
+
+        let adversaries = acting
+            .iter()
+            .map(|adversary| {
+                let suggestion = match policy.choose_with_rng(&opponents, rng) {
+                    Some(target) => GmMove::Attack {
+                        target: target.id,
+                        range: RangeBand::from_zones(adversary.zone.abs_diff(target.zone)),
+                    },
+                    None => match hidden.first() {
+                        Some(target) => GmMove::Search { target: target.id },
+                        None => GmMove::Hold,
+                    },
+                };
+                AdversaryOption {
+                    combatant: adversary.id,
+                    name: adversary.name.to_string(),
+                    can_move: !adversary.has_condition(Condition::Restrained),
+                    suggestion,
+                }
+            })
+            .collect();
+
+        let mut fear_options = Vec::new();
+        if !acting.is_empty() {
+            fear_options.push(FearOption {
+                combatant: None,
+                name: String::from("Spotlight an adversary"),
+                fear: SPOTLIGHT_FEAR,
+                affordable: self.fear.current >= SPOTLIGHT_FEAR,
+            });
+        }
+        for adversary in &acting {
+            for reaction in adversary.reactions.iter().filter(|r| r.cost.fear > 0) {
+                fear_options.push(FearOption {
+                    combatant: Some(adversary.id),
+                    name: reaction.name.clone(),
+                    fear: reaction.cost.fear,
+                    affordable: self.can_afford(adversary.id, &reaction.cost),
+                });
+            }
+        }
+
+        GmAdvice {
+            fear: self.fear.current,
+            tracked_actions: (self.turn_model == TurnModel::ActionTokens)
+                .then_some(self.action_tokens.tracker),
+            adversaries,
+            fear_options,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Class};
+    use crate::combat::reactions::{Reaction, ReactionEffect, ReactionWindow};
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::test_support::player;
+
+    fn hero(name: &str, hp: u8) -> Combatant {
+        player(name, Class::Warrior, Ancestry::Human)
+            .with_hp(hp)
+            .build()
+            .unwrap()
+    }
+
+    fn create_test_encounter() -> (CombatEncounter, CombatantId, CombatantId, CombatantId) {
+        let mut encounter = CombatEncounter::new(5);
+        let grom = encounter.add_combatant(hero("Grom", 6));
+        let lia = encounter.add_combatant(hero("Lia", 3));
+        let ogre = encounter.add_combatant(
+            CombatantBuilder::adversary("Ogre")
+                .with_hp(8)
+                .with_evasion(10)
+                .with_zone(2)
+                .with_reaction(
+                    Reaction::new(
+                        "Bellow",
+                        ReactionWindow::AttackDeclared,
+                        ReactionEffect::EvasionBonus(2),
+                    )
+                    .costing_fear(2),
+                )
+                .build()
+                .unwrap(),
+        );
+        (encounter, grom, lia, ogre)
+    }
+
+    #[test]
+    fn test_targets_follow_the_policy() {
+        let (encounter, grom, lia, ogre) = create_test_encounter();
+        let weakest = encounter.gm_advice(TargetPolicy::Weakest);
+        assert_eq!(weakest.adversaries.len(), 1);
+        assert_eq!(weakest.adversaries[0].combatant, ogre);
+        assert_eq!(
+            weakest.adversaries[0].suggestion,
+            GmMove::Attack {
+                target: lia,
+                range: RangeBand::Close,
+            }
+        );
+        assert!(matches!(
+            encounter.gm_advice(TargetPolicy::Strongest).adversaries[0].suggestion,
+            GmMove::Attack { target, .. } if target == grom
+        ));
+    }
+
+    #[test]
+    fn test_hidden_targets_call_for_a_search() {
+        let (mut encounter, grom, _, _) = create_test_encounter();
+        encounter.combatants[0].conditions.push(Condition::Hidden);
+        encounter.combatants[1].hp.current = 0;
+        let advice = encounter.gm_advice(TargetPolicy::Random);
+        assert_eq!(
+            advice.adversaries[0].suggestion,
+            GmMove::Search { target: grom }
+        );
+
+        encounter.combatants[0].hp.current = 0;
+        let advice = encounter.gm_advice(TargetPolicy::Random);
+        assert_eq!(advice.adversaries[0].suggestion, GmMove::Hold);
+    }
+
+    #[test]
+    fn test_fear_options_check_the_pool() {
+        let (mut encounter, _, _, ogre) = create_test_encounter();
+        let advice = encounter.gm_advice(TargetPolicy::Weakest);
+        assert_eq!(advice.fear_options.len(), 2);
+        assert_eq!(advice.affordable().count(), 0);
+
+        encounter.fear.gain(1);
+        let advice = encounter.gm_advice(TargetPolicy::Weakest);
+        let affordable: Vec<_> = advice.affordable().map(|o| o.name.as_str()).collect();
+        assert_eq!(affordable, ["Spotlight an adversary"]);
+
+        encounter.fear.gain(1);
+        let advice = encounter.gm_advice(TargetPolicy::Weakest);
+        assert_eq!(advice.affordable().count(), 2);
+        assert_eq!(advice.fear_options[1].combatant, Some(ogre));
+        assert_eq!(advice.tracked_actions, None);
+    }
+
+    #[test]
+    fn test_restraints_and_the_tracker() {
+        let (mut encounter, grom, _, _) = create_test_encounter();
+        encounter.combatants[2]
+            .conditions
+            .push(Condition::Restrained);
+        encounter.turn_model = TurnModel::ActionTokens;
+        encounter.start();
+        encounter.spend_action_token(grom).unwrap();
+
+        let advice = encounter.gm_advice(TargetPolicy::Weakest);
+        assert!(!advice.adversaries[0].can_move);
+        assert_eq!(advice.tracked_actions, Some(1));
+    }
+}
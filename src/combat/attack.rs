@@ -1,13 +1,40 @@
 //! Combat system - Attack resolution
 
-use crate::core::dice::{DualityResult, DualityRoll};
+use crate::cards::Range;
+#[cfg(feature = "std")]
+use crate::combat::conditions::Condition;
+#[cfg(feature = "std")]
+use crate::combat::modifiers::RollKind;
+#[cfg(feature = "std")]
+use crate::combat::simulation::Combatant;
+#[cfg(feature = "std")]
+use crate::core::dice::damage::convolve;
+#[cfg(feature = "std")]
+use crate::core::dice::DamageDice;
+use crate::core::dice::{Die, DualityResult, DualityRoll};
+#[cfg(feature = "std")]
+use crate::core::stats::{crit_chance, duality_success_chance};
+#[cfg(feature = "std")]
+use crate::error::EngineError;
+#[cfg(feature = "std")]
+use crate::items::Weapon;
+#[cfg(feature = "std")]
+use crate::rules::{CritRule, RulesConfig, ThresholdRule};
+use alloc::vec::Vec;
+use rand::Rng;
 use serde::{Deserialize, Serialize};
 
 /// An attack action with modifiers
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Attack {
     pub modifier: i8,
+    #[serde(default)]
     pub with_advantage: bool,
+    /// Where the attack comes from, when known; triggered features
+    /// treat Very Close as melee
+    #[serde(default)]
+    pub range: Option<Range>,
 }
 
 impl Attack {
@@ -17,17 +44,60 @@ impl Attack {
     ///
     /// ```
     /// use daggerheart_engine::combat::Attack;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
     ///
     /// let attack = Attack::new(2);  // +2 to attack
-    /// let result = attack.roll();
+    /// let result = attack.roll_with_rng(&mut StdRng::seed_from_u64(1));
     /// ```
     pub fn new(modifier: i8) -> Self {
         Self {
             modifier,
             with_advantage: false,
+            range: None,
         }
     }
 
+    #[cfg(feature = "std")]
+    /// An attack with a weapon, its modifier worked out from the character
+    ///
+    /// Adds the character's modifier for the weapon's trait, its features'
+    /// attack bonus and any active effects on attack rolls. Proficiency
+    /// doesn't change the roll; it sets how many dice the weapon's damage
+    /// rolls, via [`Weapon::damage`] and [`Combatant::proficiency`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::{Attack, CombatantBuilder};
+    /// use daggerheart_engine::items::Weapon;
+    ///
+    /// let grom = CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///     .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// let longsword = Weapon::named("Longsword").unwrap();
+    ///
+    /// // Longswords attack with Agility
+    /// let attack = Attack::from_character(&grom, &longsword).unwrap();
+    /// assert_eq!(attack.modifier, 2);
+    /// assert_eq!(longsword.damage(grom.proficiency()).to_string(), "1d10+3");
+    /// ```
+    pub fn from_character(character: &Combatant, weapon: &Weapon) -> Result<Self, EngineError> {
+        let attributes = character
+            .attributes()
+            .ok_or_else(|| EngineError::MissingStat {
+                combatant: character.name.to_string(),
+                stat: "attributes",
+            })?;
+        let modifier = attributes
+            .get_modifier(weapon.attack_trait)
+            .saturating_add(weapon.attack_bonus())
+            .saturating_add(character.total_bonus_for(RollKind::Attack));
+        Ok(Self::new(modifier).with_range(weapon.range))
+    }
+
     /// Add advantage to this attack
     ///
     /// # Examples
@@ -42,6 +112,28 @@ impl Attack {
         self
     }
 
+    /// Set the range the attack is made from
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::cards::Range;
+    /// use daggerheart_engine::combat::Attack;
+    ///
+    /// let attack = Attack::new(1).with_range(Range::VeryClose);
+    /// assert!(attack.is_melee());
+    /// ```
+    pub fn with_range(mut self, range: Range) -> Self {
+        self.range = Some(range);
+        self
+    }
+
+    /// Whether the attack is made in melee
+    pub fn is_melee(&self) -> bool {
+        self.range == Some(Range::VeryClose)
+    }
+
+    #[cfg(feature = "std")]
     /// Roll the attack
     ///
     /// # Examples
@@ -50,16 +142,25 @@ impl Attack {
     /// use daggerheart_engine::combat::Attack;
     ///
     /// let attack = Attack::new(2);
-    /// let result = attack.roll();
+    /// let result = attack.roll().against(13);
     ///
-    /// if result.success {
+    /// if result.hit {
     ///     println!("Hit! Total: {}", result.total);
     /// }
     /// ```
     pub fn roll(&self) -> AttackResult {
-        let duality_roll = DualityRoll::roll();
+        let mut rng = rand::thread_rng();
+        self.roll_with_rng(&mut rng)
+    }
+
+    /// Roll the attack with a specific RNG (for testing/seeding)
+    pub fn roll_with_rng<R: Rng>(&self, rng: &mut R) -> AttackResult {
+        let duality_roll = DualityRoll::roll_with_rng(rng);
         let duality_result = if self.with_advantage {
-            duality_roll.with_advantage()
+            let mut result = duality_roll.with_advantage_with_rng(rng);
+            result.modifier = self.modifier;
+            result.total = (result.total as i16 + self.modifier as i16).max(0) as u16;
+            result
         } else {
             duality_roll.with_modifier(self.modifier)
         };
@@ -68,14 +169,67 @@ impl Attack {
 }
 
 /// Result of an attack roll
+///
+/// Whether the attack hits and which die was higher are separate: an
+/// attack that meets the target's Evasion hits even with Fear, and one
+/// that falls short misses even with Hope.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "StoredAttackResult")]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AttackResult {
     pub hope: u16,
     pub fear: u16,
     pub modifier: i8,
-    pub success: bool,
-    pub critical: bool,
     pub total: u16,
+    /// The Hope die rolled higher
+    pub with_hope: bool,
+    /// The Fear die rolled higher
+    pub with_fear: bool,
+    /// Doubles, which always hit
+    pub critical: bool,
+    /// Whether the attack hit; only known once checked `against` an Evasion
+    pub hit: bool,
+    /// The advantage d6, if one was rolled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub advantage_die: Option<u8>,
+    /// Every damage die rolled on a hit, dropped and exploded dice included
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub damage_dice: Vec<(Die, u8)>,
+}
+
+/// An attack result as saved, before `hit` was split from the dice
+#[derive(Deserialize)]
+struct StoredAttackResult {
+    hope: u16,
+    fear: u16,
+    modifier: i8,
+    total: u16,
+    critical: bool,
+    // Older saves only recorded `success` (Hope over Fear), which says
+    // nothing about the hit
+    #[serde(default)]
+    hit: bool,
+    #[serde(default)]
+    advantage_die: Option<u8>,
+    #[serde(default)]
+    damage_dice: Vec<(Die, u8)>,
+}
+
+impl From<StoredAttackResult> for AttackResult {
+    fn from(stored: StoredAttackResult) -> Self {
+        Self {
+            hope: stored.hope,
+            fear: stored.fear,
+            modifier: stored.modifier,
+            total: stored.total,
+            with_hope: stored.hope > stored.fear,
+            with_fear: stored.fear > stored.hope,
+            critical: stored.critical,
+            hit: stored.hit,
+            advantage_die: stored.advantage_die,
+            damage_dice: stored.damage_dice,
+        }
+    }
 }
 
 impl AttackResult {
@@ -91,47 +245,273 @@ impl AttackResult {
     /// let duality = roll.with_modifier(2);
     /// let result = AttackResult::from_duality_result(duality);
     ///
-    /// assert!(result.success);  // Hope > Fear
+    /// assert!(result.with_hope);
+    /// assert!(!result.hit); // Not checked against a target yet
     /// ```
     pub fn from_duality_result(duality: DualityResult) -> Self {
         let hope = duality.roll.hope as u16;
         let fear = duality.roll.fear as u16;
-        let success = duality.roll.hope > duality.roll.fear;
-        let critical = duality.is_critical;
 
         Self {
             hope,
             fear,
             modifier: duality.modifier,
-            success,
-            critical,
             total: duality.total,
+            with_hope: hope > fear,
+            with_fear: fear > hope,
+            critical: duality.is_critical,
+            hit: false,
+            advantage_die: duality.advantage_die,
+            damage_dice: Vec::new(),
         }
     }
 
     /// Check if this attack beats the target's evasion
     ///
+    /// The total has to meet the Evasion, whichever die is higher; a
+    /// critical always hits.
+    ///
     /// # Examples
     ///
     /// ```
     /// use daggerheart_engine::core::dice::DualityRoll;
     /// use daggerheart_engine::combat::AttackResult;
     ///
-    /// let roll = DualityRoll::from_values(10, 5);
-    /// let duality = roll.with_modifier(2);
-    /// let result = AttackResult::from_duality_result(duality);
+    /// // With Fear, but 5 + 10 + 2 still meets Evasion 17
+    /// let roll = DualityRoll::from_values(5, 10);
+    /// let result = AttackResult::from_duality_result(roll.with_modifier(2));
     ///
-    /// assert!(result.beats_evasion(12));  // Total is Hope+Fear+mod
+    /// assert!(result.with_fear);
+    /// assert!(result.beats_evasion(17));
+    /// assert!(!result.beats_evasion(18));
     /// ```
     pub fn beats_evasion(&self, evasion: u8) -> bool {
-        self.success && self.total >= evasion as u16
+        self.critical || self.total >= evasion as u16
+    }
+
+    /// Record whether this attack hit a target with this Evasion
+    pub fn against(mut self, evasion: u8) -> Self {
+        self.hit = self.beats_evasion(evasion);
+        self
+    }
+}
+
+#[cfg(feature = "std")]
+/// An attack's chances before it's rolled
+///
+/// Combines the attack roll against the target's Evasion, the damage it
+/// deals on a hit or a critical, and how the target's thresholds and
+/// armor turn that damage into Hit Points.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::combat::{Attack, AttackOutcome, CombatantBuilder};
+/// use daggerheart_engine::core::dice::DamageDice;
+///
+/// let bear = CombatantBuilder::adversary("Bear")
+///     .with_hp(6)
+///     .with_evasion(12)
+///     .build()
+///     .unwrap();
+/// let outcome = AttackOutcome::new(Attack::new(2), DamageDice::d10(1).with_bonus(3));
+///
+/// let loss = outcome.expected_hp_loss(&bear);
+/// assert!(loss > 0.0 && loss < 2.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct AttackOutcome {
+    pub attack: Attack,
+    pub damage: DamageDice,
+    pub crit: CritRule,
+    pub thresholds: ThresholdRule,
+}
+
+#[cfg(feature = "std")]
+impl AttackOutcome {
+    /// An attack dealing `damage`, under the default rules
+    pub fn new(attack: Attack, damage: DamageDice) -> Self {
+        Self {
+            attack,
+            damage,
+            crit: CritRule::default(),
+            thresholds: ThresholdRule::default(),
+        }
+    }
+
+    /// Use a table's crit and threshold rules
+    pub fn with_rules(mut self, rules: &RulesConfig) -> Self {
+        self.crit = rules.crit;
+        self.thresholds = rules.thresholds;
+        self
+    }
+
+    /// Chance the attack hits `target`, criticals included
+    ///
+    /// A Vulnerable target is attacked with advantage, as in an encounter.
+    pub fn hit_chance(&self, target: &Combatant) -> f64 {
+        let (hits, _) = self.roll_chances(target);
+        hits
+    }
+
+    /// Chance of each amount of raw damage on a hit, indexed by the amount
+    pub fn damage_distribution(&self, critical: bool) -> Vec<f64> {
+        let rolled = self.damage.distribution();
+        if critical {
+            self.critical_distribution(&rolled)
+        } else {
+            rolled
+        }
+    }
+
+    /// Raw damage on a critical, from the chances of the rolled damage
+    fn critical_distribution(&self, rolled: &[f64]) -> Vec<f64> {
+        match self.crit {
+            CritRule::Rolled => rolled.to_vec(),
+            CritRule::MaxDice => {
+                let mut shifted = vec![0.0; self.damage.dice_maximum() as usize];
+                shifted.extend_from_slice(rolled);
+                shifted
+            }
+            CritRule::DoubleDice => {
+                // Without a penalty to floor at 0, the dice alone are the
+                // rolled damage less the bonus
+                let bonus = self.damage.bonus();
+                let dice = match usize::try_from(bonus) {
+                    Ok(bonus) => rolled[bonus.min(rolled.len())..].to_vec(),
+                    Err(_) => self.damage.clone().with_bonus(0).distribution(),
+                };
+                convolve(rolled, &dice)
+            }
+        }
+    }
+
+    /// The Hit Points `target` can expect to mark from one attack
+    ///
+    /// Misses mark none; hits and criticals go through the target's
+    /// thresholds and armor as [`Combatant::measure_damage_with`] does.
+    pub fn expected_hp_loss(&self, target: &Combatant) -> f64 {
+        let (hits, crits) = self.roll_chances(target);
+        let hp_lost = |chances: &[f64]| -> f64 {
+            chances
+                .iter()
+                .enumerate()
+                .map(|(raw, chance)| {
+                    let measured = target.measure_damage_with(raw as u16, self.thresholds);
+                    chance * measured.hp_lost as f64
+                })
+                .sum()
+        };
+        let rolled = self.damage.distribution();
+        let mut loss = (hits - crits) * hp_lost(&rolled);
+        if crits > 0.0 {
+            loss += crits * hp_lost(&self.critical_distribution(&rolled));
+        }
+        loss
+    }
+
+    /// Chances of hitting at all and of a critical
+    fn roll_chances(&self, target: &Combatant) -> (f64, f64) {
+        let advantage = self.attack.with_advantage || target.has_condition(Condition::Vulnerable);
+        let hits = duality_success_chance(self.attack.modifier, target.evasion as u16, advantage);
+        (hits, crit_chance())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "std")]
+    use crate::cards::{CardEffect, Duration, Target};
+    #[cfg(feature = "std")]
+    use crate::character::{Ancestry, Attributes, Class};
+    #[cfg(feature = "std")]
+    use crate::combat::simulation::CombatantBuilder;
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_attack_from_character() {
+        let mut rogue = CombatantBuilder::player("Nyx", Class::Rogue, Ancestry::Katari)
+            .with_attributes(Attributes::from_array([1, -1, 2, 0, 1, 0]).unwrap())
+            .build()
+            .unwrap();
+        let dagger = Weapon::named("Dagger").unwrap();
+        assert_eq!(Attack::from_character(&rogue, &dagger).unwrap().modifier, 2);
+
+        let blessing = CardEffect::modifier(1, Target::Ally, Duration::Rounds(2), "attack rolls");
+        rogue.apply_effect("Blessing", &blessing);
+        assert_eq!(Attack::from_character(&rogue, &dagger).unwrap().modifier, 3);
+
+        let goblin = CombatantBuilder::adversary("Goblin")
+            .with_hp(4)
+            .with_evasion(10)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            Attack::from_character(&goblin, &dagger),
+            Err(EngineError::MissingStat {
+                stat: "attributes",
+                ..
+            })
+        ));
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_attack_outcome_chances() {
+        let wall = CombatantBuilder::adversary("Wall")
+            .with_hp(10)
+            .with_evasion(40)
+            .build()
+            .unwrap();
+        let outcome = AttackOutcome::new(Attack::new(0), DamageDice::d4(0).with_bonus(20));
+        // Only criticals reach Evasion 40
+        assert_eq!(outcome.hit_chance(&wall), 12.0 / 144.0);
+        let marked = wall.measure_damage_with(20, ThresholdRule::Flat).hp_lost as f64;
+        assert!((outcome.expected_hp_loss(&wall) - marked * 12.0 / 144.0).abs() < 1e-12);
+
+        let mut exposed = wall.clone();
+        exposed.evasion = 20;
+        let plain = outcome.hit_chance(&exposed);
+        exposed.conditions.push(Condition::Vulnerable);
+        assert!(outcome.hit_chance(&exposed) > plain);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_attack_outcome_follows_crit_rules() {
+        let bear = CombatantBuilder::adversary("Bear")
+            .with_hp(6)
+            .with_evasion(14)
+            .build()
+            .unwrap();
+        let rolled = AttackOutcome::new(Attack::new(1), DamageDice::d8(1).with_bonus(2));
+        let rules = RulesConfig::default().with_crit(CritRule::MaxDice);
+        let max_dice = rolled.clone().with_rules(&rules);
+
+        let crit = max_dice.damage_distribution(true);
+        assert_eq!(crit.len(), rolled.damage_distribution(true).len() + 8);
+        assert!((crit[11] - 1.0 / 8.0).abs() < 1e-12);
+        assert!(max_dice.expected_hp_loss(&bear) > rolled.expected_hp_loss(&bear));
+
+        // Doubled dice roll 2d8+2 on a critical, with or without a penalty
+        let double_dice =
+            rolled.with_rules(&RulesConfig::default().with_crit(CritRule::DoubleDice));
+        let crit = double_dice.damage_distribution(true);
+        let expected = DamageDice::d8(2).with_bonus(2).distribution();
+        assert_eq!(crit.len(), expected.len());
+        assert!(crit
+            .iter()
+            .zip(&expected)
+            .all(|(a, b)| (a - b).abs() < 1e-12));
+        let mut penalty = double_dice.clone();
+        penalty.damage = DamageDice::d8(1).with_bonus(-2);
+        let crit = penalty.damage_distribution(true);
+        // The penalty floors the rolled d8 at 0 before the extra d8
+        assert!((crit[1] - 2.0 / 64.0).abs() < 1e-12);
+    }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_attack_with_positive_modifier() {
         // Attack with +2 modifier
@@ -147,25 +527,64 @@ mod tests {
     }
 
     #[test]
-    fn test_attack_success_when_hope_higher() {
+    fn test_advantage_keeps_modifier() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let plain = Attack::new(3).roll_with_rng(&mut StdRng::seed_from_u64(7));
+        let advantage = Attack::new(3)
+            .with_advantage()
+            .roll_with_rng(&mut StdRng::seed_from_u64(7));
+
+        assert_eq!(advantage.modifier, 3);
+        assert!(advantage.total > plain.total);
+        assert!(advantage.total <= plain.total + 6);
+    }
+
+    #[test]
+    fn test_attack_with_hope_when_hope_higher() {
         // Create attack result where Hope wins
         let roll = DualityRoll::from_values(10, 5);
         let duality = roll.with_modifier(2);
         let result = AttackResult::from_duality_result(duality);
 
-        assert!(result.success, "Attack should succeed when Hope > Fear");
+        assert!(result.with_hope, "Hope > Fear should be with Hope");
+        assert!(!result.with_fear);
         assert_eq!(result.hope, 10);
         assert_eq!(result.fear, 5);
     }
 
     #[test]
-    fn test_attack_failure_when_fear_higher() {
+    fn test_attack_with_fear_can_still_hit() {
         // Create attack result where Fear wins
         let roll = DualityRoll::from_values(5, 10);
         let duality = roll.with_modifier(2);
-        let result = AttackResult::from_duality_result(duality);
+        let result = AttackResult::from_duality_result(duality).against(12);
 
-        assert!(!result.success, "Attack should fail when Fear > Hope");
+        assert!(result.with_fear, "Fear > Hope should be with Fear");
+        assert!(result.hit, "17 meets Evasion 12 whichever die is higher");
+        assert!(!result.clone().against(18).hit);
+    }
+
+    #[test]
+    fn test_critical_always_hits() {
+        let result =
+            AttackResult::from_duality_result(DualityRoll::from_values(1, 1).with_modifier(0));
+
+        assert!(result.critical);
+        assert!(!result.with_hope && !result.with_fear);
+        assert!(result.against(30).hit);
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_old_saves_load() {
+        let json =
+            r#"{"hope":10,"fear":5,"modifier":2,"success":true,"critical":false,"total":17}"#;
+        let result: AttackResult = serde_json::from_str(json).unwrap();
+
+        assert!(result.with_hope);
+        assert!(!result.hit);
     }
 
     #[test]
@@ -199,6 +618,7 @@ mod tests {
         assert!(!result.critical, "Non-doubles should not be critical");
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_attack_with_advantage() {
         // Attack with advantage should work
@@ -210,6 +630,18 @@ mod tests {
         assert!(result.fear >= 1 && result.fear <= 12);
     }
 
+    #[test]
+    fn test_roll_with_rng_reproducible() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let attack = Attack::new(1).with_advantage();
+        let result1 = attack.roll_with_rng(&mut StdRng::seed_from_u64(3));
+        let result2 = attack.roll_with_rng(&mut StdRng::seed_from_u64(3));
+
+        assert_eq!(result1, result2);
+    }
+
     #[test]
     fn test_attack_against_evasion() {
         let roll = DualityRoll::from_values(10, 5);
@@ -237,6 +669,7 @@ mod tests {
         assert_eq!(result.total, 15);
     }
 
+    #[cfg(feature = "persistence")]
     #[test]
     fn test_attack_result_serialization() {
         let roll = DualityRoll::from_values(10, 5);
@@ -272,7 +705,7 @@ mod property_tests {
         }
 
         #[test]
-        fn prop_success_matches_controlling_die(
+        fn prop_hope_and_fear_match_controlling_die(
             hope in 1u8..=12,
             fear in 1u8..=12,
             modifier in -10i8..=10,
@@ -281,9 +714,22 @@ mod property_tests {
             let duality = roll.with_modifier(modifier);
             let result = AttackResult::from_duality_result(duality);
 
-            let expected_success = hope > fear;
+            prop_assert_eq!(result.with_hope, hope > fear);
+            prop_assert_eq!(result.with_fear, fear > hope);
+        }
+
+        #[test]
+        fn prop_hit_depends_only_on_total(
+            hope in 1u8..=12,
+            fear in 1u8..=12,
+            modifier in -10i8..=10,
+            evasion in 1u8..=25,
+        ) {
+            let roll = DualityRoll::from_values(hope, fear);
+            let result = AttackResult::from_duality_result(roll.with_modifier(modifier));
 
-            prop_assert_eq!(result.success, expected_success);
+            let expected = hope == fear || result.total >= evasion as u16;
+            prop_assert_eq!(result.against(evasion).hit, expected);
         }
 
         #[test]
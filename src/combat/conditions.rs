@@ -0,0 +1,69 @@
+//! Conditions that can be applied to combatants
+
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// Standard conditions from the SRD
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Condition {
+    /// Out of sight; can't be attacked until found, and lost on attacking
+    Hidden,
+    /// Can't move, but can still take actions
+    Restrained,
+    /// Rolls against you have advantage
+    Vulnerable,
+    /// Carrying more than their Strength allows
+    Encumbered,
+    /// In the air; out of reach of melee attacks from the ground
+    Flying,
+}
+
+impl Condition {
+    /// Whether the condition outlasts the encounter it was gained in
+    ///
+    /// Hiding and flying only hold for the scene; the rest stay until
+    /// cleared.
+    pub fn is_lasting(self) -> bool {
+        !matches!(self, Condition::Hidden | Condition::Flying)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_condition_count() {
+        assert_eq!(Condition::iter().count(), 5);
+    }
+
+    #[test]
+    fn test_hiding_and_flying_end_with_the_encounter() {
+        let lasting: Vec<Condition> = Condition::iter().filter(|c| c.is_lasting()).collect();
+        assert_eq!(
+            lasting,
+            [
+                Condition::Restrained,
+                Condition::Vulnerable,
+                Condition::Encumbered
+            ]
+        );
+    }
+
+    #[test]
+    fn test_condition_display() {
+        assert_eq!(Condition::Vulnerable.to_string(), "Vulnerable");
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_condition_serialization() {
+        for condition in Condition::iter() {
+            let json = serde_json::to_string(&condition).unwrap();
+            let loaded: Condition = serde_json::from_str(&json).unwrap();
+            assert_eq!(condition, loaded);
+        }
+    }
+}
@@ -0,0 +1,170 @@
+//! Contests - opposed rolls between combatants
+//!
+//! A grapple, a shoving match or a staredown mid-fight is an opposed roll:
+//! player characters make an action roll with the duality dice and
+//! adversaries roll a d20, each plus the modifier the GM calls for. The
+//! rolls earn Hope and Fear as usual, and the outcome is logged as
+//! [`CombatEvent::ContestResolved`].
+
+use crate::combat::events::CombatEvent;
+use crate::combat::simulation::{CombatEncounter, CombatantId};
+use crate::core::dice::{ContestRoll, OpposedResult, Side};
+use crate::error::EngineError;
+use rand::Rng;
+
+impl CombatEncounter {
+    /// Settle a contest between two combatants
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let grom = encounter.add_combatant(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// let ogre = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Ogre")
+    ///         .with_hp(8)
+    ///         .with_evasion(11)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// // Grom wrestles the ogre with Strength
+    /// let result = encounter.contest(grom, 2, ogre, 3).unwrap();
+    /// assert!(result.margin <= 30);
+    /// ```
+    pub fn contest(
+        &mut self,
+        first: CombatantId,
+        first_modifier: i8,
+        second: CombatantId,
+        second_modifier: i8,
+    ) -> Result<OpposedResult, EngineError> {
+        let mut rng = rand::thread_rng();
+        self.contest_with_rng(first, first_modifier, second, second_modifier, &mut rng)
+    }
+
+    /// Settle a contest with a specific RNG (for testing/seeding)
+    pub fn contest_with_rng<R: Rng>(
+        &mut self,
+        first: CombatantId,
+        first_modifier: i8,
+        second: CombatantId,
+        second_modifier: i8,
+        rng: &mut R,
+    ) -> Result<OpposedResult, EngineError> {
+        if first == second {
+            return Err(EngineError::InvalidTarget {
+                reason: String::from("A contest needs two sides"),
+            });
+        }
+        for id in [first, second] {
+            let combatant = &self.combatants[self.require(id)?];
+            if !combatant.is_alive() {
+                return Err(EngineError::CombatantDefeated {
+                    name: combatant.name.to_string(),
+                });
+            }
+        }
+
+        let first_roll = self.contest_roll(first, first_modifier, rng);
+        let second_roll = self.contest_roll(second, second_modifier, rng);
+        let result = OpposedResult::resolve(first_roll, second_roll);
+        self.log(CombatEvent::ContestResolved {
+            first,
+            second,
+            winner: result.winner.map(|side| match side {
+                Side::First => first,
+                Side::Second => second,
+            }),
+            margin: result.margin,
+        });
+
+        for (id, side) in [(first, Side::First), (second, Side::Second)] {
+            // Summons have no Hope of their own to gain
+            if result.gains_hope(side) && self.hope_pool(id).is_ok() {
+                self.gain_hope(id, 1)?;
+            }
+        }
+        if result.fear_gained() > 0 {
+            self.gain_fear(result.fear_gained());
+        }
+        Ok(result)
+    }
+
+    fn contest_roll<R: Rng>(&self, id: CombatantId, modifier: i8, rng: &mut R) -> ContestRoll {
+        if self.get(id).is_some_and(|c| c.is_player()) {
+            ContestRoll::duality_with_rng(modifier, rng)
+        } else {
+            ContestRoll::adversary_with_rng(modifier, rng)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::test_support::hero;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn test_contests_earn_hope_and_fear() {
+        let mut encounter = CombatEncounter::new(5);
+        let grom = encounter.add_combatant(hero("Grom"));
+        let lia = encounter.add_combatant(hero("Lia"));
+        let mut rng = StdRng::seed_from_u64(2);
+
+        for _ in 0..20 {
+            let fear = encounter.fear.current;
+            let hope = [grom, lia].map(|id| encounter.hope_pool(id).unwrap().current);
+            let result = encounter
+                .contest_with_rng(grom, 1, lia, 0, &mut rng)
+                .unwrap();
+            assert_eq!(
+                encounter.fear.current,
+                (fear + result.fear_gained()).min(encounter.fear.maximum)
+            );
+            let gained = [Side::First, Side::Second].map(|side| result.gains_hope(side));
+            for ((id, before), gained) in [grom, lia].into_iter().zip(hope).zip(gained) {
+                let now = encounter.hope_pool(id).unwrap();
+                assert_eq!(now.current, (before + u8::from(gained)).min(now.maximum));
+            }
+        }
+    }
+
+    #[test]
+    fn test_adversaries_roll_a_d20() {
+        let mut encounter = CombatEncounter::new(5);
+        let grom = encounter.add_combatant(hero("Grom"));
+        let ogre = encounter.add_combatant(
+            CombatantBuilder::adversary("Ogre")
+                .with_hp(8)
+                .with_evasion(11)
+                .build()
+                .unwrap(),
+        );
+        let mut rng = StdRng::seed_from_u64(4);
+
+        let result = encounter
+            .contest_with_rng(grom, 2, ogre, 3, &mut rng)
+            .unwrap();
+        assert!(matches!(result.first, ContestRoll::Duality(_)));
+        assert!(matches!(result.second, ContestRoll::Adversary { .. }));
+        assert!(encounter.events.iter().any(|event| matches!(
+            event,
+            CombatEvent::ContestResolved { first, second, .. } if *first == grom && *second == ogre
+        )));
+        assert!(encounter
+            .contest_with_rng(grom, 0, grom, 0, &mut rng)
+            .is_err());
+    }
+}
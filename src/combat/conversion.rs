@@ -0,0 +1,275 @@
+//! Resource conversion - trading one pool for another
+//!
+//! Features like "mark a Stress to gain a Hope" or "spend 2 Hope to clear
+//! a Hit Point" all trade out of one pool and into another. Cards and
+//! class features describe them as [`CardEffect::Convert`] and the effect
+//! engine pays for them here, so no feature does its own pool math.
+//!
+//! [`CardEffect::Convert`]: crate::cards::CardEffect::Convert
+
+use crate::combat::events::CombatEvent;
+use crate::combat::resources::{ConversionRate, HitPoints, Hope, Pool, Stress};
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId, CombatantKind, HopeMode};
+use crate::error::{EngineError, Resource};
+
+impl Combatant {
+    /// Trade out of one pool and into another, paying from the
+    /// character's own Hope
+    ///
+    /// Returns how much was gained, which can be less than the rate
+    /// offers if the pool converted into fills up. Nothing changes if
+    /// the trade can't be made: the pools are the same, the rate is
+    /// zero, the cost can't be paid, or there is nothing to gain.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::{CombatantBuilder, ConversionRate, Pool};
+    ///
+    /// let mut grom = CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///     .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// // Mark a Stress to gain a Hope
+    /// assert_eq!(grom.convert(Pool::Stress, Pool::Hope, ConversionRate::EVEN).unwrap(), 1);
+    /// assert_eq!(grom.stress.current, 1);
+    /// assert_eq!(grom.hope().unwrap().current, 3);
+    ///
+    /// // No Hit Points to clear
+    /// assert!(grom.convert(Pool::Hope, Pool::HitPoints, ConversionRate::EVEN).is_err());
+    /// ```
+    pub fn convert(
+        &mut self,
+        from: Pool,
+        to: Pool,
+        rate: ConversionRate,
+    ) -> Result<u8, EngineError> {
+        let name = self.name.to_string();
+        let hope = match &mut self.kind {
+            CombatantKind::Player(data) => Some(&mut data.hope),
+            CombatantKind::Adversary(_) => None,
+        };
+        exchange(&name, &mut self.hp, &mut self.stress, hope, from, to, rate)
+    }
+}
+
+impl CombatEncounter {
+    /// Trade out of one pool and into another for a combatant
+    ///
+    /// Hope comes from and goes to the shared pool when playing with
+    /// one. The trade is logged.
+    pub fn convert(
+        &mut self,
+        combatant: CombatantId,
+        from: Pool,
+        to: Pool,
+        rate: ConversionRate,
+    ) -> Result<u8, EngineError> {
+        let index = self.require(combatant)?;
+        let gained = match self.hope_mode {
+            HopeMode::PerCharacter => self.combatants[index].convert(from, to, rate)?,
+            HopeMode::Shared => {
+                let character = &mut self.combatants[index];
+                let name = character.name.to_string();
+                exchange(
+                    &name,
+                    &mut character.hp,
+                    &mut character.stress,
+                    Some(&mut self.hope),
+                    from,
+                    to,
+                    rate,
+                )?
+            }
+        };
+        self.log(CombatEvent::ResourcesConverted {
+            combatant,
+            from,
+            to,
+            paid: rate.pay,
+            gained,
+        });
+        Ok(gained)
+    }
+}
+
+/// Check the trade can be made, then make it
+fn exchange(
+    name: &str,
+    hp: &mut HitPoints,
+    stress: &mut Stress,
+    mut hope: Option<&mut Hope>,
+    from: Pool,
+    to: Pool,
+    rate: ConversionRate,
+) -> Result<u8, EngineError> {
+    if from == to {
+        return Err(EngineError::InvalidTarget {
+            reason: format!("Can't convert {} into itself", from),
+        });
+    }
+    if rate.pay == 0 || rate.gain == 0 {
+        return Err(EngineError::InvalidTarget {
+            reason: String::from("A conversion has to cost and give something"),
+        });
+    }
+    if !hp.is_alive() {
+        return Err(EngineError::CombatantDefeated {
+            name: name.to_string(),
+        });
+    }
+    if (from == Pool::Hope || to == Pool::Hope) && hope.is_none() {
+        return Err(EngineError::MissingStat {
+            combatant: name.to_string(),
+            stat: "Hope",
+        });
+    }
+
+    // Marking the last Hit Point isn't a price a feature can ask
+    let affordable = match from {
+        Pool::Hope => hope.as_deref().map(|h| (Resource::Hope, h.current)),
+        Pool::Stress => None,
+        Pool::HitPoints => Some((Resource::HitPoints, hp.current - 1)),
+    };
+    if let Some((resource, have)) = affordable.filter(|&(_, have)| have < rate.pay) {
+        return Err(EngineError::InsufficientResource {
+            resource,
+            have: have.into(),
+            need: rate.pay.into(),
+        });
+    }
+    let room = match to {
+        Pool::Hope => hope
+            .as_deref()
+            .map_or(0, |h| h.maximum.saturating_sub(h.current)),
+        Pool::Stress => stress.current,
+        Pool::HitPoints => hp.maximum.saturating_sub(hp.current),
+    };
+    if room == 0 {
+        return Err(EngineError::InvalidTarget {
+            reason: format!("{} has no {} to gain", name, to),
+        });
+    }
+
+    match from {
+        Pool::Hope => hope.as_deref_mut().map_or(Ok(()), |h| h.spend(rate.pay))?,
+        Pool::Stress => stress.gain(rate.pay),
+        Pool::HitPoints => hp.take_damage(rate.pay),
+    }
+    let gained = rate.gain.min(room);
+    match to {
+        Pool::Hope => {
+            if let Some(h) = hope {
+                h.gain(gained);
+            }
+        }
+        Pool::Stress => stress.reduce(gained),
+        Pool::HitPoints => hp.heal(gained),
+    }
+    Ok(gained)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::CardEffect;
+    use crate::character::{Ancestry, Class};
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::test_support::player;
+
+    fn hero() -> Combatant {
+        player("Grom", Class::Warrior, Ancestry::Human)
+            .with_hp(6)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_gains_are_capped_by_room() {
+        let mut grom = hero();
+        grom.stress.gain(1);
+        let gained = grom
+            .convert(Pool::Hope, Pool::Stress, ConversionRate::new(1, 3))
+            .unwrap();
+        assert_eq!(gained, 1);
+        assert_eq!(grom.stress.current, 0);
+        assert_eq!(grom.hope().unwrap().current, 1);
+    }
+
+    #[test]
+    fn test_card_effects_convert() {
+        let mut grom = hero();
+        let rally = CardEffect::convert(Pool::Stress, Pool::Hope, ConversionRate::new(1, 2));
+        assert!(grom.apply_effect("Rally", &rally));
+        assert_eq!(grom.hope().unwrap().current, 4);
+
+        let mend = CardEffect::convert(Pool::Hope, Pool::HitPoints, ConversionRate::EVEN);
+        assert!(!grom.apply_effect("Mend", &mend));
+    }
+
+    #[test]
+    fn test_invalid_trades_change_nothing() {
+        let mut grom = hero();
+        grom.take_damage(2);
+        let before = grom.clone();
+
+        assert!(grom
+            .convert(Pool::Hope, Pool::Hope, ConversionRate::EVEN)
+            .is_err());
+        assert!(grom
+            .convert(Pool::Hope, Pool::HitPoints, ConversionRate::new(0, 1))
+            .is_err());
+        assert!(matches!(
+            grom.convert(Pool::Hope, Pool::HitPoints, ConversionRate::new(3, 1)),
+            Err(EngineError::InsufficientResource {
+                resource: Resource::Hope,
+                have: 2,
+                need: 3,
+            })
+        ));
+        assert!(matches!(
+            grom.convert(Pool::HitPoints, Pool::Hope, ConversionRate::new(4, 1)),
+            Err(EngineError::InsufficientResource {
+                resource: Resource::HitPoints,
+                ..
+            })
+        ));
+        assert_eq!(grom, before);
+
+        let mut goblin = CombatantBuilder::adversary("Goblin")
+            .with_hp(4)
+            .with_evasion(10)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            goblin.convert(Pool::Stress, Pool::Hope, ConversionRate::EVEN),
+            Err(EngineError::MissingStat { .. })
+        ));
+    }
+
+    #[test]
+    fn test_encounter_uses_the_shared_pool_and_logs() {
+        let mut encounter = CombatEncounter::new(5);
+        let grom = encounter.add_combatant(hero());
+        encounter.hope_mode = HopeMode::Shared;
+        encounter.hope.spend(2).unwrap();
+
+        encounter
+            .convert(grom, Pool::Stress, Pool::Hope, ConversionRate::EVEN)
+            .unwrap();
+        assert_eq!(encounter.hope.current, 4);
+        assert_eq!(encounter.get(grom).unwrap().hope().unwrap().current, 2);
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::ResourcesConverted {
+                combatant: grom,
+                from: Pool::Stress,
+                to: Pool::Hope,
+                paid: 1,
+                gained: 1,
+            })
+        );
+    }
+}
@@ -0,0 +1,68 @@
+//! Countdowns - timers that tick down at the start of each round
+
+use alloc::string::String;
+use serde::{Deserialize, Serialize};
+
+/// A named timer, such as a ritual completing or reinforcements arriving
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::combat::Countdown;
+///
+/// let mut ritual = Countdown::new("Ritual", 2);
+/// assert!(!ritual.tick());
+/// assert!(ritual.tick());
+/// assert!(ritual.is_expired());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Countdown {
+    pub name: String,
+    pub remaining: u8,
+}
+
+impl Countdown {
+    /// Create a countdown that expires after `rounds` ticks
+    pub fn new(name: impl Into<String>, rounds: u8) -> Self {
+        Self {
+            name: name.into(),
+            remaining: rounds,
+        }
+    }
+
+    /// Tick one step, returning true if this tick expired it
+    pub fn tick(&mut self) -> bool {
+        if self.remaining == 0 {
+            return false;
+        }
+        self.remaining -= 1;
+        self.remaining == 0
+    }
+
+    /// Check if the countdown has run out
+    pub fn is_expired(&self) -> bool {
+        self.remaining == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tick_expires_once() {
+        let mut countdown = Countdown::new("Collapse", 1);
+        assert!(countdown.tick());
+        assert!(!countdown.tick());
+        assert_eq!(countdown.remaining, 0);
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_countdown_serialization() {
+        let countdown = Countdown::new("Collapse", 3);
+        let json = serde_json::to_string(&countdown).unwrap();
+        assert_eq!(json, r#"{"name":"Collapse","remaining":3}"#);
+    }
+}
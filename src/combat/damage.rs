@@ -1,9 +1,17 @@
 //! Damage calculation and resolution
 
+use crate::core::dice::DamageDice;
+use alloc::string::String;
 use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+/// Damage after armor below this is a scratch that costs Stress instead
+/// of Hit Points
+pub const DAMAGE_THRESHOLD: u16 = 5;
 
 /// Result of applying damage to a character
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DamageResult {
     pub raw_damage: u16,
     pub after_armor: u16,
@@ -25,13 +33,28 @@ impl DamageResult {
     /// assert_eq!(result.after_armor, 7);
     /// ```
     pub fn calculate(raw_damage: u16, armor_score: u8) -> Self {
+        Self::calculate_with_threshold(raw_damage, armor_score, DAMAGE_THRESHOLD)
+    }
+
+    /// Calculate damage against a raised or lowered damage threshold
+    ///
+    /// Each further 5 points past the threshold costs another Hit Point,
+    /// up to 3.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::DamageResult;
+    ///
+    /// assert_eq!(DamageResult::calculate(10, 0).hp_lost, 2);
+    /// assert_eq!(DamageResult::calculate_with_threshold(10, 0, 7).hp_lost, 1);
+    /// ```
+    pub fn calculate_with_threshold(raw_damage: u16, armor_score: u8, threshold: u16) -> Self {
         let after_armor = raw_damage.saturating_sub(armor_score as u16);
 
         // Damage threshold mechanics:
-        // - Below threshold (< 5): 0 HP, 1 Stress
-        // - At/Above threshold (>= 5): HP damage based on amount
-        let threshold = 5;
-
+        // - Below threshold: 0 HP, 1 Stress
+        // - At/Above threshold: HP damage based on amount
         let (hp_lost, stress_gained) = if after_armor < threshold {
             // Below threshold = scratch (1 Stress)
             (0, 1)
@@ -55,10 +78,154 @@ impl DamageResult {
     }
 }
 
+/// Whether damage is physical or magic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum DamageType {
+    #[default]
+    #[strum(to_string = "physical")]
+    Physical,
+    #[strum(to_string = "magic")]
+    Magic,
+}
+
+/// When an ongoing effect deals its damage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum TickTiming {
+    /// As the afflicted combatant's turn begins
+    #[default]
+    StartOfTurn,
+    /// As the afflicted combatant's turn ends
+    EndOfTurn,
+}
+
+/// How much of the usual damage rules an ongoing effect goes through
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Mitigation {
+    /// Reduced by armor, then measured against the damage threshold
+    #[default]
+    ArmorAndThresholds,
+    /// Ignores armor, but still measured against the threshold
+    ThresholdsOnly,
+    /// Every point rolled is a Hit Point lost
+    Direct,
+}
+
+/// Damage that keeps coming, like burning or bleeding
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::combat::damage::{DamageOverTime, Mitigation};
+/// use daggerheart_engine::core::dice::DamageDice;
+///
+/// let mut bleeding = DamageOverTime::new("Bleeding", DamageDice::new(vec![]).with_bonus(1))
+///     .lasting(2)
+///     .with_mitigation(Mitigation::Direct);
+///
+/// assert_eq!(bleeding.damage(1, 3).hp_lost, 1);
+/// assert!(bleeding.tick());
+/// assert!(!bleeding.tick());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DamageOverTime {
+    pub name: String,
+    pub dice: DamageDice,
+    #[serde(default)]
+    pub damage_type: DamageType,
+    /// Ticks left before it wears off, or `None` until it is removed
+    #[serde(default)]
+    pub remaining: Option<u8>,
+    #[serde(default)]
+    pub timing: TickTiming,
+    #[serde(default)]
+    pub mitigation: Mitigation,
+}
+
+impl DamageOverTime {
+    /// Physical damage at the start of each turn until removed, reduced by
+    /// armor as usual
+    pub fn new(name: impl Into<String>, dice: DamageDice) -> Self {
+        Self {
+            name: name.into(),
+            dice,
+            damage_type: DamageType::Physical,
+            remaining: None,
+            timing: TickTiming::StartOfTurn,
+            mitigation: Mitigation::ArmorAndThresholds,
+        }
+    }
+
+    /// Deal magic damage
+    pub fn magic(mut self) -> Self {
+        self.damage_type = DamageType::Magic;
+        self
+    }
+
+    /// Wear off after this many ticks
+    pub fn lasting(mut self, ticks: u8) -> Self {
+        self.remaining = Some(ticks.max(1));
+        self
+    }
+
+    /// Deal damage as the afflicted combatant's turn ends instead
+    pub fn at_end_of_turn(mut self) -> Self {
+        self.timing = TickTiming::EndOfTurn;
+        self
+    }
+
+    /// Set how armor and thresholds apply
+    pub fn with_mitigation(mut self, mitigation: Mitigation) -> Self {
+        self.mitigation = mitigation;
+        self
+    }
+
+    /// What `rolled` damage does to a combatant with `armor_score` armor
+    pub fn damage(&self, rolled: u16, armor_score: u8) -> DamageResult {
+        match self.mitigation {
+            Mitigation::ArmorAndThresholds => DamageResult::calculate(rolled, armor_score),
+            Mitigation::ThresholdsOnly => DamageResult::calculate(rolled, 0),
+            Mitigation::Direct => DamageResult {
+                raw_damage: rolled,
+                after_armor: rolled,
+                hp_lost: rolled.min(u8::MAX as u16) as u8,
+                stress_gained: 0,
+            },
+        }
+    }
+
+    /// Count down a tick; false once it has worn off
+    pub fn tick(&mut self) -> bool {
+        match self.remaining {
+            Some(ticks) if ticks <= 1 => false,
+            Some(ticks) => {
+                self.remaining = Some(ticks - 1);
+                true
+            }
+            None => true,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_ongoing_mitigation() {
+        let burning = DamageOverTime::new("Burning", DamageDice::d6(1));
+        assert_eq!(burning.damage(7, 3).hp_lost, 0);
+        let searing = burning.clone().with_mitigation(Mitigation::ThresholdsOnly);
+        assert_eq!(searing.damage(7, 3).hp_lost, 1);
+        assert!(!burning.clone().lasting(1).tick());
+        let mut forever = burning;
+        assert!(forever.tick());
+        assert_eq!(forever.remaining, None);
+    }
+
     #[test]
     fn test_damage_below_threshold_gives_stress() {
         // 7 raw damage - 5 armor = 2 (below threshold of 5)
@@ -120,6 +287,7 @@ mod tests {
         assert_eq!(result.stress_gained, 1); // Even 0 damage = 1 stress
     }
 
+    #[cfg(feature = "persistence")]
     #[test]
     fn test_damage_serialization() {
         let result = DamageResult::calculate(10, 3);
@@ -0,0 +1,328 @@
+//! Snapshots and diffs - what changed between two moments of a fight
+//!
+//! An `EncounterSnapshot` keeps the parts of an encounter a display
+//! draws: each combatant's Hit Points, Stress, Hope, conditions and zone,
+//! the pools and whose turn it is. A UI holding the last snapshot it drew
+//! can [`diff`] it against a fresh one and animate only the changes
+//! instead of re-rendering the whole encounter.
+
+use crate::combat::conditions::Condition;
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId};
+use serde::{Deserialize, Serialize};
+
+/// The drawn state of one combatant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CombatantSnapshot {
+    pub id: CombatantId,
+    pub name: String,
+    /// Hit Points left
+    pub hp: u8,
+    pub stress: u8,
+    /// The character's own Hope; `None` for adversaries
+    pub hope: Option<u8>,
+    pub conditions: Vec<Condition>,
+    pub zone: i8,
+}
+
+impl CombatantSnapshot {
+    fn of(combatant: &Combatant) -> Self {
+        Self {
+            id: combatant.id,
+            name: combatant.name.to_string(),
+            hp: combatant.hp.current,
+            stress: combatant.stress.current,
+            hope: combatant.hope().map(|hope| hope.current),
+            conditions: combatant.conditions.clone(),
+            zone: combatant.zone,
+        }
+    }
+}
+
+/// The drawn state of an encounter at one moment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EncounterSnapshot {
+    pub round: u32,
+    pub current: Option<CombatantId>,
+    /// The party's shared Hope pool
+    pub hope: u8,
+    pub fear: u8,
+    pub combatants: Vec<CombatantSnapshot>,
+}
+
+impl EncounterSnapshot {
+    /// A combatant's state by id
+    pub fn get(&self, id: CombatantId) -> Option<&CombatantSnapshot> {
+        self.combatants.iter().find(|c| c.id == id)
+    }
+}
+
+/// One thing that changed between two snapshots
+///
+/// Amounts are signed deltas: negative `HitPoints` is damage, positive is
+/// healing.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Change {
+    RoundChanged {
+        from: u32,
+        to: u32,
+    },
+    TurnChanged {
+        from: Option<CombatantId>,
+        to: Option<CombatantId>,
+    },
+    CombatantJoined {
+        id: CombatantId,
+        name: String,
+    },
+    CombatantLeft {
+        id: CombatantId,
+        name: String,
+    },
+    HitPoints {
+        id: CombatantId,
+        delta: i16,
+    },
+    Stress {
+        id: CombatantId,
+        delta: i16,
+    },
+    Hope {
+        id: CombatantId,
+        delta: i16,
+    },
+    ConditionAdded {
+        id: CombatantId,
+        condition: Condition,
+    },
+    ConditionRemoved {
+        id: CombatantId,
+        condition: Condition,
+    },
+    Moved {
+        id: CombatantId,
+        from: i8,
+        to: i8,
+    },
+    PartyHope {
+        delta: i16,
+    },
+    Fear {
+        delta: i16,
+    },
+}
+
+/// Everything that changed between two snapshots, encounter-wide changes
+/// first, then each combatant's in encounter order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct StateDiff {
+    pub changes: Vec<Change>,
+}
+
+impl StateDiff {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// The changes to one combatant
+    pub fn for_combatant(&self, id: CombatantId) -> impl Iterator<Item = &Change> {
+        self.changes
+            .iter()
+            .filter(move |change| change.combatant() == Some(id))
+    }
+}
+
+impl Change {
+    /// The combatant this change happened to, if it's about one
+    pub fn combatant(&self) -> Option<CombatantId> {
+        match self {
+            Change::CombatantJoined { id, .. }
+            | Change::CombatantLeft { id, .. }
+            | Change::HitPoints { id, .. }
+            | Change::Stress { id, .. }
+            | Change::Hope { id, .. }
+            | Change::ConditionAdded { id, .. }
+            | Change::ConditionRemoved { id, .. }
+            | Change::Moved { id, .. } => Some(*id),
+            Change::RoundChanged { .. }
+            | Change::TurnChanged { .. }
+            | Change::PartyHope { .. }
+            | Change::Fear { .. } => None,
+        }
+    }
+}
+
+impl CombatEncounter {
+    /// Capture what a display would draw of the encounter right now
+    pub fn snapshot(&self) -> EncounterSnapshot {
+        EncounterSnapshot {
+            round: self.round,
+            current: self.current_combatant_id(),
+            hope: self.hope.current,
+            fear: self.fear.current,
+            combatants: self.combatants.iter().map(CombatantSnapshot::of).collect(),
+        }
+    }
+}
+
+/// What changed from `old` to `new`
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::combat::diff::{diff, Change};
+/// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder, Condition};
+///
+/// let mut encounter = CombatEncounter::new(5);
+/// let bear = encounter.add_combatant(
+///     CombatantBuilder::adversary("Bear")
+///         .with_hp(6)
+///         .with_evasion(10)
+///         .build()
+///         .unwrap(),
+/// );
+/// let before = encounter.snapshot();
+///
+/// encounter.get_mut(bear).unwrap().take_damage(2);
+/// encounter.apply_condition(bear, Condition::Restrained).unwrap();
+/// encounter.gain_fear(1);
+///
+/// let changes = diff(&before, &encounter.snapshot()).changes;
+/// assert_eq!(
+///     changes,
+///     vec![
+///         Change::Fear { delta: 1 },
+///         Change::HitPoints { id: bear, delta: -2 },
+///         Change::ConditionAdded { id: bear, condition: Condition::Restrained },
+///     ]
+/// );
+/// ```
+pub fn diff(old: &EncounterSnapshot, new: &EncounterSnapshot) -> StateDiff {
+    let mut changes = Vec::new();
+    if old.round != new.round {
+        changes.push(Change::RoundChanged {
+            from: old.round,
+            to: new.round,
+        });
+    }
+    if old.current != new.current {
+        changes.push(Change::TurnChanged {
+            from: old.current,
+            to: new.current,
+        });
+    }
+    if let Some(delta) = delta(old.hope, new.hope) {
+        changes.push(Change::PartyHope { delta });
+    }
+    if let Some(delta) = delta(old.fear, new.fear) {
+        changes.push(Change::Fear { delta });
+    }
+
+    for before in &old.combatants {
+        if new.get(before.id).is_none() {
+            changes.push(Change::CombatantLeft {
+                id: before.id,
+                name: before.name.clone(),
+            });
+        }
+    }
+    for after in &new.combatants {
+        let id = after.id;
+        let Some(before) = old.get(id) else {
+            changes.push(Change::CombatantJoined {
+                id,
+                name: after.name.clone(),
+            });
+            continue;
+        };
+        if let Some(delta) = delta(before.hp, after.hp) {
+            changes.push(Change::HitPoints { id, delta });
+        }
+        if let Some(delta) = delta(before.stress, after.stress) {
+            changes.push(Change::Stress { id, delta });
+        }
+        if let (Some(old_hope), Some(new_hope)) = (before.hope, after.hope) {
+            if let Some(delta) = delta(old_hope, new_hope) {
+                changes.push(Change::Hope { id, delta });
+            }
+        }
+        for &condition in &before.conditions {
+            if !after.conditions.contains(&condition) {
+                changes.push(Change::ConditionRemoved { id, condition });
+            }
+        }
+        for &condition in &after.conditions {
+            if !before.conditions.contains(&condition) {
+                changes.push(Change::ConditionAdded { id, condition });
+            }
+        }
+        if before.zone != after.zone {
+            changes.push(Change::Moved {
+                id,
+                from: before.zone,
+                to: after.zone,
+            });
+        }
+    }
+    StateDiff { changes }
+}
+
+fn delta(old: u8, new: u8) -> Option<i16> {
+    (old != new).then(|| new as i16 - old as i16)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::create_test_encounter;
+
+    #[test]
+    fn test_unchanged_encounters_diff_empty() {
+        let encounter = create_test_encounter();
+        assert!(diff(&encounter.snapshot(), &encounter.snapshot()).is_empty());
+    }
+
+    #[test]
+    fn test_diff_tracks_each_combatant() {
+        let mut encounter = create_test_encounter();
+        let (hero, goblin) = (CombatantId(0), CombatantId(1));
+        encounter
+            .apply_condition(goblin, Condition::Vulnerable)
+            .unwrap();
+        let before = encounter.snapshot();
+
+        encounter.gain_hope(hero, 1).unwrap();
+        encounter.get_mut(hero).unwrap().gain_stress(2);
+        encounter.get_mut(hero).unwrap().zone = 2;
+        encounter
+            .remove_condition(goblin, Condition::Vulnerable)
+            .unwrap();
+        encounter.remove_combatant(goblin).unwrap();
+        let diff = diff(&before, &encounter.snapshot());
+
+        assert_eq!(
+            diff.for_combatant(hero).cloned().collect::<Vec<_>>(),
+            vec![
+                Change::Stress { id: hero, delta: 2 },
+                Change::Hope { id: hero, delta: 1 },
+                Change::Moved {
+                    id: hero,
+                    from: 0,
+                    to: 2
+                },
+            ]
+        );
+        // A combatant who left reports only leaving
+        assert_eq!(
+            diff.for_combatant(goblin).cloned().collect::<Vec<_>>(),
+            vec![Change::CombatantLeft {
+                id: goblin,
+                name: "Goblin".to_string()
+            }]
+        );
+    }
+}
@@ -0,0 +1,693 @@
+//! Encounter building with battle-point budgets
+//!
+//! The SRD budgets encounters with battle points: `3 × PCs + 2`, adjusted
+//! for the desired difficulty and for the mix of adversaries chosen.
+//! Prewritten encounters are kept as [`EncounterTemplate`]s, which scale
+//! to other party sizes and tiers by the same budget.
+
+use crate::combat::adversary::{Adversary, AdversaryRole};
+use crate::combat::adversary_builder::AdversaryBuilder;
+use crate::combat::simulation::CombatEncounter;
+use crate::error::{EngineError, Resource};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// How hard the GM wants an encounter to be
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum EncounterDifficulty {
+    /// An easier or shorter fight (-1 battle point)
+    Easier,
+    /// A standard fight
+    Standard,
+    /// A harder or longer fight (+2 battle points)
+    Harder,
+}
+
+impl EncounterDifficulty {
+    /// Battle point adjustment for this difficulty
+    pub fn adjustment(&self) -> i16 {
+        match self {
+            EncounterDifficulty::Easier => -1,
+            EncounterDifficulty::Standard => 0,
+            EncounterDifficulty::Harder => 2,
+        }
+    }
+}
+
+/// Builds encounters within a battle-point budget
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EncounterBuilder {
+    pub party_size: u8,
+    pub party_tier: u8,
+    pub difficulty: EncounterDifficulty,
+    pub adversaries: Vec<Adversary>,
+}
+
+impl EncounterBuilder {
+    /// Create a builder for a party of the given size and tier
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::encounter_builder::EncounterBuilder;
+    ///
+    /// let builder = EncounterBuilder::new(4, 1);
+    /// assert_eq!(builder.base_budget(), 14); // 3 × 4 + 2
+    /// ```
+    pub fn new(party_size: u8, party_tier: u8) -> Self {
+        Self {
+            party_size,
+            party_tier,
+            difficulty: EncounterDifficulty::Standard,
+            adversaries: Vec::new(),
+        }
+    }
+
+    /// Set the desired difficulty
+    pub fn with_difficulty(mut self, difficulty: EncounterDifficulty) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+
+    /// Budget before adjustments for the chosen adversaries
+    pub fn base_budget(&self) -> i16 {
+        3 * self.party_size as i16 + 2 + self.difficulty.adjustment()
+    }
+
+    /// Total budget, including adjustments for the current selection
+    ///
+    /// - −2 if two or more Solos are included
+    /// - +1 for each adversary from a lower tier than the party
+    /// - +1 if there are no Bruisers, Hordes, Leaders, or Solos
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::encounter_builder::EncounterBuilder;
+    /// use daggerheart_engine::combat::{Adversary, AdversaryRole};
+    ///
+    /// let mut builder = EncounterBuilder::new(4, 1);
+    /// builder.add_adversary(Adversary::new("Bandit", 1, AdversaryRole::Standard));
+    ///
+    /// // No heavy hitters yet, so +1
+    /// assert_eq!(builder.budget(), 15);
+    /// ```
+    pub fn budget(&self) -> i16 {
+        Self::budget_for(self.base_budget(), self.party_tier, &self.adversaries)
+    }
+
+    fn budget_for(base: i16, party_tier: u8, adversaries: &[Adversary]) -> i16 {
+        let mut budget = base;
+
+        let solos = adversaries
+            .iter()
+            .filter(|a| a.role == AdversaryRole::Solo)
+            .count();
+        if solos >= 2 {
+            budget -= 2;
+        }
+
+        budget += adversaries.iter().filter(|a| a.tier < party_tier).count() as i16;
+
+        if !adversaries.iter().any(|a| a.role.is_heavy()) {
+            budget += 1;
+        }
+
+        budget
+    }
+
+    /// Battle points spent on the current selection
+    pub fn spent(&self) -> i16 {
+        self.adversaries
+            .iter()
+            .map(|a| a.battle_points() as i16)
+            .sum()
+    }
+
+    /// Battle points left to spend (negative if over budget)
+    pub fn remaining(&self) -> i16 {
+        self.budget() - self.spent()
+    }
+
+    /// Add an adversary to the selection
+    ///
+    /// A Minion entry represents a group with one Minion per PC.
+    pub fn add_adversary(&mut self, adversary: Adversary) {
+        self.adversaries.push(adversary);
+    }
+
+    /// Validate the current selection against the budget and party tier
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::encounter_builder::EncounterBuilder;
+    /// use daggerheart_engine::combat::{Adversary, AdversaryRole};
+    ///
+    /// let mut builder = EncounterBuilder::new(2, 1);
+    /// builder.add_adversary(Adversary::new("Dragon", 1, AdversaryRole::Solo));
+    /// assert!(builder.validate().is_ok()); // 5 of 8 points
+    ///
+    /// builder.add_adversary(Adversary::new("Ogre", 1, AdversaryRole::Bruiser));
+    /// assert!(builder.validate().is_err()); // 9 of 8 points
+    /// ```
+    pub fn validate(&self) -> Result<(), EngineError> {
+        if let Some(adversary) = self.adversaries.iter().find(|a| a.tier > self.party_tier) {
+            return Err(EngineError::TierTooHigh {
+                adversary: adversary.name.clone(),
+                tier: adversary.tier,
+                max_tier: self.party_tier,
+            });
+        }
+
+        if self.spent() > self.budget() {
+            return Err(EngineError::InsufficientResource {
+                resource: Resource::BattlePoints,
+                have: self.budget().max(0) as u32,
+                need: self.spent().max(0) as u32,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Fill the remaining budget with random picks from an adversary library
+    pub fn auto_generate(&mut self, library: &[Adversary]) -> Result<(), EngineError> {
+        let mut rng = rand::thread_rng();
+        self.auto_generate_with_rng(library, &mut rng)
+    }
+
+    /// Fill the remaining budget using a specific RNG (for testing/seeding)
+    ///
+    /// Only adversaries at or below the party's tier are considered. Picks
+    /// continue until nothing in the library fits the remaining budget. A
+    /// lower-tier adversary that gives back the battle point it costs is
+    /// never picked, since it would never use the budget up.
+    pub fn auto_generate_with_rng<R: Rng>(
+        &mut self,
+        library: &[Adversary],
+        rng: &mut R,
+    ) -> Result<(), EngineError> {
+        let eligible: Vec<&Adversary> = library
+            .iter()
+            .filter(|a| a.tier <= self.party_tier)
+            .collect();
+
+        if eligible.is_empty() {
+            return Err(EngineError::NoEligibleAdversaries {
+                max_tier: self.party_tier,
+            });
+        }
+
+        loop {
+            let affordable: Vec<&Adversary> =
+                eligible.iter().copied().filter(|a| self.fits(a)).collect();
+
+            match affordable.choose(rng) {
+                Some(adversary) => self.adversaries.push((*adversary).clone()),
+                None => break,
+            }
+        }
+
+        self.validate()
+    }
+
+    /// Whether adding an adversary keeps the selection within budget and
+    /// spends some of it
+    fn fits(&self, adversary: &Adversary) -> bool {
+        let remaining = self.remaining_with(adversary);
+        remaining >= 0 && remaining < self.remaining()
+    }
+
+    /// Battle points that would be left after adding an adversary
+    fn remaining_with(&self, adversary: &Adversary) -> i16 {
+        let mut selection = self.adversaries.clone();
+        selection.push(adversary.clone());
+
+        let spent: i16 = selection.iter().map(|a| a.battle_points() as i16).sum();
+        Self::budget_for(self.base_budget(), self.party_tier, &selection) - spent
+    }
+
+    /// Build a combat encounter containing the selected adversaries
+    ///
+    /// Minion entries expand to one combatant per PC.
+    pub fn build(&self, hope_max: u8) -> Result<CombatEncounter, EngineError> {
+        self.validate()?;
+
+        let mut encounter = CombatEncounter::new(hope_max);
+        for adversary in &self.adversaries {
+            let count = if adversary.role == AdversaryRole::Minion {
+                self.party_size.max(1)
+            } else {
+                1
+            };
+            for _ in 0..count {
+                encounter.add_combatant(adversary.to_combatant());
+            }
+        }
+
+        Ok(encounter)
+    }
+}
+
+/// A prewritten encounter, balanced for a party of a given size and tier
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::combat::encounter_builder::EncounterTemplate;
+/// use daggerheart_engine::combat::{Adversary, AdversaryRole};
+///
+/// let ambush = EncounterTemplate::new("Roadside Ambush", 4, 1)
+///     .with_adversary(Adversary::new("Ogre", 1, AdversaryRole::Bruiser))
+///     .with_adversary(Adversary::new("Bandit", 1, AdversaryRole::Standard))
+///     .with_adversary(Adversary::new("Bandit", 1, AdversaryRole::Standard));
+///
+/// // Six players get more bandits and another ogre
+/// let bigger = ambush.scale_to(6, 1);
+/// assert_eq!(bigger.party_size, 6);
+/// assert!(bigger.adversaries.len() > ambush.adversaries.len());
+/// assert_eq!(bigger.builder().remaining(), ambush.builder().remaining());
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EncounterTemplate {
+    pub name: String,
+    /// The party size it was written for
+    pub party_size: u8,
+    /// The party tier it was written for
+    pub tier: u8,
+    pub difficulty: EncounterDifficulty,
+    /// A Minion entry stands for a group with one Minion per PC
+    pub adversaries: Vec<Adversary>,
+}
+
+impl EncounterTemplate {
+    /// Start a template for a party of the given size and tier
+    pub fn new(name: impl Into<String>, party_size: u8, tier: u8) -> Self {
+        Self {
+            name: name.into(),
+            party_size,
+            tier,
+            difficulty: EncounterDifficulty::Standard,
+            adversaries: Vec::new(),
+        }
+    }
+
+    /// Set the intended difficulty
+    pub fn with_difficulty(mut self, difficulty: EncounterDifficulty) -> Self {
+        self.difficulty = difficulty;
+        self
+    }
+
+    /// Add an adversary
+    pub fn with_adversary(mut self, adversary: Adversary) -> Self {
+        self.adversaries.push(adversary);
+        self
+    }
+
+    /// A builder holding this encounter, to check its budget or build it
+    pub fn builder(&self) -> EncounterBuilder {
+        let mut builder =
+            EncounterBuilder::new(self.party_size, self.tier).with_difficulty(self.difficulty);
+        builder.adversaries = self.adversaries.clone();
+        builder
+    }
+
+    /// Adapt the encounter to another party size and tier
+    ///
+    /// Each adversary moves up or down as many tiers as the party does,
+    /// its stats shifting with its role's benchmarks so homebrew changes
+    /// carry over; damage takes the new tier's dice. The battle points
+    /// left unspent stay as the template had them: a bigger party gets
+    /// more copies of the adversaries that come in numbers, cheapest
+    /// first, and a smaller one loses copies, costliest first. Solos and
+    /// Leaders aren't copied, so their HP scales with the party size
+    /// instead, and Minion groups already match the party when built.
+    pub fn scale_to(&self, party_size: u8, tier: u8) -> Self {
+        let party_size = party_size.max(1);
+        let tier = tier.clamp(1, 4);
+        let tier_change = i16::from(tier) - i16::from(self.tier);
+        let slack = self.builder().remaining();
+
+        let mut builder = EncounterBuilder::new(party_size, tier).with_difficulty(self.difficulty);
+        builder.adversaries = self
+            .adversaries
+            .iter()
+            .map(|adversary| {
+                let moved = (i16::from(adversary.tier) + tier_change).clamp(1, 4) as u8;
+                let mut scaled = at_tier(adversary, moved);
+                if matches!(scaled.role, AdversaryRole::Solo | AdversaryRole::Leader) {
+                    let hp = u32::from(scaled.hp) * u32::from(party_size);
+                    let written_for = u32::from(self.party_size.max(1));
+                    scaled.hp = ((hp + written_for / 2) / written_for).clamp(1, 255) as u8;
+                }
+                scaled
+            })
+            .collect();
+
+        while builder.remaining() < slack {
+            let copies = (0..builder.adversaries.len()).filter(|&index| {
+                let name = &builder.adversaries[index].name;
+                builder
+                    .adversaries
+                    .iter()
+                    .filter(|a| &a.name == name)
+                    .count()
+                    > 1
+            });
+            let costliest =
+                copies.max_by_key(|&index| (builder.adversaries[index].battle_points(), index));
+            match costliest {
+                Some(index) => builder.adversaries.remove(index),
+                None => break,
+            };
+        }
+
+        let mut extras: Vec<Adversary> = Vec::new();
+        for adversary in &builder.adversaries {
+            let comes_in_numbers = !matches!(
+                adversary.role,
+                AdversaryRole::Solo | AdversaryRole::Leader | AdversaryRole::Minion
+            );
+            if comes_in_numbers && !extras.iter().any(|a| a.name == adversary.name) {
+                extras.push(adversary.clone());
+            }
+        }
+        extras.sort_by_key(|adversary| adversary.battle_points());
+        loop {
+            let mut added = false;
+            for extra in &extras {
+                // A lower-tier extra can give back the point it costs
+                let remaining = builder.remaining_with(extra);
+                if remaining >= slack && remaining < builder.remaining() {
+                    builder.adversaries.push(extra.clone());
+                    added = true;
+                }
+            }
+            if !added {
+                break;
+            }
+        }
+
+        Self {
+            name: self.name.clone(),
+            party_size,
+            tier,
+            difficulty: self.difficulty,
+            adversaries: builder.adversaries,
+        }
+    }
+}
+
+/// An adversary moved to another tier, keeping how far it strays from its
+/// role's benchmark
+fn at_tier(adversary: &Adversary, tier: u8) -> Adversary {
+    if adversary.tier == tier {
+        return adversary.clone();
+    }
+    let from = AdversaryBuilder::benchmark(adversary.name.clone(), adversary.tier, adversary.role);
+    let to = AdversaryBuilder::benchmark(adversary.name.clone(), tier, adversary.role);
+    let shift = |value: u8, from: u8, to: u8| {
+        (i16::from(value) + i16::from(to) - i16::from(from)).clamp(1, u8::MAX as i16) as u8
+    };
+
+    let mut scaled = adversary.clone();
+    scaled.tier = tier;
+    scaled.difficulty = shift(adversary.difficulty, from.difficulty, to.difficulty);
+    scaled.major_threshold = shift(
+        adversary.major_threshold,
+        from.major_threshold,
+        to.major_threshold,
+    );
+    scaled.severe_threshold = shift(
+        adversary.severe_threshold,
+        from.severe_threshold,
+        to.severe_threshold,
+    );
+    scaled.hp = shift(adversary.hp, from.hp, to.hp);
+    scaled.stress = shift(adversary.stress, from.stress, to.stress);
+    scaled.attack_modifier = adversary.attack_modifier + to.attack_modifier - from.attack_modifier;
+    let bonus = to.damage.bonus() + adversary.damage.bonus() - from.damage.bonus();
+    scaled.damage = to.damage.with_bonus(bonus.max(0));
+    scaled
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn test_library() -> Vec<Adversary> {
+        vec![
+            Adversary::new("Goblin", 1, AdversaryRole::Minion),
+            Adversary::new("Bandit", 1, AdversaryRole::Standard),
+            Adversary::new("Archer", 1, AdversaryRole::Ranged),
+            Adversary::new("Ogre", 1, AdversaryRole::Bruiser),
+            Adversary::new("Warlord", 2, AdversaryRole::Leader),
+        ]
+    }
+
+    #[test]
+    fn test_base_budget() {
+        assert_eq!(EncounterBuilder::new(4, 1).base_budget(), 14);
+        assert_eq!(EncounterBuilder::new(3, 1).base_budget(), 11);
+    }
+
+    #[test]
+    fn test_difficulty_adjustments() {
+        let easier = EncounterBuilder::new(4, 1).with_difficulty(EncounterDifficulty::Easier);
+        let harder = EncounterBuilder::new(4, 1).with_difficulty(EncounterDifficulty::Harder);
+
+        assert_eq!(easier.base_budget(), 13);
+        assert_eq!(harder.base_budget(), 16);
+    }
+
+    #[test]
+    fn test_no_heavy_hitters_bonus() {
+        let mut builder = EncounterBuilder::new(4, 1);
+        builder.add_adversary(Adversary::new("Bandit", 1, AdversaryRole::Standard));
+        assert_eq!(builder.budget(), 15);
+
+        builder.add_adversary(Adversary::new("Ogre", 1, AdversaryRole::Bruiser));
+        assert_eq!(builder.budget(), 14);
+    }
+
+    #[test]
+    fn test_two_solos_penalty() {
+        let mut builder = EncounterBuilder::new(4, 1);
+        builder.add_adversary(Adversary::new("Dragon", 1, AdversaryRole::Solo));
+        builder.add_adversary(Adversary::new("Wyrm", 1, AdversaryRole::Solo));
+
+        assert_eq!(builder.budget(), 12);
+    }
+
+    #[test]
+    fn test_lower_tier_bonus() {
+        let mut builder = EncounterBuilder::new(4, 2);
+        builder.add_adversary(Adversary::new("Ogre", 1, AdversaryRole::Bruiser));
+
+        assert_eq!(builder.budget(), 15);
+    }
+
+    #[test]
+    fn test_spent_and_remaining() {
+        let mut builder = EncounterBuilder::new(4, 1);
+        builder.add_adversary(Adversary::new("Ogre", 1, AdversaryRole::Bruiser));
+        builder.add_adversary(Adversary::new("Bandit", 1, AdversaryRole::Standard));
+
+        assert_eq!(builder.spent(), 6);
+        assert_eq!(builder.remaining(), 8);
+    }
+
+    #[test]
+    fn test_validate_over_budget() {
+        let mut builder = EncounterBuilder::new(1, 1);
+        builder.add_adversary(Adversary::new("Dragon", 1, AdversaryRole::Solo));
+        builder.add_adversary(Adversary::new("Ogre", 1, AdversaryRole::Bruiser));
+
+        assert!(matches!(
+            builder.validate(),
+            Err(EngineError::InsufficientResource {
+                resource: Resource::BattlePoints,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_validate_rejects_higher_tier() {
+        let mut builder = EncounterBuilder::new(4, 1);
+        builder.add_adversary(Adversary::new("Warlord", 2, AdversaryRole::Leader));
+
+        assert!(matches!(
+            builder.validate(),
+            Err(EngineError::TierTooHigh {
+                tier: 2,
+                max_tier: 1,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_auto_generate_stays_in_budget() {
+        let library = test_library();
+        let mut rng = StdRng::seed_from_u64(1);
+        let mut builder = EncounterBuilder::new(4, 1);
+
+        builder.auto_generate_with_rng(&library, &mut rng).unwrap();
+
+        assert!(!builder.adversaries.is_empty());
+        assert!(builder.spent() <= builder.budget());
+        assert!(builder.adversaries.iter().all(|a| a.tier <= 1));
+    }
+
+    #[test]
+    fn test_auto_generate_is_reproducible() {
+        let library = test_library();
+        let mut builder1 = EncounterBuilder::new(4, 1);
+        let mut builder2 = EncounterBuilder::new(4, 1);
+
+        builder1
+            .auto_generate_with_rng(&library, &mut StdRng::seed_from_u64(9))
+            .unwrap();
+        builder2
+            .auto_generate_with_rng(&library, &mut StdRng::seed_from_u64(9))
+            .unwrap();
+
+        assert_eq!(builder1.adversaries, builder2.adversaries);
+    }
+
+    #[test]
+    fn test_auto_generate_skips_free_lower_tier_picks() {
+        // A tier 1 Minion costs 1 point and adds 1 to a tier 2 budget
+        let library = vec![Adversary::new("Goblin", 1, AdversaryRole::Minion)];
+        let mut builder = EncounterBuilder::new(4, 2);
+
+        builder
+            .auto_generate_with_rng(&library, &mut StdRng::seed_from_u64(1))
+            .unwrap();
+        assert!(builder.adversaries.is_empty());
+
+        let mut library = library;
+        library.push(Adversary::new("Bandit", 2, AdversaryRole::Standard));
+        builder
+            .auto_generate_with_rng(&library, &mut StdRng::seed_from_u64(1))
+            .unwrap();
+        assert!(builder.adversaries.iter().any(|a| a.name == "Bandit"));
+        assert!(builder.validate().is_ok());
+    }
+
+    #[test]
+    fn test_auto_generate_empty_library() {
+        let mut builder = EncounterBuilder::new(4, 1);
+        assert!(builder.auto_generate(&[]).is_err());
+    }
+
+    #[test]
+    fn test_build_expands_minions() {
+        let mut builder = EncounterBuilder::new(3, 1);
+        builder.add_adversary(Adversary::new("Goblin", 1, AdversaryRole::Minion));
+        builder.add_adversary(Adversary::new("Ogre", 1, AdversaryRole::Bruiser));
+
+        let encounter = builder.build(5).unwrap();
+        assert_eq!(encounter.combatants.len(), 4);
+        assert!(encounter.combatants.iter().all(|c| !c.is_player()));
+    }
+
+    fn ambush() -> EncounterTemplate {
+        EncounterTemplate::new("Ambush", 4, 1)
+            .with_adversary(Adversary::new("Ogre", 1, AdversaryRole::Bruiser))
+            .with_adversary(Adversary::new("Bandit", 1, AdversaryRole::Standard))
+            .with_adversary(Adversary::new("Bandit", 1, AdversaryRole::Standard))
+            .with_adversary(Adversary::new("Goblin", 1, AdversaryRole::Minion))
+    }
+
+    #[test]
+    fn test_scaling_changes_counts_by_budget() {
+        let template = ambush();
+        let count = |t: &EncounterTemplate, name: &str| {
+            t.adversaries.iter().filter(|a| a.name == name).count()
+        };
+
+        let bigger = template.scale_to(6, 1);
+        assert_eq!(count(&bigger, "Ogre"), 2);
+        assert_eq!(count(&bigger, "Bandit"), 3);
+        assert_eq!(count(&bigger, "Goblin"), 1);
+        assert_eq!(bigger.builder().remaining(), template.builder().remaining());
+
+        let smaller = template.scale_to(3, 1);
+        assert_eq!(count(&smaller, "Bandit"), 1);
+        assert_eq!(count(&smaller, "Ogre"), 1);
+        assert!(smaller.builder().validate().is_ok());
+
+        assert_eq!(template.scale_to(4, 1), template);
+    }
+
+    #[test]
+    fn test_scaling_skips_extras_that_spend_nothing() {
+        let template = EncounterTemplate::new("Escort", 4, 2)
+            .with_adversary(Adversary::new("Ogre", 2, AdversaryRole::Bruiser))
+            .with_adversary(Adversary::new("Acolyte", 1, AdversaryRole::Support));
+
+        let bigger = template.scale_to(5, 2);
+        assert_eq!(bigger.adversaries.len(), 2);
+        assert!(bigger.builder().validate().is_ok());
+    }
+
+    #[test]
+    fn test_scaling_tiers_and_solo_hp() {
+        let dragon = EncounterTemplate::new("Lair", 4, 1).with_adversary(
+            AdversaryBuilder::new("Dragon", 1, AdversaryRole::Solo)
+                .with_hp(10)
+                .build(),
+        );
+        let benchmark_hp = AdversaryBuilder::benchmark("Dragon", 1, AdversaryRole::Solo).hp;
+
+        let scaled = dragon.scale_to(4, 3);
+        let moved = &scaled.adversaries[0];
+        assert_eq!(moved.tier, 3);
+        assert_eq!(
+            moved.hp,
+            10 - benchmark_hp + AdversaryBuilder::benchmark("Dragon", 3, AdversaryRole::Solo).hp
+        );
+        assert!(moved.difficulty > dragon.adversaries[0].difficulty);
+        assert!(scaled.builder().validate().is_ok());
+
+        assert_eq!(dragon.scale_to(2, 1).adversaries[0].hp, 5);
+        assert_eq!(dragon.scale_to(6, 1).adversaries[0].hp, 15);
+    }
+}
+
+#[cfg(test)]
+mod property_tests {
+    use super::*;
+    use proptest::prelude::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    proptest! {
+        #[test]
+        fn prop_auto_generate_never_exceeds_budget(
+            party_size in 1u8..=6,
+            seed in any::<u64>(),
+        ) {
+            let library = vec![
+                Adversary::new("Goblin", 1, AdversaryRole::Minion),
+                Adversary::new("Bandit", 1, AdversaryRole::Standard),
+                Adversary::new("Ogre", 1, AdversaryRole::Bruiser),
+                Adversary::new("Dragon", 1, AdversaryRole::Solo),
+            ];
+            let mut builder = EncounterBuilder::new(party_size, 1);
+            let mut rng = StdRng::seed_from_u64(seed);
+
+            prop_assert!(builder.auto_generate_with_rng(&library, &mut rng).is_ok());
+            prop_assert!(builder.spent() <= builder.budget());
+        }
+    }
+}
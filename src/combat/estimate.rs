@@ -0,0 +1,350 @@
+//! Encounter difficulty estimates
+//!
+//! Rates an encounter for a party the way the SRD budgets one: the
+//! enemies' battle points against what the party can take on. A quick
+//! Monte Carlo run can back the estimate up with how automated fights
+//! actually go.
+
+use crate::character::progression::tier_for_level;
+use crate::combat::adversary::{Adversary, AdversaryRole};
+use crate::combat::encounter_builder::EncounterBuilder;
+use crate::combat::montecarlo::{MonteCarlo, MonteCarloReport};
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantKind};
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+/// Win rate below which a simulated encounter counts as Deadly
+const DEADLY_WIN_RATE: f64 = 0.5;
+
+/// Win rate below which a simulated encounter counts as Hard
+const HARD_WIN_RATE: f64 = 0.8;
+
+/// How dangerous an encounter is for a party
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Display, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Threat {
+    /// Half the budget or less
+    Trivial,
+    /// Within the budget
+    Standard,
+    /// Up to half again over the budget, or an adversary above the
+    /// party's tier
+    Hard,
+    /// Further over the budget, or lost in half the simulated fights
+    Deadly,
+}
+
+/// What one kind of enemy costs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct EnemyCost {
+    pub name: String,
+    pub role: AdversaryRole,
+    /// The role wasn't recorded, so Standard was assumed
+    pub assumed_role: bool,
+    pub tier: u8,
+    /// How many of them there are; Minions are grouped by name
+    pub count: u32,
+    pub battle_points: i16,
+}
+
+/// An encounter's threat to a party, with the numbers behind it
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DifficultyEstimate {
+    pub threat: Threat,
+    /// The label from battle points alone
+    pub battle_point_threat: Threat,
+    pub party_size: u8,
+    pub party_tier: u8,
+    /// Battle points a Standard encounter may spend on these enemies
+    pub budget: i16,
+    /// Battle points the enemies cost
+    pub spent: i16,
+    pub enemies: Vec<EnemyCost>,
+    /// Enemies from a tier above the party's
+    pub above_tier: Vec<String>,
+    /// The automated fights, if any were run
+    pub simulation: Option<MonteCarloReport>,
+}
+
+impl CombatEncounter {
+    /// Rate the encounter for a party from battle points
+    ///
+    /// With an empty `party`, the player characters already in the
+    /// encounter are rated. Enemies without a recorded role count as
+    /// Standard, and an enemy's level is read as its tier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::estimate::Threat;
+    /// use daggerheart_engine::combat::{Adversary, AdversaryRole, CombatEncounter, CombatantBuilder};
+    ///
+    /// let party: Vec<_> = ["Grom", "Ash"]
+    ///     .map(|name| {
+    ///         CombatantBuilder::player(name, Class::Warrior, Ancestry::Orc)
+    ///             .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///             .build()
+    ///             .unwrap()
+    ///     })
+    ///     .into();
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// encounter.add_combatant(Adversary::new("Bandit", 1, AdversaryRole::Standard).to_combatant());
+    /// assert_eq!(encounter.estimate_difficulty(&party).threat, Threat::Trivial);
+    ///
+    /// for _ in 0..3 {
+    ///     encounter.add_combatant(Adversary::new("Ogre", 1, AdversaryRole::Bruiser).to_combatant());
+    /// }
+    /// let estimate = encounter.estimate_difficulty(&party);
+    /// assert_eq!(estimate.spent, 14);
+    /// assert_eq!(estimate.threat, Threat::Deadly);
+    /// ```
+    pub fn estimate_difficulty(&self, party: &[Combatant]) -> DifficultyEstimate {
+        let party: Vec<&Combatant> = if party.is_empty() {
+            self.combatants
+                .iter()
+                .filter(|c| c.player_data().is_some())
+                .collect()
+        } else {
+            party.iter().collect()
+        };
+        let party_size = party.len().clamp(1, u8::MAX as usize) as u8;
+        let party_tier = tier_for_level(party.iter().map(|c| c.level).max().unwrap_or(1));
+
+        let mut enemies: Vec<EnemyCost> = Vec::new();
+        for combatant in &self.combatants {
+            let CombatantKind::Adversary(data) = combatant.kind else {
+                continue;
+            };
+            if data.allied {
+                continue;
+            }
+            let role = data.role.unwrap_or(AdversaryRole::Standard);
+            let tier = combatant.level.clamp(1, 4);
+            let grouped = enemies.iter_mut().find(|e| {
+                role == AdversaryRole::Minion && e.role == role && e.name == combatant.name
+            });
+            match grouped {
+                Some(group) => group.count += 1,
+                None => enemies.push(EnemyCost {
+                    name: combatant.name.to_string(),
+                    role,
+                    assumed_role: data.role.is_none(),
+                    tier,
+                    count: 1,
+                    battle_points: 0,
+                }),
+            }
+        }
+
+        // Price the enemies as encounter building would: one entry per
+        // adversary, or per group of Minions as large as the party
+        let mut builder = EncounterBuilder::new(party_size, party_tier);
+        for enemy in &mut enemies {
+            let entries = match enemy.role {
+                AdversaryRole::Minion => enemy.count.div_ceil(party_size as u32),
+                _ => 1,
+            };
+            enemy.battle_points = entries as i16 * enemy.role.battle_points() as i16;
+            for _ in 0..entries {
+                builder.add_adversary(Adversary::new(&enemy.name, enemy.tier, enemy.role));
+            }
+        }
+        let budget = builder.budget();
+        let spent = builder.spent();
+
+        let above_tier: Vec<String> = enemies
+            .iter()
+            .filter(|e| e.tier > party_tier)
+            .map(|e| e.name.clone())
+            .collect();
+        let mut threat = if spent * 2 <= budget {
+            Threat::Trivial
+        } else if spent <= budget {
+            Threat::Standard
+        } else if spent * 2 <= budget * 3 {
+            Threat::Hard
+        } else {
+            Threat::Deadly
+        };
+        if !above_tier.is_empty() {
+            threat = threat.max(Threat::Hard);
+        }
+
+        DifficultyEstimate {
+            threat,
+            battle_point_threat: threat,
+            party_size,
+            party_tier,
+            budget,
+            spent,
+            enemies,
+            above_tier,
+            simulation: None,
+        }
+    }
+
+    /// Rate the encounter from battle points and `runs` automated fights
+    ///
+    /// The fights pit `party` (or the encounter's own player characters)
+    /// against its enemies. Simulation can only raise the label: losing
+    /// half the fights makes it Deadly, and losing a fifth makes it at
+    /// least Hard.
+    pub fn estimate_difficulty_simulated(
+        &self,
+        party: &[Combatant],
+        runs: u32,
+        seed: u64,
+    ) -> DifficultyEstimate {
+        let mut estimate = self.estimate_difficulty(party);
+
+        let mut fight = self.clone();
+        if !party.is_empty() {
+            fight.combatants.retain(|c| c.player_data().is_none());
+            for member in party {
+                fight.add_combatant(member.clone());
+            }
+        }
+        let report = MonteCarlo::new(fight).run(runs, seed);
+
+        let simulated = if report.win_rate() < DEADLY_WIN_RATE {
+            Threat::Deadly
+        } else if report.win_rate() < HARD_WIN_RATE {
+            Threat::Hard
+        } else {
+            Threat::Trivial
+        };
+        estimate.threat = estimate.threat.max(simulated);
+        estimate.simulation = Some(report);
+        estimate
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Class};
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::test_support::player;
+
+    fn hero(name: &str, level: u8) -> Combatant {
+        player(name, Class::Guardian, Ancestry::Giant)
+            .with_level(level)
+            .with_equipment("Battleaxe")
+            .build()
+            .unwrap()
+    }
+
+    fn party(size: usize) -> Vec<Combatant> {
+        (0..size).map(|i| hero(&format!("Hero {}", i), 1)).collect()
+    }
+
+    fn add(encounter: &mut CombatEncounter, adversary: Adversary, count: usize) {
+        for _ in 0..count {
+            encounter.add_combatant(adversary.to_combatant());
+        }
+    }
+
+    #[test]
+    fn test_minions_are_priced_in_groups() {
+        let mut encounter = CombatEncounter::new(5);
+        add(
+            &mut encounter,
+            Adversary::new("Lackey", 1, AdversaryRole::Minion),
+            5,
+        );
+        let estimate = encounter.estimate_difficulty(&party(4));
+
+        assert_eq!(estimate.enemies.len(), 1);
+        assert_eq!(estimate.enemies[0].count, 5);
+        // Five Minions against four PCs take two groups
+        assert_eq!(estimate.spent, 2);
+        assert_eq!(estimate.threat, Threat::Trivial);
+    }
+
+    #[test]
+    fn test_budget_bands() {
+        // Four PCs at tier 1 have 14 points, plus 1 without heavy hitters
+        let mut encounter = CombatEncounter::new(5);
+        add(
+            &mut encounter,
+            Adversary::new("Bandit", 1, AdversaryRole::Standard),
+            6,
+        );
+        let estimate = encounter.estimate_difficulty(&party(4));
+        assert_eq!((estimate.spent, estimate.budget), (12, 15));
+        assert_eq!(estimate.threat, Threat::Standard);
+
+        add(
+            &mut encounter,
+            Adversary::new("Bandit", 1, AdversaryRole::Standard),
+            3,
+        );
+        assert_eq!(
+            encounter.estimate_difficulty(&party(4)).threat,
+            Threat::Hard
+        );
+    }
+
+    #[test]
+    fn test_unknown_roles_and_higher_tiers() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(
+            CombatantBuilder::adversary("Knight")
+                .with_level(3)
+                .with_hp(6)
+                .with_evasion(16)
+                .build()
+                .unwrap(),
+        );
+        let estimate = encounter.estimate_difficulty(&party(4));
+
+        assert!(estimate.enemies[0].assumed_role);
+        assert_eq!(estimate.above_tier, vec!["Knight"]);
+        assert_eq!(estimate.battle_point_threat, Threat::Hard);
+    }
+
+    #[test]
+    fn test_uses_encounter_party_and_ignores_allies() {
+        let mut encounter = CombatEncounter::new(5);
+        for hero in party(2) {
+            encounter.add_combatant(hero);
+        }
+        let mut ally = Adversary::new("Wolf", 1, AdversaryRole::Solo).to_combatant();
+        ally.kind = CombatantKind::Adversary(crate::combat::AdversaryData::ally());
+        encounter.add_combatant(ally);
+
+        let estimate = encounter.estimate_difficulty(&[]);
+        assert_eq!(estimate.party_size, 2);
+        assert!(estimate.enemies.is_empty());
+        assert_eq!(estimate.threat, Threat::Trivial);
+    }
+
+    #[test]
+    fn test_simulation_can_raise_the_label() {
+        let mut encounter = CombatEncounter::new(5);
+        add(
+            &mut encounter,
+            Adversary::new("Gorgon", 2, AdversaryRole::Solo)
+                .with_hp(30)
+                .with_attack(6, crate::core::dice::DamageDice::d12(4)),
+            1,
+        );
+        let party = vec![hero("Ash", 2)];
+
+        let quick = encounter.estimate_difficulty(&party);
+        let simulated = encounter.estimate_difficulty_simulated(&party, 50, 7);
+        let report = simulated.simulation.as_ref().unwrap();
+
+        assert_eq!(report.runs, 50);
+        assert!(simulated.threat >= quick.threat);
+        assert_eq!(simulated.threat, Threat::Deadly);
+        // The encounter itself is untouched
+        assert_eq!(encounter.combatants.len(), 1);
+    }
+}
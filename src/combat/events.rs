@@ -0,0 +1,328 @@
+//! Combat event log
+//!
+//! Every mutating operation on a `CombatEncounter` appends a `CombatEvent`
+//! to its log. UIs, replays, and narrators can consume the log instead of
+//! diffing encounter state.
+
+use crate::combat::attack::AttackResult;
+use crate::combat::conditions::Condition;
+use crate::combat::damage::DamageResult;
+use crate::combat::resources::Pool;
+use crate::combat::simulation::CombatantId;
+use crate::combat::summons::SummonSource;
+use serde::{Deserialize, Serialize};
+
+/// Something that happened during a combat encounter
+///
+/// Combatants are referenced by their `CombatantId`, which stays valid
+/// even after other combatants are removed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum CombatEvent {
+    /// A combatant joined the encounter
+    CombatantAdded {
+        combatant: CombatantId,
+        name: String,
+    },
+    /// A combatant rolled initiative
+    InitiativeRolled {
+        combatant: CombatantId,
+        initiative: u8,
+    },
+    /// A new round began
+    RoundStarted { round: u32 },
+    /// A combatant joined mid-combat as a summon or reinforcement
+    CombatantSummoned {
+        combatant: CombatantId,
+        source: SummonSource,
+    },
+    /// A combatant left the encounter
+    CombatantRemoved { combatant: CombatantId },
+    /// A combatant's turn ended
+    TurnEnded { combatant: CombatantId, round: u32 },
+    /// An attack roll was made against a target
+    AttackResolved {
+        attacker: CombatantId,
+        target: CombatantId,
+        result: AttackResult,
+    },
+    /// Damage was applied to a combatant
+    DamageApplied {
+        target: CombatantId,
+        damage: DamageResult,
+    },
+    /// A combatant marked Stress
+    StressGained { combatant: CombatantId, amount: u8 },
+    /// A combatant dropped to 0 HP
+    CombatantDefeated { combatant: CombatantId },
+    /// A character gained Hope (into the shared pool when playing with one)
+    HopeGained {
+        #[serde(default)]
+        combatant: CombatantId,
+        amount: u8,
+    },
+    /// A character spent Hope (from the shared pool when playing with one)
+    HopeSpent {
+        #[serde(default)]
+        combatant: CombatantId,
+        amount: u8,
+    },
+    /// The GM gained Fear
+    FearGained { amount: u8 },
+    /// The GM spent Fear
+    FearSpent { amount: u8 },
+    /// A condition was applied to a combatant
+    ConditionApplied {
+        combatant: CombatantId,
+        condition: Condition,
+    },
+    /// A condition was removed from a combatant
+    ConditionRemoved {
+        combatant: CombatantId,
+        condition: Condition,
+    },
+    /// A combatant is immune to a condition they would have gained
+    ConditionResisted {
+        combatant: CombatantId,
+        condition: Condition,
+    },
+    /// A combatant's triggered feature fired against an opponent
+    FeatureTriggered {
+        combatant: CombatantId,
+        feature: String,
+        opponent: CombatantId,
+    },
+    /// A combatant started taking ongoing damage
+    OngoingDamageStarted {
+        combatant: CombatantId,
+        name: String,
+    },
+    /// Ongoing damage was rolled against a combatant
+    OngoingDamageTicked {
+        combatant: CombatantId,
+        name: String,
+        rolled: u16,
+    },
+    /// Ongoing damage wore off or was removed
+    OngoingDamageEnded {
+        combatant: CombatantId,
+        name: String,
+    },
+    /// A combatant moved between zones
+    CombatantMoved {
+        combatant: CombatantId,
+        from: i8,
+        to: i8,
+    },
+    /// A rider's mount took damage
+    MountDamaged {
+        rider: CombatantId,
+        mount: String,
+        damage: DamageResult,
+    },
+    /// A combatant got or was thrown off their mount
+    Dismounted {
+        combatant: CombatantId,
+        mount: String,
+    },
+    /// A combatant climbed back on their mount
+    Mounted {
+        combatant: CombatantId,
+        mount: String,
+    },
+    /// A combatant interrupted an attack with a reaction
+    ReactionTaken {
+        combatant: CombatantId,
+        reaction: String,
+        attacker: CombatantId,
+    },
+    /// A combatant played a domain card
+    CardPlayed {
+        combatant: CombatantId,
+        card: String,
+    },
+    /// A character used their class's Hope feature
+    ClassFeatureUsed {
+        combatant: CombatantId,
+        feature: String,
+        /// Who the feature was used on, if anyone
+        target: Option<CombatantId>,
+    },
+    /// A character used an innate ability, like a breath weapon
+    AbilityUsed {
+        combatant: CombatantId,
+        ability: String,
+        targets: Vec<CombatantId>,
+    },
+    /// A countdown was started
+    CountdownAdded { name: String, rounds: u8 },
+    /// A countdown ran out
+    CountdownExpired { name: String },
+    /// A character spent an action token to take the spotlight
+    ActionTokenSpent {
+        combatant: CombatantId,
+        remaining: u8,
+    },
+    /// Every character's action tokens were refilled
+    ActionTokensRefreshed { tokens: u8 },
+    /// The GM took the spotlight, with an action for each token spent
+    GmSpotlight { actions: u8 },
+    /// A hazard struck a combatant, who rolled to avoid it
+    HazardTriggered {
+        combatant: CombatantId,
+        hazard: String,
+        total: u16,
+        saved: bool,
+    },
+    /// Two combatants rolled against each other
+    ContestResolved {
+        first: CombatantId,
+        second: CombatantId,
+        /// `None` on a tie
+        winner: Option<CombatantId>,
+        margin: u16,
+    },
+    /// A combatant traded out of one pool and into another
+    ResourcesConverted {
+        combatant: CombatantId,
+        from: Pool,
+        to: Pool,
+        paid: u8,
+        gained: u8,
+    },
+}
+
+impl CombatEvent {
+    /// Check whether this event involves a specific combatant
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::{CombatEvent, CombatantId};
+    ///
+    /// let event = CombatEvent::TurnEnded {
+    ///     combatant: CombatantId(2),
+    ///     round: 1,
+    /// };
+    /// assert!(event.involves(CombatantId(2)));
+    /// assert!(!event.involves(CombatantId(0)));
+    /// ```
+    pub fn involves(&self, id: CombatantId) -> bool {
+        match self {
+            Self::CombatantAdded { combatant, .. }
+            | Self::InitiativeRolled { combatant, .. }
+            | Self::TurnEnded { combatant, .. }
+            | Self::StressGained { combatant, .. }
+            | Self::CombatantDefeated { combatant }
+            | Self::CombatantRemoved { combatant }
+            | Self::ConditionApplied { combatant, .. }
+            | Self::ConditionRemoved { combatant, .. }
+            | Self::ConditionResisted { combatant, .. }
+            | Self::HopeGained { combatant, .. }
+            | Self::HopeSpent { combatant, .. }
+            | Self::CardPlayed { combatant, .. }
+            | Self::OngoingDamageStarted { combatant, .. }
+            | Self::OngoingDamageTicked { combatant, .. }
+            | Self::OngoingDamageEnded { combatant, .. }
+            | Self::CombatantMoved { combatant, .. }
+            | Self::Dismounted { combatant, .. }
+            | Self::Mounted { combatant, .. }
+            | Self::ActionTokenSpent { combatant, .. }
+            | Self::ResourcesConverted { combatant, .. } => *combatant == id,
+            Self::MountDamaged { rider, .. } => *rider == id,
+            Self::AttackResolved {
+                attacker, target, ..
+            } => *attacker == id || *target == id,
+            Self::CombatantSummoned { combatant, source } => {
+                *combatant == id
+                    || *source == SummonSource::Summoner { summoner: id }
+                    || *source == SummonSource::Companion { owner: id }
+            }
+            Self::DamageApplied { target, .. } => *target == id,
+            Self::FeatureTriggered {
+                combatant,
+                opponent,
+                ..
+            } => *combatant == id || *opponent == id,
+            Self::ReactionTaken {
+                combatant,
+                attacker,
+                ..
+            } => *combatant == id || *attacker == id,
+            Self::ClassFeatureUsed {
+                combatant, target, ..
+            } => *combatant == id || *target == Some(id),
+            Self::AbilityUsed {
+                combatant, targets, ..
+            } => *combatant == id || targets.contains(&id),
+            Self::ContestResolved { first, second, .. } => *first == id || *second == id,
+            Self::HazardTriggered { combatant, .. } => *combatant == id,
+            Self::RoundStarted { .. }
+            | Self::CountdownAdded { .. }
+            | Self::CountdownExpired { .. }
+            | Self::ActionTokensRefreshed { .. }
+            | Self::GmSpotlight { .. }
+            | Self::FearGained { .. }
+            | Self::FearSpent { .. } => false,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_involves_attack_both_sides() {
+        let event = CombatEvent::AttackResolved {
+            attacker: CombatantId(0),
+            target: CombatantId(1),
+            result: AttackResult {
+                hope: 10,
+                fear: 5,
+                modifier: 0,
+                total: 15,
+                with_hope: true,
+                with_fear: false,
+                critical: false,
+                hit: true,
+                advantage_die: None,
+                damage_dice: Vec::new(),
+            },
+        };
+
+        assert!(event.involves(CombatantId(0)));
+        assert!(event.involves(CombatantId(1)));
+        assert!(!event.involves(CombatantId(2)));
+    }
+
+    #[test]
+    fn test_pool_events_involve_nobody() {
+        assert!(!CombatEvent::FearGained { amount: 1 }.involves(CombatantId(0)));
+        assert!(!CombatEvent::RoundStarted { round: 1 }.involves(CombatantId(0)));
+    }
+
+    #[test]
+    fn test_hope_events_involve_the_character() {
+        let event = CombatEvent::HopeSpent {
+            combatant: CombatantId(1),
+            amount: 1,
+        };
+        assert!(event.involves(CombatantId(1)));
+        assert!(!event.involves(CombatantId(0)));
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_event_serialization() {
+        let event = CombatEvent::ConditionApplied {
+            combatant: CombatantId(1),
+            condition: Condition::Vulnerable,
+        };
+
+        let json = serde_json::to_string(&event).unwrap();
+        let loaded: CombatEvent = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(event, loaded);
+    }
+}
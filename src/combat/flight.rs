@@ -0,0 +1,234 @@
+//! Flight - taking to the air and coming back down
+//!
+//! A flying combatant is [`Condition::Flying`]: melee attacks from the
+//! ground can't reach them unless the attacker has the reach for it, and
+//! dropping out of the sky deals falling damage by how far they fell.
+//! Faeries can take flight at will; anyone else needs a card effect
+//! such as [`CardEffect::Flight`] to lift them. Tables that make flight
+//! tiring set [`RulesConfig::flight_stress`], and every flyer marks that
+//! much Stress as each round begins.
+//!
+//! [`CardEffect::Flight`]: crate::cards::CardEffect::Flight
+//! [`RulesConfig::flight_stress`]: crate::rules::RulesConfig::flight_stress
+
+use crate::combat::conditions::Condition;
+use crate::combat::damage::DamageResult;
+use crate::combat::events::CombatEvent;
+use crate::combat::movement::RangeBand;
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId};
+use crate::core::dice::Die;
+use crate::error::EngineError;
+use rand::Rng;
+
+impl Combatant {
+    /// Whether this combatant can take flight on their own
+    pub fn can_fly(&self) -> bool {
+        self.ancestry()
+            .is_some_and(|ancestry| ancestry.has_flight())
+    }
+
+    /// Whether this combatant is in the air
+    pub fn is_flying(&self) -> bool {
+        self.has_condition(Condition::Flying)
+    }
+
+    /// Whether this combatant can make a melee attack on `target`
+    ///
+    /// A flyer can only be reached from the air or by a combatant whose
+    /// melee reaches further than their own zone.
+    pub fn can_reach_in_melee(&self, target: &Combatant) -> bool {
+        !target.is_flying() || self.is_flying() || self.melee_reach() > RangeBand::Melee
+    }
+}
+
+impl CombatEncounter {
+    /// Take to the air
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder, Condition};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let attributes = Attributes::from_array([0, 2, 1, 1, 0, -1]).unwrap();
+    /// let pix = encounter.add_combatant(
+    ///     CombatantBuilder::player("Pix", Class::Rogue, Ancestry::Faerie)
+    ///         .with_attributes(attributes)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// let grom = encounter.add_combatant(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(attributes)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// encounter.take_flight(pix).unwrap();
+    /// assert!(encounter.get(pix).unwrap().has_condition(Condition::Flying));
+    /// assert!(encounter.take_flight(grom).is_err());
+    /// ```
+    pub fn take_flight(&mut self, combatant: CombatantId) -> Result<(), EngineError> {
+        let index = self.require(combatant)?;
+        let flyer = &self.combatants[index];
+        if !flyer.is_alive() {
+            return Err(EngineError::CombatantDefeated {
+                name: flyer.name.to_string(),
+            });
+        }
+        if !flyer.can_fly() {
+            return Err(EngineError::InvalidTarget {
+                reason: format!("{} can't fly", flyer.name),
+            });
+        }
+        self.apply_condition(combatant, Condition::Flying)
+    }
+
+    /// Come down safely
+    pub fn land(&mut self, combatant: CombatantId) -> Result<(), EngineError> {
+        self.remove_condition(combatant, Condition::Flying)
+    }
+
+    /// Drop from `height` and take falling damage
+    ///
+    /// Falling from within Melee range does no harm. From Very Close it
+    /// deals 1d10+3, from Close 1d20+5 and from Far or further 1d100+15.
+    /// Returns the damage dealt, if any.
+    pub fn fall(
+        &mut self,
+        combatant: CombatantId,
+        height: RangeBand,
+    ) -> Result<Option<DamageResult>, EngineError> {
+        let mut rng = rand::thread_rng();
+        self.fall_with_rng(combatant, height, &mut rng)
+    }
+
+    /// Drop from `height` with a specific RNG (for testing/seeding)
+    pub fn fall_with_rng<R: Rng>(
+        &mut self,
+        combatant: CombatantId,
+        height: RangeBand,
+        rng: &mut R,
+    ) -> Result<Option<DamageResult>, EngineError> {
+        self.land(combatant)?;
+        let damage = match height {
+            RangeBand::Melee => return Ok(None),
+            RangeBand::VeryClose => u16::from(Die::D10.roll_with_rng(rng)) + 3,
+            RangeBand::Close => u16::from(Die::D20.roll_with_rng(rng)) + 5,
+            RangeBand::Far | RangeBand::VeryFar => rng.gen_range(1..=100) + 15,
+        };
+        self.apply_damage(combatant, damage).map(Some)
+    }
+
+    /// Make every flyer mark the table's Stress for staying aloft
+    pub(crate) fn strain_flyers(&mut self) {
+        let stress = self.rules.flight_stress;
+        if stress == 0 {
+            return;
+        }
+        let mut strained = Vec::new();
+        for flyer in &mut self.combatants {
+            if flyer.is_alive() && flyer.is_flying() {
+                flyer.gain_stress(stress);
+                strained.push(flyer.id);
+            }
+        }
+        for combatant in strained {
+            self.log(CombatEvent::StressGained {
+                combatant,
+                amount: stress,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{CardEffect, Range, Target};
+    use crate::character::{Ancestry, Class};
+    use crate::combat::attack::Attack;
+    use crate::core::dice::DamageDice;
+    use crate::rules::RulesConfig;
+    use crate::test_support::player;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn hero(name: &str, ancestry: Ancestry) -> Combatant {
+        player(name, Class::Warrior, ancestry).build().unwrap()
+    }
+
+    #[test]
+    fn test_flyers_are_out_of_melee_reach() {
+        let mut encounter = CombatEncounter::new(5);
+        let pix = encounter.add_combatant(hero("Pix", Ancestry::Faerie));
+        let grom = encounter.add_combatant(hero("Grom", Ancestry::Orc));
+        let brak = encounter.add_combatant(hero("Brak", Ancestry::Giant));
+        encounter.take_flight(pix).unwrap();
+
+        let sword = Attack::new(2).with_range(Range::VeryClose);
+        assert!(matches!(
+            encounter.declare_attack(grom, pix, sword.clone(), DamageDice::d8(1)),
+            Err(EngineError::InvalidTarget { .. })
+        ));
+        assert!(encounter
+            .declare_attack(grom, pix, Attack::new(2), DamageDice::d8(1))
+            .is_ok());
+        assert!(encounter
+            .declare_attack(brak, pix, sword.clone(), DamageDice::d8(1))
+            .is_ok());
+
+        // A card lifts Grom up to meet Pix
+        let wings = CardEffect::flight(Target::SelfOnly);
+        assert!(encounter
+            .get_mut(grom)
+            .unwrap()
+            .apply_effect("Wings", &wings));
+        assert!(encounter
+            .declare_attack(grom, pix, sword, DamageDice::d8(1))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_falling_damage_grows_with_height() {
+        let mut encounter = CombatEncounter::new(5);
+        let pix = encounter.add_combatant(hero("Pix", Ancestry::Faerie));
+        let mut rng = StdRng::seed_from_u64(3);
+
+        encounter.take_flight(pix).unwrap();
+        assert_eq!(
+            encounter
+                .fall_with_rng(pix, RangeBand::Melee, &mut rng)
+                .unwrap(),
+            None
+        );
+        assert!(!encounter.get(pix).unwrap().is_flying());
+
+        encounter.take_flight(pix).unwrap();
+        let hurt = encounter
+            .fall_with_rng(pix, RangeBand::VeryFar, &mut rng)
+            .unwrap()
+            .unwrap();
+        assert!(hurt.raw_damage >= 16);
+        assert!(!encounter.get(pix).unwrap().is_flying());
+    }
+
+    #[test]
+    fn test_sustained_flight_costs_stress_when_the_table_says() {
+        let mut encounter = CombatEncounter::new(5);
+        let pix = encounter.add_combatant(hero("Pix", Ancestry::Faerie));
+        let grom = encounter.add_combatant(hero("Grom", Ancestry::Orc));
+        encounter.take_flight(pix).unwrap();
+        encounter.start();
+        encounter.next_turn();
+        encounter.next_turn();
+        assert_eq!(encounter.get(pix).unwrap().stress.current, 0);
+
+        encounter.rules = RulesConfig::default().with_flight_stress(1);
+        encounter.next_turn();
+        encounter.next_turn();
+        assert_eq!(encounter.get(pix).unwrap().stress.current, 1);
+        assert_eq!(encounter.get(grom).unwrap().stress.current, 0);
+    }
+}
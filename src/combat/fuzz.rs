@@ -0,0 +1,332 @@
+//! Fuzzing - random legal play against an encounter
+//!
+//! [`fuzz`] drives an encounter with random commands a table could make
+//! and checks [`CombatEncounter::check_invariants`] after each one: Hit
+//! Points and Hope within their maximums, Fear within its cap, and a turn
+//! order naming each combatant at most once and no one who fell before
+//! the round began. A failure comes back with a [`Replay`] that
+//! reproduces it.
+
+use crate::combat::conditions::Condition;
+use crate::combat::events::CombatEvent;
+use crate::combat::replay::{EncounterCommand, Replay};
+use crate::combat::simulation::{CombatEncounter, CombatantId};
+use crate::core::dice::DamageDice;
+use crate::error::EngineError;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use std::collections::HashSet;
+use strum::IntoEnumIterator;
+
+/// Mixed into the seed for the stream that picks commands, so it differs
+/// from the one rolling dice
+const CHOICE_STREAM: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// A fuzz run that broke an invariant
+#[derive(Debug)]
+pub struct FuzzFailure {
+    /// The commands up to and including the one that broke it
+    pub replay: Replay,
+    pub error: EngineError,
+}
+
+impl CombatEncounter {
+    /// Check that the encounter is in a state the rules can produce
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let goblin = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Goblin")
+    ///         .with_hp(4)
+    ///         .with_evasion(10)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// assert!(encounter.check_invariants().is_ok());
+    ///
+    /// encounter.get_mut(goblin).unwrap().hp.current = 9;
+    /// assert!(encounter.check_invariants().is_err());
+    /// ```
+    pub fn check_invariants(&self) -> Result<(), EngineError> {
+        let mut ids = HashSet::new();
+        for combatant in &self.combatants {
+            if !ids.insert(combatant.id) {
+                return violated(format!("two combatants share id {}", combatant.id));
+            }
+            if combatant.hp.current > combatant.hp.maximum {
+                return violated(format!(
+                    "{} has {} of {} Hit Points",
+                    combatant.name, combatant.hp.current, combatant.hp.maximum
+                ));
+            }
+            if let Some(hope) = combatant.hope() {
+                if hope.current > hope.maximum {
+                    return violated(format!(
+                        "{} has {} of {} Hope",
+                        combatant.name, hope.current, hope.maximum
+                    ));
+                }
+            }
+        }
+        if self.hope.current > self.hope.maximum {
+            return violated(format!(
+                "the party has {} of {} Hope",
+                self.hope.current, self.hope.maximum
+            ));
+        }
+        if self.fear.current > self.fear.maximum {
+            return violated(format!(
+                "the GM has {} of {} Fear",
+                self.fear.current, self.fear.maximum
+            ));
+        }
+
+        // Anyone down at the start of the round has left the turn order
+        let round_start = self
+            .events
+            .iter()
+            .rposition(|event| matches!(event, CombatEvent::RoundStarted { .. }))
+            .unwrap_or(0);
+        let fell_this_round: HashSet<CombatantId> = self.events[round_start..]
+            .iter()
+            .filter_map(|event| match event {
+                CombatEvent::CombatantDefeated { combatant } => Some(*combatant),
+                _ => None,
+            })
+            .collect();
+        let mut seen = HashSet::new();
+        for &id in &self.turn_order {
+            if !seen.insert(id) {
+                return violated(format!("{} takes two turns a round", id));
+            }
+            let Some(combatant) = self.get(id) else {
+                return violated(format!("{} is in the turn order but not the encounter", id));
+            };
+            if !combatant.is_alive() && !fell_this_round.contains(&id) {
+                return violated(format!(
+                    "{} is in the turn order after falling",
+                    combatant.name
+                ));
+            }
+        }
+        if !self.turn_order.is_empty() && self.current_turn >= self.turn_order.len() {
+            return violated(format!(
+                "turn {} of {}",
+                self.current_turn,
+                self.turn_order.len()
+            ));
+        }
+        Ok(())
+    }
+}
+
+fn violated(invariant: String) -> Result<(), EngineError> {
+    Err(EngineError::InvariantViolated { invariant })
+}
+
+/// A random command the table could make in the encounter's current state
+///
+/// Starts the encounter if it hasn't begun; afterwards picks among turns,
+/// attacks between living combatants, raw damage, Hope, Fear and
+/// conditions, only spending what there is to spend.
+pub fn random_command<R: Rng>(encounter: &CombatEncounter, rng: &mut R) -> EncounterCommand {
+    if encounter.round == 0 {
+        return EncounterCommand::Start;
+    }
+    let everyone: Vec<CombatantId> = encounter.combatants.iter().map(|c| c.id).collect();
+    let living: Vec<CombatantId> = encounter
+        .combatants
+        .iter()
+        .filter(|c| c.is_alive())
+        .map(|c| c.id)
+        .collect();
+    let characters: Vec<CombatantId> = encounter
+        .combatants
+        .iter()
+        .filter(|c| c.hope().is_some())
+        .map(|c| c.id)
+        .collect();
+
+    match rng.gen_range(0..10) {
+        0 | 1 if living.len() >= 2 => {
+            let attacker = *living.choose(rng).unwrap();
+            let target = *living
+                .iter()
+                .filter(|&&id| id != attacker)
+                .collect::<Vec<_>>()
+                .choose(rng)
+                .unwrap();
+            EncounterCommand::Attack {
+                attacker,
+                target: *target,
+                modifier: rng.gen_range(-1..=3),
+                advantage: rng.gen_bool(0.2),
+                damage: DamageDice::d6(rng.gen_range(1..=3)),
+            }
+        }
+        2 if !everyone.is_empty() => EncounterCommand::ApplyDamage {
+            target: *everyone.choose(rng).unwrap(),
+            amount: rng.gen_range(1..=20),
+        },
+        3 if !characters.is_empty() => {
+            let combatant = *characters.choose(rng).unwrap();
+            let amount = rng.gen_range(1..=3);
+            let have = encounter
+                .hope_pool(combatant)
+                .map_or(0, |hope| hope.current);
+            if rng.gen_bool(0.5) && have >= amount {
+                EncounterCommand::SpendHope { combatant, amount }
+            } else {
+                EncounterCommand::GainHope { combatant, amount }
+            }
+        }
+        4 => {
+            let amount = rng.gen_range(1..=3);
+            if rng.gen_bool(0.5) && encounter.fear.current >= amount {
+                EncounterCommand::SpendFear { amount }
+            } else {
+                EncounterCommand::GainFear { amount }
+            }
+        }
+        5 if !everyone.is_empty() => {
+            let target = *everyone.choose(rng).unwrap();
+            let condition = *Condition::iter().collect::<Vec<_>>().choose(rng).unwrap();
+            if rng.gen_bool(0.5) {
+                EncounterCommand::ApplyCondition { target, condition }
+            } else {
+                EncounterCommand::RemoveCondition { target, condition }
+            }
+        }
+        6 => EncounterCommand::EndRound,
+        _ => EncounterCommand::NextTurn,
+    }
+}
+
+/// Play `steps` random commands against an encounter, checking its
+/// invariants after each
+///
+/// Dice roll from `seed`, as in a [`Replay`], and a second stream derived
+/// from it picks the commands, so a seed always plays out the same way.
+/// Commands the encounter rejects are undone and left out of the replay.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+/// use daggerheart_engine::combat::fuzz::fuzz;
+/// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder};
+///
+/// let mut encounter = CombatEncounter::new(5);
+/// encounter.add_combatant(
+///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+///         .build()
+///         .unwrap(),
+/// );
+/// encounter.add_combatant(
+///     CombatantBuilder::adversary("Goblin")
+///         .with_hp(4)
+///         .with_evasion(10)
+///         .build()
+///         .unwrap(),
+/// );
+///
+/// let replay = fuzz(encounter, 42, 100).unwrap();
+/// assert!(replay.run().unwrap().check_invariants().is_ok());
+/// ```
+pub fn fuzz(initial: CombatEncounter, seed: u64, steps: usize) -> Result<Replay, Box<FuzzFailure>> {
+    let mut replay = Replay::new(initial.clone(), seed);
+    let mut encounter = initial;
+    let mut dice = StdRng::seed_from_u64(seed);
+    let mut choices = StdRng::seed_from_u64(seed ^ CHOICE_STREAM);
+
+    for _ in 0..steps {
+        let command = random_command(&encounter, &mut choices);
+        let before = (encounter.clone(), dice.clone());
+        if encounter
+            .apply_command_with_rng(&command, &mut dice)
+            .is_err()
+        {
+            (encounter, dice) = before;
+            continue;
+        }
+        replay.record(command);
+        if let Err(error) = encounter.check_invariants() {
+            return Err(Box::new(FuzzFailure { replay, error }));
+        }
+    }
+    Ok(replay)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Class};
+    use crate::combat::simulation::HopeMode;
+    use crate::test_support::{goblin, player};
+    use proptest::prelude::*;
+
+    fn create_test_encounter() -> CombatEncounter {
+        let mut encounter = CombatEncounter::new(5);
+        for (name, class) in [("Grom", Class::Warrior), ("Ash", Class::Ranger)] {
+            encounter.add_combatant(player(name, class, Ancestry::Human).build().unwrap());
+        }
+        for name in ["Goblin A", "Goblin B", "Goblin C"] {
+            encounter.add_combatant(goblin(name));
+        }
+        encounter
+    }
+
+    #[test]
+    fn test_fallen_combatants_break_the_turn_order_next_round() {
+        let mut encounter = create_test_encounter();
+        encounter.start_with_rng(&mut StdRng::seed_from_u64(1));
+        let goblin = CombatantId(2);
+        encounter.apply_damage(goblin, 100).unwrap();
+        // Falling mid-round is fine until the round ends
+        assert!(encounter.check_invariants().is_ok());
+
+        encounter.get_mut(goblin).unwrap().hp.current = 0;
+        encounter
+            .events
+            .retain(|event| !matches!(event, CombatEvent::CombatantDefeated { .. }));
+        assert!(matches!(
+            encounter.check_invariants(),
+            Err(EngineError::InvariantViolated { .. })
+        ));
+    }
+
+    #[test]
+    fn test_fuzz_replays_what_it_played() {
+        let replay = fuzz(create_test_encounter(), 11, 200).unwrap();
+        assert!(!replay.commands.is_empty());
+        assert_eq!(
+            fuzz(create_test_encounter(), 11, 200).unwrap().commands,
+            replay.commands
+        );
+        assert!(replay.run().unwrap().check_invariants().is_ok());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_random_play_keeps_invariants(seed in any::<u64>(), shared in any::<bool>()) {
+            let mut encounter = create_test_encounter();
+            if shared {
+                encounter.hope_mode = HopeMode::Shared;
+            }
+            if let Err(failure) = fuzz(encounter, seed, 150) {
+                prop_assert!(
+                    false,
+                    "{} after {:?}",
+                    failure.error,
+                    failure.replay.commands.last()
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,391 @@
+//! Hazards - environment dangers that strike on their own
+//!
+//! A collapsing bridge or a lava field is a [`Hazard`] on the encounter,
+//! usually brought in from the scene's environment. When its trigger comes
+//! up as turns advance, everyone it catches makes a reaction roll with the
+//! hazard's trait against its Difficulty, and those who fail take its
+//! damage and condition. Characters roll the duality dice plus the trait;
+//! adversaries roll a d20. Reaction rolls don't earn Hope or give Fear,
+//! and a critical always succeeds.
+
+use crate::character::AttributeType;
+use crate::combat::conditions::Condition;
+use crate::combat::events::CombatEvent;
+use crate::combat::modifiers::RollKind;
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId};
+use crate::core::dice::{ContestRoll, DamageDice};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// When a hazard strikes
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum HazardTrigger {
+    /// Everyone it catches, at the start of each round
+    EachRound,
+    /// Each combatant it catches, as their turn begins
+    TurnStart,
+    /// Everyone it catches, once the named countdown runs out
+    Countdown(String),
+}
+
+/// A danger in the environment that strikes without the GM rolling it
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::AttributeType;
+/// use daggerheart_engine::combat::{Condition, Hazard, HazardTrigger};
+/// use daggerheart_engine::core::dice::DamageDice;
+///
+/// let bridge = Hazard::new(
+///     "Collapsing bridge",
+///     HazardTrigger::Countdown(String::from("Bridge gives way")),
+///     AttributeType::Agility,
+///     13,
+/// )
+/// .with_damage(DamageDice::d10(2))
+/// .with_condition(Condition::Restrained)
+/// .in_zone(2);
+/// assert_eq!(bridge.zone, Some(2));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Hazard {
+    pub name: String,
+    pub trigger: HazardTrigger,
+    /// The trait characters roll to avoid it
+    pub save: AttributeType,
+    pub difficulty: u8,
+    /// Damage on a failed roll, reduced by armor as usual
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub damage: Option<DamageDice>,
+    /// Condition gained on a failed roll
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub condition: Option<Condition>,
+    /// Only combatants in this zone are caught; everyone if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub zone: Option<i8>,
+}
+
+impl Hazard {
+    /// Create a hazard with no damage or condition yet
+    pub fn new(
+        name: impl Into<String>,
+        trigger: HazardTrigger,
+        save: AttributeType,
+        difficulty: u8,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            trigger,
+            save,
+            difficulty,
+            damage: None,
+            condition: None,
+            zone: None,
+        }
+    }
+
+    /// Deal damage on a failed roll
+    pub fn with_damage(mut self, damage: DamageDice) -> Self {
+        self.damage = Some(damage);
+        self
+    }
+
+    /// Apply a condition on a failed roll
+    pub fn with_condition(mut self, condition: Condition) -> Self {
+        self.condition = Some(condition);
+        self
+    }
+
+    /// Only catch combatants in one zone
+    pub fn in_zone(mut self, zone: i8) -> Self {
+        self.zone = Some(zone);
+        self
+    }
+
+    /// Whether a combatant is caught when the hazard strikes
+    pub fn catches(&self, combatant: &Combatant) -> bool {
+        combatant.is_alive() && self.zone.is_none_or(|zone| zone == combatant.zone)
+    }
+}
+
+impl Combatant {
+    /// The modifier added to this combatant's reaction rolls with a trait
+    ///
+    /// Adversaries have no traits and add nothing.
+    pub fn reaction_modifier(&self, save: AttributeType) -> i8 {
+        match self.attributes() {
+            Some(attributes) => attributes
+                .get_modifier(save)
+                .saturating_add(self.total_bonus_for(RollKind::Action)),
+            None => 0,
+        }
+    }
+}
+
+impl CombatEncounter {
+    /// Add a hazard that strikes as turns advance
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::AttributeType;
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder, Hazard, HazardTrigger};
+    /// use daggerheart_engine::core::dice::DamageDice;
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let goblin = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Goblin").with_hp(4).with_evasion(10).build().unwrap(),
+    /// );
+    /// encounter.add_hazard(
+    ///     Hazard::new("Lava field", HazardTrigger::EachRound, AttributeType::Agility, 30)
+    ///         .with_damage(DamageDice::new(vec![]).with_bonus(20)),
+    /// );
+    /// encounter.start();
+    ///
+    /// // Only a natural 20 gets the goblin clear as the next round begins
+    /// encounter.next_turn();
+    /// let goblin = encounter.get(goblin).unwrap();
+    /// assert!(!goblin.is_alive() || encounter.round == 2);
+    /// ```
+    pub fn add_hazard(&mut self, hazard: Hazard) {
+        self.hazards.push(hazard);
+    }
+
+    /// Strike with the hazards set off as a new round began
+    ///
+    /// `since` is where the round's events start, so countdowns that ran
+    /// out with it set off their hazards too.
+    pub(crate) fn spring_round_hazards<R: Rng>(&mut self, since: usize, rng: &mut R) {
+        let expired: Vec<String> = self.events[since..]
+            .iter()
+            .filter_map(|event| match event {
+                CombatEvent::CountdownExpired { name } => Some(name.clone()),
+                _ => None,
+            })
+            .collect();
+        let due: Vec<Hazard> = self
+            .hazards
+            .iter()
+            .filter(|hazard| match &hazard.trigger {
+                HazardTrigger::EachRound => true,
+                HazardTrigger::TurnStart => false,
+                HazardTrigger::Countdown(name) => expired.contains(name),
+            })
+            .cloned()
+            .collect();
+
+        for hazard in due {
+            let caught: Vec<CombatantId> = self
+                .combatants
+                .iter()
+                .filter(|c| hazard.catches(c))
+                .map(|c| c.id)
+                .collect();
+            for id in caught {
+                self.strike(&hazard, id, rng);
+            }
+        }
+    }
+
+    /// Strike with the hazards that catch a combatant as their turn begins
+    pub(crate) fn spring_turn_hazards<R: Rng>(&mut self, combatant: CombatantId, rng: &mut R) {
+        let due: Vec<Hazard> = self
+            .hazards
+            .iter()
+            .filter(|hazard| hazard.trigger == HazardTrigger::TurnStart)
+            .cloned()
+            .collect();
+        for hazard in due {
+            let caught = self.get(combatant).is_some_and(|c| hazard.catches(c));
+            if caught {
+                self.strike(&hazard, combatant, rng);
+            }
+        }
+    }
+
+    /// Roll one combatant's reaction against a hazard and apply a failure
+    fn strike<R: Rng>(&mut self, hazard: &Hazard, target: CombatantId, rng: &mut R) {
+        // Someone an earlier hazard dropped may have left the field
+        let Some(combatant) = self.get(target) else {
+            return;
+        };
+        let roll = if combatant.is_player() {
+            ContestRoll::duality_with_rng(combatant.reaction_modifier(hazard.save), rng)
+        } else {
+            ContestRoll::adversary_with_rng(0, rng)
+        };
+        let saved = roll.is_critical() || roll.total() >= u16::from(hazard.difficulty);
+        self.log(CombatEvent::HazardTriggered {
+            combatant: target,
+            hazard: hazard.name.clone(),
+            total: roll.total(),
+            saved,
+        });
+        if saved {
+            return;
+        }
+
+        if let Some(damage) = &hazard.damage {
+            let rolled = damage.roll_with_rng(rng).total;
+            // The combatant was found above, so this can't fail
+            let _ = self.apply_damage(target, rolled);
+        }
+        if let Some(condition) = hazard.condition {
+            if self.get(target).is_some_and(|c| c.is_alive()) {
+                let _ = self.apply_condition(target, condition);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Class};
+    use crate::combat::countdown::Countdown;
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::test_support::player;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn create_test_encounter() -> (CombatEncounter, CombatantId, CombatantId) {
+        let mut encounter = CombatEncounter::new(5);
+        let grom = encounter.add_combatant(
+            player("Grom", Class::Warrior, Ancestry::Orc)
+                .with_hp(20)
+                .build()
+                .unwrap(),
+        );
+        let ogre = encounter.add_combatant(
+            CombatantBuilder::adversary("Ogre")
+                .with_hp(20)
+                .with_evasion(11)
+                .build()
+                .unwrap(),
+        );
+        (encounter, grom, ogre)
+    }
+
+    fn strikes(encounter: &CombatEncounter, id: CombatantId) -> Vec<bool> {
+        encounter
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                CombatEvent::HazardTriggered {
+                    combatant, saved, ..
+                } if *combatant == id => Some(*saved),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_countdown_sets_off_its_hazard() {
+        let (mut encounter, grom, ogre) = create_test_encounter();
+        encounter.get_mut(ogre).unwrap().zone = 1;
+        encounter.add_countdown(Countdown::new("Bridge gives way", 1));
+        encounter.add_hazard(
+            Hazard::new(
+                "Collapsing bridge",
+                HazardTrigger::Countdown(String::from("Bridge gives way")),
+                AttributeType::Agility,
+                40,
+            )
+            .with_damage(DamageDice::new(vec![]).with_bonus(3))
+            .with_condition(Condition::Restrained)
+            .in_zone(0),
+        );
+        let mut rng = StdRng::seed_from_u64(6);
+        encounter.start_with_rng(&mut rng);
+
+        // Nothing happens until the countdown runs out with round 2
+        encounter.next_turn_with_rng(&mut rng);
+        assert!(strikes(&encounter, grom).is_empty());
+        encounter.next_turn_with_rng(&mut rng);
+        assert_eq!(encounter.round, 2);
+
+        // Grom is on the bridge, the ogre isn't
+        let grom_strikes = strikes(&encounter, grom);
+        assert_eq!(grom_strikes.len(), 1);
+        assert!(strikes(&encounter, ogre).is_empty());
+        let restrained = encounter
+            .get(grom)
+            .unwrap()
+            .has_condition(Condition::Restrained);
+        assert_eq!(restrained, !grom_strikes[0]);
+
+        // It only strikes once
+        for _ in 0..4 {
+            encounter.next_turn_with_rng(&mut rng);
+        }
+        assert_eq!(strikes(&encounter, grom).len(), 1);
+    }
+
+    #[test]
+    fn test_turn_hazards_catch_each_combatant() {
+        let (mut encounter, grom, ogre) = create_test_encounter();
+        encounter.add_hazard(
+            Hazard::new(
+                "Choking smoke",
+                HazardTrigger::TurnStart,
+                AttributeType::Instinct,
+                1,
+            )
+            .with_damage(DamageDice::d6(1)),
+        );
+        let mut rng = StdRng::seed_from_u64(2);
+        encounter.start_with_rng(&mut rng);
+        for _ in 0..4 {
+            encounter.next_turn_with_rng(&mut rng);
+        }
+
+        // Difficulty 1 is always met, so everyone is struck and shrugs it
+        // off; whoever goes first is struck on the first turn too
+        let first = encounter.turn_order[0];
+        for id in [grom, ogre] {
+            let struck = strikes(&encounter, id);
+            assert_eq!(struck.len(), if id == first { 3 } else { 2 });
+            assert!(struck.iter().all(|&saved| saved));
+            assert_eq!(encounter.get(id).unwrap().hp.current, 20);
+        }
+    }
+
+    #[test]
+    fn test_round_hazards_strike_from_round_one() {
+        let (mut encounter, grom, ogre) = create_test_encounter();
+        encounter.add_hazard(Hazard::new(
+            "Howling wind",
+            HazardTrigger::EachRound,
+            AttributeType::Strength,
+            1,
+        ));
+        let mut rng = StdRng::seed_from_u64(3);
+        encounter.start_with_rng(&mut rng);
+        for id in [grom, ogre] {
+            assert_eq!(strikes(&encounter, id).len(), 1);
+        }
+
+        encounter.end_round_with_rng(&mut rng);
+        for id in [grom, ogre] {
+            assert_eq!(strikes(&encounter, id).len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_reaction_modifier_uses_the_trait() {
+        let (encounter, grom, ogre) = create_test_encounter();
+        let grom = encounter.get(grom).unwrap();
+        assert_eq!(grom.reaction_modifier(AttributeType::Agility), 2);
+        assert_eq!(grom.reaction_modifier(AttributeType::Knowledge), -1);
+        assert_eq!(
+            encounter
+                .get(ogre)
+                .unwrap()
+                .reaction_modifier(AttributeType::Agility),
+            0
+        );
+    }
+}
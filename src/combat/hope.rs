@@ -0,0 +1,335 @@
+//! Hope spending - the menu of things a character can spend Hope on
+//!
+//! Lists each way a character could spend Hope right now, what it costs,
+//! and, if it can't be taken, why not: helping an ally, using an
+//! Experience, the class Hope feature, a Tag Team roll, and Hope-costing
+//! reactions from their domain cards. UIs can build a spend-Hope menu
+//! from it without encoding the rules themselves.
+
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId, HopeMode};
+use crate::content::ContentRegistry;
+use crate::error::EngineError;
+use crate::rules::RulesConfig;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Hope spent to give an ally advantage on their roll
+pub const HELP_ALLY_HOPE: u8 = 1;
+/// Hope spent to add an Experience to a roll
+pub const EXPERIENCE_HOPE: u8 = 1;
+/// Hope spent to start a Tag Team roll
+pub const TAG_TEAM_HOPE: u8 = 3;
+
+/// Something a character can spend Hope on
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum HopeAction {
+    /// Give an ally advantage on their roll
+    HelpAlly,
+    /// Add an Experience's bonus to a roll
+    UseExperience { name: String, bonus: i8 },
+    /// The class's signature Hope feature
+    ClassFeature { name: String },
+    /// Roll together with an ally and pick either result
+    TagTeam,
+    /// A reaction that costs Hope, named after its card if it has one
+    Reaction { name: String, card: Option<String> },
+}
+
+impl fmt::Display for HopeAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HopeAction::HelpAlly => write!(f, "Help an ally"),
+            HopeAction::UseExperience { name, bonus } => write!(f, "{} ({:+})", name, bonus),
+            HopeAction::ClassFeature { name } | HopeAction::Reaction { name, .. } => {
+                write!(f, "{}", name)
+            }
+            HopeAction::TagTeam => write!(f, "Tag Team roll"),
+        }
+    }
+}
+
+/// Why a Hope option can't be taken right now
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Unavailable {
+    /// The character is down
+    Defeated,
+    /// Not enough Hope to pay
+    NotEnoughHope { have: u8, need: u8 },
+    /// Nobody else on the players' side to help or team up with
+    NoAlly,
+    /// The card needs a higher level
+    LevelTooLow { required: u8 },
+    /// The party shares one pool, which the character alone can't see
+    SharedPool,
+}
+
+impl fmt::Display for Unavailable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unavailable::Defeated => write!(f, "Defeated"),
+            Unavailable::NotEnoughHope { have, need } => {
+                write!(f, "Needs {} Hope, has {}", need, have)
+            }
+            Unavailable::NoAlly => write!(f, "No ally to work with"),
+            Unavailable::LevelTooLow { required } => write!(f, "Needs level {}", required),
+            Unavailable::SharedPool => write!(f, "Paid from the party's shared Hope"),
+        }
+    }
+}
+
+/// One entry on the spend-Hope menu
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct HopeOption {
+    pub action: HopeAction,
+    pub cost: u8,
+    /// Why it can't be taken, if it can't
+    pub unavailable: Option<Unavailable>,
+}
+
+impl HopeOption {
+    /// Whether the character can take this option now
+    pub fn is_valid(&self) -> bool {
+        self.unavailable.is_none()
+    }
+}
+
+impl Combatant {
+    /// Everything this character could spend Hope on, with costs
+    ///
+    /// Costs are checked against the character's own Hope. With a shared
+    /// pool every option is marked [`Unavailable::SharedPool`]; use
+    /// [`CombatEncounter::hope_options`] to check against the pool and
+    /// whether there are allies to help. Adversaries have no Hope and get
+    /// an empty menu.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class, Experience};
+    /// use daggerheart_engine::combat::hope::HopeAction;
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    /// use daggerheart_engine::content::ContentRegistry;
+    /// use daggerheart_engine::rules::RulesConfig;
+    ///
+    /// let grom = CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///     .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///     .with_experience(Experience::new("Bounty Hunter", 2))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let options = grom.hope_options(&RulesConfig::default(), &ContentRegistry::new());
+    /// // Starting Hope covers an Experience but not No Mercy
+    /// let valid: Vec<_> = options.iter().filter(|o| o.is_valid()).collect();
+    /// assert!(valid
+    ///     .iter()
+    ///     .any(|o| matches!(&o.action, HopeAction::UseExperience { name, .. } if name == "Bounty Hunter")));
+    /// assert!(!valid.iter().any(|o| matches!(o.action, HopeAction::ClassFeature { .. })));
+    /// ```
+    pub fn hope_options(&self, rules: &RulesConfig, content: &ContentRegistry) -> Vec<HopeOption> {
+        let hope = match rules.hope_mode {
+            HopeMode::PerCharacter => self.hope().map(|hope| hope.current),
+            HopeMode::Shared => None,
+        };
+        self.hope_menu(hope, true, content)
+    }
+
+    /// The menu, checked against `hope` if known
+    fn hope_menu(
+        &self,
+        hope: Option<u8>,
+        has_ally: bool,
+        content: &ContentRegistry,
+    ) -> Vec<HopeOption> {
+        let Some(class) = self.class() else {
+            return Vec::new();
+        };
+        let check = |cost: u8, needs_ally: bool| {
+            if !self.is_alive() {
+                return Some(Unavailable::Defeated);
+            }
+            if needs_ally && !has_ally {
+                return Some(Unavailable::NoAlly);
+            }
+            match hope {
+                None => Some(Unavailable::SharedPool),
+                Some(have) if have < cost => Some(Unavailable::NotEnoughHope { have, need: cost }),
+                Some(_) => None,
+            }
+        };
+
+        let mut options = vec![HopeOption {
+            action: HopeAction::HelpAlly,
+            cost: HELP_ALLY_HOPE,
+            unavailable: check(HELP_ALLY_HOPE, true),
+        }];
+        for experience in &self.experiences {
+            options.push(HopeOption {
+                action: HopeAction::UseExperience {
+                    name: experience.name.clone(),
+                    bonus: experience.bonus,
+                },
+                cost: EXPERIENCE_HOPE,
+                unavailable: check(EXPERIENCE_HOPE, false),
+            });
+        }
+        let feature = class.hope_feature();
+        options.push(HopeOption {
+            action: HopeAction::ClassFeature {
+                name: String::from(feature.name),
+            },
+            cost: feature.hope_cost,
+            unavailable: check(feature.hope_cost, false),
+        });
+        options.push(HopeOption {
+            action: HopeAction::TagTeam,
+            cost: TAG_TEAM_HOPE,
+            unavailable: check(TAG_TEAM_HOPE, true),
+        });
+        for reaction in self.reactions.iter().filter(|r| r.cost.hope > 0) {
+            let card = reaction.card.as_deref().and_then(|id| content.card(id));
+            let unavailable = match card {
+                Some(card) if card.level_requirement > self.level => {
+                    Some(Unavailable::LevelTooLow {
+                        required: card.level_requirement,
+                    })
+                }
+                _ => check(reaction.cost.hope, false),
+            };
+            options.push(HopeOption {
+                action: HopeAction::Reaction {
+                    name: card.map_or_else(|| reaction.name.clone(), |card| card.name.clone()),
+                    card: reaction.card.clone(),
+                },
+                cost: reaction.cost.hope,
+                unavailable,
+            });
+        }
+        options
+    }
+}
+
+impl CombatEncounter {
+    /// Everything a character could spend Hope on in this encounter
+    ///
+    /// Costs are checked against the Hope they would pay from under the
+    /// encounter's `hope_mode`, and helping or teaming up needs another
+    /// living character on their side.
+    pub fn hope_options(
+        &self,
+        combatant: CombatantId,
+        content: &ContentRegistry,
+    ) -> Result<Vec<HopeOption>, EngineError> {
+        let character = &self.combatants[self.require(combatant)?];
+        let hope = self.hope_pool(combatant).ok().map(|pool| pool.current);
+        let has_ally = self.combatants.iter().any(|other| {
+            other.id != combatant && other.player_data().is_some() && other.is_alive()
+        });
+        Ok(character.hope_menu(hope, has_ally, content))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{ActionCost, DomainCard};
+    use crate::character::{Ancestry, Class, Domain, Experience};
+    use crate::combat::reactions::{Reaction, ReactionEffect, ReactionWindow};
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::test_support::player;
+
+    fn hero(name: &str) -> Combatant {
+        player(name, Class::Warrior, Ancestry::Human)
+            .with_experience(Experience::new("Sailor", 2))
+            .build()
+            .unwrap()
+    }
+
+    fn unavailable(options: &[HopeOption]) -> Vec<(String, Option<Unavailable>)> {
+        options
+            .iter()
+            .map(|option| (option.action.to_string(), option.unavailable))
+            .collect()
+    }
+
+    #[test]
+    fn test_menu_checks_costs_against_own_hope() {
+        let grom = hero("Grom");
+        let options = grom.hope_options(&RulesConfig::default(), &ContentRegistry::new());
+        let short = Some(Unavailable::NotEnoughHope { have: 2, need: 3 });
+        assert_eq!(
+            unavailable(&options),
+            vec![
+                (String::from("Help an ally"), None),
+                (String::from("Sailor (+2)"), None),
+                (String::from("No Mercy"), short),
+                (String::from("Tag Team roll"), short),
+            ]
+        );
+
+        let shared = RulesConfig::default().with_hope_mode(HopeMode::Shared);
+        assert!(grom
+            .hope_options(&shared, &ContentRegistry::new())
+            .iter()
+            .all(|o| o.unavailable == Some(Unavailable::SharedPool)));
+
+        let goblin = CombatantBuilder::adversary("Goblin")
+            .with_hp(4)
+            .with_evasion(10)
+            .build()
+            .unwrap();
+        assert!(goblin
+            .hope_options(&RulesConfig::default(), &ContentRegistry::new())
+            .is_empty());
+    }
+
+    #[test]
+    fn test_card_reactions_use_the_registry() {
+        let card = DomainCard::new(
+            "deft_parry",
+            "Deft Parry",
+            Domain::Blade,
+            3,
+            "Spend a Hope to add +2 to your Evasion",
+            ActionCost::Reaction,
+        );
+        let mut content = ContentRegistry::new();
+        content.add_card(card.clone());
+        let mut grom = hero("Grom");
+        grom.reactions.push(
+            Reaction::from_card(
+                &card,
+                ReactionWindow::AttackDeclared,
+                ReactionEffect::EvasionBonus(2),
+            )
+            .costing_hope(1),
+        );
+
+        let options = grom.hope_options(&RulesConfig::default(), &content);
+        let parry = options.last().unwrap();
+        assert_eq!(parry.action.to_string(), "Deft Parry");
+        assert_eq!(
+            parry.unavailable,
+            Some(Unavailable::LevelTooLow { required: 3 })
+        );
+    }
+
+    #[test]
+    fn test_encounter_menu_needs_allies_and_uses_the_pool() {
+        let mut encounter = CombatEncounter::new(5);
+        let grom = encounter.add_combatant(hero("Grom"));
+        let content = ContentRegistry::new();
+
+        let options = encounter.hope_options(grom, &content).unwrap();
+        assert_eq!(options[0].unavailable, Some(Unavailable::NoAlly));
+        assert!(options[1].is_valid());
+
+        encounter.add_combatant(hero("Lia"));
+        encounter.hope_mode = HopeMode::Shared;
+        let options = encounter.hope_options(grom, &content).unwrap();
+        assert!(options.iter().all(HopeOption::is_valid));
+        assert!(encounter.hope_options(CombatantId(99), &content).is_err());
+    }
+}
@@ -0,0 +1,322 @@
+//! Innate abilities - ancestry features used as actions
+//!
+//! Some ancestry features are used like a card: a Drakona's breath, a
+//! Ribbet's tongue. They show up in a character's action list with the
+//! uses left before a rest, and using one spends a use and resolves its
+//! effect. Uses are kept on the combatant, so they go back to the
+//! character sheet when an encounter ends and come back on a rest.
+
+use crate::cards::{CardEffect, Target};
+use crate::character::AncestryEffect;
+use crate::combat::attack::{Attack, AttackResult};
+use crate::combat::events::CombatEvent;
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId};
+use crate::error::EngineError;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// Something a combatant can do with the spotlight
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Action {
+    pub name: String,
+    pub description: String,
+    pub effect: CardEffect,
+    /// Uses left before a rest; `None` for no limit
+    pub uses_left: Option<u8>,
+}
+
+impl Action {
+    /// Whether there is a use left
+    pub fn is_available(&self) -> bool {
+        self.uses_left != Some(0)
+    }
+}
+
+impl Combatant {
+    /// The innate abilities this combatant can use, with uses left
+    ///
+    /// Adversaries and ancestries without such abilities have none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    ///
+    /// let mut vyr = CombatantBuilder::player("Vyr", Class::Guardian, Ancestry::Drakona)
+    ///     .with_attributes(Attributes::from_array([1, 0, 2, 1, 0, -1]).unwrap())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let actions = vyr.actions();
+    /// assert_eq!(actions[0].name, "Breath Weapon");
+    /// assert_eq!(actions[0].uses_left, Some(1));
+    ///
+    /// vyr.use_ability("Breath Weapon").unwrap();
+    /// assert!(!vyr.actions()[0].is_available());
+    /// assert!(vyr.use_ability("Breath Weapon").is_err());
+    ///
+    /// vyr.refresh_abilities();
+    /// assert!(vyr.actions()[0].is_available());
+    /// ```
+    pub fn actions(&self) -> Vec<Action> {
+        let Some(ancestry) = self.ancestry() else {
+            return Vec::new();
+        };
+        ancestry
+            .features()
+            .into_iter()
+            .filter_map(|feature| match feature.effect {
+                AncestryEffect::Ability {
+                    effect,
+                    uses_per_rest,
+                } => Some(Action {
+                    name: feature.name.to_string(),
+                    description: feature.description.to_string(),
+                    effect,
+                    uses_left: uses_per_rest
+                        .map(|uses| uses.saturating_sub(self.times_used(feature.name))),
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// Spend a use of an innate ability, by name ignoring case
+    ///
+    /// Returns the ability for the caller to resolve; use
+    /// [`CombatEncounter::use_ability`] to resolve it in an encounter.
+    pub fn use_ability(&mut self, name: &str) -> Result<Action, EngineError> {
+        if !self.is_alive() {
+            return Err(EngineError::CombatantDefeated {
+                name: self.name.to_string(),
+            });
+        }
+        let action = self
+            .actions()
+            .into_iter()
+            .find(|action| action.name.eq_ignore_ascii_case(name))
+            .ok_or_else(|| EngineError::UnknownAbility {
+                combatant: self.name.to_string(),
+                ability: name.to_string(),
+            })?;
+        if !action.is_available() {
+            return Err(EngineError::AbilityExhausted {
+                combatant: self.name.to_string(),
+                ability: action.name.clone(),
+            });
+        }
+        self.abilities_used.push(action.name.clone());
+        Ok(action)
+    }
+
+    /// Get back every use of innate abilities, as on a rest
+    pub fn refresh_abilities(&mut self) {
+        self.abilities_used.clear();
+    }
+
+    fn times_used(&self, ability: &str) -> u8 {
+        let used = self.abilities_used.iter().filter(|name| *name == ability);
+        used.count().min(u8::MAX as usize) as u8
+    }
+}
+
+impl CombatEncounter {
+    /// A combatant's action list
+    pub fn actions(&self, combatant: CombatantId) -> Result<Vec<Action>, EngineError> {
+        Ok(self.combatants[self.require(combatant)?].actions())
+    }
+
+    /// Use an innate ability against `targets`
+    ///
+    /// Abilities that only affect the user ignore `targets`. Attacks roll
+    /// `attack` against each target in turn and return the results;
+    /// other effects are applied to each target.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::{Attack, CombatEncounter, CombatantBuilder};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let vyr = encounter.add_combatant(
+    ///     CombatantBuilder::player("Vyr", Class::Guardian, Ancestry::Drakona)
+    ///         .with_attributes(Attributes::from_array([1, 0, 2, 1, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// let goblins: Vec<_> = (0..2)
+    ///     .map(|_| {
+    ///         encounter.add_combatant(
+    ///             CombatantBuilder::adversary("Goblin")
+    ///                 .with_hp(4)
+    ///                 .with_evasion(10)
+    ///                 .build()
+    ///                 .unwrap(),
+    ///         )
+    ///     })
+    ///     .collect();
+    ///
+    /// let results = encounter
+    ///     .use_ability(vyr, "Breath Weapon", &goblins, &Attack::new(2))
+    ///     .unwrap();
+    /// assert_eq!(results.len(), 2);
+    /// ```
+    pub fn use_ability(
+        &mut self,
+        user: CombatantId,
+        ability: &str,
+        targets: &[CombatantId],
+        attack: &Attack,
+    ) -> Result<Vec<AttackResult>, EngineError> {
+        let mut rng = rand::thread_rng();
+        self.use_ability_with_rng(user, ability, targets, attack, &mut rng)
+    }
+
+    /// Use an innate ability with a specific RNG (for testing/seeding)
+    pub fn use_ability_with_rng<R: Rng>(
+        &mut self,
+        user: CombatantId,
+        ability: &str,
+        targets: &[CombatantId],
+        attack: &Attack,
+        rng: &mut R,
+    ) -> Result<Vec<AttackResult>, EngineError> {
+        let index = self.require(user)?;
+        for &target in targets {
+            self.require(target)?;
+        }
+        let user_name = self.combatants[index].name.to_string();
+        let effect_target = self.combatants[index]
+            .actions()
+            .into_iter()
+            .find(|action| action.name.eq_ignore_ascii_case(ability))
+            .map(|action| action.effect.target())
+            .ok_or_else(|| EngineError::UnknownAbility {
+                combatant: user_name.clone(),
+                ability: ability.to_string(),
+            })?;
+        let targets = if effect_target == Some(Target::SelfOnly) {
+            vec![user]
+        } else {
+            targets.to_vec()
+        };
+        if targets.is_empty() {
+            return Err(EngineError::InvalidTarget {
+                reason: format!("{} needs a target", ability),
+            });
+        }
+
+        let action = self.combatants[index].use_ability(ability)?;
+        self.log(CombatEvent::AbilityUsed {
+            combatant: user,
+            ability: action.name.clone(),
+            targets: targets.clone(),
+        });
+
+        let mut results = Vec::new();
+        for target in targets {
+            if action.effect.is_attack() {
+                results.push(self.resolve_card_attack_with_rng(
+                    user,
+                    target,
+                    &action.effect,
+                    attack,
+                    rng,
+                )?);
+            } else if let Some(combatant) = self.get_mut(target) {
+                combatant.apply_effect(&action.name, &action.effect);
+            }
+        }
+        Ok(results)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Class};
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::test_support::player;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn hero(name: &str, ancestry: Ancestry) -> Combatant {
+        player(name, Class::Warrior, ancestry).build().unwrap()
+    }
+
+    #[test]
+    fn test_only_abilities_are_actions() {
+        assert!(hero("Grom", Ancestry::Orc).actions().is_empty());
+        let ribbet = hero("Pip", Ancestry::Ribbet).actions();
+        assert_eq!(ribbet.len(), 1);
+        assert_eq!(ribbet[0].name, "Long Tongue");
+        assert_eq!(ribbet[0].uses_left, None);
+
+        let goblin = CombatantBuilder::adversary("Goblin")
+            .with_hp(4)
+            .with_evasion(10)
+            .build()
+            .unwrap();
+        assert!(goblin.actions().is_empty());
+    }
+
+    #[test]
+    fn test_self_only_abilities_ignore_targets() {
+        let mut encounter = CombatEncounter::new(5);
+        let clank = encounter.add_combatant(hero("Cog", Ancestry::Clank));
+        encounter.apply_damage(clank, 20).unwrap();
+        let hp = encounter.get(clank).unwrap().hp.current;
+
+        let mut rng = StdRng::seed_from_u64(1);
+        encounter
+            .use_ability_with_rng(clank, "repair protocol", &[], &Attack::new(0), &mut rng)
+            .unwrap();
+        assert_eq!(encounter.get(clank).unwrap().hp.current, hp + 1);
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::AbilityUsed {
+                combatant: clank,
+                ability: String::from("Repair Protocol"),
+                targets: vec![clank],
+            })
+        );
+        assert!(matches!(
+            encounter.use_ability_with_rng(
+                clank,
+                "Repair Protocol",
+                &[],
+                &Attack::new(0),
+                &mut rng
+            ),
+            Err(EngineError::AbilityExhausted { .. })
+        ));
+        assert!(matches!(
+            encounter.use_ability_with_rng(clank, "Fly", &[], &Attack::new(0), &mut rng),
+            Err(EngineError::UnknownAbility { .. })
+        ));
+    }
+
+    #[test]
+    fn test_attacks_need_a_target_before_spending_a_use() {
+        let mut encounter = CombatEncounter::new(5);
+        let vyr = encounter.add_combatant(hero("Vyr", Ancestry::Drakona));
+        let mut rng = StdRng::seed_from_u64(1);
+
+        assert!(encounter
+            .use_ability_with_rng(vyr, "Breath Weapon", &[], &Attack::new(0), &mut rng)
+            .is_err());
+        assert!(encounter
+            .use_ability_with_rng(
+                vyr,
+                "Breath Weapon",
+                &[CombatantId(99)],
+                &Attack::new(0),
+                &mut rng
+            )
+            .is_err());
+        assert_eq!(encounter.actions(vyr).unwrap()[0].uses_left, Some(1));
+    }
+}
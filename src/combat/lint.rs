@@ -0,0 +1,307 @@
+//! Linting - finding and fixing inconsistent saved encounters
+//!
+//! A save edited by hand, or written by an older version, can hold an
+//! encounter no sequence of commands would produce: a turn order naming
+//! someone who was removed, a turn past the end of the order, pools over
+//! their maximums. [`CombatEncounter::validate`] lists what is wrong and
+//! [`CombatEncounter::repair`] fixes it, so a bad file loads instead of
+//! failing halfway through a command.
+
+use crate::combat::simulation::{CombatEncounter, CombatantId};
+use crate::error::Resource;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fmt;
+
+/// Something inconsistent in an encounter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum EncounterIssue {
+    /// Two combatants share an id
+    DuplicateId { id: CombatantId },
+    /// The next id to hand out already belongs to someone
+    StaleNextId { next: u32 },
+    /// The turn order names someone who isn't in the encounter
+    UnknownInTurnOrder { id: CombatantId },
+    /// The turn order names someone more than once
+    RepeatedInTurnOrder { id: CombatantId },
+    /// The current turn is past the end of the turn order
+    TurnOutOfRange { turn: usize, turns: usize },
+    /// A pool holds more than its maximum; `combatant` is `None` for the
+    /// party's Hope and the GM's Fear
+    OverMaximum {
+        combatant: Option<CombatantId>,
+        resource: Resource,
+        current: u8,
+        maximum: u8,
+    },
+    /// An action token pool belongs to someone who isn't in the encounter
+    OrphanedTokens { id: CombatantId },
+}
+
+impl fmt::Display for EncounterIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncounterIssue::DuplicateId { id } => write!(f, "two combatants share id {}", id),
+            EncounterIssue::StaleNextId { next } => {
+                write!(f, "the next id, {}, is already taken", next)
+            }
+            EncounterIssue::UnknownInTurnOrder { id } => {
+                write!(f, "{} is in the turn order but not the encounter", id)
+            }
+            EncounterIssue::RepeatedInTurnOrder { id } => {
+                write!(f, "{} takes more than one turn a round", id)
+            }
+            EncounterIssue::TurnOutOfRange { turn, turns } => {
+                write!(f, "turn {} of a {}-turn order", turn, turns)
+            }
+            EncounterIssue::OverMaximum {
+                combatant,
+                resource,
+                current,
+                maximum,
+            } => {
+                match combatant {
+                    Some(id) => write!(f, "{} has ", id)?,
+                    None => write!(f, "the pool has ")?,
+                }
+                write!(f, "{} of {} {}", current, maximum, resource)
+            }
+            EncounterIssue::OrphanedTokens { id } => {
+                write!(f, "{} has action tokens but isn't in the encounter", id)
+            }
+        }
+    }
+}
+
+impl CombatEncounter {
+    /// List everything inconsistent about the encounter
+    ///
+    /// Unlike [`check_invariants`](CombatEncounter::check_invariants),
+    /// which stops at the first rule broken during play, this looks for
+    /// damage a save can carry and reports all of it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::lint::EncounterIssue;
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder, CombatantId};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Goblin")
+    ///         .with_hp(4)
+    ///         .with_evasion(10)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// encounter.start();
+    /// assert!(encounter.validate().is_empty());
+    ///
+    /// encounter.turn_order.push(CombatantId(7));
+    /// encounter.current_turn = 5;
+    /// assert_eq!(
+    ///     encounter.validate(),
+    ///     vec![
+    ///         EncounterIssue::UnknownInTurnOrder { id: CombatantId(7) },
+    ///         EncounterIssue::TurnOutOfRange { turn: 5, turns: 2 },
+    ///     ]
+    /// );
+    ///
+    /// let fixed = encounter.repair();
+    /// assert_eq!(fixed.len(), 2);
+    /// assert!(encounter.validate().is_empty());
+    /// assert_eq!(encounter.current_combatant().unwrap().name, "Goblin");
+    /// ```
+    pub fn validate(&self) -> Vec<EncounterIssue> {
+        let mut issues = Vec::new();
+
+        let mut ids = HashSet::new();
+        for combatant in &self.combatants {
+            if !ids.insert(combatant.id) {
+                issues.push(EncounterIssue::DuplicateId { id: combatant.id });
+            }
+        }
+        if self.combatants.iter().any(|c| c.id.0 >= self.next_id) {
+            issues.push(EncounterIssue::StaleNextId { next: self.next_id });
+        }
+
+        let mut seen = HashSet::new();
+        for &id in &self.turn_order {
+            if !ids.contains(&id) {
+                issues.push(EncounterIssue::UnknownInTurnOrder { id });
+            } else if !seen.insert(id) {
+                issues.push(EncounterIssue::RepeatedInTurnOrder { id });
+            }
+        }
+        if !self.turn_order.is_empty() && self.current_turn >= self.turn_order.len() {
+            issues.push(EncounterIssue::TurnOutOfRange {
+                turn: self.current_turn,
+                turns: self.turn_order.len(),
+            });
+        }
+
+        for combatant in &self.combatants {
+            let mut over = |resource, current: u8, maximum: u8| {
+                if current > maximum {
+                    issues.push(EncounterIssue::OverMaximum {
+                        combatant: Some(combatant.id),
+                        resource,
+                        current,
+                        maximum,
+                    });
+                }
+            };
+            over(
+                Resource::HitPoints,
+                combatant.hp.current,
+                combatant.hp.maximum,
+            );
+            if let Some(hope) = combatant.hope() {
+                over(Resource::Hope, hope.current, hope.maximum);
+            }
+        }
+        for (resource, current, maximum) in [
+            (Resource::Hope, self.hope.current, self.hope.maximum),
+            (Resource::Fear, self.fear.current, self.fear.maximum),
+        ] {
+            if current > maximum {
+                issues.push(EncounterIssue::OverMaximum {
+                    combatant: None,
+                    resource,
+                    current,
+                    maximum,
+                });
+            }
+        }
+
+        for pool in &self.action_tokens.pools {
+            if !ids.contains(&pool.combatant) {
+                issues.push(EncounterIssue::OrphanedTokens { id: pool.combatant });
+            }
+        }
+        issues
+    }
+
+    /// Fix everything [`validate`](CombatEncounter::validate) finds,
+    /// returning what was fixed
+    ///
+    /// Combatants sharing an id after the first get fresh ones, the turn
+    /// order drops names it shouldn't have and keeps whoever was acting
+    /// if it can, pools over their maximums are brought down to them, and
+    /// stray token pools are dropped. Nothing is logged.
+    pub fn repair(&mut self) -> Vec<EncounterIssue> {
+        let issues = self.validate();
+        if issues.is_empty() {
+            return issues;
+        }
+
+        let highest = self.combatants.iter().map(|c| c.id.0 + 1).max();
+        self.next_id = self.next_id.max(highest.unwrap_or(0));
+        let mut ids = HashSet::new();
+        for combatant in &mut self.combatants {
+            if !ids.insert(combatant.id) {
+                combatant.id = CombatantId(self.next_id);
+                self.next_id += 1;
+                ids.insert(combatant.id);
+            }
+        }
+
+        let acting = self.current_combatant_id();
+        let mut seen = HashSet::new();
+        self.turn_order
+            .retain(|id| ids.contains(id) && seen.insert(*id));
+        self.current_turn = acting
+            .and_then(|id| self.turn_order.iter().position(|&t| t == id))
+            .unwrap_or(0);
+
+        for combatant in &mut self.combatants {
+            combatant.hp.current = combatant.hp.current.min(combatant.hp.maximum);
+            if let Ok(hope) = combatant.hope_mut() {
+                hope.current = hope.current.min(hope.maximum);
+            }
+        }
+        self.hope.current = self.hope.current.min(self.hope.maximum);
+        self.fear.current = self.fear.current.min(self.fear.maximum);
+
+        self.action_tokens
+            .pools
+            .retain(|pool| ids.contains(&pool.combatant));
+        issues
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::simulation::TurnModel;
+    use crate::test_support::hero;
+
+    #[test]
+    fn test_repair_keeps_the_acting_combatant() {
+        let mut encounter = CombatEncounter::new(5);
+        let grom = encounter.add_combatant(hero("Grom"));
+        let lia = encounter.add_combatant(hero("Lia"));
+        encounter.turn_order = vec![CombatantId(9), grom, grom, lia];
+        encounter.current_turn = 3;
+
+        assert_eq!(
+            encounter.repair(),
+            vec![
+                EncounterIssue::UnknownInTurnOrder { id: CombatantId(9) },
+                EncounterIssue::RepeatedInTurnOrder { id: grom },
+            ]
+        );
+        assert_eq!(encounter.turn_order, vec![grom, lia]);
+        assert_eq!(encounter.current_combatant_id(), Some(lia));
+    }
+
+    #[test]
+    fn test_repair_renumbers_shared_ids() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(hero("Grom"));
+        let lia = encounter.add_combatant(hero("Lia"));
+        encounter.combatants[0].id = lia;
+        encounter.combatants[1].hp.current = 40;
+        encounter.fear.current = 20;
+
+        let issues = encounter.validate();
+        let maximum = encounter.combatants[1].hp.maximum;
+        assert_eq!(issues[0], EncounterIssue::DuplicateId { id: lia });
+        assert_eq!(
+            issues[1].to_string(),
+            format!("{} has 40 of {} Hit Points", lia, maximum)
+        );
+        assert_eq!(issues[2].to_string(), "the pool has 20 of 12 Fear");
+
+        encounter.repair();
+        assert!(encounter.validate().is_empty());
+        assert_ne!(encounter.combatants[0].id, encounter.combatants[1].id);
+        assert_eq!(encounter.fear.current, 12);
+
+        // New arrivals don't collide with the renumbered
+        let kai = encounter.add_combatant(hero("Kai"));
+        assert!(encounter.combatants[..2].iter().all(|c| c.id != kai));
+    }
+
+    #[test]
+    fn test_repair_drops_stray_token_pools() {
+        let mut encounter = CombatEncounter::new(5);
+        let grom = encounter.add_combatant(hero("Grom"));
+        encounter.turn_model = TurnModel::ActionTokens;
+        encounter.start();
+        encounter.combatants.clear();
+
+        assert_eq!(
+            encounter.validate(),
+            vec![
+                EncounterIssue::UnknownInTurnOrder { id: grom },
+                EncounterIssue::OrphanedTokens { id: grom },
+            ]
+        );
+        encounter.repair();
+        assert!(encounter.action_tokens.pools.is_empty());
+        assert!(encounter.turn_order.is_empty());
+        assert_eq!(encounter.current_combatant_id(), None);
+    }
+}
@@ -5,17 +5,156 @@
 //! - Damage calculation and application
 //! - Combat resources (HP, Stress, Hope, Fear)
 //! - Action economy
-//! - Combat simulation
+//! - Combat simulation and event log
+//! - Adversaries and encounter building
+//! - Monte Carlo balance simulation
+//! - Summons and reinforcements
+//! - Countdowns
+//! - Spellcast rolls
+//! - Triggered features and condition immunity
+//! - Reactions that interrupt attacks
+//! - Modifier stacking
+//! - Hidden and Vulnerable in attack rolls
+//! - Ongoing damage
+//! - Zones, range bands and forced movement
+//! - Mounts and vehicles
+//! - Snapshots and diffs of encounter state
+//! - Invariant checks and random-play fuzzing
+//! - Linting and repairing saved encounters
+//! - Copy-on-write definition data shared between encounter clones
+//! - Action tokens, the optional action tracker variant
+//! - GM move suggestions
+//! - The spend-Hope menu
+//! - Trading one resource pool for another
+//! - Innate ancestry abilities as actions
+//! - Flight and falling
+//! - Contests settled by opposed rolls
+//! - Environment hazards that strike as turns advance
 
+#[cfg(feature = "content")]
+pub mod adversary;
+#[cfg(feature = "content")]
+pub mod adversary_builder;
+#[cfg(feature = "std")]
+pub mod advisor;
 pub mod attack;
+pub mod conditions;
+#[cfg(feature = "std")]
+pub mod contest;
+#[cfg(feature = "std")]
+pub mod conversion;
+pub mod countdown;
 pub mod damage;
+#[cfg(feature = "std")]
+pub mod diff;
+#[cfg(feature = "content")]
+pub mod encounter_builder;
+#[cfg(feature = "content")]
+pub mod estimate;
+#[cfg(feature = "std")]
+pub mod events;
+#[cfg(feature = "std")]
+pub mod flight;
+#[cfg(feature = "std")]
+pub mod fuzz;
+#[cfg(feature = "std")]
+pub mod hazards;
+#[cfg(feature = "content")]
+pub mod hope;
+#[cfg(feature = "std")]
+pub mod innate;
+#[cfg(feature = "std")]
+pub mod lint;
+pub mod modifiers;
+#[cfg(feature = "std")]
+pub mod montecarlo;
+#[cfg(feature = "std")]
+pub mod mounts;
+#[cfg(feature = "std")]
+pub mod movement;
+#[cfg(feature = "std")]
+pub mod ongoing;
+#[cfg(feature = "std")]
+pub mod reactions;
+#[cfg(feature = "std")]
+pub mod replay;
 pub mod resources;
+#[cfg(feature = "std")]
+pub mod shared;
+#[cfg(feature = "std")]
 pub mod simulation;
+#[cfg(feature = "std")]
+pub mod spellcast;
+#[cfg(feature = "std")]
+pub mod stealth;
+#[cfg(feature = "std")]
+pub mod summons;
+#[cfg(feature = "std")]
+pub mod tokens;
+pub mod triggers;
 
+#[cfg(feature = "content")]
+pub use adversary::{Adversary, AdversaryFeature, AdversaryRole};
+#[cfg(feature = "content")]
+pub use adversary_builder::{AdversaryBuilder, Deviation, Stat};
+#[cfg(feature = "std")]
+pub use advisor::{AdversaryOption, FearOption, GmAdvice, GmMove};
+#[cfg(feature = "std")]
+pub use attack::AttackOutcome;
 pub use attack::{Attack, AttackResult};
-pub use damage::DamageResult;
-pub use resources::{Fear, HitPoints, Hope, Stress};
-pub use simulation::{CombatEncounter, Combatant};
+pub use conditions::Condition;
+pub use countdown::Countdown;
+pub use damage::{DamageOverTime, DamageResult, DamageType, Mitigation, TickTiming};
+#[cfg(feature = "std")]
+pub use diff::{Change, EncounterSnapshot, StateDiff};
+#[cfg(feature = "content")]
+pub use encounter_builder::{EncounterBuilder, EncounterDifficulty, EncounterTemplate};
+#[cfg(feature = "content")]
+pub use estimate::{DifficultyEstimate, EnemyCost, Threat};
+#[cfg(feature = "std")]
+pub use events::CombatEvent;
+#[cfg(feature = "std")]
+pub use hazards::{Hazard, HazardTrigger};
+#[cfg(feature = "content")]
+pub use hope::{HopeAction, HopeOption, Unavailable};
+#[cfg(feature = "std")]
+pub use innate::Action;
+#[cfg(feature = "std")]
+pub use lint::EncounterIssue;
+pub use modifiers::{Contribution, ModifierStack, RollKind, StackingRule};
+#[cfg(feature = "std")]
+pub use montecarlo::{simulate_batch, AttackProfile, MonteCarlo, MonteCarloReport, TargetPolicy};
+#[cfg(feature = "std")]
+pub use mounts::{Mount, MountKind};
+#[cfg(feature = "std")]
+pub use movement::RangeBand;
+#[cfg(feature = "std")]
+pub use reactions::{
+    PendingAttack, Reaction, ReactionCost, ReactionEffect, ReactionOffer, ReactionScope,
+    ReactionWindow,
+};
+#[cfg(feature = "std")]
+pub use replay::{EncounterCommand, Recorder, Replay};
+pub use resources::{
+    ConversionRate, Fear, HitPoints, Hope, Pool, Stress, CHARACTER_HOPE_MAXIMUM,
+    DEFAULT_FEAR_MAXIMUM, STARTING_HOPE,
+};
+#[cfg(feature = "std")]
+pub use shared::Shared;
+#[cfg(feature = "std")]
+pub use simulation::{
+    AdversaryData, CombatEncounter, Combatant, CombatantBuilder, CombatantId, CombatantKind,
+    HopeMode, PlayerData, TurnModel,
+};
+#[cfg(feature = "std")]
+pub use spellcast::SpellcastRoll;
+#[cfg(feature = "std")]
+pub use stealth::SearchRoll;
+#[cfg(feature = "std")]
+pub use summons::SummonSource;
+#[cfg(feature = "std")]
+pub use tokens::{ActionTracker, TokenPool};
+pub use triggers::{Trigger, TriggerEffect, TriggeredFeature};
 
 // TODO: Add submodules
 // pub mod actions;
@@ -0,0 +1,256 @@
+//! Modifier stacks - adding up bonuses from every source
+//!
+//! Cards, conditions, and equipment can all grant a combatant a bonus or
+//! penalty. A `ModifierStack` holds them with where they came from, and
+//! totals what applies to a roll under its stacking rule, so every roll
+//! a combatant makes agrees on the number.
+
+use crate::cards::effects::{ActiveEffect, SourceKind};
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// A kind of roll modifiers can apply to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum RollKind {
+    /// Weapon and other attack rolls
+    #[strum(to_string = "attack rolls")]
+    Attack,
+    /// Spellcast rolls, including spell attacks
+    #[strum(to_string = "Spellcast rolls")]
+    Spellcast,
+    /// Action rolls made with a trait
+    #[strum(to_string = "action rolls")]
+    Action,
+}
+
+impl RollKind {
+    /// Whether a modifier for `applies_to` counts toward this kind of roll
+    ///
+    /// Singular and plural both match, and `rolls` or `all rolls` match
+    /// every kind.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::RollKind;
+    ///
+    /// assert!(RollKind::Attack.matches("Attack Rolls"));
+    /// assert!(RollKind::Attack.matches("attacks"));
+    /// assert!(RollKind::Spellcast.matches("all rolls"));
+    /// assert!(!RollKind::Action.matches("Evasion"));
+    /// ```
+    pub fn matches(self, applies_to: &str) -> bool {
+        let what = applies_to.trim().to_ascii_lowercase();
+        let what = what.strip_suffix('s').unwrap_or(&what);
+        let what = what.strip_suffix(" roll").unwrap_or(what);
+        let stem = match self {
+            RollKind::Attack => "attack",
+            RollKind::Spellcast => "spellcast",
+            RollKind::Action => "action",
+        };
+        matches!(what, "roll" | "all") || what == stem
+    }
+}
+
+/// How modifiers from the same source combine
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum StackingRule {
+    /// Same-named sources don't stack: only the largest bonus and the
+    /// largest penalty from each source count
+    #[default]
+    BySource,
+    /// Every modifier adds up
+    All,
+}
+
+/// One source's share of a total
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Contribution {
+    pub source: String,
+    pub kind: SourceKind,
+    pub bonus: i8,
+}
+
+/// The modifiers a combatant is under
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::cards::effects::{ActiveEffect, SourceKind};
+/// use daggerheart_engine::combat::{ModifierStack, RollKind};
+///
+/// let mut stack = ModifierStack::default();
+/// stack.add(ActiveEffect::permanent("Bless", SourceKind::Effect, 1, "attack rolls"));
+/// stack.add(ActiveEffect::permanent("Bless", SourceKind::Effect, 1, "attack rolls"));
+/// stack.add(ActiveEffect::permanent("Keen Blade", SourceKind::Equipment, 1, "attacks"));
+///
+/// // Blessed twice still only counts once
+/// assert_eq!(stack.total_bonus_for(RollKind::Attack), 2);
+/// assert_eq!(stack.total_bonus_for(RollKind::Spellcast), 0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ModifierStack {
+    #[serde(default)]
+    pub effects: Vec<ActiveEffect>,
+    #[serde(default)]
+    pub stacking: StackingRule,
+}
+
+impl ModifierStack {
+    /// Create an empty stack with a stacking rule
+    pub fn with_stacking(stacking: StackingRule) -> Self {
+        Self {
+            effects: Vec::new(),
+            stacking,
+        }
+    }
+
+    /// Add a modifier
+    pub fn add(&mut self, effect: ActiveEffect) {
+        self.effects.push(effect);
+    }
+
+    /// Remove every modifier from `source`, returning how many there were
+    pub fn remove_source(&mut self, source: &str) -> usize {
+        let before = self.effects.len();
+        self.effects
+            .retain(|effect| !effect.source.eq_ignore_ascii_case(source));
+        before - self.effects.len()
+    }
+
+    /// The total bonus to a kind of roll
+    pub fn total_bonus_for(&self, roll: RollKind) -> i8 {
+        total(&self.contributions(|effect| roll.matches(&effect.applies_to)))
+    }
+
+    /// The total bonus to `what`, e.g. `Evasion`, ignoring case
+    pub fn bonus_for(&self, what: &str) -> i8 {
+        total(&self.contributions(|effect| effect.modifies(what)))
+    }
+
+    /// What each source adds to a kind of roll, after stacking
+    pub fn breakdown(&self, roll: RollKind) -> Vec<Contribution> {
+        self.contributions(|effect| roll.matches(&effect.applies_to))
+    }
+
+    fn contributions(&self, applies: impl Fn(&ActiveEffect) -> bool) -> Vec<Contribution> {
+        let mut shares: Vec<Contribution> = Vec::new();
+        for effect in self.effects.iter().filter(|effect| applies(effect)) {
+            let share = match self.stacking {
+                StackingRule::All => None,
+                StackingRule::BySource => shares.iter_mut().find(|share| {
+                    share.source.eq_ignore_ascii_case(&effect.source)
+                        && (share.bonus >= 0) == (effect.bonus >= 0)
+                }),
+            };
+            match share {
+                Some(share) if effect.bonus.unsigned_abs() > share.bonus.unsigned_abs() => {
+                    share.bonus = effect.bonus;
+                    share.kind = effect.kind;
+                }
+                Some(_) => {}
+                None => shares.push(Contribution {
+                    source: effect.source.clone(),
+                    kind: effect.kind,
+                    bonus: effect.bonus,
+                }),
+            }
+        }
+        shares
+    }
+
+    /// Age every modifier as its owner's turn ends, dropping those that wore off
+    pub fn end_turn(&mut self) {
+        self.effects.retain_mut(ActiveEffect::end_turn);
+    }
+
+    /// Age every modifier as a new round begins, dropping those that wore off
+    pub fn new_round(&mut self) {
+        self.effects.retain_mut(ActiveEffect::new_round);
+    }
+}
+
+fn total(shares: &[Contribution]) -> i8 {
+    shares
+        .iter()
+        .fold(0i8, |total, share| total.saturating_add(share.bonus))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::effects::Duration;
+
+    fn effect(source: &str, bonus: i8, applies_to: &str) -> ActiveEffect {
+        ActiveEffect::permanent(source, SourceKind::Effect, bonus, applies_to)
+    }
+
+    #[test]
+    fn test_same_source_keeps_best_bonus_and_worst_penalty() {
+        let mut stack = ModifierStack::default();
+        stack.add(effect("Hex", -1, "attack rolls"));
+        stack.add(effect("hex", -2, "attack rolls"));
+        stack.add(effect("Rally", 1, "rolls"));
+        stack.add(effect("Rally", 3, "rolls"));
+        assert_eq!(stack.total_bonus_for(RollKind::Attack), 1);
+        assert_eq!(stack.total_bonus_for(RollKind::Action), 3);
+        assert_eq!(stack.breakdown(RollKind::Attack).len(), 2);
+    }
+
+    #[test]
+    fn test_stack_all_adds_everything() {
+        let mut stack = ModifierStack::with_stacking(StackingRule::All);
+        stack.add(effect("Rally", 1, "attack rolls"));
+        stack.add(effect("Rally", 2, "attack rolls"));
+        assert_eq!(stack.total_bonus_for(RollKind::Attack), 3);
+    }
+
+    #[test]
+    fn test_sources_are_tracked_and_removed() {
+        let mut stack = ModifierStack::default();
+        stack.add(ActiveEffect::permanent(
+            "Shield",
+            SourceKind::Equipment,
+            2,
+            "Evasion",
+        ));
+        stack.add(effect("Shield", 1, "Evasion"));
+        assert_eq!(stack.bonus_for("evasion"), 2);
+        assert_eq!(
+            stack.breakdown(RollKind::Attack),
+            Vec::<Contribution>::new()
+        );
+        assert_eq!(stack.remove_source("shield"), 2);
+        assert_eq!(stack.bonus_for("Evasion"), 0);
+    }
+
+    #[test]
+    fn test_modifiers_wear_off() {
+        let mut stack = ModifierStack::default();
+        stack.add(ActiveEffect {
+            duration: Duration::EndOfTurn,
+            ..effect("Focus", 1, "Spellcast rolls")
+        });
+        stack.add(ActiveEffect {
+            duration: Duration::Rounds(1),
+            ..effect("Ward", 1, "Evasion")
+        });
+        stack.end_turn();
+        assert_eq!(stack.effects.len(), 1);
+        stack.new_round();
+        assert!(stack.effects.is_empty());
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_stack_reads_missing_fields() {
+        let stack: ModifierStack = serde_json::from_str("{}").unwrap();
+        assert_eq!(stack, ModifierStack::default());
+    }
+}
@@ -0,0 +1,688 @@
+//! Monte Carlo combat simulation
+//!
+//! Runs an encounter to completion many times with simple automated
+//! policies and aggregates the outcomes. Useful for balancing homebrew
+//! adversaries: tweak a stat block, re-run, and compare win rates.
+//!
+//! Each run `i` is seeded with `seed + i`, so results are identical whether
+//! the runs execute sequentially or in parallel (with the `parallel`
+//! feature).
+//!
+//! For large balance sweeps, [`simulate_batch`] splits the runs into
+//! chunks of [`BATCH_CHUNK`], each drawing its run seeds from its own RNG
+//! stream derived from the batch seed. Chunks spread across cores with the
+//! `parallel` feature and give the same report however many threads run
+//! them, and batches from neighbouring seeds don't share runs.
+
+use crate::character::AttributeType;
+#[cfg(feature = "content")]
+use crate::combat::adversary::Adversary;
+use crate::combat::attack::Attack;
+use crate::combat::conditions::Condition;
+use crate::combat::events::CombatEvent;
+use crate::combat::modifiers::RollKind;
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId};
+use crate::core::dice::DamageDice;
+use crate::error::EngineError;
+use crate::items::Weapon;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// Runs that draw their seeds from one RNG stream in a batch
+pub const BATCH_CHUNK: u32 = 64;
+
+/// Spreads chunk streams apart, so chunk `k` of one seed isn't chunk
+/// `k - 1` of the next
+const STREAM_SPACING: u64 = 0x9E37_79B9_7F4A_7C15;
+
+/// How an automated combatant picks who to attack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum TargetPolicy {
+    /// Pick any living opponent at random
+    #[default]
+    Random,
+    /// Focus the living opponent with the fewest HP remaining
+    Weakest,
+    /// Focus the living opponent with the most HP remaining
+    Strongest,
+}
+
+impl TargetPolicy {
+    /// Pick a target from the opponents in sight
+    pub fn choose_with_rng<'a, R: Rng>(
+        self,
+        opponents: &[&'a Combatant],
+        rng: &mut R,
+    ) -> Option<&'a Combatant> {
+        match self {
+            TargetPolicy::Random => opponents.choose(rng).copied(),
+            TargetPolicy::Weakest => opponents.iter().copied().min_by_key(|c| c.hp.current),
+            TargetPolicy::Strongest => opponents.iter().copied().max_by_key(|c| c.hp.current),
+        }
+    }
+}
+
+/// Attack bonus and damage used by an automated combatant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AttackProfile {
+    pub modifier: i8,
+    pub damage: DamageDice,
+}
+
+impl AttackProfile {
+    /// Create an attack profile
+    pub fn new(modifier: i8, damage: DamageDice) -> Self {
+        Self { modifier, damage }
+    }
+
+    /// Default profile for a combatant
+    ///
+    /// Players attack with the first weapon they carry, or their best
+    /// trait and a d8 without one; enemies attack at +level for d8+level,
+    /// matching `Adversary::new` defaults.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::montecarlo::AttackProfile;
+    /// use daggerheart_engine::combat::simulation::CombatantBuilder;
+    ///
+    /// let goblin = CombatantBuilder::adversary("Goblin")
+    ///     .with_level(2)
+    ///     .with_hp(4)
+    ///     .with_evasion(10)
+    ///     .build()
+    ///     .unwrap();
+    /// let profile = AttackProfile::for_combatant(&goblin);
+    /// assert_eq!(profile.modifier, 2);
+    /// ```
+    pub fn for_combatant(combatant: &Combatant) -> Self {
+        let weapon = combatant
+            .equipment
+            .iter()
+            .find_map(|item| Weapon::named(item));
+        if let Some(weapon) = weapon {
+            if let Ok(attack) = Attack::from_character(combatant, &weapon) {
+                return Self::new(attack.modifier, weapon.damage(combatant.proficiency()));
+            }
+        }
+
+        if let Some(attributes) = combatant.attributes() {
+            let best = [
+                AttributeType::Agility,
+                AttributeType::Strength,
+                AttributeType::Finesse,
+                AttributeType::Instinct,
+                AttributeType::Presence,
+                AttributeType::Knowledge,
+            ]
+            .iter()
+            .map(|&attr| attributes.get_modifier(attr))
+            .max()
+            .unwrap_or(0)
+            .saturating_add(combatant.total_bonus_for(RollKind::Attack));
+            Self::new(best, DamageDice::d8(1))
+        } else {
+            let level = combatant.level.min(i8::MAX as u8) as i8;
+            Self::new(level, DamageDice::d8(1).with_bonus(level as i16))
+        }
+    }
+}
+
+#[cfg(feature = "content")]
+impl Adversary {
+    /// Attack profile from this stat block, for Monte Carlo simulation
+    pub fn attack_profile(&self) -> AttackProfile {
+        AttackProfile::new(self.attack_modifier, self.damage.clone())
+    }
+}
+
+/// Outcome of a single automated playthrough
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RunOutcome {
+    /// `Some(true)` if players won, `Some(false)` if enemies won,
+    /// `None` if the round limit was hit first
+    pub player_victory: Option<bool>,
+    pub rounds: u32,
+    pub player_hp_lost: u32,
+    pub player_stress_gained: u32,
+    pub enemy_hp_lost: u32,
+    pub hope_gained: u32,
+    pub fear_gained: u32,
+}
+
+/// Aggregated statistics over many playthroughs
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MonteCarloReport {
+    pub runs: u32,
+    pub player_wins: u32,
+    pub enemy_wins: u32,
+    /// Runs that hit the round limit without a winner
+    pub stalemates: u32,
+    pub average_rounds: f64,
+    pub average_player_hp_lost: f64,
+    pub average_player_stress_gained: f64,
+    pub average_enemy_hp_lost: f64,
+    pub average_hope_gained: f64,
+    pub average_fear_gained: f64,
+}
+
+impl MonteCarloReport {
+    /// Aggregate individual run outcomes
+    pub fn from_outcomes(outcomes: &[RunOutcome]) -> Self {
+        let runs = outcomes.len() as u32;
+        let average = |f: fn(&RunOutcome) -> u32| {
+            if outcomes.is_empty() {
+                0.0
+            } else {
+                outcomes.iter().map(f).map(f64::from).sum::<f64>() / outcomes.len() as f64
+            }
+        };
+
+        Self {
+            runs,
+            player_wins: outcomes
+                .iter()
+                .filter(|o| o.player_victory == Some(true))
+                .count() as u32,
+            enemy_wins: outcomes
+                .iter()
+                .filter(|o| o.player_victory == Some(false))
+                .count() as u32,
+            stalemates: outcomes
+                .iter()
+                .filter(|o| o.player_victory.is_none())
+                .count() as u32,
+            average_rounds: average(|o| o.rounds),
+            average_player_hp_lost: average(|o| o.player_hp_lost),
+            average_player_stress_gained: average(|o| o.player_stress_gained),
+            average_enemy_hp_lost: average(|o| o.enemy_hp_lost),
+            average_hope_gained: average(|o| o.hope_gained),
+            average_fear_gained: average(|o| o.fear_gained),
+        }
+    }
+
+    /// Fraction of runs won by the players (0.0 - 1.0)
+    pub fn win_rate(&self) -> f64 {
+        if self.runs == 0 {
+            0.0
+        } else {
+            self.player_wins as f64 / self.runs as f64
+        }
+    }
+}
+
+/// Runs an encounter many times with automated combatants
+#[derive(Debug, Clone)]
+pub struct MonteCarlo {
+    encounter: CombatEncounter,
+    profiles: Vec<AttackProfile>,
+    pub player_policy: TargetPolicy,
+    pub enemy_policy: TargetPolicy,
+    pub max_rounds: u32,
+    pub parallel: bool,
+}
+
+impl MonteCarlo {
+    /// Create a simulator for an encounter that has not started yet
+    ///
+    /// Every combatant gets its default `AttackProfile`; override with
+    /// `with_profile`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::montecarlo::MonteCarlo;
+    /// use daggerheart_engine::combat::simulation::{CombatEncounter, CombatantBuilder};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// encounter.add_combatant(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Goblin")
+    ///         .with_hp(4)
+    ///         .with_evasion(10)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// let report = MonteCarlo::new(encounter).run(100, 42);
+    /// assert_eq!(report.runs, 100);
+    /// assert!(report.win_rate() >= 0.0 && report.win_rate() <= 1.0);
+    /// ```
+    pub fn new(encounter: CombatEncounter) -> Self {
+        let profiles = encounter
+            .combatants
+            .iter()
+            .map(AttackProfile::for_combatant)
+            .collect();
+
+        Self {
+            encounter,
+            profiles,
+            player_policy: TargetPolicy::Random,
+            enemy_policy: TargetPolicy::Random,
+            max_rounds: 20,
+            parallel: false,
+        }
+    }
+
+    /// Override the attack profile for a combatant
+    pub fn with_profile(
+        mut self,
+        id: CombatantId,
+        profile: AttackProfile,
+    ) -> Result<Self, EngineError> {
+        match self.encounter.index_of(id) {
+            Some(index) => {
+                self.profiles[index] = profile;
+                Ok(self)
+            }
+            None => Err(EngineError::UnknownCombatant { id: id.0 }),
+        }
+    }
+
+    /// Set targeting policies for players and enemies
+    pub fn with_policies(mut self, players: TargetPolicy, enemies: TargetPolicy) -> Self {
+        self.player_policy = players;
+        self.enemy_policy = enemies;
+        self
+    }
+
+    /// Set the round limit after which a run counts as a stalemate
+    pub fn with_max_rounds(mut self, max_rounds: u32) -> Self {
+        self.max_rounds = max_rounds;
+        self
+    }
+
+    /// Run playthroughs in parallel (requires the `parallel` feature;
+    /// otherwise runs sequentially with identical results)
+    pub fn with_parallel(mut self, parallel: bool) -> Self {
+        self.parallel = parallel;
+        self
+    }
+
+    /// Run `runs` playthroughs starting from `seed` and aggregate them
+    pub fn run(&self, runs: u32, seed: u64) -> MonteCarloReport {
+        MonteCarloReport::from_outcomes(&self.outcomes(runs, seed))
+    }
+
+    /// Run `runs` playthroughs and return each individual outcome
+    pub fn outcomes(&self, runs: u32, seed: u64) -> Vec<RunOutcome> {
+        #[cfg(feature = "parallel")]
+        if self.parallel {
+            use rayon::prelude::*;
+            return (0..runs)
+                .into_par_iter()
+                .map(|i| self.run_once(seed.wrapping_add(i as u64)))
+                .collect();
+        }
+
+        (0..runs)
+            .map(|i| self.run_once(seed.wrapping_add(i as u64)))
+            .collect()
+    }
+
+    /// Run `runs` playthroughs in chunks with their own RNG streams and
+    /// aggregate them
+    ///
+    /// Chunks run in parallel with the `parallel` feature, whatever
+    /// `with_parallel` says.
+    pub fn run_batch(&self, runs: u32, seed: u64) -> MonteCarloReport {
+        MonteCarloReport::from_outcomes(&self.batch_outcomes(runs, seed))
+    }
+
+    /// Run `runs` playthroughs in chunks and return each individual
+    /// outcome, in run order
+    pub fn batch_outcomes(&self, runs: u32, seed: u64) -> Vec<RunOutcome> {
+        let chunks = runs.div_ceil(BATCH_CHUNK);
+        let chunk = |k: u32| {
+            let start = k * BATCH_CHUNK;
+            let size = BATCH_CHUNK.min(runs - start);
+            let stream = seed.wrapping_add((k as u64).wrapping_mul(STREAM_SPACING));
+            let mut rng = StdRng::seed_from_u64(stream);
+            (0..size)
+                .map(|_| self.run_once(rng.gen()))
+                .collect::<Vec<_>>()
+        };
+
+        #[cfg(feature = "parallel")]
+        {
+            use rayon::prelude::*;
+            (0..chunks).into_par_iter().flat_map_iter(chunk).collect()
+        }
+        #[cfg(not(feature = "parallel"))]
+        {
+            (0..chunks).flat_map(chunk).collect()
+        }
+    }
+
+    /// Run a single playthrough with the given seed
+    pub fn run_once(&self, seed: u64) -> RunOutcome {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut encounter = self.encounter.clone();
+        let first_event = encounter.events.len();
+
+        encounter.start_with_rng(&mut rng);
+
+        while !encounter.is_over() && encounter.round <= self.max_rounds {
+            if let Some(attacker) = encounter.current_combatant() {
+                if attacker.is_alive() {
+                    let attacker = attacker.id;
+                    self.take_turn(&mut encounter, attacker, &mut rng);
+                }
+            }
+            encounter.next_turn_with_rng(&mut rng);
+        }
+
+        self.summarize(&encounter, first_event)
+    }
+
+    fn take_turn<R: Rng>(
+        &self,
+        encounter: &mut CombatEncounter,
+        attacker: CombatantId,
+        rng: &mut R,
+    ) {
+        // Combatants are never removed mid-run, so indices match `profiles`
+        let Some(attacker_index) = encounter.index_of(attacker) else {
+            return;
+        };
+        let is_player = encounter.combatants[attacker_index].is_player();
+        let policy = if is_player {
+            self.player_policy
+        } else {
+            self.enemy_policy
+        };
+
+        let (hidden, opponents): (Vec<&Combatant>, Vec<&Combatant>) = encounter
+            .combatants
+            .iter()
+            .filter(|c| c.is_player() != is_player && c.is_alive())
+            .partition(|c| c.has_condition(Condition::Hidden));
+
+        // With nobody in sight, spend the turn looking
+        if opponents.is_empty() {
+            if let Some(target) = hidden.first().map(|c| c.id) {
+                let _ = encounter.search_with_rng(attacker, target, rng);
+            }
+            return;
+        }
+
+        let target = policy.choose_with_rng(&opponents, rng).map(|c| c.id);
+
+        if let Some(target) = target {
+            let profile = &self.profiles[attacker_index];
+            // Ids come from the encounter itself, so this cannot fail
+            let _ = encounter.resolve_attack_with_rng(
+                attacker,
+                target,
+                &Attack::new(profile.modifier),
+                &profile.damage,
+                rng,
+            );
+        }
+    }
+
+    fn summarize(&self, encounter: &CombatEncounter, first_event: usize) -> RunOutcome {
+        let mut outcome = RunOutcome {
+            player_victory: encounter.player_victory(),
+            rounds: encounter.round.min(self.max_rounds),
+            player_hp_lost: 0,
+            player_stress_gained: 0,
+            enemy_hp_lost: 0,
+            hope_gained: 0,
+            fear_gained: 0,
+        };
+
+        for (before, after) in self.encounter.combatants.iter().zip(&encounter.combatants) {
+            let hp_lost = before.hp.current.saturating_sub(after.hp.current) as u32;
+            if after.is_player() {
+                outcome.player_hp_lost += hp_lost;
+                outcome.player_stress_gained +=
+                    after.stress.current.saturating_sub(before.stress.current) as u32;
+            } else {
+                outcome.enemy_hp_lost += hp_lost;
+            }
+        }
+
+        for event in &encounter.events[first_event..] {
+            match event {
+                CombatEvent::HopeGained { amount, .. } => outcome.hope_gained += *amount as u32,
+                CombatEvent::FearGained { amount } => outcome.fear_gained += *amount as u32,
+                _ => {}
+            }
+        }
+
+        outcome
+    }
+}
+
+/// Run an encounter `runs` times with the given targeting policies,
+/// across cores with the `parallel` feature
+///
+/// The report depends only on the encounter, policies, run count and
+/// seed, not on the number of threads. See [`MonteCarlo::run_batch`].
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+/// use daggerheart_engine::combat::montecarlo::{simulate_batch, TargetPolicy};
+/// use daggerheart_engine::combat::simulation::{CombatEncounter, CombatantBuilder};
+///
+/// let mut encounter = CombatEncounter::new(5);
+/// encounter.add_combatant(
+///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+///         .build()
+///         .unwrap(),
+/// );
+/// encounter.add_combatant(
+///     CombatantBuilder::adversary("Goblin")
+///         .with_hp(4)
+///         .with_evasion(10)
+///         .build()
+///         .unwrap(),
+/// );
+///
+/// let policies = (TargetPolicy::Weakest, TargetPolicy::Random);
+/// let report = simulate_batch(&encounter, policies, 200, 42);
+/// assert_eq!(report.runs, 200);
+/// assert_eq!(report, simulate_batch(&encounter, policies, 200, 42));
+/// ```
+pub fn simulate_batch(
+    encounter: &CombatEncounter,
+    (players, enemies): (TargetPolicy, TargetPolicy),
+    runs: u32,
+    seed: u64,
+) -> MonteCarloReport {
+    MonteCarlo::new(encounter.clone())
+        .with_policies(players, enemies)
+        .run_batch(runs, seed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Attributes, Class};
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::test_support::{create_test_encounter, hero, player};
+
+    #[test]
+    fn test_default_profiles() {
+        let sim = MonteCarlo::new(create_test_encounter());
+        assert_eq!(sim.profiles[0].modifier, 2);
+        assert_eq!(sim.profiles[1].modifier, 1);
+    }
+
+    #[test]
+    fn test_profile_uses_carried_weapon() {
+        let wizard = CombatantBuilder::player("Elowen", Class::Wizard, Ancestry::Human)
+            .with_level(5)
+            .with_attributes(Attributes::from_array([2, -1, 1, 0, 0, 1]).unwrap())
+            .with_equipment("Leather Armor")
+            .with_equipment("Greatstaff")
+            .build()
+            .unwrap();
+
+        // Greatstaffs use Knowledge, not the best trait, and are Powerful
+        let profile = AttackProfile::for_combatant(&wizard);
+        assert_eq!(profile.modifier, 1);
+        assert_eq!(profile.damage.to_string(), "4d6kh3");
+    }
+
+    #[test]
+    fn test_run_counts_add_up() {
+        let report = MonteCarlo::new(create_test_encounter()).run(50, 1);
+
+        assert_eq!(report.runs, 50);
+        assert_eq!(
+            report.player_wins + report.enemy_wins + report.stalemates,
+            50
+        );
+    }
+
+    #[test]
+    fn test_same_seed_same_report() {
+        let sim = MonteCarlo::new(create_test_encounter());
+        assert_eq!(sim.run(30, 9), sim.run(30, 9));
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let sim = MonteCarlo::new(create_test_encounter());
+        let sequential = sim.run(30, 3);
+        let parallel = sim.clone().with_parallel(true).run(30, 3);
+        assert_eq!(sequential, parallel);
+    }
+
+    #[test]
+    fn test_batches_grow_without_changing_earlier_chunks() {
+        let sim = MonteCarlo::new(create_test_encounter());
+        let small = sim.batch_outcomes(BATCH_CHUNK, 8);
+        let large = sim.batch_outcomes(BATCH_CHUNK * 2 + 5, 8);
+        assert_eq!(large.len() as u32, BATCH_CHUNK * 2 + 5);
+        assert_eq!(&large[..small.len()], &small[..]);
+        // Neighbouring seeds draw from unrelated streams
+        assert_ne!(
+            sim.batch_outcomes(BATCH_CHUNK, 9)[..],
+            large[1..=small.len()]
+        );
+    }
+
+    #[test]
+    fn test_overwhelming_party_wins() {
+        let mut encounter = CombatEncounter::new(5);
+        let hero = encounter.add_combatant(hero("Hero"));
+        let rat = encounter.add_combatant(
+            CombatantBuilder::adversary("Rat")
+                .with_hp(1)
+                .with_evasion(1)
+                .build()
+                .unwrap(),
+        );
+
+        let sim = MonteCarlo::new(encounter)
+            .with_profile(hero, AttackProfile::new(10, DamageDice::d12(4)))
+            .unwrap()
+            .with_profile(rat, AttackProfile::new(-5, DamageDice::d4(1)))
+            .unwrap();
+
+        let report = sim.run(50, 0);
+        assert!(report.win_rate() > 0.9);
+    }
+
+    #[test]
+    fn test_with_profile_unknown_id() {
+        let result = MonteCarlo::new(create_test_encounter())
+            .with_profile(CombatantId(5), AttackProfile::new(0, DamageDice::d4(1)));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_max_rounds_caps_stalemates() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(
+            CombatantBuilder::adversary("Wall A")
+                .with_hp(12)
+                .with_evasion(30)
+                .build()
+                .unwrap(),
+        );
+        encounter.add_combatant(
+            player("Pacifist", Class::Bard, Ancestry::Human)
+                .build()
+                .unwrap(),
+        );
+        let mut sim = MonteCarlo::new(encounter).with_max_rounds(3);
+        sim.profiles[0] = AttackProfile::new(-20, DamageDice::d4(1));
+        sim.profiles[1] = AttackProfile::new(-20, DamageDice::d4(1));
+
+        let outcome = sim.run_once(0);
+        assert_eq!(outcome.player_victory, None);
+        assert_eq!(outcome.rounds, 3);
+    }
+
+    #[test]
+    fn test_empty_report() {
+        let report = MonteCarloReport::from_outcomes(&[]);
+        assert_eq!(report.runs, 0);
+        assert_eq!(report.win_rate(), 0.0);
+    }
+
+    #[cfg(feature = "content")]
+    #[test]
+    fn test_adversary_attack_profile() {
+        use crate::combat::adversary::AdversaryRole;
+
+        let adversary = Adversary::new("Bandit", 2, AdversaryRole::Standard);
+        let profile = adversary.attack_profile();
+        assert_eq!(profile.modifier, adversary.attack_modifier);
+        assert_eq!(profile.damage, adversary.damage);
+    }
+}
+
+#[cfg(test)]
+mod property_tests {
+    use super::*;
+    use crate::combat::simulation::{AdversaryData, CombatantBuilder, CombatantKind};
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn prop_win_rate_in_bounds(seed in any::<u64>(), hp in 1u8..10) {
+            let mut encounter = CombatEncounter::new(5);
+            encounter.add_combatant(
+                CombatantBuilder::adversary("A")
+                    .with_hp(hp)
+                    .with_evasion(10)
+                    .build()
+                    .unwrap(),
+            );
+            let mut ally = CombatantBuilder::adversary("B")
+                .with_hp(6)
+                .with_evasion(10)
+                .build()
+                .unwrap();
+            ally.kind = CombatantKind::Adversary(AdversaryData::ally());
+            encounter.add_combatant(ally);
+
+            let report = MonteCarlo::new(encounter).run(5, seed);
+            prop_assert!(report.win_rate() >= 0.0 && report.win_rate() <= 1.0);
+            prop_assert_eq!(report.player_wins + report.enemy_wins + report.stalemates, 5);
+        }
+    }
+}
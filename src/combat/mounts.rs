@@ -0,0 +1,326 @@
+//! Mounts and vehicles
+//!
+//! A combatant can ride a [`Mount`] - a horse, a giant beetle, a cart. The
+//! rider and mount move together, so a ridden mount has no zone of its
+//! own, but it keeps its own Hit Points and armor. A hit that costs the
+//! rider enough HP at once throws them from the saddle, as does the mount
+//! dropping; a dismounted rider can climb back on once they're beside it.
+
+use crate::combat::damage::DamageResult;
+use crate::combat::events::CombatEvent;
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId};
+use crate::combat::HitPoints;
+use crate::error::EngineError;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// Whether it's ridden or driven
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Default, EnumIter, Display, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum MountKind {
+    /// A creature that can be thrown off
+    #[default]
+    Mount,
+    /// A cart, boat or wagon that a hit alone won't unseat anyone from
+    Vehicle,
+}
+
+/// Something a combatant rides
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::combat::{Mount, MountKind};
+///
+/// let horse = Mount::new("Warhorse", 4).with_armor(1);
+/// assert!(horse.mounted);
+/// assert_eq!(horse.dismount_on, Some(2));
+///
+/// let wagon = Mount::vehicle("Wagon", 6);
+/// assert_eq!(wagon.kind, MountKind::Vehicle);
+/// assert_eq!(wagon.dismount_on, None);
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Mount {
+    pub name: String,
+    #[serde(default)]
+    pub kind: MountKind,
+    pub hp: HitPoints,
+    #[serde(default)]
+    pub armor: u8,
+    /// HP the rider must lose to a single hit to be thrown off
+    #[serde(default)]
+    pub dismount_on: Option<u8>,
+    /// Extra range bands the rider covers when moving themselves
+    #[serde(default)]
+    pub speed: u8,
+    /// Whether the rider is on it
+    #[serde(default)]
+    pub mounted: bool,
+    /// Where it was left, while nobody is riding it
+    #[serde(default)]
+    pub zone: i8,
+}
+
+impl Mount {
+    /// A mount that throws its rider on a hit of 2 HP or more and carries
+    /// them one band further when they move
+    pub fn new(name: impl Into<String>, hp: u8) -> Self {
+        Self {
+            name: name.into(),
+            kind: MountKind::Mount,
+            hp: HitPoints::new(hp),
+            armor: 0,
+            dismount_on: Some(2),
+            speed: 1,
+            mounted: true,
+            zone: 0,
+        }
+    }
+
+    /// A vehicle, which no hit throws anyone from
+    pub fn vehicle(name: impl Into<String>, hp: u8) -> Self {
+        Self {
+            kind: MountKind::Vehicle,
+            dismount_on: None,
+            ..Self::new(name, hp)
+        }
+    }
+
+    /// Set its armor score
+    pub fn with_armor(mut self, armor: u8) -> Self {
+        self.armor = armor;
+        self
+    }
+
+    /// Set the HP a single hit must cost the rider to throw them, or
+    /// `None` so that only losing the mount does
+    pub fn with_dismount_threshold(mut self, hp_lost: Option<u8>) -> Self {
+        self.dismount_on = hp_lost;
+        self
+    }
+
+    /// Set how many extra range bands it carries its rider
+    pub fn with_speed(mut self, speed: u8) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    /// Whether a hit costing the rider `hp_lost` throws them off
+    pub fn throws_rider(&self, hp_lost: u8) -> bool {
+        self.dismount_on
+            .is_some_and(|threshold| hp_lost > 0 && hp_lost >= threshold)
+    }
+}
+
+impl Combatant {
+    /// Whether it's riding its mount
+    pub fn is_mounted(&self) -> bool {
+        self.mount.as_ref().is_some_and(|mount| mount.mounted)
+    }
+
+    /// Extra range bands its mount carries it when it moves itself
+    pub fn mount_speed(&self) -> u8 {
+        match &self.mount {
+            Some(mount) if mount.mounted => mount.speed,
+            _ => 0,
+        }
+    }
+}
+
+impl CombatEncounter {
+    /// Deal damage to a rider's mount rather than the rider
+    ///
+    /// The mount's own armor applies. If it drops, its rider is thrown.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder, Mount};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let knight = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Knight")
+    ///         .with_hp(6)
+    ///         .with_evasion(12)
+    ///         .with_mount(Mount::new("Warhorse", 1))
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// encounter.damage_mount(knight, 8).unwrap();
+    /// let knight = encounter.get(knight).unwrap();
+    /// assert!(!knight.is_mounted());
+    /// assert_eq!(knight.hp.current, 6);
+    /// ```
+    pub fn damage_mount(
+        &mut self,
+        rider: CombatantId,
+        raw_damage: u16,
+    ) -> Result<DamageResult, EngineError> {
+        let index = self.require(rider)?;
+        let Some(mount) = self.combatants[index].mount.as_mut() else {
+            return Err(EngineError::InvalidTarget {
+                reason: format!("{} has no mount", self.combatants[index].name),
+            });
+        };
+        let result = DamageResult::calculate(raw_damage, mount.armor);
+        mount.hp.take_damage(result.hp_lost);
+        let name = mount.name.clone();
+        let dropped = !mount.hp.is_alive();
+
+        self.log(CombatEvent::MountDamaged {
+            rider,
+            mount: name,
+            damage: result.clone(),
+        });
+        if dropped {
+            self.dismount(rider)?;
+        }
+        Ok(result)
+    }
+
+    /// Get off a mount, leaving it in the rider's zone (returns false if
+    /// they weren't riding)
+    pub fn dismount(&mut self, rider: CombatantId) -> Result<bool, EngineError> {
+        let index = self.require(rider)?;
+        let combatant = &mut self.combatants[index];
+        let zone = combatant.zone;
+        let Some(mount) = combatant.mount.as_mut().filter(|mount| mount.mounted) else {
+            return Ok(false);
+        };
+        mount.mounted = false;
+        mount.zone = zone;
+        let name = mount.name.clone();
+        self.log(CombatEvent::Dismounted {
+            combatant: rider,
+            mount: name,
+        });
+        Ok(true)
+    }
+
+    /// Climb back on a mount that's still standing in the rider's zone
+    pub fn remount(&mut self, rider: CombatantId) -> Result<(), EngineError> {
+        let index = self.require(rider)?;
+        let combatant = &mut self.combatants[index];
+        let zone = combatant.zone;
+        let Some(mount) = combatant.mount.as_mut() else {
+            return Err(EngineError::InvalidTarget {
+                reason: format!("{} has no mount", combatant.name),
+            });
+        };
+        if mount.mounted {
+            return Ok(());
+        }
+        if !mount.hp.is_alive() {
+            return Err(EngineError::InvalidTarget {
+                reason: format!("{} can't be ridden until it recovers", mount.name),
+            });
+        }
+        if mount.zone != zone {
+            return Err(EngineError::InvalidTarget {
+                reason: format!("{} was left in zone {}", mount.name, mount.zone),
+            });
+        }
+        mount.mounted = true;
+        let name = mount.name.clone();
+        self.log(CombatEvent::Mounted {
+            combatant: rider,
+            mount: name,
+        });
+        Ok(())
+    }
+
+    /// Throw a rider whose hit was heavy enough
+    pub(crate) fn check_dismount(
+        &mut self,
+        rider: CombatantId,
+        result: &DamageResult,
+    ) -> Result<(), EngineError> {
+        let index = self.require(rider)?;
+        let thrown = match &self.combatants[index].mount {
+            Some(mount) => mount.mounted && mount.throws_rider(result.hp_lost),
+            None => false,
+        };
+        if thrown {
+            self.dismount(rider)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{MoveDirection, Range};
+    use crate::combat::simulation::CombatantBuilder;
+
+    fn rider(encounter: &mut CombatEncounter, name: &str, mount: Mount) -> CombatantId {
+        encounter.add_combatant(
+            CombatantBuilder::adversary(name)
+                .with_hp(6)
+                .with_evasion(10)
+                .with_mount(mount)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_heavy_hits_throw_the_rider() {
+        let mut encounter = CombatEncounter::new(5);
+        let knight = rider(&mut encounter, "Knight", Mount::new("Warhorse", 4));
+        let driver = rider(&mut encounter, "Driver", Mount::vehicle("Wagon", 6));
+
+        // A scratch doesn't unseat anyone
+        encounter.apply_damage(knight, 9).unwrap();
+        assert!(encounter.get(knight).unwrap().is_mounted());
+        encounter.apply_damage(knight, 10).unwrap();
+        assert!(!encounter.get(knight).unwrap().is_mounted());
+        assert!(encounter.events.contains(&CombatEvent::Dismounted {
+            combatant: knight,
+            mount: String::from("Warhorse"),
+        }));
+
+        encounter.apply_damage(driver, 20).unwrap();
+        assert!(encounter.get(driver).unwrap().is_mounted());
+    }
+
+    #[test]
+    fn test_riders_move_with_their_mount() {
+        let mut encounter = CombatEncounter::new(5);
+        let knight = rider(&mut encounter, "Knight", Mount::new("Warhorse", 4));
+
+        // The horse carries the knight a band further than a Close move
+        encounter
+            .force_move(knight, knight, MoveDirection::Away, 3, Range::Close)
+            .unwrap();
+        assert_eq!(encounter.get(knight).unwrap().zone, 3);
+
+        encounter.dismount(knight).unwrap();
+        encounter.place(knight, 1).unwrap();
+        assert!(encounter.remount(knight).is_err());
+        assert!(encounter
+            .force_move(knight, knight, MoveDirection::Away, 3, Range::Close)
+            .is_err());
+
+        encounter.place(knight, 3).unwrap();
+        encounter.remount(knight).unwrap();
+        assert!(encounter.get(knight).unwrap().is_mounted());
+    }
+
+    #[test]
+    fn test_a_fallen_mount_cannot_be_ridden() {
+        let mut encounter = CombatEncounter::new(5);
+        let knight = rider(&mut encounter, "Knight", Mount::new("Pony", 1));
+        assert!(encounter.damage_mount(knight, 3).is_ok());
+        assert!(encounter.get(knight).unwrap().is_mounted());
+
+        encounter.damage_mount(knight, 6).unwrap();
+        assert!(!encounter.get(knight).unwrap().is_mounted());
+        assert!(encounter.remount(knight).is_err());
+    }
+}
@@ -0,0 +1,380 @@
+//! Movement between range bands
+//!
+//! Combatants stand in zones along a line, one range band apart: two in
+//! the same zone are in Melee, one zone apart Very Close, then Close,
+//! Far and Very Far. Movement effects push or pull a target a number of
+//! bands, no further than the effect's [`Range`] allows.
+//!
+//! Under the optional opportunity rule, leaving an opponent's zone fires
+//! their [`Trigger::OpponentLeavesMelee`] features.
+
+use crate::cards::{CardEffect, MoveDirection, Range};
+use crate::character::AncestryEffect;
+use crate::combat::events::CombatEvent;
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId};
+use crate::combat::triggers::Trigger;
+use crate::error::EngineError;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// How far apart two combatants are
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    EnumIter,
+    Display,
+    Serialize,
+    Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum RangeBand {
+    Melee,
+    #[strum(to_string = "Very Close")]
+    VeryClose,
+    Close,
+    Far,
+    #[strum(to_string = "Very Far")]
+    VeryFar,
+}
+
+impl RangeBand {
+    /// The band a number of zones apart
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::RangeBand;
+    ///
+    /// assert_eq!(RangeBand::from_zones(0), RangeBand::Melee);
+    /// assert_eq!(RangeBand::from_zones(2), RangeBand::Close);
+    /// assert_eq!(RangeBand::from_zones(9), RangeBand::VeryFar);
+    /// ```
+    pub fn from_zones(zones: u8) -> Self {
+        match zones {
+            0 => RangeBand::Melee,
+            1 => RangeBand::VeryClose,
+            2 => RangeBand::Close,
+            3 => RangeBand::Far,
+            _ => RangeBand::VeryFar,
+        }
+    }
+
+    /// Whether an ability of `range` reaches this far
+    pub fn within(self, range: Range) -> bool {
+        match range.steps() {
+            Some(steps) => self as u8 <= steps,
+            None => true,
+        }
+    }
+}
+
+impl Combatant {
+    /// The furthest band this combatant's melee reaches: Melee, or Very
+    /// Close with an ancestry's Reach
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::{CombatantBuilder, RangeBand};
+    ///
+    /// let attributes = Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap();
+    /// let giant = CombatantBuilder::player("Brak", Class::Guardian, Ancestry::Giant)
+    ///     .with_attributes(attributes)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(giant.melee_reach(), RangeBand::VeryClose);
+    /// ```
+    pub fn melee_reach(&self) -> RangeBand {
+        let reach = self.ancestry().is_some_and(|ancestry| {
+            ancestry
+                .features()
+                .iter()
+                .any(|feature| feature.effect == AncestryEffect::Reach)
+        });
+        if reach {
+            RangeBand::VeryClose
+        } else {
+            RangeBand::Melee
+        }
+    }
+}
+
+impl CombatEncounter {
+    /// How far apart two combatants are
+    pub fn range_between(&self, a: CombatantId, b: CombatantId) -> Result<RangeBand, EngineError> {
+        let a = self.combatants[self.require(a)?].zone;
+        let b = self.combatants[self.require(b)?].zone;
+        Ok(RangeBand::from_zones(a.abs_diff(b)))
+    }
+
+    /// Put a combatant in a zone, as when setting up the battlefield
+    pub fn place(&mut self, combatant: CombatantId, zone: i8) -> Result<(), EngineError> {
+        let index = self.require(combatant)?;
+        let from = self.combatants[index].zone;
+        self.combatants[index].zone = zone;
+        self.log(CombatEvent::CombatantMoved {
+            combatant,
+            from,
+            to: zone,
+        });
+        Ok(())
+    }
+
+    /// Push or pull `target` up to `bands` range bands, returning how far
+    /// it ends up from `source`
+    ///
+    /// A pull stops once the target reaches the source's zone. When a
+    /// combatant moves themselves, away is along the line and toward is
+    /// back. Moving more bands than `limit` allows is an error.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::cards::{MoveDirection, Range};
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder, RangeBand};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let ogre = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Ogre").with_hp(8).with_evasion(10).build().unwrap(),
+    /// );
+    /// let knight = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Knight").with_hp(6).with_evasion(12).build().unwrap(),
+    /// );
+    ///
+    /// let band = encounter
+    ///     .force_move(ogre, knight, MoveDirection::Away, 2, Range::Close)
+    ///     .unwrap();
+    /// assert_eq!(band, RangeBand::Close);
+    /// assert!(encounter
+    ///     .force_move(ogre, knight, MoveDirection::Toward, 2, Range::VeryClose)
+    ///     .is_err());
+    /// ```
+    pub fn force_move(
+        &mut self,
+        source: CombatantId,
+        target: CombatantId,
+        direction: MoveDirection,
+        bands: u8,
+        limit: Range,
+    ) -> Result<RangeBand, EngineError> {
+        let source_zone = self.combatants[self.require(source)?].zone;
+        let target_index = self.require(target)?;
+        // A rider moving themselves goes further
+        let reach = limit.steps().map(|steps| {
+            if source == target {
+                steps.saturating_add(self.combatants[target_index].mount_speed())
+            } else {
+                steps
+            }
+        });
+        if let Some(steps) = reach.filter(|&steps| bands > steps) {
+            return Err(EngineError::InvalidTarget {
+                reason: format!(
+                    "A {:?} move covers at most {} range bands, not {}",
+                    limit, steps, bands
+                ),
+            });
+        }
+
+        let from = self.combatants[target_index].zone;
+        let bands = bands.min(i8::MAX as u8) as i8;
+        let away: i8 = if source == target || from >= source_zone {
+            1
+        } else {
+            -1
+        };
+        let to = match direction {
+            MoveDirection::Away => from.saturating_add(away * bands),
+            MoveDirection::Toward if source == target => from.saturating_sub(bands),
+            MoveDirection::Toward if from >= source_zone => {
+                from.saturating_sub(bands).max(source_zone)
+            }
+            MoveDirection::Toward => from.saturating_add(bands).min(source_zone),
+        };
+        if to != from {
+            self.combatants[target_index].zone = to;
+            self.log(CombatEvent::CombatantMoved {
+                combatant: target,
+                from,
+                to,
+            });
+            if self.opportunity_attacks {
+                self.fire_opportunity(source, target, from)?;
+            }
+        }
+        self.range_between(source, target)
+    }
+
+    /// Resolve a card's movement effect, moving `target` `bands` range bands
+    pub fn resolve_move(
+        &mut self,
+        user: CombatantId,
+        target: CombatantId,
+        effect: &CardEffect,
+        bands: u8,
+    ) -> Result<RangeBand, EngineError> {
+        let CardEffect::Move {
+            distance,
+            direction,
+            ..
+        } = effect
+        else {
+            return Err(EngineError::InvalidTarget {
+                reason: String::from("Only movement effects can move a target"),
+            });
+        };
+        self.force_move(user, target, *direction, bands, *distance)
+    }
+
+    /// Fire the features of every opponent `mover` just left the melee
+    /// reach of, other than whoever moved them
+    fn fire_opportunity(
+        &mut self,
+        source: CombatantId,
+        mover: CombatantId,
+        from: i8,
+    ) -> Result<(), EngineError> {
+        let Some(moved) = self.get(mover) else {
+            return Ok(());
+        };
+        let to = moved.zone;
+        let left: Vec<CombatantId> = self
+            .combatants
+            .iter()
+            .filter(|c| {
+                let reach = c.melee_reach();
+                c.id != source
+                    && c.id != mover
+                    && c.is_alive()
+                    && c.is_player() != moved.is_player()
+                    && RangeBand::from_zones(c.zone.abs_diff(from)) <= reach
+                    && RangeBand::from_zones(c.zone.abs_diff(to)) > reach
+            })
+            .map(|c| c.id)
+            .collect();
+        for opponent in left {
+            self.fire_triggers(opponent, mover, |t| t == Trigger::OpponentLeavesMelee)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::Target;
+    use crate::character::{Ancestry, Class};
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::combat::triggers::{TriggerEffect, TriggeredFeature};
+    use crate::test_support::player;
+
+    fn add(encounter: &mut CombatEncounter, name: &str, zone: i8) -> CombatantId {
+        encounter.add_combatant(
+            CombatantBuilder::adversary(name)
+                .with_hp(6)
+                .with_evasion(10)
+                .with_zone(zone)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_pulls_stop_at_the_source() {
+        let mut encounter = CombatEncounter::new(5);
+        let hook = add(&mut encounter, "Hook", 0);
+        let victim = add(&mut encounter, "Victim", -3);
+
+        let pull = CardEffect::pull(Range::Far, Target::Enemy);
+        let band = encounter.resolve_move(hook, victim, &pull, 3).unwrap();
+        assert_eq!(band, RangeBand::Melee);
+        assert_eq!(encounter.get(victim).unwrap().zone, 0);
+
+        let push = CardEffect::push(Range::Close, Target::Enemy);
+        encounter.resolve_move(hook, victim, &push, 1).unwrap();
+        assert_eq!(encounter.get(victim).unwrap().zone, 1);
+        assert!(encounter.resolve_move(hook, victim, &push, 3).is_err());
+    }
+
+    #[test]
+    fn test_leaving_melee_fires_opportunity_features() {
+        let mut encounter = CombatEncounter::new(5);
+        let ranger = encounter.add_combatant(
+            player("Ash", Class::Ranger, Ancestry::Human)
+                .build()
+                .unwrap(),
+        );
+        let guard = encounter.add_combatant(
+            CombatantBuilder::adversary("Guard")
+                .with_hp(6)
+                .with_evasion(10)
+                .with_trigger(TriggeredFeature::new(
+                    "Hold the Line",
+                    Trigger::OpponentLeavesMelee,
+                    TriggerEffect::OpponentMarksStress(1),
+                ))
+                .build()
+                .unwrap(),
+        );
+
+        // Without the optional rule nothing fires
+        encounter
+            .force_move(ranger, ranger, MoveDirection::Away, 1, Range::VeryClose)
+            .unwrap();
+        assert_eq!(encounter.get(ranger).unwrap().stress.current, 0);
+
+        encounter.place(ranger, 0).unwrap();
+        encounter.opportunity_attacks = true;
+        encounter
+            .force_move(ranger, ranger, MoveDirection::Away, 1, Range::VeryClose)
+            .unwrap();
+        assert_eq!(encounter.get(ranger).unwrap().stress.current, 1);
+        assert_eq!(
+            encounter.range_between(ranger, guard).unwrap(),
+            RangeBand::VeryClose
+        );
+    }
+
+    #[test]
+    fn test_reach_extends_opportunity_to_very_close() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.opportunity_attacks = true;
+        encounter.add_combatant(
+            player("Brak", Class::Guardian, Ancestry::Giant)
+                .with_trigger(TriggeredFeature::new(
+                    "Sweep",
+                    Trigger::OpponentLeavesMelee,
+                    TriggerEffect::OpponentMarksStress(1),
+                ))
+                .build()
+                .unwrap(),
+        );
+        let goblin = add(&mut encounter, "Goblin", 0);
+
+        // Still within reach
+        encounter
+            .force_move(goblin, goblin, MoveDirection::Away, 1, Range::VeryClose)
+            .unwrap();
+        assert_eq!(encounter.get(goblin).unwrap().stress.current, 0);
+
+        encounter
+            .force_move(goblin, goblin, MoveDirection::Away, 1, Range::VeryClose)
+            .unwrap();
+        assert_eq!(encounter.get(goblin).unwrap().stress.current, 1);
+    }
+
+    #[test]
+    fn test_bands_and_ranges() {
+        assert!(RangeBand::VeryClose.within(Range::VeryClose));
+        assert!(!RangeBand::Far.within(Range::Close));
+        assert!(RangeBand::VeryFar.within(Range::Any));
+        assert_eq!(RangeBand::VeryFar.to_string(), "Very Far");
+    }
+}
@@ -0,0 +1,218 @@
+//! Ongoing damage - burning, bleeding and the like
+//!
+//! A [`DamageOverTime`] stays on a combatant and is rolled each turn as
+//! [`CombatEncounter::next_turn`] moves past them, at the start or end of
+//! their turn, until it wears off or is removed.
+
+use crate::combat::damage::{DamageOverTime, TickTiming};
+use crate::combat::events::CombatEvent;
+use crate::combat::simulation::{CombatEncounter, CombatantId};
+use crate::error::EngineError;
+use rand::Rng;
+
+impl CombatEncounter {
+    /// Start ongoing damage on a combatant, replacing any of the same name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder, DamageOverTime};
+    /// use daggerheart_engine::core::dice::DamageDice;
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let goblin = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Goblin").with_hp(4).with_evasion(10).build().unwrap(),
+    /// );
+    /// encounter.start();
+    ///
+    /// let burning = DamageOverTime::new("Burning", DamageDice::new(vec![]).with_bonus(6));
+    /// encounter.apply_ongoing(goblin, burning).unwrap();
+    ///
+    /// // The goblin burns as its next turn begins
+    /// encounter.next_turn();
+    /// assert_eq!(encounter.get(goblin).unwrap().hp.current, 3);
+    /// ```
+    pub fn apply_ongoing(
+        &mut self,
+        target: CombatantId,
+        ongoing: DamageOverTime,
+    ) -> Result<(), EngineError> {
+        let index = self.require(target)?;
+        let name = ongoing.name.clone();
+        self.combatants[index].add_ongoing(ongoing);
+        self.log(CombatEvent::OngoingDamageStarted {
+            combatant: target,
+            name,
+        });
+        Ok(())
+    }
+
+    /// Stop ongoing damage by name (returns false if it wasn't being taken)
+    pub fn remove_ongoing(&mut self, target: CombatantId, name: &str) -> Result<bool, EngineError> {
+        let index = self.require(target)?;
+        let removed = self.combatants[index].remove_ongoing(name);
+        if removed {
+            self.log(CombatEvent::OngoingDamageEnded {
+                combatant: target,
+                name: String::from(name),
+            });
+        }
+        Ok(removed)
+    }
+
+    /// Roll a combatant's ongoing damage that falls due at `timing`
+    pub(crate) fn tick_ongoing<R: Rng>(
+        &mut self,
+        combatant: CombatantId,
+        timing: TickTiming,
+        rng: &mut R,
+    ) {
+        let Some(index) = self.index_of(combatant) else {
+            return;
+        };
+        if !self.combatants[index].is_alive() {
+            return;
+        }
+        let due: Vec<DamageOverTime> = self.combatants[index]
+            .ongoing
+            .iter()
+            .filter(|ongoing| ongoing.timing == timing)
+            .cloned()
+            .collect();
+
+        for ongoing in due {
+            // A summon that drops leaves the field part way through
+            let Some(index) = self.index_of(combatant) else {
+                return;
+            };
+            let rolled = ongoing.dice.roll_with_rng(rng).total;
+            let result = ongoing.damage(rolled, self.combatants[index].armor);
+            self.log(CombatEvent::OngoingDamageTicked {
+                combatant,
+                name: ongoing.name.clone(),
+                rolled,
+            });
+            // The combatant was found above, so this can't fail
+            let _ = self.apply_damage_result(combatant, result);
+
+            let Some(index) = self.index_of(combatant) else {
+                return;
+            };
+            let effects = &mut self.combatants[index].ongoing;
+            let Some(position) = effects.iter().position(|e| e.name == ongoing.name) else {
+                continue;
+            };
+            if !effects[position].tick() {
+                effects.remove(position);
+                self.log(CombatEvent::OngoingDamageEnded {
+                    combatant,
+                    name: ongoing.name,
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::effects::CardEffect;
+    use crate::cards::Target;
+    use crate::combat::damage::Mitigation;
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::combat::triggers::{Trigger, TriggerEffect, TriggeredFeature};
+    use crate::combat::Attack;
+    use crate::core::dice::DamageDice;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn flat(amount: i16) -> DamageDice {
+        DamageDice::new(vec![]).with_bonus(amount)
+    }
+
+    fn started(encounter: &mut CombatEncounter, names: &[&str]) -> Vec<CombatantId> {
+        let ids = names
+            .iter()
+            .map(|name| {
+                encounter.add_combatant(
+                    CombatantBuilder::adversary(*name)
+                        .with_hp(6)
+                        .with_evasion(10)
+                        .with_armor(2)
+                        .build()
+                        .unwrap(),
+                )
+            })
+            .collect();
+        encounter.start_with_rng(&mut StdRng::seed_from_u64(0));
+        ids
+    }
+
+    #[test]
+    fn test_ongoing_damage_wears_off() {
+        let mut encounter = CombatEncounter::new(5);
+        let ids = started(&mut encounter, &["Goblin"]);
+        let bleeding = DamageOverTime::new("Bleeding", flat(1))
+            .lasting(2)
+            .with_mitigation(Mitigation::Direct);
+        encounter.apply_ongoing(ids[0], bleeding).unwrap();
+
+        let mut rng = StdRng::seed_from_u64(1);
+        encounter.next_turn_with_rng(&mut rng);
+        encounter.next_turn_with_rng(&mut rng);
+        encounter.next_turn_with_rng(&mut rng);
+        let goblin = encounter.get(ids[0]).unwrap();
+        assert_eq!(goblin.hp.current, 4);
+        assert!(goblin.ongoing.is_empty());
+        assert!(encounter.events.contains(&CombatEvent::OngoingDamageEnded {
+            combatant: ids[0],
+            name: String::from("Bleeding"),
+        }));
+    }
+
+    #[test]
+    fn test_end_of_turn_damage_ignoring_armor() {
+        let mut encounter = CombatEncounter::new(5);
+        let ids = started(&mut encounter, &["First", "Second"]);
+        let first = encounter.current_combatant_id().unwrap();
+        let second = *ids.iter().find(|&&id| id != first).unwrap();
+        let acid = DamageOverTime::new("Acid", flat(6))
+            .at_end_of_turn()
+            .with_mitigation(Mitigation::ThresholdsOnly);
+        encounter.apply_ongoing(first, acid.clone()).unwrap();
+        encounter.apply_ongoing(second, acid).unwrap();
+
+        encounter.next_turn_with_rng(&mut StdRng::seed_from_u64(1));
+        assert_eq!(encounter.get(first).unwrap().hp.current, 5);
+        assert_eq!(encounter.get(second).unwrap().hp.current, 6);
+    }
+
+    #[test]
+    fn test_cards_and_features_apply_ongoing_damage() {
+        let mut encounter = CombatEncounter::new(5);
+        let ids = started(&mut encounter, &["Imp", "Knight"]);
+        let burning = DamageOverTime::new("Burning", DamageDice::d4(1)).magic();
+
+        let card = CardEffect::DamageOverTime {
+            effect: burning.clone(),
+            target: Target::Enemy,
+        };
+        assert!(card.targets_enemies());
+        assert!(encounter.combatants[1].apply_effect("Ignite", &card));
+        assert_eq!(encounter.combatants[1].ongoing, vec![burning.clone()]);
+
+        encounter.combatants[0].triggers.push(TriggeredFeature::new(
+            "Searing Claws",
+            Trigger::Hits,
+            TriggerEffect::OpponentSuffers(burning),
+        ));
+        encounter
+            .resolve_attack(ids[0], ids[1], &Attack::new(50), &flat(1))
+            .unwrap();
+        assert_eq!(encounter.combatants[1].ongoing.len(), 1);
+        assert!(encounter
+            .events
+            .iter()
+            .any(|e| matches!(e, CombatEvent::OngoingDamageStarted { .. })));
+    }
+}
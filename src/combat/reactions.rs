@@ -0,0 +1,702 @@
+//! Reactions - interrupting an attack before it resolves
+//!
+//! An attack is resolved in steps so combatants can answer it. When the
+//! attack is declared, and again when its damage is about to land, every
+//! combatant with a matching reaction that can pay for it is offered the
+//! interrupt. Taking one pays its cost and changes the pending attack
+//! before the next step resolves.
+//!
+//! [`CombatEncounter::resolve_attack`] runs the same steps without
+//! offering anything.
+
+use crate::cards::DomainCard;
+use crate::combat::attack::{Attack, AttackResult};
+use crate::combat::conditions::Condition;
+use crate::combat::damage::DamageResult;
+use crate::combat::events::CombatEvent;
+use crate::combat::simulation::{CombatEncounter, CombatantId};
+use crate::combat::triggers::Trigger;
+use crate::core::dice::DamageDice;
+use crate::error::EngineError;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+/// A point in an attack where reactions are offered
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum ReactionWindow {
+    /// The attack has been declared but not rolled
+    #[strum(to_string = "attack declared")]
+    AttackDeclared,
+    /// The attack hit and its damage is about to be applied
+    #[strum(to_string = "damage incoming")]
+    DamageIncoming,
+    /// Nothing more can be changed
+    #[strum(to_string = "resolved")]
+    Resolved,
+}
+
+/// Whose attacks a reaction answers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum ReactionScope {
+    /// Attacks against the combatant itself
+    #[default]
+    Own,
+    /// Attacks against someone on the combatant's side
+    Ally,
+}
+
+/// How a reaction changes the pending attack
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum ReactionEffect {
+    /// Add to the attack roll; negative to hinder it
+    AttackModifier(i8),
+    /// Raise the target's Evasion against this attack
+    EvasionBonus(u8),
+    /// Take this much off the damage before armor
+    ReduceDamage(u16),
+    /// Halve the damage before armor
+    HalveDamage,
+    /// Stop the attack, or its damage, entirely
+    Cancel,
+}
+
+/// What taking a reaction costs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ReactionCost {
+    /// Spent from the reactor's Hope (or the shared pool)
+    #[serde(default)]
+    pub hope: u8,
+    /// Marked by the reactor
+    #[serde(default)]
+    pub stress: u8,
+    /// Spent from the GM's Fear, for adversaries
+    #[serde(default)]
+    pub fear: u8,
+}
+
+/// A reaction card or feature a combatant can interrupt with
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::combat::reactions::{Reaction, ReactionEffect, ReactionWindow};
+///
+/// let parry = Reaction::new("Parry", ReactionWindow::AttackDeclared, ReactionEffect::EvasionBonus(3))
+///     .costing_stress(1);
+/// assert_eq!(parry.cost.stress, 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Reaction {
+    pub name: String,
+    pub window: ReactionWindow,
+    #[serde(default)]
+    pub scope: ReactionScope,
+    #[serde(default)]
+    pub cost: ReactionCost,
+    pub effect: ReactionEffect,
+    /// Id of the domain card it comes from, if any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub card: Option<String>,
+}
+
+impl Reaction {
+    /// Create a free reaction to attacks on the combatant itself
+    pub fn new(name: impl Into<String>, window: ReactionWindow, effect: ReactionEffect) -> Self {
+        Self {
+            name: name.into(),
+            window,
+            scope: ReactionScope::Own,
+            cost: ReactionCost::default(),
+            effect,
+            card: None,
+        }
+    }
+
+    /// A reaction granted by a domain card, named after it
+    pub fn from_card(card: &DomainCard, window: ReactionWindow, effect: ReactionEffect) -> Self {
+        let mut reaction = Self::new(card.name.clone(), window, effect);
+        reaction.card = Some(card.id.clone());
+        reaction
+    }
+
+    /// Answer attacks against allies instead
+    pub fn for_allies(mut self) -> Self {
+        self.scope = ReactionScope::Ally;
+        self
+    }
+
+    /// Cost Hope to take
+    pub fn costing_hope(mut self, hope: u8) -> Self {
+        self.cost.hope = hope;
+        self
+    }
+
+    /// Cost Stress to take
+    pub fn costing_stress(mut self, stress: u8) -> Self {
+        self.cost.stress = stress;
+        self
+    }
+
+    /// Cost the GM Fear to take
+    pub fn costing_fear(mut self, fear: u8) -> Self {
+        self.cost.fear = fear;
+        self
+    }
+}
+
+/// A reaction a combatant could take right now
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ReactionOffer {
+    pub combatant: CombatantId,
+    pub reaction: String,
+}
+
+/// An attack part way through resolving
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PendingAttack {
+    pub attacker: CombatantId,
+    pub target: CombatantId,
+    pub attack: Attack,
+    pub damage: DamageDice,
+    pub window: ReactionWindow,
+    /// Added to the target's Evasion for this attack
+    pub evasion_bonus: u8,
+    /// A reaction stopped the attack or its damage
+    pub cancelled: bool,
+    /// The roll, once made
+    pub result: Option<AttackResult>,
+    /// Damage rolled on a hit, before reactions and armor
+    pub raw_damage: u16,
+    pub damage_reduction: u16,
+    pub halve_damage: bool,
+    /// Reactions that can be taken in the current window
+    pub offers: Vec<ReactionOffer>,
+    /// Reactions taken so far; each combatant reacts once per attack
+    pub taken: Vec<ReactionOffer>,
+}
+
+impl PendingAttack {
+    /// Damage that will be applied, before armor
+    pub fn damage_to_apply(&self) -> u16 {
+        if self.cancelled {
+            return 0;
+        }
+        let damage = self.raw_damage.saturating_sub(self.damage_reduction);
+        if self.halve_damage {
+            damage.div_ceil(2)
+        } else {
+            damage
+        }
+    }
+}
+
+impl CombatEncounter {
+    /// Declare an attack, opening its first reaction window
+    ///
+    /// Hidden targets can't be attacked, nor flying ones in melee by an
+    /// attacker who can't reach them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::reactions::{Reaction, ReactionEffect, ReactionWindow};
+    /// use daggerheart_engine::combat::{Attack, CombatEncounter, CombatantBuilder};
+    /// use daggerheart_engine::core::dice::DamageDice;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let ogre = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Ogre").with_hp(8).with_evasion(10).build().unwrap(),
+    /// );
+    /// let knight = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Knight")
+    ///         .with_hp(6)
+    ///         .with_evasion(12)
+    ///         .with_reaction(Reaction::new(
+    ///             "Shield Block",
+    ///             ReactionWindow::DamageIncoming,
+    ///             ReactionEffect::Cancel,
+    ///         ))
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// let mut rng = StdRng::seed_from_u64(2);
+    /// let mut pending = encounter
+    ///     .declare_attack(ogre, knight, Attack::new(50), DamageDice::d8(2))
+    ///     .unwrap();
+    /// assert!(pending.offers.is_empty());
+    ///
+    /// // The attack hits, and the knight can block before damage lands
+    /// encounter.roll_attack_with_rng(&mut pending, &mut rng).unwrap();
+    /// assert_eq!(pending.offers.len(), 1);
+    /// encounter.react(&mut pending, knight, "Shield Block").unwrap();
+    ///
+    /// let damage = encounter.land_attack(&mut pending).unwrap();
+    /// assert!(damage.is_none());
+    /// assert_eq!(encounter.get(knight).unwrap().hp.current, 6);
+    /// ```
+    pub fn declare_attack(
+        &mut self,
+        attacker: CombatantId,
+        target: CombatantId,
+        attack: Attack,
+        damage: DamageDice,
+    ) -> Result<PendingAttack, EngineError> {
+        let attacker_index = self.require(attacker)?;
+        let target_index = self.require(target)?;
+        let hidden = &self.combatants[target_index];
+        if hidden.has_condition(Condition::Hidden) {
+            return Err(EngineError::TargetHidden {
+                name: hidden.name.to_string(),
+            });
+        }
+        let striker = &self.combatants[attacker_index];
+        if attack.is_melee() && !striker.can_reach_in_melee(hidden) {
+            return Err(EngineError::InvalidTarget {
+                reason: format!("{} is flying out of {}'s reach", hidden.name, striker.name),
+            });
+        }
+        let mut pending = PendingAttack {
+            attacker,
+            target,
+            attack,
+            damage,
+            window: ReactionWindow::AttackDeclared,
+            evasion_bonus: 0,
+            cancelled: false,
+            result: None,
+            raw_damage: 0,
+            damage_reduction: 0,
+            halve_damage: false,
+            offers: Vec::new(),
+            taken: Vec::new(),
+        };
+        pending.offers = self.reaction_offers(&pending);
+        Ok(pending)
+    }
+
+    /// Reactions that could be taken in the pending attack's window
+    pub fn reaction_offers(&self, pending: &PendingAttack) -> Vec<ReactionOffer> {
+        let Some(target) = self.get(pending.target) else {
+            return Vec::new();
+        };
+        let mut offers = Vec::new();
+        for combatant in self.combatants.iter().filter(|c| c.is_alive()) {
+            if pending.taken.iter().any(|t| t.combatant == combatant.id) {
+                continue;
+            }
+            let own = combatant.id == pending.target;
+            let ally = !own && combatant.is_player() == target.is_player();
+            for reaction in &combatant.reactions {
+                let in_scope = match reaction.scope {
+                    ReactionScope::Own => own,
+                    ReactionScope::Ally => ally,
+                };
+                if reaction.window == pending.window
+                    && in_scope
+                    && self.can_afford(combatant.id, &reaction.cost)
+                {
+                    offers.push(ReactionOffer {
+                        combatant: combatant.id,
+                        reaction: reaction.name.clone(),
+                    });
+                }
+            }
+        }
+        offers
+    }
+
+    pub(crate) fn can_afford(&self, combatant: CombatantId, cost: &ReactionCost) -> bool {
+        let hope = cost.hope == 0
+            || self
+                .hope_pool(combatant)
+                .is_ok_and(|pool| pool.current >= cost.hope);
+        hope && self.fear.current >= cost.fear
+    }
+
+    /// Take an offered reaction, paying its cost and changing the attack
+    pub fn react(
+        &mut self,
+        pending: &mut PendingAttack,
+        reactor: CombatantId,
+        reaction: &str,
+    ) -> Result<(), EngineError> {
+        let offered = pending
+            .offers
+            .iter()
+            .any(|o| o.combatant == reactor && o.reaction.eq_ignore_ascii_case(reaction));
+        let found = self.get(reactor).and_then(|c| {
+            c.reactions
+                .iter()
+                .find(|r| r.name.eq_ignore_ascii_case(reaction))
+                .cloned()
+        });
+        let Some(found) = found.filter(|_| offered) else {
+            return Err(EngineError::ReactionUnavailable {
+                reaction: reaction.to_string(),
+                window: pending.window.to_string(),
+            });
+        };
+
+        if found.cost.hope > 0 {
+            self.spend_hope(reactor, found.cost.hope)?;
+        }
+        if found.cost.fear > 0 {
+            self.spend_fear(found.cost.fear)?;
+        }
+        if found.cost.stress > 0 {
+            if let Some(combatant) = self.get_mut(reactor) {
+                combatant.gain_stress(found.cost.stress);
+            }
+            self.log(CombatEvent::StressGained {
+                combatant: reactor,
+                amount: found.cost.stress,
+            });
+        }
+        if let Some(card) = &found.card {
+            self.log(CombatEvent::CardPlayed {
+                combatant: reactor,
+                card: card.clone(),
+            });
+        }
+
+        match found.effect {
+            ReactionEffect::AttackModifier(bonus) => {
+                pending.attack.modifier = pending.attack.modifier.saturating_add(bonus)
+            }
+            ReactionEffect::EvasionBonus(bonus) => {
+                pending.evasion_bonus = pending.evasion_bonus.saturating_add(bonus)
+            }
+            ReactionEffect::ReduceDamage(amount) => {
+                pending.damage_reduction = pending.damage_reduction.saturating_add(amount)
+            }
+            ReactionEffect::HalveDamage => pending.halve_damage = true,
+            ReactionEffect::Cancel => pending.cancelled = true,
+        }
+        self.log(CombatEvent::ReactionTaken {
+            combatant: reactor,
+            reaction: found.name.clone(),
+            attacker: pending.attacker,
+        });
+
+        pending.taken.push(ReactionOffer {
+            combatant: reactor,
+            reaction: found.name,
+        });
+        pending.offers = self.reaction_offers(pending);
+        Ok(())
+    }
+
+    /// Roll the declared attack
+    ///
+    /// On a hit the damage is rolled and the damage window opens. A
+    /// cancelled attack is resolved without a roll.
+    pub fn roll_attack(
+        &mut self,
+        pending: &mut PendingAttack,
+    ) -> Result<Option<AttackResult>, EngineError> {
+        let mut rng = rand::thread_rng();
+        self.roll_attack_with_rng(pending, &mut rng)
+    }
+
+    /// Roll the declared attack with a specific RNG (for testing/seeding)
+    pub fn roll_attack_with_rng<R: Rng>(
+        &mut self,
+        pending: &mut PendingAttack,
+        rng: &mut R,
+    ) -> Result<Option<AttackResult>, EngineError> {
+        if pending.window != ReactionWindow::AttackDeclared {
+            return Ok(pending.result.clone());
+        }
+        if pending.cancelled {
+            pending.window = ReactionWindow::Resolved;
+            pending.offers.clear();
+            return Ok(None);
+        }
+
+        let attacker = pending.attacker;
+        let target = pending.target;
+        let defender = &self.combatants[self.require(target)?];
+        let evasion = defender.evasion as u16 + pending.evasion_bonus as u16;
+        if defender.has_condition(Condition::Vulnerable) {
+            pending.attack.with_advantage = true;
+        }
+        let mut result = pending
+            .attack
+            .roll_with_rng(rng)
+            .against(evasion.min(u8::MAX as u16) as u8);
+        if result.hit {
+            // Rolled before it's logged so the event carries the damage dice
+            let (raw_damage, faces) =
+                self.rules
+                    .roll_damage_faces_with_rng(&pending.damage, result.critical, rng);
+            pending.raw_damage = raw_damage;
+            result.damage_dice = faces;
+        }
+        self.log(CombatEvent::AttackResolved {
+            attacker,
+            target,
+            result: result.clone(),
+        });
+
+        if self.combatants[self.require(attacker)?].is_player() {
+            if result.critical || result.with_hope {
+                // Summons have no Hope of their own to gain
+                if self.hope_pool(attacker).is_ok() {
+                    self.gain_hope(attacker, 1)?;
+                }
+            } else {
+                self.gain_fear(1);
+            }
+        }
+        // Attacking gives a Hidden attacker away
+        if self.combatants[self.require(attacker)?].has_condition(Condition::Hidden) {
+            self.remove_condition(attacker, Condition::Hidden)?;
+        }
+
+        if result.hit {
+            self.fire_triggers(attacker, target, |t| t == Trigger::Hits)?;
+            pending.window = ReactionWindow::DamageIncoming;
+        } else {
+            self.fire_triggers(target, attacker, |t| t == Trigger::Missed)?;
+            pending.window = ReactionWindow::Resolved;
+        }
+        pending.result = Some(result.clone());
+        pending.offers = match pending.window {
+            ReactionWindow::DamageIncoming => self.reaction_offers(pending),
+            _ => Vec::new(),
+        };
+        Ok(Some(result))
+    }
+
+    /// Apply the pending attack's damage, after any reactions
+    ///
+    /// Returns `None` if there was nothing to apply: the attack missed, or
+    /// a reaction cancelled it or its damage.
+    pub fn land_attack(
+        &mut self,
+        pending: &mut PendingAttack,
+    ) -> Result<Option<DamageResult>, EngineError> {
+        if pending.window != ReactionWindow::DamageIncoming {
+            return Ok(None);
+        }
+        pending.window = ReactionWindow::Resolved;
+        pending.offers.clear();
+        if pending.cancelled {
+            return Ok(None);
+        }
+
+        let attacker = pending.attacker;
+        let target = pending.target;
+        let was_alive = self.get(target).is_some_and(|c| c.is_alive());
+        let dealt = self.apply_damage(target, pending.damage_to_apply())?;
+        if dealt.hp_lost > 0 {
+            let melee = pending.attack.is_melee();
+            self.fire_triggers(target, attacker, |t| {
+                t == Trigger::Damaged || (melee && t == Trigger::DamagedByMelee)
+            })?;
+        }
+        let defeated = self
+            .get(target)
+            .map_or(was_alive, |c| was_alive && !c.is_alive());
+        if defeated {
+            self.fire_triggers(target, attacker, |t| t == Trigger::Defeated)?;
+        }
+        Ok(Some(dealt))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::ActionCost;
+    use crate::character::{Ancestry, Class, Domain};
+    use crate::combat::simulation::{Combatant, CombatantBuilder};
+    use crate::rules::CritRule;
+    use crate::test_support::player;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn hero(name: &str, reaction: Reaction) -> Combatant {
+        player(name, Class::Guardian, Ancestry::Human)
+            .with_reaction(reaction)
+            .build()
+            .unwrap()
+    }
+
+    fn brute() -> Combatant {
+        CombatantBuilder::adversary("Brute")
+            .with_hp(10)
+            .with_evasion(10)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_crit_rule_adds_damage_on_critical_hits() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.rules.crit = CritRule::MaxDice;
+        let a = encounter.add_combatant(brute());
+        let b = encounter.add_combatant(brute());
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut crits = 0;
+        for _ in 0..200 {
+            let mut pending = encounter
+                .declare_attack(a, b, Attack::new(50), DamageDice::d4(1))
+                .unwrap();
+            let result = encounter
+                .roll_attack_with_rng(&mut pending, &mut rng)
+                .unwrap()
+                .unwrap();
+            if result.critical {
+                crits += 1;
+                assert!((5..=8).contains(&pending.raw_damage));
+            } else {
+                assert!((1..=4).contains(&pending.raw_damage));
+            }
+        }
+        assert!(crits > 0);
+    }
+
+    #[test]
+    fn test_offers_follow_window_scope_and_cost() {
+        let mut encounter = CombatEncounter::new(5);
+        let brute = encounter.add_combatant(brute());
+        let ash = encounter.add_combatant(hero(
+            "Ash",
+            Reaction::new(
+                "Sidestep",
+                ReactionWindow::AttackDeclared,
+                ReactionEffect::EvasionBonus(2),
+            )
+            .costing_hope(3),
+        ));
+        let grom = encounter.add_combatant(hero(
+            "Grom",
+            Reaction::new(
+                "Intercept",
+                ReactionWindow::AttackDeclared,
+                ReactionEffect::AttackModifier(-2),
+            )
+            .for_allies(),
+        ));
+
+        let pending = encounter
+            .declare_attack(brute, ash, Attack::new(1), DamageDice::d6(1))
+            .unwrap();
+        // Ash starts with 2 Hope, too little for Sidestep
+        assert_eq!(
+            pending.offers,
+            vec![ReactionOffer {
+                combatant: grom,
+                reaction: String::from("Intercept"),
+            }]
+        );
+
+        encounter.gain_hope(ash, 1).unwrap();
+        let pending = encounter
+            .declare_attack(brute, ash, Attack::new(1), DamageDice::d6(1))
+            .unwrap();
+        assert_eq!(pending.offers.len(), 2);
+    }
+
+    #[test]
+    fn test_reactions_pay_costs_and_change_the_attack() {
+        let mut encounter = CombatEncounter::new(5);
+        let brute = encounter.add_combatant(brute());
+        let card = DomainCard::new(
+            "deflect",
+            "Deflect",
+            Domain::Valor,
+            1,
+            "Turn a blow aside",
+            ActionCost::Reaction,
+        );
+        let ash = encounter.add_combatant(hero(
+            "Ash",
+            Reaction::from_card(
+                &card,
+                ReactionWindow::DamageIncoming,
+                ReactionEffect::HalveDamage,
+            )
+            .costing_stress(1),
+        ));
+
+        let mut pending = encounter
+            .declare_attack(
+                brute,
+                ash,
+                Attack::new(50),
+                DamageDice::new(vec![]).with_bonus(9),
+            )
+            .unwrap();
+        assert!(matches!(
+            encounter.react(&mut pending, ash, "Deflect"),
+            Err(EngineError::ReactionUnavailable { window, .. }) if window == "attack declared"
+        ));
+
+        let mut rng = StdRng::seed_from_u64(4);
+        encounter
+            .roll_attack_with_rng(&mut pending, &mut rng)
+            .unwrap();
+        encounter.react(&mut pending, ash, "deflect").unwrap();
+        assert!(pending.offers.is_empty());
+        assert_eq!(pending.damage_to_apply(), 5);
+
+        encounter.land_attack(&mut pending).unwrap();
+        let ash = encounter.get(ash).unwrap();
+        assert_eq!(ash.stress.current, 1);
+        assert!(encounter.events.iter().any(|e| matches!(
+            e,
+            CombatEvent::CardPlayed { card, .. } if card == "deflect"
+        )));
+        assert!(encounter
+            .events
+            .iter()
+            .any(|e| matches!(e, CombatEvent::ReactionTaken { .. })));
+    }
+
+    #[test]
+    fn test_cancelled_attack_is_never_rolled() {
+        let mut encounter = CombatEncounter::new(5);
+        let brute = encounter.add_combatant(brute());
+        encounter.fear.gain(2);
+        let wisp = encounter.add_combatant(
+            CombatantBuilder::adversary("Wisp")
+                .with_hp(1)
+                .with_evasion(10)
+                .with_reaction(
+                    Reaction::new(
+                        "Blink",
+                        ReactionWindow::AttackDeclared,
+                        ReactionEffect::Cancel,
+                    )
+                    .costing_fear(1),
+                )
+                .build()
+                .unwrap(),
+        );
+
+        let mut pending = encounter
+            .declare_attack(brute, wisp, Attack::new(5), DamageDice::d6(1))
+            .unwrap();
+        encounter.react(&mut pending, wisp, "Blink").unwrap();
+        let logged = encounter.events.len();
+
+        assert!(encounter.roll_attack(&mut pending).unwrap().is_none());
+        assert!(encounter.land_attack(&mut pending).unwrap().is_none());
+        assert_eq!(encounter.events.len(), logged);
+        assert_eq!(encounter.fear.current, 1);
+    }
+}
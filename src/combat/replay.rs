@@ -0,0 +1,840 @@
+//! Deterministic encounter replay
+//!
+//! A `Replay` captures an initial encounter state, an RNG seed, and the
+//! sequence of commands applied to it. Running the replay reproduces the
+//! exact same rolls and outcomes, which makes bug reports and balance
+//! regression tests reproducible.
+
+use crate::cards::{CardEffect, DomainCard, MoveDirection, Range};
+use crate::combat::attack::Attack;
+use crate::combat::conditions::Condition;
+use crate::combat::countdown::Countdown;
+use crate::combat::damage::DamageOverTime;
+use crate::combat::hazards::Hazard;
+use crate::combat::movement::RangeBand;
+use crate::combat::reactions::{PendingAttack, ReactionWindow};
+use crate::combat::resources::{ConversionRate, Pool};
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId};
+use crate::core::dice::DamageDice;
+use crate::error::EngineError;
+#[cfg(feature = "persistence")]
+use crate::storage::{self, Persist};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "persistence")]
+use std::path::Path;
+
+/// A mutating operation on an encounter that can be recorded and replayed
+///
+/// Every public method that changes a [`CombatEncounter`] has a variant
+/// here. An attack taken through its reaction window is declared with
+/// `DeclareAttack` and waits in `pending_attack` until `RollAttack` and
+/// `LandAttack` resolve it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum EncounterCommand {
+    /// Roll initiative and begin round 1
+    Start,
+    /// Advance to the next turn
+    NextTurn,
+    /// End the round, skipping anyone who hasn't acted
+    EndRound,
+    /// Start a countdown
+    AddCountdown { countdown: Countdown },
+    /// Add an environment hazard
+    AddHazard { hazard: Hazard },
+    /// Roll an attack, applying damage on a hit
+    Attack {
+        attacker: CombatantId,
+        target: CombatantId,
+        modifier: i8,
+        advantage: bool,
+        damage: DamageDice,
+    },
+    /// Look for a Hidden combatant
+    Search {
+        seeker: CombatantId,
+        target: CombatantId,
+    },
+    /// GM spends Fear to bring in an adversary
+    SpawnReinforcement { combatant: Combatant, fear_cost: u8 },
+    /// A combatant summons an ally
+    Summon {
+        summoner: CombatantId,
+        combatant: Combatant,
+    },
+    /// Remove a combatant from the encounter
+    RemoveCombatant { target: CombatantId },
+    /// Apply raw damage directly
+    ApplyDamage { target: CombatantId, amount: u16 },
+    /// A character gains Hope
+    GainHope {
+        #[serde(default)]
+        combatant: CombatantId,
+        amount: u8,
+    },
+    /// A character spends Hope
+    SpendHope {
+        #[serde(default)]
+        combatant: CombatantId,
+        amount: u8,
+    },
+    /// Gain GM Fear
+    GainFear { amount: u8 },
+    /// Spend GM Fear
+    SpendFear { amount: u8 },
+    /// Apply a condition to a combatant
+    ApplyCondition {
+        target: CombatantId,
+        condition: Condition,
+    },
+    /// Remove a condition from a combatant
+    RemoveCondition {
+        target: CombatantId,
+        condition: Condition,
+    },
+    /// A character uses their class's Hope feature
+    UseClassFeature {
+        combatant: CombatantId,
+        #[serde(default)]
+        target: Option<CombatantId>,
+    },
+    /// Bring a combatant into the encounter
+    AddCombatant { combatant: Combatant },
+    /// Put a combatant in a zone
+    Place { combatant: CombatantId, zone: i8 },
+    /// Push or pull a target some range bands from a source
+    ForceMove {
+        source: CombatantId,
+        target: CombatantId,
+        direction: MoveDirection,
+        bands: u8,
+        limit: Range,
+    },
+    /// Resolve a card's movement effect
+    ResolveMove {
+        user: CombatantId,
+        target: CombatantId,
+        effect: CardEffect,
+        bands: u8,
+    },
+    /// Start ongoing damage on a combatant
+    ApplyOngoing {
+        target: CombatantId,
+        ongoing: DamageOverTime,
+    },
+    /// End a combatant's ongoing damage by name
+    RemoveOngoing { target: CombatantId, name: String },
+    /// Deal raw damage to a rider's mount
+    DamageMount { rider: CombatantId, amount: u16 },
+    /// A rider gets off their mount
+    Dismount { rider: CombatantId },
+    /// A rider gets back on their mount
+    Remount { rider: CombatantId },
+    /// Declare an attack, holding it as the encounter's pending attack
+    /// while reactions are offered
+    DeclareAttack {
+        attacker: CombatantId,
+        target: CombatantId,
+        attack: Attack,
+        damage: DamageDice,
+    },
+    /// Take an offered reaction to the pending attack
+    React {
+        reactor: CombatantId,
+        reaction: String,
+    },
+    /// Roll the pending attack
+    RollAttack,
+    /// Apply the pending attack's damage
+    LandAttack,
+    /// Resolve a card's attack effect against a target
+    ResolveCardAttack {
+        user: CombatantId,
+        target: CombatantId,
+        effect: CardEffect,
+        attack: Attack,
+    },
+    /// A character plays a domain card
+    PlayCard {
+        player: CombatantId,
+        card: DomainCard,
+    },
+    /// Trade out of one pool and into another
+    Convert {
+        combatant: CombatantId,
+        from: Pool,
+        to: Pool,
+        rate: ConversionRate,
+    },
+    /// Spend one of a character's action tokens
+    SpendActionToken { combatant: CombatantId },
+    /// The GM takes the spotlight, spending the tracked actions
+    GmSpotlight,
+    /// Use an innate ability against some targets
+    UseAbility {
+        user: CombatantId,
+        ability: String,
+        #[serde(default)]
+        targets: Vec<CombatantId>,
+        attack: Attack,
+    },
+    /// Take to the air
+    TakeFlight { combatant: CombatantId },
+    /// Come down to the ground safely
+    Land { combatant: CombatantId },
+    /// Drop from a height, taking falling damage
+    Fall {
+        combatant: CombatantId,
+        height: RangeBand,
+    },
+    /// Settle a contest between two combatants with opposed rolls
+    Contest {
+        first: CombatantId,
+        first_modifier: i8,
+        second: CombatantId,
+        second_modifier: i8,
+    },
+    /// Remove everything a combatant has summoned
+    DismissSummons { summoner: CombatantId },
+}
+
+impl CombatEncounter {
+    /// Apply a command to this encounter using a specific RNG
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::replay::EncounterCommand;
+    /// use daggerheart_engine::combat::simulation::CombatEncounter;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let mut rng = StdRng::seed_from_u64(42);
+    ///
+    /// encounter
+    ///     .apply_command_with_rng(&EncounterCommand::GainFear { amount: 2 }, &mut rng)
+    ///     .unwrap();
+    /// assert_eq!(encounter.fear.current, 2);
+    /// ```
+    pub fn apply_command_with_rng<R: Rng>(
+        &mut self,
+        command: &EncounterCommand,
+        rng: &mut R,
+    ) -> Result<(), EngineError> {
+        match command {
+            EncounterCommand::Start => self.start_with_rng(rng),
+            EncounterCommand::NextTurn => self.next_turn_with_rng(rng),
+            EncounterCommand::EndRound => self.end_round_with_rng(rng),
+            EncounterCommand::AddCountdown { countdown } => self.add_countdown(countdown.clone()),
+            EncounterCommand::AddHazard { hazard } => self.add_hazard(hazard.clone()),
+            EncounterCommand::Attack {
+                attacker,
+                target,
+                modifier,
+                advantage,
+                damage,
+            } => {
+                let mut attack = Attack::new(*modifier);
+                if *advantage {
+                    attack = attack.with_advantage();
+                }
+                self.resolve_attack_with_rng(*attacker, *target, &attack, damage, rng)?;
+            }
+            EncounterCommand::Search { seeker, target } => {
+                self.search_with_rng(*seeker, *target, rng)?;
+            }
+            EncounterCommand::SpawnReinforcement {
+                combatant,
+                fear_cost,
+            } => {
+                self.spawn_reinforcement(combatant.clone(), *fear_cost)?;
+            }
+            EncounterCommand::Summon {
+                summoner,
+                combatant,
+            } => {
+                self.summon(*summoner, combatant.clone())?;
+            }
+            EncounterCommand::RemoveCombatant { target } => {
+                self.remove_combatant(*target)?;
+            }
+            EncounterCommand::ApplyDamage { target, amount } => {
+                self.apply_damage(*target, *amount)?;
+            }
+            EncounterCommand::GainHope { combatant, amount } => {
+                self.gain_hope(*combatant, *amount)?
+            }
+            EncounterCommand::SpendHope { combatant, amount } => {
+                self.spend_hope(*combatant, *amount)?
+            }
+            EncounterCommand::GainFear { amount } => self.gain_fear(*amount),
+            EncounterCommand::SpendFear { amount } => self.spend_fear(*amount)?,
+            EncounterCommand::ApplyCondition { target, condition } => {
+                self.apply_condition(*target, *condition)?
+            }
+            EncounterCommand::RemoveCondition { target, condition } => {
+                self.remove_condition(*target, *condition)?
+            }
+            EncounterCommand::UseClassFeature { combatant, target } => {
+                self.use_class_feature(*combatant, *target)?;
+            }
+            EncounterCommand::AddCombatant { combatant } => {
+                self.add_combatant(combatant.clone());
+            }
+            EncounterCommand::Place { combatant, zone } => self.place(*combatant, *zone)?,
+            EncounterCommand::ForceMove {
+                source,
+                target,
+                direction,
+                bands,
+                limit,
+            } => {
+                self.force_move(*source, *target, *direction, *bands, *limit)?;
+            }
+            EncounterCommand::ResolveMove {
+                user,
+                target,
+                effect,
+                bands,
+            } => {
+                self.resolve_move(*user, *target, effect, *bands)?;
+            }
+            EncounterCommand::ApplyOngoing { target, ongoing } => {
+                self.apply_ongoing(*target, ongoing.clone())?
+            }
+            EncounterCommand::RemoveOngoing { target, name } => {
+                self.remove_ongoing(*target, name)?;
+            }
+            EncounterCommand::DamageMount { rider, amount } => {
+                self.damage_mount(*rider, *amount)?;
+            }
+            EncounterCommand::Dismount { rider } => {
+                self.dismount(*rider)?;
+            }
+            EncounterCommand::Remount { rider } => self.remount(*rider)?,
+            EncounterCommand::DeclareAttack {
+                attacker,
+                target,
+                attack,
+                damage,
+            } => {
+                if self.pending_attack.is_some() {
+                    return Err(EngineError::InvalidCombatAction(String::from(
+                        "Another attack is still resolving",
+                    )));
+                }
+                let pending =
+                    self.declare_attack(*attacker, *target, attack.clone(), damage.clone())?;
+                self.pending_attack = Some(pending);
+            }
+            EncounterCommand::React { reactor, reaction } => {
+                self.with_pending_attack(|encounter, pending| {
+                    encounter.react(pending, *reactor, reaction)
+                })?;
+            }
+            EncounterCommand::RollAttack => {
+                self.with_pending_attack(|encounter, pending| {
+                    encounter.roll_attack_with_rng(pending, rng)
+                })?;
+            }
+            EncounterCommand::LandAttack => {
+                self.with_pending_attack(|encounter, pending| encounter.land_attack(pending))?;
+            }
+            EncounterCommand::ResolveCardAttack {
+                user,
+                target,
+                effect,
+                attack,
+            } => {
+                self.resolve_card_attack_with_rng(*user, *target, effect, attack, rng)?;
+            }
+            EncounterCommand::PlayCard { player, card } => self.play_card(*player, card)?,
+            EncounterCommand::Convert {
+                combatant,
+                from,
+                to,
+                rate,
+            } => {
+                self.convert(*combatant, *from, *to, *rate)?;
+            }
+            EncounterCommand::SpendActionToken { combatant } => {
+                self.spend_action_token(*combatant)?;
+            }
+            EncounterCommand::GmSpotlight => {
+                self.gm_spotlight();
+            }
+            EncounterCommand::UseAbility {
+                user,
+                ability,
+                targets,
+                attack,
+            } => {
+                self.use_ability_with_rng(*user, ability, targets, attack, rng)?;
+            }
+            EncounterCommand::TakeFlight { combatant } => self.take_flight(*combatant)?,
+            EncounterCommand::Land { combatant } => self.land(*combatant)?,
+            EncounterCommand::Fall { combatant, height } => {
+                self.fall_with_rng(*combatant, *height, rng)?;
+            }
+            EncounterCommand::Contest {
+                first,
+                first_modifier,
+                second,
+                second_modifier,
+            } => {
+                self.contest_with_rng(*first, *first_modifier, *second, *second_modifier, rng)?;
+            }
+            EncounterCommand::DismissSummons { summoner } => {
+                self.dismiss_summons(*summoner);
+            }
+        }
+        Ok(())
+    }
+
+    /// Work on the pending attack, clearing it once it's resolved
+    fn with_pending_attack<T>(
+        &mut self,
+        step: impl FnOnce(&mut Self, &mut PendingAttack) -> Result<T, EngineError>,
+    ) -> Result<T, EngineError> {
+        let mut pending = self.pending_attack.take().ok_or_else(|| {
+            EngineError::InvalidCombatAction(String::from("No attack is pending"))
+        })?;
+        let result = step(self, &mut pending);
+        if pending.window != ReactionWindow::Resolved {
+            self.pending_attack = Some(pending);
+        }
+        result
+    }
+}
+
+/// A recorded encounter that can be reproduced exactly
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Replay {
+    /// Encounter state before any commands were applied
+    pub initial: CombatEncounter,
+    /// Seed for the RNG driving every roll
+    pub seed: u64,
+    /// Commands in the order they were applied
+    pub commands: Vec<EncounterCommand>,
+}
+
+impl Replay {
+    #[cfg(feature = "persistence")]
+    /// Save replay to a JSON file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use daggerheart_engine::combat::replay::Replay;
+    /// use daggerheart_engine::combat::simulation::CombatEncounter;
+    ///
+    /// let replay = Replay::new(CombatEncounter::new(5), 42);
+    /// replay.save_to_file("bug_report.json").unwrap();
+    /// ```
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), EngineError> {
+        storage::save_file(self, path)
+    }
+
+    #[cfg(feature = "persistence")]
+    /// Load replay from a JSON file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use daggerheart_engine::combat::replay::Replay;
+    ///
+    /// let replay = Replay::load_from_file("bug_report.json").unwrap();
+    /// let encounter = replay.run().unwrap();
+    /// ```
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, EngineError> {
+        storage::load_file(path)
+    }
+
+    /// Create an empty replay from an initial state and seed
+    pub fn new(initial: CombatEncounter, seed: u64) -> Self {
+        Self {
+            initial,
+            seed,
+            commands: Vec::new(),
+        }
+    }
+
+    /// Append a command to the replay
+    pub fn record(&mut self, command: EncounterCommand) {
+        self.commands.push(command);
+    }
+
+    /// Re-run every command from the initial state
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::replay::{EncounterCommand, Replay};
+    /// use daggerheart_engine::combat::simulation::{CombatEncounter, CombatantBuilder};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Goblin A")
+    ///         .with_hp(4)
+    ///         .with_evasion(10)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Goblin B")
+    ///         .with_hp(4)
+    ///         .with_evasion(10)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// let mut replay = Replay::new(encounter, 1234);
+    /// replay.record(EncounterCommand::Start);
+    /// replay.record(EncounterCommand::NextTurn);
+    ///
+    /// let first = replay.run().unwrap();
+    /// let second = replay.run().unwrap();
+    /// assert_eq!(first.events, second.events);
+    /// ```
+    pub fn run(&self) -> Result<CombatEncounter, EngineError> {
+        let mut encounter = self.initial.clone();
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        for command in &self.commands {
+            encounter.apply_command_with_rng(command, &mut rng)?;
+        }
+        Ok(encounter)
+    }
+
+    /// Re-run the replay and check it reproduces an expected final state
+    ///
+    /// Returns an error naming the first event where the logs diverge.
+    pub fn verify(&self, expected: &CombatEncounter) -> Result<(), EngineError> {
+        let actual = self.run()?;
+
+        if let Some(index) = actual
+            .events
+            .iter()
+            .zip(&expected.events)
+            .position(|(a, e)| a != e)
+        {
+            return Err(EngineError::ReplayDiverged {
+                index,
+                expected: format!("{:?}", expected.events[index]),
+                actual: format!("{:?}", actual.events[index]),
+            });
+        }
+
+        if actual.events.len() != expected.events.len() {
+            return Err(EngineError::ReplayLength {
+                expected: expected.events.len(),
+                actual: actual.events.len(),
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// Plays an encounter live while recording a replay of it
+#[derive(Debug, Clone)]
+pub struct Recorder {
+    replay: Replay,
+    encounter: CombatEncounter,
+    rng: StdRng,
+}
+
+impl Recorder {
+    /// Start recording from an initial state with a seed
+    pub fn new(initial: CombatEncounter, seed: u64) -> Self {
+        Self {
+            encounter: initial.clone(),
+            replay: Replay::new(initial, seed),
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Apply a command to the live encounter and record it
+    ///
+    /// Commands that fail are not recorded, so the replay only contains
+    /// operations that actually happened.
+    pub fn execute(&mut self, command: EncounterCommand) -> Result<(), EngineError> {
+        self.encounter
+            .apply_command_with_rng(&command, &mut self.rng)?;
+        self.replay.record(command);
+        Ok(())
+    }
+
+    /// Current live encounter state
+    pub fn encounter(&self) -> &CombatEncounter {
+        &self.encounter
+    }
+
+    /// The replay recorded so far
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    /// Stop recording, returning the final state and the replay
+    pub fn finish(self) -> (CombatEncounter, Replay) {
+        (self.encounter, self.replay)
+    }
+}
+
+#[cfg(feature = "persistence")]
+impl Persist for Replay {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::reactions::{Reaction, ReactionEffect};
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::test_support::create_test_encounter;
+
+    fn attack(attacker: u32, target: u32) -> EncounterCommand {
+        EncounterCommand::Attack {
+            attacker: CombatantId(attacker),
+            target: CombatantId(target),
+            modifier: 2,
+            advantage: false,
+            damage: DamageDice::d8(1).with_bonus(2),
+        }
+    }
+
+    #[test]
+    fn test_replay_is_deterministic() {
+        let mut replay = Replay::new(create_test_encounter(), 99);
+        replay.record(EncounterCommand::Start);
+        replay.record(attack(0, 1));
+        replay.record(EncounterCommand::NextTurn);
+        replay.record(attack(1, 0));
+
+        let first = replay.run().unwrap();
+        let second = replay.run().unwrap();
+
+        assert_eq!(first.events, second.events);
+        assert_eq!(first.combatants[1].hp, second.combatants[1].hp);
+    }
+
+    #[test]
+    fn test_recorder_matches_replay() {
+        let mut recorder = Recorder::new(create_test_encounter(), 7);
+        recorder.execute(EncounterCommand::Start).unwrap();
+        recorder.execute(attack(0, 1)).unwrap();
+        recorder.execute(EncounterCommand::NextTurn).unwrap();
+
+        let (live, replay) = recorder.finish();
+        assert!(replay.verify(&live).is_ok());
+    }
+
+    #[test]
+    fn test_recorder_skips_failed_commands() {
+        let mut recorder = Recorder::new(create_test_encounter(), 7);
+        assert!(recorder
+            .execute(EncounterCommand::SpendFear { amount: 3 })
+            .is_err());
+
+        assert!(recorder.replay().commands.is_empty());
+    }
+
+    #[test]
+    fn test_verify_detects_divergence() {
+        let mut replay = Replay::new(create_test_encounter(), 7);
+        replay.record(EncounterCommand::GainFear { amount: 1 });
+
+        let mut tampered = replay.run().unwrap();
+        tampered.gain_fear(1);
+
+        assert!(replay.verify(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_different_seeds_can_differ() {
+        let mut initiatives = std::collections::HashSet::new();
+        for seed in 0..20 {
+            let mut replay = Replay::new(create_test_encounter(), seed);
+            replay.record(EncounterCommand::Start);
+            let encounter = replay.run().unwrap();
+            initiatives.insert(encounter.combatants[0].initiative);
+        }
+
+        assert!(initiatives.len() > 1);
+    }
+
+    #[test]
+    fn test_pool_and_condition_commands() {
+        let mut encounter = create_test_encounter();
+        let mut rng = StdRng::seed_from_u64(0);
+
+        encounter
+            .apply_command_with_rng(
+                &EncounterCommand::SpendHope {
+                    combatant: CombatantId(0),
+                    amount: 2,
+                },
+                &mut rng,
+            )
+            .unwrap();
+        encounter
+            .apply_command_with_rng(
+                &EncounterCommand::ApplyCondition {
+                    target: CombatantId(1),
+                    condition: Condition::Restrained,
+                },
+                &mut rng,
+            )
+            .unwrap();
+
+        assert_eq!(encounter.combatants[0].hope().unwrap().current, 0);
+        assert!(encounter.combatants[1].has_condition(Condition::Restrained));
+    }
+
+    #[test]
+    fn test_reaction_window_commands() {
+        let mut encounter = create_test_encounter();
+        let knight = encounter.add_combatant(
+            CombatantBuilder::adversary("Knight")
+                .with_hp(6)
+                .with_evasion(12)
+                .with_reaction(Reaction::new(
+                    "Shield Block",
+                    ReactionWindow::DamageIncoming,
+                    ReactionEffect::Cancel,
+                ))
+                .build()
+                .unwrap(),
+        );
+        let mut recorder = Recorder::new(encounter, 2);
+
+        recorder
+            .execute(EncounterCommand::DeclareAttack {
+                attacker: CombatantId(1),
+                target: knight,
+                attack: Attack::new(50),
+                damage: DamageDice::d8(2),
+            })
+            .unwrap();
+        assert!(recorder.execute(EncounterCommand::LandAttack).is_ok());
+        recorder.execute(EncounterCommand::RollAttack).unwrap();
+        let pending = recorder.encounter().pending_attack.as_ref().unwrap();
+        assert_eq!(pending.window, ReactionWindow::DamageIncoming);
+        recorder
+            .execute(EncounterCommand::React {
+                reactor: knight,
+                reaction: String::from("Shield Block"),
+            })
+            .unwrap();
+        recorder.execute(EncounterCommand::LandAttack).unwrap();
+
+        let (live, replay) = recorder.finish();
+        assert!(live.pending_attack.is_none());
+        assert_eq!(live.get(knight).unwrap().hp.current, 6);
+        assert!(replay.verify(&live).is_ok());
+    }
+
+    #[test]
+    fn test_pending_attack_commands_need_an_attack() {
+        let mut recorder = Recorder::new(create_test_encounter(), 2);
+        assert!(recorder.execute(EncounterCommand::RollAttack).is_err());
+
+        let declare = EncounterCommand::DeclareAttack {
+            attacker: CombatantId(0),
+            target: CombatantId(1),
+            attack: Attack::new(0),
+            damage: DamageDice::d8(1),
+        };
+        recorder.execute(declare.clone()).unwrap();
+        assert!(recorder.execute(declare).is_err());
+    }
+
+    #[test]
+    fn test_movement_and_flight_commands() {
+        let mut recorder = Recorder::new(create_test_encounter(), 3);
+        recorder
+            .execute(EncounterCommand::Place {
+                combatant: CombatantId(1),
+                zone: 1,
+            })
+            .unwrap();
+        recorder
+            .execute(EncounterCommand::ForceMove {
+                source: CombatantId(0),
+                target: CombatantId(1),
+                direction: MoveDirection::Away,
+                bands: 1,
+                limit: Range::Far,
+            })
+            .unwrap();
+        recorder
+            .execute(EncounterCommand::Fall {
+                combatant: CombatantId(0),
+                height: RangeBand::Close,
+            })
+            .unwrap();
+
+        let (live, replay) = recorder.finish();
+        assert_eq!(live.combatants[1].zone, 2);
+        assert!(live.combatants[0].hp.current < live.combatants[0].hp.maximum);
+        assert!(replay.verify(&live).is_ok());
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_replay_serialization() {
+        let mut replay = Replay::new(create_test_encounter(), 5);
+        replay.record(EncounterCommand::Start);
+        replay.record(attack(0, 1));
+
+        let json = serde_json::to_string(&replay).unwrap();
+        let loaded: Replay = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.commands, replay.commands);
+        assert_eq!(loaded.run().unwrap().events, replay.run().unwrap().events);
+    }
+}
+
+#[cfg(test)]
+mod property_tests {
+    use super::*;
+    use crate::combat::simulation::CombatantBuilder;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn prop_same_seed_same_outcome(seed in any::<u64>(), attacks in 1u32..10) {
+            let mut encounter = CombatEncounter::new(5);
+            encounter.add_combatant(
+                CombatantBuilder::adversary("A")
+                    .with_hp(6)
+                    .with_evasion(10)
+                    .build()
+                    .unwrap(),
+            );
+            encounter.add_combatant(
+                CombatantBuilder::adversary("B")
+                    .with_hp(6)
+                    .with_evasion(10)
+                    .build()
+                    .unwrap(),
+            );
+
+            let mut replay = Replay::new(encounter, seed);
+            replay.record(EncounterCommand::Start);
+            for i in 0..attacks {
+                replay.record(EncounterCommand::Attack {
+                    attacker: CombatantId(i % 2),
+                    target: CombatantId((i + 1) % 2),
+                    modifier: 0,
+                    advantage: i % 3 == 0,
+                    damage: DamageDice::d6(2),
+                });
+            }
+
+            let first = replay.run().unwrap();
+            prop_assert!(replay.verify(&first).is_ok());
+        }
+    }
+}
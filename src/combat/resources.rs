@@ -1,10 +1,12 @@
 //! Combat resources - HP, Stress, Hope, and Fear
 
-use crate::error::EngineError;
+use crate::error::{EngineError, Resource};
 use serde::{Deserialize, Serialize};
+use strum_macros::Display;
 
 /// Hit Points pool
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct HitPoints {
     pub current: u8,
     pub maximum: u8,
@@ -73,6 +75,7 @@ impl HitPoints {
 
 /// Stress tracking
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Stress {
     pub current: u8,
 }
@@ -122,6 +125,22 @@ impl Stress {
     pub fn clear(&mut self) {
         self.current = 0;
     }
+
+    /// Clear some stress
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::Stress;
+    ///
+    /// let mut stress = Stress::new();
+    /// stress.gain(3);
+    /// stress.reduce(2);
+    /// assert_eq!(stress.current, 1);
+    /// ```
+    pub fn reduce(&mut self, amount: u8) {
+        self.current = self.current.saturating_sub(amount);
+    }
 }
 
 impl Default for Stress {
@@ -130,8 +149,18 @@ impl Default for Stress {
     }
 }
 
+/// Most Hope a character can hold
+pub const CHARACTER_HOPE_MAXIMUM: u8 = 6;
+
+/// Hope a new character starts with
+pub const STARTING_HOPE: u8 = 2;
+
 /// Hope resource pool
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Each character has their own, starting at 2 of 6. Tables playing with
+/// a shared party pool use one on the encounter instead.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Hope {
     pub current: u8,
     pub maximum: u8,
@@ -156,6 +185,23 @@ impl Hope {
         }
     }
 
+    /// A new character's Hope: 2 of 6
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::Hope;
+    ///
+    /// let hope = Hope::starting();
+    /// assert_eq!((hope.current, hope.maximum), (2, 6));
+    /// ```
+    pub fn starting() -> Self {
+        Self {
+            current: STARTING_HOPE,
+            maximum: CHARACTER_HOPE_MAXIMUM,
+        }
+    }
+
     /// Gain hope (capped at maximum)
     ///
     /// # Examples
@@ -191,10 +237,11 @@ impl Hope {
             self.current -= amount;
             Ok(())
         } else {
-            Err(EngineError::ResourceExceeded(format!(
-                "Not enough Hope: have {}, need {}",
-                self.current, amount
-            )))
+            Err(EngineError::InsufficientResource {
+                resource: Resource::Hope,
+                have: self.current.into(),
+                need: amount.into(),
+            })
         }
     }
 
@@ -205,14 +252,25 @@ impl Hope {
     }
 }
 
+/// The usual cap on the GM's Fear
+pub const DEFAULT_FEAR_MAXIMUM: u8 = 12;
+
+fn default_fear_maximum() -> u8 {
+    DEFAULT_FEAR_MAXIMUM
+}
+
 /// Fear resource pool (GM resource)
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Fear {
     pub current: u8,
+    /// Most Fear the GM can hold; gains past it are lost
+    #[serde(default = "default_fear_maximum")]
+    pub maximum: u8,
 }
 
 impl Fear {
-    /// Create a new Fear pool (starts at 0)
+    /// Create a new Fear pool (starts at 0, capped at 12)
     ///
     /// # Examples
     ///
@@ -221,12 +279,21 @@ impl Fear {
     ///
     /// let fear = Fear::new();
     /// assert_eq!(fear.current, 0);
+    /// assert_eq!(fear.maximum, 12);
     /// ```
     pub fn new() -> Self {
-        Self { current: 0 }
+        Self::with_maximum(DEFAULT_FEAR_MAXIMUM)
+    }
+
+    /// Create an empty Fear pool with a different cap
+    pub fn with_maximum(maximum: u8) -> Self {
+        Self {
+            current: 0,
+            maximum,
+        }
     }
 
-    /// Gain fear
+    /// Gain fear (capped at maximum)
     ///
     /// # Examples
     ///
@@ -236,9 +303,18 @@ impl Fear {
     /// let mut fear = Fear::new();
     /// fear.gain(3);
     /// assert_eq!(fear.current, 3);
+    ///
+    /// fear.gain(20);
+    /// assert_eq!(fear.current, 12);
     /// ```
     pub fn gain(&mut self, amount: u8) {
-        self.current = self.current.saturating_add(amount);
+        self.current = self.current.saturating_add(amount).min(self.maximum);
+    }
+
+    /// Change the cap, dropping any Fear above it
+    pub fn set_maximum(&mut self, maximum: u8) {
+        self.maximum = maximum;
+        self.current = self.current.min(maximum);
     }
 
     /// Spend fear
@@ -261,10 +337,11 @@ impl Fear {
             self.current -= amount;
             Ok(())
         } else {
-            Err(EngineError::ResourceExceeded(format!(
-                "Not enough Fear: have {}, need {}",
-                self.current, amount
-            )))
+            Err(EngineError::InsufficientResource {
+                resource: Resource::Fear,
+                have: self.current.into(),
+                need: amount.into(),
+            })
         }
     }
 }
@@ -275,6 +352,39 @@ impl Default for Fear {
     }
 }
 
+/// A character pool a feature can trade out of or into
+///
+/// Paying from a pool spends Hope or marks Stress or Hit Points; gaining
+/// into it gains Hope or clears Stress or Hit Points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Pool {
+    Hope,
+    Stress,
+    #[strum(serialize = "Hit Points")]
+    HitPoints,
+}
+
+/// How much of one pool buys how much of another
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ConversionRate {
+    /// Taken from the pool converted out of
+    pub pay: u8,
+    /// Given to the pool converted into, at most
+    pub gain: u8,
+}
+
+impl ConversionRate {
+    /// One for one
+    pub const EVEN: Self = Self { pay: 1, gain: 1 };
+
+    /// Pay `pay` to gain `gain`
+    pub fn new(pay: u8, gain: u8) -> Self {
+        Self { pay, gain }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -365,7 +475,14 @@ mod tests {
     #[test]
     fn test_hope_spend_too_much() {
         let mut hope = Hope::new(5);
-        assert!(hope.spend(10).is_err());
+        assert!(matches!(
+            hope.spend(10),
+            Err(EngineError::InsufficientResource {
+                resource: Resource::Hope,
+                have: 5,
+                need: 10
+            })
+        ));
         assert_eq!(hope.current, 5); // Unchanged
     }
 
@@ -422,7 +539,24 @@ mod tests {
         assert_eq!(fear.current, 3); // Unchanged
     }
 
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_fear_cap() {
+        let mut fear = Fear::with_maximum(4);
+        fear.gain(3);
+        fear.gain(3);
+        assert_eq!(fear.current, 4);
+
+        fear.set_maximum(2);
+        assert_eq!(fear.current, 2);
+
+        // Saves from before the cap load with the default
+        let loaded: Fear = serde_json::from_str(r#"{"current": 5}"#).unwrap();
+        assert_eq!(loaded.maximum, DEFAULT_FEAR_MAXIMUM);
+    }
+
     // Serialization tests
+    #[cfg(feature = "persistence")]
     #[test]
     fn test_hp_serialization() {
         let hp = HitPoints::new(6);
@@ -431,6 +565,7 @@ mod tests {
         assert_eq!(hp, loaded);
     }
 
+    #[cfg(feature = "persistence")]
     #[test]
     fn test_hope_serialization() {
         let hope = Hope::new(5);
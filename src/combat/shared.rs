@@ -0,0 +1,205 @@
+//! Shared definition data - copy-on-write fields for combatants
+//!
+//! A combatant's name, Experiences, gear, features and reactions rarely
+//! change during a fight, but simulations clone encounters thousands of
+//! times. `Shared` keeps such data behind an `Arc`, so cloning a combatant
+//! only copies its combat state. Reading derefs to the data; changing it
+//! copies the data first if another clone still points at it.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+
+/// Data shared between clones until one of them changes it
+///
+/// Serializes as the data itself.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::combat::shared::Shared;
+///
+/// let gear: Shared<Vec<String>> = vec!["Longsword".to_string()].into();
+/// let mut copy = gear.clone();
+/// assert!(Shared::ptr_eq(&gear, &copy));
+///
+/// copy.push("Rope".to_string());
+/// assert!(!Shared::ptr_eq(&gear, &copy));
+/// assert_eq!(gear.len(), 1);
+/// ```
+#[derive(Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Shared<T>(Arc<T>);
+
+impl<T> Shared<T> {
+    /// Share a value
+    pub fn new(value: T) -> Self {
+        Self(Arc::new(value))
+    }
+
+    /// Whether two handles point at the same data
+    pub fn ptr_eq(a: &Self, b: &Self) -> bool {
+        Arc::ptr_eq(&a.0, &b.0)
+    }
+}
+
+impl<T: Clone> Shared<T> {
+    /// The data, copied out of the share if needed
+    pub fn into_inner(self) -> T {
+        Arc::try_unwrap(self.0).unwrap_or_else(|shared| (*shared).clone())
+    }
+}
+
+impl<T> Clone for Shared<T> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+impl<T> Deref for Shared<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Clone> DerefMut for Shared<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        Arc::make_mut(&mut self.0)
+    }
+}
+
+impl<T> From<T> for Shared<T> {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<&str> for Shared<String> {
+    fn from(value: &str) -> Self {
+        Self::new(value.to_string())
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Shared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Shared<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl PartialEq<str> for Shared<String> {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<&str> for Shared<String> {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<String> for Shared<String> {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == *other
+    }
+}
+
+impl<T: PartialEq<U>, U> PartialEq<Vec<U>> for Shared<Vec<T>> {
+    fn eq(&self, other: &Vec<U>) -> bool {
+        *self.0 == *other
+    }
+}
+
+impl<T: PartialEq<U>, U, const N: usize> PartialEq<[U; N]> for Shared<Vec<T>> {
+    fn eq(&self, other: &[U; N]) -> bool {
+        self.0[..] == other[..]
+    }
+}
+
+impl PartialEq<Shared<String>> for String {
+    fn eq(&self, other: &Shared<String>) -> bool {
+        *self == *other.0
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Shared<Vec<T>> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl<T: Serialize> Serialize for Shared<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for Shared<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        T::deserialize(deserializer).map(Self::new)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl<T: schemars::JsonSchema> schemars::JsonSchema for Shared<T> {
+    fn is_referenceable() -> bool {
+        T::is_referenceable()
+    }
+
+    fn schema_name() -> String {
+        T::schema_name()
+    }
+
+    fn schema_id() -> std::borrow::Cow<'static, str> {
+        T::schema_id()
+    }
+
+    fn json_schema(gen: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        T::json_schema(gen)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::simulation::CombatantBuilder;
+
+    #[test]
+    fn test_cloned_combatants_share_until_changed() {
+        let goblin = CombatantBuilder::adversary("Goblin")
+            .with_hp(4)
+            .with_evasion(10)
+            .with_equipment("Spear")
+            .build()
+            .unwrap();
+        let mut copy = goblin.clone();
+        assert!(Shared::ptr_eq(&goblin.name, &copy.name));
+        assert!(Shared::ptr_eq(&goblin.equipment, &copy.equipment));
+
+        copy.equipment.push("Net".to_string());
+        assert!(!Shared::ptr_eq(&goblin.equipment, &copy.equipment));
+        assert_eq!(goblin.equipment, ["Spear"]);
+        assert_eq!(copy.name, "Goblin");
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_serializes_as_the_data() {
+        let gear: Shared<Vec<String>> = vec!["Rope".to_string()].into();
+        let json = serde_json::to_string(&gear).unwrap();
+        assert_eq!(json, r#"["Rope"]"#);
+        let loaded: Shared<Vec<String>> = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, gear);
+    }
+}
@@ -1,52 +1,245 @@
 //! Combat simulation - full combat encounter management
 
-use crate::character::{Ancestry, Attributes, Class};
+use crate::cards::{ActiveEffect, CardEffect, DomainCard, SourceKind, Target};
+use crate::character::progression::tier_for_level;
+use crate::character::{
+    Ancestry, Attributes, Class, ClassHopeFeature, Community, Experience, Subclass,
+};
+#[cfg(feature = "content")]
+use crate::combat::adversary::AdversaryRole;
+use crate::combat::attack::{Attack, AttackResult};
+use crate::combat::conditions::Condition;
+use crate::combat::countdown::Countdown;
+use crate::combat::damage::{DamageOverTime, DamageResult, TickTiming, DAMAGE_THRESHOLD};
+use crate::combat::events::CombatEvent;
+use crate::combat::hazards::Hazard;
+use crate::combat::modifiers::{ModifierStack, RollKind};
+use crate::combat::mounts::Mount;
+use crate::combat::reactions::{PendingAttack, Reaction};
+use crate::combat::shared::Shared;
+use crate::combat::summons::SummonSource;
+use crate::combat::tokens::ActionTracker;
+use crate::combat::triggers::{Trigger, TriggerEffect, TriggeredFeature};
 use crate::combat::{Fear, HitPoints, Hope, Stress};
-use crate::core::dice::DualityRoll;
+use crate::core::dice::{DamageDice, Die, DualityRoll};
+use crate::error::EngineError;
+use crate::hooks::{EngineHooks, Hooks};
+use crate::items::armor::Armor;
+use crate::rules::{DeathMove, RulesConfig, ThresholdRule, TokenRefresh};
+#[cfg(feature = "persistence")]
+use crate::storage::{self, Persist};
+use rand::Rng;
 use serde::{Deserialize, Serialize};
+#[cfg(feature = "persistence")]
+use std::path::Path;
+use std::sync::Arc;
+
+/// Stable identifier for a combatant within an encounter
+///
+/// Ids are handed out by `CombatEncounter::add_combatant` and never reused,
+/// so they stay valid when other combatants are removed or reordered.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct CombatantId(pub u32);
+
+impl std::fmt::Display for CombatantId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
 
-/// A combatant in an encounter
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Combatant {
-    pub name: String,
-    pub level: u8,
+/// What a player character has that an adversary doesn't
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PlayerData {
     pub class: Class,
     pub ancestry: Ancestry,
     pub attributes: Attributes,
+    #[serde(default)]
+    pub subclass: Option<Subclass>,
+    #[serde(default)]
+    pub community: Option<Community>,
+    /// The character's own Hope (unused when the table shares a pool)
+    #[serde(default = "Hope::starting")]
+    pub hope: Hope,
+}
+
+/// Whose Hope a character gains and spends
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum HopeMode {
+    /// Each character has their own Hope, as in the rules
+    #[default]
+    PerCharacter,
+    /// Table variant: the party shares one pool
+    Shared,
+}
+
+impl HopeMode {
+    /// The mode of saves from before per-character Hope, which all shared
+    pub(crate) fn legacy() -> Self {
+        HopeMode::Shared
+    }
+}
+
+/// How an encounter decides who acts first
+///
+/// Daggerheart itself has no initiative; these are the usual ways tables
+/// that want a fixed turn order set one up.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum TurnModel {
+    /// Duality dice plus the character's Agility; adversaries add nothing
+    #[default]
+    Agility,
+    /// A straight d20
+    D20,
+    /// No roll: the players' side acts before adversaries, each in the
+    /// order they joined
+    ByRole,
+    /// The action tracker variant: characters spend action tokens to
+    /// take the spotlight, and the GM acts once for each token spent. The
+    /// turn order is kept as for `ByRole` so rounds still pass.
+    ActionTokens,
+}
+
+impl TurnModel {
+    /// Roll initiative for a combatant
+    pub fn roll_with_rng<R: Rng>(&self, combatant: &Combatant, rng: &mut R) -> u8 {
+        match self {
+            TurnModel::Agility => {
+                let roll = DualityRoll::roll_with_rng(rng);
+                let agility = combatant.attributes().map_or(0, |a| a.agility);
+                (roll.hope as i16 + roll.fear as i16 + agility as i16).clamp(0, u8::MAX as i16)
+                    as u8
+            }
+            TurnModel::D20 => Die::D20.roll_with_rng(rng),
+            TurnModel::ByRole | TurnModel::ActionTokens => u8::from(combatant.is_player()),
+        }
+    }
+}
+
+/// What an adversary has that a player character doesn't
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AdversaryData {
+    /// Fights on the players' side, like a summoned companion
+    #[serde(default)]
+    pub allied: bool,
+    /// The stat block's role, when it came from one
+    #[cfg(feature = "content")]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub role: Option<AdversaryRole>,
+}
+
+impl AdversaryData {
+    /// An adversary on the players' side
+    pub fn ally() -> Self {
+        Self {
+            allied: true,
+            #[cfg(feature = "content")]
+            role: None,
+        }
+    }
+}
+
+/// Whether a combatant is a player character or an adversary
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum CombatantKind {
+    Player(PlayerData),
+    Adversary(AdversaryData),
+}
+
+/// A combatant in an encounter
+///
+/// The name, Experiences, equipment, features, immunities and reactions
+/// are [`Shared`] between clones, so copying a combatant for a simulation
+/// run copies only its combat state.
+///
+/// Saves from before `kind` existed, with `class`, `ancestry`,
+/// `attributes` and `is_player` fields, still load. Enemies lose their
+/// placeholder class and ancestry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(try_from = "StoredCombatant")]
+pub struct Combatant {
+    /// Assigned when the combatant is added to an encounter
+    #[serde(default)]
+    pub id: CombatantId,
+    pub name: Shared<String>,
+    pub level: u8,
+    pub kind: CombatantKind,
     pub hp: HitPoints,
     pub stress: Stress,
     pub evasion: u8,
     pub armor: u8,
     pub initiative: u8,
-    pub is_player: bool,
+    #[serde(default)]
+    pub conditions: Vec<Condition>,
+    /// Set for combatants that joined mid-combat
+    #[serde(default)]
+    pub summon: Option<SummonSource>,
+    #[serde(default)]
+    pub experiences: Shared<Vec<Experience>>,
+    /// Names of carried weapons, armor and gear
+    #[serde(default)]
+    pub equipment: Shared<Vec<String>>,
+    /// Modifiers from cards, conditions and equipment that haven't worn off
+    #[serde(default)]
+    pub modifiers: ModifierStack,
+    /// Passive features the encounter fires during attacks
+    #[serde(default)]
+    pub triggers: Shared<Vec<TriggeredFeature>>,
+    /// Conditions that can't be applied
+    #[serde(default)]
+    pub immunities: Shared<Vec<Condition>>,
+    /// Reactions it can interrupt attacks with
+    #[serde(default)]
+    pub reactions: Shared<Vec<Reaction>>,
+    /// Damage it keeps taking each turn
+    #[serde(default)]
+    pub ongoing: Vec<DamageOverTime>,
+    /// Where it stands on the battlefield, counted in range bands along a
+    /// line; combatants in the same zone are in melee
+    #[serde(default)]
+    pub zone: i8,
+    /// What it rides, if anything
+    #[serde(default)]
+    pub mount: Option<Mount>,
+    /// The armor it has on, whose features apply as it takes damage
+    #[serde(default)]
+    pub worn_armor: Option<Armor>,
+    /// Innate abilities used since the last rest, once per use
+    #[serde(default)]
+    pub abilities_used: Vec<String>,
 }
 
 impl Combatant {
+    #[cfg(feature = "persistence")]
     /// Save combatant to a JSON file
     ///
     /// # Examples
     ///
     /// ```no_run
-    /// use daggerheart_engine::combat::simulation::Combatant;
-    /// use daggerheart_engine::character::{Class, Ancestry, Attributes};
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::simulation::CombatantBuilder;
     ///
-    /// let warrior = Combatant::player(
-    ///     "Grom",
-    ///     1,
-    ///     Class::Warrior,
-    ///     Ancestry::Orc,
-    ///     Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap(),
-    /// );
+    /// let warrior = CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///     .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///     .build()
+    ///     .unwrap();
     ///
     /// warrior.save_to_file("grom.json").unwrap();
     /// ```
-    pub fn save_to_file(&self, path: &str) -> Result<(), std::io::Error> {
-        let json = serde_json::to_string_pretty(self)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-        std::fs::write(path, json)?;
-        Ok(())
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), EngineError> {
+        storage::save_file(self, path)
     }
 
+    #[cfg(feature = "persistence")]
     /// Load combatant from a JSON file
     ///
     /// # Examples
@@ -57,14 +250,15 @@ impl Combatant {
     /// let warrior = Combatant::load_from_file("grom.json").unwrap();
     /// println!("Loaded: {}", warrior.name);
     /// ```
-    pub fn load_from_file(path: &str) -> Result<Self, std::io::Error> {
-        let json = std::fs::read_to_string(path)?;
-        serde_json::from_str(&json)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, EngineError> {
+        storage::load_file(path)
     }
 
     /// Create a new player character combatant
     ///
+    /// Prefer `CombatantBuilder::player`, which validates the level and
+    /// takes the optional fields by name.
+    ///
     /// # Examples
     ///
     /// ```
@@ -80,8 +274,9 @@ impl Combatant {
     /// );
     ///
     /// assert_eq!(warrior.name, "Grom");
-    /// assert!(warrior.is_player);
+    /// assert!(warrior.is_player());
     /// ```
+    #[deprecated(note = "use `CombatantBuilder::player` instead")]
     pub fn player(
         name: impl Into<String>,
         level: u8,
@@ -89,29 +284,15 @@ impl Combatant {
         ancestry: Ancestry,
         attributes: Attributes,
     ) -> Self {
-        let base_hp = class.starting_hp();
-        let hp_total = (base_hp as i16 + ancestry.hp_modifier() as i16).max(1) as u8;
-
-        let base_evasion = class.starting_evasion();
-        let evasion_total = (base_evasion as i16 + ancestry.evasion_modifier() as i16).max(1) as u8;
-
-        Self {
-            name: name.into(),
-            level,
-            class,
-            ancestry,
-            attributes,
-            hp: HitPoints::new(hp_total),
-            stress: Stress::new(),
-            evasion: evasion_total,
-            armor: 0, // Can be set later with equipment
-            initiative: 0,
-            is_player: true,
-        }
+        CombatantBuilder::player(name, class, ancestry)
+            .with_attributes(attributes)
+            .assemble(level)
     }
 
     /// Create a new enemy combatant
     ///
+    /// Prefer `CombatantBuilder::adversary`, which names each stat.
+    ///
     /// # Examples
     ///
     /// ```
@@ -120,22 +301,81 @@ impl Combatant {
     /// let goblin = Combatant::enemy("Goblin Scout", 1, 4, 13, 1);
     ///
     /// assert_eq!(goblin.name, "Goblin Scout");
-    /// assert!(!goblin.is_player);
+    /// assert!(!goblin.is_player());
     /// ```
+    #[deprecated(note = "use `CombatantBuilder::adversary` instead")]
     pub fn enemy(name: impl Into<String>, level: u8, hp: u8, evasion: u8, armor: u8) -> Self {
-        // For enemies, use valid placeholder stats
-        Self {
-            name: name.into(),
-            level,
-            class: Class::Rogue, // Placeholder - enemies don't need real classes
-            ancestry: Ancestry::Goblin, // Placeholder
-            attributes: Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap(),
-            hp: HitPoints::new(hp),
-            stress: Stress::new(),
-            evasion,
-            armor,
-            initiative: 0,
-            is_player: false,
+        CombatantBuilder::adversary(name)
+            .with_hp(hp)
+            .with_evasion(evasion)
+            .with_armor(armor)
+            .assemble(level)
+    }
+
+    /// Check if this combatant fights on the players' side
+    ///
+    /// True for player characters and allied adversaries such as summons.
+    pub fn is_player(&self) -> bool {
+        match self.kind {
+            CombatantKind::Player(_) => true,
+            CombatantKind::Adversary(data) => data.allied,
+        }
+    }
+
+    /// Class, ancestry and attributes, if this is a player character
+    pub fn player_data(&self) -> Option<&PlayerData> {
+        match &self.kind {
+            CombatantKind::Player(data) => Some(data),
+            CombatantKind::Adversary(_) => None,
+        }
+    }
+
+    /// Class, if this is a player character
+    pub fn class(&self) -> Option<Class> {
+        self.player_data().map(|data| data.class)
+    }
+
+    /// Class, failing with `MissingStat` for adversaries
+    pub(crate) fn require_class(&self) -> Result<Class, EngineError> {
+        self.class().ok_or_else(|| EngineError::MissingStat {
+            combatant: self.name.to_string(),
+            stat: "class",
+        })
+    }
+
+    /// Ancestry, if this is a player character
+    pub fn ancestry(&self) -> Option<Ancestry> {
+        self.player_data().map(|data| data.ancestry)
+    }
+
+    /// Attributes, if this is a player character
+    pub fn attributes(&self) -> Option<&Attributes> {
+        self.player_data().map(|data| &data.attributes)
+    }
+
+    /// Subclass, if this is a player character who has chosen one
+    pub fn subclass(&self) -> Option<Subclass> {
+        self.player_data().and_then(|data| data.subclass)
+    }
+
+    /// Community, if this is a player character who has chosen one
+    pub fn community(&self) -> Option<Community> {
+        self.player_data().and_then(|data| data.community)
+    }
+
+    /// The character's own Hope, if this is a player character
+    pub fn hope(&self) -> Option<&Hope> {
+        self.player_data().map(|data| &data.hope)
+    }
+
+    /// The character's own Hope for mutation, failing for adversaries
+    pub fn hope_mut(&mut self) -> Result<&mut Hope, EngineError> {
+        match &mut self.kind {
+            CombatantKind::Player(data) => Ok(&mut data.hope),
+            CombatantKind::Adversary(_) => Err(EngineError::MissingStat {
+                combatant: self.name.to_string(),
+                stat: "Hope",
+            }),
         }
     }
 
@@ -145,11 +385,15 @@ impl Combatant {
         self
     }
 
-    /// Roll initiative
+    /// Roll initiative with the duality dice plus Agility
     pub fn roll_initiative(&mut self) {
-        let roll = DualityRoll::roll();
-        let result = roll.with_modifier(0); // Could add Agility modifier
-        self.initiative = (result.total % 20) as u8; // Cap at 20
+        let mut rng = rand::thread_rng();
+        self.roll_initiative_with_rng(&mut rng);
+    }
+
+    /// Roll initiative with a specific RNG (for testing/seeding)
+    pub fn roll_initiative_with_rng<R: Rng>(&mut self, rng: &mut R) {
+        self.initiative = TurnModel::Agility.roll_with_rng(self, rng);
     }
 
     /// Check if combatant is alive
@@ -166,20 +410,737 @@ impl Combatant {
     pub fn gain_stress(&mut self, amount: u8) {
         self.stress.gain(amount);
     }
+
+    /// Check if combatant has a condition
+    pub fn has_condition(&self, condition: Condition) -> bool {
+        self.conditions.contains(&condition)
+    }
+
+    /// Check if combatant is immune to a condition
+    pub fn is_immune(&self, condition: Condition) -> bool {
+        self.immunities.contains(&condition)
+    }
+
+    /// Apply a condition (returns false if already applied or immune)
+    pub fn add_condition(&mut self, condition: Condition) -> bool {
+        if self.has_condition(condition) || self.is_immune(condition) {
+            return false;
+        }
+        self.conditions.push(condition);
+        true
+    }
+
+    /// Start taking ongoing damage, replacing any of the same name
+    pub fn add_ongoing(&mut self, ongoing: DamageOverTime) {
+        self.remove_ongoing(&ongoing.name);
+        self.ongoing.push(ongoing);
+    }
+
+    /// Stop taking ongoing damage by name, ignoring case (returns false if
+    /// it wasn't being taken)
+    pub fn remove_ongoing(&mut self, name: &str) -> bool {
+        let before = self.ongoing.len();
+        self.ongoing
+            .retain(|ongoing| !ongoing.name.eq_ignore_ascii_case(name));
+        self.ongoing.len() != before
+    }
+
+    /// Remove a condition (returns false if it wasn't applied)
+    pub fn remove_condition(&mut self, condition: Condition) -> bool {
+        let before = self.conditions.len();
+        self.conditions.retain(|&c| c != condition);
+        self.conditions.len() != before
+    }
+
+    /// Put on a suit of armor, taking off and returning any already worn
+    ///
+    /// Its score is added to the combatant's armor and its features'
+    /// Evasion and roll modifiers apply until it comes off.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    /// use daggerheart_engine::items::Armor;
+    ///
+    /// let mut knight = CombatantBuilder::adversary("Knight")
+    ///     .with_hp(6)
+    ///     .with_evasion(12)
+    ///     .build()
+    ///     .unwrap();
+    /// knight.wear(Armor::named("Full Plate Armor").unwrap());
+    /// assert_eq!((knight.armor, knight.evasion), (4, 10));
+    ///
+    /// knight.take_off_armor();
+    /// assert_eq!((knight.armor, knight.evasion), (0, 12));
+    /// ```
+    pub fn wear(&mut self, armor: Armor) -> Option<Armor> {
+        let previous = self.take_off_armor();
+        self.armor = self.armor.saturating_add(armor.score);
+        self.evasion = self.evasion.saturating_add_signed(armor.evasion_modifier());
+        for (bonus, applies_to) in armor.roll_bonuses() {
+            self.modifiers.add(ActiveEffect::permanent(
+                armor.name.clone(),
+                SourceKind::Equipment,
+                bonus,
+                applies_to,
+            ));
+        }
+        if !self.equipment.contains(&armor.name) {
+            self.equipment.push(armor.name.clone());
+        }
+        self.worn_armor = Some(armor);
+        previous
+    }
+
+    /// Take off worn armor, undoing what it gave
+    pub fn take_off_armor(&mut self) -> Option<Armor> {
+        let armor = self.worn_armor.take()?;
+        self.armor = self.armor.saturating_sub(armor.score);
+        self.evasion = self
+            .evasion
+            .saturating_add_signed(armor.evasion_modifier().saturating_neg());
+        self.modifiers.remove_source(&armor.name);
+        self.equipment.retain(|item| *item != armor.name);
+        Some(armor)
+    }
+
+    /// What `raw_damage` does to this combatant, through its armor score
+    /// and any worn armor's features
+    pub fn measure_damage(&self, raw_damage: u16) -> DamageResult {
+        self.measure_damage_with(raw_damage, ThresholdRule::Flat)
+    }
+
+    /// What `raw_damage` does to this combatant, with the damage
+    /// threshold placed by a house rule
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    /// use daggerheart_engine::rules::ThresholdRule;
+    ///
+    /// let veteran = CombatantBuilder::adversary("Veteran")
+    ///     .with_level(5)
+    ///     .with_hp(6)
+    ///     .with_evasion(12)
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(veteran.measure_damage_with(7, ThresholdRule::Flat).hp_lost, 1);
+    /// assert_eq!(veteran.measure_damage_with(7, ThresholdRule::PlusLevel).hp_lost, 0);
+    /// ```
+    pub fn measure_damage_with(&self, raw_damage: u16, thresholds: ThresholdRule) -> DamageResult {
+        let shift = thresholds.shift(self.level);
+        match &self.worn_armor {
+            Some(armor) => armor.damage_with_threshold(
+                raw_damage,
+                self.armor,
+                armor.threshold().saturating_add(shift),
+            ),
+            None => DamageResult::calculate_with_threshold(
+                raw_damage,
+                self.armor,
+                DAMAGE_THRESHOLD.saturating_add(shift),
+            ),
+        }
+    }
+
+    /// Apply an effect from `source` to this combatant
+    ///
+    /// Healing, clearing Stress, conversions and flight change the
+    /// combatant, and modifiers and ongoing damage stay until they wear
+    /// off. Other effects are left for the GM to track, and return false,
+    /// as does a conversion the combatant can't make.
+    pub fn apply_effect(&mut self, source: &str, effect: &CardEffect) -> bool {
+        match effect {
+            CardEffect::Heal { amount: 0, .. } => self.hp.heal(self.hp.maximum),
+            CardEffect::Heal { amount, .. } => self.hp.heal(*amount),
+            CardEffect::ClearStress { .. } => self.stress.clear(),
+            CardEffect::Convert { from, to, rate } => {
+                return self.convert(*from, *to, *rate).is_ok()
+            }
+            CardEffect::DamageOverTime { effect, .. } => self.add_ongoing(effect.clone()),
+            CardEffect::Flight { .. } => return self.add_condition(Condition::Flying),
+            _ => match effect.active_effect(source) {
+                Some(active) => self.modifiers.add(active),
+                None => return false,
+            },
+        }
+        true
+    }
+
+    /// The total bonus active effects give to `what`, e.g. `Evasion`
+    pub fn effect_bonus(&self, what: &str) -> i8 {
+        self.modifiers.bonus_for(what)
+    }
+
+    /// The total bonus this combatant's modifiers give to a kind of roll
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::cards::effects::{ActiveEffect, SourceKind};
+    /// use daggerheart_engine::combat::{CombatantBuilder, RollKind};
+    ///
+    /// let knight = CombatantBuilder::adversary("Knight")
+    ///     .with_hp(6)
+    ///     .with_evasion(12)
+    ///     .with_modifier(ActiveEffect::permanent("Runed Blade", SourceKind::Equipment, 1, "attacks"))
+    ///     .build()
+    ///     .unwrap();
+    /// assert_eq!(knight.total_bonus_for(RollKind::Attack), 1);
+    /// ```
+    pub fn total_bonus_for(&self, roll: RollKind) -> i8 {
+        self.modifiers.total_bonus_for(roll)
+    }
+
+    /// How many damage dice this combatant's weapon attacks roll
+    ///
+    /// Proficiency starts at 1 and rises with each tier.
+    pub fn proficiency(&self) -> u8 {
+        tier_for_level(self.level)
+    }
+
+    /// Use this character's class Hope feature, paying from their own Hope
+    ///
+    /// Effects aimed at the character themselves are applied; use
+    /// [`CombatEncounter::use_class_feature`] to target someone else and
+    /// log the use.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    ///
+    /// let mut grom = CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///     .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// // No Mercy costs 3 Hope, and characters start with 2
+    /// assert!(grom.use_class_feature().is_err());
+    /// grom.hope_mut().unwrap().gain(1);
+    /// assert_eq!(grom.use_class_feature().unwrap().name, "No Mercy");
+    /// assert_eq!(grom.hope().unwrap().current, 0);
+    /// assert_eq!(grom.effect_bonus("attack rolls"), 1);
+    /// ```
+    pub fn use_class_feature(&mut self) -> Result<ClassHopeFeature, EngineError> {
+        let feature = self.class_feature()?;
+        self.hope_mut()?.spend(feature.hope_cost)?;
+        if feature.effect.target() == Some(Target::SelfOnly) {
+            self.apply_effect(feature.name, &feature.effect);
+        }
+        Ok(feature)
+    }
+
+    /// The class Hope feature this combatant can use right now
+    fn class_feature(&self) -> Result<ClassHopeFeature, EngineError> {
+        let class = self.require_class()?;
+        if !self.is_alive() {
+            return Err(EngineError::CombatantDefeated {
+                name: self.name.to_string(),
+            });
+        }
+        Ok(class.hope_feature())
+    }
+}
+
+#[cfg(feature = "persistence")]
+impl Persist for Combatant {}
+
+/// A combatant as saved, before or after `kind` was introduced
+#[derive(Deserialize)]
+struct StoredCombatant {
+    #[serde(default)]
+    id: CombatantId,
+    name: String,
+    level: u8,
+    kind: Option<CombatantKind>,
+    hp: HitPoints,
+    stress: Stress,
+    evasion: u8,
+    armor: u8,
+    initiative: u8,
+    #[serde(default)]
+    conditions: Vec<Condition>,
+    #[serde(default)]
+    summon: Option<SummonSource>,
+    #[serde(default)]
+    experiences: Vec<Experience>,
+    #[serde(default)]
+    equipment: Vec<String>,
+    #[serde(default)]
+    modifiers: Option<ModifierStack>,
+    #[serde(default)]
+    triggers: Vec<TriggeredFeature>,
+    #[serde(default)]
+    immunities: Vec<Condition>,
+    #[serde(default)]
+    reactions: Vec<Reaction>,
+    #[serde(default)]
+    ongoing: Vec<DamageOverTime>,
+    #[serde(default)]
+    zone: i8,
+    #[serde(default)]
+    mount: Option<Mount>,
+    #[serde(default)]
+    worn_armor: Option<Armor>,
+    #[serde(default)]
+    abilities_used: Vec<String>,
+    // Legacy fields, replaced by `kind`
+    class: Option<Class>,
+    ancestry: Option<Ancestry>,
+    attributes: Option<Attributes>,
+    is_player: Option<bool>,
+    // Replaced by `modifiers`
+    #[serde(default)]
+    effects: Vec<ActiveEffect>,
+}
+
+impl StoredCombatant {
+    fn legacy_kind(&self) -> Option<CombatantKind> {
+        match self.is_player? {
+            // Summons copied their summoner's side, but were never characters
+            true if self.summon.is_some() => Some(CombatantKind::Adversary(AdversaryData::ally())),
+            true => Some(CombatantKind::Player(PlayerData {
+                class: self.class?,
+                ancestry: self.ancestry?,
+                attributes: self.attributes?,
+                subclass: None,
+                community: None,
+                hope: Hope::starting(),
+            })),
+            false => Some(CombatantKind::Adversary(AdversaryData::default())),
+        }
+    }
+}
+
+impl TryFrom<StoredCombatant> for Combatant {
+    type Error = EngineError;
+
+    fn try_from(stored: StoredCombatant) -> Result<Self, Self::Error> {
+        let kind = match stored.kind {
+            Some(kind) => kind,
+            None => stored
+                .legacy_kind()
+                .ok_or_else(|| EngineError::MissingStat {
+                    combatant: stored.name.clone(),
+                    stat: "kind",
+                })?,
+        };
+        Ok(Self {
+            id: stored.id,
+            name: stored.name.into(),
+            level: stored.level,
+            kind,
+            hp: stored.hp,
+            stress: stored.stress,
+            evasion: stored.evasion,
+            armor: stored.armor,
+            initiative: stored.initiative,
+            conditions: stored.conditions,
+            summon: stored.summon,
+            experiences: stored.experiences.into(),
+            equipment: stored.equipment.into(),
+            modifiers: stored.modifiers.unwrap_or(ModifierStack {
+                effects: stored.effects,
+                ..ModifierStack::default()
+            }),
+            triggers: stored.triggers.into(),
+            immunities: stored.immunities.into(),
+            reactions: stored.reactions.into(),
+            ongoing: stored.ongoing,
+            zone: stored.zone,
+            mount: stored.mount,
+            worn_armor: stored.worn_armor,
+            abilities_used: stored.abilities_used,
+        })
+    }
+}
+
+/// What kind of combatant a builder makes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BuilderKind {
+    Player { class: Class, ancestry: Ancestry },
+    Adversary,
+}
+
+/// Builds a validated `Combatant`
+///
+/// Players take their HP and Evasion from class and ancestry, and need
+/// attributes and a level from 1 to 10. Adversaries have no class, so
+/// their HP and Evasion must be given. Everything else is optional.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::{Ancestry, Attributes, Class, Experience};
+/// use daggerheart_engine::combat::{CombatantBuilder, Condition};
+///
+/// let warrior = CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+///     .with_level(3)
+///     .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+///     .with_armor(3)
+///     .with_experience(Experience::new("Blacksmith", 2))
+///     .with_equipment("Longsword")
+///     .build()
+///     .unwrap();
+/// assert_eq!(warrior.level, 3);
+/// assert_eq!(warrior.equipment, vec!["Longsword"]);
+///
+/// let goblin = CombatantBuilder::adversary("Goblin Scout")
+///     .with_hp(4)
+///     .with_evasion(13)
+///     .with_condition(Condition::Hidden)
+///     .build()
+///     .unwrap();
+/// assert!(!goblin.is_player());
+///
+/// // Adversaries have no class to derive HP from
+/// assert!(CombatantBuilder::adversary("Ogre")
+///     .with_evasion(10)
+///     .build()
+///     .is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct CombatantBuilder {
+    name: String,
+    kind: BuilderKind,
+    level: u8,
+    attributes: Option<Attributes>,
+    subclass: Option<Subclass>,
+    community: Option<Community>,
+    hp: Option<u8>,
+    evasion: Option<u8>,
+    armor: u8,
+    conditions: Vec<Condition>,
+    experiences: Vec<Experience>,
+    equipment: Vec<String>,
+    triggers: Vec<TriggeredFeature>,
+    immunities: Vec<Condition>,
+    reactions: Vec<Reaction>,
+    modifiers: ModifierStack,
+    zone: i8,
+    mount: Option<Mount>,
+    worn_armor: Option<Armor>,
+    #[cfg(feature = "content")]
+    role: Option<AdversaryRole>,
+}
+
+impl CombatantBuilder {
+    fn new(name: impl Into<String>, kind: BuilderKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            level: 1,
+            attributes: None,
+            subclass: None,
+            community: None,
+            hp: None,
+            evasion: None,
+            armor: 0,
+            conditions: Vec::new(),
+            experiences: Vec::new(),
+            equipment: Vec::new(),
+            triggers: Vec::new(),
+            immunities: Vec::new(),
+            reactions: Vec::new(),
+            modifiers: ModifierStack::default(),
+            zone: 0,
+            mount: None,
+            worn_armor: None,
+            #[cfg(feature = "content")]
+            role: None,
+        }
+    }
+
+    /// Start building a player character
+    pub fn player(name: impl Into<String>, class: Class, ancestry: Ancestry) -> Self {
+        Self::new(name, BuilderKind::Player { class, ancestry })
+    }
+
+    /// Start building an adversary
+    pub fn adversary(name: impl Into<String>) -> Self {
+        Self::new(name, BuilderKind::Adversary)
+    }
+
+    /// Set the level (defaults to 1)
+    pub fn with_level(mut self, level: u8) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Set the attributes (players only)
+    pub fn with_attributes(mut self, attributes: Attributes) -> Self {
+        self.attributes = Some(attributes);
+        self
+    }
+
+    /// Set the subclass (players only, must belong to their class)
+    pub fn with_subclass(mut self, subclass: Subclass) -> Self {
+        self.subclass = Some(subclass);
+        self
+    }
+
+    /// Set the community (players only)
+    pub fn with_community(mut self, community: Community) -> Self {
+        self.community = Some(community);
+        self
+    }
+
+    /// Set maximum HP, overriding the class value for players
+    pub fn with_hp(mut self, hp: u8) -> Self {
+        self.hp = Some(hp);
+        self
+    }
+
+    /// Set Evasion, overriding the class value for players
+    pub fn with_evasion(mut self, evasion: u8) -> Self {
+        self.evasion = Some(evasion);
+        self
+    }
+
+    /// Set the armor score (defaults to 0)
+    pub fn with_armor(mut self, armor: u8) -> Self {
+        self.armor = armor;
+        self
+    }
+
+    /// Start with a condition applied
+    pub fn with_condition(mut self, condition: Condition) -> Self {
+        if !self.conditions.contains(&condition) {
+            self.conditions.push(condition);
+        }
+        self
+    }
+
+    /// Add an experience
+    pub fn with_experience(mut self, experience: Experience) -> Self {
+        self.experiences.push(experience);
+        self
+    }
+
+    /// Add a piece of equipment
+    pub fn with_equipment(mut self, item: impl Into<String>) -> Self {
+        self.equipment.push(item.into());
+        self
+    }
+
+    /// Add a passive feature that fires during attacks
+    pub fn with_trigger(mut self, feature: TriggeredFeature) -> Self {
+        self.triggers.push(feature);
+        self
+    }
+
+    /// Make the combatant immune to a condition
+    pub fn with_immunity(mut self, condition: Condition) -> Self {
+        if !self.immunities.contains(&condition) {
+            self.immunities.push(condition);
+        }
+        self.conditions.retain(|&c| c != condition);
+        self
+    }
+
+    /// Add a lasting modifier, such as from equipment
+    pub fn with_modifier(mut self, modifier: ActiveEffect) -> Self {
+        self.modifiers.add(modifier);
+        self
+    }
+
+    /// Set which zone it starts in
+    pub fn with_zone(mut self, zone: i8) -> Self {
+        self.zone = zone;
+        self
+    }
+
+    /// Start it riding a mount or vehicle
+    pub fn with_mount(mut self, mount: Mount) -> Self {
+        self.mount = Some(mount);
+        self
+    }
+
+    /// Start it wearing armor, on top of any armor score set with
+    /// [`CombatantBuilder::with_armor`]
+    pub fn with_worn_armor(mut self, armor: Armor) -> Self {
+        self.worn_armor = Some(armor);
+        self
+    }
+
+    /// Add a reaction it can interrupt attacks with
+    pub fn with_reaction(mut self, reaction: Reaction) -> Self {
+        self.reactions.push(reaction);
+        self
+    }
+
+    /// Record the adversary's role (adversaries only)
+    #[cfg(feature = "content")]
+    pub fn with_role(mut self, role: AdversaryRole) -> Self {
+        self.role = Some(role);
+        self
+    }
+
+    /// Validate and build the combatant
+    ///
+    /// Fails with `InvalidLevel` for a player outside levels 1-10,
+    /// `WrongSubclass` for another class's subclass, or `MissingStat` when
+    /// a required stat wasn't given.
+    pub fn build(self) -> Result<Combatant, EngineError> {
+        match self.kind {
+            BuilderKind::Player { class, .. } => {
+                if !(1..=10).contains(&self.level) {
+                    return Err(EngineError::InvalidLevel { level: self.level });
+                }
+                if self.attributes.is_none() {
+                    return Err(self.missing("attributes"));
+                }
+                if let Some(subclass) = self.subclass.filter(|s| s.class() != class) {
+                    return Err(EngineError::WrongSubclass { subclass, class });
+                }
+            }
+            BuilderKind::Adversary => {
+                if self.hp.is_none() {
+                    return Err(self.missing("HP"));
+                }
+                if self.evasion.is_none() {
+                    return Err(self.missing("Evasion"));
+                }
+            }
+        }
+        let level = self.level;
+        Ok(self.assemble(level))
+    }
+
+    fn missing(&self, stat: &'static str) -> EngineError {
+        EngineError::MissingStat {
+            combatant: self.name.clone(),
+            stat,
+        }
+    }
+
+    /// Build without checking the level, once the required stats are set
+    fn assemble(mut self, level: u8) -> Combatant {
+        // Ancestry features the engine runs on its own
+        if let BuilderKind::Player { ancestry, .. } = self.kind {
+            for feature in ancestry.features() {
+                self.triggers.extend(feature.trigger());
+                if let Some(modifier) = feature.modifier() {
+                    self.modifiers.add(modifier);
+                }
+            }
+        }
+        let (kind, hp, evasion) = match self.kind {
+            BuilderKind::Player { class, ancestry } => {
+                let hp = self.hp.unwrap_or_else(|| {
+                    (class.starting_hp() as i16 + ancestry.hp_modifier() as i16).max(1) as u8
+                });
+                let evasion = self.evasion.unwrap_or_else(|| {
+                    (class.starting_evasion() as i16 + ancestry.evasion_modifier() as i16).max(1)
+                        as u8
+                });
+                let player = PlayerData {
+                    class,
+                    ancestry,
+                    attributes: self.attributes.expect("players are built with attributes"),
+                    subclass: self.subclass,
+                    community: self.community,
+                    hope: Hope::starting(),
+                };
+                (CombatantKind::Player(player), hp, evasion)
+            }
+            BuilderKind::Adversary => (
+                CombatantKind::Adversary(AdversaryData {
+                    allied: false,
+                    #[cfg(feature = "content")]
+                    role: self.role,
+                }),
+                self.hp.expect("adversaries are built with HP"),
+                self.evasion.expect("adversaries are built with Evasion"),
+            ),
+        };
+
+        let mut combatant = Combatant {
+            id: CombatantId::default(),
+            name: self.name.into(),
+            level,
+            kind,
+            hp: HitPoints::new(hp),
+            stress: Stress::new(),
+            evasion,
+            armor: self.armor,
+            initiative: 0,
+            conditions: self.conditions,
+            summon: None,
+            experiences: self.experiences.into(),
+            equipment: self.equipment.into(),
+            modifiers: self.modifiers,
+            triggers: self.triggers.into(),
+            immunities: self.immunities.into(),
+            reactions: self.reactions.into(),
+            ongoing: Vec::new(),
+            zone: self.zone,
+            mount: self.mount,
+            worn_armor: None,
+            abilities_used: Vec::new(),
+        };
+        if let Some(armor) = self.worn_armor {
+            combatant.wear(armor);
+        }
+        combatant
+    }
 }
 
 /// Combat encounter state
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct CombatEncounter {
     pub combatants: Vec<Combatant>,
     pub round: u32,
-    pub turn_order: Vec<usize>,
+    pub turn_order: Vec<CombatantId>,
     pub current_turn: usize,
+    /// The party's shared pool, used when `hope_mode` is `Shared`
     pub hope: Hope,
+    /// Saves from before per-character Hope load as `Shared`
+    #[serde(default = "HopeMode::legacy")]
+    pub hope_mode: HopeMode,
+    /// How `start` orders turns
+    #[serde(default)]
+    pub turn_model: TurnModel,
+    /// Token pools, under `TurnModel::ActionTokens`
+    #[serde(default)]
+    pub action_tokens: ActionTracker,
     pub fear: Fear,
+    /// Append-only log of everything that happened
+    #[serde(default)]
+    pub events: Vec<CombatEvent>,
+    /// Countdowns ticked at the start of each round
+    #[serde(default)]
+    pub countdowns: Vec<Countdown>,
+    /// Environment hazards that strike as turns advance
+    #[serde(default)]
+    pub hazards: Vec<Hazard>,
+    /// An attack declared by `EncounterCommand::DeclareAttack`, waiting
+    /// for reactions before it's rolled and landed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pending_attack: Option<PendingAttack>,
+    /// Optional rule: leaving an opponent's melee range fires their
+    /// opportunity features
+    #[serde(default)]
+    pub opportunity_attacks: bool,
+    /// House rules for crits, damage thresholds and death moves; Hope is
+    /// governed by `hope_mode` and Fear by its own cap
+    #[serde(default)]
+    pub rules: RulesConfig,
+    /// Next id to hand out in `add_combatant`
+    #[serde(default)]
+    pub(crate) next_id: u32,
+    /// Observers notified of every logged event
+    #[serde(skip)]
+    hooks: Hooks,
 }
 
 impl CombatEncounter {
+    #[cfg(feature = "persistence")]
     /// Save combat encounter to a JSON file
     ///
     /// # Examples
@@ -190,13 +1151,11 @@ impl CombatEncounter {
     /// let encounter = CombatEncounter::new(5);
     /// encounter.save_session("encounter.json").unwrap();
     /// ```
-    pub fn save_session(&self, path: &str) -> Result<(), std::io::Error> {
-        let json = serde_json::to_string_pretty(self)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
-        std::fs::write(path, json)?;
-        Ok(())
+    pub fn save_session(&self, path: impl AsRef<Path>) -> Result<(), EngineError> {
+        storage::save_file(self, path)
     }
 
+    #[cfg(feature = "persistence")]
     /// Load combat encounter from a JSON file
     ///
     /// # Examples
@@ -207,22 +1166,38 @@ impl CombatEncounter {
     /// let encounter = CombatEncounter::load_session("encounter.json").unwrap();
     /// println!("Round: {}", encounter.round);
     /// ```
-    pub fn load_session(path: &str) -> Result<Self, std::io::Error> {
-        let json = std::fs::read_to_string(path)?;
-        serde_json::from_str(&json)
-            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    pub fn load_session(path: impl AsRef<Path>) -> Result<Self, EngineError> {
+        storage::load_file(path)
+    }
+
+    /// Assign ids to combatants from saves that predate `CombatantId`
+    ///
+    /// Older saves stored raw indices in `turn_order` and the event log.
+    /// Giving each combatant its index as id keeps those references valid.
+    pub fn migrate_legacy_ids(&mut self) {
+        if self.next_id != 0 || self.combatants.is_empty() {
+            return;
+        }
+        for (index, combatant) in self.combatants.iter_mut().enumerate() {
+            combatant.id = CombatantId(index as u32);
+        }
+        self.next_id = self.combatants.len() as u32;
     }
 
     /// Create a new combat encounter
     ///
+    /// Characters use their own Hope; `hope_max` sizes the shared pool for
+    /// tables that switch to `HopeMode::Shared`.
+    ///
     /// # Examples
     ///
     /// ```
-    /// use daggerheart_engine::combat::simulation::CombatEncounter;
+    /// use daggerheart_engine::combat::simulation::{CombatEncounter, HopeMode};
     ///
-    /// let encounter = CombatEncounter::new(5); // Party Hope max = 5
+    /// let encounter = CombatEncounter::new(5); // Shared Hope max = 5
     /// assert_eq!(encounter.round, 0);
     /// assert_eq!(encounter.combatants.len(), 0);
+    /// assert_eq!(encounter.hope_mode, HopeMode::PerCharacter);
     /// ```
     pub fn new(hope_max: u8) -> Self {
         Self {
@@ -231,7 +1206,18 @@ impl CombatEncounter {
             turn_order: Vec::new(),
             current_turn: 0,
             hope: Hope::new(hope_max),
+            hope_mode: HopeMode::default(),
+            turn_model: TurnModel::default(),
+            action_tokens: ActionTracker::default(),
             fear: Fear::new(),
+            events: Vec::new(),
+            countdowns: Vec::new(),
+            hazards: Vec::new(),
+            pending_attack: None,
+            opportunity_attacks: false,
+            rules: RulesConfig::default(),
+            next_id: 0,
+            hooks: Hooks::default(),
         }
     }
 
@@ -240,87 +1226,780 @@ impl CombatEncounter {
     /// # Examples
     ///
     /// ```
-    /// use daggerheart_engine::combat::simulation::{CombatEncounter, Combatant};
-    /// use daggerheart_engine::character::{Class, Ancestry, Attributes};
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::simulation::{CombatEncounter, CombatantBuilder};
     ///
     /// let mut encounter = CombatEncounter::new(5);
-    /// let warrior = Combatant::player(
-    ///     "Grom",
-    ///     1,
-    ///     Class::Warrior,
-    ///     Ancestry::Orc,
-    ///     Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap(),
-    /// );
+    /// let warrior = CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///     .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///     .build()
+    ///     .unwrap();
     ///
-    /// encounter.add_combatant(warrior);
+    /// let id = encounter.add_combatant(warrior);
     /// assert_eq!(encounter.combatants.len(), 1);
+    /// assert_eq!(encounter.get(id).unwrap().name, "Grom");
     /// ```
-    pub fn add_combatant(&mut self, combatant: Combatant) {
+    pub fn add_combatant(&mut self, mut combatant: Combatant) -> CombatantId {
+        let id = CombatantId(self.next_id);
+        self.next_id += 1;
+
+        combatant.id = id;
+        let name = combatant.name.clone();
         self.combatants.push(combatant);
+        self.log(CombatEvent::CombatantAdded {
+            combatant: id,
+            name: name.to_string(),
+        });
+        id
+    }
+
+    /// Remove a combatant, keeping the turn order consistent
+    ///
+    /// If the removed combatant was acting, the turn passes to whoever
+    /// was next in initiative order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::simulation::{CombatEncounter, CombatantBuilder};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let goblin = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Goblin")
+    ///         .with_hp(4)
+    ///         .with_evasion(10)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// let orc = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Orc")
+    ///         .with_hp(6)
+    ///         .with_evasion(10)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// encounter.start();
+    ///
+    /// encounter.remove_combatant(goblin).unwrap();
+    /// assert_eq!(encounter.turn_order, vec![orc]);
+    /// assert_eq!(encounter.current_combatant().unwrap().name, "Orc");
+    /// ```
+    pub fn remove_combatant(&mut self, id: CombatantId) -> Result<Combatant, EngineError> {
+        let index = self.require(id)?;
+        let combatant = self.combatants.remove(index);
+        self.dismiss_summons(id);
+
+        if let Some(position) = self.turn_order.iter().position(|&t| t == id) {
+            self.turn_order.remove(position);
+            if position < self.current_turn {
+                self.current_turn -= 1;
+            }
+            if self.current_turn >= self.turn_order.len() {
+                self.current_turn = 0;
+            }
+        }
+
+        self.log(CombatEvent::CombatantRemoved { combatant: id });
+        Ok(combatant)
+    }
+
+    /// Look up a combatant by id
+    pub fn get(&self, id: CombatantId) -> Option<&Combatant> {
+        self.combatants.iter().find(|c| c.id == id)
+    }
+
+    /// Look up a combatant by id for mutation
+    pub fn get_mut(&mut self, id: CombatantId) -> Option<&mut Combatant> {
+        self.combatants.iter_mut().find(|c| c.id == id)
+    }
+
+    /// Current position of a combatant in `combatants`
+    pub fn index_of(&self, id: CombatantId) -> Option<usize> {
+        self.combatants.iter().position(|c| c.id == id)
     }
 
     /// Start the encounter (roll initiative for all combatants)
+    ///
+    /// Initiative is rolled with the encounter's `turn_model`; ties keep
+    /// the order combatants were added in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::simulation::{CombatEncounter, CombatantBuilder, TurnModel};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// encounter.turn_model = TurnModel::D20;
+    /// let goblin = CombatantBuilder::adversary("Goblin")
+    ///     .with_hp(4)
+    ///     .with_evasion(10)
+    ///     .build()
+    ///     .unwrap();
+    /// encounter.add_combatant(goblin);
+    /// encounter.start();
+    ///
+    /// assert!((1..=20).contains(&encounter.combatants[0].initiative));
+    /// ```
     pub fn start(&mut self) {
+        let mut rng = rand::thread_rng();
+        self.start_with_rng(&mut rng);
+    }
+
+    /// Start the encounter with a specific RNG (for testing/seeding)
+    ///
+    /// Hazards strike as round 1 and the first turn begin, as they do for
+    /// every later round and turn.
+    pub fn start_with_rng<R: Rng>(&mut self, rng: &mut R) {
         // Roll initiative for all combatants
-        for combatant in &mut self.combatants {
-            combatant.roll_initiative();
+        for index in 0..self.combatants.len() {
+            self.combatants[index].initiative =
+                self.turn_model.roll_with_rng(&self.combatants[index], rng);
+            self.log(CombatEvent::InitiativeRolled {
+                combatant: self.combatants[index].id,
+                initiative: self.combatants[index].initiative,
+            });
         }
 
         // Sort by initiative (highest first)
-        let mut indices: Vec<usize> = (0..self.combatants.len()).collect();
-        indices.sort_by(|&a, &b| {
-            self.combatants[b]
-                .initiative
-                .cmp(&self.combatants[a].initiative)
-        });
-        self.turn_order = indices;
+        let mut order: Vec<&Combatant> = self.combatants.iter().collect();
+        order.sort_by_key(|c| std::cmp::Reverse(c.initiative));
+        self.turn_order = order.iter().map(|c| c.id).collect();
 
         // Start round 1
         self.round = 1;
         self.current_turn = 0;
+        let since = self.events.len();
+        self.log(CombatEvent::RoundStarted { round: 1 });
+        if self.turn_model == TurnModel::ActionTokens {
+            self.refresh_action_tokens();
+        }
+        self.spring_round_hazards(since, rng);
+        if let Some(id) = self.current_combatant_id() {
+            self.spring_turn_hazards(id, rng);
+        }
     }
 
-    /// Get the current combatant's index
-    pub fn current_combatant_index(&self) -> Option<usize> {
-        if self.current_turn < self.turn_order.len() {
-            Some(self.turn_order[self.current_turn])
-        } else {
-            None
-        }
+    /// Get the current combatant's id
+    pub fn current_combatant_id(&self) -> Option<CombatantId> {
+        self.turn_order.get(self.current_turn).copied()
     }
 
     /// Get the current combatant
     pub fn current_combatant(&self) -> Option<&Combatant> {
-        self.current_combatant_index()
-            .and_then(|idx| self.combatants.get(idx))
+        self.current_combatant_id().and_then(|id| self.get(id))
+    }
+
+    /// Get a mutable reference to the current combatant
+    pub fn current_combatant_mut(&mut self) -> Option<&mut Combatant> {
+        self.current_combatant_id().and_then(|id| self.get_mut(id))
+    }
+
+    /// Advance to the next turn
+    pub fn next_turn(&mut self) {
+        let mut rng = rand::thread_rng();
+        self.next_turn_with_rng(&mut rng)
+    }
+
+    /// Advance to the next turn with a specific RNG (for testing/seeding)
+    ///
+    /// Ongoing damage is rolled as the current combatant's turn ends and
+    /// as the next one's begins. Hazards strike as a round or a turn
+    /// begins, as their triggers say.
+    pub fn next_turn_with_rng<R: Rng>(&mut self, rng: &mut R) {
+        if let Some(id) = self.current_combatant_id() {
+            self.tick_ongoing(id, TickTiming::EndOfTurn, rng);
+        }
+        self.end_current_turn();
+        self.current_turn += 1;
+
+        // If we've gone through all combatants, start new round
+        if self.current_turn >= self.turn_order.len() {
+            let since = self.events.len();
+            self.begin_round();
+            self.spring_round_hazards(since, rng);
+        }
+        if let Some(id) = self.current_combatant_id() {
+            self.tick_ongoing(id, TickTiming::StartOfTurn, rng);
+            self.spring_turn_hazards(id, rng);
+        }
+    }
+
+    /// End the round now, skipping anyone who hasn't acted yet
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::simulation::{CombatEncounter, CombatantBuilder};
+    /// use daggerheart_engine::combat::Countdown;
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// for name in ["Goblin A", "Goblin B"] {
+    ///     encounter.add_combatant(
+    ///         CombatantBuilder::adversary(name)
+    ///             .with_hp(4)
+    ///             .with_evasion(10)
+    ///             .build()
+    ///             .unwrap(),
+    ///     );
+    /// }
+    /// encounter.add_countdown(Countdown::new("Bridge collapses", 1));
+    /// encounter.start();
+    ///
+    /// encounter.end_round();
+    /// assert_eq!(encounter.round, 2);
+    /// assert_eq!(encounter.current_turn, 0);
+    /// assert!(encounter.countdowns.is_empty());
+    /// ```
+    pub fn end_round(&mut self) {
+        let mut rng = rand::thread_rng();
+        self.end_round_with_rng(&mut rng)
+    }
+
+    /// End the round with a specific RNG for the hazards it sets off
+    pub fn end_round_with_rng<R: Rng>(&mut self, rng: &mut R) {
+        self.end_current_turn();
+        let since = self.events.len();
+        self.begin_round();
+        self.spring_round_hazards(since, rng);
+    }
+
+    /// Add a countdown that ticks at the start of each round
+    pub fn add_countdown(&mut self, countdown: Countdown) {
+        self.log(CombatEvent::CountdownAdded {
+            name: countdown.name.clone(),
+            rounds: countdown.remaining,
+        });
+        self.countdowns.push(countdown);
+    }
+
+    fn end_current_turn(&mut self) {
+        if let Some(id) = self.current_combatant_id() {
+            if let Some(index) = self.index_of(id) {
+                self.combatants[index].modifiers.end_turn();
+            }
+            self.log(CombatEvent::TurnEnded {
+                combatant: id,
+                round: self.round,
+            });
+        }
+    }
+
+    fn begin_round(&mut self) {
+        self.round += 1;
+        self.current_turn = 0;
+
+        // Remove dead combatants from turn order
+        let combatants = &self.combatants;
+        self.turn_order
+            .retain(|&id| combatants.iter().any(|c| c.id == id && c.is_alive()));
+
+        self.log(CombatEvent::RoundStarted { round: self.round });
+        for combatant in &mut self.combatants {
+            combatant.modifiers.new_round();
+        }
+        if self.turn_model == TurnModel::ActionTokens
+            && self.rules.action_tokens.refresh == TokenRefresh::EachRound
+        {
+            self.refresh_action_tokens();
+        }
+        self.strain_flyers();
+
+        let mut expired = Vec::new();
+        for countdown in &mut self.countdowns {
+            if countdown.tick() {
+                expired.push(countdown.name.clone());
+            }
+        }
+        self.countdowns.retain(|countdown| !countdown.is_expired());
+        for name in expired {
+            self.log(CombatEvent::CountdownExpired { name });
+        }
+    }
+
+    /// Roll an attack from one combatant against another
+    ///
+    /// On a hit, damage is rolled and applied to the target. Player rolls
+    /// generate Hope (with Hope or on a critical) or Fear (with Fear).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::simulation::{CombatEncounter, CombatantBuilder};
+    /// use daggerheart_engine::combat::Attack;
+    /// use daggerheart_engine::core::dice::DamageDice;
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let a = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Goblin A")
+    ///         .with_hp(4)
+    ///         .with_evasion(10)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// let b = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Goblin B")
+    ///         .with_hp(4)
+    ///         .with_evasion(10)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// let result = encounter
+    ///     .resolve_attack(a, b, &Attack::new(1), &DamageDice::d6(1))
+    ///     .unwrap();
+    /// assert!(result.hope >= 1 && result.hope <= 12);
+    /// ```
+    pub fn resolve_attack(
+        &mut self,
+        attacker: CombatantId,
+        target: CombatantId,
+        attack: &Attack,
+        damage: &DamageDice,
+    ) -> Result<AttackResult, EngineError> {
+        let mut rng = rand::thread_rng();
+        self.resolve_attack_with_rng(attacker, target, attack, damage, &mut rng)
+    }
+
+    /// Roll an attack with a specific RNG (for testing/seeding)
+    ///
+    /// Reactions aren't offered; see [`CombatEncounter::declare_attack`]
+    /// to resolve an attack step by step.
+    pub fn resolve_attack_with_rng<R: Rng>(
+        &mut self,
+        attacker: CombatantId,
+        target: CombatantId,
+        attack: &Attack,
+        damage: &DamageDice,
+        rng: &mut R,
+    ) -> Result<AttackResult, EngineError> {
+        let mut pending = self.declare_attack(attacker, target, attack.clone(), damage.clone())?;
+        let result = self.roll_attack_with_rng(&mut pending, rng)?;
+        self.land_attack(&mut pending)?;
+        match result {
+            Some(result) => Ok(result),
+            None => Err(EngineError::AttackCancelled {
+                attacker: self.combatants[self.require(attacker)?].name.to_string(),
+                target: self.combatants[self.require(target)?].name.to_string(),
+            }),
+        }
+    }
+
+    /// Fire `owner`'s triggered features whose trigger matches, against
+    /// `opponent`
+    ///
+    /// Damage from a feature doesn't set off further features. Features of
+    /// a summon that already left the field don't fire.
+    pub(crate) fn fire_triggers(
+        &mut self,
+        owner: CombatantId,
+        opponent: CombatantId,
+        matches: impl Fn(Trigger) -> bool,
+    ) -> Result<(), EngineError> {
+        let Some(combatant) = self.get(owner) else {
+            return Ok(());
+        };
+        let fired: Vec<TriggeredFeature> = combatant
+            .triggers
+            .iter()
+            .filter(|feature| matches(feature.trigger))
+            .cloned()
+            .collect();
+
+        for feature in fired {
+            self.log(CombatEvent::FeatureTriggered {
+                combatant: owner,
+                feature: feature.name.clone(),
+                opponent,
+            });
+            match feature.effect {
+                TriggerEffect::OpponentMarksStress(amount) => {
+                    if let Some(combatant) = self.get_mut(opponent) {
+                        combatant.gain_stress(amount);
+                        self.log(CombatEvent::StressGained {
+                            combatant: opponent,
+                            amount,
+                        });
+                    }
+                }
+                TriggerEffect::OpponentTakesDamage(amount) => {
+                    if self.get(opponent).is_some() {
+                        self.apply_damage(opponent, amount)?;
+                    }
+                }
+                TriggerEffect::OpponentGains(condition) => {
+                    if self.get(opponent).is_some() {
+                        self.apply_condition(opponent, condition)?;
+                    }
+                }
+                TriggerEffect::OpponentSuffers(ongoing) => {
+                    if self.get(opponent).is_some() {
+                        self.apply_ongoing(opponent, ongoing)?;
+                    }
+                }
+                TriggerEffect::GainFear(amount) => self.gain_fear(amount),
+                TriggerEffect::ClearStress(amount) => {
+                    if let Some(combatant) = self.get_mut(owner) {
+                        combatant.stress.current = combatant.stress.current.saturating_sub(amount);
+                    }
+                }
+                TriggerEffect::Heal(amount) => {
+                    if let Some(combatant) = self.get_mut(owner) {
+                        combatant.hp.heal(amount);
+                    }
+                }
+                TriggerEffect::Gains(condition) => {
+                    if self.get(owner).is_some() {
+                        self.apply_condition(owner, condition)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply raw damage to a combatant, reduced by their armor
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::simulation::{CombatEncounter, CombatantBuilder};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let goblin = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Goblin")
+    ///         .with_hp(4)
+    ///         .with_evasion(10)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// let result = encounter.apply_damage(goblin, 7).unwrap();
+    /// assert_eq!(result.hp_lost, 1);
+    /// assert_eq!(encounter.get(goblin).unwrap().hp.current, 3);
+    /// ```
+    pub fn apply_damage(
+        &mut self,
+        target: CombatantId,
+        raw_damage: u16,
+    ) -> Result<DamageResult, EngineError> {
+        let index = self.require(target)?;
+        let result = self.combatants[index].measure_damage_with(raw_damage, self.rules.thresholds);
+        self.apply_damage_result(target, result)
+    }
+
+    /// Apply damage that has already been measured against armor and
+    /// thresholds
+    pub(crate) fn apply_damage_result(
+        &mut self,
+        target: CombatantId,
+        result: DamageResult,
+    ) -> Result<DamageResult, EngineError> {
+        let index = self.require(target)?;
+
+        let combatant = &mut self.combatants[index];
+        let was_alive = combatant.is_alive();
+        combatant.take_damage(result.hp_lost);
+        combatant.gain_stress(result.stress_gained);
+        let defeated = was_alive && !combatant.is_alive();
+
+        self.log(CombatEvent::DamageApplied {
+            target,
+            damage: result.clone(),
+        });
+        if result.stress_gained > 0 {
+            self.log(CombatEvent::StressGained {
+                combatant: target,
+                amount: result.stress_gained,
+            });
+        }
+        if defeated {
+            self.log(CombatEvent::CombatantDefeated { combatant: target });
+            // Summons and reinforcements leave the field when they drop
+            if self.combatants[index].leaves_when_defeated() {
+                self.remove_combatant(target)?;
+                return Ok(result);
+            }
+        }
+        self.check_dismount(target, &result)?;
+
+        Ok(result)
+    }
+
+    /// A character gains Hope, into the shared pool when playing with one
+    pub fn gain_hope(&mut self, combatant: CombatantId, amount: u8) -> Result<(), EngineError> {
+        self.hope_pool_mut(combatant)?.gain(amount);
+        self.log(CombatEvent::HopeGained { combatant, amount });
+        Ok(())
+    }
+
+    /// Spend a character's Hope
+    ///
+    /// Comes from the character's own Hope, or from the shared pool when
+    /// `hope_mode` is `Shared`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::simulation::{CombatEncounter, CombatantBuilder, HopeMode};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let grom = encounter.add_combatant(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// // Characters start with 2 Hope of their own
+    /// encounter.spend_hope(grom, 2).unwrap();
+    /// assert!(encounter.spend_hope(grom, 1).is_err());
+    ///
+    /// // With a shared pool, the party's 5 Hope is spent instead
+    /// encounter.hope_mode = HopeMode::Shared;
+    /// encounter.spend_hope(grom, 1).unwrap();
+    /// assert_eq!(encounter.hope.current, 4);
+    /// ```
+    pub fn spend_hope(&mut self, combatant: CombatantId, amount: u8) -> Result<(), EngineError> {
+        self.hope_pool_mut(combatant)?.spend(amount)?;
+        self.log(CombatEvent::HopeSpent { combatant, amount });
+        Ok(())
+    }
+
+    /// The Hope a character gains and spends under the current mode
+    pub fn hope_pool(&self, combatant: CombatantId) -> Result<&Hope, EngineError> {
+        let combatant = &self.combatants[self.require(combatant)?];
+        match self.hope_mode {
+            HopeMode::Shared => Ok(&self.hope),
+            HopeMode::PerCharacter => combatant.hope().ok_or_else(|| EngineError::MissingStat {
+                combatant: combatant.name.to_string(),
+                stat: "Hope",
+            }),
+        }
+    }
+
+    fn hope_pool_mut(&mut self, combatant: CombatantId) -> Result<&mut Hope, EngineError> {
+        let index = self.require(combatant)?;
+        match self.hope_mode {
+            HopeMode::Shared => Ok(&mut self.hope),
+            HopeMode::PerCharacter => self.combatants[index].hope_mut(),
+        }
+    }
+
+    /// Gain Fear for the GM
+    pub fn gain_fear(&mut self, amount: u8) {
+        self.fear.gain(amount);
+        self.log(CombatEvent::FearGained { amount });
+    }
+
+    /// Spend Fear from the GM pool
+    pub fn spend_fear(&mut self, amount: u8) -> Result<(), EngineError> {
+        self.fear.spend(amount)?;
+        self.log(CombatEvent::FearSpent { amount });
+        Ok(())
+    }
+
+    /// Apply a condition to a combatant
+    pub fn apply_condition(
+        &mut self,
+        target: CombatantId,
+        condition: Condition,
+    ) -> Result<(), EngineError> {
+        let index = self.require(target)?;
+        if self.combatants[index].is_immune(condition) {
+            self.log(CombatEvent::ConditionResisted {
+                combatant: target,
+                condition,
+            });
+        } else if self.combatants[index].add_condition(condition) {
+            self.log(CombatEvent::ConditionApplied {
+                combatant: target,
+                condition,
+            });
+        }
+        Ok(())
+    }
+
+    /// Remove a condition from a combatant
+    pub fn remove_condition(
+        &mut self,
+        target: CombatantId,
+        condition: Condition,
+    ) -> Result<(), EngineError> {
+        let index = self.require(target)?;
+        if self.combatants[index].remove_condition(condition) {
+            self.log(CombatEvent::ConditionRemoved {
+                combatant: target,
+                condition,
+            });
+        }
+        Ok(())
+    }
+
+    /// Play a domain card for a combatant
+    ///
+    /// The combatant must be alive, have the card's domain and meet its
+    /// level requirement.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::cards::{ActionCost, DomainCard};
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class, Domain};
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatEvent, CombatantBuilder};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let grom = encounter.add_combatant(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// let card = DomainCard::new(
+    ///     "blade_strike",
+    ///     "Blade Strike",
+    ///     Domain::Blade,
+    ///     1,
+    ///     "A swift sword strike",
+    ///     ActionCost::Major,
+    /// );
+    ///
+    /// encounter.play_card(grom, &card).unwrap();
+    /// assert!(matches!(
+    ///     encounter.last_event(),
+    ///     Some(CombatEvent::CardPlayed { .. })
+    /// ));
+    /// ```
+    pub fn play_card(&mut self, player: CombatantId, card: &DomainCard) -> Result<(), EngineError> {
+        let combatant = &self.combatants[self.require(player)?];
+        if !combatant.is_alive() {
+            return Err(EngineError::CombatantDefeated {
+                name: combatant.name.to_string(),
+            });
+        }
+        card.check_allowed(combatant.require_class()?, combatant.level)?;
+
+        self.hooks.card_played(&combatant.name, card);
+        self.log(CombatEvent::CardPlayed {
+            combatant: player,
+            card: card.id.clone(),
+        });
+        Ok(())
+    }
+
+    /// A character uses their class's Hope feature
+    ///
+    /// The Hope comes from the character, or the shared pool when playing
+    /// with one. Features aimed at an ally or enemy need a `target`; ones
+    /// aimed at the character use them. Healing, clearing Stress and
+    /// modifiers are applied, and the use is logged either way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatEvent, CombatantBuilder};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let attributes = Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap();
+    /// let grom = encounter.add_combatant(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(attributes)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// let seraph = encounter.add_combatant(
+    ///     CombatantBuilder::player("Ilse", Class::Seraph, Ancestry::Human)
+    ///         .with_attributes(attributes)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// encounter.apply_damage(grom, 20).unwrap();
+    /// let hp = encounter.get(grom).unwrap().hp.current;
+    /// encounter.gain_hope(seraph, 1).unwrap();
+    ///
+    /// // Life Support clears a Hit Point on an ally
+    /// encounter.use_class_feature(seraph, Some(grom)).unwrap();
+    /// assert_eq!(encounter.get(grom).unwrap().hp.current, hp + 1);
+    /// assert!(matches!(
+    ///     encounter.last_event(),
+    ///     Some(CombatEvent::ClassFeatureUsed { .. })
+    /// ));
+    /// ```
+    pub fn use_class_feature(
+        &mut self,
+        user: CombatantId,
+        target: Option<CombatantId>,
+    ) -> Result<ClassHopeFeature, EngineError> {
+        let feature = self.combatants[self.require(user)?].class_feature()?;
+        let target = match feature.effect.target() {
+            Some(Target::SelfOnly) => Some(user),
+            Some(Target::Ally | Target::Enemy) => {
+                Some(target.ok_or_else(|| EngineError::InvalidTarget {
+                    reason: format!("{} needs a target", feature.name),
+                })?)
+            }
+            _ => target,
+        };
+        let target_index = target.map(|id| self.require(id)).transpose()?;
+
+        self.spend_hope(user, feature.hope_cost)?;
+        if let Some(index) = target_index {
+            self.combatants[index].apply_effect(feature.name, &feature.effect);
+        }
+        self.log(CombatEvent::ClassFeatureUsed {
+            combatant: user,
+            feature: feature.name.to_string(),
+            target,
+        });
+        Ok(feature)
+    }
+
+    /// Get all events involving a specific combatant
+    pub fn events_for(&self, id: CombatantId) -> Vec<&CombatEvent> {
+        self.events.iter().filter(|e| e.involves(id)).collect()
+    }
+
+    /// Get the most recent event
+    pub fn last_event(&self) -> Option<&CombatEvent> {
+        self.events.last()
+    }
+
+    /// Register an observer for this encounter's events
+    ///
+    /// Hooks are not saved with the encounter; register them again after
+    /// loading.
+    pub fn add_hook(&mut self, hook: Arc<dyn EngineHooks>) {
+        self.hooks.add(hook);
     }
 
-    /// Get a mutable reference to the current combatant
-    pub fn current_combatant_mut(&mut self) -> Option<&mut Combatant> {
-        self.current_combatant_index()
-            .and_then(|idx| self.combatants.get_mut(idx))
+    /// Observers registered on this encounter
+    pub fn hooks(&self) -> &Hooks {
+        &self.hooks
     }
 
-    /// Advance to the next turn
-    pub fn next_turn(&mut self) {
-        self.current_turn += 1;
-
-        // If we've gone through all combatants, start new round
-        if self.current_turn >= self.turn_order.len() {
-            self.round += 1;
-            self.current_turn = 0;
+    pub(crate) fn log(&mut self, event: CombatEvent) {
+        self.hooks.event(&event);
+        self.events.push(event);
+    }
 
-            // Remove dead combatants from turn order
-            self.turn_order
-                .retain(|&idx| self.combatants[idx].is_alive());
-        }
+    pub(crate) fn require(&self, id: CombatantId) -> Result<usize, EngineError> {
+        self.index_of(id)
+            .ok_or(EngineError::UnknownCombatant { id: id.0 })
     }
 
     /// Check if combat is over
     pub fn is_over(&self) -> bool {
         // Combat is over if all players are dead or all enemies are dead
-        let players_alive = self.combatants.iter().any(|c| c.is_player && c.is_alive());
-        let enemies_alive = self.combatants.iter().any(|c| !c.is_player && c.is_alive());
+        let players_alive = self
+            .combatants
+            .iter()
+            .any(|c| c.is_player() && c.is_alive());
+        let enemies_alive = self
+            .combatants
+            .iter()
+            .any(|c| !c.is_player() && c.is_alive());
 
         !players_alive || !enemies_alive
     }
@@ -331,29 +2010,81 @@ impl CombatEncounter {
             return None;
         }
 
-        let players_alive = self.combatants.iter().any(|c| c.is_player && c.is_alive());
+        let players_alive = self
+            .combatants
+            .iter()
+            .any(|c| c.is_player() && c.is_alive());
 
         Some(players_alive)
     }
+
+    /// The death moves a character at 0 Hit Points can choose from under
+    /// this table's rules
+    ///
+    /// Adversaries and characters still standing have none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder};
+    /// use daggerheart_engine::rules::{DeathMove, DeathRule};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// encounter.rules.death = DeathRule::AvoidDeathOnly;
+    /// let grom = encounter.add_combatant(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// assert!(encounter.death_moves(grom).unwrap().is_empty());
+    ///
+    /// encounter.get_mut(grom).unwrap().take_damage(u8::MAX);
+    /// assert_eq!(encounter.death_moves(grom).unwrap(), vec![DeathMove::AvoidDeath]);
+    /// ```
+    pub fn death_moves(&self, combatant: CombatantId) -> Result<Vec<DeathMove>, EngineError> {
+        let combatant = &self.combatants[self.require(combatant)?];
+        if !combatant.is_player() || combatant.is_alive() {
+            return Ok(Vec::new());
+        }
+        Ok(self.rules.death.moves())
+    }
+}
+
+#[cfg(feature = "persistence")]
+impl Persist for CombatEncounter {
+    fn after_load(&mut self) -> Result<(), std::io::Error> {
+        self.migrate_legacy_ids();
+        Ok(())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cards::ActionCost;
+    use crate::character::Domain;
+    use crate::hooks::tests::Recorder;
+    use crate::items::ArmorFeature;
+    use crate::test_support::player;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
 
     fn create_test_warrior() -> Combatant {
-        Combatant::player(
-            "Test Warrior",
-            1,
-            Class::Warrior,
-            Ancestry::Human,
-            Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap(),
-        )
-        .with_armor(3)
+        player("Test Warrior", Class::Warrior, Ancestry::Human)
+            .with_armor(3)
+            .build()
+            .unwrap()
     }
 
     fn create_test_goblin() -> Combatant {
-        Combatant::enemy("Goblin", 1, 4, 13, 1)
+        CombatantBuilder::adversary("Goblin")
+            .with_hp(4)
+            .with_evasion(13)
+            .with_armor(1)
+            .build()
+            .unwrap()
     }
 
     #[test]
@@ -362,11 +2093,266 @@ mod tests {
 
         assert_eq!(warrior.name, "Test Warrior");
         assert_eq!(warrior.level, 1);
-        assert_eq!(warrior.class, Class::Warrior);
-        assert!(warrior.is_player);
+        assert_eq!(warrior.class(), Some(Class::Warrior));
+        assert!(warrior.is_player());
         assert!(warrior.is_alive());
     }
 
+    #[test]
+    fn test_builder_player_uses_class_stats() {
+        let bard = CombatantBuilder::player("Elara", Class::Bard, Ancestry::Giant)
+            .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+            .build()
+            .unwrap();
+
+        assert_eq!(bard.hp.maximum, Class::Bard.starting_hp() + 1);
+        assert_eq!(bard.evasion, Class::Bard.starting_evasion());
+        assert_eq!(bard.armor, 0);
+    }
+
+    #[test]
+    fn test_builder_optional_fields() {
+        let ranger = CombatantBuilder::player("Theron", Class::Ranger, Ancestry::Faerie)
+            .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+            .with_hp(8)
+            .with_condition(Condition::Hidden)
+            .with_condition(Condition::Hidden)
+            .with_experience(Experience::new("Tracker", 2))
+            .with_equipment("Shortbow")
+            .build()
+            .unwrap();
+
+        assert_eq!(ranger.hp.maximum, 8);
+        assert_eq!(ranger.conditions, vec![Condition::Hidden]);
+        assert_eq!(ranger.experiences, vec![Experience::new("Tracker", 2)]);
+        assert_eq!(ranger.equipment, vec!["Shortbow"]);
+    }
+
+    #[test]
+    fn test_builder_validates_players() {
+        let builder = CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc);
+        assert!(matches!(
+            builder.clone().build(),
+            Err(EngineError::MissingStat {
+                stat: "attributes",
+                ..
+            })
+        ));
+
+        let builder = builder.with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap());
+        for level in [0, 11] {
+            assert!(matches!(
+                builder.clone().with_level(level).build(),
+                Err(EngineError::InvalidLevel { level: l }) if l == level
+            ));
+        }
+        assert!(builder.clone().with_level(10).build().is_ok());
+
+        assert!(matches!(
+            builder.clone().with_subclass(Subclass::Stalwart).build(),
+            Err(EngineError::WrongSubclass {
+                class: Class::Warrior,
+                ..
+            })
+        ));
+        let grom = builder
+            .with_subclass(Subclass::CallOfTheSlayer)
+            .with_community(Community::Ridgeborne)
+            .build()
+            .unwrap();
+        assert_eq!(grom.subclass(), Some(Subclass::CallOfTheSlayer));
+        assert_eq!(grom.community(), Some(Community::Ridgeborne));
+    }
+
+    #[test]
+    fn test_builder_validates_adversaries() {
+        let error = CombatantBuilder::adversary("Ogre")
+            .with_evasion(10)
+            .build()
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Ogre has no HP");
+
+        let error = CombatantBuilder::adversary("Ogre")
+            .with_hp(8)
+            .build()
+            .unwrap_err();
+        assert_eq!(error.to_string(), "Ogre has no Evasion");
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn test_deprecated_constructors_match_builder() {
+        let attributes = Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap();
+        assert_eq!(
+            Combatant::player("Grom", 2, Class::Warrior, Ancestry::Orc, attributes),
+            CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+                .with_level(2)
+                .with_attributes(attributes)
+                .build()
+                .unwrap()
+        );
+        assert_eq!(
+            Combatant::enemy("Goblin", 1, 4, 13, 1),
+            create_test_goblin()
+        );
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_adversaries_have_no_class() {
+        let goblin = create_test_goblin();
+        assert_eq!(
+            goblin.kind,
+            CombatantKind::Adversary(AdversaryData::default())
+        );
+        assert_eq!(goblin.class(), None);
+        assert!(!goblin.is_player());
+
+        let json = serde_json::to_value(&goblin).unwrap();
+        assert!(json.get("class").is_none());
+        assert_eq!(
+            json["kind"],
+            serde_json::json!({"Adversary": {"allied": false}})
+        );
+        assert_eq!(serde_json::from_value::<Combatant>(json).unwrap(), goblin);
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_legacy_combatants_migrate() {
+        let mut player = serde_json::to_value(create_test_warrior()).unwrap();
+        let object = player.as_object_mut().unwrap();
+        let data = object.remove("kind").unwrap()["Player"].clone();
+        object.extend(data.as_object().unwrap().clone());
+        object.insert("is_player".into(), true.into());
+        let loaded: Combatant = serde_json::from_value(player).unwrap();
+        assert_eq!(loaded, create_test_warrior());
+
+        let mut enemy = serde_json::to_value(create_test_goblin()).unwrap();
+        let object = enemy.as_object_mut().unwrap();
+        object.remove("kind");
+        object.insert("class".into(), "Rogue".into());
+        object.insert("ancestry".into(), "Goblin".into());
+        object.insert("is_player".into(), false.into());
+        let loaded: Combatant = serde_json::from_value(enemy.clone()).unwrap();
+        assert_eq!(loaded, create_test_goblin());
+
+        // A summon that fought for the players
+        let object = enemy.as_object_mut().unwrap();
+        object.insert("is_player".into(), true.into());
+        object.insert(
+            "summon".into(),
+            serde_json::json!({"Summoner": {"summoner": 1}}),
+        );
+        let loaded: Combatant = serde_json::from_value(enemy).unwrap();
+        assert_eq!(loaded.kind, CombatantKind::Adversary(AdversaryData::ally()));
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_legacy_effects_load_into_modifiers() {
+        let mut json = serde_json::to_value(create_test_goblin()).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.remove("modifiers");
+        object.insert(
+            "effects".into(),
+            serde_json::json!([{
+                "source": "Bless",
+                "bonus": 1,
+                "applies_to": "attack rolls",
+                "duration": "Permanent"
+            }]),
+        );
+        let loaded: Combatant = serde_json::from_value(json).unwrap();
+        assert_eq!(loaded.total_bonus_for(RollKind::Attack), 1);
+        assert_eq!(loaded.modifiers.effects[0].kind, SourceKind::Effect);
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_combatant_without_kind_rejected() {
+        let mut json = serde_json::to_value(create_test_goblin()).unwrap();
+        json.as_object_mut().unwrap().remove("kind");
+        let error = serde_json::from_value::<Combatant>(json).unwrap_err();
+        assert!(error.to_string().contains("Goblin has no kind"));
+    }
+
+    #[test]
+    fn test_class_feature_needs_hope_and_target() {
+        let mut encounter = CombatEncounter::new(5);
+        let goblin = encounter.add_combatant(create_test_goblin());
+        let bard = encounter.add_combatant(
+            CombatantBuilder::player("Lyra", Class::Bard, Ancestry::Faerie)
+                .with_attributes(Attributes::from_array([0, -1, 1, 0, 2, 1]).unwrap())
+                .build()
+                .unwrap(),
+        );
+
+        assert!(matches!(
+            encounter.use_class_feature(goblin, None),
+            Err(EngineError::MissingStat { stat: "class", .. })
+        ));
+        assert!(matches!(
+            encounter.use_class_feature(bard, Some(goblin)),
+            Err(EngineError::InsufficientResource {
+                have: 2,
+                need: 3,
+                ..
+            })
+        ));
+
+        // Make a Scene needs someone to distract
+        encounter.gain_hope(bard, 1).unwrap();
+        assert!(matches!(
+            encounter.use_class_feature(bard, None),
+            Err(EngineError::InvalidTarget { .. })
+        ));
+        assert_eq!(encounter.get(bard).unwrap().hope().unwrap().current, 3);
+
+        let feature = encounter.use_class_feature(bard, Some(goblin)).unwrap();
+        assert_eq!(feature.name, "Make a Scene");
+        assert_eq!(encounter.get(bard).unwrap().hope().unwrap().current, 0);
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::ClassFeatureUsed {
+                combatant: bard,
+                feature: String::from("Make a Scene"),
+                target: Some(goblin),
+            })
+        );
+        assert_eq!(encounter.events_for(goblin).len(), 2);
+    }
+
+    #[test]
+    fn test_class_feature_from_shared_pool() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.hope_mode = HopeMode::Shared;
+        let warrior = encounter.add_combatant(create_test_warrior());
+
+        encounter.use_class_feature(warrior, None).unwrap();
+        assert_eq!(encounter.hope.current, 2);
+        assert_eq!(encounter.get(warrior).unwrap().hope().unwrap().current, 2);
+        assert!(encounter.use_class_feature(warrior, None).is_err());
+    }
+
+    #[test]
+    fn test_play_card_needs_class() {
+        let mut encounter = CombatEncounter::new(5);
+        let goblin = encounter.add_combatant(create_test_goblin());
+        let card = DomainCard::new(
+            "blade_strike",
+            "Blade Strike",
+            Domain::Blade,
+            1,
+            "A swift sword strike",
+            ActionCost::Major,
+        );
+        assert!(matches!(
+            encounter.play_card(goblin, &card),
+            Err(EngineError::MissingStat { stat: "class", .. })
+        ));
+    }
+
     #[test]
     fn test_combatant_take_damage() {
         let mut warrior = create_test_warrior();
@@ -418,9 +2404,28 @@ mod tests {
         assert_eq!(encounter.turn_order.len(), 2);
         assert_eq!(encounter.current_turn, 0);
 
-        // Initiative should be rolled (non-zero)
-        assert!(encounter.combatants[0].initiative < 20);
-        assert!(encounter.combatants[1].initiative < 20);
+        // Duality dice, plus the warrior's +2 Agility
+        assert!((4..=26).contains(&encounter.combatants[0].initiative));
+        assert!((2..=24).contains(&encounter.combatants[1].initiative));
+    }
+
+    #[test]
+    fn test_turn_models() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let warrior = create_test_warrior();
+        for _ in 0..50 {
+            let roll = TurnModel::D20.roll_with_rng(&warrior, &mut rng);
+            assert!((1..=20).contains(&roll));
+        }
+
+        let mut encounter = CombatEncounter::new(5);
+        encounter.turn_model = TurnModel::ByRole;
+        let goblin = encounter.add_combatant(create_test_goblin());
+        let warrior = encounter.add_combatant(create_test_warrior());
+        let wolf = encounter.add_combatant(create_test_goblin());
+        encounter.start_with_rng(&mut rng);
+
+        assert_eq!(encounter.turn_order, vec![warrior, goblin, wolf]);
     }
 
     #[test]
@@ -488,6 +2493,593 @@ mod tests {
         assert_eq!(encounter.player_victory(), Some(false));
     }
 
+    #[test]
+    fn test_add_combatant_logs_event() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(create_test_warrior());
+
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::CombatantAdded {
+                combatant: CombatantId(0),
+                name: "Test Warrior".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_start_and_next_turn_log_events() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(create_test_warrior());
+        encounter.add_combatant(create_test_goblin());
+        encounter.start();
+
+        let initiative_rolls = encounter
+            .events
+            .iter()
+            .filter(|e| matches!(e, CombatEvent::InitiativeRolled { .. }))
+            .count();
+        assert_eq!(initiative_rolls, 2);
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::RoundStarted { round: 1 })
+        );
+
+        let first = encounter.current_combatant_id().unwrap();
+        encounter.next_turn();
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::TurnEnded {
+                combatant: first,
+                round: 1,
+            })
+        );
+
+        encounter.next_turn();
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::RoundStarted { round: 2 })
+        );
+    }
+
+    #[test]
+    fn test_end_round_skips_remaining_turns() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(create_test_warrior());
+        let goblin = encounter.add_combatant(create_test_goblin());
+        encounter.start();
+
+        encounter.get_mut(goblin).unwrap().hp.current = 0;
+        let first = encounter.current_combatant_id().unwrap();
+        encounter.end_round();
+
+        assert_eq!(encounter.round, 2);
+        assert_eq!(encounter.current_turn, 0);
+        assert_eq!(encounter.turn_order.len(), 1);
+        assert!(!encounter.turn_order.contains(&goblin));
+        let ended = encounter
+            .events
+            .iter()
+            .filter(|e| matches!(e, CombatEvent::TurnEnded { .. }))
+            .count();
+        assert_eq!(ended, 1);
+        assert!(encounter.events.contains(&CombatEvent::TurnEnded {
+            combatant: first,
+            round: 1,
+        }));
+    }
+
+    #[test]
+    fn test_countdowns_tick_each_round() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(create_test_warrior());
+        encounter.add_countdown(Countdown::new("Ritual", 2));
+        encounter.add_countdown(Countdown::new("Reinforcements", 3));
+        encounter.start();
+
+        encounter.next_turn();
+        assert_eq!(encounter.countdowns[0].remaining, 1);
+        assert_eq!(encounter.countdowns[1].remaining, 2);
+
+        encounter.next_turn();
+        assert_eq!(
+            encounter.countdowns,
+            vec![Countdown::new("Reinforcements", 1)]
+        );
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::CountdownExpired {
+                name: "Ritual".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_apply_damage_logs_and_defeats() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(
+            CombatantBuilder::adversary("Imp")
+                .with_hp(3)
+                .with_evasion(10)
+                .build()
+                .unwrap(),
+        );
+
+        let result = encounter.apply_damage(CombatantId(0), 30).unwrap();
+        assert_eq!(result.hp_lost, 3);
+        assert!(encounter.events.iter().any(|e| matches!(
+            e,
+            CombatEvent::DamageApplied {
+                target: CombatantId(0),
+                ..
+            }
+        )));
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::CombatantDefeated {
+                combatant: CombatantId(0)
+            })
+        );
+    }
+
+    #[test]
+    fn test_apply_damage_below_threshold_logs_stress() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(create_test_goblin());
+
+        encounter.apply_damage(CombatantId(0), 2).unwrap();
+        assert_eq!(encounter.combatants[0].stress.current, 1);
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::StressGained {
+                combatant: CombatantId(0),
+                amount: 1,
+            })
+        );
+    }
+
+    #[test]
+    fn test_worn_armor_features_apply_to_damage() {
+        let reinforced = Armor::new("Brigandine", 2).with_feature(ArmorFeature::reinforced());
+        let mut encounter = CombatEncounter::new(5);
+        let knight = encounter.add_combatant(
+            CombatantBuilder::adversary("Knight")
+                .with_hp(6)
+                .with_evasion(12)
+                .with_worn_armor(reinforced)
+                .build()
+                .unwrap(),
+        );
+        assert_eq!(encounter.get(knight).unwrap().armor, 2);
+
+        // 6 after armor is under the raised threshold of 7
+        let result = encounter.apply_damage(knight, 8).unwrap();
+        assert_eq!((result.hp_lost, result.stress_gained), (0, 1));
+
+        let combatant = &mut encounter.combatants[0];
+        combatant.wear(Armor::named("Leather Armor").unwrap());
+        assert_eq!(combatant.armor, 3);
+        assert_eq!(encounter.apply_damage(knight, 8).unwrap().hp_lost, 1);
+    }
+
+    #[test]
+    fn test_threshold_rule_raises_worn_armor_threshold() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.rules.thresholds = ThresholdRule::PlusLevel;
+        let knight = encounter.add_combatant(
+            CombatantBuilder::adversary("Knight")
+                .with_level(3)
+                .with_hp(6)
+                .with_evasion(12)
+                .with_worn_armor(
+                    Armor::new("Brigandine", 2).with_feature(ArmorFeature::reinforced()),
+                )
+                .build()
+                .unwrap(),
+        );
+
+        // 8 after armor is under the reinforced threshold of 7 plus 2
+        let result = encounter.apply_damage(knight, 10).unwrap();
+        assert_eq!((result.hp_lost, result.stress_gained), (0, 1));
+        assert_eq!(encounter.apply_damage(knight, 11).unwrap().hp_lost, 1);
+    }
+
+    #[test]
+    fn test_armor_roll_bonuses_come_off_with_it() {
+        let mut mage = create_test_warrior();
+        let robes = Armor::new("Robes", 1).with_feature(ArmorFeature::channeling());
+        mage.wear(robes);
+        assert_eq!(mage.total_bonus_for(RollKind::Spellcast), 1);
+        assert!(mage.equipment.contains(&String::from("Robes")));
+
+        assert_eq!(mage.take_off_armor().unwrap().name, "Robes");
+        assert_eq!(mage.total_bonus_for(RollKind::Spellcast), 0);
+        assert!(mage.take_off_armor().is_none());
+    }
+
+    #[test]
+    fn test_apply_damage_invalid_index() {
+        let mut encounter = CombatEncounter::new(5);
+        assert!(encounter.apply_damage(CombatantId(3), 10).is_err());
+        assert!(encounter.events.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_attack_logs_attack() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(create_test_warrior());
+        encounter.add_combatant(create_test_goblin());
+
+        encounter
+            .resolve_attack(
+                CombatantId(0),
+                CombatantId(1),
+                &Attack::new(2),
+                &DamageDice::d8(1),
+            )
+            .unwrap();
+
+        assert!(encounter.events.iter().any(|e| matches!(
+            e,
+            CombatEvent::AttackResolved {
+                attacker: CombatantId(0),
+                target: CombatantId(1),
+                ..
+            }
+        )));
+        // Player rolls always generate Hope or Fear
+        assert!(encounter.events.iter().any(|e| matches!(
+            e,
+            CombatEvent::HopeGained { .. } | CombatEvent::FearGained { .. }
+        )));
+    }
+
+    #[test]
+    fn test_hope_and_fear_log_events() {
+        let mut encounter = CombatEncounter::new(5);
+        let warrior = encounter.add_combatant(create_test_warrior());
+        let logged = encounter.events.len();
+
+        encounter.spend_hope(warrior, 2).unwrap();
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::HopeSpent {
+                combatant: warrior,
+                amount: 2
+            })
+        );
+
+        encounter.gain_fear(3);
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::FearGained { amount: 3 })
+        );
+
+        assert!(encounter.spend_fear(10).is_err());
+        assert_eq!(encounter.events.len(), logged + 2); // Failed spend isn't logged
+    }
+
+    #[test]
+    fn test_conditions_log_events() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(create_test_goblin());
+
+        encounter
+            .apply_condition(CombatantId(0), Condition::Vulnerable)
+            .unwrap();
+        encounter
+            .apply_condition(CombatantId(0), Condition::Vulnerable)
+            .unwrap();
+        assert!(encounter.combatants[0].has_condition(Condition::Vulnerable));
+        assert_eq!(
+            encounter
+                .events
+                .iter()
+                .filter(|e| matches!(e, CombatEvent::ConditionApplied { .. }))
+                .count(),
+            1
+        );
+
+        encounter
+            .remove_condition(CombatantId(0), Condition::Vulnerable)
+            .unwrap();
+        assert!(!encounter.combatants[0].has_condition(Condition::Vulnerable));
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::ConditionRemoved {
+                combatant: CombatantId(0),
+                condition: Condition::Vulnerable,
+            })
+        );
+    }
+
+    #[test]
+    fn test_immunity_resists_conditions() {
+        let mut encounter = CombatEncounter::new(5);
+        let golem = encounter.add_combatant(
+            CombatantBuilder::adversary("Golem")
+                .with_hp(8)
+                .with_evasion(12)
+                .with_condition(Condition::Restrained)
+                .with_immunity(Condition::Restrained)
+                .build()
+                .unwrap(),
+        );
+        assert!(!encounter.combatants[0].has_condition(Condition::Restrained));
+
+        encounter
+            .apply_condition(golem, Condition::Restrained)
+            .unwrap();
+        assert!(!encounter.combatants[0].has_condition(Condition::Restrained));
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::ConditionResisted {
+                combatant: golem,
+                condition: Condition::Restrained,
+            })
+        );
+    }
+
+    #[test]
+    fn test_ancestry_features_are_built_in() {
+        let attributes = Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap();
+        let orc = CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+            .with_attributes(attributes)
+            .build()
+            .unwrap();
+        assert_eq!(orc.triggers.len(), 1);
+        assert_eq!(orc.triggers[0].name, "Savage Attacks");
+
+        let inferis = CombatantBuilder::player("Ash", Class::Wizard, Ancestry::Inferis)
+            .with_attributes(attributes)
+            .build()
+            .unwrap();
+        assert!(inferis.triggers.is_empty());
+        assert_eq!(inferis.total_bonus_for(RollKind::Spellcast), 1);
+    }
+
+    #[test]
+    fn test_melee_damage_fires_triggers() {
+        let mut encounter = CombatEncounter::new(5);
+        let warrior = encounter.add_combatant(create_test_warrior());
+        let urchin = encounter.add_combatant(
+            CombatantBuilder::adversary("Urchin")
+                .with_hp(20)
+                .with_evasion(1)
+                .with_trigger(TriggeredFeature::new(
+                    "Spiny Hide",
+                    Trigger::DamagedByMelee,
+                    TriggerEffect::OpponentMarksStress(1),
+                ))
+                .with_trigger(TriggeredFeature::new(
+                    "Ink Cloud",
+                    Trigger::Damaged,
+                    TriggerEffect::Gains(Condition::Hidden),
+                ))
+                .build()
+                .unwrap(),
+        );
+        let mut rng = StdRng::seed_from_u64(1);
+        let damage = DamageDice::new(vec![]).with_bonus(30);
+
+        // From range only the Damaged feature fires
+        encounter
+            .resolve_attack_with_rng(warrior, urchin, &Attack::new(50), &damage, &mut rng)
+            .unwrap();
+        assert_eq!(encounter.combatants[0].stress.current, 0);
+        assert!(encounter.combatants[1].has_condition(Condition::Hidden));
+        // Found again before the next attack
+        encounter
+            .remove_condition(urchin, Condition::Hidden)
+            .unwrap();
+
+        let melee = Attack::new(50).with_range(crate::cards::Range::VeryClose);
+        encounter
+            .resolve_attack_with_rng(warrior, urchin, &melee, &damage, &mut rng)
+            .unwrap();
+        assert_eq!(encounter.combatants[0].stress.current, 1);
+        assert!(encounter.events.contains(&CombatEvent::FeatureTriggered {
+            combatant: urchin,
+            feature: String::from("Spiny Hide"),
+            opponent: warrior,
+        }));
+    }
+
+    #[test]
+    fn test_miss_and_defeat_triggers() {
+        let mut encounter = CombatEncounter::new(5);
+        let warrior = encounter.add_combatant(create_test_warrior());
+        let wisp = encounter.add_combatant(
+            CombatantBuilder::adversary("Wisp")
+                .with_hp(1)
+                .with_evasion(200)
+                .with_trigger(TriggeredFeature::new(
+                    "Flicker",
+                    Trigger::Missed,
+                    TriggerEffect::GainFear(1),
+                ))
+                .with_trigger(TriggeredFeature::new(
+                    "Death Burst",
+                    Trigger::Defeated,
+                    TriggerEffect::OpponentTakesDamage(20),
+                ))
+                .build()
+                .unwrap(),
+        );
+        let damage = DamageDice::new(vec![]).with_bonus(30);
+        let mut rng = StdRng::seed_from_u64(3);
+
+        let result = encounter
+            .resolve_attack_with_rng(warrior, wisp, &Attack::new(0), &damage, &mut rng)
+            .unwrap();
+        assert!(!result.hit);
+        let fear = u8::from(!(result.critical || result.with_hope));
+        assert_eq!(encounter.fear.current, fear + 1);
+
+        encounter.combatants[1].evasion = 1;
+        let hp = encounter.combatants[0].hp.current;
+        encounter
+            .resolve_attack_with_rng(warrior, wisp, &Attack::new(50), &damage, &mut rng)
+            .unwrap();
+        assert!(!encounter.combatants[1].is_alive());
+        assert!(encounter.combatants[0].hp.current < hp);
+    }
+
+    #[test]
+    fn test_events_for_combatant() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(create_test_warrior());
+        encounter.add_combatant(create_test_goblin());
+        encounter.apply_damage(CombatantId(1), 7).unwrap();
+
+        assert_eq!(encounter.events_for(CombatantId(0)).len(), 1);
+        assert_eq!(encounter.events_for(CombatantId(1)).len(), 2);
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_legacy_encounter_without_events_loads() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(create_test_warrior());
+
+        let mut value = serde_json::to_value(&encounter).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("events");
+        let combatant = object["combatants"][0].as_object_mut().unwrap();
+        for field in ["conditions", "experiences", "equipment"] {
+            combatant.remove(field);
+        }
+
+        let loaded: CombatEncounter = serde_json::from_value(value).unwrap();
+        assert!(loaded.events.is_empty());
+        assert!(loaded.combatants[0].conditions.is_empty());
+        assert!(loaded.combatants[0].experiences.is_empty());
+    }
+
+    #[test]
+    fn test_add_combatant_assigns_unique_ids() {
+        let mut encounter = CombatEncounter::new(5);
+        let a = encounter.add_combatant(create_test_warrior());
+        let b = encounter.add_combatant(create_test_goblin());
+
+        assert_ne!(a, b);
+        assert_eq!(encounter.get(a).unwrap().name, "Test Warrior");
+        assert_eq!(encounter.get(b).unwrap().name, "Goblin");
+    }
+
+    #[test]
+    fn test_ids_not_reused_after_removal() {
+        let mut encounter = CombatEncounter::new(5);
+        let a = encounter.add_combatant(create_test_goblin());
+        encounter.remove_combatant(a).unwrap();
+        let b = encounter.add_combatant(create_test_goblin());
+
+        assert_ne!(a, b);
+        assert!(encounter.get(a).is_none());
+    }
+
+    #[test]
+    fn test_remove_combatant_keeps_references_valid() {
+        let mut encounter = CombatEncounter::new(5);
+        let warrior = encounter.add_combatant(create_test_warrior());
+        let goblin = encounter.add_combatant(create_test_goblin());
+        let orc = encounter.add_combatant(
+            CombatantBuilder::adversary("Orc")
+                .with_hp(6)
+                .with_evasion(10)
+                .build()
+                .unwrap(),
+        );
+        encounter.start();
+
+        encounter.remove_combatant(goblin).unwrap();
+
+        assert_eq!(encounter.turn_order.len(), 2);
+        assert!(!encounter.turn_order.contains(&goblin));
+        encounter.apply_damage(orc, 7).unwrap();
+        assert_eq!(encounter.get(orc).unwrap().hp.current, 5);
+        assert_eq!(encounter.get(warrior).unwrap().hp.current, 6);
+        assert!(encounter
+            .events
+            .contains(&CombatEvent::CombatantRemoved { combatant: goblin }));
+    }
+
+    #[test]
+    fn test_remove_current_combatant_passes_turn() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(create_test_warrior());
+        encounter.add_combatant(create_test_goblin());
+        encounter.add_combatant(
+            CombatantBuilder::adversary("Orc")
+                .with_hp(6)
+                .with_evasion(10)
+                .build()
+                .unwrap(),
+        );
+        encounter.start();
+        encounter.next_turn();
+
+        let acting = encounter.current_combatant_id().unwrap();
+        let next = encounter.turn_order[2];
+        encounter.remove_combatant(acting).unwrap();
+
+        assert_eq!(encounter.current_combatant_id(), Some(next));
+    }
+
+    #[test]
+    fn test_remove_earlier_combatant_keeps_current() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(create_test_warrior());
+        encounter.add_combatant(create_test_goblin());
+        encounter.add_combatant(
+            CombatantBuilder::adversary("Orc")
+                .with_hp(6)
+                .with_evasion(10)
+                .build()
+                .unwrap(),
+        );
+        encounter.start();
+        encounter.next_turn();
+        encounter.next_turn();
+
+        let acting = encounter.current_combatant_id().unwrap();
+        let first = encounter.turn_order[0];
+        encounter.remove_combatant(first).unwrap();
+
+        assert_eq!(encounter.current_combatant_id(), Some(acting));
+    }
+
+    #[test]
+    fn test_remove_unknown_combatant() {
+        let mut encounter = CombatEncounter::new(5);
+        assert!(encounter.remove_combatant(CombatantId(7)).is_err());
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_migrate_legacy_ids() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(create_test_warrior());
+        encounter.add_combatant(create_test_goblin());
+        encounter.start();
+
+        let mut value = serde_json::to_value(&encounter).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("next_id");
+        for combatant in object["combatants"].as_array_mut().unwrap() {
+            combatant.as_object_mut().unwrap().remove("id");
+        }
+
+        let mut loaded: CombatEncounter = serde_json::from_value(value).unwrap();
+        loaded.migrate_legacy_ids();
+
+        assert_eq!(loaded.combatants[1].id, CombatantId(1));
+        assert_eq!(loaded.turn_order, encounter.turn_order);
+        let fresh = loaded.add_combatant(create_test_goblin());
+        assert_eq!(fresh, CombatantId(2));
+    }
+
+    #[cfg(feature = "persistence")]
     #[test]
     fn test_encounter_serialization() {
         let mut encounter = CombatEncounter::new(5);
@@ -498,5 +3090,77 @@ mod tests {
 
         assert_eq!(loaded.combatants.len(), encounter.combatants.len());
         assert_eq!(loaded.hope.maximum, encounter.hope.maximum);
+        assert_eq!(loaded.events, encounter.events);
+    }
+
+    #[test]
+    fn test_hooks_see_rolls_and_damage() {
+        let recorder = Arc::new(Recorder::default());
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_hook(recorder.clone());
+        let warrior = encounter.add_combatant(create_test_warrior());
+        let goblin = encounter.add_combatant(create_test_goblin());
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let result = encounter
+            .resolve_attack_with_rng(
+                warrior,
+                goblin,
+                &Attack::new(10),
+                &DamageDice::d6(1),
+                &mut rng,
+            )
+            .unwrap();
+        assert!(result.hit);
+
+        let calls = recorder.calls();
+        assert_eq!(calls[0], "roll #0 -> #1");
+        assert!(calls[1].starts_with("damage #1"));
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_hooks_not_serialized() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_hook(Arc::new(Recorder::default()));
+
+        let json = serde_json::to_string(&encounter).unwrap();
+        let loaded: CombatEncounter = serde_json::from_str(&json).unwrap();
+
+        assert!(!json.contains("hooks"));
+        assert!(loaded.hooks().is_empty());
+    }
+
+    #[test]
+    fn test_play_card() {
+        let recorder = Arc::new(Recorder::default());
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_hook(recorder.clone());
+        let warrior = encounter.add_combatant(create_test_warrior());
+        let card = |level| {
+            DomainCard::new(
+                "blade_strike",
+                "Blade Strike",
+                Domain::Blade,
+                level,
+                "A swift sword strike",
+                ActionCost::Major,
+            )
+        };
+
+        assert!(encounter.play_card(warrior, &card(3)).is_err());
+        encounter.play_card(warrior, &card(1)).unwrap();
+
+        assert_eq!(
+            encounter.last_event(),
+            Some(&CombatEvent::CardPlayed {
+                combatant: warrior,
+                card: "blade_strike".to_string()
+            })
+        );
+        assert_eq!(recorder.calls(), vec!["card Test Warrior blade_strike"]);
+
+        encounter.get_mut(warrior).unwrap().hp.current = 0;
+        assert!(encounter.play_card(warrior, &card(1)).is_err());
     }
 }
@@ -0,0 +1,221 @@
+//! Spellcast rolls
+//!
+//! Casters roll Spellcast with the trait their subclass names: a
+//! Troubadour uses Presence, a School of War wizard Knowledge. Spell
+//! attacks on cards use that trait in place of a weapon attack's modifier.
+
+use crate::cards::CardEffect;
+use crate::character::AttributeType;
+use crate::combat::attack::{Attack, AttackResult};
+use crate::combat::modifiers::RollKind;
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId};
+use crate::core::dice::{DualityResult, DualityRoll, SuccessType};
+use crate::error::EngineError;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// The outcome of a Spellcast roll
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SpellcastRoll {
+    /// The subclass's Spellcast trait
+    pub spellcast_trait: AttributeType,
+    pub result: DualityResult,
+    pub difficulty: u16,
+    pub outcome: SuccessType,
+}
+
+impl SpellcastRoll {
+    /// Whether the spell takes hold
+    pub fn is_success(&self) -> bool {
+        self.outcome != SuccessType::Failure
+    }
+}
+
+impl Combatant {
+    /// The trait this character rolls Spellcast with, from their subclass
+    pub fn spellcast_trait(&self) -> Option<AttributeType> {
+        self.subclass()
+            .and_then(|subclass| subclass.spellcast_trait())
+    }
+
+    /// The modifier added to this character's Spellcast rolls
+    pub fn spellcast_modifier(&self) -> Result<i8, EngineError> {
+        let missing = |stat| EngineError::MissingStat {
+            combatant: self.name.to_string(),
+            stat,
+        };
+        let spellcast_trait = self
+            .spellcast_trait()
+            .ok_or_else(|| missing("Spellcast trait"))?;
+        let attributes = self.attributes().ok_or_else(|| missing("attributes"))?;
+        Ok(attributes
+            .get_modifier(spellcast_trait)
+            .saturating_add(self.total_bonus_for(RollKind::Spellcast)))
+    }
+
+    /// Roll Spellcast against a difficulty
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, AttributeType, Attributes, Class, Subclass};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    ///
+    /// let wizard = CombatantBuilder::player("Elowen", Class::Wizard, Ancestry::Human)
+    ///     .with_subclass(Subclass::SchoolOfKnowledge)
+    ///     .with_attributes(Attributes::from_array([0, -1, 1, 0, 1, 2]).unwrap())
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let roll = wizard.spellcast_roll(12).unwrap();
+    /// assert_eq!(roll.spellcast_trait, AttributeType::Knowledge);
+    /// assert_eq!(roll.result.modifier, 2);
+    /// ```
+    pub fn spellcast_roll(&self, difficulty: u16) -> Result<SpellcastRoll, EngineError> {
+        let mut rng = rand::thread_rng();
+        self.spellcast_roll_with_rng(difficulty, &mut rng)
+    }
+
+    /// Roll Spellcast with a specific RNG (for testing/seeding)
+    pub fn spellcast_roll_with_rng<R: Rng>(
+        &self,
+        difficulty: u16,
+        rng: &mut R,
+    ) -> Result<SpellcastRoll, EngineError> {
+        let modifier = self.spellcast_modifier()?;
+        let result = DualityRoll::roll_with_rng(rng).with_modifier(modifier);
+        Ok(SpellcastRoll {
+            spellcast_trait: self.spellcast_trait().expect("checked by the modifier"),
+            outcome: result.success_type(difficulty),
+            result,
+            difficulty,
+        })
+    }
+}
+
+impl CombatEncounter {
+    /// Resolve a card's attack effect against a target
+    ///
+    /// Spells are rolled as Spellcast, with the caster's subclass trait as
+    /// the modifier; other attacks roll `attack` as given. Either way the
+    /// target's Evasion is the difficulty, and a hit rolls the effect's
+    /// damage.
+    pub fn resolve_card_attack(
+        &mut self,
+        user: CombatantId,
+        target: CombatantId,
+        effect: &CardEffect,
+        attack: &Attack,
+    ) -> Result<AttackResult, EngineError> {
+        let mut rng = rand::thread_rng();
+        self.resolve_card_attack_with_rng(user, target, effect, attack, &mut rng)
+    }
+
+    /// Resolve a card's attack effect with a specific RNG (for testing/seeding)
+    pub fn resolve_card_attack_with_rng<R: Rng>(
+        &mut self,
+        user: CombatantId,
+        target: CombatantId,
+        effect: &CardEffect,
+        attack: &Attack,
+        rng: &mut R,
+    ) -> Result<AttackResult, EngineError> {
+        let CardEffect::Attack { damage, spell, .. } = effect else {
+            return Err(EngineError::InvalidCombatAction(String::from(
+                "Only attack effects can be rolled against a target",
+            )));
+        };
+
+        let mut attack = attack.clone();
+        if *spell {
+            attack.modifier = self.combatants[self.require(user)?].spellcast_modifier()?;
+        }
+        self.resolve_attack_with_rng(user, target, &attack, damage, rng)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Range, Target};
+    use crate::character::{Ancestry, Attributes, Class, Subclass};
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::combat::CombatEvent;
+    use crate::core::dice::DamageDice;
+    use crate::test_support::player;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn create_test_sorcerer() -> Combatant {
+        CombatantBuilder::player("Vex", Class::Sorcerer, Ancestry::Daemon)
+            .with_subclass(Subclass::ElementalOrigin)
+            .with_attributes(Attributes::from_array([0, -1, 1, 2, 1, 0]).unwrap())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_spellcast_needs_a_casting_subclass() {
+        let sorcerer = create_test_sorcerer();
+        assert_eq!(sorcerer.spellcast_modifier().unwrap(), 2);
+
+        let warrior = player("Grom", Class::Warrior, Ancestry::Orc)
+            .with_subclass(Subclass::CallOfTheSlayer)
+            .build()
+            .unwrap();
+        assert!(matches!(
+            warrior.spellcast_roll(10),
+            Err(EngineError::MissingStat {
+                stat: "Spellcast trait",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_spellcast_roll_outcome() {
+        let sorcerer = create_test_sorcerer();
+        let mut rng = StdRng::seed_from_u64(7);
+        let roll = sorcerer.spellcast_roll_with_rng(10, &mut rng).unwrap();
+
+        assert_eq!(roll.spellcast_trait, AttributeType::Instinct);
+        assert_eq!(roll.outcome, roll.result.success_type(10));
+        assert_eq!(roll.is_success(), roll.result.is_success(10));
+    }
+
+    #[test]
+    fn test_spell_attacks_use_spellcast_modifier() {
+        let mut encounter = CombatEncounter::new(5);
+        let sorcerer = encounter.add_combatant(create_test_sorcerer());
+        let goblin = encounter.add_combatant(
+            CombatantBuilder::adversary("Goblin")
+                .with_hp(4)
+                .with_evasion(10)
+                .build()
+                .unwrap(),
+        );
+        let mut rng = StdRng::seed_from_u64(3);
+
+        let spell = CardEffect::spell(DamageDice::d8(1), Range::Far, Target::Enemy);
+        let result = encounter
+            .resolve_card_attack_with_rng(sorcerer, goblin, &spell, &Attack::new(-3), &mut rng)
+            .unwrap();
+        assert_eq!(result.modifier, 2);
+
+        let swing = CardEffect::attack(DamageDice::d8(1), Range::Close, Target::Enemy);
+        let result = encounter
+            .resolve_card_attack_with_rng(sorcerer, goblin, &swing, &Attack::new(-3), &mut rng)
+            .unwrap();
+        assert_eq!(result.modifier, -3);
+        assert!(encounter
+            .events
+            .iter()
+            .any(|event| matches!(event, CombatEvent::AttackResolved { .. })));
+
+        let heal = CardEffect::heal(1, Target::Ally);
+        assert!(encounter
+            .resolve_card_attack_with_rng(sorcerer, goblin, &heal, &Attack::new(0), &mut rng)
+            .is_err());
+    }
+}
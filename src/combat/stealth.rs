@@ -0,0 +1,193 @@
+//! Finding Hidden combatants
+//!
+//! A Hidden combatant can't be attacked until someone finds them. Finding
+//! them is an Instinct roll against their Evasion; on a success they lose
+//! Hidden. A Hidden attacker gives themselves away by attacking, and
+//! attacks against a Vulnerable target are made with advantage.
+
+use crate::character::AttributeType;
+use crate::combat::conditions::Condition;
+use crate::combat::modifiers::RollKind;
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId};
+use crate::core::dice::{ControllingDie, DualityResult, DualityRoll, SuccessType};
+use crate::error::EngineError;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// The outcome of looking for a Hidden combatant
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SearchRoll {
+    pub result: DualityResult,
+    /// The Hidden combatant's Evasion
+    pub difficulty: u16,
+    pub outcome: SuccessType,
+    /// Whether the target lost Hidden
+    pub found: bool,
+}
+
+impl Combatant {
+    /// The modifier added to this combatant's rolls to notice things
+    ///
+    /// Characters roll Instinct; adversaries without traits add their
+    /// level.
+    pub fn perception_modifier(&self) -> i8 {
+        let base = match self.attributes() {
+            Some(attributes) => attributes.get_modifier(AttributeType::Instinct),
+            None => self.level.min(i8::MAX as u8) as i8,
+        };
+        base.saturating_add(self.total_bonus_for(RollKind::Action))
+    }
+}
+
+impl CombatEncounter {
+    /// Look for a Hidden combatant so they can be attacked
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder, Condition};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let scout = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Scout").with_hp(4).with_evasion(10).build().unwrap(),
+    /// );
+    /// let thief = encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Thief")
+    ///         .with_hp(4)
+    ///         .with_evasion(1)
+    ///         .with_condition(Condition::Hidden)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// let search = encounter.search(scout, thief).unwrap();
+    /// assert!(search.found);
+    /// assert!(!encounter.get(thief).unwrap().has_condition(Condition::Hidden));
+    /// ```
+    pub fn search(
+        &mut self,
+        seeker: CombatantId,
+        target: CombatantId,
+    ) -> Result<SearchRoll, EngineError> {
+        let mut rng = rand::thread_rng();
+        self.search_with_rng(seeker, target, &mut rng)
+    }
+
+    /// Look for a Hidden combatant with a specific RNG (for testing/seeding)
+    ///
+    /// Like any action roll, a character's search gives them Hope or the
+    /// GM Fear.
+    pub fn search_with_rng<R: Rng>(
+        &mut self,
+        seeker: CombatantId,
+        target: CombatantId,
+        rng: &mut R,
+    ) -> Result<SearchRoll, EngineError> {
+        let seeker_index = self.require(seeker)?;
+        let target_index = self.require(target)?;
+        let difficulty = self.combatants[target_index].evasion as u16;
+        let modifier = self.combatants[seeker_index].perception_modifier();
+
+        let result = DualityRoll::roll_with_rng(rng).with_modifier(modifier);
+        let outcome = result.success_type(difficulty);
+        if self.combatants[seeker_index].is_player() {
+            if result.is_critical || result.controlling == ControllingDie::Hope {
+                if self.hope_pool(seeker).is_ok() {
+                    self.gain_hope(seeker, 1)?;
+                }
+            } else {
+                self.gain_fear(1);
+            }
+        }
+
+        let found = outcome != SuccessType::Failure
+            && self.combatants[target_index].has_condition(Condition::Hidden);
+        if found {
+            self.remove_condition(target, Condition::Hidden)?;
+        }
+        Ok(SearchRoll {
+            result,
+            difficulty,
+            outcome,
+            found,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::attack::Attack;
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::core::dice::DamageDice;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn add(
+        encounter: &mut CombatEncounter,
+        name: &str,
+        condition: Option<Condition>,
+    ) -> CombatantId {
+        let mut builder = CombatantBuilder::adversary(name)
+            .with_hp(6)
+            .with_evasion(10);
+        if let Some(condition) = condition {
+            builder = builder.with_condition(condition);
+        }
+        encounter.add_combatant(builder.build().unwrap())
+    }
+
+    #[test]
+    fn test_hidden_targets_must_be_found() {
+        let mut encounter = CombatEncounter::new(5);
+        let hunter = add(&mut encounter, "Hunter", None);
+        let thief = add(&mut encounter, "Thief", Some(Condition::Hidden));
+
+        let error = encounter
+            .resolve_attack(hunter, thief, &Attack::new(3), &DamageDice::d6(1))
+            .unwrap_err();
+        assert!(matches!(error, EngineError::TargetHidden { .. }));
+
+        let mut rng = StdRng::seed_from_u64(1);
+        while !encounter
+            .search_with_rng(hunter, thief, &mut rng)
+            .unwrap()
+            .found
+        {}
+        assert!(encounter
+            .resolve_attack(hunter, thief, &Attack::new(3), &DamageDice::d6(1))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_attacking_reveals_the_attacker() {
+        let mut encounter = CombatEncounter::new(5);
+        let thief = add(&mut encounter, "Thief", Some(Condition::Hidden));
+        let guard = add(&mut encounter, "Guard", None);
+
+        encounter
+            .resolve_attack(thief, guard, &Attack::new(0), &DamageDice::d6(1))
+            .unwrap();
+        assert!(!encounter
+            .get(thief)
+            .unwrap()
+            .has_condition(Condition::Hidden));
+    }
+
+    #[test]
+    fn test_vulnerable_targets_give_advantage() {
+        let mut encounter = CombatEncounter::new(5);
+        let ogre = add(&mut encounter, "Ogre", None);
+        let knight = add(&mut encounter, "Knight", Some(Condition::Vulnerable));
+
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut pending = encounter
+            .declare_attack(ogre, knight, Attack::new(0), DamageDice::d6(1))
+            .unwrap();
+        encounter
+            .roll_attack_with_rng(&mut pending, &mut rng)
+            .unwrap();
+        assert!(pending.attack.with_advantage);
+    }
+}
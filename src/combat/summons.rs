@@ -0,0 +1,477 @@
+//! Summons and mid-combat reinforcements
+//!
+//! Combatants can join an encounter after it has started: the GM spends
+//! Fear to bring in reinforcements, or a character summons a creature to
+//! fight alongside them. Both are slotted into the existing turn order
+//! rather than re-rolling initiative for everyone.
+
+use crate::combat::events::CombatEvent;
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId, CombatantKind};
+use crate::error::EngineError;
+use serde::{Deserialize, Serialize};
+
+/// Where a mid-combat arrival came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum SummonSource {
+    /// The GM spent Fear to bring in reinforcements
+    Reinforcement { fear_spent: u8 },
+    /// Summoned by another combatant, who controls it
+    Summoner { summoner: CombatantId },
+    /// A character's companion, fighting under their control
+    Companion { owner: CombatantId },
+}
+
+impl Combatant {
+    /// The combatant controlling this one, if it was summoned
+    pub fn controller(&self) -> Option<CombatantId> {
+        match self.summon {
+            Some(SummonSource::Summoner { summoner }) => Some(summoner),
+            Some(SummonSource::Companion { owner }) => Some(owner),
+            _ => None,
+        }
+    }
+
+    /// Whether it leaves the field when it drops
+    ///
+    /// Summons and reinforcements do; a companion stays down until its
+    /// owner tends to it.
+    pub fn leaves_when_defeated(&self) -> bool {
+        matches!(
+            self.summon,
+            Some(SummonSource::Reinforcement { .. } | SummonSource::Summoner { .. })
+        )
+    }
+
+    /// Whether this combatant joined mid-combat
+    pub fn is_summoned(&self) -> bool {
+        self.summon.is_some()
+    }
+}
+
+impl CombatEncounter {
+    /// Spend Fear to bring an adversary into the fight
+    ///
+    /// Reinforcements act at the end of the current round. Player
+    /// characters can't be brought in this way.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::simulation::{CombatEncounter, CombatantBuilder};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Goblin")
+    ///         .with_hp(4)
+    ///         .with_evasion(10)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// encounter.start();
+    /// encounter.gain_fear(2);
+    ///
+    /// let id = encounter
+    ///     .spawn_reinforcement(
+    ///         CombatantBuilder::adversary("Goblin Archer")
+    ///             .with_hp(3)
+    ///             .with_evasion(12)
+    ///             .build()
+    ///             .unwrap(),
+    ///         1,
+    ///     )
+    ///     .unwrap();
+    /// assert_eq!(encounter.fear.current, 1);
+    /// assert_eq!(encounter.turn_order.last(), Some(&id));
+    /// ```
+    pub fn spawn_reinforcement(
+        &mut self,
+        mut combatant: Combatant,
+        fear_cost: u8,
+    ) -> Result<CombatantId, EngineError> {
+        let CombatantKind::Adversary(data) = &mut combatant.kind else {
+            return Err(EngineError::NotAnAdversary {
+                name: combatant.name.to_string(),
+            });
+        };
+        data.allied = false;
+        self.spend_fear(fear_cost)?;
+
+        let source = SummonSource::Reinforcement {
+            fear_spent: fear_cost,
+        };
+        combatant.summon = Some(source);
+        let id = self.add_combatant(combatant);
+
+        if self.round > 0 {
+            self.turn_order.push(id);
+        }
+        self.log(CombatEvent::CombatantSummoned {
+            combatant: id,
+            source,
+        });
+        Ok(id)
+    }
+
+    /// Summon a creature that fights on the summoner's side
+    ///
+    /// The summon acts immediately after its summoner in the turn order.
+    /// Only adversaries can be summoned, not player characters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::simulation::{CombatEncounter, CombatantBuilder};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// let druid = encounter.add_combatant(
+    ///     CombatantBuilder::player("Moss", Class::Druid, Ancestry::Fungril)
+    ///         .with_attributes(Attributes::from_array([0, 1, 1, 2, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// let wolf = encounter
+    ///     .summon(
+    ///         druid,
+    ///         CombatantBuilder::adversary("Spirit Wolf")
+    ///             .with_hp(3)
+    ///             .with_evasion(11)
+    ///             .build()
+    ///             .unwrap(),
+    ///     )
+    ///     .unwrap();
+    /// assert!(encounter.get(wolf).unwrap().is_player());
+    /// assert_eq!(encounter.get(wolf).unwrap().controller(), Some(druid));
+    /// ```
+    pub fn summon(
+        &mut self,
+        summoner: CombatantId,
+        combatant: Combatant,
+    ) -> Result<CombatantId, EngineError> {
+        self.join_under(summoner, combatant, SummonSource::Summoner { summoner })
+    }
+
+    /// Bring in a combatant on `summoner`'s side, acting right after them
+    pub(crate) fn join_under(
+        &mut self,
+        summoner: CombatantId,
+        mut combatant: Combatant,
+        source: SummonSource,
+    ) -> Result<CombatantId, EngineError> {
+        let owner = self
+            .get(summoner)
+            .ok_or(EngineError::UnknownCombatant { id: summoner.0 })?;
+        if !owner.is_alive() {
+            return Err(EngineError::CombatantDefeated {
+                name: owner.name.to_string(),
+            });
+        }
+
+        let allied = owner.is_player();
+        let CombatantKind::Adversary(data) = &mut combatant.kind else {
+            return Err(EngineError::NotAnAdversary {
+                name: combatant.name.to_string(),
+            });
+        };
+        data.allied = allied;
+
+        combatant.summon = Some(source);
+        let id = self.add_combatant(combatant);
+
+        if self.round > 0 {
+            match self.turn_order.iter().position(|&t| t == summoner) {
+                Some(position) => {
+                    self.turn_order.insert(position + 1, id);
+                    if position < self.current_turn {
+                        self.current_turn += 1;
+                    }
+                }
+                None => self.turn_order.push(id),
+            }
+        }
+        self.log(CombatEvent::CombatantSummoned {
+            combatant: id,
+            source,
+        });
+        Ok(id)
+    }
+
+    /// Ids of every combatant controlled by `summoner`
+    pub fn summons_of(&self, summoner: CombatantId) -> Vec<CombatantId> {
+        self.combatants
+            .iter()
+            .filter(|c| c.controller() == Some(summoner))
+            .map(|c| c.id)
+            .collect()
+    }
+
+    /// Remove every combatant controlled by `summoner`
+    pub fn dismiss_summons(&mut self, summoner: CombatantId) -> Vec<Combatant> {
+        self.summons_of(summoner)
+            .into_iter()
+            .filter_map(|id| self.remove_combatant(id).ok())
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Attributes, Class};
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::test_support::goblin;
+    use crate::test_support::player;
+
+    fn create_test_encounter() -> (CombatEncounter, CombatantId, CombatantId) {
+        let mut encounter = CombatEncounter::new(5);
+        let druid = encounter.add_combatant(
+            CombatantBuilder::player("Druid", Class::Druid, Ancestry::Human)
+                .with_attributes(Attributes::from_array([0, 1, 1, 2, 0, -1]).unwrap())
+                .build()
+                .unwrap(),
+        );
+        let goblin = encounter.add_combatant(goblin("Goblin"));
+        (encounter, druid, goblin)
+    }
+
+    #[test]
+    fn test_reinforcement_costs_fear() {
+        let (mut encounter, _, _) = create_test_encounter();
+
+        let result = encounter.spawn_reinforcement(
+            CombatantBuilder::adversary("Orc")
+                .with_hp(6)
+                .with_evasion(10)
+                .build()
+                .unwrap(),
+            2,
+        );
+        assert!(result.is_err());
+        assert_eq!(encounter.combatants.len(), 2);
+
+        encounter.gain_fear(2);
+        let orc = encounter
+            .spawn_reinforcement(
+                CombatantBuilder::adversary("Orc")
+                    .with_hp(6)
+                    .with_evasion(10)
+                    .build()
+                    .unwrap(),
+                2,
+            )
+            .unwrap();
+        assert_eq!(encounter.fear.current, 0);
+        assert_eq!(
+            encounter.get(orc).unwrap().summon,
+            Some(SummonSource::Reinforcement { fear_spent: 2 })
+        );
+    }
+
+    #[test]
+    fn test_reinforcement_before_start_waits_for_initiative() {
+        let (mut encounter, _, _) = create_test_encounter();
+        encounter
+            .spawn_reinforcement(
+                CombatantBuilder::adversary("Orc")
+                    .with_hp(6)
+                    .with_evasion(10)
+                    .build()
+                    .unwrap(),
+                0,
+            )
+            .unwrap();
+
+        assert!(encounter.turn_order.is_empty());
+        encounter.start();
+        assert_eq!(encounter.turn_order.len(), 3);
+    }
+
+    #[test]
+    fn test_summon_acts_after_summoner() {
+        let (mut encounter, druid, _) = create_test_encounter();
+        encounter.start();
+
+        let wolf = encounter
+            .summon(
+                druid,
+                CombatantBuilder::adversary("Wolf")
+                    .with_hp(3)
+                    .with_evasion(11)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        let druid_position = encounter
+            .turn_order
+            .iter()
+            .position(|&id| id == druid)
+            .unwrap();
+        assert_eq!(encounter.turn_order[druid_position + 1], wolf);
+    }
+
+    #[test]
+    fn test_summon_keeps_current_turn() {
+        let (mut encounter, druid, _) = create_test_encounter();
+        encounter.start();
+        encounter.next_turn();
+        let acting = encounter.current_combatant_id();
+
+        encounter
+            .summon(
+                druid,
+                CombatantBuilder::adversary("Wolf")
+                    .with_hp(3)
+                    .with_evasion(11)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        assert_eq!(encounter.current_combatant_id(), acting);
+    }
+
+    #[test]
+    fn test_summon_joins_summoner_side() {
+        let (mut encounter, druid, goblin) = create_test_encounter();
+
+        let wolf = encounter
+            .summon(
+                druid,
+                CombatantBuilder::adversary("Wolf")
+                    .with_hp(3)
+                    .with_evasion(11)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+        let imp = encounter
+            .summon(
+                goblin,
+                CombatantBuilder::adversary("Imp")
+                    .with_hp(2)
+                    .with_evasion(12)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        assert!(encounter.get(wolf).unwrap().is_player());
+        assert!(!encounter.get(imp).unwrap().is_player());
+        assert_eq!(encounter.summons_of(druid), vec![wolf]);
+    }
+
+    #[test]
+    fn test_player_characters_cannot_be_summoned() {
+        let (mut encounter, druid, _) = create_test_encounter();
+        encounter.gain_fear(1);
+        let player = player("Imp", Class::Rogue, Ancestry::Goblin)
+            .build()
+            .unwrap();
+
+        assert!(matches!(
+            encounter.summon(druid, player.clone()),
+            Err(EngineError::NotAnAdversary { .. })
+        ));
+        assert!(matches!(
+            encounter.spawn_reinforcement(player, 1),
+            Err(EngineError::NotAnAdversary { .. })
+        ));
+        assert_eq!(encounter.fear.current, 1);
+    }
+
+    #[test]
+    fn test_defeated_cannot_summon() {
+        let (mut encounter, druid, _) = create_test_encounter();
+        encounter.get_mut(druid).unwrap().take_damage(100);
+
+        assert!(encounter
+            .summon(
+                druid,
+                CombatantBuilder::adversary("Wolf")
+                    .with_hp(3)
+                    .with_evasion(11)
+                    .build()
+                    .unwrap()
+            )
+            .is_err());
+        assert!(encounter
+            .summon(
+                CombatantId(99),
+                CombatantBuilder::adversary("Wolf")
+                    .with_hp(3)
+                    .with_evasion(11)
+                    .build()
+                    .unwrap()
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_summon_removed_when_it_drops() {
+        let (mut encounter, druid, _) = create_test_encounter();
+        encounter.start();
+        let wolf = encounter
+            .summon(
+                druid,
+                CombatantBuilder::adversary("Wolf")
+                    .with_hp(3)
+                    .with_evasion(11)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        encounter.apply_damage(wolf, 100).unwrap();
+
+        assert!(encounter.get(wolf).is_none());
+        assert!(!encounter.turn_order.contains(&wolf));
+        assert!(encounter
+            .events
+            .contains(&CombatEvent::CombatantDefeated { combatant: wolf }));
+    }
+
+    #[test]
+    fn test_removing_summoner_dismisses_summons() {
+        let (mut encounter, druid, _) = create_test_encounter();
+        let wolf = encounter
+            .summon(
+                druid,
+                CombatantBuilder::adversary("Wolf")
+                    .with_hp(3)
+                    .with_evasion(11)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        encounter.remove_combatant(druid).unwrap();
+
+        assert!(encounter.get(wolf).is_none());
+        assert_eq!(encounter.combatants.len(), 1);
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_summon_source_serialization() {
+        let (mut encounter, druid, _) = create_test_encounter();
+        encounter
+            .summon(
+                druid,
+                CombatantBuilder::adversary("Wolf")
+                    .with_hp(3)
+                    .with_evasion(11)
+                    .build()
+                    .unwrap(),
+            )
+            .unwrap();
+
+        let json = serde_json::to_string(&encounter).unwrap();
+        let loaded: CombatEncounter = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(loaded.combatants[2].controller(), Some(druid));
+        assert_eq!(loaded.events, encounter.events);
+    }
+}
@@ -0,0 +1,205 @@
+//! Action tokens - the optional action tracker variant
+//!
+//! Under [`TurnModel::ActionTokens`] nobody rolls initiative. Each player
+//! character holds a pool of tokens, sized by the table's
+//! [`ActionTokenRule`], and spends one to take the spotlight. Spent tokens
+//! go on the tracker; when the GM takes the spotlight, each one on it
+//! lets an adversary act. Pools refill as each round begins, or once
+//! every character has spent out, as the rule says.
+
+use crate::combat::events::CombatEvent;
+use crate::combat::simulation::{CombatEncounter, CombatantId, TurnModel};
+use crate::error::{EngineError, Resource};
+use crate::rules::{ActionTokenRule, TokenRefresh};
+use serde::{Deserialize, Serialize};
+
+/// One character's unspent tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TokenPool {
+    pub combatant: CombatantId,
+    pub tokens: u8,
+}
+
+/// Every character's pool and the tokens waiting on the tracker
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ActionTracker {
+    pub pools: Vec<TokenPool>,
+    /// Tokens spent since the GM last took the spotlight
+    pub tracker: u8,
+}
+
+impl ActionTracker {
+    /// Tokens a character has left, if they have a pool
+    pub fn tokens(&self, combatant: CombatantId) -> Option<u8> {
+        self.pools
+            .iter()
+            .find(|pool| pool.combatant == combatant)
+            .map(|pool| pool.tokens)
+    }
+}
+
+impl CombatEncounter {
+    /// Refill every living player character's pool
+    ///
+    /// `start` and, under [`TokenRefresh::EachRound`], each new round do
+    /// this for encounters using action tokens. Summons and allies act
+    /// with whoever brought them and get no pool of their own.
+    pub fn refresh_action_tokens(&mut self) {
+        let ActionTokenRule { per_character, .. } = self.rules.action_tokens;
+        self.action_tokens.pools = self
+            .combatants
+            .iter()
+            .filter(|combatant| combatant.player_data().is_some() && combatant.is_alive())
+            .map(|combatant| TokenPool {
+                combatant: combatant.id,
+                tokens: per_character,
+            })
+            .collect();
+        self.log(CombatEvent::ActionTokensRefreshed {
+            tokens: per_character,
+        });
+    }
+
+    /// Spend one of a character's tokens to take the spotlight
+    ///
+    /// The token goes on the tracker for the GM. Returns how many the
+    /// character has left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder, TurnModel};
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// encounter.turn_model = TurnModel::ActionTokens;
+    /// let grom = encounter.add_combatant(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// encounter.start();
+    ///
+    /// assert_eq!(encounter.spend_action_token(grom).unwrap(), 2);
+    /// assert_eq!(encounter.gm_spotlight(), 1);
+    /// ```
+    pub fn spend_action_token(&mut self, combatant: CombatantId) -> Result<u8, EngineError> {
+        if self.turn_model != TurnModel::ActionTokens {
+            return Err(EngineError::InvalidTarget {
+                reason: String::from("This encounter doesn't use action tokens"),
+            });
+        }
+        let index = self.require(combatant)?;
+        let name = self.combatants[index].name.to_string();
+        if !self.combatants[index].is_alive() {
+            return Err(EngineError::CombatantDefeated { name });
+        }
+        let Some(pool) = self
+            .action_tokens
+            .pools
+            .iter_mut()
+            .find(|pool| pool.combatant == combatant)
+        else {
+            return Err(EngineError::InvalidTarget {
+                reason: format!("{} has no action tokens", name),
+            });
+        };
+        if pool.tokens == 0 {
+            return Err(EngineError::InsufficientResource {
+                resource: Resource::ActionTokens,
+                have: 0,
+                need: 1,
+            });
+        }
+        pool.tokens -= 1;
+        let remaining = pool.tokens;
+        self.action_tokens.tracker = self.action_tokens.tracker.saturating_add(1);
+        self.log(CombatEvent::ActionTokenSpent {
+            combatant,
+            remaining,
+        });
+
+        if self.rules.action_tokens.refresh == TokenRefresh::WhenSpent
+            && self.action_tokens.pools.iter().all(|pool| pool.tokens == 0)
+        {
+            self.refresh_action_tokens();
+        }
+        Ok(remaining)
+    }
+
+    /// The GM takes the spotlight, clearing the tracker
+    ///
+    /// Returns how many adversary actions the spent tokens bought.
+    pub fn gm_spotlight(&mut self) -> u8 {
+        let actions = std::mem::take(&mut self.action_tokens.tracker);
+        self.log(CombatEvent::GmSpotlight { actions });
+        actions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rules::RulesConfig;
+    use crate::test_support;
+
+    fn create_test_encounter(refresh: TokenRefresh) -> (CombatEncounter, CombatantId, CombatantId) {
+        let mut encounter = test_support::create_test_encounter();
+        encounter.turn_model = TurnModel::ActionTokens;
+        encounter.rules = RulesConfig::default().with_action_tokens(ActionTokenRule {
+            per_character: 2,
+            refresh,
+        });
+        encounter.start();
+        (encounter, CombatantId(0), CombatantId(1))
+    }
+
+    #[test]
+    fn test_spending_fills_the_tracker() {
+        let (mut encounter, grom, goblin) = create_test_encounter(TokenRefresh::EachRound);
+        assert_eq!(encounter.action_tokens.tokens(grom), Some(2));
+        assert_eq!(encounter.action_tokens.tokens(goblin), None);
+
+        encounter.spend_action_token(grom).unwrap();
+        encounter.spend_action_token(grom).unwrap();
+        assert!(matches!(
+            encounter.spend_action_token(grom),
+            Err(EngineError::InsufficientResource {
+                resource: Resource::ActionTokens,
+                ..
+            })
+        ));
+        assert!(matches!(
+            encounter.spend_action_token(goblin),
+            Err(EngineError::InvalidTarget { .. })
+        ));
+
+        assert_eq!(encounter.gm_spotlight(), 2);
+        assert_eq!(encounter.gm_spotlight(), 0);
+
+        encounter.end_round();
+        assert_eq!(encounter.action_tokens.tokens(grom), Some(2));
+    }
+
+    #[test]
+    fn test_refresh_when_everyone_is_spent() {
+        let (mut encounter, grom, _) = create_test_encounter(TokenRefresh::WhenSpent);
+        encounter.spend_action_token(grom).unwrap();
+        encounter.end_round();
+        assert_eq!(encounter.action_tokens.tokens(grom), Some(1));
+
+        assert_eq!(encounter.spend_action_token(grom).unwrap(), 0);
+        assert_eq!(encounter.action_tokens.tokens(grom), Some(2));
+        assert_eq!(encounter.action_tokens.tracker, 2);
+    }
+
+    #[test]
+    fn test_tokens_need_the_turn_model() {
+        let (mut encounter, grom, _) = create_test_encounter(TokenRefresh::EachRound);
+        encounter.turn_model = TurnModel::Agility;
+        assert!(encounter.spend_action_token(grom).is_err());
+    }
+}
@@ -0,0 +1,167 @@
+//! Triggered features - passive stat block features the engine fires
+//!
+//! Many adversary features are reactions written into the stat block:
+//! "when this adversary takes damage from a melee attack, the attacker
+//! marks a Stress". A `TriggeredFeature` pairs the moment it fires with
+//! what it does, and the encounter fires it while resolving attacks so
+//! the GM doesn't have to remember.
+
+use crate::combat::conditions::Condition;
+use crate::combat::damage::DamageOverTime;
+use alloc::string::String;
+use serde::{Deserialize, Serialize};
+use strum_macros::Display;
+
+/// When a triggered feature fires
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Trigger {
+    /// It takes damage from an attack in Very Close range
+    #[strum(to_string = "When damaged by a melee attack")]
+    DamagedByMelee,
+    /// It takes damage from any attack
+    #[strum(to_string = "When damaged by an attack")]
+    Damaged,
+    /// An attack against it misses
+    #[strum(to_string = "When an attack against it misses")]
+    Missed,
+    /// It hits with an attack
+    #[strum(to_string = "When it hits with an attack")]
+    Hits,
+    /// An attack drops it to 0 HP
+    #[strum(to_string = "When it is defeated")]
+    Defeated,
+    /// An opponent leaves its melee range, under the optional
+    /// opportunity rule
+    #[strum(to_string = "When an opponent leaves its melee range")]
+    OpponentLeavesMelee,
+}
+
+/// What a triggered feature does
+///
+/// The opponent is the other side of the attack that set it off: the
+/// attacker, or the target for [`Trigger::Hits`]. For
+/// [`Trigger::OpponentLeavesMelee`] it is whoever moved away.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum TriggerEffect {
+    /// The opponent marks Stress
+    OpponentMarksStress(u8),
+    /// The opponent takes damage, reduced by their armor
+    OpponentTakesDamage(u16),
+    /// The opponent gains a condition
+    OpponentGains(Condition),
+    /// The opponent starts taking ongoing damage
+    OpponentSuffers(DamageOverTime),
+    /// The GM gains Fear
+    GainFear(u8),
+    /// It clears Stress
+    ClearStress(u8),
+    /// It clears Hit Points
+    Heal(u8),
+    /// It gains a condition
+    Gains(Condition),
+}
+
+impl core::fmt::Display for TriggerEffect {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TriggerEffect::OpponentMarksStress(amount) => {
+                write!(f, "the opponent marks {} Stress", amount)
+            }
+            TriggerEffect::OpponentTakesDamage(amount) => {
+                write!(f, "the opponent takes {} damage", amount)
+            }
+            TriggerEffect::OpponentGains(condition) => {
+                write!(f, "the opponent becomes {}", condition)
+            }
+            TriggerEffect::OpponentSuffers(ongoing) => {
+                write!(
+                    f,
+                    "the opponent suffers {} ({})",
+                    ongoing.name, ongoing.dice
+                )
+            }
+            TriggerEffect::GainFear(amount) => write!(f, "the GM gains {} Fear", amount),
+            TriggerEffect::ClearStress(amount) => write!(f, "it clears {} Stress", amount),
+            TriggerEffect::Heal(amount) => write!(f, "it clears {} HP", amount),
+            TriggerEffect::Gains(condition) => write!(f, "it becomes {}", condition),
+        }
+    }
+}
+
+/// A passive feature that fires by itself
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::combat::{Trigger, TriggerEffect, TriggeredFeature};
+///
+/// let spines = TriggeredFeature::new(
+///     "Spiny Hide",
+///     Trigger::DamagedByMelee,
+///     TriggerEffect::OpponentMarksStress(1),
+/// );
+/// assert_eq!(
+///     spines.to_string(),
+///     "Spiny Hide: When damaged by a melee attack, the opponent marks 1 Stress"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TriggeredFeature {
+    pub name: String,
+    pub trigger: Trigger,
+    pub effect: TriggerEffect,
+}
+
+impl TriggeredFeature {
+    /// Create a triggered feature
+    pub fn new(name: impl Into<String>, trigger: Trigger, effect: TriggerEffect) -> Self {
+        Self {
+            name: name.into(),
+            trigger,
+            effect,
+        }
+    }
+}
+
+impl core::fmt::Display for TriggeredFeature {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}: {}, {}", self.name, self.trigger, self.effect)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effect_display() {
+        assert_eq!(
+            TriggerEffect::OpponentGains(Condition::Vulnerable).to_string(),
+            "the opponent becomes Vulnerable"
+        );
+        assert_eq!(
+            TriggerEffect::GainFear(1).to_string(),
+            "the GM gains 1 Fear"
+        );
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_feature_serialization() {
+        let feature = TriggeredFeature::new(
+            "Cloud",
+            Trigger::Missed,
+            TriggerEffect::Gains(Condition::Hidden),
+        );
+        let json = serde_json::to_string(&feature).unwrap();
+        assert_eq!(
+            json,
+            r#"{"name":"Cloud","trigger":"Missed","effect":{"Gains":"Hidden"}}"#
+        );
+        let loaded: TriggeredFeature = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, feature);
+    }
+}
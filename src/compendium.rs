@@ -0,0 +1,388 @@
+//! Compendium export for reference sites
+//!
+//! Dumps a content registry's cards, adversaries and weapons as one file
+//! per entry under `cards/`, `adversaries/` and
+//! `weapons/`, each with an index listing its entries. JSON files hold the
+//! entry as saved; Markdown files start with YAML front matter, which
+//! static site generators such as Hugo, Jekyll and Zola read as page data.
+
+use crate::cards::{ActionCost, DomainCard, Range};
+use crate::combat::adversary::Adversary;
+use crate::content::ContentRegistry;
+use crate::error::EngineError;
+use crate::items::Weapon;
+use serde::Serialize;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How compendium entries are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompendiumFormat {
+    Json,
+    Markdown,
+}
+
+impl CompendiumFormat {
+    /// File extension for this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            CompendiumFormat::Json => "json",
+            CompendiumFormat::Markdown => "md",
+        }
+    }
+}
+
+/// One file of an export, relative to the export directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompendiumFile {
+    pub path: PathBuf,
+    pub contents: String,
+}
+
+/// An index entry: the title and the file it links to
+#[derive(Serialize)]
+struct IndexEntry<'a> {
+    name: &'a str,
+    file: String,
+}
+
+/// Render every entry, and an index per section, without writing anything
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::compendium::{self, CompendiumFormat};
+/// use daggerheart_engine::content::ContentRegistry;
+/// use std::path::Path;
+///
+/// let files = compendium::render(&ContentRegistry::builtin(), CompendiumFormat::Markdown).unwrap();
+/// let card = files
+///     .iter()
+///     .find(|file| file.path == Path::new("cards/get-back-up.md"))
+///     .unwrap();
+/// assert!(card.contents.starts_with("---\ntitle: \"Get Back Up\"\n"));
+/// ```
+pub fn render(
+    content: &ContentRegistry,
+    format: CompendiumFormat,
+) -> Result<Vec<CompendiumFile>, EngineError> {
+    let mut files = Vec::new();
+    section(
+        &mut files,
+        "cards",
+        &content.cards,
+        format,
+        |c| &c.name,
+        card_markdown,
+    )?;
+    section(
+        &mut files,
+        "adversaries",
+        &content.adversaries,
+        format,
+        |a| &a.name,
+        adversary_markdown,
+    )?;
+    section(
+        &mut files,
+        "weapons",
+        &content.weapons,
+        format,
+        |w| &w.name,
+        weapon_markdown,
+    )?;
+    Ok(files)
+}
+
+/// Write the compendium under `dir`, creating folders as needed
+///
+/// Returns the paths written.
+pub fn export(
+    content: &ContentRegistry,
+    format: CompendiumFormat,
+    dir: impl AsRef<Path>,
+) -> Result<Vec<PathBuf>, EngineError> {
+    let dir = dir.as_ref();
+    let mut written = Vec::new();
+    for file in render(content, format)? {
+        let path = dir.join(&file.path);
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(&path, file.contents));
+        result.map_err(|error| EngineError::File {
+            path: path.clone(),
+            source: Box::new(error.into()),
+        })?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// A section's entries and its index
+fn section<T: Serialize>(
+    files: &mut Vec<CompendiumFile>,
+    name: &str,
+    entries: &[T],
+    format: CompendiumFormat,
+    title: impl Fn(&T) -> &String,
+    markdown: impl Fn(&T) -> String,
+) -> Result<(), EngineError> {
+    let extension = format.extension();
+    let mut index = Vec::new();
+    let mut used = Vec::new();
+    for entry in entries {
+        // Homebrew can repeat a name; number the repeats
+        let base = slug(title(entry));
+        let mut file = base.clone();
+        let mut n = 1;
+        while used.contains(&file) {
+            n += 1;
+            file = format!("{}-{}", base, n);
+        }
+        used.push(file.clone());
+        let file = format!("{}.{}", file, extension);
+
+        let contents = match format {
+            CompendiumFormat::Json => serde_json::to_string_pretty(entry)?,
+            CompendiumFormat::Markdown => markdown(entry),
+        };
+        files.push(CompendiumFile {
+            path: Path::new(name).join(&file),
+            contents,
+        });
+        index.push(IndexEntry {
+            name: title(entry),
+            file,
+        });
+    }
+
+    let contents = match format {
+        CompendiumFormat::Json => serde_json::to_string_pretty(&index)?,
+        CompendiumFormat::Markdown => {
+            let mut md = front_matter(&[("title", quote(&capitalize(name)))]);
+            for entry in &index {
+                let _ = writeln!(md, "- [{}]({})", entry.name, entry.file);
+            }
+            md
+        }
+    };
+    files.push(CompendiumFile {
+        path: Path::new(name).join(format!("index.{}", extension)),
+        contents,
+    });
+    Ok(())
+}
+
+fn card_markdown(card: &DomainCard) -> String {
+    let cost = match card.action_cost {
+        ActionCost::Major => "Major action",
+        ActionCost::Minor => "Minor action",
+        ActionCost::Reaction => "Reaction",
+        ActionCost::Free => "Free action",
+    };
+    let mut md = front_matter(&[
+        ("title", quote(&card.name)),
+        ("id", quote(&card.id)),
+        ("domain", quote(&card.domain.to_string())),
+        ("level", card.level_requirement.to_string()),
+        ("cost", quote(cost)),
+    ]);
+    let _ = writeln!(md, "# {}\n", card.name);
+    let _ = writeln!(
+        md,
+        "*{} · level {} · {}*\n",
+        card.domain, card.level_requirement, cost
+    );
+    let _ = writeln!(md, "{}", card.description);
+    md
+}
+
+fn adversary_markdown(adversary: &Adversary) -> String {
+    let mut md = front_matter(&[
+        ("title", quote(&adversary.name)),
+        ("tier", adversary.tier.to_string()),
+        ("role", quote(&adversary.role.to_string())),
+        ("difficulty", adversary.difficulty.to_string()),
+    ]);
+    let _ = writeln!(md, "# {}\n", adversary.name);
+    let _ = writeln!(md, "*Tier {} {}*\n", adversary.tier, adversary.role);
+    let _ = writeln!(md, "- **Difficulty:** {}", adversary.difficulty);
+    let _ = writeln!(
+        md,
+        "- **Thresholds:** {}/{}",
+        adversary.major_threshold, adversary.severe_threshold
+    );
+    let _ = writeln!(md, "- **HP:** {}", adversary.hp);
+    let _ = writeln!(md, "- **Stress:** {}", adversary.stress);
+    let _ = writeln!(md, "- **Attack:** {:+}", adversary.attack_modifier);
+    let _ = writeln!(md, "- **Damage:** {}", adversary.damage);
+    if !adversary.features.is_empty() {
+        let _ = writeln!(md, "\n## Features\n");
+        for feature in &adversary.features {
+            let _ = writeln!(md, "- **{}:** {}", feature.name, feature.description);
+        }
+    }
+    if !adversary.triggers.is_empty() {
+        let _ = writeln!(md, "\n## Triggered Features\n");
+        for feature in &adversary.triggers {
+            let _ = writeln!(
+                md,
+                "- **{}:** {}, {}",
+                feature.name, feature.trigger, feature.effect
+            );
+        }
+    }
+    if !adversary.immunities.is_empty() {
+        let immunities: Vec<String> = adversary.immunities.iter().map(|c| c.to_string()).collect();
+        let _ = writeln!(md, "\n**Immune to:** {}", immunities.join(", "));
+    }
+    if let Some(mount) = &adversary.mount {
+        let _ = writeln!(
+            md,
+            "\n**Mounted on:** {} (HP {}, Armor {})",
+            mount.name, mount.hp.maximum, mount.armor
+        );
+    }
+    md
+}
+
+fn weapon_markdown(weapon: &Weapon) -> String {
+    let kind = if weapon.magic { "Magic" } else { "Physical" };
+    let mut md = front_matter(&[
+        ("title", quote(&weapon.name)),
+        ("trait", quote(&weapon.attack_trait.to_string())),
+        ("damage_type", quote(kind)),
+    ]);
+    let _ = writeln!(md, "# {}\n", weapon.name);
+    let _ = writeln!(md, "- **Trait:** {}", weapon.attack_trait);
+    let range = match weapon.range {
+        Range::VeryClose => "Very Close",
+        Range::Close => "Close",
+        Range::Far => "Far",
+        Range::Any => "Any",
+    };
+    let _ = writeln!(md, "- **Range:** {}", range);
+    let _ = writeln!(
+        md,
+        "- **Damage:** {} {}",
+        weapon.damage(1),
+        kind.to_lowercase()
+    );
+    let _ = writeln!(md, "- **Burden:** {}", weapon.burden);
+    for feature in &weapon.features {
+        let _ = writeln!(md, "- **{}:** {}", feature.name, feature.description);
+    }
+    md
+}
+
+/// YAML front matter with already formatted values
+fn front_matter(fields: &[(&str, String)]) -> String {
+    let mut md = String::from("---\n");
+    for (key, value) in fields {
+        let _ = writeln!(md, "{}: {}", key, value);
+    }
+    md.push_str("---\n\n");
+    md
+}
+
+/// A YAML double-quoted string
+fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+/// A file name from a title: lowercase words joined by hyphens
+fn slug(title: &str) -> String {
+    let slug = title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        String::from("entry")
+    } else {
+        slug
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::AdversaryRole;
+
+    #[test]
+    fn test_json_entries_round_trip() {
+        let content = ContentRegistry::builtin();
+        let files = render(&content, CompendiumFormat::Json).unwrap();
+
+        let cards = files
+            .iter()
+            .filter(|file| file.path.starts_with("cards") && !file.path.ends_with("index.json"))
+            .count();
+        assert_eq!(cards, content.cards.len());
+
+        let index = files
+            .iter()
+            .find(|file| file.path == Path::new("adversaries/index.json"))
+            .unwrap();
+        let index: serde_json::Value = serde_json::from_str(&index.contents).unwrap();
+        let first = &index[0];
+        let entry = files
+            .iter()
+            .find(|file| {
+                file.path == Path::new("adversaries").join(first["file"].as_str().unwrap())
+            })
+            .unwrap();
+        let adversary: Adversary = serde_json::from_str(&entry.contents).unwrap();
+        assert_eq!(adversary.name, first["name"]);
+    }
+
+    #[test]
+    fn test_repeated_names_get_their_own_files() {
+        let mut content = ContentRegistry::new();
+        for _ in 0..2 {
+            content.adversaries.push(Adversary::new(
+                "Jagged \"Knife\" Bandit",
+                1,
+                AdversaryRole::Standard,
+            ));
+        }
+        let files = render(&content, CompendiumFormat::Markdown).unwrap();
+        let paths: Vec<_> = files.iter().map(|file| file.path.clone()).collect();
+
+        assert!(paths.contains(&PathBuf::from("adversaries/jagged-knife-bandit.md")));
+        assert!(paths.contains(&PathBuf::from("adversaries/jagged-knife-bandit-2.md")));
+        let first = files
+            .iter()
+            .find(|file| file.path == Path::new("adversaries/jagged-knife-bandit.md"))
+            .unwrap();
+        assert!(first
+            .contents
+            .starts_with("---\ntitle: \"Jagged \\\"Knife\\\" Bandit\"\n"));
+    }
+
+    #[test]
+    fn test_export_writes_files() {
+        let dir = std::env::temp_dir().join("daggerheart_compendium_test");
+        let written = export(
+            &ContentRegistry::builtin(),
+            CompendiumFormat::Markdown,
+            &dir,
+        )
+        .unwrap();
+
+        assert!(written.iter().all(|path| path.exists()));
+        assert!(dir.join("weapons/longsword.md").exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
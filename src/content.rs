@@ -0,0 +1,646 @@
+//! Content registry - adversary stat blocks, domain cards, weapons and
+//! armor to browse
+//!
+//! `ContentRegistry::builtin()` is a small starter set for prep and
+//! testing. Groups with their own homebrew can build a registry from
+//! scratch, or save one to a file and load it back, and check it with
+//! [`lint`].
+//!
+//! Lookups go through an index of interned [`ContentId`]s built the
+//! first time one is needed and kept up to date as content is added, so
+//! they take constant time however much content is loaded.
+
+pub mod id;
+pub mod lint;
+
+pub use id::ContentId;
+
+use crate::cards::{ActionCost, DomainCard};
+use crate::character::Domain;
+use crate::combat::adversary::{Adversary, AdversaryRole};
+use crate::core::dice::DamageDice;
+use crate::items::{Armor, Weapon};
+#[cfg(feature = "persistence")]
+use crate::storage::Persist;
+use id::{ContentIndex, Kind};
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// A collection of adversaries, domain cards, weapons and armor
+///
+/// The lists can be changed directly, but call
+/// [`reindex`](ContentRegistry::reindex) afterwards: until then, lookups
+/// can miss entries that moved or were renamed (though they never return
+/// the wrong one).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ContentRegistry {
+    #[serde(default)]
+    pub adversaries: Vec<Adversary>,
+    #[serde(default)]
+    pub cards: Vec<DomainCard>,
+    #[serde(default)]
+    pub weapons: Vec<Weapon>,
+    #[serde(default)]
+    pub armor: Vec<Armor>,
+    /// Built on first lookup and extended by the `add_*` methods
+    #[serde(skip)]
+    index: OnceLock<ContentIndex>,
+}
+
+impl PartialEq for ContentRegistry {
+    fn eq(&self, other: &Self) -> bool {
+        self.adversaries == other.adversaries
+            && self.cards == other.cards
+            && self.weapons == other.weapons
+            && self.armor == other.armor
+    }
+}
+
+impl ContentRegistry {
+    /// Create an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The built-in starter adversaries, cards, weapons and armor
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::AdversaryRole;
+    /// use daggerheart_engine::content::ContentRegistry;
+    ///
+    /// let registry = ContentRegistry::builtin();
+    /// let bruisers = registry.find_adversaries(Some(1), Some(AdversaryRole::Bruiser));
+    /// assert!(bruisers.iter().any(|a| a.name == "Bear"));
+    /// ```
+    pub fn builtin() -> Self {
+        let adversaries = vec![
+            Adversary::new("Jagged Knife Bandit", 1, AdversaryRole::Standard)
+                .with_difficulty(12)
+                .with_thresholds(8, 14)
+                .with_hp(5)
+                .with_stress(3)
+                .with_attack(1, DamageDice::d8(1).with_bonus(1)),
+            Adversary::new("Jagged Knife Lackey", 1, AdversaryRole::Minion)
+                .with_difficulty(9)
+                .with_thresholds(1, 1)
+                .with_hp(1)
+                .with_stress(1)
+                .with_attack(-2, DamageDice::new(vec![]).with_bonus(2)),
+            Adversary::new("Bear", 1, AdversaryRole::Bruiser)
+                .with_difficulty(14)
+                .with_thresholds(9, 17)
+                .with_hp(7)
+                .with_stress(2)
+                .with_attack(1, DamageDice::d8(1).with_bonus(3)),
+            Adversary::new("Dire Wolf", 1, AdversaryRole::Skulk)
+                .with_difficulty(12)
+                .with_thresholds(5, 9)
+                .with_hp(4)
+                .with_stress(3)
+                .with_attack(2, DamageDice::d6(1).with_bonus(2)),
+            Adversary::new("Giant Mosquitoes", 1, AdversaryRole::Horde)
+                .with_difficulty(10)
+                .with_thresholds(5, 9)
+                .with_hp(6)
+                .with_stress(3)
+                .with_attack(-2, DamageDice::d8(1).with_bonus(3)),
+            Adversary::new("Merchant", 1, AdversaryRole::Social)
+                .with_difficulty(12)
+                .with_thresholds(4, 8)
+                .with_hp(3)
+                .with_stress(3)
+                .with_attack(-4, DamageDice::d6(1)),
+            Adversary::new("Cave Ogre", 1, AdversaryRole::Solo)
+                .with_difficulty(13)
+                .with_thresholds(8, 15)
+                .with_hp(8)
+                .with_stress(3)
+                .with_attack(1, DamageDice::d10(1).with_bonus(2)),
+            Adversary::new("Archer Squadron", 2, AdversaryRole::Horde)
+                .with_difficulty(13)
+                .with_thresholds(8, 16)
+                .with_hp(4)
+                .with_stress(3)
+                .with_attack(0, DamageDice::d6(2).with_bonus(3)),
+            Adversary::new("Mercenary", 2, AdversaryRole::Standard)
+                .with_difficulty(14)
+                .with_thresholds(10, 20)
+                .with_hp(5)
+                .with_stress(3)
+                .with_attack(1, DamageDice::d10(1).with_bonus(4)),
+            Adversary::new("War Wizard", 2, AdversaryRole::Ranged)
+                .with_difficulty(16)
+                .with_thresholds(11, 23)
+                .with_hp(5)
+                .with_stress(6)
+                .with_attack(4, DamageDice::d10(2).with_bonus(4)),
+            Adversary::new("Gorgon", 2, AdversaryRole::Solo)
+                .with_difficulty(15)
+                .with_thresholds(13, 25)
+                .with_hp(9)
+                .with_stress(3)
+                .with_attack(4, DamageDice::d10(2).with_bonus(4)),
+            Adversary::new("Young Dryad", 3, AdversaryRole::Leader)
+                .with_difficulty(16)
+                .with_thresholds(19, 35)
+                .with_hp(6)
+                .with_stress(5)
+                .with_attack(0, DamageDice::d8(3).with_bonus(2)),
+        ];
+
+        let cards = vec![
+            DomainCard::new(
+                "rune_ward",
+                "Rune Ward",
+                Domain::Arcana,
+                1,
+                "Hold a protective trinket; an ally can spend Hope to reduce incoming damage",
+                ActionCost::Reaction,
+            ),
+            DomainCard::new(
+                "unleash_chaos",
+                "Unleash Chaos",
+                Domain::Arcana,
+                1,
+                "Hurl raw magic at a target within Far range",
+                ActionCost::Major,
+            ),
+            DomainCard::new(
+                "get_back_up",
+                "Get Back Up",
+                Domain::Blade,
+                1,
+                "When you take Severe damage, mark a Stress to reduce it by one threshold",
+                ActionCost::Reaction,
+            ),
+            DomainCard::new(
+                "whirlwind",
+                "Whirlwind",
+                Domain::Blade,
+                1,
+                "Strike every enemy within Very Close range",
+                ActionCost::Major,
+            ),
+            DomainCard::new(
+                "reckless",
+                "Reckless",
+                Domain::Blade,
+                2,
+                "Mark a Stress to gain advantage on an attack",
+                ActionCost::Free,
+            ),
+            DomainCard::new(
+                "untouchable",
+                "Untouchable",
+                Domain::Bone,
+                1,
+                "Gain a bonus to Evasion equal to half your Agility",
+                ActionCost::Free,
+            ),
+            DomainCard::new(
+                "deft_maneuvers",
+                "Deft Maneuvers",
+                Domain::Bone,
+                1,
+                "Mark a Stress to sprint anywhere within Far range",
+                ActionCost::Minor,
+            ),
+            DomainCard::new(
+                "book_of_illiat",
+                "Book of Illiat",
+                Domain::Codex,
+                1,
+                "A grimoire of slumber and arcane barriers",
+                ActionCost::Major,
+            ),
+            DomainCard::new(
+                "inspirational_words",
+                "Inspirational Words",
+                Domain::Grace,
+                1,
+                "Your speech heals Stress or grants Hope to allies",
+                ActionCost::Minor,
+            ),
+            DomainCard::new(
+                "deft_deceiver",
+                "Deft Deceiver",
+                Domain::Grace,
+                1,
+                "Spend a Hope to gain advantage on a roll to deceive",
+                ActionCost::Free,
+            ),
+            DomainCard::new(
+                "pick_and_pull",
+                "Pick and Pull",
+                Domain::Midnight,
+                1,
+                "Gain advantage on rolls to pick locks and pockets",
+                ActionCost::Free,
+            ),
+            DomainCard::new(
+                "rain_of_blades",
+                "Rain of Blades",
+                Domain::Midnight,
+                1,
+                "Conjure throwing blades that strike every enemy within Very Close range",
+                ActionCost::Major,
+            ),
+            DomainCard::new(
+                "gifted_tracker",
+                "Gifted Tracker",
+                Domain::Sage,
+                1,
+                "Spend Hope to learn about the creature you are tracking",
+                ActionCost::Minor,
+            ),
+            DomainCard::new(
+                "vicious_entangle",
+                "Vicious Entangle",
+                Domain::Sage,
+                1,
+                "Roots burst from the ground and Restrain a target",
+                ActionCost::Major,
+            ),
+            DomainCard::new(
+                "mending_touch",
+                "Mending Touch",
+                Domain::Splendor,
+                1,
+                "Spend Hope to clear Hit Points or Stress on a creature you touch",
+                ActionCost::Major,
+            ),
+            DomainCard::new(
+                "bolt_beacon",
+                "Bolt Beacon",
+                Domain::Splendor,
+                1,
+                "A bolt of light damages a target and makes them Vulnerable",
+                ActionCost::Major,
+            ),
+            DomainCard::new(
+                "bare_bones",
+                "Bare Bones",
+                Domain::Valor,
+                1,
+                "Without armor, your base Armor Score and thresholds improve",
+                ActionCost::Free,
+            ),
+            DomainCard::new(
+                "i_am_your_shield",
+                "I Am Your Shield",
+                Domain::Valor,
+                1,
+                "Mark a Stress to take the damage meant for an ally",
+                ActionCost::Reaction,
+            ),
+            DomainCard::new(
+                "body_basher",
+                "Body Basher",
+                Domain::Valor,
+                3,
+                "Add your Strength to damage with melee attacks",
+                ActionCost::Free,
+            ),
+        ];
+
+        Self {
+            adversaries,
+            cards,
+            weapons: Weapon::builtin(),
+            armor: Armor::builtin(),
+            index: OnceLock::new(),
+        }
+    }
+
+    /// Add an adversary
+    pub fn add_adversary(&mut self, adversary: Adversary) {
+        if let Some(index) = self.index.get_mut() {
+            index.place(Kind::Adversary, &adversary.name, self.adversaries.len());
+        }
+        self.adversaries.push(adversary);
+    }
+
+    /// Add a domain card
+    pub fn add_card(&mut self, card: DomainCard) {
+        if let Some(index) = self.index.get_mut() {
+            index.place(Kind::Card, &card.id, self.cards.len());
+        }
+        self.cards.push(card);
+    }
+
+    /// Add a weapon
+    pub fn add_weapon(&mut self, weapon: Weapon) {
+        if let Some(index) = self.index.get_mut() {
+            index.place(Kind::Weapon, &weapon.name, self.weapons.len());
+        }
+        self.weapons.push(weapon);
+    }
+
+    /// Add a suit of armor
+    pub fn add_armor(&mut self, armor: Armor) {
+        if let Some(index) = self.index.get_mut() {
+            index.place(Kind::Armor, &armor.name, self.armor.len());
+        }
+        self.armor.push(armor);
+    }
+
+    /// Bring the index up to date after changing the lists directly
+    ///
+    /// Ids already handed out keep naming the same card id or name.
+    pub fn reindex(&mut self) {
+        if let Some(mut index) = self.index.take() {
+            index.rebuild(self);
+            let _ = self.index.set(index);
+        }
+    }
+
+    /// Look up armor by name (case-insensitive)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::content::ContentRegistry;
+    ///
+    /// let registry = ContentRegistry::builtin();
+    /// let gambeson = registry.armor("gambeson armor").unwrap();
+    /// assert_eq!(gambeson.features[0].name, "Flexible");
+    /// ```
+    pub fn armor(&self, name: &str) -> Option<&Armor> {
+        let id = self.index().name(name)?;
+        self.armor_by_id(id)
+    }
+
+    /// Look up a weapon by name (case-insensitive)
+    pub fn weapon(&self, name: &str) -> Option<&Weapon> {
+        let id = self.index().name(name)?;
+        self.weapon_by_id(id)
+    }
+
+    /// Look up an adversary by name (case-insensitive)
+    pub fn adversary(&self, name: &str) -> Option<&Adversary> {
+        let id = self.index().name(name)?;
+        self.adversary_by_id(id)
+    }
+
+    /// Look up a domain card by id
+    pub fn card(&self, id: &str) -> Option<&DomainCard> {
+        let interned = self.index().card(id)?;
+        self.card_by_id(interned)
+    }
+
+    /// The interned id of a card id or an adversary, weapon or armor name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::content::ContentRegistry;
+    ///
+    /// let registry = ContentRegistry::builtin();
+    /// let bear = registry.content_id("bear").unwrap();
+    /// assert_eq!(registry.content_id("Bear"), Some(bear));
+    /// assert_eq!(registry.adversary_by_id(bear).unwrap().name, "Bear");
+    /// assert_eq!(registry.key_of(bear), Some("bear"));
+    /// ```
+    pub fn content_id(&self, key: &str) -> Option<ContentId> {
+        let index = self.index();
+        index.card(key).or_else(|| index.name(key))
+    }
+
+    /// The card id or lowercased name an id was interned from
+    pub fn key_of(&self, id: ContentId) -> Option<&str> {
+        self.index().key(id)
+    }
+
+    /// Look up an adversary by interned id
+    pub fn adversary_by_id(&self, id: ContentId) -> Option<&Adversary> {
+        let key = self.key_of(id)?;
+        self.indexed(&self.adversaries, Kind::Adversary, id)
+            .filter(|a| a.name.eq_ignore_ascii_case(key))
+    }
+
+    /// Look up a domain card by interned id
+    pub fn card_by_id(&self, id: ContentId) -> Option<&DomainCard> {
+        let key = self.key_of(id)?;
+        self.indexed(&self.cards, Kind::Card, id)
+            .filter(|c| c.id == key)
+    }
+
+    /// Look up a weapon by interned id
+    pub fn weapon_by_id(&self, id: ContentId) -> Option<&Weapon> {
+        let key = self.key_of(id)?;
+        self.indexed(&self.weapons, Kind::Weapon, id)
+            .filter(|w| w.name.eq_ignore_ascii_case(key))
+    }
+
+    /// Look up armor by interned id
+    pub fn armor_by_id(&self, id: ContentId) -> Option<&Armor> {
+        let key = self.key_of(id)?;
+        self.indexed(&self.armor, Kind::Armor, id)
+            .filter(|a| a.name.eq_ignore_ascii_case(key))
+    }
+
+    fn index(&self) -> &ContentIndex {
+        self.index.get_or_init(|| ContentIndex::build(self))
+    }
+
+    /// The entry of a kind the index places at `id`
+    ///
+    /// Callers check the entry still has the id's key, in case the list
+    /// was changed without reindexing.
+    fn indexed<'a, T>(&self, list: &'a [T], kind: Kind, id: ContentId) -> Option<&'a T> {
+        list.get(self.index().position(kind, id)?)
+    }
+
+    /// Adversaries of a tier and role, sorted by tier then name
+    ///
+    /// `None` matches anything.
+    pub fn find_adversaries(
+        &self,
+        tier: Option<u8>,
+        role: Option<AdversaryRole>,
+    ) -> Vec<&Adversary> {
+        let mut found: Vec<&Adversary> = self
+            .adversaries
+            .iter()
+            .filter(|a| tier.is_none_or(|tier| a.tier == tier))
+            .filter(|a| role.is_none_or(|role| a.role == role))
+            .collect();
+        found.sort_by(|a, b| a.tier.cmp(&b.tier).then_with(|| a.name.cmp(&b.name)));
+        found
+    }
+
+    /// Domain cards from a domain that a character of `level` can use,
+    /// sorted by level then name
+    ///
+    /// `None` matches anything.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::Domain;
+    /// use daggerheart_engine::content::ContentRegistry;
+    ///
+    /// let registry = ContentRegistry::builtin();
+    /// let cards = registry.find_cards(Some(Domain::Blade), Some(1));
+    /// assert!(cards.iter().all(|c| c.domain == Domain::Blade && c.level_requirement <= 1));
+    /// ```
+    pub fn find_cards(&self, domain: Option<Domain>, level: Option<u8>) -> Vec<&DomainCard> {
+        let mut found: Vec<&DomainCard> = self
+            .cards
+            .iter()
+            .filter(|c| domain.is_none_or(|domain| c.domain == domain))
+            .filter(|c| level.is_none_or(|level| c.can_use(level)))
+            .collect();
+        found.sort_by(|a, b| {
+            a.level_requirement
+                .cmp(&b.level_requirement)
+                .then_with(|| a.name.cmp(&b.name))
+        });
+        found
+    }
+}
+
+#[cfg(feature = "persistence")]
+impl Persist for ContentRegistry {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_builtin_covers_every_domain() {
+        let registry = ContentRegistry::builtin();
+        for domain in Domain::iter() {
+            assert!(
+                !registry.find_cards(Some(domain), Some(1)).is_empty(),
+                "no level 1 {} cards",
+                domain
+            );
+        }
+    }
+
+    #[test]
+    fn test_find_adversaries_sorted_and_filtered() {
+        let registry = ContentRegistry::builtin();
+
+        let tier_one = registry.find_adversaries(Some(1), None);
+        assert!(tier_one.iter().all(|a| a.tier == 1));
+        assert!(tier_one.windows(2).all(|w| w[0].name <= w[1].name));
+
+        let solos = registry.find_adversaries(None, Some(AdversaryRole::Solo));
+        let names: Vec<&str> = solos.iter().map(|a| a.name.as_str()).collect();
+        assert_eq!(names, vec!["Cave Ogre", "Gorgon"]);
+    }
+
+    #[test]
+    fn test_find_cards_by_level() {
+        let registry = ContentRegistry::builtin();
+        assert!(registry
+            .find_cards(Some(Domain::Valor), Some(2))
+            .iter()
+            .all(|c| c.id != "body_basher"));
+        assert_eq!(
+            registry
+                .find_cards(Some(Domain::Valor), None)
+                .last()
+                .unwrap()
+                .id,
+            "body_basher"
+        );
+    }
+
+    #[test]
+    fn test_lookups() {
+        let mut registry = ContentRegistry::new();
+        registry.add_adversary(Adversary::new("Sand Wyrm", 3, AdversaryRole::Solo));
+        registry.add_card(DomainCard::new(
+            "dust_veil",
+            "Dust Veil",
+            Domain::Midnight,
+            2,
+            "Become Hidden",
+            ActionCost::Minor,
+        ));
+
+        registry.add_armor(Armor::new("Sandcloak", 2));
+
+        assert_eq!(registry.adversary("sand wyrm").unwrap().tier, 3);
+        assert_eq!(registry.armor("SANDCLOAK").unwrap().score, 2);
+        assert!(registry.card("dust_veil").is_some());
+        assert!(registry.card("Dust Veil").is_none());
+    }
+
+    #[test]
+    fn test_interned_ids() {
+        let mut registry = ContentRegistry::builtin();
+        let longsword = registry.content_id("LONGSWORD").unwrap();
+        assert_eq!(registry.weapon_by_id(longsword).unwrap().name, "Longsword");
+        assert!(registry.card_by_id(longsword).is_none());
+        assert_eq!(registry.content_id("no such thing"), None);
+
+        // Adding content never renumbers the ids already handed out
+        let lackey = registry.content_id("Jagged Knife Lackey").unwrap();
+        registry.add_card(DomainCard::new(
+            "dust_veil",
+            "Dust Veil",
+            Domain::Midnight,
+            2,
+            "Become Hidden",
+            ActionCost::Minor,
+        ));
+        registry.add_adversary(Adversary::new("Mimic", 1, AdversaryRole::Skulk));
+        let veil = registry.content_id("dust_veil").unwrap();
+        assert_eq!(registry.card_by_id(veil).unwrap().name, "Dust Veil");
+        assert_eq!(
+            registry.adversary_by_id(lackey).unwrap().name,
+            "Jagged Knife Lackey"
+        );
+        assert_eq!(registry.adversary("mimic").unwrap().name, "Mimic");
+    }
+
+    #[test]
+    fn test_reindex_after_direct_changes() {
+        let mut registry = ContentRegistry::builtin();
+        let bear = registry.content_id("bear").unwrap();
+
+        // Until reindexed, moved entries can be missed but never mixed up
+        registry
+            .adversaries
+            .insert(0, Adversary::new("Mimic", 1, AdversaryRole::Skulk));
+        let grizzly = registry
+            .adversaries
+            .iter_mut()
+            .find(|a| a.name == "Bear")
+            .unwrap();
+        grizzly.name = "Grizzly".to_string();
+        assert!(registry.adversary("mimic").is_none());
+        assert!(registry.adversary_by_id(bear).is_none());
+        for adversary in &registry.adversaries {
+            if let Some(found) = registry.adversary(&adversary.name) {
+                assert_eq!(found.name, adversary.name);
+            }
+        }
+
+        registry.reindex();
+        assert_eq!(registry.adversary("mimic").unwrap().name, "Mimic");
+        assert_eq!(registry.adversary("GRIZZLY").unwrap().name, "Grizzly");
+        assert!(registry.adversary_by_id(bear).is_none());
+        assert_eq!(registry.content_id("bear"), Some(bear));
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_registry_serialization() {
+        let registry = ContentRegistry::builtin();
+        let json = serde_json::to_string(&registry).unwrap();
+        let loaded: ContentRegistry = serde_json::from_str(&json).unwrap();
+        assert_eq!(loaded, registry);
+
+        let empty: ContentRegistry = serde_json::from_str("{}").unwrap();
+        assert_eq!(empty, ContentRegistry::new());
+    }
+}
@@ -0,0 +1,173 @@
+//! Content ids - interned keys for registry lookups
+//!
+//! A registry interns each card id and each adversary, weapon and armor
+//! name, handing out a [`ContentId`] for each. Code that looks the same
+//! content up again and again can hold on to the id, a `Copy` integer,
+//! and find the entry in constant time instead of comparing strings down
+//! the list.
+//!
+//! Keys are only ever added, so an id keeps naming the same key for as
+//! long as the registry (or a clone of it) lives.
+
+use crate::content::ContentRegistry;
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+
+/// An interned content key, valid for the registry that issued it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ContentId(pub u32);
+
+/// The kinds of content a key can name
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Kind {
+    Adversary,
+    Card,
+    Weapon,
+    Armor,
+}
+
+/// Where the entry for a key sits in each of the registry's lists
+#[derive(Debug, Clone, Copy, Default)]
+struct Slots {
+    adversary: Option<usize>,
+    card: Option<usize>,
+    weapon: Option<usize>,
+    armor: Option<usize>,
+}
+
+impl Slots {
+    fn get_mut(&mut self, kind: Kind) -> &mut Option<usize> {
+        match kind {
+            Kind::Adversary => &mut self.adversary,
+            Kind::Card => &mut self.card,
+            Kind::Weapon => &mut self.weapon,
+            Kind::Armor => &mut self.armor,
+        }
+    }
+
+    fn get(&self, kind: Kind) -> Option<usize> {
+        match kind {
+            Kind::Adversary => self.adversary,
+            Kind::Card => self.card,
+            Kind::Weapon => self.weapon,
+            Kind::Armor => self.armor,
+        }
+    }
+}
+
+/// Interned keys and where each one's entries sit
+///
+/// Card ids match exactly and names ignore ASCII case. Names are hashed
+/// a byte at a time as they're folded, so looking one up never
+/// allocates.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct ContentIndex {
+    keys: Vec<String>,
+    slots: Vec<Slots>,
+    cards: HashMap<String, ContentId>,
+    names: HashMap<u64, Vec<ContentId>>,
+    hasher: RandomState,
+}
+
+impl ContentIndex {
+    /// Index every entry, keeping the first of any duplicates
+    pub(crate) fn build(registry: &ContentRegistry) -> Self {
+        let mut index = Self::default();
+        index.place_all(registry);
+        index
+    }
+
+    /// Place every entry again after the lists were changed directly,
+    /// keeping the ids already handed out
+    pub(crate) fn rebuild(&mut self, registry: &ContentRegistry) {
+        self.slots.fill(Slots::default());
+        self.place_all(registry);
+    }
+
+    fn place_all(&mut self, registry: &ContentRegistry) {
+        for (position, card) in registry.cards.iter().enumerate() {
+            self.place(Kind::Card, &card.id, position);
+        }
+        for (position, adversary) in registry.adversaries.iter().enumerate() {
+            self.place(Kind::Adversary, &adversary.name, position);
+        }
+        for (position, weapon) in registry.weapons.iter().enumerate() {
+            self.place(Kind::Weapon, &weapon.name, position);
+        }
+        for (position, armor) in registry.armor.iter().enumerate() {
+            self.place(Kind::Armor, &armor.name, position);
+        }
+    }
+
+    /// Record an entry at `position`, unless an earlier one has its key
+    pub(crate) fn place(&mut self, kind: Kind, key: &str, position: usize) {
+        let id = match kind {
+            Kind::Card => self.intern_card(key),
+            _ => self.intern_name(key),
+        };
+        self.slots[id.0 as usize]
+            .get_mut(kind)
+            .get_or_insert(position);
+    }
+
+    fn intern_card(&mut self, key: &str) -> ContentId {
+        if let Some(&id) = self.cards.get(key) {
+            return id;
+        }
+        let id = self.push(key.to_string());
+        self.cards.insert(key.to_string(), id);
+        id
+    }
+
+    fn intern_name(&mut self, name: &str) -> ContentId {
+        if let Some(id) = self.name(name) {
+            return id;
+        }
+        let id = self.push(name.to_ascii_lowercase());
+        let hash = self.fold_hash(name);
+        self.names.entry(hash).or_default().push(id);
+        id
+    }
+
+    fn push(&mut self, key: String) -> ContentId {
+        let id = ContentId(self.keys.len() as u32);
+        self.keys.push(key);
+        self.slots.push(Slots::default());
+        id
+    }
+
+    /// The id of a card id, matched exactly
+    pub(crate) fn card(&self, key: &str) -> Option<ContentId> {
+        self.cards.get(key).copied()
+    }
+
+    /// The id of a name, ignoring case
+    pub(crate) fn name(&self, name: &str) -> Option<ContentId> {
+        let candidates = self.names.get(&self.fold_hash(name))?;
+        candidates
+            .iter()
+            .copied()
+            .find(|id| self.keys[id.0 as usize].eq_ignore_ascii_case(name))
+    }
+
+    /// Where the entry of a kind with this id sits
+    pub(crate) fn position(&self, kind: Kind, id: ContentId) -> Option<usize> {
+        self.slots.get(id.0 as usize)?.get(kind)
+    }
+
+    /// The key an id was interned from
+    pub(crate) fn key(&self, id: ContentId) -> Option<&str> {
+        self.keys.get(id.0 as usize).map(String::as_str)
+    }
+
+    fn fold_hash(&self, name: &str) -> u64 {
+        let mut hasher = self.hasher.build_hasher();
+        for byte in name.bytes() {
+            hasher.write_u8(byte.to_ascii_lowercase());
+        }
+        hasher.finish()
+    }
+}
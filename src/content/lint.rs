@@ -0,0 +1,450 @@
+//! Content lint - red flags in homebrew before it reaches the table
+//!
+//! [`lint`] looks over a registry for entries that can't work as written
+//! (a card nobody can ever take, thresholds in the wrong order) and for
+//! numbers far from what the official content uses at the same tier. It
+//! reports rather than fixes: every finding is a [`Diagnostic`] naming the
+//! entry, a [`LintKind`] a UI can match on, and a message for people.
+
+use super::ContentRegistry;
+use crate::cards::DomainCard;
+use crate::combat::adversary::Adversary;
+use crate::combat::AdversaryBuilder;
+use crate::items::Weapon;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use strum_macros::Display;
+
+/// The highest level a character can reach
+const MAX_LEVEL: u8 = 10;
+
+/// The most Stress slots a character can reach through level-ups
+const MAX_STRESS_SLOTS: u8 = 12;
+
+/// How many times the role's benchmark damage counts as far above it
+const DAMAGE_LIMIT: f32 = 2.0;
+
+/// Average damage at Proficiency 1 that no weapon, at any tier, reaches
+const WEAPON_DAMAGE_LIMIT: f32 = 20.0;
+
+/// How far an adversary's Difficulty can stray from its role's benchmark
+/// before it's flagged
+const DIFFICULTY_SPREAD: u16 = 4;
+
+/// How bad a finding is
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Display, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Severity {
+    /// Legal, but probably not what was meant
+    Warning,
+    /// Can't work as written
+    Error,
+}
+
+/// The entry a finding is about
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Subject {
+    /// A domain card, by id
+    Card(String),
+    /// An adversary, by name
+    Adversary(String),
+    /// A weapon, by name
+    Weapon(String),
+}
+
+/// What a finding is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum LintKind {
+    /// An id or name is blank
+    MissingName,
+    /// Another entry of the same kind has this id or name
+    Duplicate,
+    /// A card without a level requirement
+    MissingLevel,
+    /// A card above the highest level
+    UnreachableLevel,
+    /// A recall cost above any character's Stress slots
+    UnreachableRecallCost,
+    /// An adversary tier outside 1-4
+    InvalidTier,
+    /// An adversary with no Hit Points
+    NoHitPoints,
+    /// A Major threshold above the Severe one
+    ThresholdOrder,
+    /// A Difficulty far from its tier's
+    DifficultyOffTier,
+    /// Damage far above the tier's
+    DamageAboveTier,
+}
+
+/// One finding
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub subject: Subject,
+    pub kind: LintKind,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn error(subject: Subject, kind: LintKind, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Error,
+            subject,
+            kind,
+            message: message.into(),
+        }
+    }
+
+    fn warning(subject: Subject, kind: LintKind, message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            ..Self::error(subject, kind, message)
+        }
+    }
+}
+
+/// Check every card, adversary and weapon in a registry
+///
+/// Findings come in registry order: cards, then adversaries, then weapons.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::cards::{ActionCost, DomainCard};
+/// use daggerheart_engine::character::Domain;
+/// use daggerheart_engine::content::lint::{self, LintKind};
+/// use daggerheart_engine::content::ContentRegistry;
+///
+/// let mut homebrew = ContentRegistry::new();
+/// homebrew.add_card(
+///     DomainCard::new("overdrive", "Overdrive", Domain::Blade, 1, "Go all out", ActionCost::Free)
+///         .with_recall_cost(15),
+/// );
+///
+/// let diagnostics = lint::lint(&homebrew);
+/// assert_eq!(diagnostics[0].kind, LintKind::UnreachableRecallCost);
+/// assert!(lint::lint(&ContentRegistry::builtin()).is_empty());
+/// ```
+pub fn lint(content: &ContentRegistry) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let mut ids = HashSet::new();
+    for card in &content.cards {
+        if !ids.insert(card.id.as_str()) {
+            diagnostics.push(Diagnostic::error(
+                Subject::Card(card.id.clone()),
+                LintKind::Duplicate,
+                format!("Another card already has the id \"{}\"", card.id),
+            ));
+        }
+        diagnostics.extend(lint_card(card));
+    }
+
+    let mut names = HashSet::new();
+    for adversary in &content.adversaries {
+        // Lookups ignore case, so the later one could never be found
+        if !names.insert(adversary.name.to_lowercase()) {
+            diagnostics.push(Diagnostic::warning(
+                Subject::Adversary(adversary.name.clone()),
+                LintKind::Duplicate,
+                format!("Another adversary is already named {}", adversary.name),
+            ));
+        }
+        diagnostics.extend(lint_adversary(adversary));
+    }
+
+    let mut names = HashSet::new();
+    for weapon in &content.weapons {
+        if !names.insert(weapon.name.to_lowercase()) {
+            diagnostics.push(Diagnostic::warning(
+                Subject::Weapon(weapon.name.clone()),
+                LintKind::Duplicate,
+                format!("Another weapon is already named {}", weapon.name),
+            ));
+        }
+        diagnostics.extend(lint_weapon(weapon));
+    }
+
+    diagnostics
+}
+
+/// Check one domain card on its own
+pub fn lint_card(card: &DomainCard) -> Vec<Diagnostic> {
+    let subject = || Subject::Card(card.id.clone());
+    let mut diagnostics = Vec::new();
+
+    if card.id.trim().is_empty() {
+        diagnostics.push(Diagnostic::error(
+            subject(),
+            LintKind::MissingName,
+            format!("{} has no id", describe(&card.name, "A card")),
+        ));
+    }
+    if card.name.trim().is_empty() {
+        diagnostics.push(Diagnostic::error(
+            subject(),
+            LintKind::MissingName,
+            "The card has no name",
+        ));
+    }
+    if card.level_requirement == 0 {
+        diagnostics.push(Diagnostic::error(
+            subject(),
+            LintKind::MissingLevel,
+            "The card has no level requirement",
+        ));
+    } else if card.level_requirement > MAX_LEVEL {
+        diagnostics.push(Diagnostic::error(
+            subject(),
+            LintKind::UnreachableLevel,
+            format!(
+                "Level {} is above the highest level, {}",
+                card.level_requirement, MAX_LEVEL
+            ),
+        ));
+    }
+    if card.recall_cost > MAX_STRESS_SLOTS {
+        diagnostics.push(Diagnostic::error(
+            subject(),
+            LintKind::UnreachableRecallCost,
+            format!(
+                "Recalling it costs {} Stress, but characters have at most {} slots",
+                card.recall_cost, MAX_STRESS_SLOTS
+            ),
+        ));
+    }
+    diagnostics
+}
+
+/// Check one adversary's stat block on its own
+pub fn lint_adversary(adversary: &Adversary) -> Vec<Diagnostic> {
+    let subject = || Subject::Adversary(adversary.name.clone());
+    let mut diagnostics = Vec::new();
+
+    if adversary.name.trim().is_empty() {
+        diagnostics.push(Diagnostic::error(
+            subject(),
+            LintKind::MissingName,
+            "The adversary has no name",
+        ));
+    }
+    if !(1..=4).contains(&adversary.tier) {
+        diagnostics.push(Diagnostic::error(
+            subject(),
+            LintKind::InvalidTier,
+            format!("Tier {} isn't one of tiers 1-4", adversary.tier),
+        ));
+    }
+    if adversary.hp == 0 {
+        diagnostics.push(Diagnostic::error(
+            subject(),
+            LintKind::NoHitPoints,
+            "The adversary has no Hit Points",
+        ));
+    }
+    // Minions share one threshold, so only a reversed pair is wrong
+    if adversary.major_threshold > adversary.severe_threshold {
+        diagnostics.push(Diagnostic::error(
+            subject(),
+            LintKind::ThresholdOrder,
+            format!(
+                "The Major threshold ({}) is above the Severe threshold ({})",
+                adversary.major_threshold, adversary.severe_threshold
+            ),
+        ));
+    }
+
+    let benchmark = AdversaryBuilder::benchmark("", adversary.tier, adversary.role);
+    if adversary.difficulty.abs_diff(benchmark.difficulty) as u16 > DIFFICULTY_SPREAD {
+        diagnostics.push(Diagnostic::warning(
+            subject(),
+            LintKind::DifficultyOffTier,
+            format!(
+                "Difficulty {} is far from the tier {} {} benchmark of {}",
+                adversary.difficulty, adversary.tier, adversary.role, benchmark.difficulty
+            ),
+        ));
+    }
+
+    let typical = benchmark.damage.average();
+    let average = adversary.damage.average();
+    if average > typical * DAMAGE_LIMIT {
+        diagnostics.push(Diagnostic::warning(
+            subject(),
+            LintKind::DamageAboveTier,
+            format!(
+                "{} averages {:.1} damage, against about {} for a tier {} {}",
+                adversary.damage, average, typical, adversary.tier, adversary.role
+            ),
+        ));
+    }
+    diagnostics
+}
+
+/// Check one weapon on its own
+pub fn lint_weapon(weapon: &Weapon) -> Vec<Diagnostic> {
+    let subject = || Subject::Weapon(weapon.name.clone());
+    let mut diagnostics = Vec::new();
+
+    if weapon.name.trim().is_empty() {
+        diagnostics.push(Diagnostic::error(
+            subject(),
+            LintKind::MissingName,
+            "The weapon has no name",
+        ));
+    }
+    let damage = weapon.damage(1);
+    if damage.average() > WEAPON_DAMAGE_LIMIT {
+        diagnostics.push(Diagnostic::warning(
+            subject(),
+            LintKind::DamageAboveTier,
+            format!(
+                "{} averages {:.1} damage at Proficiency 1, more than any tier's weapons",
+                damage,
+                damage.average()
+            ),
+        ));
+    }
+    diagnostics
+}
+
+/// A name to start a message with, or `fallback` if it's blank
+fn describe<'a>(name: &'a str, fallback: &'a str) -> &'a str {
+    if name.trim().is_empty() {
+        fallback
+    } else {
+        name
+    }
+}
+
+impl ContentRegistry {
+    /// Check the registry for mistakes and balance red flags
+    ///
+    /// See [`lint()`].
+    pub fn lint(&self) -> Vec<Diagnostic> {
+        lint(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::ActionCost;
+    use crate::cards::Range;
+    use crate::character::{AttributeType, Domain};
+    use crate::combat::AdversaryRole;
+    use crate::core::dice::{DamageDice, Die};
+
+    fn kinds(diagnostics: &[Diagnostic]) -> Vec<LintKind> {
+        diagnostics.iter().map(|d| d.kind).collect()
+    }
+
+    #[test]
+    fn test_builtin_content_is_clean() {
+        assert_eq!(ContentRegistry::builtin().lint(), vec![]);
+    }
+
+    #[test]
+    fn test_card_structure() {
+        let card = DomainCard::new("", "Blank", Domain::Arcana, 0, "", ActionCost::Major);
+        let diagnostics = lint_card(&card);
+        assert_eq!(
+            kinds(&diagnostics),
+            vec![LintKind::MissingName, LintKind::MissingLevel]
+        );
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Error));
+        assert_eq!(diagnostics[0].message, "Blank has no id");
+
+        let late = DomainCard::new("late", "Late", Domain::Arcana, 11, "", ActionCost::Free)
+            .with_recall_cost(MAX_STRESS_SLOTS);
+        assert_eq!(kinds(&lint_card(&late)), vec![LintKind::UnreachableLevel]);
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let mut content = ContentRegistry::new();
+        for _ in 0..2 {
+            content.add_card(DomainCard::new(
+                "twin",
+                "Twin",
+                Domain::Grace,
+                1,
+                "",
+                ActionCost::Free,
+            ));
+        }
+        content.add_adversary(Adversary::new("Bandit", 1, AdversaryRole::Standard));
+        content.add_adversary(Adversary::new("BANDIT", 1, AdversaryRole::Skulk));
+
+        let diagnostics = content.lint();
+        assert_eq!(
+            kinds(&diagnostics),
+            vec![LintKind::Duplicate, LintKind::Duplicate]
+        );
+        assert_eq!(diagnostics[0].severity, Severity::Error);
+        assert_eq!(diagnostics[1].subject, Subject::Adversary("BANDIT".into()));
+        assert_eq!(diagnostics[1].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_adversary_balance() {
+        let broken = Adversary::new("Broken", 1, AdversaryRole::Solo)
+            .with_thresholds(12, 10)
+            .with_hp(0);
+        assert_eq!(
+            kinds(&lint_adversary(&broken)),
+            vec![LintKind::NoHitPoints, LintKind::ThresholdOrder]
+        );
+
+        let brutal = Adversary::new("Brutal", 1, AdversaryRole::Bruiser)
+            .with_difficulty(20)
+            .with_attack(3, DamageDice::d12(3).with_bonus(5));
+        let diagnostics = lint_adversary(&brutal);
+        assert_eq!(
+            kinds(&diagnostics),
+            vec![LintKind::DifficultyOffTier, LintKind::DamageAboveTier]
+        );
+        assert!(diagnostics.iter().all(|d| d.severity == Severity::Warning));
+
+        // The same attack is fine for a tier 4 adversary
+        let mut epic = brutal.clone().with_difficulty(20);
+        epic.tier = 4;
+        assert!(lint_adversary(&epic).is_empty());
+
+        epic.tier = 5;
+        assert_eq!(kinds(&lint_adversary(&epic)), vec![LintKind::InvalidTier]);
+    }
+
+    #[test]
+    fn test_weapons() {
+        let mut content = ContentRegistry::new();
+        content.add_weapon(Weapon::new(
+            "Worldsplitter",
+            AttributeType::Strength,
+            Range::VeryClose,
+            Die::D20,
+            12,
+        ));
+        let diagnostics = content.lint();
+        assert_eq!(kinds(&diagnostics), vec![LintKind::DamageAboveTier]);
+        assert_eq!(
+            diagnostics[0].subject,
+            Subject::Weapon("Worldsplitter".into())
+        );
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_homebrew_file_without_level_loads() {
+        let json = r#"{"cards": [{"id": "x", "name": "X", "domain": "Bone",
+            "description": "", "action_cost": "Free"}]}"#;
+        let content: ContentRegistry = serde_json::from_str(json).unwrap();
+        assert_eq!(kinds(&content.lint()), vec![LintKind::MissingLevel]);
+    }
+}
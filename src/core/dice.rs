@@ -4,11 +4,14 @@
 //! - Basic dice (d4, d6, d8, d10, d12, d20)
 //! - Duality dice (2d12 Hope/Fear system)
 //! - Damage dice (multiple dice with bonuses)
+//! - Opposed rolls (contests between two sides)
 
 pub mod basic;
 pub mod damage;
 pub mod duality;
+pub mod opposed;
 
 pub use basic::Die;
 pub use damage::{DamageDice, DamageRoll};
 pub use duality::{ControllingDie, DualityResult, DualityRoll, SuccessType};
+pub use opposed::{ContestRoll, OpposedResult, Side};
@@ -3,7 +3,8 @@
 use rand::Rng;
 
 /// Standard polyhedral dice
-#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum Die {
     D4,
     D6,
@@ -14,6 +15,7 @@ pub enum Die {
 }
 
 impl Die {
+    #[cfg(feature = "std")]
     /// Roll this die and return the result
     pub fn roll(&self) -> u8 {
         let mut rng = rand::thread_rng();
@@ -55,6 +57,7 @@ mod tests {
         assert_eq!(Die::D20.max(), 20);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_d4_rolls_in_range() {
         for _ in 0..100 {
@@ -63,6 +66,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_d6_rolls_in_range() {
         for _ in 0..100 {
@@ -71,6 +75,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_d8_rolls_in_range() {
         for _ in 0..100 {
@@ -79,6 +84,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_d10_rolls_in_range() {
         for _ in 0..100 {
@@ -87,6 +93,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_d12_rolls_in_range() {
         for _ in 0..100 {
@@ -95,6 +102,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_d20_rolls_in_range() {
         for _ in 0..100 {
@@ -1,29 +1,64 @@
 //! Damage dice system (multiple dice with bonuses)
 //!
 //! Weapons and attacks roll damage using one or more dice plus a bonus.
-//! For example: Longsword Tier 1 = d10+3
+//! For example: Longsword Tier 1 = d10+3. Weapon features can add dice
+//! that are rolled and the lowest dropped, or make dice that roll their
+//! maximum roll again.
 
 use super::basic::Die;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt;
+use rand::Rng;
+
+/// Most extra dice a single roll's exploding dice can add
+pub const MAX_EXPLOSIONS: usize = 10;
 
 /// A collection of dice to roll for damage
-#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DamageDice {
     dice: Vec<Die>,
     bonus: i16,
+    /// Extra dice of the largest size rolled, then this many of the
+    /// lowest results dropped
+    #[serde(default, skip_serializing_if = "is_zero")]
+    drop_lowest: u8,
+    /// Whether a die that rolls its maximum adds another of the same die
+    #[serde(default, skip_serializing_if = "is_false")]
+    exploding: bool,
 }
 
 /// The result of rolling damage dice
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DamageRoll {
+    /// The dice that count, including any that exploded
     pub rolls: Vec<u8>,
+    /// Dice rolled and dropped as the lowest
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dropped: Vec<u8>,
     pub bonus: i16,
     pub total: u16,
 }
 
+fn is_zero(n: &u8) -> bool {
+    *n == 0
+}
+
+fn is_false(b: &bool) -> bool {
+    !*b
+}
+
 impl DamageDice {
     /// Create damage dice from a vector of dice
     pub fn new(dice: Vec<Die>) -> Self {
-        Self { dice, bonus: 0 }
+        Self {
+            dice,
+            bonus: 0,
+            drop_lowest: 0,
+            exploding: false,
+        }
     }
 
     /// Add a bonus to the damage
@@ -32,23 +67,311 @@ impl DamageDice {
         self
     }
 
+    /// Roll `count` more of the largest die and drop the lowest `count`
+    /// results
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::core::dice::DamageDice;
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let damage = DamageDice::d6(2).with_drop_lowest(1);
+    /// assert_eq!(damage.to_string(), "3d6kh2");
+    /// let mut rng = StdRng::seed_from_u64(1);
+    /// assert_eq!(damage.roll_with_rng(&mut rng).dropped.len(), 1);
+    /// ```
+    pub fn with_drop_lowest(mut self, count: u8) -> Self {
+        self.drop_lowest = count;
+        self
+    }
+
+    /// Make each die that rolls its maximum add another of the same die,
+    /// which can explode in turn
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::core::dice::DamageDice;
+    ///
+    /// let damage = DamageDice::d10(1).with_bonus(3).with_exploding();
+    /// assert_eq!(damage.to_string(), "1d10!+3");
+    /// ```
+    pub fn with_exploding(mut self) -> Self {
+        self.exploding = true;
+        self
+    }
+
+    /// The flat bonus added to the dice
+    pub fn bonus(&self) -> i16 {
+        self.bonus
+    }
+
+    /// The most the dice can show together, without the bonus or any
+    /// dice that explode
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::core::dice::DamageDice;
+    ///
+    /// assert_eq!(DamageDice::d8(2).with_bonus(3).dice_maximum(), 16);
+    /// ```
+    pub fn dice_maximum(&self) -> u16 {
+        self.dice.iter().map(|die| die.max() as u16).sum()
+    }
+
+    /// The mean of the roll, before it is floored at 0
+    ///
+    /// With dropped dice, the pool is averaged as if every die were the
+    /// largest one, which is exact for a weapon's matching dice.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::core::dice::DamageDice;
+    ///
+    /// assert_eq!(DamageDice::d8(2).with_bonus(3).average(), 12.0);
+    /// // Keeping the best of two d4s
+    /// assert_eq!(DamageDice::d4(1).with_drop_lowest(1).average(), 3.125);
+    /// ```
+    pub fn average(&self) -> f32 {
+        let largest = self.largest();
+        let dice: f32 = match largest {
+            Some(largest) if self.drop_lowest > 0 => {
+                let kept = self.dice.len();
+                let pool = kept + self.drop_lowest as usize;
+                let sides = largest.max() as u32;
+                let explosions = if self.exploding {
+                    expected_maximums(pool, kept, sides) * exploded_average(sides)
+                } else {
+                    0.0
+                };
+                kept_average(pool, kept, sides) + explosions
+            }
+            _ => self
+                .dice
+                .iter()
+                .map(|die| {
+                    let sides = die.max() as u32;
+                    let mean = (sides as f32 + 1.0) / 2.0;
+                    if self.exploding {
+                        mean + exploded_average(sides) / sides as f32
+                    } else {
+                        mean
+                    }
+                })
+                .sum(),
+        };
+        dice + self.bonus as f32
+    }
+
+    /// The exact chance of each total, indexed by the total
+    ///
+    /// Each die's chances are convolved together, with the bonus added and
+    /// the total floored at 0 as when rolled. Dropped dice are worked out
+    /// from which die is the last one dropped, so even large pools are
+    /// quick. Each exploding die stops after [`MAX_EXPLOSIONS`] extra dice,
+    /// rather than the whole roll, which only differs once ten dice have
+    /// exploded.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::core::dice::DamageDice;
+    ///
+    /// let chances = DamageDice::d6(2).with_bonus(1).distribution();
+    /// assert_eq!(chances.len(), 14);
+    /// assert_eq!(chances[1], 0.0);
+    /// assert!((chances[8] - 6.0 / 36.0).abs() < 1e-12);
+    /// ```
+    pub fn distribution(&self) -> Vec<f64> {
+        let depth = if self.exploding { MAX_EXPLOSIONS } else { 0 };
+        let dice = match self.largest() {
+            Some(largest) if self.drop_lowest > 0 => self.kept_distribution(largest),
+            _ => self.dice.iter().fold(vec![1.0], |sums, die| {
+                convolve(&sums, &die_distribution(die.max() as usize, depth))
+            }),
+        };
+
+        let highest = (dice.len() as i32 - 1 + self.bonus as i32).max(0) as usize;
+        let mut totals = vec![0.0; highest + 1];
+        for (sum, chance) in dice.into_iter().enumerate() {
+            totals[(sum as i32 + self.bonus as i32).max(0) as usize] += chance;
+        }
+        totals
+    }
+
+    /// The mean total, floored at 0 as when rolled
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::core::dice::DamageDice;
+    ///
+    /// assert_eq!(DamageDice::d8(2).with_bonus(3).expected(), 12.0);
+    /// // Unlike `average`, a d4-3 can't go below 0
+    /// assert_eq!(DamageDice::d4(1).with_bonus(-3).expected(), 0.25);
+    /// ```
+    pub fn expected(&self) -> f64 {
+        mean(&self.distribution())
+    }
+
+    /// How far totals spread around the expected total
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::core::dice::DamageDice;
+    ///
+    /// assert!((DamageDice::d6(1).variance() - 35.0 / 12.0).abs() < 1e-9);
+    /// assert_eq!(DamageDice::d6(0).with_bonus(4).variance(), 0.0);
+    /// ```
+    pub fn variance(&self) -> f64 {
+        let chances = self.distribution();
+        let mean = mean(&chances);
+        chances
+            .iter()
+            .enumerate()
+            .map(|(total, chance)| chance * (total as f64 - mean) * (total as f64 - mean))
+            .sum()
+    }
+
+    /// Chances of the kept dice's sum, dropping as
+    /// [`roll_with_rng`](Self::roll_with_rng) does
+    ///
+    /// The dice dropped are the lowest rolls, ties going to the earliest
+    /// die, so the last one dropped splits the pool: the dice before it
+    /// in that order are dropped and the rest kept. Summing over which die
+    /// that is and what it rolled leaves every other die independent,
+    /// either under the line or kept with its (exploded) value, which
+    /// takes polynomial rather than exponential time.
+    fn kept_distribution(&self, largest: Die) -> Vec<f64> {
+        let mut pool = self.dice.clone();
+        pool.extend(core::iter::repeat_n(largest, self.drop_lowest as usize));
+        let depth = if self.exploding { MAX_EXPLOSIONS } else { 0 };
+        let values: Vec<Vec<f64>> = pool
+            .iter()
+            .map(|die| die_distribution(die.max() as usize, depth))
+            .collect();
+        let dropped = self.drop_lowest as usize;
+
+        let mut totals = vec![0.0];
+        for (last, last_die) in pool.iter().enumerate() {
+            let last_sides = last_die.max() as usize;
+            for line in 1..=last_sides {
+                // Ways the other dice land, by how many fall under the line
+                let mut under: Vec<Vec<f64>> = vec![Vec::new(); dropped];
+                under[0] = vec![1.0];
+                for (index, die) in pool.iter().enumerate().filter(|&(i, _)| i != last) {
+                    let sides = die.max() as usize;
+                    // Ties with the line go under it for earlier dice
+                    let lowest_kept = if index < last { line + 1 } else { line };
+                    let chance_under = (lowest_kept - 1).min(sides) as f64 / sides as f64;
+                    let kept = kept_values(&values[index], sides, lowest_kept);
+                    for count in (0..dropped).rev() {
+                        let mut next = match &kept {
+                            Some(kept) if !under[count].is_empty() => convolve(&under[count], kept),
+                            _ => Vec::new(),
+                        };
+                        if count > 0 && chance_under > 0.0 {
+                            add_scaled(&mut next, &under[count - 1], chance_under);
+                        }
+                        under[count] = next;
+                    }
+                }
+                add_scaled(&mut totals, &under[dropped - 1], 1.0 / last_sides as f64);
+            }
+        }
+        totals
+    }
+
+    fn largest(&self) -> Option<Die> {
+        self.dice.iter().copied().max_by_key(|die| die.max())
+    }
+
+    #[cfg(feature = "std")]
     /// Roll the damage dice
     pub fn roll(&self) -> DamageRoll {
         let mut rng = rand::thread_rng();
-        let rolls: Vec<u8> = self
+        self.roll_with_rng(&mut rng)
+    }
+
+    /// Roll the damage dice with a specific RNG (for testing/seeding)
+    pub fn roll_with_rng<R: Rng>(&self, rng: &mut R) -> DamageRoll {
+        self.roll_faces_with_rng(rng).0
+    }
+
+    /// Roll the damage dice, also returning every die rolled with the face
+    /// it landed on, dropped and exploded dice included
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::core::dice::{DamageDice, Die};
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let damage = DamageDice::d6(2).with_drop_lowest(1);
+    /// let (roll, faces) = damage.roll_faces_with_rng(&mut StdRng::seed_from_u64(1));
+    /// assert_eq!(faces.len(), 3);
+    /// assert!(faces.iter().all(|&(die, _)| die == Die::D6));
+    /// assert_eq!(roll.rolls.len() + roll.dropped.len(), faces.len());
+    /// ```
+    pub fn roll_faces_with_rng<R: Rng>(&self, rng: &mut R) -> (DamageRoll, Vec<(Die, u8)>) {
+        let mut pool: Vec<(Die, u8)> = self
             .dice
             .iter()
-            .map(|die| die.roll_with_rng(&mut rng))
+            .map(|&die| (die, die.roll_with_rng(rng)))
             .collect();
+        let mut faces = pool.clone();
+
+        let mut dropped = Vec::new();
+        if let Some(largest) = self.largest().filter(|_| self.drop_lowest > 0) {
+            for _ in 0..self.drop_lowest {
+                let extra = (largest, largest.roll_with_rng(rng));
+                pool.push(extra);
+                faces.push(extra);
+            }
+            for _ in 0..self.drop_lowest {
+                let lowest = pool
+                    .iter()
+                    .enumerate()
+                    .min_by_key(|(_, (_, roll))| *roll)
+                    .map(|(index, _)| index);
+                if let Some(index) = lowest {
+                    dropped.push(pool.remove(index).1);
+                }
+            }
+        }
 
+        if self.exploding {
+            let mut index = 0;
+            let mut explosions = 0;
+            while index < pool.len() && explosions < MAX_EXPLOSIONS {
+                let (die, roll) = pool[index];
+                if roll == die.max() {
+                    let extra = (die, die.roll_with_rng(rng));
+                    pool.push(extra);
+                    faces.push(extra);
+                    explosions += 1;
+                }
+                index += 1;
+            }
+        }
+
+        let rolls: Vec<u8> = pool.into_iter().map(|(_, roll)| roll).collect();
         let dice_total: i32 = rolls.iter().map(|&x| x as i32).sum();
         let total = (dice_total + self.bonus as i32).max(0) as u16;
 
-        DamageRoll {
+        let roll = DamageRoll {
             rolls,
+            dropped,
             bonus: self.bonus,
             total,
-        }
+        };
+        (roll, faces)
     }
 
     // Convenience constructors for common patterns
@@ -84,6 +407,162 @@ impl DamageDice {
     }
 }
 
+/// Dice notation, grouping repeated dice: `2d6+1d4+2`
+///
+/// Exploding dice are marked `!`, and dropped dice are added to the
+/// largest group with `kh` giving how many are kept, as Roll20 writes them.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::core::dice::{DamageDice, Die};
+///
+/// let damage = DamageDice::new(vec![Die::D6, Die::D6, Die::D4]).with_bonus(2);
+/// assert_eq!(damage.to_string(), "2d6+1d4+2");
+/// assert_eq!(DamageDice::d8(1).with_bonus(-1).to_string(), "1d8-1");
+/// ```
+impl fmt::Display for DamageDice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut first = true;
+        let mut index = 0;
+        let mut extra = self.drop_lowest as usize;
+        let largest = self.largest();
+        while index < self.dice.len() {
+            let die = self.dice[index];
+            let count = self.dice[index..].iter().take_while(|&&d| d == die).count();
+            if !first {
+                write!(f, "+")?;
+            }
+            let added = if Some(die) == largest {
+                core::mem::take(&mut extra)
+            } else {
+                0
+            };
+            write!(f, "{}d{}", count + added, die.max())?;
+            if self.exploding {
+                write!(f, "!")?;
+            }
+            if added > 0 {
+                write!(f, "kh{}", count)?;
+            }
+            first = false;
+            index += count;
+        }
+
+        if first {
+            write!(f, "{}", self.bonus)
+        } else if self.bonus != 0 {
+            write!(f, "{:+}", self.bonus)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Expected sum of the highest `kept` of `pool` dice with `sides` sides
+fn kept_average(pool: usize, kept: usize, sides: u32) -> f32 {
+    // The i-th lowest die is at least x when fewer than i dice roll under x
+    let dropped = pool - kept;
+    let mut total = 0.0;
+    for rank in dropped + 1..=pool {
+        for x in 1..=sides {
+            let under = (x - 1) as f32 / sides as f32;
+            total += (0..rank)
+                .map(|j| binomial(pool, j) * power(under, j) * power(1.0 - under, pool - j))
+                .sum::<f32>();
+        }
+    }
+    total
+}
+
+/// Expected number of kept dice showing their maximum
+fn expected_maximums(pool: usize, kept: usize, sides: u32) -> f32 {
+    let top = 1.0 / sides as f32;
+    (0..=pool)
+        .map(|count| {
+            binomial(pool, count)
+                * power(top, count)
+                * power(1.0 - top, pool - count)
+                * count.min(kept) as f32
+        })
+        .sum()
+}
+
+/// Expected total of one die that keeps exploding
+fn exploded_average(sides: u32) -> f32 {
+    let sides = sides as f32;
+    if sides <= 1.0 {
+        return 1.0;
+    }
+    sides * (sides + 1.0) / (2.0 * (sides - 1.0))
+}
+
+/// Chances of each value of one die, which explodes up to `depth` times
+fn die_distribution(sides: usize, depth: usize) -> Vec<f64> {
+    let face = 1.0 / sides as f64;
+    let mut chances = vec![0.0; sides + 1];
+    chances[1..].fill(face);
+    for _ in 0..depth {
+        // A maximum adds another die, which may explode one time fewer
+        let mut next = vec![0.0; sides + chances.len()];
+        next[1..sides].fill(face);
+        for (value, chance) in chances.iter().enumerate() {
+            next[sides + value] += face * chance;
+        }
+        chances = next;
+    }
+    chances
+}
+
+/// Chances of a die's value when its first roll is at least
+/// `lowest_kept`, or `None` if it can't be
+fn kept_values(values: &[f64], sides: usize, lowest_kept: usize) -> Option<Vec<f64>> {
+    if lowest_kept > sides {
+        return None;
+    }
+    // Values from an explosion all start with a maximum, which is kept
+    let mut kept = values.to_vec();
+    kept[..lowest_kept].fill(0.0);
+    Some(kept)
+}
+
+/// Add `scale` times `b` into `a`, growing it to fit
+fn add_scaled(a: &mut Vec<f64>, b: &[f64], scale: f64) {
+    if a.len() < b.len() {
+        a.resize(b.len(), 0.0);
+    }
+    for (sum, chance) in a.iter_mut().zip(b) {
+        *sum += scale * chance;
+    }
+}
+
+/// Chances of the sum of two independent values
+pub(crate) fn convolve(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut sums = vec![0.0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate().filter(|(_, x)| **x > 0.0) {
+        for (j, y) in b.iter().enumerate() {
+            sums[i + j] += x * y;
+        }
+    }
+    sums
+}
+
+fn mean(chances: &[f64]) -> f64 {
+    chances
+        .iter()
+        .enumerate()
+        .map(|(total, chance)| total as f64 * chance)
+        .sum()
+}
+
+fn binomial(n: usize, k: usize) -> f32 {
+    (0..k).fold(1.0, |acc, i| acc * (n - i) as f32 / (i + 1) as f32)
+}
+
+fn power(base: f32, exponent: usize) -> f32 {
+    (0..exponent).fold(1.0, |acc, _| acc * base)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -113,6 +592,16 @@ mod tests {
         assert_eq!(damage.bonus, -2);
     }
 
+    #[test]
+    fn test_display_notation() {
+        use alloc::string::ToString;
+
+        let damage = DamageDice::new(vec![Die::D6, Die::D4, Die::D6]);
+        assert_eq!(damage.to_string(), "1d6+1d4+1d6");
+        assert_eq!(DamageDice::new(vec![]).with_bonus(3).to_string(), "3");
+    }
+
+    #[cfg(feature = "std")]
     #[test]
     fn test_roll_single_die() {
         let damage = DamageDice::new(vec![Die::D6]);
@@ -125,6 +614,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_roll_with_bonus() {
         let damage = DamageDice::new(vec![Die::D6]).with_bonus(3);
@@ -137,6 +627,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_roll_multiple_dice() {
         let damage = DamageDice::new(vec![Die::D6, Die::D6]);
@@ -149,6 +640,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_roll_multiple_dice_with_bonus() {
         let damage = DamageDice::new(vec![Die::D6, Die::D6]).with_bonus(5);
@@ -160,6 +652,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_longsword_tier_1() {
         // Longsword Tier 1: d10+3
@@ -172,6 +665,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_spear_tier_1() {
         // Spear Tier 1: d8+3
@@ -184,6 +678,67 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_drop_lowest_keeps_the_best_dice() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let damage = DamageDice::d8(2).with_bonus(1).with_drop_lowest(1);
+        let mut rng = StdRng::seed_from_u64(11);
+        for _ in 0..50 {
+            let roll = damage.roll_with_rng(&mut rng);
+            assert_eq!(roll.rolls.len(), 2);
+            assert_eq!(roll.dropped.len(), 1);
+            assert!(roll.rolls.iter().all(|&kept| kept >= roll.dropped[0]));
+            let sum: u16 = roll.rolls.iter().map(|&x| x as u16).sum();
+            assert_eq!(roll.total, sum + 1);
+        }
+        assert!(damage.average() > DamageDice::d8(2).with_bonus(1).average());
+    }
+
+    #[test]
+    fn test_exploding_dice_roll_again_on_maximum() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let damage = DamageDice::d4(2).with_exploding();
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut exploded = false;
+        for _ in 0..100 {
+            let roll = damage.roll_with_rng(&mut rng);
+            let maximums = roll.rolls.iter().filter(|&&r| r == 4).count();
+            // Every maximum adds a die
+            assert_eq!(roll.rolls.len(), 2 + maximums.min(MAX_EXPLOSIONS));
+            exploded |= maximums > 0;
+        }
+        assert!(exploded);
+        // A d4 averages 2.5, plus 1/4 of an exploding d4's 10/3
+        assert!((DamageDice::d4(1).with_exploding().average() - 10.0 / 3.0).abs() < 1e-5);
+    }
+
+    #[test]
+    fn test_feature_notation() {
+        use alloc::string::ToString;
+
+        let damage = DamageDice::new(vec![Die::D10, Die::D10, Die::D4])
+            .with_bonus(3)
+            .with_drop_lowest(1)
+            .with_exploding();
+        assert_eq!(damage.to_string(), "3d10!kh2+1d4!+3");
+    }
+
+    #[test]
+    fn test_roll_with_rng_reproducible() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let damage = DamageDice::d6(3).with_bonus(2);
+        let roll1 = damage.roll_with_rng(&mut StdRng::seed_from_u64(5));
+        let roll2 = damage.roll_with_rng(&mut StdRng::seed_from_u64(5));
+
+        assert_eq!(roll1, roll2);
+    }
+
     #[test]
     fn test_d6_convenience() {
         let damage = DamageDice::d6(2);
@@ -199,6 +754,7 @@ mod tests {
         assert_eq!(damage.dice[0], Die::D4);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_mixed_dice() {
         let damage = DamageDice::new(vec![Die::D8, Die::D6, Die::D4]);
@@ -212,6 +768,7 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_negative_bonus_doesnt_go_below_zero() {
         let damage = DamageDice::d4(1).with_bonus(-10);
@@ -225,6 +782,102 @@ mod tests {
             assert_eq!(roll.bonus, -10); // Bonus is preserved
         }
     }
+
+    #[test]
+    fn test_distribution_of_plain_dice() {
+        let chances = DamageDice::d6(2).distribution();
+        assert_eq!(chances.len(), 13);
+        for (total, ways) in [(2, 1.0), (7, 6.0), (12, 1.0)] {
+            assert!((chances[total] - ways / 36.0).abs() < 1e-12);
+        }
+
+        // Everything at or under 0 lands on 0
+        let floored = DamageDice::d4(1).with_bonus(-2).distribution();
+        assert_eq!(floored.len(), 3);
+        assert!((floored[0] - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_distribution_drops_and_explodes() {
+        // Best of two d4s: a 4 comes up in 7 of 16 rolls
+        let best = DamageDice::d4(1).with_drop_lowest(1).distribution();
+        assert!((best[4] - 7.0 / 16.0).abs() < 1e-12);
+        assert!((best[1] - 1.0 / 16.0).abs() < 1e-12);
+
+        let exploding = DamageDice::d4(1).with_exploding().distribution();
+        assert_eq!(exploding[4], 0.0);
+        assert!((exploding[5] - 1.0 / 16.0).abs() < 1e-12);
+        assert!((exploding.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+
+        let both = DamageDice::d6(2).with_drop_lowest(1).with_exploding();
+        assert!((both.expected() - both.average() as f64).abs() < 1e-3);
+    }
+
+    /// Chances of the kept dice's sum, going through every roll of the pool
+    fn brute_force(damage: &DamageDice) -> Vec<f64> {
+        let largest = damage.largest().unwrap();
+        let mut pool = damage.dice.clone();
+        pool.extend(core::iter::repeat_n(largest, damage.drop_lowest as usize));
+        let chance: f64 = pool.iter().map(|die| 1.0 / die.max() as f64).product();
+        let mut totals = vec![0.0];
+        let mut rolls = vec![1u8; pool.len()];
+        loop {
+            let mut kept: Vec<(Die, u8)> = pool.iter().copied().zip(rolls.clone()).collect();
+            for _ in 0..damage.drop_lowest {
+                let lowest = kept.iter().enumerate().min_by_key(|(_, (_, roll))| *roll);
+                kept.remove(lowest.unwrap().0);
+            }
+            let sums = kept.iter().fold(vec![1.0], |sums, &(die, roll)| {
+                let sides = die.max() as usize;
+                let mut value = vec![0.0; roll as usize + 1];
+                value[roll as usize] = 1.0;
+                if damage.exploding && roll == die.max() {
+                    value = convolve(&value, &die_distribution(sides, MAX_EXPLOSIONS - 1));
+                }
+                convolve(&sums, &value)
+            });
+            add_scaled(&mut totals, &sums, chance);
+
+            let mut index = 0;
+            while index < rolls.len() && rolls[index] == pool[index].max() {
+                rolls[index] = 1;
+                index += 1;
+            }
+            if index == rolls.len() {
+                return totals;
+            }
+            rolls[index] += 1;
+        }
+    }
+
+    #[test]
+    fn test_kept_distribution_matches_every_roll() {
+        let pools = [
+            DamageDice::new(vec![Die::D8, Die::D6]).with_drop_lowest(1),
+            DamageDice::new(vec![Die::D6, Die::D4, Die::D6]).with_drop_lowest(2),
+            DamageDice::new(vec![Die::D4, Die::D6])
+                .with_drop_lowest(1)
+                .with_exploding(),
+            DamageDice::d4(3).with_drop_lowest(2).with_exploding(),
+        ];
+        for damage in pools {
+            let expected = brute_force(&damage);
+            let chances = damage.distribution();
+            assert_eq!(chances.len(), expected.len(), "{}", damage);
+            for (chance, expected) in chances.iter().zip(&expected) {
+                assert!((chance - expected).abs() < 1e-12, "{}", damage);
+            }
+        }
+    }
+
+    #[test]
+    fn test_large_pools_are_quick() {
+        // A level 10 Powerful d12 weapon keeps 5 of 6d12
+        let chances = DamageDice::d12(5).with_drop_lowest(1).distribution();
+        assert!((chances.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+        let exploding = DamageDice::d12(6).with_drop_lowest(2).with_exploding();
+        assert!((exploding.expected() - exploding.average() as f64).abs() < 1e-2);
+    }
 }
 
 #[cfg(test)]
@@ -243,11 +896,13 @@ mod property_tests {
         ]
     }
 
+    #[cfg(feature = "std")]
     fn die_vec() -> impl Strategy<Value = Vec<Die>> {
         prop::collection::vec(any_die(), 1..=5)
     }
 
     proptest! {
+        #[cfg(feature = "std")]
         #[test]
         fn prop_damage_total_includes_bonus(
             dice in die_vec(),
@@ -263,6 +918,7 @@ mod property_tests {
             prop_assert_eq!(roll.total, expected);
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn prop_damage_rolls_count_matches_dice(dice in die_vec()) {
             let damage_dice = DamageDice::new(dice.clone());
@@ -271,6 +927,7 @@ mod property_tests {
             prop_assert_eq!(roll.rolls.len(), dice.len());
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn prop_each_die_roll_is_valid(dice in die_vec()) {
             let damage_dice = DamageDice::new(dice.clone());
@@ -283,6 +940,7 @@ mod property_tests {
             }
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn prop_bonus_is_preserved(dice in die_vec(), bonus in -20i16..=20) {
             let damage_dice = DamageDice::new(dice).with_bonus(bonus);
@@ -291,6 +949,7 @@ mod property_tests {
             prop_assert_eq!(roll.bonus, bonus);
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn prop_negative_bonus_never_goes_negative(
             dice in die_vec(),
@@ -324,6 +983,7 @@ mod property_tests {
             }
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn prop_minimum_damage_with_positive_bonus(
             count in 1usize..=5,
@@ -337,6 +997,7 @@ mod property_tests {
             prop_assert!(roll.total >= min_possible);
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn prop_maximum_damage_respects_limits(
             count in 1usize..=5,
@@ -349,5 +1010,26 @@ mod property_tests {
             let max_possible = count as u16 * 6 + bonus as u16;
             prop_assert!(roll.total <= max_possible);
         }
+
+        #[test]
+        fn prop_distribution_matches_average(
+            die in any_die(),
+            count in 0usize..=3,
+            bonus in -5i16..=5,
+            drop_lowest in 0u8..=1,
+        ) {
+            // `average` is only exact for dropped dice that match
+            let damage = DamageDice::new(vec![die; count])
+                .with_bonus(bonus)
+                .with_drop_lowest(drop_lowest);
+            let chances = damage.distribution();
+
+            prop_assert!((chances.iter().sum::<f64>() - 1.0).abs() < 1e-9);
+            prop_assert!(damage.variance() >= 0.0);
+            // Without the floor at 0 in play, the mean is the average
+            if bonus >= 0 {
+                prop_assert!((damage.expected() - damage.average() as f64).abs() < 1e-3);
+            }
+        }
     }
 }
@@ -3,18 +3,20 @@
 //! The core mechanic of Daggerheart: rolling two d12s simultaneously,
 //! one representing Hope and one representing Fear.
 
+use core::cmp::Ordering;
 use rand::Rng;
-use std::cmp::Ordering;
 
 /// A roll of the duality dice (2d12: Hope and Fear)
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DualityRoll {
     pub hope: u8,
     pub fear: u8,
 }
 
 /// Which die controls the outcome
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum ControllingDie {
     Hope,
     Fear,
@@ -22,7 +24,8 @@ pub enum ControllingDie {
 }
 
 /// The result of a duality roll with modifiers
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct DualityResult {
     pub roll: DualityRoll,
     pub modifier: i8,
@@ -33,7 +36,8 @@ pub struct DualityResult {
 }
 
 /// Type of success based on the roll
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub enum SuccessType {
     Failure,
     SuccessWithHope,
@@ -42,9 +46,15 @@ pub enum SuccessType {
 }
 
 impl DualityRoll {
+    #[cfg(feature = "std")]
     /// Roll both hope and fear dice
     pub fn roll() -> Self {
         let mut rng = rand::thread_rng();
+        Self::roll_with_rng(&mut rng)
+    }
+
+    /// Roll both dice with a specific RNG (for testing/seeding)
+    pub fn roll_with_rng<R: Rng>(rng: &mut R) -> Self {
         let hope = rng.gen_range(1..=12);
         let fear = rng.gen_range(1..=12);
         Self { hope, fear }
@@ -83,9 +93,15 @@ impl DualityRoll {
         }
     }
 
+    #[cfg(feature = "std")]
     /// Apply advantage (roll extra d6) to create a DualityResult
     pub fn with_advantage(self) -> DualityResult {
         let mut rng = rand::thread_rng();
+        self.with_advantage_with_rng(&mut rng)
+    }
+
+    /// Apply advantage using a specific RNG for the d6 (for testing/seeding)
+    pub fn with_advantage_with_rng<R: Rng>(self, rng: &mut R) -> DualityResult {
         let d6 = rng.gen_range(1..=6);
 
         let total = self.hope as u16 + self.fear as u16 + d6 as u16;
@@ -187,6 +203,7 @@ mod tests {
         assert_eq!(result.total, 8 + 5 - 1);
     }
 
+    #[cfg(feature = "std")]
     #[test]
     fn test_with_advantage() {
         let roll = DualityRoll::from_values(5, 7);
@@ -248,6 +265,21 @@ mod tests {
         assert_eq!(result.success_type(12), SuccessType::Failure);
     }
 
+    #[test]
+    fn test_roll_with_rng_reproducible() {
+        use rand::rngs::StdRng;
+        use rand::SeedableRng;
+
+        let mut rng1 = StdRng::seed_from_u64(42);
+        let mut rng2 = StdRng::seed_from_u64(42);
+
+        assert_eq!(
+            DualityRoll::roll_with_rng(&mut rng1),
+            DualityRoll::roll_with_rng(&mut rng2)
+        );
+    }
+
+    #[cfg(feature = "std")]
     #[test]
     fn test_roll_produces_valid_values() {
         for _ in 0..20 {
@@ -349,6 +381,7 @@ mod property_tests {
             }
         }
 
+        #[cfg(feature = "std")]
         #[test]
         fn prop_advantage_adds_d6(hope in d12_value(), fear in d12_value()) {
             let roll = DualityRoll::from_values(hope, fear);
@@ -0,0 +1,258 @@
+//! Opposed rolls - two sides rolling against each other
+//!
+//! Grapples, contests of strength and social standoffs are settled by
+//! both sides rolling and comparing totals instead of meeting a
+//! Difficulty. Player characters make an action roll with the duality
+//! dice; adversaries and NPCs roll a d20. A critical beats any total, and
+//! each character's roll still earns them Hope or gives the GM Fear.
+
+use crate::core::dice::{ControllingDie, Die, DualityResult, DualityRoll};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+/// One side's roll in a contest
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum ContestRoll {
+    /// A player character's action roll
+    Duality(DualityResult),
+    /// An adversary or NPC's d20 plus their modifier
+    Adversary { roll: u8, modifier: i8, total: u16 },
+}
+
+impl ContestRoll {
+    /// Roll the duality dice plus `modifier`
+    pub fn duality_with_rng<R: Rng>(modifier: i8, rng: &mut R) -> Self {
+        let mut result = DualityRoll::roll_with_rng(rng).with_modifier(modifier);
+        result.total = (i16::from(result.roll.hope)
+            + i16::from(result.roll.fear)
+            + i16::from(modifier))
+        .max(0) as u16;
+        ContestRoll::Duality(result)
+    }
+
+    /// Roll a d20 plus `modifier`
+    pub fn adversary_with_rng<R: Rng>(modifier: i8, rng: &mut R) -> Self {
+        let roll = Die::D20.roll_with_rng(rng);
+        ContestRoll::Adversary {
+            roll,
+            modifier,
+            total: (i16::from(roll) + i16::from(modifier)).max(0) as u16,
+        }
+    }
+
+    /// The total compared against the other side's
+    pub fn total(&self) -> u16 {
+        match self {
+            ContestRoll::Duality(result) => result.total,
+            ContestRoll::Adversary { total, .. } => *total,
+        }
+    }
+
+    /// Doubles on the duality dice, or a natural 20
+    pub fn is_critical(&self) -> bool {
+        match self {
+            ContestRoll::Duality(result) => result.is_critical,
+            ContestRoll::Adversary { roll, .. } => *roll == 20,
+        }
+    }
+
+    /// Whether the roller gains a Hope: rolled with Hope or a critical
+    pub fn gains_hope(&self) -> bool {
+        match self {
+            ContestRoll::Duality(result) => {
+                result.is_critical || result.controlling == ControllingDie::Hope
+            }
+            ContestRoll::Adversary { .. } => false,
+        }
+    }
+
+    /// Whether the GM gains a Fear: a character rolled with Fear
+    pub fn gives_fear(&self) -> bool {
+        match self {
+            ContestRoll::Duality(result) => {
+                !result.is_critical && result.controlling == ControllingDie::Fear
+            }
+            ContestRoll::Adversary { .. } => false,
+        }
+    }
+}
+
+/// One of the two sides of a contest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Side {
+    First,
+    Second,
+}
+
+/// How a contest came out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OpposedResult {
+    pub first: ContestRoll,
+    pub second: ContestRoll,
+    /// `None` on a tie: neither side gets the upper hand
+    pub winner: Option<Side>,
+    /// How far the winner's total beat the other's; 0 on a tie, or when
+    /// a critical won with the lower total
+    pub margin: u16,
+}
+
+impl OpposedResult {
+    /// Compare two rolls
+    ///
+    /// The higher total wins, except that a critical beats anything but
+    /// another critical. Equal totals, or two criticals with equal
+    /// totals, are a tie.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::core::dice::{ContestRoll, DualityRoll, OpposedResult, Side};
+    ///
+    /// let grom = ContestRoll::Duality(DualityRoll::from_values(9, 4).with_modifier(2));
+    /// let lia = ContestRoll::Duality(DualityRoll::from_values(3, 8).with_modifier(1));
+    ///
+    /// let result = OpposedResult::resolve(grom, lia);
+    /// assert_eq!(result.winner, Some(Side::First));
+    /// assert_eq!(result.margin, 3);
+    /// assert!(result.gains_hope(Side::First));
+    /// assert_eq!(result.fear_gained(), 1);
+    /// ```
+    pub fn resolve(first: ContestRoll, second: ContestRoll) -> Self {
+        let (a, b) = (first.total(), second.total());
+        let winner = match (first.is_critical(), second.is_critical()) {
+            (true, false) => Some(Side::First),
+            (false, true) => Some(Side::Second),
+            _ if a > b => Some(Side::First),
+            _ if b > a => Some(Side::Second),
+            _ => None,
+        };
+        let margin = match winner {
+            Some(Side::First) => a.saturating_sub(b),
+            Some(Side::Second) => b.saturating_sub(a),
+            None => 0,
+        };
+        Self {
+            first,
+            second,
+            winner,
+            margin,
+        }
+    }
+
+    /// A side's roll
+    pub fn roll(&self, side: Side) -> &ContestRoll {
+        match side {
+            Side::First => &self.first,
+            Side::Second => &self.second,
+        }
+    }
+
+    /// Whether the contest was a tie
+    pub fn is_tie(&self) -> bool {
+        self.winner.is_none()
+    }
+
+    /// Whether `side` gains a Hope from their roll
+    pub fn gains_hope(&self, side: Side) -> bool {
+        self.roll(side).gains_hope()
+    }
+
+    /// Fear the GM gains from the two rolls
+    pub fn fear_gained(&self) -> u8 {
+        u8::from(self.first.gives_fear()) + u8::from(self.second.gives_fear())
+    }
+}
+
+#[cfg(feature = "std")]
+/// Two characters roll against each other
+pub fn opposed_roll(a_mod: i8, b_mod: i8) -> OpposedResult {
+    let mut rng = rand::thread_rng();
+    opposed_roll_with_rng(a_mod, b_mod, &mut rng)
+}
+
+/// Two characters roll against each other with a specific RNG (for
+/// testing/seeding)
+pub fn opposed_roll_with_rng<R: Rng>(a_mod: i8, b_mod: i8, rng: &mut R) -> OpposedResult {
+    let first = ContestRoll::duality_with_rng(a_mod, rng);
+    let second = ContestRoll::duality_with_rng(b_mod, rng);
+    OpposedResult::resolve(first, second)
+}
+
+#[cfg(feature = "std")]
+/// A character rolls against an adversary or NPC
+pub fn opposed_roll_vs_adversary(pc_mod: i8, adversary_mod: i8) -> OpposedResult {
+    let mut rng = rand::thread_rng();
+    opposed_roll_vs_adversary_with_rng(pc_mod, adversary_mod, &mut rng)
+}
+
+/// A character rolls against an adversary or NPC with a specific RNG (for
+/// testing/seeding)
+pub fn opposed_roll_vs_adversary_with_rng<R: Rng>(
+    pc_mod: i8,
+    adversary_mod: i8,
+    rng: &mut R,
+) -> OpposedResult {
+    let first = ContestRoll::duality_with_rng(pc_mod, rng);
+    let second = ContestRoll::adversary_with_rng(adversary_mod, rng);
+    OpposedResult::resolve(first, second)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn duality(hope: u8, fear: u8, modifier: i8) -> ContestRoll {
+        ContestRoll::Duality(DualityRoll::from_values(hope, fear).with_modifier(modifier))
+    }
+
+    #[test]
+    fn test_criticals_beat_higher_totals() {
+        let result = OpposedResult::resolve(duality(11, 10, 3), duality(2, 2, 0));
+        assert_eq!(result.winner, Some(Side::Second));
+        assert_eq!(result.margin, 0);
+        assert!(result.gains_hope(Side::Second));
+        assert_eq!(result.fear_gained(), 0);
+
+        let natural = ContestRoll::Adversary {
+            roll: 20,
+            modifier: 0,
+            total: 20,
+        };
+        let result = OpposedResult::resolve(duality(12, 11, 2), natural);
+        assert_eq!(result.winner, Some(Side::Second));
+    }
+
+    #[test]
+    fn test_equal_totals_tie() {
+        let result = OpposedResult::resolve(duality(6, 4, 0), duality(3, 5, 2));
+        assert!(result.is_tie());
+        assert_eq!(result.margin, 0);
+        assert_eq!(result.fear_gained(), 1);
+
+        let both = OpposedResult::resolve(duality(5, 5, 0), duality(4, 4, 2));
+        assert!(both.is_tie());
+    }
+
+    #[test]
+    fn test_adversaries_earn_no_hope_or_fear() {
+        let mut rng = StdRng::seed_from_u64(5);
+        for _ in 0..50 {
+            let result = opposed_roll_vs_adversary_with_rng(1, -30, &mut rng);
+            assert!(!result.gains_hope(Side::Second));
+            assert!(matches!(
+                result.second,
+                ContestRoll::Adversary { total: 0, .. }
+            ));
+            assert!(result.fear_gained() <= 1);
+            // Only a natural 20 saves the adversary
+            if result.winner == Some(Side::Second) {
+                assert!(result.second.is_critical());
+            }
+        }
+    }
+}
@@ -0,0 +1,122 @@
+//! Standard difficulties
+//!
+//! Action rolls are made against a Difficulty the GM sets. Rather than
+//! picking a number each time, callers can ask for an easy, average or hard
+//! roll at the party's tier; each step is worth 3.
+
+use core::fmt;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// How hard a task is for characters of its tier
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, EnumIter, Display, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Challenge {
+    Easy,
+    #[default]
+    Average,
+    Hard,
+}
+
+/// The number an action roll's total has to meet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Difficulty(pub u16);
+
+impl Difficulty {
+    /// An average task at tier 1
+    pub const AVERAGE: Difficulty = Difficulty(12);
+
+    /// The standard Difficulty of a task at a tier (1-4)
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::core::{Challenge, Difficulty};
+    ///
+    /// assert_eq!(Difficulty::for_tier(1, Challenge::Average), Difficulty::AVERAGE);
+    /// assert_eq!(Difficulty::for_tier(1, Challenge::Hard).value(), 15);
+    /// assert_eq!(Difficulty::for_tier(3, Challenge::Easy).value(), 15);
+    /// ```
+    pub fn for_tier(tier: u8, challenge: Challenge) -> Self {
+        let average = Self::AVERAGE.0 + 3 * Self::steps(tier);
+        Difficulty(match challenge {
+            Challenge::Easy => average - 3,
+            Challenge::Average => average,
+            Challenge::Hard => average + 3,
+        })
+    }
+
+    /// The usual Difficulty of an adversary at a tier (1-4), also used as
+    /// its Evasion
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::core::Difficulty;
+    ///
+    /// assert_eq!(Difficulty::adversary(1).value(), 11);
+    /// assert_eq!(Difficulty::adversary(4).value(), 20);
+    /// ```
+    pub fn adversary(tier: u8) -> Self {
+        Difficulty(11 + 3 * Self::steps(tier))
+    }
+
+    /// The number to meet
+    pub fn value(self) -> u16 {
+        self.0
+    }
+
+    /// Tiers above the first, with the tier clamped to 1-4
+    fn steps(tier: u8) -> u16 {
+        tier.clamp(1, 4) as u16 - 1
+    }
+}
+
+impl Default for Difficulty {
+    fn default() -> Self {
+        Self::AVERAGE
+    }
+}
+
+impl From<Difficulty> for u16 {
+    fn from(difficulty: Difficulty) -> Self {
+        difficulty.0
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_challenges_step_by_three() {
+        for tier in 1..=4 {
+            let values: Vec<u16> = Challenge::iter()
+                .map(|challenge| Difficulty::for_tier(tier, challenge).value())
+                .collect();
+            assert_eq!(values[1] - values[0], 3);
+            assert_eq!(values[2] - values[1], 3);
+        }
+    }
+
+    #[test]
+    fn test_tiers_are_clamped() {
+        assert_eq!(
+            Difficulty::for_tier(0, Challenge::Average),
+            Difficulty::for_tier(1, Challenge::Average)
+        );
+        assert_eq!(Difficulty::adversary(9), Difficulty::adversary(4));
+        assert!(Difficulty::adversary(2) > Difficulty::adversary(1));
+    }
+}
@@ -3,13 +3,20 @@
 //! This module contains the fundamental systems:
 //! - Dice rolling (duality dice, damage dice)
 //! - Roll resolution
+//! - Opposed rolls between two sides
+//! - Standard difficulties
 //! - Hope and Fear mechanics
+//! - Roll odds
 //! - Action tokens and resources
 
 pub mod dice;
+pub mod difficulty;
+pub mod stats;
 // pub mod roll;
 // pub mod resources;
 
 pub use dice::{
-    ControllingDie, DamageDice, DamageRoll, Die, DualityResult, DualityRoll, SuccessType,
+    ContestRoll, ControllingDie, DamageDice, DamageRoll, Die, DualityResult, DualityRoll,
+    OpposedResult, SuccessType,
 };
+pub use difficulty::{Challenge, Difficulty};
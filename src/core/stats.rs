@@ -0,0 +1,72 @@
+//! Roll odds - chances of a duality roll before it's made
+//!
+//! Works the chances out exactly over every way the Hope and Fear dice
+//! (and an advantage die) can land, so a UI can show a player their odds
+//! before they commit Hope to a roll.
+
+/// Chance a duality roll plus `modifier` meets `difficulty`
+///
+/// Doubles are a critical success and always succeed, as attacks treat
+/// them. With advantage, a d6 is added to the total.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::core::stats::duality_success_chance;
+///
+/// let plain = duality_success_chance(1, 12, false);
+/// assert!(plain > 0.5 && plain < 1.0);
+/// assert!(duality_success_chance(1, 12, true) > plain);
+/// // Only doubles reach an impossible number
+/// assert_eq!(duality_success_chance(0, 40, false), 1.0 / 12.0);
+/// ```
+pub fn duality_success_chance(modifier: i8, difficulty: u16, advantage: bool) -> f64 {
+    let advantage_dice: &[i32] = if advantage { &[1, 2, 3, 4, 5, 6] } else { &[0] };
+    let mut successes = 0u32;
+    for hope in 1..=12i32 {
+        for fear in 1..=12i32 {
+            for &d6 in advantage_dice {
+                let total = (hope + fear + d6 + modifier as i32).max(0);
+                if hope == fear || total >= difficulty as i32 {
+                    successes += 1;
+                }
+            }
+        }
+    }
+    successes as f64 / (144 * advantage_dice.len()) as f64
+}
+
+/// Chance a duality roll comes up doubles, a critical success
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::core::stats::crit_chance;
+///
+/// assert_eq!(crit_chance(), 1.0 / 12.0);
+/// ```
+pub fn crit_chance() -> f64 {
+    12.0 / 144.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_success_chance_bounds() {
+        assert_eq!(duality_success_chance(0, 2, false), 1.0);
+        assert_eq!(duality_success_chance(0, 0, true), 1.0);
+        assert_eq!(duality_success_chance(-10, 40, true), crit_chance());
+    }
+
+    #[test]
+    fn test_success_chance_counts_rolls() {
+        // 78 rolls make 13 or more, and the 6 doubles under it succeed anyway
+        assert_eq!(duality_success_chance(0, 13, false), 84.0 / 144.0);
+        assert_eq!(
+            duality_success_chance(2, 15, false),
+            duality_success_chance(0, 13, false)
+        );
+    }
+}
@@ -0,0 +1,395 @@
+//! Downtime - rests and long-term projects between sessions
+//!
+//! When the party rests, each character picks downtime moves: tending
+//! wounds, clearing Stress, or preparing. Longer undertakings such as
+//! crafting, research, or building a reputation are tracked as project
+//! clocks that fill a segment at a time as the character rolls to make
+//! progress.
+
+use crate::character::progression::tier_for_level;
+use crate::character::AttributeType;
+use crate::combat::modifiers::RollKind;
+use crate::combat::simulation::Combatant;
+use crate::combat::Hope;
+use crate::core::dice::{Die, DualityResult, DualityRoll, SuccessType};
+use crate::error::EngineError;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// Downtime moves each character makes per rest
+pub const MOVES_PER_REST: usize = 2;
+
+/// How long the party rests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum RestKind {
+    /// About an hour; moves recover 1d4 + tier
+    Short,
+    /// A full night; moves recover everything
+    Long,
+}
+
+/// A downtime move made during a rest
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum RestActivity {
+    /// Clear Hit Points
+    #[strum(serialize = "Tend to Wounds")]
+    TendToWounds,
+    /// Clear Stress
+    #[strum(serialize = "Clear Stress")]
+    ClearStress,
+    /// Gain a Hope
+    Prepare,
+}
+
+impl RestActivity {
+    /// Make this move for `combatant`, returning how much it recovered
+    ///
+    /// The amount is HP or Stress cleared, or Hope gained for `Prepare`.
+    /// Prepared Hope goes to `shared_hope` if the table shares a pool, and
+    /// to the character otherwise.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, Class};
+    /// use daggerheart_engine::combat::simulation::CombatantBuilder;
+    /// use daggerheart_engine::downtime::{RestActivity, RestKind};
+    ///
+    /// let mut grom = CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///     .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///     .build()
+    ///     .unwrap();
+    /// grom.gain_stress(4);
+    ///
+    /// let cleared = RestActivity::ClearStress.apply(RestKind::Long, &mut grom, None);
+    /// assert_eq!(cleared, 4);
+    /// assert_eq!(grom.stress.current, 0);
+    ///
+    /// RestActivity::Prepare.apply(RestKind::Short, &mut grom, None);
+    /// assert_eq!(grom.hope().unwrap().current, 3);
+    /// ```
+    pub fn apply(
+        self,
+        rest: RestKind,
+        combatant: &mut Combatant,
+        shared_hope: Option<&mut Hope>,
+    ) -> u8 {
+        let mut rng = rand::thread_rng();
+        self.apply_with_rng(rest, combatant, shared_hope, &mut rng)
+    }
+
+    /// Make this move with a specific RNG (for testing)
+    pub fn apply_with_rng<R: Rng>(
+        self,
+        rest: RestKind,
+        combatant: &mut Combatant,
+        shared_hope: Option<&mut Hope>,
+        rng: &mut R,
+    ) -> u8 {
+        let recovery = match rest {
+            RestKind::Short => Die::D4.roll_with_rng(rng) + tier_for_level(combatant.level),
+            RestKind::Long => u8::MAX,
+        };
+
+        match self {
+            RestActivity::TendToWounds => {
+                let before = combatant.hp.current;
+                combatant.hp.heal(recovery.min(combatant.hp.maximum));
+                combatant.hp.current - before
+            }
+            RestActivity::ClearStress => {
+                let cleared = recovery.min(combatant.stress.current);
+                combatant.stress.current -= cleared;
+                cleared
+            }
+            RestActivity::Prepare => {
+                let hope = match shared_hope {
+                    Some(hope) => hope,
+                    None => match combatant.hope_mut() {
+                        Ok(hope) => hope,
+                        Err(_) => return 0,
+                    },
+                };
+                let before = hope.current;
+                hope.gain(1);
+                hope.current - before
+            }
+        }
+    }
+}
+
+/// A downtime move that was made
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ActivityRecord {
+    pub rest: RestKind,
+    pub activity: RestActivity,
+    /// HP or Stress cleared, or Hope gained
+    pub amount: u8,
+}
+
+/// A long-term project, tracked as a clock of segments to fill
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ProjectClock {
+    pub name: String,
+    /// Segments needed to finish
+    pub segments: u8,
+    /// Segments filled so far
+    pub progress: u8,
+}
+
+/// The outcome of a roll to work on a project
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ProjectRoll {
+    pub result: DualityResult,
+    pub difficulty: u16,
+    pub outcome: SuccessType,
+    /// Segments filled by this roll
+    pub ticks: u8,
+    /// Whether this roll finished the project
+    pub completed: bool,
+}
+
+impl ProjectClock {
+    /// Start a project with nothing filled
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::downtime::ProjectClock;
+    ///
+    /// let mut forge = ProjectClock::new("Reforge the blade", 6);
+    /// forge.tick(4);
+    /// assert_eq!(forge.remaining(), 2);
+    /// assert!(!forge.is_complete());
+    /// ```
+    pub fn new(name: impl Into<String>, segments: u8) -> Self {
+        Self {
+            name: name.into(),
+            segments: segments.max(1),
+            progress: 0,
+        }
+    }
+
+    /// Fill up to `amount` segments, returning how many were filled
+    pub fn tick(&mut self, amount: u8) -> u8 {
+        let filled = amount.min(self.remaining());
+        self.progress += filled;
+        filled
+    }
+
+    /// Segments left to fill
+    pub fn remaining(&self) -> u8 {
+        self.segments.saturating_sub(self.progress)
+    }
+
+    /// Whether every segment is filled
+    pub fn is_complete(&self) -> bool {
+        self.progress >= self.segments
+    }
+
+    /// Roll to make progress, using a trait modifier against `difficulty`
+    ///
+    /// A success with Fear fills one segment, a success with Hope two, and
+    /// a critical success three. A failure fills nothing.
+    pub fn advance(&mut self, modifier: i8, difficulty: u16) -> ProjectRoll {
+        let mut rng = rand::thread_rng();
+        self.advance_with_rng(modifier, difficulty, &mut rng)
+    }
+
+    /// Roll to make progress with a specific RNG (for testing)
+    pub fn advance_with_rng<R: Rng>(
+        &mut self,
+        modifier: i8,
+        difficulty: u16,
+        rng: &mut R,
+    ) -> ProjectRoll {
+        let result = DualityRoll::roll_with_rng(rng).with_modifier(modifier);
+        self.resolve(result, difficulty)
+    }
+
+    /// Apply an already rolled result to the clock
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::core::dice::DualityRoll;
+    /// use daggerheart_engine::downtime::ProjectClock;
+    ///
+    /// let mut research = ProjectClock::new("Decipher the codex", 4);
+    /// let roll = research.resolve(DualityRoll::from_values(9, 4).with_modifier(1), 12);
+    /// assert_eq!(roll.ticks, 2);
+    /// assert_eq!(research.progress, 2);
+    /// ```
+    pub fn resolve(&mut self, result: DualityResult, difficulty: u16) -> ProjectRoll {
+        let outcome = result.success_type(difficulty);
+        let ticks = self.tick(match outcome {
+            SuccessType::Failure => 0,
+            SuccessType::SuccessWithFear => 1,
+            SuccessType::SuccessWithHope => 2,
+            SuccessType::CriticalSuccess => 3,
+        });
+
+        ProjectRoll {
+            result,
+            difficulty,
+            outcome,
+            ticks,
+            completed: self.is_complete(),
+        }
+    }
+}
+
+/// One character's projects and downtime history
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DowntimeRecord {
+    /// Name of the party member
+    pub character: String,
+    pub projects: Vec<ProjectClock>,
+    pub activities: Vec<ActivityRecord>,
+}
+
+impl DowntimeRecord {
+    /// An empty record for a character
+    pub fn new(character: impl Into<String>) -> Self {
+        Self {
+            character: character.into(),
+            projects: Vec::new(),
+            activities: Vec::new(),
+        }
+    }
+
+    /// Find a project by name (ignoring case)
+    pub fn project(&self, name: &str) -> Option<&ProjectClock> {
+        self.projects
+            .iter()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Find a project by name (ignoring case) for mutation
+    pub fn project_mut(&mut self, name: &str) -> Option<&mut ProjectClock> {
+        self.projects
+            .iter_mut()
+            .find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Projects that still have segments to fill
+    pub fn open_projects(&self) -> impl Iterator<Item = &ProjectClock> {
+        self.projects.iter().filter(|p| !p.is_complete())
+    }
+}
+
+/// The modifier a character rolls with for a trait
+pub(crate) fn trait_modifier(
+    combatant: &Combatant,
+    attribute: AttributeType,
+) -> Result<i8, EngineError> {
+    combatant
+        .attributes()
+        .map(|attributes| {
+            attributes
+                .get_modifier(attribute)
+                .saturating_add(combatant.total_bonus_for(RollKind::Action))
+        })
+        .ok_or_else(|| EngineError::MissingStat {
+            combatant: combatant.name.to_string(),
+            stat: "attributes",
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Class};
+    use crate::test_support::player;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn create_test_combatant() -> Combatant {
+        player("Grom", Class::Warrior, Ancestry::Orc)
+            .with_level(2)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_project_ticks_by_outcome() {
+        let cases = [
+            ((3, 2), 0, false),
+            ((4, 9), 1, false),
+            ((9, 4), 2, false),
+            ((6, 6), 3, true),
+        ];
+        for ((hope, fear), ticks, completed) in cases {
+            let mut clock = ProjectClock::new("Build a boat", 3);
+            let roll = clock.resolve(DualityRoll::from_values(hope, fear).with_modifier(0), 12);
+            assert_eq!(roll.ticks, ticks, "{}/{}", hope, fear);
+            assert_eq!(roll.completed, completed);
+        }
+
+        // Ticks never overfill the clock
+        let mut clock = ProjectClock::new("Build a boat", 3);
+        clock.tick(2);
+        assert_eq!(clock.tick(3), 1);
+        assert!(clock.is_complete());
+        assert_eq!(ProjectClock::new("Nothing", 0).segments, 1);
+    }
+
+    #[test]
+    fn test_short_rest_recovers_d4_plus_tier() {
+        let mut rng = StdRng::seed_from_u64(3);
+        for _ in 0..20 {
+            let mut grom = create_test_combatant();
+            grom.hp.current = 0;
+            grom.stress.current = 6;
+
+            let healed = RestActivity::TendToWounds.apply_with_rng(
+                RestKind::Short,
+                &mut grom,
+                None,
+                &mut rng,
+            );
+            // Level 2 is tier 2
+            assert!((3..=6).contains(&healed), "healed {}", healed);
+            assert_eq!(grom.hp.current, healed.min(grom.hp.maximum));
+
+            let cleared = RestActivity::ClearStress.apply_with_rng(
+                RestKind::Short,
+                &mut grom,
+                None,
+                &mut rng,
+            );
+            assert_eq!(grom.stress.current, 6 - cleared);
+        }
+    }
+
+    #[test]
+    fn test_long_rest_and_prepare() {
+        let mut grom = create_test_combatant();
+        grom.hp.current = 1;
+        let mut hope = Hope::new(5);
+        hope.spend(3).unwrap();
+
+        RestActivity::TendToWounds.apply(RestKind::Long, &mut grom, None);
+        assert!(grom.hp.is_full());
+
+        assert_eq!(
+            RestActivity::Prepare.apply(RestKind::Short, &mut grom, Some(&mut hope)),
+            1
+        );
+        assert_eq!(hope.current, 3);
+        assert_eq!(grom.hope().unwrap().current, 2);
+
+        grom.hope_mut().unwrap().gain(6);
+        assert_eq!(
+            RestActivity::Prepare.apply(RestKind::Short, &mut grom, None),
+            0
+        );
+    }
+}
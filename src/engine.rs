@@ -0,0 +1,733 @@
+//! Game engine - one entry point for running a table
+//!
+//! A `GameEngine` owns a campaign, the content it draws on, the house
+//! rules, an RNG and the hooks that watch it all. Its methods take names
+//! and content ids, look up what they refer to, and route each operation
+//! to the party sheet or, while a fight is on, the encounter in progress,
+//! so an application has one place to call instead of wiring the pieces
+//! together itself.
+//!
+//! Every operation can also be sent as a [`Command`], which the engine
+//! keeps a history of to undo and replay.
+
+pub mod command;
+
+pub use command::Command;
+
+use crate::campaign::Campaign;
+use crate::character::{AttributeType, CharacterSheet};
+use crate::combat::attack::{Attack, AttackResult};
+use crate::combat::events::CombatEvent;
+use crate::combat::montecarlo::AttackProfile;
+use crate::combat::simulation::{CombatEncounter, CombatantId, HopeMode};
+use crate::content::{ContentId, ContentRegistry};
+use crate::core::dice::{ControllingDie, DualityResult, DualityRoll, SuccessType};
+use crate::downtime::{self, ActivityRecord, RestActivity, RestKind};
+use crate::error::EngineError;
+use crate::hooks::{EngineHooks, Hooks};
+use crate::rules::RulesConfig;
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::sync::Arc;
+
+/// Most commands that can be undone in a row
+pub const UNDO_LIMIT: usize = 64;
+
+/// An action roll made through the engine
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ActionRoll {
+    pub result: DualityResult,
+    pub difficulty: u16,
+    pub outcome: SuccessType,
+}
+
+/// A campaign with its content, rules, dice and observers
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::campaign::Campaign;
+/// use daggerheart_engine::character::{Ancestry, AttributeType, Attributes, CharacterSheet, Class};
+/// use daggerheart_engine::combat::CombatantBuilder;
+/// use daggerheart_engine::content::ContentRegistry;
+/// use daggerheart_engine::engine::GameEngine;
+///
+/// let mut engine = GameEngine::new(Campaign::new("The Witherwild", 5))
+///     .with_content(ContentRegistry::builtin())
+///     .with_seed(7);
+/// engine
+///     .add_character(CharacterSheet::new(
+///         CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+///             .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+///             .build()
+///             .unwrap(),
+///     ))
+///     .unwrap();
+///
+/// let roll = engine.roll_action("Grom", AttributeType::Strength, 12).unwrap();
+/// assert_eq!(roll.result.modifier, 1);
+/// assert_eq!(engine.campaign().roll_log.len(), 1);
+///
+/// let encounter = engine.begin_encounter("Ambush", &["Bear"]).unwrap();
+/// assert_eq!(encounter.combatants.len(), 2);
+/// ```
+#[derive(Debug)]
+pub struct GameEngine {
+    campaign: Campaign,
+    content: ContentRegistry,
+    seed: u64,
+    rng: StdRng,
+    hooks: Hooks,
+    /// Commands applied, oldest first
+    commands: Vec<Command>,
+    /// State before each of the latest commands, up to `UNDO_LIMIT`
+    checkpoints: VecDeque<Checkpoint>,
+}
+
+/// Where the table stood before a command
+#[derive(Debug, Clone)]
+struct Checkpoint {
+    campaign: Campaign,
+    rng: StdRng,
+}
+
+impl GameEngine {
+    /// Run a campaign with no content, rolling from a random seed
+    pub fn new(campaign: Campaign) -> Self {
+        let seed = rand::random();
+        Self {
+            campaign,
+            content: ContentRegistry::new(),
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+            hooks: Hooks::default(),
+            commands: Vec::new(),
+            checkpoints: VecDeque::new(),
+        }
+    }
+
+    /// Look adversaries and cards up in this registry
+    pub fn with_content(mut self, content: ContentRegistry) -> Self {
+        self.content = content;
+        self
+    }
+
+    /// Roll from a seed, so the same calls give the same results
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self.rng = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// The seed the engine started rolling from
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Run `commands` again from a starting campaign and seed
+    ///
+    /// Given the campaign, content and seed an engine started with and the
+    /// [`history`](GameEngine::history) it built up, this reaches the same
+    /// state, as long as every roll along the way went through a command.
+    pub fn replay(
+        campaign: Campaign,
+        content: ContentRegistry,
+        seed: u64,
+        commands: &[Command],
+    ) -> Result<Self, EngineError> {
+        let mut engine = Self::new(campaign).with_content(content).with_seed(seed);
+        for command in commands {
+            engine.apply(command.clone())?;
+        }
+        Ok(engine)
+    }
+
+    /// The campaign being played
+    pub fn campaign(&self) -> &Campaign {
+        &self.campaign
+    }
+
+    /// The campaign being played, for changes the engine doesn't cover
+    pub fn campaign_mut(&mut self) -> &mut Campaign {
+        &mut self.campaign
+    }
+
+    /// Stop running the campaign and hand it back, e.g. to save it
+    pub fn into_campaign(self) -> Campaign {
+        self.campaign
+    }
+
+    /// The adversaries, cards, weapons and armor to draw on
+    pub fn content(&self) -> &ContentRegistry {
+        &self.content
+    }
+
+    /// The content registry, to add homebrew to
+    pub fn content_mut(&mut self) -> &mut ContentRegistry {
+        &mut self.content
+    }
+
+    /// The campaign's house rules
+    pub fn rules(&self) -> &RulesConfig {
+        &self.campaign.rules
+    }
+
+    /// Change the house rules, including those of a fight in progress
+    pub fn set_rules(&mut self, rules: RulesConfig) {
+        if let Some(encounter) = self.campaign.encounter_mut() {
+            encounter.hope_mode = rules.hope_mode;
+            encounter.fear.set_maximum(rules.max_fear);
+            encounter.rules = rules.clone();
+        }
+        self.campaign.set_rules(rules);
+    }
+
+    /// The encounter in progress, if any
+    pub fn encounter(&self) -> Option<&CombatEncounter> {
+        self.campaign.encounter()
+    }
+
+    /// Watch every party member and encounter, including those added
+    /// later through the engine
+    pub fn add_hook(&mut self, hook: Arc<dyn EngineHooks>) {
+        for sheet in &mut self.campaign.party {
+            sheet.add_hook(hook.clone());
+        }
+        if let Some(encounter) = self.campaign.encounter_mut() {
+            encounter.add_hook(hook.clone());
+        }
+        self.hooks.add(hook);
+    }
+
+    /// Add a character to the party
+    pub fn add_character(&mut self, mut sheet: CharacterSheet) -> Result<(), EngineError> {
+        for hook in self.hooks.iter() {
+            sheet.add_hook(hook.clone());
+        }
+        self.campaign.add_character(sheet)
+    }
+
+    /// Start a fight against adversaries from the content registry, by
+    /// name
+    pub fn begin_encounter(
+        &mut self,
+        name: impl Into<String>,
+        adversaries: &[&str],
+    ) -> Result<&mut CombatEncounter, EngineError> {
+        let enemies = adversaries
+            .iter()
+            .map(|&name| {
+                self.content
+                    .adversary(name)
+                    .map(|adversary| adversary.to_combatant())
+                    .ok_or_else(|| EngineError::UnknownAdversary {
+                        name: name.to_string(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let hooks = self.hooks.clone();
+        let encounter = self.campaign.begin_encounter(name, enemies)?;
+        for hook in hooks.iter() {
+            encounter.add_hook(hook.clone());
+        }
+        Ok(encounter)
+    }
+
+    /// End the fight in progress, carrying its results into the campaign
+    pub fn finish_encounter(&mut self) -> Result<(), EngineError> {
+        self.campaign.finish_encounter().map(|_| ())
+    }
+
+    /// Roll one of a character's traits against a Difficulty
+    ///
+    /// With Hope the character gains a Hope, with Fear the GM gains a
+    /// Fear, and a critical also clears a Stress. During a fight the
+    /// character's encounter state changes; otherwise their sheet does.
+    /// The roll is added to the campaign log.
+    pub fn roll_action(
+        &mut self,
+        character: &str,
+        attribute: AttributeType,
+        difficulty: u16,
+    ) -> Result<ActionRoll, EngineError> {
+        let fighter = self.party_id(character)?;
+        let combatant = match (fighter, self.campaign.encounter()) {
+            (Some(id), Some(encounter)) => encounter.get(id),
+            _ => self.campaign.character(character).map(|s| &s.combatant),
+        }
+        .ok_or_else(|| unknown_character(character))?;
+        let modifier = downtime::trait_modifier(combatant, attribute)?;
+
+        let result = DualityRoll::roll_with_rng(&mut self.rng).with_modifier(modifier);
+        let with_hope = result.is_critical || result.controlling == ControllingDie::Hope;
+        let stress_cleared = u8::from(result.is_critical);
+        match (fighter, self.campaign.encounter_mut()) {
+            (Some(id), Some(encounter)) => {
+                if with_hope {
+                    encounter.gain_hope(id, 1)?;
+                } else {
+                    encounter.gain_fear(1);
+                }
+                if let Some(combatant) = encounter.get_mut(id) {
+                    combatant.stress.current =
+                        combatant.stress.current.saturating_sub(stress_cleared);
+                }
+            }
+            _ => {
+                let shared = self.campaign.rules.hope_mode == HopeMode::Shared;
+                if !with_hope {
+                    self.campaign.fear.gain(1);
+                } else if shared {
+                    self.campaign.hope.gain(1);
+                }
+                let sheet = self
+                    .campaign
+                    .character_mut(character)
+                    .ok_or_else(|| unknown_character(character))?;
+                if with_hope && !shared {
+                    sheet.combatant.hope_mut()?.gain(1);
+                }
+                let stress = &mut sheet.combatant.stress.current;
+                *stress = stress.saturating_sub(stress_cleared);
+            }
+        }
+
+        let description = format!("{} roll", attribute);
+        self.campaign
+            .log_roll(character, description, result.clone());
+        Ok(ActionRoll {
+            outcome: result.success_type(difficulty),
+            result,
+            difficulty,
+        })
+    }
+
+    /// Play one of a character's domain cards, by content id
+    ///
+    /// The character must have the card. During a fight it's played in the
+    /// encounter, so it's logged there.
+    pub fn play_card(&mut self, character: &str, card_id: &str) -> Result<(), EngineError> {
+        let id = self
+            .content
+            .content_id(card_id)
+            .filter(|&id| self.content.card_by_id(id).is_some())
+            .ok_or_else(|| EngineError::UnknownCard {
+                id: card_id.to_string(),
+            })?;
+        self.play_card_by_id(character, id)
+    }
+
+    /// Play one of a character's domain cards, by an id interned in the
+    /// engine's content
+    pub fn play_card_by_id(&mut self, character: &str, card: ContentId) -> Result<(), EngineError> {
+        let card =
+            self.content
+                .card_by_id(card)
+                .cloned()
+                .ok_or_else(|| EngineError::UnknownCard {
+                    id: self.content.key_of(card).unwrap_or_default().to_string(),
+                })?;
+        let fighter = self.party_id(character)?;
+        let sheet = self
+            .campaign
+            .character(character)
+            .ok_or_else(|| unknown_character(character))?;
+        match (fighter, self.campaign.active.is_some()) {
+            (Some(id), true) => {
+                if !sheet.progress.has_card(&card.id) {
+                    return Err(EngineError::CardNotOwned {
+                        character: character.to_string(),
+                        card: card.name,
+                    });
+                }
+                self.encounter_mut()?.play_card(id, &card)
+            }
+            _ => sheet.play_card(&card),
+        }
+    }
+
+    /// Attack with one combatant in the encounter in progress against
+    /// another
+    ///
+    /// Characters attack with the first weapon they carry that the
+    /// registry or the built-in list knows, adversaries with their level.
+    pub fn resolve_attack(
+        &mut self,
+        attacker: CombatantId,
+        target: CombatantId,
+    ) -> Result<AttackResult, EngineError> {
+        let encounter = self
+            .campaign
+            .encounter()
+            .ok_or(EngineError::NoActiveEncounter)?;
+        let combatant = encounter
+            .get(attacker)
+            .ok_or(EngineError::UnknownCombatant { id: attacker.0 })?;
+        let registered = combatant
+            .equipment
+            .iter()
+            .filter_map(|item| self.content.content_id(item))
+            .find_map(|id| self.content.weapon_by_id(id))
+            .and_then(|weapon| {
+                Attack::from_character(combatant, weapon)
+                    .ok()
+                    .map(|attack| (attack, weapon.damage(combatant.proficiency())))
+            });
+        let (attack, damage) = registered.unwrap_or_else(|| {
+            let profile = AttackProfile::for_combatant(combatant);
+            (Attack::new(profile.modifier), profile.damage)
+        });
+
+        let rng = &mut self.rng;
+        let encounter = self
+            .campaign
+            .encounter_mut()
+            .ok_or(EngineError::NoActiveEncounter)?;
+        encounter.resolve_attack_with_rng(attacker, target, &attack, &damage, rng)
+    }
+
+    /// Take a rest: a character's downtime moves, then the GM's Fear
+    ///
+    /// Fails during a fight.
+    pub fn rest(
+        &mut self,
+        character: &str,
+        rest: RestKind,
+        activities: &[RestActivity],
+    ) -> Result<Vec<ActivityRecord>, EngineError> {
+        if let Some(active) = &self.campaign.active {
+            return Err(EngineError::EncounterInProgress {
+                name: active.name.clone(),
+            });
+        }
+        let records = self
+            .campaign
+            .rest_with_rng(character, rest, activities, &mut self.rng)?;
+        self.campaign.gain_rest_fear_with_rng(rest, &mut self.rng);
+        Ok(records)
+    }
+
+    /// Level a character up, returning their new level
+    pub fn level_up(&mut self, character: &str) -> Result<u8, EngineError> {
+        let sheet = self
+            .campaign
+            .character_mut(character)
+            .ok_or_else(|| unknown_character(character))?;
+        sheet.level_up()?;
+        Ok(sheet.progress.level)
+    }
+
+    /// Apply a command, returning the combat events it logged
+    ///
+    /// A command that fails changes nothing and isn't recorded. One that
+    /// succeeds joins the [`history`](GameEngine::history) and can be
+    /// [undone](GameEngine::undo).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::Campaign;
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::{CombatantBuilder, EncounterCommand};
+    /// use daggerheart_engine::content::ContentRegistry;
+    /// use daggerheart_engine::engine::{Command, GameEngine};
+    ///
+    /// let mut engine = GameEngine::new(Campaign::new("The Witherwild", 5))
+    ///     .with_content(ContentRegistry::builtin());
+    /// let sheet = CharacterSheet::new(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    /// engine
+    ///     .apply(Command::AddCharacter { sheet: Box::new(sheet) })
+    ///     .unwrap();
+    /// engine
+    ///     .apply(Command::BeginEncounter {
+    ///         name: "Ambush".to_string(),
+    ///         adversaries: vec!["Bear".to_string()],
+    ///     })
+    ///     .unwrap();
+    /// let events = engine
+    ///     .apply(Command::Encounter(Box::new(EncounterCommand::Start)))
+    ///     .unwrap();
+    /// assert!(!events.is_empty());
+    ///
+    /// assert_eq!(engine.undo(), Some(Command::Encounter(Box::new(EncounterCommand::Start))));
+    /// assert_eq!(engine.encounter().unwrap().round, 0);
+    /// assert_eq!(engine.history().len(), 2);
+    /// ```
+    pub fn apply(&mut self, command: Command) -> Result<Vec<CombatEvent>, EngineError> {
+        let checkpoint = Checkpoint {
+            campaign: self.campaign.clone(),
+            rng: self.rng.clone(),
+        };
+        let logged = self.campaign.encounter().map(|e| e.events.len());
+        if let Err(err) = self.dispatch(&command) {
+            self.campaign = checkpoint.campaign;
+            self.rng = checkpoint.rng;
+            return Err(err);
+        }
+
+        let events = match (logged, self.campaign.encounter()) {
+            (Some(logged), Some(encounter)) => encounter.events[logged..].to_vec(),
+            (None, Some(encounter)) => encounter.events.clone(),
+            (Some(logged), None) => self
+                .campaign
+                .completed
+                .last()
+                .map(|record| record.encounter.events[logged..].to_vec())
+                .unwrap_or_default(),
+            (None, None) => Vec::new(),
+        };
+        if self.checkpoints.len() == UNDO_LIMIT {
+            self.checkpoints.pop_front();
+        }
+        self.checkpoints.push_back(checkpoint);
+        self.commands.push(command);
+        Ok(events)
+    }
+
+    /// Take back the last command applied, returning it
+    ///
+    /// Only the last `UNDO_LIMIT` commands can be undone.
+    pub fn undo(&mut self) -> Option<Command> {
+        let checkpoint = self.checkpoints.pop_back()?;
+        self.campaign = checkpoint.campaign;
+        self.rng = checkpoint.rng;
+        self.commands.pop()
+    }
+
+    /// Every command applied and not undone, oldest first
+    pub fn history(&self) -> &[Command] {
+        &self.commands
+    }
+
+    fn dispatch(&mut self, command: &Command) -> Result<(), EngineError> {
+        match command {
+            Command::AddCharacter { sheet } => self.add_character(sheet.as_ref().clone()),
+            Command::SetRules { rules } => {
+                self.set_rules(rules.clone());
+                Ok(())
+            }
+            Command::BeginEncounter { name, adversaries } => {
+                let adversaries: Vec<&str> = adversaries.iter().map(String::as_str).collect();
+                self.begin_encounter(name.clone(), &adversaries).map(|_| ())
+            }
+            Command::FinishEncounter => self.finish_encounter(),
+            Command::Encounter(command) => {
+                let encounter = self
+                    .campaign
+                    .encounter_mut()
+                    .ok_or(EngineError::NoActiveEncounter)?;
+                encounter.apply_command_with_rng(command, &mut self.rng)
+            }
+            Command::RollAction {
+                character,
+                attribute,
+                difficulty,
+            } => self
+                .roll_action(character, *attribute, *difficulty)
+                .map(|_| ()),
+            Command::PlayCard { character, card } => self.play_card(character, card),
+            Command::ResolveAttack { attacker, target } => {
+                self.resolve_attack(*attacker, *target).map(|_| ())
+            }
+            Command::Rest {
+                character,
+                rest,
+                activities,
+            } => self.rest(character, *rest, activities).map(|_| ()),
+            Command::LevelUp { character } => self.level_up(character).map(|_| ()),
+        }
+    }
+
+    /// A party member's id in the encounter in progress, checking they're
+    /// in the party
+    fn party_id(&self, character: &str) -> Result<Option<CombatantId>, EngineError> {
+        let index = self
+            .campaign
+            .party
+            .iter()
+            .position(|sheet| sheet.name() == character)
+            .ok_or_else(|| unknown_character(character))?;
+        Ok(self
+            .campaign
+            .active
+            .as_ref()
+            .and_then(|active| active.party_ids.get(index).copied()))
+    }
+
+    fn encounter_mut(&mut self) -> Result<&mut CombatEncounter, EngineError> {
+        self.campaign
+            .encounter_mut()
+            .ok_or(EngineError::NoActiveEncounter)
+    }
+}
+
+fn unknown_character(name: &str) -> EngineError {
+    EngineError::UnknownCharacter {
+        name: name.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Class};
+    use crate::combat::CombatEvent;
+    use crate::test_support::player;
+
+    fn create_test_engine() -> GameEngine {
+        let mut engine = GameEngine::new(Campaign::new("Test", 5))
+            .with_content(ContentRegistry::builtin())
+            .with_seed(3);
+        let mut sheet = CharacterSheet::new(
+            player("Grom", Class::Warrior, Ancestry::Orc)
+                .with_equipment("Longsword")
+                .build()
+                .unwrap(),
+        );
+        sheet.progress.add_card("whirlwind");
+        engine.add_character(sheet).unwrap();
+        engine
+    }
+
+    #[test]
+    fn test_action_rolls_feed_the_encounter_during_a_fight() {
+        let mut engine = create_test_engine();
+        engine.begin_encounter("Ambush", &["Bear"]).unwrap();
+        let roll = engine
+            .roll_action("Grom", AttributeType::Agility, 10)
+            .unwrap();
+
+        let encounter = engine.encounter().unwrap();
+        let hope = encounter.get(CombatantId(0)).unwrap().hope().copied();
+        if roll.result.controlling == ControllingDie::Hope || roll.result.is_critical {
+            assert_eq!(hope.unwrap().current, 3);
+        } else {
+            assert_eq!(encounter.fear.current, 1);
+        }
+        // The sheet catches up when the fight ends
+        engine.finish_encounter().unwrap();
+        let sheet = engine.campaign().character("Grom").unwrap();
+        assert_eq!(sheet.combatant.hope().copied(), hope);
+    }
+
+    #[test]
+    fn test_cards_and_attacks_route_to_the_encounter() {
+        let mut engine = create_test_engine();
+        assert!(engine.play_card("Grom", "whirlwind").is_ok());
+        assert!(engine
+            .resolve_attack(CombatantId(0), CombatantId(1))
+            .is_err());
+
+        engine.begin_encounter("Ambush", &["Bear"]).unwrap();
+        engine.play_card("Grom", "whirlwind").unwrap();
+        let whirlwind = engine.content().content_id("whirlwind").unwrap();
+        engine.play_card_by_id("Grom", whirlwind).unwrap();
+        assert!(matches!(
+            engine.play_card("Grom", "bear"),
+            Err(EngineError::UnknownCard { .. })
+        ));
+        let result = engine
+            .resolve_attack(CombatantId(0), CombatantId(1))
+            .unwrap();
+        // Longsword, Agility +2
+        assert_eq!(result.modifier, 2);
+
+        let events = &engine.encounter().unwrap().events;
+        assert!(events
+            .iter()
+            .any(|e| matches!(e, CombatEvent::CardPlayed { .. })));
+        assert!(engine.rest("Grom", RestKind::Short, &[]).is_err());
+        assert!(matches!(
+            engine.begin_encounter("Second", &["Nobody"]),
+            Err(EngineError::UnknownAdversary { .. })
+        ));
+    }
+
+    #[test]
+    fn test_failed_commands_change_nothing() {
+        let mut engine = create_test_engine();
+        let events = engine
+            .apply(Command::BeginEncounter {
+                name: "Ambush".to_string(),
+                adversaries: vec!["Bear".to_string(), "Nobody".to_string()],
+            })
+            .unwrap_err();
+        assert!(matches!(events, EngineError::UnknownAdversary { .. }));
+        assert!(engine.encounter().is_none());
+        assert!(engine.history().is_empty());
+        assert_eq!(engine.undo(), None);
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_commands_undo_and_replay() {
+        use crate::combat::replay::EncounterCommand;
+
+        let mut engine = create_test_engine();
+        let start = engine.campaign().clone();
+        let commands = [
+            Command::BeginEncounter {
+                name: "Ambush".to_string(),
+                adversaries: vec!["Bear".to_string()],
+            },
+            Command::Encounter(Box::new(EncounterCommand::Start)),
+            Command::ResolveAttack {
+                attacker: CombatantId(0),
+                target: CombatantId(1),
+            },
+            Command::FinishEncounter,
+            Command::RollAction {
+                character: "Grom".to_string(),
+                attribute: AttributeType::Strength,
+                difficulty: 12,
+            },
+        ];
+        let mut attacked = Vec::new();
+        for command in &commands {
+            let events = engine.apply(command.clone()).unwrap();
+            if matches!(command, Command::ResolveAttack { .. }) {
+                attacked = events;
+            }
+        }
+        assert!(attacked
+            .iter()
+            .any(|e| matches!(e, CombatEvent::AttackResolved { .. })));
+        assert_eq!(engine.history(), &commands);
+
+        let replayed =
+            GameEngine::replay(start, ContentRegistry::builtin(), 3, engine.history()).unwrap();
+        let json = |engine: &GameEngine| serde_json::to_value(engine.campaign()).unwrap();
+        assert_eq!(json(&replayed), json(&engine));
+
+        // Undoing the roll and rolling again gives the same result
+        let log = engine.campaign().roll_log.clone();
+        assert_eq!(engine.undo(), commands.last().cloned());
+        assert_eq!(engine.campaign().roll_log.len(), log.len() - 1);
+        engine.apply(commands[4].clone()).unwrap();
+        assert_eq!(engine.campaign().roll_log, log);
+    }
+
+    #[test]
+    fn test_seeded_engines_agree() {
+        let rolls = |seed| {
+            let mut engine = create_test_engine().with_seed(seed);
+            (0..5)
+                .map(|_| {
+                    engine
+                        .roll_action("Grom", AttributeType::Strength, 12)
+                        .unwrap()
+                        .result
+                })
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(rolls(9), rolls(9));
+    }
+}
@@ -0,0 +1,60 @@
+//! Commands - every change to a table as data
+//!
+//! A `Command` names one operation on a [`GameEngine`](super::GameEngine)
+//! and its arguments. Sending commands instead of calling methods lets a
+//! client play over the network, a bot drive the table from one match,
+//! and the engine keep a history it can undo and replay.
+//!
+//! Changes to the fight in progress go through [`Command::Encounter`],
+//! whose [`EncounterCommand`] has a variant for every public method that
+//! changes an encounter.
+
+use crate::character::{AttributeType, CharacterSheet};
+use crate::combat::replay::EncounterCommand;
+use crate::combat::simulation::CombatantId;
+use crate::downtime::{RestActivity, RestKind};
+use crate::rules::RulesConfig;
+use serde::{Deserialize, Serialize};
+
+/// An operation on a game engine
+///
+/// Applied with [`GameEngine::apply`](super::GameEngine::apply).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Command {
+    /// Add a character to the party
+    AddCharacter { sheet: Box<CharacterSheet> },
+    /// Switch to a different set of house rules
+    SetRules { rules: RulesConfig },
+    /// Start a fight against adversaries from the content registry
+    BeginEncounter {
+        name: String,
+        adversaries: Vec<String>,
+    },
+    /// End the fight in progress
+    FinishEncounter,
+    /// Change the fight in progress directly
+    Encounter(Box<EncounterCommand>),
+    /// Roll one of a character's traits against a Difficulty
+    RollAction {
+        character: String,
+        attribute: AttributeType,
+        difficulty: u16,
+    },
+    /// Play one of a character's domain cards
+    PlayCard { character: String, card: String },
+    /// Attack with a combatant's own weapon or stat block
+    ResolveAttack {
+        attacker: CombatantId,
+        target: CombatantId,
+    },
+    /// Take a rest, making downtime moves
+    Rest {
+        character: String,
+        rest: RestKind,
+        #[serde(default)]
+        activities: Vec<RestActivity>,
+    },
+    /// Level a character up
+    LevelUp { character: String },
+}
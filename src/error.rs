@@ -1,7 +1,44 @@
 //! Error types for the Daggerheart engine
+//!
+//! Rule violations are reported with structured variants, so callers can
+//! match on "not enough Hope" or "card from the wrong domain" and read the
+//! numbers involved instead of parsing messages. The string variants
+//! remain for free-form failures such as malformed imports.
+//!
+//! Saving and loading files reports `Io` and `Serialization` errors
+//! wrapped in `File`, which names the file involved.
 
+use crate::character::{Class, Domain, Subclass};
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use std::path::{Path, PathBuf};
+use strum_macros::Display;
 use thiserror::Error;
 
+/// A spendable or limited resource
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Resource {
+    Hope,
+    Fear,
+    Gold,
+    #[strum(serialize = "XP")]
+    Experience,
+    #[strum(serialize = "battle points")]
+    BattlePoints,
+    /// A magic item's charges
+    #[strum(serialize = "charges")]
+    Charges,
+    #[strum(serialize = "action tokens")]
+    ActionTokens,
+    #[strum(serialize = "Hit Points")]
+    HitPoints,
+}
+
 /// Main error type for the Daggerheart engine
 #[derive(Error, Debug)]
 pub enum EngineError {
@@ -21,6 +58,10 @@ pub enum EngineError {
     #[error("Resource limit exceeded: {0}")]
     ResourceExceeded(String),
 
+    /// Invalid item or item not found
+    #[error("Invalid item: {0}")]
+    InvalidItem(String),
+
     /// Serialization error
     #[error("Serialization error: {0}")]
     SerializationError(String),
@@ -28,7 +69,306 @@ pub enum EngineError {
     /// Generic error
     #[error("{0}")]
     Other(String),
+
+    /// Reading or writing failed
+    #[cfg(feature = "std")]
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+
+    /// A save couldn't be encoded, or a file isn't valid save data
+    #[cfg(feature = "persistence")]
+    #[error("Serialization error: {0}")]
+    Serialization(#[from] serde_json::Error),
+
+    /// An error while saving or loading a file
+    #[cfg(feature = "std")]
+    #[error("{}: {source}", path.display())]
+    File {
+        path: PathBuf,
+        #[source]
+        source: Box<EngineError>,
+    },
+
+    /// Not enough of a resource to pay a cost
+    #[error("Not enough {resource}: have {have}, need {need}")]
+    InsufficientResource {
+        resource: Resource,
+        have: u32,
+        need: u32,
+    },
+
+    /// No combatant with this id in the encounter
+    #[error("No combatant with id #{id}")]
+    UnknownCombatant { id: u32 },
+
+    /// A defeated combatant tried to act
+    #[error("{name} is defeated")]
+    CombatantDefeated { name: String },
+
+    /// Attribute modifiers aren't the standard starting distribution
+    #[error("Attributes must use the standard modifiers +2, +1, +1, 0, 0, -1, got {modifiers:?}")]
+    InvalidAttributes { modifiers: Vec<i8> },
+
+    /// A level outside 1-10
+    #[error("Level must be between 1 and 10, got {level}")]
+    InvalidLevel { level: u8 },
+
+    /// A combatant was built without a stat it needs
+    #[error("{combatant} has no {stat}")]
+    MissingStat {
+        combatant: String,
+        stat: &'static str,
+    },
+
+    /// A player character was used where only an adversary can be
+    #[error("{name} is a player character, not an adversary")]
+    NotAnAdversary { name: String },
+
+    /// The character can't level up any further
+    #[error("Already at the maximum level ({level})")]
+    MaxLevel { level: u8 },
+
+    /// The character doesn't have this card
+    #[error("{character} doesn't have {card}")]
+    CardNotOwned { character: String, card: String },
+
+    /// The card's domain isn't one of the character's class domains
+    #[error("{card} is a {domain} card, outside this character's domains")]
+    CardNotAllowed { card: String, domain: Domain },
+
+    /// The character's level is below the card's requirement
+    #[error("{card} requires level {required}, character is level {level}")]
+    CardLevelTooLow {
+        card: String,
+        required: u8,
+        level: u8,
+    },
+
+    /// A subclass was chosen for a class it doesn't belong to
+    #[error("{subclass} is not a {class} subclass")]
+    WrongSubclass { subclass: Subclass, class: Class },
+
+    /// A character with this name is already in the party
+    #[error("{name} is already in the party")]
+    DuplicateCharacter { name: String },
+
+    /// No party member has this name
+    #[error("No character named {name} in the party")]
+    UnknownCharacter { name: String },
+
+    /// A character has no downtime project with this name
+    #[error("{character} has no project named '{project}'")]
+    UnknownProject { character: String, project: String },
+
+    /// A character already has a downtime project with this name
+    #[error("{character} already has a project named '{project}'")]
+    DuplicateProject { character: String, project: String },
+
+    /// A Hidden combatant was attacked before anyone found them
+    #[error("{name} is Hidden and must be found first")]
+    TargetHidden { name: String },
+
+    /// A combatant can't be targeted, moved or changed the way asked
+    #[error("Invalid target: {reason}")]
+    InvalidTarget { reason: String },
+
+    /// A combatant has no innate ability with this name
+    #[error("{combatant} has no ability named {ability}")]
+    UnknownAbility { combatant: String, ability: String },
+
+    /// A combatant has used up an ability until their next rest
+    #[error("{combatant} has no uses of {ability} left before a rest")]
+    AbilityExhausted { combatant: String, ability: String },
+
+    /// A reaction cancelled an attack before it was rolled
+    #[error("{attacker}'s attack on {target} was cancelled")]
+    AttackCancelled { attacker: String, target: String },
+
+    /// A reaction isn't on offer at this point in the attack
+    #[error("{reaction} can't be taken at {window}")]
+    ReactionUnavailable { reaction: String, window: String },
+
+    /// The current scene has no NPC with this name
+    #[error("No NPC named {name} in the current scene")]
+    UnknownNpc { name: String },
+
+    /// The content registry has no adversary with this name
+    #[error("No adversary named {name}")]
+    UnknownAdversary { name: String },
+
+    /// The content registry has no card with this id
+    #[error("No card with id '{id}'")]
+    UnknownCard { id: String },
+
+    /// An encounter reached a state the rules can't produce
+    #[error("Encounter invariant broken: {invariant}")]
+    InvariantViolated { invariant: String },
+
+    /// An encounter is already being played
+    #[error("Encounter '{name}' is still in progress")]
+    EncounterInProgress { name: String },
+
+    /// There is no encounter being played
+    #[error("No encounter in progress")]
+    NoActiveEncounter,
+
+    /// An adversary is above the tier the party can face
+    #[error("{adversary} is tier {tier}, above the party's tier {max_tier}")]
+    TierTooHigh {
+        adversary: String,
+        tier: u8,
+        max_tier: u8,
+    },
+
+    /// No adversary in a library fits the party's tier
+    #[error("No adversaries of tier {max_tier} or lower in library")]
+    NoEligibleAdversaries { max_tier: u8 },
+
+    /// A shop doesn't sell this item
+    #[error("{item} is not sold here")]
+    UnknownItem { item: String },
+
+    /// A shop has none of this item left
+    #[error("{item} is out of stock")]
+    OutOfStock { item: String },
+
+    /// A character doesn't carry this item
+    #[error("{character} isn't carrying {item}")]
+    ItemNotCarried { character: String, item: String },
+
+    /// Fewer of an item are carried than were asked to be dropped
+    #[error("Only {carried} {item} carried, can't drop {quantity}")]
+    TooFewCarried {
+        item: String,
+        carried: u16,
+        quantity: u16,
+    },
+
+    /// Attuning another item would go over the character's limit
+    #[error("{character} is already attuned to {limit} items")]
+    AttunementLimit { character: String, limit: u8 },
+
+    /// A weapon needs a hand another wielded weapon is using
+    #[error("{weapon} can't be wielded alongside {wielding}")]
+    HandsFull { weapon: String, wielding: String },
+
+    /// Replaying recorded commands produced a different event log
+    #[error("Replay diverged at event {index}: expected {expected}, got {actual}")]
+    ReplayDiverged {
+        index: usize,
+        expected: String,
+        actual: String,
+    },
+
+    /// Replaying recorded commands produced a different number of events
+    #[error("Replay produced {actual} events, expected {expected}")]
+    ReplayLength { expected: usize, actual: usize },
+}
+
+#[cfg(feature = "std")]
+impl EngineError {
+    /// Attach the file this error happened in
+    pub fn in_file(self, path: impl AsRef<Path>) -> Self {
+        Self::File {
+            path: path.as_ref().to_path_buf(),
+            source: Box::new(self),
+        }
+    }
+
+    /// The file involved, if this error came from saving or loading one
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::File { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
+    /// The underlying I/O error, looking through file context
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::error::EngineError;
+    /// use std::io::{Error, ErrorKind};
+    ///
+    /// let error = EngineError::from(Error::from(ErrorKind::NotFound)).in_file("no_such_save.json");
+    /// assert_eq!(error.io_kind(), Some(ErrorKind::NotFound));
+    /// assert!(error.to_string().starts_with("no_such_save.json: "));
+    /// ```
+    pub fn io_kind(&self) -> Option<std::io::ErrorKind> {
+        match self {
+            Self::Io(error) => Some(error.kind()),
+            Self::File { source, .. } => source.io_kind(),
+            _ => None,
+        }
+    }
 }
 
 /// Result type alias for convenience
-pub type Result<T> = std::result::Result<T, EngineError>;
+pub type Result<T> = core::result::Result<T, EngineError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_structured_messages() {
+        let error = EngineError::InsufficientResource {
+            resource: Resource::Hope,
+            have: 1,
+            need: 3,
+        };
+        assert_eq!(error.to_string(), "Not enough Hope: have 1, need 3");
+
+        let error = EngineError::InsufficientResource {
+            resource: Resource::Experience,
+            have: 40,
+            need: 100,
+        };
+        assert_eq!(error.to_string(), "Not enough XP: have 40, need 100");
+
+        let error = EngineError::UnknownCombatant { id: 4 };
+        assert_eq!(error.to_string(), "No combatant with id #4");
+
+        let error = EngineError::InvalidTarget {
+            reason: "Grom can't fly".to_string(),
+        };
+        assert_eq!(error.to_string(), "Invalid target: Grom can't fly");
+
+        let error = EngineError::AbilityExhausted {
+            combatant: "Vyr".to_string(),
+            ability: "Breath Weapon".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Vyr has no uses of Breath Weapon left before a rest"
+        );
+
+        let error = EngineError::ReactionUnavailable {
+            reaction: "Deflect".to_string(),
+            window: "attack declared".to_string(),
+        };
+        assert_eq!(
+            error.to_string(),
+            "Deflect can't be taken at attack declared"
+        );
+    }
+
+    #[test]
+    fn test_match_on_resource() {
+        let error = EngineError::InsufficientResource {
+            resource: Resource::Fear,
+            have: 0,
+            need: 2,
+        };
+        assert!(matches!(
+            error,
+            EngineError::InsufficientResource {
+                resource: Resource::Fear,
+                need: 2,
+                ..
+            }
+        ));
+    }
+}
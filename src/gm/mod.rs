@@ -0,0 +1,14 @@
+//! GM tools - rulings and prompts for running the table
+//!
+//! Utilities for the GM (or a solo player) that sit outside the rules
+//! proper:
+//! - An oracle for yes/no questions and complications
+//! - Solo play, with adversaries acting on their own (`solo` feature)
+
+pub mod oracle;
+#[cfg(feature = "solo")]
+pub mod solo;
+
+pub use oracle::{Answer, Complication, Likelihood, Oracle, OracleAnswer, Severity};
+#[cfg(feature = "solo")]
+pub use solo::{Activation, SoloGm};
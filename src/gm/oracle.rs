@@ -0,0 +1,343 @@
+//! Oracle - quick answers when no one at the table knows
+//!
+//! When play asks a question the GM hasn't prepared for (is the door
+//! locked, does the guard recognize them) or a solo player needs a
+//! ruling, the oracle answers. A yes/no question is weighted by how
+//! likely the answer is and rolled on a d100; rolls far from the line add
+//! an "and", rolls close to it a "but". When things go wrong, the
+//! complication table rolls a d12 for how badly.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use strum_macros::{Display, EnumIter};
+
+/// How likely a yes is, before rolling
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, EnumIter, Display, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Likelihood {
+    #[strum(serialize = "Nearly impossible")]
+    NearlyImpossible,
+    #[strum(serialize = "Very unlikely")]
+    VeryUnlikely,
+    Unlikely,
+    #[default]
+    #[strum(serialize = "Even odds")]
+    EvenOdds,
+    Likely,
+    #[strum(serialize = "Very likely")]
+    VeryLikely,
+    #[strum(serialize = "Nearly certain")]
+    NearlyCertain,
+}
+
+impl Likelihood {
+    /// Percent chance of a yes
+    pub fn chance(&self) -> u8 {
+        match self {
+            Likelihood::NearlyImpossible => 10,
+            Likelihood::VeryUnlikely => 25,
+            Likelihood::Unlikely => 35,
+            Likelihood::EvenOdds => 50,
+            Likelihood::Likely => 65,
+            Likelihood::VeryLikely => 75,
+            Likelihood::NearlyCertain => 90,
+        }
+    }
+}
+
+impl FromStr for Likelihood {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().replace(['-', '_', ' '], "").as_str() {
+            "nearlyimpossible" | "impossible" => Ok(Likelihood::NearlyImpossible),
+            "veryunlikely" => Ok(Likelihood::VeryUnlikely),
+            "unlikely" => Ok(Likelihood::Unlikely),
+            "evenodds" | "even" => Ok(Likelihood::EvenOdds),
+            "likely" => Ok(Likelihood::Likely),
+            "verylikely" => Ok(Likelihood::VeryLikely),
+            "nearlycertain" | "certain" => Ok(Likelihood::NearlyCertain),
+            _ => Err(format!(
+                "Unknown likelihood: {}. Use impossible, very-unlikely, unlikely, even, \
+                 likely, very-likely, or certain",
+                s
+            )),
+        }
+    }
+}
+
+/// The oracle's answer to a yes/no question
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, EnumIter, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Answer {
+    /// Yes, and something more in the asker's favor
+    YesAnd,
+    Yes,
+    /// Yes, but with a catch
+    YesBut,
+    /// No, but with a silver lining
+    NoBut,
+    No,
+    /// No, and things get worse
+    NoAnd,
+}
+
+impl Answer {
+    /// Whether the answer is some kind of yes
+    pub fn is_yes(&self) -> bool {
+        matches!(self, Answer::YesAnd | Answer::Yes | Answer::YesBut)
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Answer::YesAnd => "Yes, and…",
+            Answer::Yes => "Yes",
+            Answer::YesBut => "Yes, but…",
+            Answer::NoBut => "No, but…",
+            Answer::No => "No",
+            Answer::NoAnd => "No, and…",
+        })
+    }
+}
+
+/// A rolled answer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct OracleAnswer {
+    pub likelihood: Likelihood,
+    /// The d100 roll; at or under the chance is a yes
+    pub roll: u8,
+    pub answer: Answer,
+}
+
+impl OracleAnswer {
+    /// Read a d100 roll against a likelihood
+    ///
+    /// The fifth of each side furthest from the line adds an "and"; a
+    /// roll within 5 of the line adds a "but".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::gm::oracle::{Answer, Likelihood, OracleAnswer};
+    ///
+    /// let likely = Likelihood::Likely; // 65%
+    /// assert_eq!(OracleAnswer::read(likely, 5).answer, Answer::YesAnd);
+    /// assert_eq!(OracleAnswer::read(likely, 40).answer, Answer::Yes);
+    /// assert_eq!(OracleAnswer::read(likely, 63).answer, Answer::YesBut);
+    /// assert_eq!(OracleAnswer::read(likely, 68).answer, Answer::NoBut);
+    /// assert_eq!(OracleAnswer::read(likely, 80).answer, Answer::No);
+    /// assert_eq!(OracleAnswer::read(likely, 97).answer, Answer::NoAnd);
+    /// ```
+    pub fn read(likelihood: Likelihood, roll: u8) -> Self {
+        let chance = likelihood.chance();
+        let answer = if roll <= chance {
+            if roll <= chance / 5 {
+                Answer::YesAnd
+            } else if roll > chance.saturating_sub(5) {
+                Answer::YesBut
+            } else {
+                Answer::Yes
+            }
+        } else if roll > 100 - (100 - chance) / 5 {
+            Answer::NoAnd
+        } else if roll <= chance + 5 {
+            Answer::NoBut
+        } else {
+            Answer::No
+        };
+        Self {
+            likelihood,
+            roll,
+            answer,
+        }
+    }
+}
+
+/// How badly a complication hurts
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    EnumIter,
+    Display,
+    Serialize,
+    Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Severity {
+    Minor,
+    Moderate,
+    Serious,
+    Dire,
+}
+
+impl Severity {
+    /// What the complication might cost, as a suggestion for the GM
+    pub fn suggested_cost(&self) -> &'static str {
+        match self {
+            Severity::Minor => "a moment's delay or a point of Stress",
+            Severity::Moderate => "a Stress, a spent resource or a lost advantage",
+            Severity::Serious => "damage, a Fear for the GM or a new threat",
+            Severity::Dire => "a countdown starting or a threat striking now",
+        }
+    }
+}
+
+/// The complication table, one entry per face of a d12
+const COMPLICATIONS: [(Severity, &str); 12] = [
+    (Severity::Minor, "Something small is dropped or left behind"),
+    (Severity::Minor, "An onlooker takes notice"),
+    (Severity::Minor, "It takes longer than it should"),
+    (Severity::Minor, "A scrape, a tear or a spill"),
+    (Severity::Moderate, "The easy way is blocked"),
+    (Severity::Moderate, "Supplies run low"),
+    (Severity::Moderate, "Someone's trust is shaken"),
+    (Severity::Moderate, "Noise draws unwanted attention"),
+    (Severity::Serious, "An enemy arrives or is alerted"),
+    (Severity::Serious, "Gear breaks at the worst moment"),
+    (Severity::Serious, "The party is split up"),
+    (Severity::Dire, "A looming danger strikes now"),
+];
+
+/// A rolled complication
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Complication {
+    /// The d12 roll
+    pub roll: u8,
+    pub severity: Severity,
+    /// A prompt for what goes wrong
+    pub description: String,
+}
+
+impl Complication {
+    /// The complication for a d12 roll, clamped to 1-12
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::gm::oracle::{Complication, Severity};
+    ///
+    /// assert_eq!(Complication::for_roll(1).severity, Severity::Minor);
+    /// assert_eq!(Complication::for_roll(9).severity, Severity::Serious);
+    /// assert_eq!(Complication::for_roll(12).severity, Severity::Dire);
+    /// ```
+    pub fn for_roll(roll: u8) -> Self {
+        let roll = roll.clamp(1, 12);
+        let (severity, description) = COMPLICATIONS[usize::from(roll - 1)];
+        Self {
+            roll,
+            severity,
+            description: description.to_string(),
+        }
+    }
+}
+
+/// Rolls answers and complications
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::gm::oracle::{Likelihood, Oracle};
+///
+/// let mut oracle = Oracle::seeded(7);
+/// let answer = oracle.ask(Likelihood::Likely);
+/// assert!((1..=100).contains(&answer.roll));
+///
+/// // Same seed, same rulings
+/// assert_eq!(Oracle::seeded(7).ask(Likelihood::Likely), answer);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Oracle<R = StdRng> {
+    rng: R,
+}
+
+impl Oracle {
+    /// An oracle seeded from the system's entropy
+    pub fn new() -> Self {
+        Self::from_rng(StdRng::from_entropy())
+    }
+
+    /// An oracle that always gives the same answers for a seed
+    pub fn seeded(seed: u64) -> Self {
+        Self::from_rng(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Default for Oracle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: Rng> Oracle<R> {
+    /// Roll with an existing RNG
+    pub fn from_rng(rng: R) -> Self {
+        Self { rng }
+    }
+
+    /// Ask a yes/no question
+    pub fn ask(&mut self, likelihood: Likelihood) -> OracleAnswer {
+        OracleAnswer::read(likelihood, self.rng.gen_range(1..=100))
+    }
+
+    /// Roll on the complication table
+    pub fn complication(&mut self) -> Complication {
+        Complication::for_roll(self.rng.gen_range(1..=12))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_likelihood_sets_the_odds() {
+        for likelihood in Likelihood::iter() {
+            let yeses = (1..=100)
+                .filter(|&roll| OracleAnswer::read(likelihood, roll).answer.is_yes())
+                .count();
+            assert_eq!(yeses, usize::from(likelihood.chance()));
+            assert_eq!(likelihood.to_string().parse(), Ok(likelihood));
+        }
+        assert!("maybe".parse::<Likelihood>().is_err());
+    }
+
+    #[test]
+    fn test_every_answer_can_come_up() {
+        let answers: Vec<Answer> = (1..=100)
+            .map(|roll| OracleAnswer::read(Likelihood::EvenOdds, roll).answer)
+            .collect();
+        for answer in Answer::iter() {
+            assert!(answers.contains(&answer), "{} never came up", answer);
+        }
+    }
+
+    #[test]
+    fn test_complications_cover_every_face() {
+        let mut oracle = Oracle::seeded(3);
+        for _ in 0..100 {
+            let complication = oracle.complication();
+            assert_eq!(complication, Complication::for_roll(complication.roll));
+        }
+        let severities: Vec<Severity> = (1..=12)
+            .map(|roll| Complication::for_roll(roll).severity)
+            .collect();
+        assert!(severities.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(Complication::for_roll(0).roll, 1);
+    }
+}
@@ -0,0 +1,383 @@
+//! Solo play - running an encounter without a GM
+//!
+//! [`SoloGm`] stands in for the GM at a one-player table. Adversaries act
+//! on their own when their turn comes up, following the same advice the
+//! advisor gives a human GM; scene questions go to the [`Oracle`]; and
+//! everything that happens is journaled as campaign notes, so it ends up
+//! in the session log when the session ends.
+
+use crate::campaign::Campaign;
+use crate::character::generator::NameGenerator;
+use crate::character::Ancestry;
+use crate::combat::advisor::GmMove;
+use crate::combat::events::CombatEvent;
+use crate::combat::montecarlo::{AttackProfile, TargetPolicy};
+use crate::combat::simulation::{CombatEncounter, CombatantId};
+use crate::combat::{Attack, Countdown};
+use crate::error::EngineError;
+use crate::gm::oracle::{Complication, Likelihood, Oracle, OracleAnswer};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// What an adversary did when it activated
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Activation {
+    pub adversary: CombatantId,
+    pub name: String,
+    pub action: GmMove,
+    /// Whether the attack hit or the search found its target
+    pub succeeded: bool,
+}
+
+/// Plays the GM's side for a solo player
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::campaign::Campaign;
+/// use daggerheart_engine::gm::oracle::Likelihood;
+/// use daggerheart_engine::gm::solo::SoloGm;
+///
+/// let mut campaign = Campaign::new("Alone in the Witherwild", 5);
+/// let mut gm = SoloGm::seeded(7);
+///
+/// let answer = gm.ask(&mut campaign, "Is the bridge guarded?", Likelihood::Likely);
+/// assert!((1..=100).contains(&answer.roll));
+/// assert!(campaign.notes[0].starts_with("Is the bridge guarded?"));
+/// ```
+#[derive(Debug, Clone)]
+pub struct SoloGm<R = StdRng> {
+    rng: R,
+    /// How adversaries pick who to attack
+    pub policy: TargetPolicy,
+}
+
+impl SoloGm {
+    /// A solo GM seeded from the system's entropy
+    pub fn new() -> Self {
+        Self::from_rng(StdRng::from_entropy())
+    }
+
+    /// A solo GM that always plays the same way for a seed
+    pub fn seeded(seed: u64) -> Self {
+        Self::from_rng(StdRng::seed_from_u64(seed))
+    }
+}
+
+impl Default for SoloGm {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<R: Rng> SoloGm<R> {
+    /// Play with an existing RNG
+    pub fn from_rng(rng: R) -> Self {
+        Self {
+            rng,
+            policy: TargetPolicy::default(),
+        }
+    }
+
+    /// Pick adversary targets with `policy`
+    pub fn with_policy(mut self, policy: TargetPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// Ask the oracle a scene question and journal the answer
+    pub fn ask(
+        &mut self,
+        campaign: &mut Campaign,
+        question: &str,
+        likelihood: Likelihood,
+    ) -> OracleAnswer {
+        let answer = Oracle::from_rng(&mut self.rng).ask(likelihood);
+        campaign.add_note(format!(
+            "{} ({}, rolled {}) {}",
+            question, likelihood, answer.roll, answer.answer
+        ));
+        answer
+    }
+
+    /// Roll a complication and journal it
+    pub fn complication(&mut self, campaign: &mut Campaign) -> Complication {
+        let complication = Oracle::from_rng(&mut self.rng).complication();
+        campaign.add_note(format!(
+            "Complication ({}): {}",
+            complication.severity, complication.description
+        ));
+        complication
+    }
+
+    /// Name an NPC the player just met
+    pub fn npc(&mut self, campaign: &mut Campaign) -> (Ancestry, String) {
+        let (ancestry, name) = NameGenerator::from_rng(&mut self.rng).any();
+        campaign.add_note(format!("Met {}, {}", name, ancestry));
+        (ancestry, name)
+    }
+
+    /// Start a countdown in the encounter in progress and journal it
+    pub fn start_countdown(
+        &mut self,
+        campaign: &mut Campaign,
+        countdown: Countdown,
+    ) -> Result<(), EngineError> {
+        let note = format!("Countdown: {} in {}", countdown.name, countdown.remaining);
+        campaign
+            .encounter_mut()
+            .ok_or(EngineError::NoActiveEncounter)?
+            .add_countdown(countdown);
+        campaign.add_note(note);
+        Ok(())
+    }
+
+    /// End the current turn and play adversary turns until a player is up
+    ///
+    /// An encounter that hasn't started yet rolls initiative instead of
+    /// ending a turn. Each adversary whose turn comes up attacks or
+    /// searches as the advisor suggests. Activations and expired
+    /// countdowns are journaled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::Campaign;
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    /// use daggerheart_engine::gm::solo::SoloGm;
+    ///
+    /// let mut campaign = Campaign::new("Alone in the Witherwild", 5);
+    /// campaign
+    ///     .add_character(CharacterSheet::new(
+    ///         CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///             .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///             .build()
+    ///             .unwrap(),
+    ///     ))
+    ///     .unwrap();
+    /// let ogre = CombatantBuilder::adversary("Ogre")
+    ///     .with_hp(8)
+    ///     .with_evasion(11)
+    ///     .build()
+    ///     .unwrap();
+    /// campaign.begin_encounter("Ambush", vec![ogre]).unwrap();
+    ///
+    /// let mut gm = SoloGm::seeded(3);
+    /// gm.advance(&mut campaign).unwrap();
+    /// let encounter = campaign.encounter().unwrap();
+    /// assert!(encounter.is_over() || encounter.current_combatant().unwrap().is_player());
+    /// ```
+    pub fn advance(&mut self, campaign: &mut Campaign) -> Result<Vec<Activation>, EngineError> {
+        let encounter = campaign
+            .encounter_mut()
+            .ok_or(EngineError::NoActiveEncounter)?;
+        let first_event = encounter.events.len();
+        let mut activations = Vec::new();
+
+        if encounter.turn_order.is_empty() {
+            encounter.start_with_rng(&mut self.rng);
+        } else {
+            encounter.next_turn_with_rng(&mut self.rng);
+        }
+        // A full round with nobody on the players' side able to act ends
+        // the loop even if the encounter isn't over
+        let mut turns = 0;
+        while !encounter.is_over() && turns <= encounter.turn_order.len() {
+            let Some(current) = encounter.current_combatant() else {
+                break;
+            };
+            if current.is_player() && current.is_alive() {
+                break;
+            }
+            if !current.is_player() && current.is_alive() {
+                let id = current.id;
+                activations.push(self.activate(encounter, id)?);
+            }
+            encounter.next_turn_with_rng(&mut self.rng);
+            turns += 1;
+        }
+
+        let mut notes: Vec<String> = activations
+            .iter()
+            .map(|activation| describe(encounter, activation))
+            .collect();
+        notes.extend(
+            encounter.events[first_event..]
+                .iter()
+                .filter_map(|event| match event {
+                    CombatEvent::CountdownExpired { name } => {
+                        Some(format!("Countdown expired: {}", name))
+                    }
+                    _ => None,
+                }),
+        );
+        for note in notes {
+            campaign.add_note(note);
+        }
+        Ok(activations)
+    }
+
+    /// Have one adversary act as the advisor suggests
+    pub fn activate(
+        &mut self,
+        encounter: &mut CombatEncounter,
+        adversary: CombatantId,
+    ) -> Result<Activation, EngineError> {
+        let index = encounter.require(adversary)?;
+        let profile = AttackProfile::for_combatant(&encounter.combatants[index]);
+        let option = encounter
+            .gm_advice_with_rng(self.policy, &mut self.rng)
+            .adversaries
+            .into_iter()
+            .find(|option| option.combatant == adversary)
+            .ok_or_else(|| EngineError::InvalidTarget {
+                reason: format!("{} can't act", encounter.combatants[index].name),
+            })?;
+
+        let succeeded = match option.suggestion {
+            GmMove::Attack { target, .. } => {
+                encounter
+                    .resolve_attack_with_rng(
+                        adversary,
+                        target,
+                        &Attack::new(profile.modifier),
+                        &profile.damage,
+                        &mut self.rng,
+                    )?
+                    .hit
+            }
+            GmMove::Search { target } => {
+                encounter
+                    .search_with_rng(adversary, target, &mut self.rng)?
+                    .found
+            }
+            GmMove::Hold => false,
+        };
+        Ok(Activation {
+            adversary,
+            name: option.name,
+            action: option.suggestion,
+            succeeded,
+        })
+    }
+}
+
+/// A journal line for an activation
+fn describe(encounter: &CombatEncounter, activation: &Activation) -> String {
+    let name_of = |id| {
+        encounter
+            .get(id)
+            .map_or_else(String::new, |c| c.name.to_string())
+    };
+    match activation.action {
+        GmMove::Attack { target, .. } => format!(
+            "{} attacks {} and {}",
+            activation.name,
+            name_of(target),
+            if activation.succeeded {
+                "hits"
+            } else {
+                "misses"
+            }
+        ),
+        GmMove::Search { target } => format!(
+            "{} searches for {} and {}",
+            activation.name,
+            name_of(target),
+            if activation.succeeded {
+                "finds them"
+            } else {
+                "comes up empty"
+            }
+        ),
+        GmMove::Hold => format!("{} holds", activation.name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{CharacterSheet, Class};
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::test_support::player;
+
+    fn campaign_with_ambush() -> Campaign {
+        let mut campaign = Campaign::new("Alone", 5);
+        campaign
+            .add_character(CharacterSheet::new(
+                player("Grom", Class::Warrior, Ancestry::Orc)
+                    .with_hp(20)
+                    .build()
+                    .unwrap(),
+            ))
+            .unwrap();
+        let goblins = (1..=2)
+            .map(|n| {
+                CombatantBuilder::adversary(format!("Goblin {}", n))
+                    .with_hp(4)
+                    .with_evasion(10)
+                    .build()
+                    .unwrap()
+            })
+            .collect();
+        campaign.begin_encounter("Ambush", goblins).unwrap();
+        campaign
+    }
+
+    #[test]
+    fn test_adversaries_act_until_a_player_is_up() {
+        let mut campaign = campaign_with_ambush();
+        let mut gm = SoloGm::seeded(11);
+
+        for _ in 0..5 {
+            let activations = gm.advance(&mut campaign).unwrap();
+            let encounter = campaign.encounter().unwrap();
+            if encounter.is_over() {
+                break;
+            }
+            assert!(encounter.current_combatant().unwrap().is_player());
+            for activation in &activations {
+                assert!(!encounter.get(activation.adversary).unwrap().is_player());
+                assert!(matches!(activation.action, GmMove::Attack { .. }));
+            }
+        }
+        assert!(campaign.notes.iter().any(|note| note.contains("Goblin")));
+    }
+
+    #[test]
+    fn test_journals_into_the_session_log() {
+        let mut campaign = campaign_with_ambush();
+        let mut gm = SoloGm::seeded(4);
+
+        gm.ask(&mut campaign, "Is it raining?", Likelihood::Unlikely);
+        gm.complication(&mut campaign);
+        gm.npc(&mut campaign);
+        gm.start_countdown(&mut campaign, Countdown::new("Reinforcements", 1))
+            .unwrap();
+        while !campaign.encounter().unwrap().countdowns.is_empty() {
+            gm.advance(&mut campaign).unwrap();
+        }
+        assert!(campaign
+            .notes
+            .iter()
+            .any(|note| note == "Countdown expired: Reinforcements"));
+
+        let session = campaign.end_session("2025-05-01", 1, Vec::new());
+        assert!(session.notes[0].starts_with("Is it raining? (Unlikely"));
+        assert!(session.notes[1].starts_with("Complication"));
+        assert!(session.notes[2].starts_with("Met "));
+    }
+
+    #[test]
+    fn test_needs_an_encounter() {
+        let mut campaign = Campaign::new("Alone", 5);
+        let mut gm = SoloGm::seeded(1);
+        assert!(matches!(
+            gm.advance(&mut campaign),
+            Err(EngineError::NoActiveEncounter)
+        ));
+    }
+}
@@ -0,0 +1,662 @@
+//! GraphQL schema for campaign data
+//!
+//! Enabled with the `graphql` cargo feature. A companion web app can query
+//! campaigns, characters, domain cards and encounters and fetch exactly the
+//! fields it needs. Data is read from any [`Storage`] backend, using the
+//! key prefixes in [`keys`].
+//!
+//! The schema is read-only; changes go through the engine (or the REST
+//! server) so game rules are always applied.
+
+use crate::campaign::{Campaign, EncounterRecord, RollRecord};
+use crate::cards::DomainCard;
+use crate::character::{Attributes, CharacterSheet};
+use crate::combat::events::CombatEvent;
+use crate::combat::simulation::{CombatEncounter, Combatant};
+use crate::storage::{keys, Persist, Storage};
+use async_graphql::{Context, EmptyMutation, EmptySubscription, Json, Object, Schema};
+use std::io;
+use std::sync::Mutex;
+
+/// The campaign GraphQL schema
+pub type CampaignSchema = Schema<QueryRoot, EmptyMutation, EmptySubscription>;
+
+/// Storage shared with resolvers
+type StorageHandle = Mutex<Box<dyn Storage + Send>>;
+
+/// Build the schema over a storage backend
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::graphql;
+/// use daggerheart_engine::storage::MemoryStorage;
+///
+/// let schema = graphql::schema(MemoryStorage::new());
+/// assert!(schema.sdl().contains("type Character"));
+/// ```
+pub fn schema<S: Storage + Send + 'static>(storage: S) -> CampaignSchema {
+    let handle: StorageHandle = Mutex::new(Box::new(storage));
+    Schema::build(QueryRoot, EmptyMutation, EmptySubscription)
+        .data(handle)
+        .finish()
+}
+
+fn load<T: Persist>(
+    ctx: &Context<'_>,
+    prefix: &str,
+    name: &str,
+) -> async_graphql::Result<Option<T>> {
+    let storage = ctx.data::<StorageHandle>()?;
+    let storage = storage.lock().unwrap_or_else(|e| e.into_inner());
+    match T::load_from(&**storage, &keys::key(prefix, name)) {
+        Ok(value) => Ok(Some(value)),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.into()),
+    }
+}
+
+fn load_all<T: Persist>(
+    ctx: &Context<'_>,
+    prefix: &str,
+) -> async_graphql::Result<Vec<(String, T)>> {
+    let storage = ctx.data::<StorageHandle>()?;
+    let storage = storage.lock().unwrap_or_else(|e| e.into_inner());
+    let mut names = keys::names(&**storage, prefix)?;
+    names.sort();
+    names
+        .into_iter()
+        .map(|name| {
+            let value = T::load_from(&**storage, &keys::key(prefix, &name))?;
+            Ok((name, value))
+        })
+        .collect()
+}
+
+/// Root query type
+pub struct QueryRoot;
+
+#[Object]
+impl QueryRoot {
+    /// Every stored campaign
+    async fn campaigns(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<CampaignObject>> {
+        Ok(load_all(ctx, keys::CAMPAIGN)?
+            .into_iter()
+            .map(|(_, campaign)| CampaignObject(campaign))
+            .collect())
+    }
+
+    /// A campaign by name
+    async fn campaign(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+    ) -> async_graphql::Result<Option<CampaignObject>> {
+        Ok(load(ctx, keys::CAMPAIGN, &name)?.map(CampaignObject))
+    }
+
+    /// Stored characters, optionally only those of one class
+    async fn characters(
+        &self,
+        ctx: &Context<'_>,
+        class: Option<String>,
+    ) -> async_graphql::Result<Vec<CharacterObject>> {
+        Ok(load_all::<CharacterSheet>(ctx, keys::CHARACTER)?
+            .into_iter()
+            .map(|(_, sheet)| sheet)
+            .filter(|sheet| match &class {
+                Some(class) => sheet
+                    .combatant
+                    .class()
+                    .is_some_and(|c| c.to_string().eq_ignore_ascii_case(class)),
+                None => true,
+            })
+            .map(CharacterObject)
+            .collect())
+    }
+
+    /// A character by name
+    async fn character(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+    ) -> async_graphql::Result<Option<CharacterObject>> {
+        Ok(load(ctx, keys::CHARACTER, &name)?.map(CharacterObject))
+    }
+
+    /// Stored encounters
+    async fn encounters(&self, ctx: &Context<'_>) -> async_graphql::Result<Vec<EncounterObject>> {
+        Ok(load_all(ctx, keys::ENCOUNTER)?
+            .into_iter()
+            .map(|(name, encounter)| EncounterObject { name, encounter })
+            .collect())
+    }
+
+    /// An encounter by name
+    async fn encounter(
+        &self,
+        ctx: &Context<'_>,
+        name: String,
+    ) -> async_graphql::Result<Option<EncounterObject>> {
+        Ok(load(ctx, keys::ENCOUNTER, &name)?.map(|encounter| EncounterObject { name, encounter }))
+    }
+
+    /// Domain cards, optionally filtered by domain and maximum level
+    async fn cards(
+        &self,
+        ctx: &Context<'_>,
+        domain: Option<String>,
+        max_level: Option<u8>,
+    ) -> async_graphql::Result<Vec<CardObject>> {
+        Ok(load_all::<DomainCard>(ctx, keys::CARD)?
+            .into_iter()
+            .map(|(_, card)| card)
+            .filter(|card| match &domain {
+                Some(domain) => card.domain.to_string().eq_ignore_ascii_case(domain),
+                None => true,
+            })
+            .filter(|card| max_level.is_none_or(|level| card.level_requirement <= level))
+            .map(CardObject)
+            .collect())
+    }
+
+    /// A domain card by id
+    async fn card(
+        &self,
+        ctx: &Context<'_>,
+        id: String,
+    ) -> async_graphql::Result<Option<CardObject>> {
+        Ok(load(ctx, keys::CARD, &id)?.map(CardObject))
+    }
+}
+
+/// A campaign
+pub struct CampaignObject(Campaign);
+
+#[Object(name = "Campaign")]
+impl CampaignObject {
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn version(&self) -> u32 {
+        self.0.version
+    }
+
+    /// Shared party Hope (tables where characters keep their own ignore it)
+    async fn hope(&self) -> u8 {
+        self.0.hope.current
+    }
+
+    async fn hope_max(&self) -> u8 {
+        self.0.hope.maximum
+    }
+
+    async fn fear(&self) -> u8 {
+        self.0.fear.current
+    }
+
+    async fn party(&self) -> Vec<CharacterObject> {
+        self.0.party.iter().cloned().map(CharacterObject).collect()
+    }
+
+    /// A party member by name
+    async fn character(&self, name: String) -> Option<CharacterObject> {
+        self.0.character(&name).cloned().map(CharacterObject)
+    }
+
+    /// The encounter being played, if any
+    async fn active_encounter(&self) -> Option<EncounterObject> {
+        self.0.active.as_ref().map(|active| EncounterObject {
+            name: active.name.clone(),
+            encounter: active.encounter.clone(),
+        })
+    }
+
+    /// Finished encounters, oldest first
+    async fn completed(&self) -> Vec<EncounterObject> {
+        self.0
+            .completed
+            .iter()
+            .map(|EncounterRecord { name, encounter }| EncounterObject {
+                name: name.clone(),
+                encounter: encounter.clone(),
+            })
+            .collect()
+    }
+
+    /// Logged duality rolls, optionally only one character's
+    async fn roll_log(&self, roller: Option<String>) -> Vec<RollObject> {
+        self.0
+            .roll_log
+            .iter()
+            .filter(|roll| roller.as_ref().is_none_or(|r| &roll.roller == r))
+            .cloned()
+            .map(RollObject)
+            .collect()
+    }
+
+    async fn notes(&self) -> &[String] {
+        &self.0.notes
+    }
+}
+
+/// A character sheet
+pub struct CharacterObject(CharacterSheet);
+
+#[Object(name = "Character")]
+impl CharacterObject {
+    async fn name(&self) -> &str {
+        self.0.name()
+    }
+
+    async fn class(&self) -> Option<String> {
+        self.0.combatant.class().map(|class| class.to_string())
+    }
+
+    async fn ancestry(&self) -> Option<String> {
+        self.0
+            .combatant
+            .ancestry()
+            .map(|ancestry| ancestry.to_string())
+    }
+
+    async fn level(&self) -> u8 {
+        self.0.progress.level
+    }
+
+    async fn experience(&self) -> u32 {
+        self.0.progress.experience
+    }
+
+    async fn hp(&self) -> u8 {
+        self.0.combatant.hp.current
+    }
+
+    /// The character's own Hope
+    async fn hope(&self) -> Option<u8> {
+        self.0.combatant.hope().map(|hope| hope.current)
+    }
+
+    async fn max_hp(&self) -> u8 {
+        self.0.combatant.hp.maximum
+    }
+
+    async fn stress(&self) -> u8 {
+        self.0.combatant.stress.current
+    }
+
+    async fn evasion(&self) -> u8 {
+        self.0.combatant.evasion
+    }
+
+    async fn armor(&self) -> u8 {
+        self.0.combatant.armor
+    }
+
+    async fn attributes(&self) -> Option<AttributesObject> {
+        self.0.combatant.attributes().copied().map(AttributesObject)
+    }
+
+    /// Ids of the domain cards this character has learned
+    async fn cards(&self) -> &[String] {
+        &self.0.progress.available_cards
+    }
+}
+
+/// Trait modifiers
+pub struct AttributesObject(Attributes);
+
+#[Object(name = "Attributes")]
+impl AttributesObject {
+    async fn agility(&self) -> i8 {
+        self.0.agility
+    }
+
+    async fn strength(&self) -> i8 {
+        self.0.strength
+    }
+
+    async fn finesse(&self) -> i8 {
+        self.0.finesse
+    }
+
+    async fn instinct(&self) -> i8 {
+        self.0.instinct
+    }
+
+    async fn presence(&self) -> i8 {
+        self.0.presence
+    }
+
+    async fn knowledge(&self) -> i8 {
+        self.0.knowledge
+    }
+}
+
+/// A named combat encounter
+pub struct EncounterObject {
+    name: String,
+    encounter: CombatEncounter,
+}
+
+#[Object(name = "Encounter")]
+impl EncounterObject {
+    async fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn round(&self) -> u32 {
+        self.encounter.round
+    }
+
+    /// Shared party Hope (tables where characters keep their own ignore it)
+    async fn hope(&self) -> u8 {
+        self.encounter.hope.current
+    }
+
+    async fn fear(&self) -> u8 {
+        self.encounter.fear.current
+    }
+
+    async fn is_over(&self) -> bool {
+        self.encounter.is_over()
+    }
+
+    /// Whether the party won (null while the fight is ongoing)
+    async fn player_victory(&self) -> Option<bool> {
+        self.encounter.player_victory()
+    }
+
+    async fn combatants(&self) -> Vec<CombatantObject> {
+        self.encounter
+            .combatants
+            .iter()
+            .cloned()
+            .map(CombatantObject)
+            .collect()
+    }
+
+    /// Whose turn it is
+    async fn current_combatant(&self) -> Option<CombatantObject> {
+        self.encounter
+            .current_combatant()
+            .cloned()
+            .map(CombatantObject)
+    }
+
+    /// The full event log, as engine JSON
+    async fn events(&self) -> Json<Vec<CombatEvent>> {
+        Json(self.encounter.events.clone())
+    }
+}
+
+/// A combatant in an encounter
+pub struct CombatantObject(Combatant);
+
+#[Object(name = "Combatant")]
+impl CombatantObject {
+    async fn id(&self) -> u32 {
+        self.0.id.0
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn is_player(&self) -> bool {
+        self.0.is_player()
+    }
+
+    async fn is_alive(&self) -> bool {
+        self.0.is_alive()
+    }
+
+    async fn hp(&self) -> u8 {
+        self.0.hp.current
+    }
+
+    async fn max_hp(&self) -> u8 {
+        self.0.hp.maximum
+    }
+
+    async fn stress(&self) -> u8 {
+        self.0.stress.current
+    }
+
+    /// The character's own Hope (null for adversaries)
+    async fn hope(&self) -> Option<u8> {
+        self.0.hope().map(|hope| hope.current)
+    }
+
+    async fn evasion(&self) -> u8 {
+        self.0.evasion
+    }
+
+    async fn conditions(&self) -> Vec<String> {
+        self.0.conditions.iter().map(|c| c.to_string()).collect()
+    }
+}
+
+/// A logged duality roll
+pub struct RollObject(RollRecord);
+
+#[Object(name = "Roll")]
+impl RollObject {
+    async fn roller(&self) -> &str {
+        &self.0.roller
+    }
+
+    async fn description(&self) -> &str {
+        &self.0.description
+    }
+
+    async fn total(&self) -> u16 {
+        self.0.result.total
+    }
+
+    async fn hope_die(&self) -> u8 {
+        self.0.result.roll.hope
+    }
+
+    async fn fear_die(&self) -> u8 {
+        self.0.result.roll.fear
+    }
+
+    async fn is_critical(&self) -> bool {
+        self.0.result.is_critical
+    }
+}
+
+/// A domain card
+pub struct CardObject(DomainCard);
+
+#[Object(name = "Card")]
+impl CardObject {
+    async fn id(&self) -> &str {
+        &self.0.id
+    }
+
+    async fn name(&self) -> &str {
+        &self.0.name
+    }
+
+    async fn domain(&self) -> String {
+        self.0.domain.to_string()
+    }
+
+    async fn level_requirement(&self) -> u8 {
+        self.0.level_requirement
+    }
+
+    async fn description(&self) -> &str {
+        &self.0.description
+    }
+
+    async fn action_cost(&self) -> String {
+        format!("{:?}", self.0.action_cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::ActionCost;
+    use crate::character::{Ancestry, Class, Domain};
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::core::dice::DualityRoll;
+    use crate::storage::MemoryStorage;
+    use crate::test_support::player;
+    use serde_json::{json, Value};
+
+    fn create_test_sheet(name: &str, class: Class) -> CharacterSheet {
+        CharacterSheet::new(player(name, class, Ancestry::Human).build().unwrap())
+    }
+
+    fn create_test_schema() -> CampaignSchema {
+        let mut storage = MemoryStorage::new();
+
+        let mut campaign = Campaign::new("Sablewood", 5);
+        campaign
+            .add_character(create_test_sheet("Grom", Class::Warrior))
+            .unwrap();
+        campaign
+            .begin_encounter(
+                "Ambush",
+                vec![CombatantBuilder::adversary("Goblin")
+                    .with_hp(4)
+                    .with_evasion(10)
+                    .build()
+                    .unwrap()],
+            )
+            .unwrap();
+        campaign.log_roll(
+            "Grom",
+            "Smash",
+            DualityRoll::from_values(7, 3).with_modifier(2),
+        );
+        campaign
+            .save_to(&mut storage, "campaign.Sablewood")
+            .unwrap();
+
+        create_test_sheet("Grom", Class::Warrior)
+            .save_to(&mut storage, "character.Grom")
+            .unwrap();
+        create_test_sheet("Ash", Class::Rogue)
+            .save_to(&mut storage, "character.Ash")
+            .unwrap();
+
+        DomainCard::new(
+            "strike",
+            "Strike",
+            Domain::Blade,
+            1,
+            "Hit",
+            ActionCost::Major,
+        )
+        .save_to(&mut storage, "card.strike")
+        .unwrap();
+        DomainCard::new(
+            "storm",
+            "Storm",
+            Domain::Arcana,
+            4,
+            "Zap",
+            ActionCost::Major,
+        )
+        .save_to(&mut storage, "card.storm")
+        .unwrap();
+
+        schema(storage)
+    }
+
+    async fn query(schema: &CampaignSchema, query: &str) -> Value {
+        let response = schema.execute(query).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        serde_json::to_value(response.data).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_query_campaign() {
+        let schema = create_test_schema();
+        let data = query(
+            &schema,
+            r#"{
+                campaign(name: "Sablewood") {
+                    name
+                    party { name class hp }
+                    activeEncounter { name combatants { name isPlayer } }
+                    rollLog { roller total }
+                }
+            }"#,
+        )
+        .await;
+
+        let campaign = &data["campaign"];
+        assert_eq!(campaign["name"], "Sablewood");
+        assert_eq!(campaign["party"][0]["class"], "Warrior");
+        assert_eq!(campaign["activeEncounter"]["name"], "Ambush");
+        assert_eq!(
+            campaign["activeEncounter"]["combatants"]
+                .as_array()
+                .unwrap()
+                .len(),
+            2
+        );
+        assert_eq!(
+            campaign["rollLog"][0],
+            json!({ "roller": "Grom", "total": 12 })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_characters_by_class() {
+        let schema = create_test_schema();
+        let data = query(&schema, r#"{ characters(class: "rogue") { name } }"#).await;
+        assert_eq!(data["characters"], json!([{ "name": "Ash" }]));
+
+        let data = query(&schema, "{ characters { name attributes { agility } } }").await;
+        assert_eq!(data["characters"].as_array().unwrap().len(), 2);
+        assert_eq!(data["characters"][0]["attributes"]["agility"], 2);
+    }
+
+    #[tokio::test]
+    async fn test_cards_filtered() {
+        let schema = create_test_schema();
+        let data = query(&schema, "{ cards(maxLevel: 2) { id domain } }").await;
+        assert_eq!(
+            data["cards"],
+            json!([{ "id": "strike", "domain": "Blade" }])
+        );
+
+        let data = query(&schema, r#"{ cards(domain: "arcana") { name } }"#).await;
+        assert_eq!(data["cards"], json!([{ "name": "Storm" }]));
+    }
+
+    #[tokio::test]
+    async fn test_missing_items_are_null() {
+        let schema = create_test_schema();
+        let data = query(
+            &schema,
+            r#"{ character(name: "Nobody") { name } card(id: "nope") { id } }"#,
+        )
+        .await;
+        assert_eq!(data, json!({ "character": null, "card": null }));
+    }
+
+    #[tokio::test]
+    async fn test_encounter_events_as_json() {
+        let mut storage = MemoryStorage::new();
+        let mut encounter = CombatEncounter::new(5);
+        encounter.gain_fear(1);
+        encounter.save_to(&mut storage, "encounter.ambush").unwrap();
+
+        let data = query(&schema(storage), "{ encounters { name fear events } }").await;
+        assert_eq!(data["encounters"][0]["name"], "ambush");
+        assert_eq!(data["encounters"][0]["fear"], 1);
+        assert_eq!(
+            data["encounters"][0]["events"],
+            json!([{ "FearGained": { "amount": 1 } }])
+        );
+    }
+}
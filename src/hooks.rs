@@ -0,0 +1,237 @@
+//! Observer hooks for engine mutations
+//!
+//! Embedders register an `EngineHooks` implementation on a
+//! `CombatEncounter` or `CharacterSheet` to drive UI updates, sound effects
+//! or analytics as things happen, instead of polling for state diffs.
+//!
+//! Hooks are not part of the saved state: they are skipped when
+//! serializing and must be registered again after loading.
+
+use crate::cards::DomainCard;
+use crate::combat::attack::AttackResult;
+use crate::combat::damage::DamageResult;
+use crate::combat::events::CombatEvent;
+use crate::combat::simulation::CombatantId;
+use std::fmt;
+use std::sync::Arc;
+
+/// Callbacks fired when the engine changes state
+///
+/// Every method has an empty default, so implementors only override the
+/// ones they care about.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::combat::{
+///     CombatEncounter, CombatantBuilder, CombatantId, DamageResult,
+/// };
+/// use daggerheart_engine::hooks::EngineHooks;
+/// use std::sync::atomic::{AtomicU32, Ordering};
+/// use std::sync::Arc;
+///
+/// #[derive(Default)]
+/// struct DamageMeter(AtomicU32);
+///
+/// impl EngineHooks for DamageMeter {
+///     fn on_damage(&self, _target: CombatantId, damage: &DamageResult) {
+///         self.0.fetch_add(damage.hp_lost as u32, Ordering::Relaxed);
+///     }
+/// }
+///
+/// let meter = Arc::new(DamageMeter::default());
+/// let mut encounter = CombatEncounter::new(5);
+/// encounter.add_hook(meter.clone());
+///
+/// let goblin = encounter.add_combatant(
+///     CombatantBuilder::adversary("Goblin")
+///         .with_hp(4)
+///         .with_evasion(10)
+///         .build()
+///         .unwrap(),
+/// );
+/// encounter.apply_damage(goblin, 7).unwrap();
+/// assert_eq!(meter.0.load(Ordering::Relaxed), 1);
+/// ```
+pub trait EngineHooks: Send + Sync {
+    /// Any event was appended to an encounter's log
+    fn on_event(&self, _event: &CombatEvent) {}
+
+    /// An attack roll was made
+    fn on_roll(&self, _attacker: CombatantId, _target: CombatantId, _result: &AttackResult) {}
+
+    /// Damage was applied to a combatant
+    fn on_damage(&self, _target: CombatantId, _damage: &DamageResult) {}
+
+    /// A character reached a new level
+    fn on_level_up(&self, _character: &str, _level: u8) {}
+
+    /// A character's companion grew with them and can take an upgrade
+    fn on_companion_level_up(&self, _character: &str, _companion: &str, _level: u8) {}
+
+    /// A character played a domain card
+    fn on_card_played(&self, _character: &str, _card: &DomainCard) {}
+}
+
+/// The hooks registered on an encounter or sheet
+///
+/// Cloning shares the registered hooks. Hooks never affect equality, so
+/// two sheets with the same state compare equal whoever is listening.
+#[derive(Clone, Default)]
+pub struct Hooks(Vec<Arc<dyn EngineHooks>>);
+
+impl Hooks {
+    /// Register a hook
+    pub fn add(&mut self, hook: Arc<dyn EngineHooks>) {
+        self.0.push(hook);
+    }
+
+    /// Remove every registered hook
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    /// Number of registered hooks
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Check if no hooks are registered
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The registered hooks, in the order they were added
+    pub fn iter(&self) -> impl Iterator<Item = &Arc<dyn EngineHooks>> {
+        self.0.iter()
+    }
+
+    /// Dispatch a logged combat event
+    ///
+    /// Calls `on_event`, plus `on_roll` or `on_damage` for attack and
+    /// damage events.
+    pub(crate) fn event(&self, event: &CombatEvent) {
+        for hook in &self.0 {
+            hook.on_event(event);
+            match event {
+                CombatEvent::AttackResolved {
+                    attacker,
+                    target,
+                    result,
+                } => hook.on_roll(*attacker, *target, result),
+                CombatEvent::DamageApplied { target, damage } => hook.on_damage(*target, damage),
+                _ => {}
+            }
+        }
+    }
+
+    pub(crate) fn level_up(&self, character: &str, level: u8) {
+        for hook in &self.0 {
+            hook.on_level_up(character, level);
+        }
+    }
+
+    pub(crate) fn companion_level_up(&self, character: &str, companion: &str, level: u8) {
+        for hook in &self.0 {
+            hook.on_companion_level_up(character, companion, level);
+        }
+    }
+
+    pub(crate) fn card_played(&self, character: &str, card: &DomainCard) {
+        for hook in &self.0 {
+            hook.on_card_played(character, card);
+        }
+    }
+}
+
+impl fmt::Debug for Hooks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Hooks({})", self.0.len())
+    }
+}
+
+impl PartialEq for Hooks {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Records every callback as a short string
+    #[derive(Default)]
+    pub(crate) struct Recorder(pub(crate) Mutex<Vec<String>>);
+
+    impl Recorder {
+        pub(crate) fn calls(&self) -> Vec<String> {
+            self.0.lock().unwrap().clone()
+        }
+
+        fn record(&self, call: String) {
+            self.0.lock().unwrap().push(call);
+        }
+    }
+
+    impl EngineHooks for Recorder {
+        fn on_roll(&self, attacker: CombatantId, target: CombatantId, _result: &AttackResult) {
+            self.record(format!("roll {} -> {}", attacker, target));
+        }
+
+        fn on_damage(&self, target: CombatantId, damage: &DamageResult) {
+            self.record(format!("damage {} {}", target, damage.hp_lost));
+        }
+
+        fn on_level_up(&self, character: &str, level: u8) {
+            self.record(format!("level {} {}", character, level));
+        }
+
+        fn on_companion_level_up(&self, character: &str, companion: &str, level: u8) {
+            self.record(format!("companion {} {} {}", character, companion, level));
+        }
+
+        fn on_card_played(&self, character: &str, card: &DomainCard) {
+            self.record(format!("card {} {}", character, card.id));
+        }
+    }
+
+    #[test]
+    fn test_event_dispatch() {
+        let recorder = Arc::new(Recorder::default());
+        let mut hooks = Hooks::default();
+        hooks.add(recorder.clone());
+
+        hooks.event(&CombatEvent::DamageApplied {
+            target: CombatantId(1),
+            damage: DamageResult::calculate(7, 0),
+        });
+        hooks.event(&CombatEvent::HopeGained {
+            combatant: CombatantId(0),
+            amount: 1,
+        });
+
+        assert_eq!(recorder.calls(), vec!["damage #1 1"]);
+    }
+
+    #[test]
+    fn test_default_methods_are_noops() {
+        struct Silent;
+        impl EngineHooks for Silent {}
+
+        let mut hooks = Hooks::default();
+        hooks.add(Arc::new(Silent));
+        hooks.event(&CombatEvent::FearGained { amount: 1 });
+        hooks.level_up("Grom", 2);
+        assert_eq!(hooks.len(), 1);
+    }
+
+    #[test]
+    fn test_hooks_ignored_by_equality() {
+        let mut hooks = Hooks::default();
+        hooks.add(Arc::new(Recorder::default()));
+        assert_eq!(hooks, Hooks::default());
+        assert_eq!(format!("{:?}", hooks), "Hooks(1)");
+    }
+}
@@ -0,0 +1,231 @@
+//! Translated game text
+//!
+//! A [`Catalog`] maps message keys to text in one locale. Anything the
+//! catalog doesn't have falls back to the engine's English, so a partial
+//! translation still renders. Keys follow what they name:
+//!
+//! - `card.<id>.name`, `card.<id>.description`
+//! - `adversary.<name>`
+//! - `class.<Class>`, `subclass.<Subclass>`, `ancestry.<Ancestry>`,
+//!   `community.<Community>`, `domain.<Domain>`, `trait.<Trait>`,
+//!   `ability.<Ability>`, `feature.<Feature>`, `cost.<Action cost>`
+//! - `sheet.<Label>` for the headings and labels of rendered sheets, and
+//!   `sheet.level`, `sheet.card_level` and `sheet.marked` for the phrases
+//!   around numbers
+//!
+//! Text may contain `{name}` placeholders, filled in by [`Catalog::format`].
+
+#[cfg(feature = "content")]
+use crate::character::{Ancestry, AttributeType, Class, Community, Domain, Subclass};
+#[cfg(feature = "content")]
+use crate::content::ContentRegistry;
+#[cfg(feature = "persistence")]
+use crate::storage::Persist;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+#[cfg(feature = "content")]
+use strum::IntoEnumIterator;
+
+/// Headings and labels of rendered character sheets
+#[cfg(feature = "content")]
+const SHEET_LABELS: [&str; 12] = [
+    "Traits",
+    "Stats",
+    "Features",
+    "Experiences",
+    "Domain Cards",
+    "Equipment",
+    "HP",
+    "Stress",
+    "Evasion",
+    "Armor",
+    "Tier",
+    "XP",
+];
+
+/// Sheet phrases around numbers, with their English text
+#[cfg(feature = "content")]
+const SHEET_PHRASES: [(&str, &str); 3] = [
+    ("level", "Level {level}"),
+    ("card_level", "level {level}"),
+    ("marked", "{count} marked"),
+];
+
+/// Messages for one locale
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Catalog {
+    /// A language tag such as `de` or `pt-BR`
+    pub locale: String,
+    #[serde(default)]
+    pub messages: BTreeMap<String, String>,
+}
+
+impl Catalog {
+    /// Create an empty catalog for a locale
+    pub fn new(locale: impl Into<String>) -> Self {
+        Self {
+            locale: locale.into(),
+            messages: BTreeMap::new(),
+        }
+    }
+
+    /// The engine's own text, with nothing translated
+    pub fn english() -> Self {
+        Self::new("en")
+    }
+
+    /// Add a message
+    pub fn with_message(mut self, key: impl Into<String>, text: impl Into<String>) -> Self {
+        self.messages.insert(key.into(), text.into());
+        self
+    }
+
+    /// The text for `key`, or `fallback` if it isn't translated
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::i18n::Catalog;
+    ///
+    /// let german = Catalog::new("de").with_message("trait.Strength", "Stärke");
+    /// assert_eq!(german.text("trait.Strength", "Strength"), "Stärke");
+    /// assert_eq!(german.text("trait.Agility", "Agility"), "Agility");
+    /// ```
+    pub fn text<'a>(&'a self, key: &str, fallback: &'a str) -> &'a str {
+        self.messages.get(key).map_or(fallback, String::as_str)
+    }
+
+    /// Translated text with `{name}` placeholders filled in
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::i18n::Catalog;
+    ///
+    /// let french = Catalog::new("fr").with_message("sheet.level", "Niveau {level}");
+    /// assert_eq!(french.format("sheet.level", "Level {level}", &[("level", "3")]), "Niveau 3");
+    /// ```
+    pub fn format(&self, key: &str, fallback: &str, args: &[(&str, &str)]) -> String {
+        args.iter().fold(
+            self.text(key, fallback).to_string(),
+            |text, (name, value)| text.replace(&format!("{{{}}}", name), value),
+        )
+    }
+
+    /// A value's display name, under `<prefix>.<name>`
+    #[cfg(feature = "content")]
+    pub(crate) fn name(&self, prefix: &str, english: &str) -> String {
+        self.text(&format!("{}.{}", prefix, english), english)
+            .to_string()
+    }
+
+    /// Every key sheets and `content` use, with its English text, for
+    /// translators to start from
+    #[cfg(feature = "content")]
+    pub fn template(locale: impl Into<String>, content: &ContentRegistry) -> Self {
+        let mut catalog = Self::new(locale);
+        let names = Class::iter()
+            .map(|c| ("class", c.to_string()))
+            .chain(Subclass::iter().map(|s| ("subclass", s.to_string())))
+            .chain(Ancestry::iter().map(|a| ("ancestry", a.to_string())))
+            .chain(Ancestry::iter().flat_map(|a| {
+                a.features()
+                    .into_iter()
+                    .map(|feature| ("ability", feature.name.to_string()))
+            }))
+            .chain(Community::iter().map(|c| ("community", c.to_string())))
+            .chain(Community::iter().map(|c| ("feature", c.feature().to_string())))
+            .chain(Domain::iter().map(|d| ("domain", d.to_string())))
+            .chain(AttributeType::iter().map(|t| ("trait", t.to_string())))
+            .chain(
+                ["Major action", "Minor action", "Reaction", "Free action"]
+                    .map(|cost| ("cost", cost.to_string())),
+            )
+            .chain(SHEET_LABELS.map(|label| ("sheet", label.to_string())));
+        for (prefix, name) in names {
+            catalog = catalog.with_message(format!("{}.{}", prefix, name), name);
+        }
+        for (key, text) in SHEET_PHRASES {
+            catalog = catalog.with_message(format!("sheet.{}", key), text);
+        }
+        for card in &content.cards {
+            catalog = catalog
+                .with_message(format!("card.{}.name", card.id), card.name.clone())
+                .with_message(
+                    format!("card.{}.description", card.id),
+                    card.description.clone(),
+                );
+        }
+        for adversary in &content.adversaries {
+            catalog = catalog.with_message(
+                format!("adversary.{}", adversary.name),
+                adversary.name.clone(),
+            );
+        }
+        catalog
+    }
+}
+
+#[cfg(feature = "persistence")]
+impl Persist for Catalog {}
+
+#[cfg(feature = "content")]
+impl ContentRegistry {
+    /// A copy with card and adversary text from `catalog`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::content::ContentRegistry;
+    /// use daggerheart_engine::i18n::Catalog;
+    ///
+    /// let spanish = Catalog::new("es").with_message("card.get_back_up.name", "Levántate");
+    /// let content = ContentRegistry::builtin().localized(&spanish);
+    /// assert_eq!(content.card("get_back_up").unwrap().name, "Levántate");
+    /// ```
+    pub fn localized(&self, catalog: &Catalog) -> ContentRegistry {
+        let mut content = self.clone();
+        for card in &mut content.cards {
+            card.name = catalog
+                .text(&format!("card.{}.name", card.id), &card.name)
+                .to_string();
+            card.description = catalog
+                .text(&format!("card.{}.description", card.id), &card.description)
+                .to_string();
+        }
+        for adversary in &mut content.adversaries {
+            adversary.name = catalog.name("adversary", &adversary.name);
+        }
+        content.reindex();
+        content
+    }
+}
+
+#[cfg(all(test, feature = "content", feature = "persistence"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template_covers_content() {
+        let content = ContentRegistry::builtin();
+        let template = Catalog::template("de", &content);
+        assert_eq!(template.text("class.Seraph", ""), "Seraph");
+        assert_eq!(template.text("sheet.marked", ""), "{count} marked");
+        assert_eq!(
+            template.text("card.get_back_up.name", ""),
+            content.card("get_back_up").unwrap().name
+        );
+
+        // An untouched template changes nothing
+        assert_eq!(content.localized(&template), content);
+    }
+
+    #[test]
+    fn test_catalog_file_format() {
+        let json = r#"{"locale": "de", "messages": {"class.Warrior": "Krieger"}}"#;
+        let catalog: Catalog = serde_json::from_str(json).unwrap();
+        assert_eq!(catalog.name("class", "Warrior"), "Krieger");
+        assert_eq!(catalog.name("class", "Bard"), "Bard");
+    }
+}
@@ -0,0 +1,483 @@
+//! Foundry VTT actor export/import
+//!
+//! Converts a `CharacterSheet` to and from the actor JSON used by the
+//! Foundry VTT Daggerheart system. Class, ancestry and domain cards become
+//! embedded items; traits, resources, evasion and level map onto the
+//! actor's `system` data.
+//!
+//! Values Foundry has no field for (armor, initiative, experience,
+//! conditions) are kept under `flags["daggerheart-engine"]`, so an
+//! export/import round trip is lossless. Actors created in Foundry import
+//! with defaults for those values.
+
+use crate::character::{Ancestry, Attributes, CharacterSheet, Class};
+use crate::combat::conditions::Condition;
+use crate::combat::simulation::CombatantBuilder;
+use crate::error::EngineError;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use strum::IntoEnumIterator;
+
+/// Flag scope used for engine-only values
+pub const FLAG_SCOPE: &str = "daggerheart-engine";
+
+/// Stress slots on a new Foundry character
+const DEFAULT_STRESS_SLOTS: u8 = 6;
+
+/// Hope a new character starts with, and the Hope cap
+const STARTING_HOPE: u8 = 2;
+const MAX_HOPE: u8 = 6;
+
+/// A Foundry actor document
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FoundryActor {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub actor_type: String,
+    #[serde(default)]
+    pub system: ActorSystem,
+    #[serde(default)]
+    pub items: Vec<FoundryItem>,
+    #[serde(default)]
+    pub flags: Map<String, Value>,
+}
+
+/// The `system` data of a character actor
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActorSystem {
+    #[serde(default)]
+    pub traits: Traits,
+    #[serde(default)]
+    pub resources: Resources,
+    #[serde(default)]
+    pub evasion: u8,
+    #[serde(default)]
+    pub level_data: LevelData,
+}
+
+/// Trait modifiers, one entry per trait
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Traits {
+    #[serde(default)]
+    pub agility: TraitValue,
+    #[serde(default)]
+    pub strength: TraitValue,
+    #[serde(default)]
+    pub finesse: TraitValue,
+    #[serde(default)]
+    pub instinct: TraitValue,
+    #[serde(default)]
+    pub presence: TraitValue,
+    #[serde(default)]
+    pub knowledge: TraitValue,
+}
+
+/// A single trait modifier
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraitValue {
+    pub value: i8,
+}
+
+/// Character resource tracks
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Resources {
+    /// `value` counts marked hit points, as on the Foundry sheet
+    #[serde(default)]
+    pub hit_points: ResourceTrack,
+    #[serde(default)]
+    pub stress: ResourceTrack,
+    #[serde(default)]
+    pub hope: ResourceTrack,
+}
+
+/// A resource with a current value and a maximum
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResourceTrack {
+    pub value: u8,
+    pub max: u8,
+}
+
+/// Level information
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LevelData {
+    pub level: Level,
+}
+
+impl Default for LevelData {
+    fn default() -> Self {
+        Self {
+            level: Level { current: 1 },
+        }
+    }
+}
+
+/// The character's current level
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Level {
+    pub current: u8,
+}
+
+/// An item embedded in an actor (class, ancestry, domain card, ...)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FoundryItem {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub item_type: String,
+    #[serde(default)]
+    pub system: Value,
+}
+
+impl FoundryItem {
+    fn new(name: impl Into<String>, item_type: &str) -> Self {
+        Self {
+            name: name.into(),
+            item_type: item_type.to_string(),
+            system: Value::Object(Map::new()),
+        }
+    }
+}
+
+/// Engine-only values stored in the actor's flags
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct EngineFlags {
+    #[serde(default)]
+    armor: u8,
+    #[serde(default)]
+    initiative: u8,
+    #[serde(default)]
+    experience: u32,
+    #[serde(default)]
+    conditions: Vec<Condition>,
+}
+
+impl FoundryActor {
+    /// Build a character actor from a sheet
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    /// use daggerheart_engine::interop::FoundryActor;
+    ///
+    /// let sheet = CharacterSheet::new(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// let actor = FoundryActor::from_sheet(&sheet);
+    /// assert_eq!(actor.actor_type, "character");
+    /// assert_eq!(actor.system.traits.agility.value, 2);
+    /// ```
+    pub fn from_sheet(sheet: &CharacterSheet) -> Self {
+        let combatant = &sheet.combatant;
+
+        let mut items = Vec::new();
+        let mut traits = Traits::default();
+        if let Some(player) = combatant.player_data() {
+            items.push(FoundryItem::new(player.class.to_string(), "class"));
+            items.push(FoundryItem::new(player.ancestry.to_string(), "ancestry"));
+
+            let attributes = &player.attributes;
+            traits = Traits {
+                agility: TraitValue {
+                    value: attributes.agility,
+                },
+                strength: TraitValue {
+                    value: attributes.strength,
+                },
+                finesse: TraitValue {
+                    value: attributes.finesse,
+                },
+                instinct: TraitValue {
+                    value: attributes.instinct,
+                },
+                presence: TraitValue {
+                    value: attributes.presence,
+                },
+                knowledge: TraitValue {
+                    value: attributes.knowledge,
+                },
+            };
+        }
+        items.extend(
+            sheet
+                .progress
+                .available_cards
+                .iter()
+                .map(|card| FoundryItem::new(card.clone(), "domainCard")),
+        );
+
+        let engine_flags = EngineFlags {
+            armor: combatant.armor,
+            initiative: combatant.initiative,
+            experience: sheet.progress.experience,
+            conditions: combatant.conditions.clone(),
+        };
+        let mut flags = Map::new();
+        flags.insert(
+            FLAG_SCOPE.to_string(),
+            serde_json::to_value(engine_flags).unwrap_or_default(),
+        );
+
+        Self {
+            name: combatant.name.to_string(),
+            actor_type: "character".to_string(),
+            system: ActorSystem {
+                traits,
+                resources: Resources {
+                    hit_points: ResourceTrack {
+                        value: combatant.hp.maximum - combatant.hp.current,
+                        max: combatant.hp.maximum,
+                    },
+                    stress: ResourceTrack {
+                        value: combatant.stress.current,
+                        max: DEFAULT_STRESS_SLOTS.max(combatant.stress.current),
+                    },
+                    hope: ResourceTrack {
+                        value: STARTING_HOPE,
+                        max: MAX_HOPE,
+                    },
+                },
+                evasion: combatant.evasion,
+                level_data: LevelData {
+                    level: Level {
+                        current: sheet.progress.level,
+                    },
+                },
+            },
+            items,
+            flags,
+        }
+    }
+
+    /// Convert a character actor back into a sheet
+    ///
+    /// The actor must be of type `character` and carry exactly the class
+    /// and ancestry items the engine knows about. Traits are taken as-is,
+    /// since a levelled-up character may no longer match the starting
+    /// distribution.
+    pub fn to_sheet(&self) -> Result<CharacterSheet, EngineError> {
+        if self.actor_type != "character" {
+            return Err(EngineError::InvalidCharacterState(format!(
+                "Foundry actor '{}' is a {}, not a character",
+                self.name, self.actor_type
+            )));
+        }
+
+        let class_name = self.item_named("class")?;
+        let class = Class::iter()
+            .find(|c| c.to_string().eq_ignore_ascii_case(class_name))
+            .ok_or_else(|| {
+                EngineError::InvalidCharacterState(format!("Unknown class: {}", class_name))
+            })?;
+        let ancestry_name = self.item_named("ancestry")?;
+        let ancestry = Ancestry::iter()
+            .find(|a| a.to_string().eq_ignore_ascii_case(ancestry_name))
+            .ok_or_else(|| {
+                EngineError::InvalidCharacterState(format!("Unknown ancestry: {}", ancestry_name))
+            })?;
+
+        let traits = &self.system.traits;
+        let attributes = Attributes {
+            agility: traits.agility.value,
+            strength: traits.strength.value,
+            finesse: traits.finesse.value,
+            instinct: traits.instinct.value,
+            presence: traits.presence.value,
+            knowledge: traits.knowledge.value,
+        };
+
+        let level = self.system.level_data.level.current;
+        let mut combatant = CombatantBuilder::player(&self.name, class, ancestry)
+            .with_level(level)
+            .with_attributes(attributes)
+            .build()?;
+
+        let hit_points = self.system.resources.hit_points;
+        if hit_points.max > 0 {
+            combatant.hp.maximum = hit_points.max;
+        }
+        combatant.hp.current = combatant.hp.maximum.saturating_sub(hit_points.value);
+        combatant.stress.current = self.system.resources.stress.value;
+        if self.system.evasion > 0 {
+            combatant.evasion = self.system.evasion;
+        }
+
+        let engine_flags: EngineFlags = match self.flags.get(FLAG_SCOPE) {
+            Some(value) => serde_json::from_value(value.clone())
+                .map_err(|e| EngineError::SerializationError(e.to_string()))?,
+            None => EngineFlags::default(),
+        };
+        combatant.armor = engine_flags.armor;
+        combatant.initiative = engine_flags.initiative;
+        combatant.conditions = engine_flags.conditions;
+
+        let mut sheet = CharacterSheet::new(combatant);
+        sheet.progress.experience = engine_flags.experience;
+        for item in self.items.iter().filter(|i| i.item_type == "domainCard") {
+            sheet.progress.add_card(item.name.clone());
+        }
+        Ok(sheet)
+    }
+
+    fn item_named(&self, item_type: &str) -> Result<&str, EngineError> {
+        self.items
+            .iter()
+            .find(|item| item.item_type == item_type)
+            .map(|item| item.name.as_str())
+            .ok_or_else(|| {
+                EngineError::InvalidCharacterState(format!(
+                    "Foundry actor '{}' has no {} item",
+                    self.name, item_type
+                ))
+            })
+    }
+}
+
+/// Export a character sheet as Foundry actor JSON
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+/// use daggerheart_engine::combat::CombatantBuilder;
+/// use daggerheart_engine::interop::{export_character, import_character};
+///
+/// let sheet = CharacterSheet::new(
+///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+///         .build()
+///         .unwrap(),
+/// );
+///
+/// let json = export_character(&sheet).unwrap();
+/// assert_eq!(import_character(&json).unwrap(), sheet);
+/// ```
+pub fn export_character(sheet: &CharacterSheet) -> Result<String, EngineError> {
+    serde_json::to_string_pretty(&FoundryActor::from_sheet(sheet))
+        .map_err(|e| EngineError::SerializationError(e.to_string()))
+}
+
+/// Import a character sheet from Foundry actor JSON
+pub fn import_character(json: &str) -> Result<CharacterSheet, EngineError> {
+    let actor: FoundryActor =
+        serde_json::from_str(json).map_err(|e| EngineError::SerializationError(e.to_string()))?;
+    actor.to_sheet()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::player;
+
+    fn create_test_sheet() -> CharacterSheet {
+        let mut sheet = CharacterSheet::new(
+            player("Grom", Class::Warrior, Ancestry::Orc)
+                .with_level(2)
+                .build()
+                .unwrap(),
+        );
+        sheet.combatant.armor = 3;
+        sheet.combatant.take_damage(2);
+        sheet.combatant.stress.gain(1);
+        sheet.combatant.conditions.push(Condition::Vulnerable);
+        sheet.progress.experience = 40;
+        sheet.progress.add_card("blade_strike");
+        sheet
+    }
+
+    #[test]
+    fn test_roundtrip_is_lossless() {
+        let sheet = create_test_sheet();
+        let json = export_character(&sheet).unwrap();
+        assert_eq!(import_character(&json).unwrap(), sheet);
+    }
+
+    #[test]
+    fn test_export_layout() {
+        let sheet = create_test_sheet();
+        let json: Value = serde_json::from_str(&export_character(&sheet).unwrap()).unwrap();
+
+        assert_eq!(json["type"], "character");
+        assert_eq!(json["system"]["traits"]["strength"]["value"], 1);
+        assert_eq!(json["system"]["resources"]["hitPoints"]["value"], 2);
+        assert_eq!(json["system"]["levelData"]["level"]["current"], 2);
+        assert_eq!(json["items"][0]["type"], "class");
+        assert_eq!(json["items"][2]["name"], "blade_strike");
+    }
+
+    #[test]
+    fn test_import_foundry_created_actor() {
+        let json = r#"{
+            "name": "Sable",
+            "type": "character",
+            "system": {
+                "traits": {
+                    "agility": { "value": 2 },
+                    "finesse": { "value": 1 },
+                    "knowledge": { "value": -1 }
+                },
+                "resources": {
+                    "hitPoints": { "value": 1, "max": 6 },
+                    "stress": { "value": 2, "max": 6 }
+                },
+                "evasion": 12,
+                "levelData": { "level": { "current": 3 } },
+                "biography": "Unknown fields are ignored"
+            },
+            "items": [
+                { "name": "rogue", "type": "class" },
+                { "name": "Katari", "type": "ancestry" },
+                { "name": "Leather Armor", "type": "armor" }
+            ]
+        }"#;
+
+        let sheet = import_character(json).unwrap();
+        assert_eq!(sheet.combatant.class(), Some(Class::Rogue));
+        assert_eq!(sheet.combatant.ancestry(), Some(Ancestry::Katari));
+        assert_eq!(sheet.combatant.hp.current, 5);
+        assert_eq!(sheet.combatant.stress.current, 2);
+        assert_eq!(sheet.combatant.evasion, 12);
+        assert_eq!(sheet.combatant.attributes().unwrap().agility, 2);
+        assert_eq!(sheet.progress.level, 3);
+        assert!(sheet.progress.available_cards.is_empty());
+    }
+
+    #[test]
+    fn test_import_rejects_non_characters() {
+        let json = r#"{ "name": "Goblin", "type": "adversary" }"#;
+        assert!(matches!(
+            import_character(json),
+            Err(EngineError::InvalidCharacterState(_))
+        ));
+    }
+
+    #[test]
+    fn test_import_requires_known_class() {
+        let json = r#"{
+            "name": "Odd",
+            "type": "character",
+            "items": [
+                { "name": "Necromancer", "type": "class" },
+                { "name": "Human", "type": "ancestry" }
+            ]
+        }"#;
+        assert!(import_character(json).is_err());
+
+        let json = r#"{ "name": "Odd", "type": "character", "items": [] }"#;
+        assert!(import_character(json).is_err());
+    }
+
+    #[test]
+    fn test_import_invalid_json() {
+        assert!(matches!(
+            import_character("not json"),
+            Err(EngineError::SerializationError(_))
+        ));
+    }
+}
@@ -0,0 +1,484 @@
+//! Best-effort import of character JSON from other tools
+//!
+//! Character builders such as Demiplane export JSON with their own layout,
+//! and those layouts change between versions. Rather than one strict
+//! format, the importer looks for each value under the keys these exports
+//! commonly use, case-insensitively:
+//!
+//! - `name` / `characterName`
+//! - `class` (a name, `{ "name", "subclass" }`, or a list of those),
+//!   `subclass`, `ancestry` / `heritage.ancestry`, `community` /
+//!   `heritage.community`, `level`
+//! - `traits` / `attributes` / `stats`: a number, or `{ "value" }`, per trait
+//! - `hp` / `hitPoints`, `stress`, `evasion`, `armor` / `armorScore`
+//! - `domainCards` / `cards` / `loadout` / `vault`: card ids or names
+//! - `equipment` / `inventory` / `weapons`, `experiences`, `xp`
+//!
+//! Exports wrapped in `{ "character": ... }` or `{ "data": ... }` are
+//! unwrapped first. Class, ancestry and traits are required; anything else
+//! that can't be mapped is skipped and reported as a warning.
+
+use crate::character::{
+    Ancestry, Attributes, CharacterSheet, Class, Community, Experience, Subclass,
+};
+use crate::combat::simulation::CombatantBuilder;
+use crate::content::ContentRegistry;
+use crate::error::EngineError;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use strum::IntoEnumIterator;
+
+/// Bonus for an experience exported without one
+const DEFAULT_EXPERIENCE_BONUS: i8 = 2;
+
+/// A converted character and everything that didn't carry over
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportReport {
+    pub sheet: CharacterSheet,
+    /// One line per skipped field or unrecognized value
+    pub warnings: Vec<String>,
+}
+
+/// Convert a third-party character export
+///
+/// Cards are matched against `content` by id or name.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::{Class, Subclass};
+/// use daggerheart_engine::content::ContentRegistry;
+/// use daggerheart_engine::interop::import;
+///
+/// let json = r#"{
+///     "name": "Grom",
+///     "class": { "name": "Warrior", "subclass": "Call of the Slayer" },
+///     "heritage": { "ancestry": "Orc", "community": "Ridgeborne" },
+///     "traits": { "agility": 2, "strength": 1, "finesse": 1,
+///                 "instinct": 0, "presence": 0, "knowledge": -1 },
+///     "domainCards": ["Get Back Up", "Mystery Card"],
+///     "gold": 12
+/// }"#;
+///
+/// let report = import::from_json(json, &ContentRegistry::builtin()).unwrap();
+/// assert_eq!(report.sheet.combatant.class(), Some(Class::Warrior));
+/// assert_eq!(report.sheet.combatant.subclass(), Some(Subclass::CallOfTheSlayer));
+/// assert_eq!(report.sheet.progress.available_cards, vec!["get_back_up"]);
+/// assert_eq!(
+///     report.warnings,
+///     vec!["Unknown card 'Mystery Card', skipped", "Ignored field 'gold'"]
+/// );
+/// ```
+pub fn from_json(json: &str, content: &ContentRegistry) -> Result<ImportReport, EngineError> {
+    let value: Value = serde_json::from_str(json)?;
+    from_value(&value, content)
+}
+
+/// Convert an already parsed third-party character export
+pub fn from_value(value: &Value, content: &ContentRegistry) -> Result<ImportReport, EngineError> {
+    let root = unwrap_character(value).ok_or_else(|| {
+        EngineError::InvalidCharacterState(String::from("Character export must be a JSON object"))
+    })?;
+    let mut source = Source::new(root);
+    let mut warnings = Vec::new();
+
+    let name = source
+        .take(&["name", "characterName", "character_name"])
+        .and_then(text)
+        .unwrap_or_else(|| String::from("Imported Character"));
+    let missing = |stat| EngineError::MissingStat {
+        combatant: name.clone(),
+        stat,
+    };
+
+    let class_value = source
+        .take(&["class", "className", "classes"])
+        .ok_or_else(|| missing("class"))?;
+    let class_name = text(class_value).ok_or_else(|| missing("class"))?;
+    let class: Class = parse(&class_name).ok_or_else(|| {
+        EngineError::InvalidCharacterState(format!("Unknown class: {}", class_name))
+    })?;
+
+    let ancestry_name = source
+        .take(&["ancestry", "heritage.ancestry", "race"])
+        .and_then(text)
+        .ok_or_else(|| missing("ancestry"))?;
+    let ancestry: Ancestry = parse(&ancestry_name).ok_or_else(|| {
+        EngineError::InvalidCharacterState(format!("Unknown ancestry: {}", ancestry_name))
+    })?;
+
+    let traits = source
+        .take(&["traits", "attributes", "stats"])
+        .and_then(Value::as_object)
+        .ok_or_else(|| missing("traits"))?;
+    let attributes = read_traits(traits).ok_or_else(|| missing("traits"))?;
+    if attributes.validate().is_err() {
+        warnings.push(String::from(
+            "Traits don't use the standard +2/+1/+1/0/0/-1 spread, kept as exported",
+        ));
+    }
+
+    let level = source
+        .take(&["level"])
+        .and_then(number)
+        .or_else(|| first(class_value).get("level").and_then(number))
+        .unwrap_or(1);
+    let mut builder = CombatantBuilder::player(name.clone(), class, ancestry)
+        .with_level(clamp_u8(level))
+        .with_attributes(attributes);
+
+    let subclass_name = source
+        .take(&["subclass", "subclassName", "class.subclass"])
+        .and_then(text)
+        .or_else(|| first(class_value).get("subclass").and_then(text));
+    if let Some(subclass_name) = subclass_name {
+        match parse::<Subclass>(&subclass_name) {
+            Some(subclass) if subclass.class() == class => {
+                builder = builder.with_subclass(subclass)
+            }
+            Some(_) => warnings.push(format!(
+                "Subclass '{}' isn't a {} subclass, skipped",
+                subclass_name, class
+            )),
+            None => warnings.push(format!("Unknown subclass '{}', skipped", subclass_name)),
+        }
+    }
+
+    if let Some(community_name) = source
+        .take(&["community", "heritage.community"])
+        .and_then(text)
+    {
+        match parse::<Community>(&community_name) {
+            Some(community) => builder = builder.with_community(community),
+            None => warnings.push(format!("Unknown community '{}', skipped", community_name)),
+        }
+    }
+
+    for entry in source.take_all(&["equipment", "inventory", "weapons", "items"]) {
+        match text(entry) {
+            Some(item) => builder = builder.with_equipment(item),
+            None => warnings.push(format!("Unreadable equipment entry {}, skipped", entry)),
+        }
+    }
+
+    for entry in source.take_all(&["experiences"]) {
+        match read_experience(entry) {
+            Some(experience) => builder = builder.with_experience(experience),
+            None => warnings.push(format!("Unreadable experience {}, skipped", entry)),
+        }
+    }
+
+    let mut combatant = builder.build()?;
+
+    if let Some(hp) = source.take(&["hp", "hitPoints", "health"]) {
+        let maximum = number(hp).or_else(|| field_number(hp, &["max", "maximum", "total"]));
+        if let Some(maximum) = maximum {
+            combatant.hp.maximum = clamp_u8(maximum);
+            combatant.hp.current = combatant.hp.maximum;
+        }
+        if let Some(current) = field_number(hp, &["current", "value"]) {
+            combatant.hp.current = clamp_u8(current).min(combatant.hp.maximum);
+        } else if let Some(marked) = field_number(hp, &["marked", "damage"]) {
+            combatant.hp.current = combatant.hp.maximum.saturating_sub(clamp_u8(marked));
+        }
+    }
+    if let Some(stress) = source.take(&["stress"]) {
+        if let Some(marked) =
+            number(stress).or_else(|| field_number(stress, &["marked", "current", "value"]))
+        {
+            combatant.stress.current = clamp_u8(marked);
+        }
+    }
+    if let Some(evasion) = source
+        .take(&["evasion"])
+        .and_then(|v| number(v).or_else(|| field_number(v, &["value"])))
+    {
+        combatant.evasion = clamp_u8(evasion);
+    }
+    if let Some(armor) = source
+        .take(&["armor", "armorScore", "armor_score"])
+        .and_then(|v| number(v).or_else(|| field_number(v, &["score", "value", "max"])))
+    {
+        combatant.armor = clamp_u8(armor);
+    }
+
+    let mut sheet = CharacterSheet::new(combatant);
+    if let Some(xp) = source.take(&["xp", "experiencePoints"]).and_then(number) {
+        sheet.progress.experience = xp.max(0) as u32;
+    }
+
+    for entry in source.take_all(&["domainCards", "cards", "loadout", "vault"]) {
+        let Some(card_name) = text(entry) else {
+            warnings.push(format!("Unreadable card entry {}, skipped", entry));
+            continue;
+        };
+        let card = content.card(&card_name).or_else(|| {
+            content
+                .cards
+                .iter()
+                .find(|card| card.name.eq_ignore_ascii_case(&card_name))
+        });
+        match card {
+            Some(card) if !sheet.progress.has_card(&card.id) => sheet.progress.add_card(&card.id),
+            Some(_) => {}
+            None => warnings.push(format!("Unknown card '{}', skipped", card_name)),
+        }
+    }
+
+    warnings.extend(
+        source
+            .unused()
+            .map(|key| format!("Ignored field '{}'", key)),
+    );
+
+    Ok(ImportReport { sheet, warnings })
+}
+
+/// The character object, looking inside common wrappers
+fn unwrap_character(value: &Value) -> Option<&Map<String, Value>> {
+    let object = value.as_object()?;
+    if object.len() == 1 {
+        for wrapper in ["character", "data"] {
+            if let Some(inner) = object.get(wrapper).and_then(Value::as_object) {
+                return Some(inner);
+            }
+        }
+    }
+    Some(object)
+}
+
+/// Top-level fields, remembering which ones were read
+struct Source<'a> {
+    root: &'a Map<String, Value>,
+    used: BTreeSet<&'a str>,
+}
+
+impl<'a> Source<'a> {
+    fn new(root: &'a Map<String, Value>) -> Self {
+        Self {
+            root,
+            used: BTreeSet::new(),
+        }
+    }
+
+    /// The first of `paths` that's present, such as `heritage.ancestry`
+    fn take(&mut self, paths: &[&str]) -> Option<&'a Value> {
+        paths.iter().find_map(|path| {
+            let mut segments = path.split('.');
+            let (key, mut value) = get(self.root, segments.next()?)?;
+            for segment in segments {
+                value = get(value.as_object()?, segment)?.1;
+            }
+            self.used.insert(key);
+            Some(value)
+        })
+    }
+
+    /// Entries of every list present under `keys`, combined
+    fn take_all(&mut self, keys: &[&str]) -> Vec<&'a Value> {
+        let mut entries = Vec::new();
+        for key in keys {
+            if let Some(list) = self.take(&[key]).and_then(Value::as_array) {
+                entries.extend(list);
+            }
+        }
+        entries
+    }
+
+    /// Fields nothing read
+    fn unused(&self) -> impl Iterator<Item = &'a str> + '_ {
+        self.root
+            .keys()
+            .map(String::as_str)
+            .filter(|key| !self.used.contains(key))
+    }
+}
+
+/// Look up a key case-insensitively, returning the key as written
+fn get<'a>(object: &'a Map<String, Value>, key: &str) -> Option<(&'a str, &'a Value)> {
+    object
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(key))
+        .map(|(k, v)| (k.as_str(), v))
+}
+
+/// The first element of a list, or the value itself
+fn first(value: &Value) -> &Value {
+    match value {
+        Value::Array(list) => list.first().unwrap_or(&Value::Null),
+        other => other,
+    }
+}
+
+/// A string, or the `name` / `id` of an object, or of a list's first entry
+fn text(value: &Value) -> Option<String> {
+    match first(value) {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Object(object) => ["name", "title", "id"]
+            .iter()
+            .find_map(|key| get(object, key).and_then(|(_, v)| v.as_str()))
+            .map(|s| s.trim().to_string()),
+        _ => None,
+    }
+}
+
+/// A number, or a numeric string like "+2"
+fn number(value: &Value) -> Option<i64> {
+    match value {
+        Value::Number(n) => n.as_i64().or_else(|| n.as_f64().map(|f| f as i64)),
+        Value::String(s) => s.trim().trim_start_matches('+').parse().ok(),
+        _ => None,
+    }
+}
+
+/// A number under one of `keys` of an object
+fn field_number(value: &Value, keys: &[&str]) -> Option<i64> {
+    let object = value.as_object()?;
+    keys.iter()
+        .find_map(|key| get(object, key).and_then(|(_, v)| number(v)))
+}
+
+fn clamp_u8(value: i64) -> u8 {
+    value.clamp(0, u8::MAX as i64) as u8
+}
+
+/// Match a display name, ignoring case, spaces and punctuation
+fn parse<T: IntoEnumIterator + ToString>(name: &str) -> Option<T> {
+    let wanted = normalize(name);
+    T::iter().find(|option| normalize(&option.to_string()) == wanted)
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn read_traits(traits: &Map<String, Value>) -> Option<Attributes> {
+    let read = |name: &str| {
+        let (_, value) = get(traits, name)?;
+        let modifier =
+            number(value).or_else(|| field_number(value, &["value", "modifier", "score"]))?;
+        i8::try_from(modifier).ok()
+    };
+    Some(Attributes {
+        agility: read("agility")?,
+        strength: read("strength")?,
+        finesse: read("finesse")?,
+        instinct: read("instinct")?,
+        presence: read("presence")?,
+        knowledge: read("knowledge")?,
+    })
+}
+
+fn read_experience(value: &Value) -> Option<Experience> {
+    let name = text(value)?;
+    let bonus = field_number(value, &["bonus", "modifier", "value"])
+        .and_then(|b| i8::try_from(b).ok())
+        .unwrap_or(DEFAULT_EXPERIENCE_BONUS);
+    Some(Experience::new(name, bonus))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn import(value: Value) -> Result<ImportReport, EngineError> {
+        from_value(&value, &ContentRegistry::builtin())
+    }
+
+    fn create_test_export() -> Value {
+        json!({
+            "character": {
+                "characterName": "Lyra",
+                "classes": [{ "name": "bard", "subclass": "Troubadour", "level": 3 }],
+                "ancestry": { "name": "Human" },
+                "community": "Loreborne",
+                "level": 3,
+                "Traits": {
+                    "Agility": { "value": 0 },
+                    "Strength": { "value": -1 },
+                    "Finesse": { "value": 1 },
+                    "Instinct": { "value": 0 },
+                    "Presence": { "value": "+2" },
+                    "Knowledge": { "value": 1 }
+                },
+                "hitPoints": { "max": 7, "marked": 2 },
+                "stress": { "marked": 1 },
+                "evasion": 12,
+                "armorScore": 3,
+                "loadout": [{ "name": "Inspirational Words" }],
+                "vault": ["book_of_illiat"],
+                "inventory": [{ "name": "Rapier" }, "Lute"],
+                "experiences": [{ "name": "Traveling Performer", "modifier": 2 }, "Gossip"],
+                "xp": 40,
+                "portrait": "lyra.png"
+            }
+        })
+    }
+
+    #[test]
+    fn test_maps_common_fields() {
+        let report = import(create_test_export()).unwrap();
+        let lyra = &report.sheet.combatant;
+
+        assert_eq!(lyra.name, "Lyra");
+        assert_eq!(lyra.class(), Some(Class::Bard));
+        assert_eq!(lyra.subclass(), Some(Subclass::Troubadour));
+        assert_eq!(lyra.community(), Some(Community::Loreborne));
+        assert_eq!(lyra.level, 3);
+        assert_eq!(lyra.attributes().unwrap().presence, 2);
+        assert_eq!((lyra.hp.current, lyra.hp.maximum), (5, 7));
+        assert_eq!(lyra.stress.current, 1);
+        assert_eq!((lyra.evasion, lyra.armor), (12, 3));
+        assert_eq!(lyra.equipment, vec!["Rapier", "Lute"]);
+        assert_eq!(lyra.experiences[1], Experience::new("Gossip", 2));
+        assert_eq!(
+            report.sheet.progress.available_cards,
+            vec!["inspirational_words", "book_of_illiat"]
+        );
+        assert_eq!(report.sheet.progress.experience, 40);
+        assert_eq!(report.warnings, vec!["Ignored field 'portrait'"]);
+    }
+
+    #[test]
+    fn test_skips_unknown_values_with_warnings() {
+        let mut export = create_test_export();
+        export["character"]["community"] = json!("Skyborne");
+        export["character"]["classes"][0]["subclass"] = json!("Stalwart");
+
+        let report = import(export).unwrap();
+        assert_eq!(report.sheet.combatant.subclass(), None);
+        assert_eq!(report.sheet.combatant.community(), None);
+        assert!(report.warnings.contains(&String::from(
+            "Subclass 'Stalwart' isn't a Bard subclass, skipped"
+        )));
+        assert!(report
+            .warnings
+            .contains(&String::from("Unknown community 'Skyborne', skipped")));
+    }
+
+    #[test]
+    fn test_required_fields() {
+        let mut export = create_test_export();
+        export["character"]
+            .as_object_mut()
+            .unwrap()
+            .remove("Traits");
+        assert!(matches!(
+            import(export),
+            Err(EngineError::MissingStat { stat: "traits", .. })
+        ));
+
+        let mut export = create_test_export();
+        export["character"]["classes"] = json!("Paladin");
+        assert_eq!(
+            import(export).unwrap_err().to_string(),
+            "Invalid character state: Unknown class: Paladin"
+        );
+
+        assert!(import(json!([1, 2])).is_err());
+    }
+}
@@ -0,0 +1,387 @@
+//! Roll macros for virtual tabletops
+//!
+//! Weapons, attacks and cards become a [`RollTemplate`]: a title, labelled
+//! dice formulas and text fields. The template serializes as plain JSON for
+//! tools that build their own roll cards, and [`RollTemplate::to_roll20`]
+//! turns it into a Roll20 macro using the default roll template.
+//!
+//! Formulas use the dice notation Roll20, Foundry and most dice bots share,
+//! with each duality die labelled: `1d12[Hope]+1d12[Fear]+2`.
+
+use crate::cards::{ActionCost, CardEffect, DomainCard, Duration, Range};
+#[cfg(feature = "content")]
+use crate::character::CharacterSheet;
+use crate::combat::attack::Attack;
+use crate::combat::damage::{DamageOverTime, TickTiming};
+#[cfg(feature = "content")]
+use crate::content::ContentRegistry;
+use crate::core::dice::DamageDice;
+#[cfg(feature = "content")]
+use crate::error::EngineError;
+use crate::items::Weapon;
+use serde::{Deserialize, Serialize};
+
+/// A titled set of rolls and notes, ready for any VTT's chat
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RollTemplate {
+    pub name: String,
+    #[serde(default)]
+    pub rolls: Vec<TemplateRoll>,
+    #[serde(default)]
+    pub fields: Vec<TemplateField>,
+}
+
+/// A labelled dice formula, such as `Damage: 1d10+3`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateRoll {
+    pub label: String,
+    pub formula: String,
+}
+
+/// A labelled line of text, such as `Range: Far`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TemplateField {
+    pub label: String,
+    pub value: String,
+}
+
+impl RollTemplate {
+    /// Create an empty template
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            rolls: Vec::new(),
+            fields: Vec::new(),
+        }
+    }
+
+    /// Add a dice roll
+    pub fn with_roll(mut self, label: impl Into<String>, formula: impl Into<String>) -> Self {
+        self.rolls.push(TemplateRoll {
+            label: label.into(),
+            formula: formula.into(),
+        });
+        self
+    }
+
+    /// Add a line of text
+    pub fn with_field(mut self, label: impl Into<String>, value: impl Into<String>) -> Self {
+        self.fields.push(TemplateField {
+            label: label.into(),
+            value: value.into(),
+        });
+        self
+    }
+
+    /// An attack roll and the damage it deals on a hit
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::Attack;
+    /// use daggerheart_engine::core::dice::DamageDice;
+    /// use daggerheart_engine::interop::macros::RollTemplate;
+    ///
+    /// let template = RollTemplate::for_attack("Claws", &Attack::new(1), &DamageDice::d6(2));
+    /// assert_eq!(
+    ///     template.to_roll20(),
+    ///     "&{template:default} {{name=Claws}} {{Attack=[[1d12[Hope]+1d12[Fear]+1]]}} {{Damage=[[2d6]]}}"
+    /// );
+    /// ```
+    pub fn for_attack(name: impl Into<String>, attack: &Attack, damage: &DamageDice) -> Self {
+        Self::new(name)
+            .with_roll("Attack", duality_formula(attack))
+            .with_roll("Damage", damage.to_string())
+    }
+
+    /// A weapon's attack at a modifier and Proficiency
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::Attack;
+    /// use daggerheart_engine::interop::macros::RollTemplate;
+    /// use daggerheart_engine::items::Weapon;
+    ///
+    /// let shortbow = Weapon::named("Shortbow").unwrap();
+    /// let template = RollTemplate::for_weapon(&shortbow, &Attack::new(2), 2);
+    ///
+    /// assert_eq!(template.rolls[1].formula, "2d6+3");
+    /// assert_eq!(template.fields[0].value, "Agility");
+    /// ```
+    pub fn for_weapon(weapon: &Weapon, attack: &Attack, proficiency: u8) -> Self {
+        Self::for_attack(weapon.name.clone(), attack, &weapon.damage(proficiency))
+            .with_field("Trait", weapon.attack_trait.to_string())
+            .with_field("Range", range_name(weapon.range))
+            .with_field(
+                "Damage type",
+                if weapon.magic { "Magic" } else { "Physical" },
+            )
+    }
+
+    /// A domain card's text
+    ///
+    /// Add the rolls of what the card does with [`RollTemplate::with_effect`].
+    pub fn for_card(card: &DomainCard) -> Self {
+        let cost = match card.action_cost {
+            ActionCost::Major => "Major action",
+            ActionCost::Minor => "Minor action",
+            ActionCost::Reaction => "Reaction",
+            ActionCost::Free => "Free action",
+        };
+        Self::new(card.name.clone())
+            .with_field("Domain", card.domain.to_string())
+            .with_field("Level", card.level_requirement.to_string())
+            .with_field("Cost", cost)
+            .with_field("Effect", card.description.clone())
+    }
+
+    /// Add the rolls or text of a card effect
+    ///
+    /// Attacks roll duality dice with `attack`; spells should be given the
+    /// caster's Spellcast modifier.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::cards::{CardEffect, Range, Target};
+    /// use daggerheart_engine::combat::Attack;
+    /// use daggerheart_engine::core::dice::DamageDice;
+    /// use daggerheart_engine::interop::macros::RollTemplate;
+    ///
+    /// let bolt = CardEffect::spell(DamageDice::d8(1), Range::Far, Target::Enemy);
+    /// let template = RollTemplate::new("Arcane Bolt").with_effect(&bolt, &Attack::new(2));
+    ///
+    /// assert_eq!(template.rolls[0].label, "Spellcast");
+    /// assert_eq!(template.rolls[1].formula, "1d8");
+    /// ```
+    pub fn with_effect(self, effect: &CardEffect, attack: &Attack) -> Self {
+        match effect {
+            CardEffect::Attack {
+                damage,
+                range,
+                spell,
+                ..
+            } => self
+                .with_roll(
+                    if *spell { "Spellcast" } else { "Attack" },
+                    duality_formula(attack),
+                )
+                .with_roll("Damage", damage.to_string())
+                .with_field("Range", range_name(*range)),
+            CardEffect::Heal { amount: 0, .. } => self.with_field("Heal", "All HP"),
+            CardEffect::Heal { amount, .. } => self.with_field("Heal", format!("{} HP", amount)),
+            CardEffect::Modifier {
+                bonus,
+                duration,
+                applies_to,
+                ..
+            } => self.with_field(
+                "Modifier",
+                format!("{:+} {} ({})", bonus, applies_to, duration_name(*duration)),
+            ),
+            CardEffect::ClearStress { .. } => self.with_field("Clear", "All Stress"),
+            CardEffect::Move { distance, .. } => self.with_field("Move", range_name(*distance)),
+            CardEffect::DamageOverTime { effect, .. } => self
+                .with_roll(&effect.name, effect.dice.to_string())
+                .with_field("Ongoing", ongoing_text(effect)),
+            CardEffect::Convert { from, to, rate } => self.with_field(
+                "Convert",
+                format!("{} {} for {} {}", rate.pay, from, rate.gain, to),
+            ),
+            CardEffect::Flight { .. } => self.with_field("Flight", "Until landing"),
+            CardEffect::Special { description } => self.with_field("Special", description.clone()),
+        }
+    }
+
+    /// This template as a Roll20 macro using the default roll template
+    ///
+    /// Braces and line breaks in names and text are replaced, since Roll20
+    /// would read them as the end of a field.
+    pub fn to_roll20(&self) -> String {
+        let mut parts = vec![
+            String::from("&{template:default}"),
+            format!("{{{{name={}}}}}", escape(&self.name)),
+        ];
+        for roll in &self.rolls {
+            parts.push(format!(
+                "{{{{{}=[[{}]]}}}}",
+                escape(&roll.label),
+                roll.formula
+            ));
+        }
+        for field in &self.fields {
+            parts.push(format!(
+                "{{{{{}={}}}}}",
+                escape(&field.label),
+                escape(&field.value)
+            ));
+        }
+        parts.join(" ")
+    }
+}
+
+/// A template for each of a character's weapons and domain cards
+///
+/// Weapons are the built-in ones among the character's equipment; cards
+/// are looked up in `content`, and ones it doesn't have are skipped.
+#[cfg(feature = "content")]
+pub fn character_templates(
+    sheet: &CharacterSheet,
+    content: &ContentRegistry,
+) -> Result<Vec<RollTemplate>, EngineError> {
+    let combatant = &sheet.combatant;
+    let mut templates = Vec::new();
+    for weapon in combatant
+        .equipment
+        .iter()
+        .filter_map(|item| Weapon::named(item))
+    {
+        let attack = Attack::from_character(combatant, &weapon)?;
+        templates.push(RollTemplate::for_weapon(
+            &weapon,
+            &attack,
+            combatant.proficiency(),
+        ));
+    }
+    templates.extend(
+        sheet
+            .progress
+            .available_cards
+            .iter()
+            .filter_map(|id| content.card(id))
+            .map(RollTemplate::for_card),
+    );
+    Ok(templates)
+}
+
+/// Duality dice plus the attack's modifier and advantage die
+fn duality_formula(attack: &Attack) -> String {
+    let mut formula = String::from("1d12[Hope]+1d12[Fear]");
+    if attack.with_advantage {
+        formula.push_str("+1d6[Advantage]");
+    }
+    if attack.modifier != 0 {
+        formula.push_str(&format!("{:+}", attack.modifier));
+    }
+    formula
+}
+
+fn range_name(range: Range) -> &'static str {
+    match range {
+        Range::VeryClose => "Very Close",
+        Range::Close => "Close",
+        Range::Far => "Far",
+        Range::Any => "Any",
+    }
+}
+
+fn duration_name(duration: Duration) -> String {
+    match duration {
+        Duration::EndOfTurn => String::from("until end of turn"),
+        Duration::EndOfNextTurn => String::from("until end of next turn"),
+        Duration::Rounds(1) => String::from("1 round"),
+        Duration::Rounds(rounds) => format!("{} rounds", rounds),
+        Duration::Permanent => String::from("until removed"),
+        Duration::Instant => String::from("instant"),
+    }
+}
+
+fn ongoing_text(ongoing: &DamageOverTime) -> String {
+    let timing = match ongoing.timing {
+        TickTiming::StartOfTurn => "start",
+        TickTiming::EndOfTurn => "end",
+    };
+    let lasting = match ongoing.remaining {
+        Some(1) => String::from("once"),
+        Some(ticks) => format!("{} times", ticks),
+        None => String::from("until removed"),
+    };
+    format!(
+        "{} damage at the {} of each turn, {}",
+        ongoing.damage_type, timing, lasting
+    )
+}
+
+/// Text Roll20 can show inside a `{{field=value}}`
+fn escape(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '{' => '(',
+            '}' => ')',
+            '\n' | '\r' => ' ',
+            c => c,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::Target;
+    use crate::character::Domain;
+
+    #[test]
+    fn test_card_macro() {
+        let card = DomainCard::new(
+            "whirlwind",
+            "Whirlwind",
+            Domain::Blade,
+            1,
+            "Spin and strike {everyone} nearby.\nMark a Stress.",
+            ActionCost::Major,
+        );
+        let effect = CardEffect::attack(DamageDice::d8(2), Range::VeryClose, Target::AllEnemies);
+        let template = RollTemplate::for_card(&card).with_effect(&effect, &Attack::new(-1));
+
+        assert_eq!(
+            template.to_roll20(),
+            "&{template:default} {{name=Whirlwind}} \
+             {{Attack=[[1d12[Hope]+1d12[Fear]-1]]}} {{Damage=[[2d8]]}} \
+             {{Domain=Blade}} {{Level=1}} {{Cost=Major action}} \
+             {{Effect=Spin and strike (everyone) nearby. Mark a Stress.}} \
+             {{Range=Very Close}}"
+        );
+    }
+
+    #[test]
+    fn test_template_json_round_trips() {
+        let template = RollTemplate::for_attack(
+            "Shortsword",
+            &Attack::new(2).with_advantage(),
+            &DamageDice::d8(1).with_bonus(1),
+        );
+        assert_eq!(
+            template.rolls[0].formula,
+            "1d12[Hope]+1d12[Fear]+1d6[Advantage]+2"
+        );
+
+        let json = serde_json::to_string(&template).unwrap();
+        assert_eq!(
+            serde_json::from_str::<RollTemplate>(&json).unwrap(),
+            template
+        );
+    }
+
+    #[cfg(feature = "content")]
+    #[test]
+    fn test_character_templates() {
+        use crate::character::{Ancestry, Class};
+        use crate::test_support::player;
+
+        let mut sheet = CharacterSheet::new(
+            player("Grom", Class::Warrior, Ancestry::Orc)
+                .with_equipment("Longsword")
+                .with_equipment("Rope")
+                .build()
+                .unwrap(),
+        );
+        sheet.progress.add_card("get_back_up");
+        sheet.progress.add_card("not_a_card");
+
+        let templates = character_templates(&sheet, &ContentRegistry::builtin()).unwrap();
+        let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, vec!["Longsword", "Get Back Up"]);
+        assert_eq!(templates[0].rolls[0].formula, "1d12[Hope]+1d12[Fear]+2");
+    }
+}
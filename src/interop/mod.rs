@@ -0,0 +1,14 @@
+//! Interoperability with other tools
+//!
+//! Converters between engine types and the formats used by virtual
+//! tabletops, so characters can move between this engine and online play.
+//! [`import`] reads character exports from other builders, and [`macros`]
+//! writes rolls and cards as VTT macros.
+
+pub mod foundry;
+#[cfg(feature = "content")]
+pub mod import;
+pub mod macros;
+
+pub use foundry::{export_character, import_character, FoundryActor};
+pub use macros::RollTemplate;
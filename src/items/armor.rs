@@ -0,0 +1,293 @@
+//! Armor and armor features
+//!
+//! A suit of armor sets its wearer's Armor Score. Its features, such as
+//! Flexible or Heavy, are lists of [`ArmorEffect`]s: some change the
+//! wearer while it's worn, like Evasion or a bonus to rolls, and some
+//! change how a hit is measured against the damage threshold.
+
+use crate::combat::damage::{DamageResult, DAMAGE_THRESHOLD};
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// One way an armor feature changes the numbers
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum ArmorEffect {
+    /// Added to the wearer's Evasion
+    Evasion(i8),
+    /// Added to the damage threshold hits are measured against
+    Threshold(i8),
+    /// Hit Points a hit costs are cut by this much, but never below 1
+    ReduceSeverity(u8),
+    /// A modifier while it's worn, e.g. +1 to `Spellcast rolls`
+    RollBonus { bonus: i8, applies_to: String },
+}
+
+/// A named armor feature
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ArmorFeature {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub effects: Vec<ArmorEffect>,
+}
+
+impl ArmorFeature {
+    /// A feature with no effects yet
+    pub fn new(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            effects: Vec::new(),
+        }
+    }
+
+    /// Add an effect
+    pub fn with_effect(mut self, effect: ArmorEffect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+
+    /// +1 to Evasion
+    pub fn flexible() -> Self {
+        Self::new("Flexible", "+1 to Evasion").with_effect(ArmorEffect::Evasion(1))
+    }
+
+    /// -1 to Evasion
+    pub fn heavy() -> Self {
+        Self::new("Heavy", "-1 to Evasion").with_effect(ArmorEffect::Evasion(-1))
+    }
+
+    /// -2 to Evasion
+    pub fn very_heavy() -> Self {
+        Self::new("Very Heavy", "-2 to Evasion").with_effect(ArmorEffect::Evasion(-2))
+    }
+
+    /// +2 to the damage threshold
+    pub fn reinforced() -> Self {
+        Self::new("Reinforced", "+2 to your damage threshold")
+            .with_effect(ArmorEffect::Threshold(2))
+    }
+
+    /// Heavy hits cost one Hit Point less
+    pub fn resilient() -> Self {
+        Self::new(
+            "Resilient",
+            "A hit that would cost 2 or more Hit Points costs one less",
+        )
+        .with_effect(ArmorEffect::ReduceSeverity(1))
+    }
+
+    /// +1 to Spellcast rolls
+    pub fn channeling() -> Self {
+        Self::new("Channeling", "+1 to Spellcast rolls").with_effect(ArmorEffect::RollBonus {
+            bonus: 1,
+            applies_to: String::from("Spellcast rolls"),
+        })
+    }
+
+    /// The features the built-in armor uses, and a few for homebrew
+    pub fn builtin() -> Vec<ArmorFeature> {
+        vec![
+            Self::flexible(),
+            Self::heavy(),
+            Self::very_heavy(),
+            Self::reinforced(),
+            Self::resilient(),
+            Self::channeling(),
+        ]
+    }
+
+    /// Look up a built-in feature by name, ignoring case
+    pub fn named(name: &str) -> Option<ArmorFeature> {
+        Self::builtin()
+            .into_iter()
+            .find(|feature| feature.name.eq_ignore_ascii_case(name))
+    }
+}
+
+/// A suit of armor
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::items::{Armor, ArmorFeature};
+///
+/// let chainmail = Armor::named("Chainmail Armor").unwrap();
+/// assert_eq!(chainmail.score, 4);
+/// assert_eq!(chainmail.evasion_modifier(), -1);
+///
+/// // Homebrew armor mixes features freely
+/// let mithral = Armor::new("Mithral Shirt", 3)
+///     .with_feature(ArmorFeature::flexible())
+///     .with_feature(ArmorFeature::reinforced());
+/// assert_eq!(mithral.damage(12).hp_lost, 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Armor {
+    pub name: String,
+    /// The wearer's Armor Score while it's worn
+    pub score: u8,
+    #[serde(default)]
+    pub features: Vec<ArmorFeature>,
+}
+
+impl Armor {
+    /// Armor with no features
+    pub fn new(name: impl Into<String>, score: u8) -> Self {
+        Self {
+            name: name.into(),
+            score,
+            features: Vec::new(),
+        }
+    }
+
+    /// Give it a feature
+    pub fn with_feature(mut self, feature: ArmorFeature) -> Self {
+        self.features.push(feature);
+        self
+    }
+
+    /// Every effect of its features
+    pub fn effects(&self) -> impl Iterator<Item = &ArmorEffect> {
+        self.features
+            .iter()
+            .flat_map(|feature| feature.effects.iter())
+    }
+
+    /// What its features add to the wearer's Evasion
+    pub fn evasion_modifier(&self) -> i8 {
+        self.effects().fold(0i8, |total, effect| match effect {
+            ArmorEffect::Evasion(bonus) => total.saturating_add(*bonus),
+            _ => total,
+        })
+    }
+
+    /// The damage threshold hits against its wearer are measured with
+    pub fn threshold(&self) -> u16 {
+        let shift = self.effects().fold(0i16, |total, effect| match effect {
+            ArmorEffect::Threshold(bonus) => total.saturating_add(*bonus as i16),
+            _ => total,
+        });
+        DAMAGE_THRESHOLD.saturating_add_signed(shift)
+    }
+
+    /// How much its features cut the Hit Points a hit costs
+    pub fn severity_reduction(&self) -> u8 {
+        self.effects().fold(0u8, |total, effect| match effect {
+            ArmorEffect::ReduceSeverity(amount) => total.saturating_add(*amount),
+            _ => total,
+        })
+    }
+
+    /// Modifiers it grants while worn, as `(bonus, applies_to)`
+    pub fn roll_bonuses(&self) -> impl Iterator<Item = (i8, &str)> {
+        self.effects().filter_map(|effect| match effect {
+            ArmorEffect::RollBonus { bonus, applies_to } => Some((*bonus, applies_to.as_str())),
+            _ => None,
+        })
+    }
+
+    /// What `raw_damage` does to its wearer
+    pub fn damage(&self, raw_damage: u16) -> DamageResult {
+        self.damage_with_score(raw_damage, self.score)
+    }
+
+    /// What `raw_damage` does to a wearer whose Armor Score is `score`,
+    /// e.g. after other bonuses
+    pub fn damage_with_score(&self, raw_damage: u16, score: u8) -> DamageResult {
+        self.damage_with_threshold(raw_damage, score, self.threshold())
+    }
+
+    /// What `raw_damage` does to a wearer whose Armor Score is `score`,
+    /// measured against `threshold` instead of the armor's own
+    pub fn damage_with_threshold(
+        &self,
+        raw_damage: u16,
+        score: u8,
+        threshold: u16,
+    ) -> DamageResult {
+        let mut result = DamageResult::calculate_with_threshold(raw_damage, score, threshold);
+        if result.hp_lost > 1 {
+            result.hp_lost = result
+                .hp_lost
+                .saturating_sub(self.severity_reduction())
+                .max(1);
+        }
+        result
+    }
+
+    /// The tier 1 armor the classes start with
+    pub fn builtin() -> Vec<Armor> {
+        vec![
+            Armor::new("Gambeson Armor", 3).with_feature(ArmorFeature::flexible()),
+            Armor::new("Leather Armor", 3),
+            Armor::new("Chainmail Armor", 4).with_feature(ArmorFeature::heavy()),
+            Armor::new("Full Plate Armor", 4).with_feature(ArmorFeature::very_heavy()),
+        ]
+    }
+
+    /// Look up built-in armor by name, ignoring case
+    pub fn named(name: &str) -> Option<Armor> {
+        Self::builtin()
+            .into_iter()
+            .find(|armor| armor.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::Class;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_features_change_damage_resolution() {
+        let plain = Armor::new("Plain", 2);
+        let tough = Armor::new("Tough", 2)
+            .with_feature(ArmorFeature::reinforced())
+            .with_feature(ArmorFeature::resilient());
+
+        // 6 after armor: a hit through plain armor, a scratch past Reinforced
+        assert_eq!(plain.damage(8).hp_lost, 1);
+        assert_eq!(tough.damage(8).hp_lost, 0);
+        assert_eq!(tough.damage(8).stress_gained, 1);
+
+        // 14 after armor: 2 HP plain, 2 past the raised threshold then 1
+        // after Resilient
+        assert_eq!(plain.damage(16).hp_lost, 2);
+        assert_eq!(tough.damage(16).hp_lost, 1);
+        // 18 after armor: 3 HP either way, cut to 2 by Resilient
+        assert_eq!(plain.damage(20).hp_lost, 3);
+        assert_eq!(tough.damage(20).hp_lost, 2);
+        // Resilient never makes a hit free
+        assert_eq!(tough.damage(9).hp_lost, 1);
+    }
+
+    #[test]
+    fn test_suggested_armor_is_builtin() {
+        for class in Class::iter() {
+            assert!(
+                class
+                    .suggested_equipment()
+                    .iter()
+                    .any(|item| Armor::named(item).is_some()),
+                "{} starts without armor",
+                class
+            );
+        }
+        let channeling = ArmorFeature::named("channeling").unwrap();
+        assert_eq!(
+            Armor::new("Robes", 1)
+                .with_feature(channeling)
+                .roll_bonuses()
+                .collect::<Vec<_>>(),
+            [(1, "Spellcast rolls")]
+        );
+    }
+}
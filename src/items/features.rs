@@ -0,0 +1,157 @@
+//! Weapon features - Reliable, Powerful, Brutal and the rest
+//!
+//! A feature is a name and a list of [`FeatureEffect`]s, each a small
+//! change to the attack or damage roll or to the wielder's defenses. The
+//! SRD features are built from these, and a homebrew weapon can list any
+//! mix of them without new code.
+
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// One way a feature changes the numbers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum FeatureEffect {
+    /// Added to attack rolls with the weapon
+    AttackBonus(i8),
+    /// Added to the weapon's damage
+    DamageBonus(i16),
+    /// Roll this many more damage dice and drop the lowest results
+    DropLowest(u8),
+    /// A damage die that rolls its maximum adds another die
+    Exploding,
+    /// Added to the wielder's Evasion
+    Evasion(i8),
+    /// Added to the wielder's Armor Score
+    Armor(i8),
+}
+
+/// A named weapon feature
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::items::{FeatureEffect, WeaponFeature};
+///
+/// let reliable = WeaponFeature::named("reliable").unwrap();
+/// assert_eq!(reliable.effects, vec![FeatureEffect::AttackBonus(1)]);
+///
+/// // Homebrew features are just a different mix of effects
+/// let keen = WeaponFeature::new("Keen", "+2 damage, and max rolls explode")
+///     .with_effect(FeatureEffect::DamageBonus(2))
+///     .with_effect(FeatureEffect::Exploding);
+/// assert_eq!(keen.effects.len(), 2);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct WeaponFeature {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    #[serde(default)]
+    pub effects: Vec<FeatureEffect>,
+}
+
+impl WeaponFeature {
+    /// A feature with no effects yet
+    pub fn new(name: impl Into<String>, description: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: description.into(),
+            effects: Vec::new(),
+        }
+    }
+
+    /// Add an effect
+    pub fn with_effect(mut self, effect: FeatureEffect) -> Self {
+        self.effects.push(effect);
+        self
+    }
+
+    /// +1 to attack rolls
+    pub fn reliable() -> Self {
+        Self::new("Reliable", "+1 to attack rolls").with_effect(FeatureEffect::AttackBonus(1))
+    }
+
+    /// Roll an extra damage die and drop the lowest
+    pub fn powerful() -> Self {
+        Self::new(
+            "Powerful",
+            "On a successful attack, roll an additional damage die and discard the lowest result",
+        )
+        .with_effect(FeatureEffect::DropLowest(1))
+    }
+
+    /// Damage dice that roll their maximum roll again
+    pub fn brutal() -> Self {
+        Self::new(
+            "Brutal",
+            "When you roll the maximum value on a damage die, roll an additional damage die",
+        )
+        .with_effect(FeatureEffect::Exploding)
+    }
+
+    /// -1 to Evasion
+    pub fn heavy() -> Self {
+        Self::new("Heavy", "-1 to Evasion").with_effect(FeatureEffect::Evasion(-1))
+    }
+
+    /// -1 to Evasion, and an extra damage die with the lowest dropped
+    pub fn massive() -> Self {
+        Self::new(
+            "Massive",
+            "-1 to Evasion; on a successful attack, roll an additional damage die and discard the lowest result",
+        )
+        .with_effect(FeatureEffect::Evasion(-1))
+        .with_effect(FeatureEffect::DropLowest(1))
+    }
+
+    /// +1 to Armor Score
+    pub fn protective() -> Self {
+        Self::new("Protective", "+1 to Armor Score").with_effect(FeatureEffect::Armor(1))
+    }
+
+    /// +2 to Armor Score, -1 to Evasion
+    pub fn barrier() -> Self {
+        Self::new("Barrier", "+2 to Armor Score; -1 to Evasion")
+            .with_effect(FeatureEffect::Armor(2))
+            .with_effect(FeatureEffect::Evasion(-1))
+    }
+
+    /// The SRD features the engine knows
+    pub fn builtin() -> Vec<WeaponFeature> {
+        vec![
+            Self::reliable(),
+            Self::powerful(),
+            Self::brutal(),
+            Self::heavy(),
+            Self::massive(),
+            Self::protective(),
+            Self::barrier(),
+        ]
+    }
+
+    /// Look up a built-in feature by name, ignoring case
+    pub fn named(name: &str) -> Option<WeaponFeature> {
+        Self::builtin()
+            .into_iter()
+            .find(|feature| feature.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_names_are_unique() {
+        let features = WeaponFeature::builtin();
+        for feature in &features {
+            assert_eq!(WeaponFeature::named(&feature.name).as_ref(), Some(feature));
+            assert!(!feature.effects.is_empty());
+        }
+        assert!(WeaponFeature::named("Vorpal").is_none());
+    }
+}
@@ -0,0 +1,175 @@
+//! Inventory - what a character carries, and optionally how heavy it is
+//!
+//! Items are tracked by name and quantity. With encumbrance turned on,
+//! each item's weight counts towards a load that's measured against a
+//! capacity set by the character's Strength; a character over capacity
+//! is overloaded.
+
+use crate::error::EngineError;
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Load anyone can carry, before Strength
+pub const BASE_CAPACITY: u16 = 10;
+
+/// Load each point of Strength adds to, or takes from, capacity
+pub const CAPACITY_PER_STRENGTH: u16 = 3;
+
+/// A stack of one kind of item
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct InventoryItem {
+    pub name: String,
+    /// Weight of one of them
+    #[serde(default)]
+    pub weight: u16,
+    pub quantity: u16,
+}
+
+impl InventoryItem {
+    /// Weight of the whole stack
+    pub fn load(&self) -> u16 {
+        self.weight.saturating_mul(self.quantity)
+    }
+}
+
+/// Everything a character carries
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::items::Inventory;
+///
+/// let mut pack = Inventory::default().with_encumbrance(true);
+/// pack.add("Rope", 2, 1);
+/// pack.add("Torch", 1, 3);
+/// assert_eq!(pack.load(), 5);
+///
+/// // Strength -1 carries 7
+/// pack.add("Anvil", 5, 1);
+/// assert!(pack.is_overloaded(-1));
+/// assert!(!pack.is_overloaded(1));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Inventory {
+    #[serde(default)]
+    pub items: Vec<InventoryItem>,
+    /// Whether weight counts; without it nobody is ever overloaded
+    #[serde(default)]
+    pub encumbrance: bool,
+    /// Handfuls of gold, which weigh nothing
+    #[serde(default)]
+    pub gold: u32,
+}
+
+impl Inventory {
+    /// Turn encumbrance on or off
+    pub fn with_encumbrance(mut self, enabled: bool) -> Self {
+        self.encumbrance = enabled;
+        self
+    }
+
+    /// Carry `quantity` more of an item, stacking with any of the same
+    /// name
+    pub fn add(&mut self, name: impl Into<String>, weight: u16, quantity: u16) {
+        let name = name.into();
+        match self.item_mut(&name) {
+            Some(item) => item.quantity = item.quantity.saturating_add(quantity),
+            None => self.items.push(InventoryItem {
+                name,
+                weight,
+                quantity,
+            }),
+        }
+    }
+
+    /// Drop `quantity` of an item, failing if there aren't that many
+    pub fn remove(&mut self, name: &str, quantity: u16) -> Result<(), EngineError> {
+        let carried = self.quantity(name);
+        if carried < quantity {
+            return Err(EngineError::TooFewCarried {
+                item: name.into(),
+                carried,
+                quantity,
+            });
+        }
+        if let Some(item) = self.item_mut(name) {
+            item.quantity -= quantity;
+        }
+        self.items.retain(|item| item.quantity > 0);
+        Ok(())
+    }
+
+    /// How many of an item are carried, by name ignoring case
+    pub fn quantity(&self, name: &str) -> u16 {
+        self.items
+            .iter()
+            .find(|item| item.name.eq_ignore_ascii_case(name))
+            .map_or(0, |item| item.quantity)
+    }
+
+    /// Total weight carried
+    pub fn load(&self) -> u16 {
+        self.items
+            .iter()
+            .fold(0u16, |total, item| total.saturating_add(item.load()))
+    }
+
+    /// Load a character with this Strength modifier can carry
+    pub fn capacity(strength: i8) -> u16 {
+        let shift = strength as i16 * CAPACITY_PER_STRENGTH as i16;
+        BASE_CAPACITY.saturating_add_signed(shift)
+    }
+
+    /// Whether encumbrance is on and the load is over capacity
+    pub fn is_overloaded(&self, strength: i8) -> bool {
+        self.encumbrance && self.load() > Self::capacity(strength)
+    }
+
+    fn item_mut(&mut self, name: &str) -> Option<&mut InventoryItem> {
+        self.items
+            .iter_mut()
+            .find(|item| item.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stacks_and_removal() {
+        let mut pack = Inventory::default();
+        pack.add("Torch", 1, 2);
+        pack.add("torch", 1, 1);
+        assert_eq!(pack.items.len(), 1);
+        assert_eq!(pack.quantity("Torch"), 3);
+
+        assert!(matches!(
+            pack.remove("Torch", 4),
+            Err(EngineError::TooFewCarried {
+                carried: 3,
+                quantity: 4,
+                ..
+            })
+        ));
+        pack.remove("Torch", 3).unwrap();
+        assert!(pack.items.is_empty());
+    }
+
+    #[test]
+    fn test_overloaded_only_with_encumbrance() {
+        assert_eq!(Inventory::capacity(0), BASE_CAPACITY);
+        assert_eq!(Inventory::capacity(2), 16);
+        assert_eq!(Inventory::capacity(-5), 0);
+
+        let mut pack = Inventory::default();
+        pack.add("Full Plate Armor", 12, 1);
+        assert!(!pack.is_overloaded(0));
+        pack.encumbrance = true;
+        assert!(pack.is_overloaded(0));
+        assert!(!pack.is_overloaded(1));
+    }
+}
@@ -0,0 +1,131 @@
+//! Loadouts - the weapons a character has in hand
+//!
+//! A character wields a primary weapon and, if it leaves a hand free, a
+//! secondary one. Two-handed weapons take both hands, so they can't be
+//! wielded alongside a secondary.
+
+use crate::error::EngineError;
+use crate::items::weapons::{Weapon, WeaponSlot};
+use alloc::string::String;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+
+/// Hands a character has to wield weapons with
+pub const HANDS: u8 = 2;
+
+/// The weapons a character is wielding
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::items::{Loadout, Weapon};
+///
+/// let mut loadout = Loadout::default();
+/// loadout.equip(Weapon::named("Broadsword").unwrap()).unwrap();
+/// loadout.equip(Weapon::named("Round Shield").unwrap()).unwrap();
+/// assert_eq!(loadout.free_hands(), 0);
+///
+/// // A two-handed weapon won't fit beside the shield
+/// assert!(loadout.equip(Weapon::named("Longsword").unwrap()).is_err());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Loadout {
+    #[serde(default)]
+    pub primary: Option<Weapon>,
+    #[serde(default)]
+    pub secondary: Option<Weapon>,
+}
+
+impl Loadout {
+    /// Wield a weapon in its slot, returning whatever was there before
+    ///
+    /// Fails, leaving the loadout unchanged, if the weapon needs a hand
+    /// the other slot's weapon is using.
+    pub fn equip(&mut self, weapon: Weapon) -> Result<Option<Weapon>, EngineError> {
+        let other = match weapon.slot {
+            WeaponSlot::Primary => &self.secondary,
+            WeaponSlot::Secondary => &self.primary,
+        };
+        if let Some(other) = other {
+            if weapon.hands() + other.hands() > HANDS {
+                return Err(EngineError::HandsFull {
+                    weapon: weapon.name,
+                    wielding: other.name.clone(),
+                });
+            }
+        }
+        Ok(self.slot_mut(weapon.slot).replace(weapon))
+    }
+
+    /// Put away the weapon in a slot
+    pub fn unequip(&mut self, slot: WeaponSlot) -> Option<Weapon> {
+        self.slot_mut(slot).take()
+    }
+
+    /// Hands not holding a weapon
+    pub fn free_hands(&self) -> u8 {
+        HANDS.saturating_sub(self.wielded().map(Weapon::hands).sum())
+    }
+
+    /// Weapons in hand, primary first
+    pub fn wielded(&self) -> impl Iterator<Item = &Weapon> {
+        self.primary.iter().chain(self.secondary.iter())
+    }
+
+    /// Names of the weapons in hand, primary first
+    pub fn names(&self) -> Vec<String> {
+        self.wielded().map(|weapon| weapon.name.clone()).collect()
+    }
+
+    fn slot_mut(&mut self, slot: WeaponSlot) -> &mut Option<Weapon> {
+        match slot {
+            WeaponSlot::Primary => &mut self.primary,
+            WeaponSlot::Secondary => &mut self.secondary,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weapon(name: &str) -> Weapon {
+        Weapon::named(name).unwrap()
+    }
+
+    #[test]
+    fn test_two_handed_weapons_leave_no_room_for_a_secondary() {
+        let mut loadout = Loadout::default();
+        assert_eq!(loadout.free_hands(), 2);
+        loadout.equip(weapon("Longsword")).unwrap();
+        assert_eq!(loadout.free_hands(), 0);
+
+        let err = loadout.equip(weapon("Small Dagger")).unwrap_err();
+        assert!(matches!(
+            err,
+            EngineError::HandsFull { weapon, wielding }
+                if weapon == "Small Dagger" && wielding == "Longsword"
+        ));
+        assert!(loadout.secondary.is_none());
+    }
+
+    #[test]
+    fn test_dual_wielding_and_swapping() {
+        let mut loadout = Loadout::default();
+        loadout.equip(weapon("Dagger")).unwrap();
+        loadout.equip(weapon("Small Dagger")).unwrap();
+        assert_eq!(loadout.names(), ["Dagger", "Small Dagger"]);
+        assert_eq!(loadout.free_hands(), 0);
+
+        // Swapping one primary for another hands back the old one
+        let old = loadout.equip(weapon("Rapier")).unwrap();
+        assert_eq!(old.unwrap().name, "Dagger");
+
+        // The bow needs the off hand back first
+        assert!(loadout.equip(weapon("Shortbow")).is_err());
+        loadout.unequip(WeaponSlot::Secondary);
+        loadout.equip(weapon("Shortbow")).unwrap();
+        assert_eq!(loadout.free_hands(), 0);
+    }
+}
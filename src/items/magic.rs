@@ -0,0 +1,202 @@
+//! Magic items - attunement and charges
+//!
+//! These are optional rules. A magic item can need attuning before it
+//! works, and a character can only be attuned to so many items at once.
+//! An item with charges spends them as it's used and gets them back on a
+//! rest.
+
+use crate::downtime::RestKind;
+use crate::error::{EngineError, Resource};
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// Items a character can be attuned to at once, unless their sheet says
+/// otherwise
+pub const DEFAULT_ATTUNEMENT_LIMIT: u8 = 3;
+
+/// When an item's charges come back
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Default, EnumIter, Display, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Recharge {
+    /// On any rest
+    #[strum(to_string = "short rest")]
+    ShortRest,
+    /// Only on a long rest
+    #[default]
+    #[strum(to_string = "long rest")]
+    LongRest,
+    /// Once spent, they're gone
+    #[strum(to_string = "never")]
+    Never,
+}
+
+impl Recharge {
+    /// Whether a rest of this kind restores charges
+    pub fn on(self, rest: RestKind) -> bool {
+        match self {
+            Recharge::ShortRest => true,
+            Recharge::LongRest => rest == RestKind::Long,
+            Recharge::Never => false,
+        }
+    }
+}
+
+/// An item's charges
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Charges {
+    pub current: u8,
+    pub maximum: u8,
+    #[serde(default)]
+    pub recharge: Recharge,
+}
+
+impl Charges {
+    /// A full set of charges
+    pub fn new(maximum: u8, recharge: Recharge) -> Self {
+        Self {
+            current: maximum,
+            maximum,
+            recharge,
+        }
+    }
+
+    /// Spend charges, failing if there aren't enough
+    pub fn spend(&mut self, amount: u8) -> Result<(), EngineError> {
+        if amount > self.current {
+            return Err(EngineError::InsufficientResource {
+                resource: Resource::Charges,
+                have: self.current as u32,
+                need: amount as u32,
+            });
+        }
+        self.current -= amount;
+        Ok(())
+    }
+
+    /// Refill on a rest that recharges them, returning how many came back
+    pub fn rest(&mut self, rest: RestKind) -> u8 {
+        if !self.recharge.on(rest) {
+            return 0;
+        }
+        let restored = self.maximum - self.current;
+        self.current = self.maximum;
+        restored
+    }
+}
+
+/// A magic item a character carries
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::items::magic::{MagicItem, Recharge};
+///
+/// let mut wand = MagicItem::new("Wand of Sparks")
+///     .with_charges(3, Recharge::LongRest)
+///     .requiring_attunement();
+///
+/// // It does nothing until attuned
+/// assert!(wand.use_charges(1).is_err());
+/// wand.attuned = true;
+/// wand.use_charges(2).unwrap();
+/// assert_eq!(wand.charges.as_ref().unwrap().current, 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MagicItem {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Whether it must be attuned to work
+    #[serde(default)]
+    pub requires_attunement: bool,
+    #[serde(default)]
+    pub attuned: bool,
+    #[serde(default)]
+    pub charges: Option<Charges>,
+}
+
+impl MagicItem {
+    /// An item that needs no attunement and has no charges
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            description: String::new(),
+            requires_attunement: false,
+            attuned: false,
+            charges: None,
+        }
+    }
+
+    /// Set what it does
+    pub fn with_description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+
+    /// Give it charges, starting full
+    pub fn with_charges(mut self, maximum: u8, recharge: Recharge) -> Self {
+        self.charges = Some(Charges::new(maximum, recharge));
+        self
+    }
+
+    /// Make it need attuning before it works
+    pub fn requiring_attunement(mut self) -> Self {
+        self.requires_attunement = true;
+        self
+    }
+
+    /// Whether it works right now
+    pub fn is_active(&self) -> bool {
+        self.attuned || !self.requires_attunement
+    }
+
+    /// Spend charges to use it
+    ///
+    /// Fails if it needs attuning and isn't, or hasn't the charges. Items
+    /// without charges can always be used.
+    pub fn use_charges(&mut self, amount: u8) -> Result<(), EngineError> {
+        if !self.is_active() {
+            return Err(EngineError::InvalidItem(format!(
+                "{} must be attuned before it can be used",
+                self.name
+            )));
+        }
+        match &mut self.charges {
+            Some(charges) => charges.spend(amount),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_charges_recharge_by_rest() {
+        let mut ring = Charges::new(2, Recharge::LongRest);
+        ring.spend(2).unwrap();
+        assert!(matches!(
+            ring.spend(1),
+            Err(EngineError::InsufficientResource {
+                resource: Resource::Charges,
+                have: 0,
+                need: 1,
+            })
+        ));
+        assert_eq!(ring.rest(RestKind::Short), 0);
+        assert_eq!(ring.rest(RestKind::Long), 2);
+
+        let mut scroll = Charges::new(1, Recharge::Never);
+        scroll.spend(1).unwrap();
+        assert_eq!(scroll.rest(RestKind::Long), 0);
+
+        let mut amulet = Charges::new(1, Recharge::ShortRest);
+        amulet.spend(1).unwrap();
+        assert_eq!(amulet.rest(RestKind::Short), 1);
+    }
+}
@@ -2,11 +2,33 @@
 //!
 //! This module manages:
 //! - Weapons and their properties
-//! - Armor system
+//! - Weapon features such as Reliable and Brutal
+//! - Loadouts and the hands weapons take
+//! - Magic items with attunement and charges
+//! - Armor and armor features
 //! - Inventory management
 //! - Equipment tags
+//! - Shops and the gold economy
+//! - Rewards of gold and loot after play
 
-// TODO: Add submodules
-// pub mod weapons;
-// pub mod armor;
-// pub mod inventory;
+pub mod armor;
+pub mod features;
+pub mod inventory;
+pub mod loadout;
+#[cfg(feature = "std")]
+pub mod magic;
+pub mod rewards;
+#[cfg(feature = "content")]
+pub mod shop;
+pub mod weapons;
+
+pub use armor::{Armor, ArmorEffect, ArmorFeature};
+pub use features::{FeatureEffect, WeaponFeature};
+pub use inventory::{Inventory, InventoryItem};
+pub use loadout::Loadout;
+#[cfg(feature = "std")]
+pub use magic::{MagicItem, Recharge};
+pub use rewards::{ItemReward, RewardSuggestion, RewardTable};
+#[cfg(feature = "content")]
+pub use shop::{HaggleResult, Shop, ShopItem};
+pub use weapons::{Burden, Weapon, WeaponSlot};
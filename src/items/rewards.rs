@@ -0,0 +1,142 @@
+//! Rewards - treasure to hand out after play
+//!
+//! Suggests gold and items for a stretch of play from the party's tier
+//! and how many sessions and encounters it covered. Gold is counted in
+//! handfuls, as in shops; ten handfuls make a bag and ten bags a chest,
+//! and the amounts climb from handfuls at tier 1 towards chests at tier
+//! 4. Items are rolls on the loot and consumable tables, with one more
+//! d12 per tier, left for the GM to roll and name.
+
+use alloc::vec::Vec;
+use core::fmt;
+use serde::{Deserialize, Serialize};
+
+/// Handfuls of gold in a bag
+pub const HANDFULS_PER_BAG: u32 = 10;
+/// Bags of gold in a chest
+pub const BAGS_PER_CHEST: u32 = 10;
+
+/// Handfuls of gold each encounter turns up, by tier
+const GOLD_PER_ENCOUNTER: [u32; 4] = [1, 3, 10, 30];
+/// Handfuls of gold each session pays out on top, by tier
+const GOLD_PER_SESSION: [u32; 4] = [2, 10, 30, 100];
+/// Encounters per consumable found
+const ENCOUNTERS_PER_CONSUMABLE: u32 = 2;
+
+/// Which table an item reward is rolled on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum RewardTable {
+    /// Potions, scrolls and other single-use items
+    Consumable,
+    /// Lasting magic items and curiosities
+    Loot,
+}
+
+/// One item to roll for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ItemReward {
+    pub table: RewardTable,
+    /// d12s to roll and sum on the table
+    pub dice: u8,
+}
+
+impl fmt::Display for ItemReward {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let table = match self.table {
+            RewardTable::Consumable => "consumable",
+            RewardTable::Loot => "loot",
+        };
+        write!(f, "Unrolled {} ({}d12)", table, self.dice)
+    }
+}
+
+/// Gold and items suggested for a stretch of play
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RewardSuggestion {
+    pub tier: u8,
+    /// Handfuls of gold for the whole party
+    pub gold: u32,
+    pub items: Vec<ItemReward>,
+}
+
+impl RewardSuggestion {
+    /// Suggest rewards for `sessions` sessions and `encounters`
+    /// encounters played at `tier`
+    ///
+    /// Each encounter turns up some gold and every second one a
+    /// consumable; each session adds a purse and one piece of loot.
+    /// Tiers outside 1-4 are clamped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::items::rewards::{RewardSuggestion, RewardTable};
+    ///
+    /// let rewards = RewardSuggestion::for_play(1, 1, 3);
+    /// assert_eq!(rewards.gold, 5);
+    /// assert_eq!(rewards.items.len(), 2);
+    /// assert_eq!(rewards.items[0].table, RewardTable::Consumable);
+    /// assert_eq!(rewards.items[1].to_string(), "Unrolled loot (1d12)");
+    ///
+    /// // Tier 3 pays in bags
+    /// assert_eq!(RewardSuggestion::for_play(3, 1, 2).bags(), 5);
+    /// ```
+    pub fn for_play(tier: u8, sessions: u32, encounters: u32) -> Self {
+        let tier = tier.clamp(1, 4);
+        let index = usize::from(tier - 1);
+        let gold = GOLD_PER_ENCOUNTER[index]
+            .saturating_mul(encounters)
+            .saturating_add(GOLD_PER_SESSION[index].saturating_mul(sessions));
+
+        let consumables = encounters / ENCOUNTERS_PER_CONSUMABLE;
+        let items = core::iter::repeat_n(RewardTable::Consumable, consumables as usize)
+            .chain(core::iter::repeat_n(RewardTable::Loot, sessions as usize))
+            .map(|table| ItemReward { table, dice: tier })
+            .collect();
+
+        RewardSuggestion { tier, gold, items }
+    }
+
+    /// The gold in whole bags, leaving out loose handfuls
+    pub fn bags(&self) -> u32 {
+        self.gold / HANDFULS_PER_BAG
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gold_climbs_with_tier() {
+        let golds: Vec<u32> = (1..=4)
+            .map(|tier| RewardSuggestion::for_play(tier, 1, 3).gold)
+            .collect();
+        assert_eq!(golds, vec![5, 19, 60, 190]);
+        assert_eq!(RewardSuggestion::for_play(4, 1, 0).bags(), BAGS_PER_CHEST);
+    }
+
+    #[test]
+    fn test_items_roll_more_dice_at_higher_tiers() {
+        let rewards = RewardSuggestion::for_play(9, 2, 5);
+        assert_eq!(rewards.tier, 4);
+        assert_eq!(rewards.items.len(), 4);
+        assert!(rewards.items.iter().all(|item| item.dice == 4));
+        assert_eq!(
+            rewards
+                .items
+                .iter()
+                .filter(|item| item.table == RewardTable::Loot)
+                .count(),
+            2
+        );
+
+        let nothing = RewardSuggestion::for_play(0, 0, 0);
+        assert_eq!(nothing.tier, 1);
+        assert_eq!(nothing.gold, 0);
+        assert!(nothing.items.is_empty());
+    }
+}
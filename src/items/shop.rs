@@ -0,0 +1,525 @@
+//! Shops and the gold economy
+//!
+//! Prices are measured in handfuls of gold. Shops stock items by tier,
+//! sell at a markup and buy back at a fraction of the base price.
+//! Characters can haggle with a Presence duality roll to get a discount.
+
+use crate::core::dice::{DualityResult, DualityRoll, SuccessType};
+use crate::core::Difficulty;
+use crate::error::{EngineError, Resource};
+#[cfg(feature = "persistence")]
+use crate::storage::{self, Persist};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "persistence")]
+use std::path::Path;
+
+/// An item stocked by a shop
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ShopItem {
+    /// Unique identifier
+    pub id: String,
+    /// Display name
+    pub name: String,
+    /// Item tier (1-4)
+    pub tier: u8,
+    /// Base price in handfuls of gold
+    pub base_price: u32,
+    /// Number of units in stock
+    pub quantity: u32,
+}
+
+impl ShopItem {
+    /// Create a new shop item
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::items::ShopItem;
+    ///
+    /// let potion = ShopItem::new("minor_health_potion", "Minor Health Potion", 1, 2, 5);
+    /// assert_eq!(potion.tier, 1);
+    /// assert_eq!(potion.quantity, 5);
+    /// ```
+    pub fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        tier: u8,
+        base_price: u32,
+        quantity: u32,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            tier,
+            base_price,
+            quantity,
+        }
+    }
+}
+
+/// The result of a haggling attempt
+#[derive(Debug, Clone, PartialEq)]
+pub struct HaggleResult {
+    /// The Presence roll made while haggling
+    pub roll: DualityResult,
+    /// Outcome of the roll against the shopkeeper's difficulty
+    pub outcome: SuccessType,
+    /// Discount (or surcharge, if negative) in percent
+    pub discount_percent: i8,
+}
+
+/// A shop with stocked inventory and pricing rules
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Shop {
+    /// The shop's name, as shown to players
+    pub name: String,
+    /// Items for sale, with how many of each are left
+    pub stock: Vec<ShopItem>,
+    /// Markup applied when the shop sells, in percent
+    pub markup_percent: u8,
+    /// Fraction of base price paid when the shop buys, in percent
+    pub buyback_percent: u8,
+    /// Difficulty of haggling with the shopkeeper
+    pub haggle_difficulty: u16,
+}
+
+impl Shop {
+    /// Save shop to a JSON file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use daggerheart_engine::items::Shop;
+    ///
+    /// let shop = Shop::new("The Gilded Anvil");
+    /// shop.save_to_file("anvil.json").unwrap();
+    /// ```
+    #[cfg(feature = "persistence")]
+    pub fn save_to_file(&self, path: impl AsRef<Path>) -> Result<(), EngineError> {
+        storage::save_file(self, path)
+    }
+
+    /// Load shop from a JSON file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use daggerheart_engine::items::Shop;
+    ///
+    /// let shop = Shop::load_from_file("anvil.json").unwrap();
+    /// println!("Welcome to {}", shop.name);
+    /// ```
+    #[cfg(feature = "persistence")]
+    pub fn load_from_file(path: impl AsRef<Path>) -> Result<Self, EngineError> {
+        storage::load_file(path)
+    }
+
+    /// Create an empty shop with default pricing
+    ///
+    /// Default pricing sells at base price, buys back at half price,
+    /// and haggling is an average tier 1 (12) Presence roll.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::items::Shop;
+    ///
+    /// let shop = Shop::new("The Gilded Anvil");
+    /// assert!(shop.stock.is_empty());
+    /// assert_eq!(shop.buyback_percent, 50);
+    /// ```
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            stock: Vec::new(),
+            markup_percent: 0,
+            buyback_percent: 50,
+            haggle_difficulty: Difficulty::AVERAGE.value(),
+        }
+    }
+
+    /// Set the markup applied to sale prices
+    pub fn with_markup(mut self, markup_percent: u8) -> Self {
+        self.markup_percent = markup_percent;
+        self
+    }
+
+    /// Set the haggling difficulty
+    pub fn with_haggle_difficulty(mut self, difficulty: u16) -> Self {
+        self.haggle_difficulty = difficulty;
+        self
+    }
+
+    /// Add an item to the stock, merging quantities with an existing entry
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::items::{Shop, ShopItem};
+    ///
+    /// let mut shop = Shop::new("Apothecary");
+    /// shop.add_stock(ShopItem::new("potion", "Potion", 1, 2, 3));
+    /// shop.add_stock(ShopItem::new("potion", "Potion", 1, 2, 2));
+    /// assert_eq!(shop.stock.len(), 1);
+    /// assert_eq!(shop.stock[0].quantity, 5);
+    /// ```
+    pub fn add_stock(&mut self, item: ShopItem) {
+        match self.stock.iter_mut().find(|i| i.id == item.id) {
+            Some(existing) => existing.quantity += item.quantity,
+            None => self.stock.push(item),
+        }
+    }
+
+    /// Get all in-stock items of a given tier
+    pub fn items_for_tier(&self, tier: u8) -> Vec<&ShopItem> {
+        self.stock
+            .iter()
+            .filter(|i| i.tier == tier && i.quantity > 0)
+            .collect()
+    }
+
+    /// Find an item by id
+    pub fn find(&self, item_id: &str) -> Option<&ShopItem> {
+        self.stock.iter().find(|i| i.id == item_id)
+    }
+
+    /// Price the shop charges for an item, after markup and discount
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::items::{Shop, ShopItem};
+    ///
+    /// let shop = Shop::new("Smithy").with_markup(20);
+    /// let sword = ShopItem::new("sword", "Sword", 1, 10, 1);
+    ///
+    /// assert_eq!(shop.buy_price(&sword, 0), 12);   // 10 + 20%
+    /// assert_eq!(shop.buy_price(&sword, 10), 11);  // 12 - 10%, rounded up
+    /// ```
+    pub fn buy_price(&self, item: &ShopItem, discount_percent: i8) -> u32 {
+        let marked_up = apply_percent(item.base_price, 100 + self.markup_percent as i32);
+        apply_percent(marked_up, 100 - discount_percent as i32)
+    }
+
+    /// Price the shop pays when buying an item from a character
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::items::{Shop, ShopItem};
+    ///
+    /// let shop = Shop::new("Smithy");
+    /// let sword = ShopItem::new("sword", "Sword", 1, 10, 1);
+    /// assert_eq!(shop.sell_price(&sword), 5); // Half of base price
+    /// ```
+    pub fn sell_price(&self, item: &ShopItem) -> u32 {
+        let paid = u64::from(item.base_price) * u64::from(self.buyback_percent) / 100;
+        paid.min(u64::from(u32::MAX)) as u32
+    }
+
+    /// Haggle with the shopkeeper using a Presence roll
+    pub fn haggle(&self, presence: i8) -> HaggleResult {
+        let mut rng = rand::thread_rng();
+        self.haggle_with_rng(presence, &mut rng)
+    }
+
+    /// Haggle with a specific RNG (for testing/seeding)
+    ///
+    /// A critical success earns 20% off, success with Hope 10%, success
+    /// with Fear 5%, and a failure annoys the shopkeeper (+10%).
+    pub fn haggle_with_rng<R: Rng>(&self, presence: i8, rng: &mut R) -> HaggleResult {
+        let roll = DualityRoll::roll_with_rng(rng).with_modifier(presence);
+        let outcome = roll.success_type(self.haggle_difficulty);
+        HaggleResult {
+            roll,
+            outcome,
+            discount_percent: haggle_discount(outcome),
+        }
+    }
+
+    /// Buy one unit of an item, deducting its price from `gold`
+    ///
+    /// Returns the price paid.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::items::{Shop, ShopItem};
+    ///
+    /// let mut shop = Shop::new("Smithy");
+    /// shop.add_stock(ShopItem::new("sword", "Sword", 1, 10, 1));
+    ///
+    /// let mut gold = 15;
+    /// assert_eq!(shop.buy("sword", &mut gold, 0).unwrap(), 10);
+    /// assert_eq!(gold, 5);
+    ///
+    /// // Sold out now
+    /// assert!(shop.buy("sword", &mut gold, 0).is_err());
+    /// ```
+    pub fn buy(
+        &mut self,
+        item_id: &str,
+        gold: &mut u32,
+        discount_percent: i8,
+    ) -> Result<u32, EngineError> {
+        let item = self.find(item_id).ok_or_else(|| EngineError::UnknownItem {
+            item: item_id.to_string(),
+        })?;
+
+        if item.quantity == 0 {
+            return Err(EngineError::OutOfStock {
+                item: item.name.clone(),
+            });
+        }
+
+        let price = self.buy_price(item, discount_percent);
+        if *gold < price {
+            return Err(EngineError::InsufficientResource {
+                resource: Resource::Gold,
+                have: *gold,
+                need: price,
+            });
+        }
+
+        *gold -= price;
+        if let Some(item) = self.stock.iter_mut().find(|i| i.id == item_id) {
+            item.quantity -= 1;
+        }
+        Ok(price)
+    }
+
+    /// Sell one unit of an item to the shop, adding its price to `gold`
+    ///
+    /// Returns the price received.
+    pub fn sell(&mut self, item: ShopItem, gold: &mut u32) -> u32 {
+        let price = self.sell_price(&item);
+        *gold += price;
+        self.add_stock(ShopItem {
+            quantity: 1,
+            ..item
+        });
+        price
+    }
+}
+
+/// Discount granted by a haggling outcome
+fn haggle_discount(outcome: SuccessType) -> i8 {
+    match outcome {
+        SuccessType::CriticalSuccess => 20,
+        SuccessType::SuccessWithHope => 10,
+        SuccessType::SuccessWithFear => 5,
+        SuccessType::Failure => -10,
+    }
+}
+
+/// Apply a percentage to a price, rounding up
+fn apply_percent(price: u32, percent: i32) -> u32 {
+    let scaled = price as i64 * percent.max(0) as i64;
+    ((scaled + 99) / 100).min(u32::MAX as i64) as u32
+}
+
+#[cfg(feature = "persistence")]
+impl Persist for Shop {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn create_test_shop() -> Shop {
+        let mut shop = Shop::new("Test Shop");
+        shop.add_stock(ShopItem::new("potion", "Potion", 1, 2, 3));
+        shop.add_stock(ShopItem::new("sword", "Sword", 1, 10, 1));
+        shop.add_stock(ShopItem::new("greatsword", "Greatsword", 2, 40, 1));
+        shop
+    }
+
+    #[test]
+    fn test_items_for_tier() {
+        let shop = create_test_shop();
+
+        assert_eq!(shop.items_for_tier(1).len(), 2);
+        assert_eq!(shop.items_for_tier(2).len(), 1);
+        assert!(shop.items_for_tier(3).is_empty());
+    }
+
+    #[test]
+    fn test_add_stock_merges() {
+        let mut shop = create_test_shop();
+        shop.add_stock(ShopItem::new("potion", "Potion", 1, 2, 2));
+
+        assert_eq!(shop.find("potion").unwrap().quantity, 5);
+    }
+
+    #[test]
+    fn test_buy_price_with_markup() {
+        let shop = Shop::new("Shop").with_markup(50);
+        let item = ShopItem::new("sword", "Sword", 1, 10, 1);
+
+        assert_eq!(shop.buy_price(&item, 0), 15);
+    }
+
+    #[test]
+    fn test_buy_price_with_surcharge() {
+        let shop = Shop::new("Shop");
+        let item = ShopItem::new("sword", "Sword", 1, 10, 1);
+
+        assert_eq!(shop.buy_price(&item, -10), 11);
+    }
+
+    #[test]
+    fn test_sell_price() {
+        let shop = Shop::new("Shop");
+        let item = ShopItem::new("sword", "Sword", 1, 10, 1);
+
+        assert_eq!(shop.sell_price(&item), 5);
+
+        // Large prices don't overflow
+        let crown = ShopItem::new("crown", "Crown", 4, u32::MAX, 1);
+        assert_eq!(shop.sell_price(&crown), u32::MAX / 2);
+        assert_eq!(shop.buy_price(&crown, 0), u32::MAX);
+    }
+
+    #[test]
+    fn test_buy_success() {
+        let mut shop = create_test_shop();
+        let mut gold = 20;
+
+        let paid = shop.buy("sword", &mut gold, 0).unwrap();
+        assert_eq!(paid, 10);
+        assert_eq!(gold, 10);
+        assert_eq!(shop.find("sword").unwrap().quantity, 0);
+    }
+
+    #[test]
+    fn test_buy_not_enough_gold() {
+        let mut shop = create_test_shop();
+        let mut gold = 5;
+
+        assert!(matches!(
+            shop.buy("sword", &mut gold, 0),
+            Err(EngineError::InsufficientResource {
+                resource: Resource::Gold,
+                have: 5,
+                ..
+            })
+        ));
+        assert_eq!(gold, 5); // Unchanged
+        assert_eq!(shop.find("sword").unwrap().quantity, 1);
+    }
+
+    #[test]
+    fn test_buy_unknown_item() {
+        let mut shop = create_test_shop();
+        let mut gold = 100;
+
+        assert!(matches!(
+            shop.buy("dragon", &mut gold, 0),
+            Err(EngineError::UnknownItem { .. })
+        ));
+    }
+
+    #[test]
+    fn test_buy_out_of_stock() {
+        let mut shop = create_test_shop();
+        let mut gold = 100;
+
+        shop.buy("sword", &mut gold, 0).unwrap();
+        assert!(matches!(
+            shop.buy("sword", &mut gold, 0),
+            Err(EngineError::OutOfStock { .. })
+        ));
+    }
+
+    #[test]
+    fn test_sell_adds_stock() {
+        let mut shop = create_test_shop();
+        let mut gold = 0;
+
+        let received = shop.sell(ShopItem::new("shield", "Shield", 1, 8, 1), &mut gold);
+        assert_eq!(received, 4);
+        assert_eq!(gold, 4);
+        assert!(shop.find("shield").is_some());
+    }
+
+    #[test]
+    fn test_haggle_discounts() {
+        assert_eq!(haggle_discount(SuccessType::CriticalSuccess), 20);
+        assert_eq!(haggle_discount(SuccessType::SuccessWithHope), 10);
+        assert_eq!(haggle_discount(SuccessType::SuccessWithFear), 5);
+        assert_eq!(haggle_discount(SuccessType::Failure), -10);
+    }
+
+    #[test]
+    fn test_haggle_with_rng_reproducible() {
+        let shop = create_test_shop();
+        let mut rng1 = StdRng::seed_from_u64(7);
+        let mut rng2 = StdRng::seed_from_u64(7);
+
+        let result1 = shop.haggle_with_rng(2, &mut rng1);
+        let result2 = shop.haggle_with_rng(2, &mut rng2);
+
+        assert_eq!(result1, result2);
+        assert_eq!(result1.roll.modifier, 2);
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_shop_serialization() {
+        let shop = create_test_shop();
+
+        let json = serde_json::to_string(&shop).unwrap();
+        let loaded: Shop = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(shop, loaded);
+    }
+}
+
+#[cfg(test)]
+mod property_tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn prop_sell_never_exceeds_buy(
+            base in 0u32..1000,
+            markup in 0u8..100,
+        ) {
+            let shop = Shop::new("Shop").with_markup(markup);
+            let item = ShopItem::new("item", "Item", 1, base, 1);
+
+            prop_assert!(shop.sell_price(&item) <= shop.buy_price(&item, 0));
+        }
+
+        #[test]
+        fn prop_discount_never_increases_price(
+            base in 0u32..1000,
+            discount in 0i8..=20,
+        ) {
+            let shop = Shop::new("Shop");
+            let item = ShopItem::new("item", "Item", 1, base, 1);
+
+            prop_assert!(shop.buy_price(&item, discount) <= shop.buy_price(&item, 0));
+        }
+
+        #[test]
+        fn prop_buy_conserves_gold(
+            base in 1u32..100,
+            gold in 0u32..200,
+        ) {
+            let mut shop = Shop::new("Shop");
+            shop.add_stock(ShopItem::new("item", "Item", 1, base, 1));
+            let mut wallet = gold;
+
+            match shop.buy("item", &mut wallet, 0) {
+                Ok(paid) => prop_assert_eq!(wallet + paid, gold),
+                Err(_) => prop_assert_eq!(wallet, gold),
+            }
+        }
+    }
+}
@@ -0,0 +1,301 @@
+//! Weapons
+//!
+//! A weapon names the trait its attacks roll with, its range and its
+//! damage die. The die is rolled once per point of the wielder's
+//! Proficiency, plus the weapon's flat bonus. Its burden says how many
+//! hands it takes, and its slot whether it's a primary or secondary
+//! weapon. Its features, such as Reliable or Brutal, adjust the attack
+//! and damage rolls and the wielder's defenses.
+
+use crate::cards::Range;
+use crate::character::AttributeType;
+use crate::core::dice::{DamageDice, Die};
+use crate::items::features::{FeatureEffect, WeaponFeature};
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// How many hands a weapon takes
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Default, EnumIter, Display, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Burden {
+    #[default]
+    #[strum(to_string = "One-Handed")]
+    OneHanded,
+    #[strum(to_string = "Two-Handed")]
+    TwoHanded,
+}
+
+impl Burden {
+    /// Hands it takes to wield
+    pub fn hands(self) -> u8 {
+        match self {
+            Burden::OneHanded => 1,
+            Burden::TwoHanded => 2,
+        }
+    }
+}
+
+/// Where a weapon is carried
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Default, EnumIter, Display, Serialize, Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum WeaponSlot {
+    #[default]
+    Primary,
+    /// An off-hand weapon or shield, wielded alongside a one-handed primary
+    Secondary,
+}
+
+/// A weapon a character can attack with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Weapon {
+    pub name: String,
+    /// The trait attack rolls use
+    pub attack_trait: AttributeType,
+    pub range: Range,
+    /// Rolled once per point of Proficiency
+    pub damage_die: Die,
+    /// Added to the damage once
+    pub damage_bonus: i16,
+    /// Deals magic damage rather than physical
+    #[serde(default)]
+    pub magic: bool,
+    #[serde(default)]
+    pub burden: Burden,
+    #[serde(default)]
+    pub slot: WeaponSlot,
+    #[serde(default)]
+    pub features: Vec<WeaponFeature>,
+}
+
+impl Weapon {
+    /// Create a physical weapon
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::cards::Range;
+    /// use daggerheart_engine::character::AttributeType;
+    /// use daggerheart_engine::core::dice::Die;
+    /// use daggerheart_engine::items::Weapon;
+    ///
+    /// let spear = Weapon::new("Spear", AttributeType::Finesse, Range::VeryClose, Die::D8, 3);
+    /// assert_eq!(spear.damage(2).to_string(), "2d8+3");
+    /// ```
+    pub fn new(
+        name: impl Into<String>,
+        attack_trait: AttributeType,
+        range: Range,
+        damage_die: Die,
+        damage_bonus: i16,
+    ) -> Self {
+        Self {
+            name: name.into(),
+            attack_trait,
+            range,
+            damage_die,
+            damage_bonus,
+            magic: false,
+            burden: Burden::OneHanded,
+            slot: WeaponSlot::Primary,
+            features: Vec::new(),
+        }
+    }
+
+    /// Give it a feature
+    pub fn with_feature(mut self, feature: WeaponFeature) -> Self {
+        self.features.push(feature);
+        self
+    }
+
+    /// Make this a magic weapon
+    pub fn with_magic(mut self) -> Self {
+        self.magic = true;
+        self
+    }
+
+    /// Make this take both hands
+    pub fn two_handed(mut self) -> Self {
+        self.burden = Burden::TwoHanded;
+        self
+    }
+
+    /// Make this a secondary weapon
+    pub fn secondary(mut self) -> Self {
+        self.slot = WeaponSlot::Secondary;
+        self
+    }
+
+    /// Hands it takes to wield
+    pub fn hands(&self) -> u8 {
+        self.burden.hands()
+    }
+
+    /// Every effect of its features
+    pub fn effects(&self) -> impl Iterator<Item = FeatureEffect> + '_ {
+        self.features
+            .iter()
+            .flat_map(|feature| feature.effects.iter().copied())
+    }
+
+    /// What its features add to attack rolls
+    pub fn attack_bonus(&self) -> i8 {
+        self.effects().fold(0i8, |total, effect| match effect {
+            FeatureEffect::AttackBonus(bonus) => total.saturating_add(bonus),
+            _ => total,
+        })
+    }
+
+    /// What its features add to the wielder's Evasion
+    pub fn evasion_modifier(&self) -> i8 {
+        self.effects().fold(0i8, |total, effect| match effect {
+            FeatureEffect::Evasion(bonus) => total.saturating_add(bonus),
+            _ => total,
+        })
+    }
+
+    /// What its features add to the wielder's Armor Score
+    pub fn armor_modifier(&self) -> i8 {
+        self.effects().fold(0i8, |total, effect| match effect {
+            FeatureEffect::Armor(bonus) => total.saturating_add(bonus),
+            _ => total,
+        })
+    }
+
+    /// The damage this weapon deals at a Proficiency, with its features
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::items::{Weapon, WeaponFeature};
+    ///
+    /// let axe = Weapon::named("Battleaxe").unwrap().with_feature(WeaponFeature::brutal());
+    /// assert_eq!(axe.damage(2).to_string(), "2d10!+3");
+    /// ```
+    pub fn damage(&self, proficiency: u8) -> DamageDice {
+        let mut bonus = self.damage_bonus;
+        let mut dropped = 0u8;
+        let mut exploding = false;
+        for effect in self.effects() {
+            match effect {
+                FeatureEffect::DamageBonus(extra) => bonus = bonus.saturating_add(extra),
+                FeatureEffect::DropLowest(count) => dropped = dropped.saturating_add(count),
+                FeatureEffect::Exploding => exploding = true,
+                _ => {}
+            }
+        }
+        let damage = DamageDice::new(vec![self.damage_die; proficiency.max(1) as usize])
+            .with_bonus(bonus)
+            .with_drop_lowest(dropped);
+        if exploding {
+            damage.with_exploding()
+        } else {
+            damage
+        }
+    }
+
+    /// The tier 1 weapons the classes start with
+    pub fn builtin() -> Vec<Weapon> {
+        use AttributeType::*;
+        vec![
+            Weapon::new("Broadsword", Agility, Range::VeryClose, Die::D8, 0)
+                .with_feature(WeaponFeature::reliable()),
+            Weapon::new("Longsword", Agility, Range::VeryClose, Die::D10, 3).two_handed(),
+            Weapon::new("Battleaxe", Strength, Range::VeryClose, Die::D10, 3).two_handed(),
+            Weapon::new("Dagger", Finesse, Range::VeryClose, Die::D8, 1),
+            Weapon::new("Small Dagger", Finesse, Range::VeryClose, Die::D8, 0).secondary(),
+            Weapon::new("Rapier", Presence, Range::VeryClose, Die::D8, 0),
+            Weapon::new("Round Shield", Strength, Range::VeryClose, Die::D4, 0)
+                .secondary()
+                .with_feature(WeaponFeature::protective()),
+            Weapon::new("Shortbow", Agility, Range::Far, Die::D6, 3).two_handed(),
+            Weapon::new("Hallowed Axe", Strength, Range::VeryClose, Die::D8, 1).with_magic(),
+            Weapon::new("Shortstaff", Instinct, Range::Close, Die::D8, 1).with_magic(),
+            Weapon::new("Dualstaff", Instinct, Range::Far, Die::D6, 3)
+                .with_magic()
+                .two_handed(),
+            Weapon::new("Greatstaff", Knowledge, Range::Far, Die::D6, 0)
+                .with_magic()
+                .two_handed()
+                .with_feature(WeaponFeature::powerful()),
+        ]
+    }
+
+    /// Look up a built-in weapon by name, ignoring case
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::AttributeType;
+    /// use daggerheart_engine::items::Weapon;
+    ///
+    /// let longsword = Weapon::named("longsword").unwrap();
+    /// assert_eq!(longsword.attack_trait, AttributeType::Agility);
+    /// assert!(Weapon::named("Chainmail Armor").is_none());
+    /// ```
+    pub fn named(name: &str) -> Option<Weapon> {
+        Self::builtin()
+            .into_iter()
+            .find(|weapon| weapon.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::Class;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_damage_scales_with_proficiency() {
+        let longsword = Weapon::named("Longsword").unwrap();
+        assert_eq!(longsword.damage(1).to_string(), "1d10+3");
+        assert_eq!(longsword.damage(3).to_string(), "3d10+3");
+        // Everyone rolls at least one die
+        assert_eq!(longsword.damage(0).to_string(), "1d10+3");
+    }
+
+    #[test]
+    fn test_features_combine() {
+        let homebrew = Weapon::new(
+            "Sunder",
+            AttributeType::Strength,
+            Range::VeryClose,
+            Die::D12,
+            2,
+        )
+        .two_handed()
+        .with_feature(WeaponFeature::reliable())
+        .with_feature(WeaponFeature::massive())
+        .with_feature(WeaponFeature::brutal())
+        .with_feature(
+            WeaponFeature::new("Honed", "+1 damage").with_effect(FeatureEffect::DamageBonus(1)),
+        );
+        assert_eq!(homebrew.attack_bonus(), 1);
+        assert_eq!(homebrew.evasion_modifier(), -1);
+        assert_eq!(homebrew.damage(2).to_string(), "3d12!kh2+3");
+
+        let shield = Weapon::named("Round Shield").unwrap();
+        assert_eq!(shield.armor_modifier(), 1);
+        assert_eq!(shield.attack_bonus(), 0);
+    }
+
+    #[test]
+    fn test_suggested_weapons_are_builtin() {
+        for class in Class::iter() {
+            let weapons = class
+                .suggested_equipment()
+                .iter()
+                .filter(|item| Weapon::named(item).is_some())
+                .count();
+            assert!(weapons > 0, "{} starts without a weapon", class);
+        }
+    }
+}
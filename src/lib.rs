@@ -8,14 +8,88 @@
 //! - Combat resolution
 //! - Domain card mechanics
 //! - Item and equipment management
+//! - Campaign state across encounters
+//! - End-of-session stats over the roll log and encounter logs
+//! - A `GameEngine` facade tying campaign, content, rules and dice together
+//! - Party overviews: coverage, damage estimates and gaps
+//! - Downtime rests and long-term projects
+//! - Scenes: combat, exploration, social, and downtime
+//! - House rules configured per campaign
+//! - GM screen summaries in text, Markdown and HTML
+//! - Social conflict against NPC resolve and pressure
+//! - A GM oracle for yes/no questions and complications
+//! - Pluggable persistence backends
+//! - Import/export for virtual tabletops
+//!
+//! ## Features
+//!
+//! With `default-features = false` the crate is just dice and rules types
+//! (attack and damage resolution, resources, attributes, progression,
+//! domain cards), built `no_std` + `alloc`. Rolls then need an explicit
+//! RNG (the `*_with_rng` methods).
+//!
+//! ```toml
+//! daggerheart-engine = { version = "0.1", default-features = false }
+//! ```
+//!
+//! The default features opt back in to the rest:
+//!
+//! - `std` - thread-local dice, encounters, campaigns, simulation, hooks
+//! - `persistence` - save/load, `Storage` backends, VTT import/export
+//! - `content` - adversaries, encounter building, shops, content registry
+//! - `cli` - the `daggerheart` binary
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
 // Public modules
+#[cfg(feature = "std")]
+pub mod analytics;
+#[cfg(feature = "std")]
+pub mod campaign;
 pub mod cards;
 pub mod character;
 pub mod combat;
+#[cfg(all(feature = "content", feature = "persistence"))]
+pub mod compendium;
+#[cfg(feature = "content")]
+pub mod content;
 pub mod core;
+#[cfg(feature = "std")]
+pub mod downtime;
+#[cfg(feature = "content")]
+pub mod engine;
 pub mod error;
+#[cfg(feature = "std")]
+pub mod gm;
+#[cfg(feature = "graphql")]
+pub mod graphql;
+#[cfg(feature = "std")]
+pub mod hooks;
+#[cfg(feature = "std")]
+pub mod i18n;
+#[cfg(feature = "persistence")]
+pub mod interop;
 pub mod items;
+#[cfg(feature = "std")]
+pub mod party;
+#[cfg(feature = "std")]
+pub mod render;
+#[cfg(feature = "std")]
+pub mod rules;
+#[cfg(feature = "std")]
+pub mod scene;
+#[cfg(feature = "schema")]
+pub mod schema;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "std")]
+pub mod social;
+#[cfg(feature = "persistence")]
+pub mod storage;
+#[cfg(all(test, feature = "std"))]
+mod test_support;
 
 // Re-export commonly used types
 pub use error::EngineError;
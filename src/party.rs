@@ -0,0 +1,337 @@
+//! Party overview - how a group of characters fits together
+//!
+//! Looks across a party's sheets for which domains they can draw on, who
+//! is best at each trait, who can heal, roughly how much damage they deal
+//! in a round, and what the party is missing.
+
+use crate::cards::Range;
+use crate::character::progression::tier_for_level;
+use crate::character::{AttributeType, CharacterSheet, Class, Domain};
+use crate::combat::montecarlo::AttackProfile;
+use crate::combat::simulation::Combatant;
+#[cfg(feature = "content")]
+use crate::content::ContentRegistry;
+use crate::core::dice::DamageDice;
+use crate::core::stats::duality_success_chance;
+use crate::core::Difficulty;
+use crate::items::weapons::Weapon;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use strum::IntoEnumIterator;
+
+/// Who in the party can draw on a domain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct DomainCoverage {
+    pub domain: Domain,
+    pub members: Vec<String>,
+}
+
+/// The party's best modifier in a trait
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TraitSpread {
+    pub attribute: AttributeType,
+    pub best: i8,
+    /// Everyone at the best modifier
+    pub members: Vec<String>,
+}
+
+/// One character's part in the overview
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MemberSummary {
+    pub name: String,
+    pub class: Option<Class>,
+    pub level: u8,
+    pub hp: u8,
+    pub evasion: u8,
+    pub attack_modifier: i8,
+    pub damage: DamageDice,
+    /// Chance to hit an adversary of the party's tier
+    pub hit_chance: f64,
+    /// Average damage a round against that adversary
+    pub damage_per_round: f64,
+    /// Whether their attack reaches past melee
+    pub ranged: bool,
+    /// Where their healing comes from: their Hope feature or cards
+    pub healing: Vec<String>,
+}
+
+/// Something the party lacks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Gap {
+    /// Nobody can restore Hit Points or Stress
+    NoHealing,
+    /// Nobody attacks beyond melee
+    NoRangedAttacks,
+    /// Nobody is better than +0 in this trait
+    WeakTrait { attribute: AttributeType, best: i8 },
+}
+
+impl fmt::Display for Gap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Gap::NoHealing => write!(f, "No one can heal"),
+            Gap::NoRangedAttacks => write!(f, "No one attacks beyond melee"),
+            Gap::WeakTrait { attribute, best } => {
+                write!(f, "Weak in {}: best is {:+}", attribute, best)
+            }
+        }
+    }
+}
+
+/// A party's strengths and gaps
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PartyOverview {
+    pub tier: u8,
+    /// The Difficulty damage estimates are made against
+    pub difficulty: u16,
+    pub members: Vec<MemberSummary>,
+    /// Every domain, with who can take its cards
+    pub domains: Vec<DomainCoverage>,
+    pub traits: Vec<TraitSpread>,
+    /// The whole party's average damage a round
+    pub damage_per_round: f64,
+    pub gaps: Vec<Gap>,
+}
+
+impl PartyOverview {
+    /// Domains no one in the party can take cards from
+    pub fn missing_domains(&self) -> Vec<Domain> {
+        self.domains
+            .iter()
+            .filter(|coverage| coverage.members.is_empty())
+            .map(|coverage| coverage.domain)
+            .collect()
+    }
+}
+
+/// Summarise a party from their sheets
+///
+/// Healing counts class Hope features that heal; use [`overview_with`]
+/// to count healing domain cards too. Damage is estimated against an
+/// adversary of the party's tier.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class, Domain};
+/// use daggerheart_engine::combat::CombatantBuilder;
+/// use daggerheart_engine::party::{self, Gap};
+///
+/// let sheet = |name: &str, class| {
+///     CharacterSheet::new(
+///         CombatantBuilder::player(name, class, Ancestry::Human)
+///             .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+///             .build()
+///             .unwrap(),
+///     )
+/// };
+/// let report = party::overview(&[sheet("Grom", Class::Warrior), sheet("Ash", Class::Ranger)]);
+///
+/// assert_eq!(report.members.len(), 2);
+/// assert!(report.missing_domains().contains(&Domain::Splendor));
+/// assert!(report.gaps.contains(&Gap::NoHealing));
+/// ```
+pub fn overview(sheets: &[CharacterSheet]) -> PartyOverview {
+    build(sheets, |_| Vec::new())
+}
+
+/// Summarise a party, also counting healing domain cards from `content`
+///
+/// A card heals if its text mentions healing or clearing Hit Points.
+#[cfg(feature = "content")]
+pub fn overview_with(sheets: &[CharacterSheet], content: &ContentRegistry) -> PartyOverview {
+    build(sheets, |sheet| {
+        sheet
+            .progress
+            .available_cards
+            .iter()
+            .filter_map(|id| content.card(id))
+            .filter(|card| {
+                let text = card.description.to_lowercase();
+                text.contains("heal") || text.contains("clear a hit point")
+            })
+            .map(|card| card.name.clone())
+            .collect()
+    })
+}
+
+fn build(
+    sheets: &[CharacterSheet],
+    healing_cards: impl Fn(&CharacterSheet) -> Vec<String>,
+) -> PartyOverview {
+    let tier = sheets
+        .iter()
+        .map(|sheet| tier_for_level(sheet.progress.level))
+        .max()
+        .unwrap_or(1);
+    let difficulty = Difficulty::adversary(tier).value();
+
+    let members: Vec<MemberSummary> = sheets
+        .iter()
+        .map(|sheet| {
+            let combatant = &sheet.combatant;
+            let profile = AttackProfile::for_combatant(combatant);
+            let hit_chance = duality_success_chance(profile.modifier, difficulty, false);
+            let mut healing = Vec::new();
+            if let Some(class) = combatant.class() {
+                let feature = class.hope_feature();
+                if feature.effect.is_heal() {
+                    healing.push(String::from(feature.name));
+                }
+            }
+            healing.extend(healing_cards(sheet));
+            MemberSummary {
+                name: combatant.name.to_string(),
+                class: combatant.class(),
+                level: combatant.level,
+                hp: combatant.hp.maximum,
+                evasion: combatant.evasion,
+                attack_modifier: profile.modifier,
+                damage_per_round: hit_chance * profile.damage.average().max(0.0) as f64,
+                damage: profile.damage,
+                hit_chance,
+                ranged: is_ranged(combatant),
+                healing,
+            }
+        })
+        .collect();
+
+    let domains = Domain::iter()
+        .map(|domain| DomainCoverage {
+            domain,
+            members: sheets
+                .iter()
+                .filter(|sheet| {
+                    sheet
+                        .combatant
+                        .class()
+                        .is_some_and(|c| c.has_domain(domain))
+                })
+                .map(|sheet| sheet.combatant.name.to_string())
+                .collect(),
+        })
+        .collect();
+
+    let traits: Vec<TraitSpread> = AttributeType::iter()
+        .filter_map(|attribute| {
+            let scores: Vec<(&str, i8)> = sheets
+                .iter()
+                .filter_map(|sheet| {
+                    let attributes = sheet.combatant.attributes()?;
+                    Some((sheet.name(), attributes.get_modifier(attribute)))
+                })
+                .collect();
+            let best = scores.iter().map(|&(_, score)| score).max()?;
+            Some(TraitSpread {
+                attribute,
+                best,
+                members: scores
+                    .iter()
+                    .filter(|&&(_, score)| score == best)
+                    .map(|&(name, _)| String::from(name))
+                    .collect(),
+            })
+        })
+        .collect();
+
+    let mut gaps = Vec::new();
+    if !members.is_empty() {
+        if members.iter().all(|member| member.healing.is_empty()) {
+            gaps.push(Gap::NoHealing);
+        }
+        if !members.iter().any(|member| member.ranged) {
+            gaps.push(Gap::NoRangedAttacks);
+        }
+    }
+    for spread in traits.iter().filter(|spread| spread.best <= 0) {
+        gaps.push(Gap::WeakTrait {
+            attribute: spread.attribute,
+            best: spread.best,
+        });
+    }
+
+    PartyOverview {
+        tier,
+        difficulty,
+        damage_per_round: members.iter().map(|m| m.damage_per_round).sum(),
+        members,
+        domains,
+        traits,
+        gaps,
+    }
+}
+
+fn is_ranged(combatant: &Combatant) -> bool {
+    combatant
+        .equipment
+        .iter()
+        .filter_map(|item| Weapon::named(item))
+        .any(|weapon| weapon.range != Range::VeryClose)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Attributes};
+    use crate::combat::simulation::CombatantBuilder;
+
+    fn sheet(name: &str, class: Class, attributes: [i8; 6], weapon: &str) -> CharacterSheet {
+        CharacterSheet::new(
+            CombatantBuilder::player(name, class, Ancestry::Human)
+                .with_attributes(Attributes::from_array(attributes).unwrap())
+                .with_equipment(weapon)
+                .build()
+                .unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_domains_traits_and_healing() {
+        let party = [
+            sheet("Grom", Class::Warrior, [1, 2, 1, 0, 0, -1], "Longsword"),
+            sheet("Lia", Class::Seraph, [0, 1, -1, 1, 2, 0], "Longsword"),
+        ];
+        let report = overview(&party);
+
+        let splendor = report
+            .domains
+            .iter()
+            .find(|coverage| coverage.domain == Domain::Splendor)
+            .unwrap();
+        assert_eq!(splendor.members, vec!["Lia"]);
+        assert_eq!(report.missing_domains().len(), 5);
+
+        let strength = &report.traits[1];
+        assert_eq!(strength.attribute, AttributeType::Strength);
+        assert_eq!((strength.best, strength.members.len()), (2, 1));
+        assert!(report.gaps.contains(&Gap::WeakTrait {
+            attribute: AttributeType::Knowledge,
+            best: 0,
+        }));
+
+        assert_eq!(report.members[1].healing, vec!["Life Support"]);
+        assert!(!report.gaps.contains(&Gap::NoHealing));
+        assert!(report.gaps.contains(&Gap::NoRangedAttacks));
+    }
+
+    #[test]
+    fn test_damage_estimates() {
+        let party = [sheet(
+            "Grom",
+            Class::Warrior,
+            [1, 2, 1, 0, 0, -1],
+            "Longsword",
+        )];
+        let report = overview(&party);
+        let grom = &report.members[0];
+        assert!(grom.hit_chance > 0.5 && grom.hit_chance < 1.0);
+        assert_eq!(report.damage_per_round, grom.damage_per_round);
+        assert!(grom.damage_per_round > 0.0);
+        assert_eq!(overview(&[]).damage_per_round, 0.0);
+    }
+}
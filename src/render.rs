@@ -0,0 +1,403 @@
+//! Table-ready summaries - the GM screen
+//!
+//! [`gm_screen`] gathers what a GM glances at mid-fight: each adversary's
+//! Hit Points, Stress, Evasion and damage thresholds, its features and
+//! conditions, the running countdowns and the Fear pool. The resulting
+//! [`GmScreen`] renders as plain text for a terminal, Markdown for session
+//! notes or HTML for a second screen.
+
+use crate::combat::conditions::Condition;
+use crate::combat::countdown::Countdown;
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantId};
+use crate::rules::ThresholdRule;
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+/// One adversary's line on the GM screen
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AdversaryEntry {
+    pub id: CombatantId,
+    pub name: String,
+    pub hp: u8,
+    pub hp_maximum: u8,
+    pub stress: u8,
+    pub evasion: u8,
+    /// The raw damage that marks 1, 2 and 3 Hit Points, after armor;
+    /// shorter when armor keeps a hit from marking as many
+    pub thresholds: Vec<u16>,
+    /// Triggered features, then reactions
+    pub features: Vec<String>,
+    pub conditions: Vec<Condition>,
+    pub defeated: bool,
+}
+
+/// Everything on the GM screen at one moment
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct GmScreen {
+    pub round: u32,
+    pub fear: u8,
+    pub fear_maximum: u8,
+    pub adversaries: Vec<AdversaryEntry>,
+    pub countdowns: Vec<Countdown>,
+}
+
+/// Gather the GM screen for an encounter
+///
+/// Allied adversaries such as summons fight for the party and are left
+/// off. Thresholds follow the encounter's house rules.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder, Condition, Countdown};
+/// use daggerheart_engine::render::gm_screen;
+///
+/// let mut encounter = CombatEncounter::new(5);
+/// let bear = encounter.add_combatant(
+///     CombatantBuilder::adversary("Bear")
+///         .with_hp(6)
+///         .with_evasion(12)
+///         .with_armor(2)
+///         .build()
+///         .unwrap(),
+/// );
+/// encounter.apply_condition(bear, Condition::Restrained).unwrap();
+/// encounter.countdowns.push(Countdown::new("Reinforcements", 3));
+/// encounter.gain_fear(2);
+///
+/// let screen = gm_screen(&encounter);
+/// assert_eq!(screen.adversaries[0].thresholds, vec![7, 12, 17]);
+///
+/// let text = screen.to_text();
+/// assert!(text.contains("Fear 2/12"));
+/// assert!(text.contains("Bear  HP 6/6"));
+/// assert!(text.contains("Reinforcements: 3"));
+/// ```
+pub fn gm_screen(encounter: &CombatEncounter) -> GmScreen {
+    GmScreen {
+        round: encounter.round,
+        fear: encounter.fear.current,
+        fear_maximum: encounter.fear.maximum,
+        adversaries: encounter
+            .combatants
+            .iter()
+            .filter(|combatant| !combatant.is_player())
+            .map(|combatant| AdversaryEntry::of(combatant, encounter.rules.thresholds))
+            .collect(),
+        countdowns: encounter.countdowns.clone(),
+    }
+}
+
+impl AdversaryEntry {
+    fn of(combatant: &Combatant, rule: ThresholdRule) -> Self {
+        let mut thresholds = Vec::new();
+        for raw in 0..=u8::MAX as u16 {
+            let marked = combatant.measure_damage_with(raw, rule).hp_lost as usize;
+            while thresholds.len() < marked {
+                thresholds.push(raw);
+            }
+            if thresholds.len() >= 3 {
+                break;
+            }
+        }
+        let features = combatant
+            .triggers
+            .iter()
+            .map(|feature| feature.name.clone())
+            .chain(
+                combatant
+                    .reactions
+                    .iter()
+                    .map(|reaction| format!("{} (reaction)", reaction.name)),
+            )
+            .collect();
+        Self {
+            id: combatant.id,
+            name: combatant.name.to_string(),
+            hp: combatant.hp.current,
+            hp_maximum: combatant.hp.maximum,
+            stress: combatant.stress.current,
+            evasion: combatant.evasion,
+            thresholds,
+            features,
+            conditions: combatant.conditions.clone(),
+            defeated: !combatant.is_alive(),
+        }
+    }
+
+    /// "7 / 12 / 17"
+    fn threshold_line(&self) -> String {
+        if self.thresholds.is_empty() {
+            return String::from("—");
+        }
+        self.thresholds
+            .iter()
+            .map(|raw| raw.to_string())
+            .collect::<Vec<_>>()
+            .join(" / ")
+    }
+
+    /// "Restrained, Vulnerable", or "—"
+    fn condition_line(&self) -> String {
+        if self.conditions.is_empty() {
+            return String::from("—");
+        }
+        self.conditions
+            .iter()
+            .map(|condition| condition.to_string())
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+impl GmScreen {
+    /// "Round 2 · Fear 3/12", or "Not started · Fear 3/12"
+    fn header(&self) -> String {
+        let round = if self.round == 0 {
+            String::from("Not started")
+        } else {
+            format!("Round {}", self.round)
+        };
+        format!("{} · Fear {}/{}", round, self.fear, self.fear_maximum)
+    }
+
+    /// Render as plain text for a terminal
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        let _ = writeln!(text, "{}", self.header());
+        for adversary in &self.adversaries {
+            let _ = writeln!(
+                text,
+                "\n{}{}  HP {}/{}  Stress {}  Evasion {}",
+                adversary.name,
+                if adversary.defeated {
+                    " (defeated)"
+                } else {
+                    ""
+                },
+                adversary.hp,
+                adversary.hp_maximum,
+                adversary.stress,
+                adversary.evasion
+            );
+            let _ = writeln!(text, "  Thresholds: {}", adversary.threshold_line());
+            if !adversary.conditions.is_empty() {
+                let _ = writeln!(text, "  Conditions: {}", adversary.condition_line());
+            }
+            if !adversary.features.is_empty() {
+                let _ = writeln!(text, "  Features: {}", adversary.features.join(", "));
+            }
+        }
+        if !self.countdowns.is_empty() {
+            let _ = writeln!(text, "\nCountdowns:");
+            for countdown in &self.countdowns {
+                let _ = writeln!(text, "  {}: {}", countdown.name, countdown.remaining);
+            }
+        }
+        text
+    }
+
+    /// Render as Markdown, one table row per adversary
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::combat::{CombatEncounter, CombatantBuilder};
+    /// use daggerheart_engine::render::gm_screen;
+    ///
+    /// let mut encounter = CombatEncounter::new(5);
+    /// encounter.add_combatant(
+    ///     CombatantBuilder::adversary("Wolf")
+    ///         .with_hp(4)
+    ///         .with_evasion(10)
+    ///         .build()
+    ///         .unwrap(),
+    /// );
+    ///
+    /// let markdown = gm_screen(&encounter).to_markdown();
+    /// assert!(markdown.contains("| Wolf | 4/4 | 0 | 10 | 5 / 10 / 15 | — | — |"));
+    /// ```
+    pub fn to_markdown(&self) -> String {
+        let mut md = String::new();
+        let _ = writeln!(md, "## GM Screen\n\n*{}*\n", self.header());
+        if !self.adversaries.is_empty() {
+            let _ = writeln!(
+                md,
+                "| Adversary | HP | Stress | Evasion | Thresholds | Conditions | Features |"
+            );
+            let _ = writeln!(md, "|---|:---:|:---:|:---:|:---:|---|---|");
+            for adversary in &self.adversaries {
+                let name = if adversary.defeated {
+                    format!("~~{}~~", cell(&adversary.name))
+                } else {
+                    cell(&adversary.name)
+                };
+                let features = if adversary.features.is_empty() {
+                    String::from("—")
+                } else {
+                    cell(&adversary.features.join(", "))
+                };
+                let _ = writeln!(
+                    md,
+                    "| {} | {}/{} | {} | {} | {} | {} | {} |",
+                    name,
+                    adversary.hp,
+                    adversary.hp_maximum,
+                    adversary.stress,
+                    adversary.evasion,
+                    adversary.threshold_line(),
+                    adversary.condition_line(),
+                    features
+                );
+            }
+        }
+        if !self.countdowns.is_empty() {
+            let _ = writeln!(md, "\n### Countdowns\n");
+            for countdown in &self.countdowns {
+                let _ = writeln!(md, "- **{}:** {}", countdown.name, countdown.remaining);
+            }
+        }
+        md
+    }
+
+    /// Render as a standalone HTML page
+    pub fn to_html(&self) -> String {
+        let mut html = String::new();
+        html.push_str(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>GM Screen</title>\n",
+        );
+        html.push_str(STYLE);
+        html.push_str("</head>\n<body>\n<h1>GM Screen</h1>\n");
+        let _ = writeln!(html, "<p class=\"summary\">{}</p>", escape(&self.header()));
+        if !self.adversaries.is_empty() {
+            html.push_str(
+                "<table>\n<tr><th>Adversary</th><th>HP</th><th>Stress</th><th>Evasion</th>\
+                 <th>Thresholds</th><th>Conditions</th><th>Features</th></tr>\n",
+            );
+            for adversary in &self.adversaries {
+                let _ = writeln!(
+                    html,
+                    "<tr{}><td>{}</td><td>{}/{}</td><td>{}</td><td>{}</td><td>{}</td>\
+                     <td>{}</td><td>{}</td></tr>",
+                    if adversary.defeated {
+                        " class=\"defeated\""
+                    } else {
+                        ""
+                    },
+                    escape(&adversary.name),
+                    adversary.hp,
+                    adversary.hp_maximum,
+                    adversary.stress,
+                    adversary.evasion,
+                    escape(&adversary.threshold_line()),
+                    escape(&adversary.condition_line()),
+                    escape(&adversary.features.join(", "))
+                );
+            }
+            html.push_str("</table>\n");
+        }
+        if !self.countdowns.is_empty() {
+            html.push_str("<h2>Countdowns</h2>\n<ul>\n");
+            for countdown in &self.countdowns {
+                let _ = writeln!(
+                    html,
+                    "<li><b>{}:</b> {}</li>",
+                    escape(&countdown.name),
+                    countdown.remaining
+                );
+            }
+            html.push_str("</ul>\n");
+        }
+        html.push_str("</body>\n</html>\n");
+        html
+    }
+}
+
+/// Keep text from breaking a Markdown table row
+fn cell(text: &str) -> String {
+    text.replace('|', "\\|")
+}
+
+/// Escape text for HTML
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "<style>
+body { font-family: Georgia, serif; margin: 1em; color: #222; }
+h1 { margin-bottom: 0; }
+.summary { font-style: italic; margin-top: 0.25em; }
+h2 { border-bottom: 2px solid #633; color: #633; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #999; padding: 0.3em 0.5em; }
+th { background: #eee; }
+tr.defeated { color: #999; text-decoration: line-through; }
+</style>
+";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Class};
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::items::{Armor, ArmorEffect, ArmorFeature};
+    use crate::rules::RulesConfig;
+    use crate::test_support::player;
+
+    fn create_test_encounter() -> (CombatEncounter, CombatantId) {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(
+            player("Ash", Class::Ranger, Ancestry::Human)
+                .build()
+                .unwrap(),
+        );
+        let ogre = encounter.add_combatant(
+            CombatantBuilder::adversary("Ogre <Brute>")
+                .with_hp(8)
+                .with_evasion(11)
+                .build()
+                .unwrap(),
+        );
+        (encounter, ogre)
+    }
+
+    #[test]
+    fn test_screen_lists_only_adversaries() {
+        let (mut encounter, ogre) = create_test_encounter();
+        encounter.get_mut(ogre).unwrap().hp.current = 0;
+        let screen = gm_screen(&encounter);
+
+        assert_eq!(screen.adversaries.len(), 1);
+        assert!(screen.adversaries[0].defeated);
+        assert!(screen.to_text().contains("Ogre <Brute> (defeated)"));
+        assert!(screen.to_markdown().contains("~~Ogre <Brute>~~"));
+        let html = screen.to_html();
+        assert!(html.contains("<tr class=\"defeated\"><td>Ogre &lt;Brute&gt;</td>"));
+        assert!(!html.contains("Ash"));
+    }
+
+    #[test]
+    fn test_thresholds_follow_rules_and_armor() {
+        let (mut encounter, ogre) = create_test_encounter();
+        encounter.rules = RulesConfig::default().with_thresholds(ThresholdRule::PlusLevel);
+        encounter.get_mut(ogre).unwrap().level = 3;
+        assert_eq!(
+            gm_screen(&encounter).adversaries[0].thresholds,
+            vec![7, 12, 17]
+        );
+
+        // Armor that cuts severe hits to 1 Hit Point stops the list there
+        let plated = Armor::new("Plate", 0).with_feature(
+            ArmorFeature::new("Padded", "Hits mark at most 1 Hit Point")
+                .with_effect(ArmorEffect::ReduceSeverity(2)),
+        );
+        encounter.get_mut(ogre).unwrap().worn_armor = Some(plated);
+        assert_eq!(gm_screen(&encounter).adversaries[0].thresholds, vec![7]);
+    }
+}
@@ -0,0 +1,334 @@
+//! House rules - the table's choices where the rules leave room
+//!
+//! A `RulesConfig` is stored with the campaign and handed to each
+//! encounter it starts, so a table that plays crits, damage thresholds,
+//! Hope, Fear, encumbrance, death moves, action tokens or sustained
+//! flight differently changes a setting instead of the engine.
+
+use crate::combat::resources::DEFAULT_FEAR_MAXIMUM;
+use crate::combat::simulation::HopeMode;
+use crate::core::dice::{DamageDice, Die};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// How much damage a critical hit deals
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum CritRule {
+    /// The damage rolled, like any other hit
+    #[default]
+    Rolled,
+    /// The damage rolled plus the most the dice could show, as in the
+    /// core rules
+    MaxDice,
+    /// The damage dice are rolled twice
+    DoubleDice,
+}
+
+/// Where the damage threshold sits
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum ThresholdRule {
+    /// The same threshold at every level, moved only by armor
+    #[default]
+    Flat,
+    /// Raised by one for each level past the first
+    PlusLevel,
+}
+
+impl ThresholdRule {
+    /// How far the threshold moves for a combatant of this level
+    pub fn shift(&self, level: u8) -> u16 {
+        match self {
+            ThresholdRule::Flat => 0,
+            ThresholdRule::PlusLevel => level.saturating_sub(1) as u16,
+        }
+    }
+}
+
+/// When characters get their action tokens back
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum TokenRefresh {
+    /// Every character's pool refills as each round begins
+    #[default]
+    EachRound,
+    /// Pools refill once every character has spent their last token
+    WhenSpent,
+}
+
+/// The action tracker variant, used with `TurnModel::ActionTokens`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ActionTokenRule {
+    /// Tokens each character's pool holds
+    pub per_character: u8,
+    #[serde(default)]
+    pub refresh: TokenRefresh,
+}
+
+impl Default for ActionTokenRule {
+    fn default() -> Self {
+        Self {
+            per_character: 3,
+            refresh: TokenRefresh::default(),
+        }
+    }
+}
+
+/// What a character can do when they mark their last Hit Point
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum DeathMove {
+    /// Take one last action that critically succeeds, then die
+    #[strum(to_string = "Blaze of Glory")]
+    BlazeOfGlory,
+    /// Drop unconscious and live, with a lasting scar
+    #[strum(to_string = "Avoid Death")]
+    AvoidDeath,
+    /// Roll the duality dice to live or die
+    #[strum(to_string = "Risk It All")]
+    RiskItAll,
+}
+
+/// Which death moves the table allows
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum DeathRule {
+    /// The player picks any of them
+    #[default]
+    AnyMove,
+    /// Characters always Avoid Death; nobody dies without the player's say
+    AvoidDeathOnly,
+    /// No death moves: a character at 0 Hit Points is dead
+    Permadeath,
+}
+
+impl DeathRule {
+    /// The moves a character at 0 Hit Points may choose from
+    pub fn moves(&self) -> Vec<DeathMove> {
+        match self {
+            DeathRule::AnyMove => vec![
+                DeathMove::BlazeOfGlory,
+                DeathMove::AvoidDeath,
+                DeathMove::RiskItAll,
+            ],
+            DeathRule::AvoidDeathOnly => vec![DeathMove::AvoidDeath],
+            DeathRule::Permadeath => Vec::new(),
+        }
+    }
+}
+
+/// A table's house rules
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::combat::HopeMode;
+/// use daggerheart_engine::rules::{DeathRule, RulesConfig, ThresholdRule};
+///
+/// let rules = RulesConfig::default()
+///     .with_hope_mode(HopeMode::Shared)
+///     .with_thresholds(ThresholdRule::PlusLevel)
+///     .with_death(DeathRule::AvoidDeathOnly);
+///
+/// assert_eq!(rules.thresholds.shift(4), 3);
+/// assert_eq!(rules.death.moves().len(), 1);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct RulesConfig {
+    #[serde(default)]
+    pub crit: CritRule,
+    #[serde(default)]
+    pub thresholds: ThresholdRule,
+    /// Saves from before per-character Hope load as `Shared`
+    #[serde(default = "HopeMode::legacy")]
+    pub hope_mode: HopeMode,
+    /// Most Fear the GM can hold
+    #[serde(default = "default_max_fear")]
+    pub max_fear: u8,
+    /// Whether item weight counts and overloaded characters are
+    /// Encumbered
+    #[serde(default)]
+    pub encumbrance: bool,
+    #[serde(default)]
+    pub death: DeathRule,
+    /// Pool size and refresh for encounters using action tokens
+    #[serde(default)]
+    pub action_tokens: ActionTokenRule,
+    /// Stress each flyer marks as a round begins; 0 keeps flight free
+    #[serde(default)]
+    pub flight_stress: u8,
+}
+
+fn default_max_fear() -> u8 {
+    DEFAULT_FEAR_MAXIMUM
+}
+
+impl Default for RulesConfig {
+    fn default() -> Self {
+        Self {
+            crit: CritRule::default(),
+            thresholds: ThresholdRule::default(),
+            hope_mode: HopeMode::default(),
+            max_fear: DEFAULT_FEAR_MAXIMUM,
+            encumbrance: false,
+            death: DeathRule::default(),
+            action_tokens: ActionTokenRule::default(),
+            flight_stress: 0,
+        }
+    }
+}
+
+impl RulesConfig {
+    /// Choose how critical hits deal damage
+    pub fn with_crit(mut self, crit: CritRule) -> Self {
+        self.crit = crit;
+        self
+    }
+
+    /// Choose where damage thresholds sit
+    pub fn with_thresholds(mut self, thresholds: ThresholdRule) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// Choose whether characters keep their own Hope or share a pool
+    pub fn with_hope_mode(mut self, mode: HopeMode) -> Self {
+        self.hope_mode = mode;
+        self
+    }
+
+    /// Cap the GM's Fear at something other than 12
+    pub fn with_max_fear(mut self, maximum: u8) -> Self {
+        self.max_fear = maximum;
+        self
+    }
+
+    /// Turn encumbrance on or off
+    pub fn with_encumbrance(mut self, enabled: bool) -> Self {
+        self.encumbrance = enabled;
+        self
+    }
+
+    /// Choose which death moves are allowed
+    pub fn with_death(mut self, death: DeathRule) -> Self {
+        self.death = death;
+        self
+    }
+
+    /// Set up the action tracker variant's token pools
+    pub fn with_action_tokens(mut self, rule: ActionTokenRule) -> Self {
+        self.action_tokens = rule;
+        self
+    }
+
+    /// Make flyers mark Stress each round to stay in the air
+    pub fn with_flight_stress(mut self, stress: u8) -> Self {
+        self.flight_stress = stress;
+        self
+    }
+
+    /// Roll damage for a hit, adding what this table's crit rule gives a
+    /// critical one
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::core::dice::DamageDice;
+    /// use daggerheart_engine::rules::{CritRule, RulesConfig};
+    /// use rand::rngs::StdRng;
+    /// use rand::SeedableRng;
+    ///
+    /// let rules = RulesConfig::default().with_crit(CritRule::MaxDice);
+    /// let mut rng = StdRng::seed_from_u64(1);
+    ///
+    /// // 2d8 on a crit adds the 16 the dice could have shown
+    /// let damage = rules.roll_damage_with_rng(&DamageDice::d8(2), true, &mut rng);
+    /// assert!((18..=32).contains(&damage));
+    /// ```
+    pub fn roll_damage_with_rng<R: Rng>(
+        &self,
+        damage: &DamageDice,
+        critical: bool,
+        rng: &mut R,
+    ) -> u16 {
+        self.roll_damage_faces_with_rng(damage, critical, rng).0
+    }
+
+    /// Roll damage for a hit like [`RulesConfig::roll_damage_with_rng`],
+    /// also returning every die rolled with the face it landed on
+    pub fn roll_damage_faces_with_rng<R: Rng>(
+        &self,
+        damage: &DamageDice,
+        critical: bool,
+        rng: &mut R,
+    ) -> (u16, Vec<(Die, u8)>) {
+        let (roll, mut faces) = damage.roll_faces_with_rng(rng);
+        if !critical {
+            return (roll.total, faces);
+        }
+        let extra = match self.crit {
+            CritRule::Rolled => 0,
+            CritRule::MaxDice => damage.dice_maximum(),
+            CritRule::DoubleDice => {
+                let (second, second_faces) = damage.roll_faces_with_rng(rng);
+                faces.extend(second_faces);
+                second.rolls.iter().map(|&roll| roll as u16).sum()
+            }
+        };
+        (roll.total.saturating_add(extra), faces)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use strum::IntoEnumIterator;
+
+    #[test]
+    fn test_death_rules_narrow_the_moves() {
+        assert_eq!(
+            DeathRule::AnyMove.moves(),
+            DeathMove::iter().collect::<Vec<_>>()
+        );
+        assert_eq!(
+            DeathRule::AvoidDeathOnly.moves(),
+            vec![DeathMove::AvoidDeath]
+        );
+        assert!(DeathRule::Permadeath.moves().is_empty());
+    }
+
+    #[test]
+    fn test_crit_rules_add_damage_only_on_crits() {
+        let damage = DamageDice::d6(1).with_bonus(2);
+        for crit in [CritRule::Rolled, CritRule::MaxDice, CritRule::DoubleDice] {
+            let rules = RulesConfig::default().with_crit(crit);
+            let mut rng = StdRng::seed_from_u64(7);
+            let hit = rules.roll_damage_with_rng(&damage, false, &mut rng);
+            assert!((3..=8).contains(&hit));
+
+            let crit_damage = rules.roll_damage_with_rng(&damage, true, &mut rng);
+            let range = match crit {
+                CritRule::Rolled => 3..=8,
+                CritRule::MaxDice => 9..=14,
+                CritRule::DoubleDice => 4..=14,
+            };
+            assert!(range.contains(&crit_damage), "{:?}: {}", crit, crit_damage);
+        }
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_missing_fields_take_defaults() {
+        let rules: RulesConfig = serde_json::from_str(r#"{"crit":"MaxDice"}"#).unwrap();
+        assert_eq!(rules.crit, CritRule::MaxDice);
+        assert_eq!(rules.max_fear, DEFAULT_FEAR_MAXIMUM);
+        assert_eq!(rules.hope_mode, HopeMode::Shared);
+        assert_eq!(rules.thresholds.shift(10), 0);
+    }
+}
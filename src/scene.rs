@@ -0,0 +1,222 @@
+//! Scenes - where the table is between and during encounters
+//!
+//! Play moves through scenes: a fight, travelling through the wilds, a
+//! tense negotiation, or a rest. A `Scene` records which kind it is, the
+//! environment it plays out in, which PCs are present, and any countdowns
+//! running, so a campaign save can pick up where the table left off.
+
+use crate::combat::{Countdown, Hazard};
+use crate::social::SocialNpc;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// What kind of play a scene is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, EnumIter, Display, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum SceneKind {
+    /// An encounter is being fought
+    Combat,
+    /// Travel, investigation, and overcoming obstacles
+    Exploration,
+    /// Conversation, negotiation, and intrigue
+    Social,
+    /// Resting and working on projects
+    Downtime,
+}
+
+/// The place a scene plays out in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Environment {
+    pub name: String,
+    /// Difficulty of rolls against the environment, if it sets one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub difficulty: Option<u8>,
+    /// Features the GM can bring into play
+    #[serde(default)]
+    pub features: Vec<String>,
+    /// Dangers that strike on their own during combat here
+    #[serde(default)]
+    pub hazards: Vec<Hazard>,
+}
+
+impl Environment {
+    /// Create an environment with no Difficulty or features
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            difficulty: None,
+            features: Vec::new(),
+            hazards: Vec::new(),
+        }
+    }
+
+    /// Set the Difficulty of rolls against the environment
+    pub fn with_difficulty(mut self, difficulty: u8) -> Self {
+        self.difficulty = Some(difficulty);
+        self
+    }
+
+    /// Add a feature
+    pub fn with_feature(mut self, feature: impl Into<String>) -> Self {
+        self.features.push(feature.into());
+        self
+    }
+
+    /// Add a hazard, which encounters begun here will spring
+    pub fn with_hazard(mut self, hazard: Hazard) -> Self {
+        self.hazards.push(hazard);
+        self
+    }
+}
+
+/// A scene being played
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::scene::{Environment, Scene, SceneKind};
+///
+/// let mut scene = Scene::new("Crossing the Marsh", SceneKind::Exploration)
+///     .with_environment(Environment::new("Sunken Marsh").with_difficulty(12))
+///     .with_pc("Grom")
+///     .with_countdown("Rising tide", 2);
+///
+/// assert!(scene.tick_countdowns().is_empty());
+/// assert_eq!(scene.tick_countdowns(), vec!["Rising tide"]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Scene {
+    pub name: String,
+    pub kind: SceneKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub environment: Option<Environment>,
+    /// Names of the PCs taking part
+    #[serde(default)]
+    pub active_pcs: Vec<String>,
+    #[serde(default)]
+    pub countdowns: Vec<Countdown>,
+    /// NPCs the party is trying to win over
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub npcs: Vec<SocialNpc>,
+}
+
+impl Scene {
+    /// Create a scene with no environment, PCs, or countdowns
+    pub fn new(name: impl Into<String>, kind: SceneKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            environment: None,
+            active_pcs: Vec::new(),
+            countdowns: Vec::new(),
+            npcs: Vec::new(),
+        }
+    }
+
+    /// Set where the scene takes place
+    pub fn with_environment(mut self, environment: Environment) -> Self {
+        self.environment = Some(environment);
+        self
+    }
+
+    /// Add a PC to the scene
+    pub fn with_pc(mut self, name: impl Into<String>) -> Self {
+        self.add_pc(name);
+        self
+    }
+
+    /// Start a countdown that expires after `steps` ticks
+    pub fn with_countdown(mut self, name: impl Into<String>, steps: u8) -> Self {
+        self.countdowns.push(Countdown::new(name, steps));
+        self
+    }
+
+    /// Add an NPC to win over
+    pub fn with_npc(mut self, npc: SocialNpc) -> Self {
+        self.npcs.push(npc);
+        self
+    }
+
+    /// Find an NPC by name, ignoring case
+    pub fn npc(&self, name: &str) -> Option<&SocialNpc> {
+        self.npcs.iter().find(|n| n.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Find an NPC by name for mutation, ignoring case
+    pub fn npc_mut(&mut self, name: &str) -> Option<&mut SocialNpc> {
+        self.npcs
+            .iter_mut()
+            .find(|n| n.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Bring a PC into the scene, returning false if they were already in it
+    pub fn add_pc(&mut self, name: impl Into<String>) -> bool {
+        let name = name.into();
+        if self.has_pc(&name) {
+            return false;
+        }
+        self.active_pcs.push(name);
+        true
+    }
+
+    /// Take a PC out of the scene, returning false if they weren't in it
+    pub fn remove_pc(&mut self, name: &str) -> bool {
+        let before = self.active_pcs.len();
+        self.active_pcs.retain(|pc| pc != name);
+        self.active_pcs.len() != before
+    }
+
+    /// Whether a PC is taking part
+    pub fn has_pc(&self, name: &str) -> bool {
+        self.active_pcs.iter().any(|pc| pc == name)
+    }
+
+    /// Tick every running countdown, returning the names of those that
+    /// just expired
+    pub fn tick_countdowns(&mut self) -> Vec<String> {
+        self.countdowns
+            .iter_mut()
+            .filter_map(|countdown| countdown.tick().then(|| countdown.name.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pcs_join_once() {
+        let mut scene = Scene::new("Parley", SceneKind::Social).with_pc("Ash");
+        assert!(!scene.add_pc("Ash"));
+        assert!(scene.add_pc("Grom"));
+        assert!(scene.remove_pc("Ash"));
+        assert!(!scene.remove_pc("Ash"));
+        assert_eq!(scene.active_pcs, vec!["Grom"]);
+    }
+
+    #[test]
+    fn test_expired_countdowns_stop_reporting() {
+        let mut scene = Scene::new("Vault", SceneKind::Exploration)
+            .with_countdown("Guards", 1)
+            .with_countdown("Collapse", 2);
+        assert_eq!(scene.tick_countdowns(), vec!["Guards"]);
+        assert_eq!(scene.tick_countdowns(), vec!["Collapse"]);
+        assert!(scene.tick_countdowns().is_empty());
+    }
+
+    #[cfg(feature = "persistence")]
+    #[test]
+    fn test_scene_serialization() {
+        let scene = Scene::new("Camp", SceneKind::Downtime);
+        let json = serde_json::to_string(&scene).unwrap();
+        assert_eq!(
+            json,
+            r#"{"name":"Camp","kind":"Downtime","active_pcs":[],"countdowns":[]}"#
+        );
+        let back: Scene = serde_json::from_str(r#"{"name":"Camp","kind":"Downtime"}"#).unwrap();
+        assert_eq!(back, scene);
+    }
+}
@@ -0,0 +1,157 @@
+//! JSON Schema for persisted types
+//!
+//! Enabled with the `schema` cargo feature. External tools - web character
+//! builders, save-file validators - can check files against these schemas
+//! without linking the engine.
+//!
+//! Fields marked `#[serde(default)]` are optional in the schema, so files
+//! written by older versions still validate.
+
+use crate::campaign::Campaign;
+use crate::cards::DomainCard;
+use crate::character::{CharacterProgress, CharacterSheet};
+use crate::combat::adversary::Adversary;
+use crate::combat::replay::Replay;
+use crate::combat::simulation::{CombatEncounter, Combatant};
+use crate::content::ContentRegistry;
+use crate::items::{Shop, ShopItem};
+
+pub use schemars::schema::RootSchema;
+pub use schemars::JsonSchema;
+
+/// Names of every type with a published schema
+pub const SCHEMA_NAMES: &[&str] = &[
+    "combatant",
+    "character_progress",
+    "character_sheet",
+    "combat_encounter",
+    "replay",
+    "campaign",
+    "domain_card",
+    "adversary",
+    "shop",
+    "shop_item",
+    "content_registry",
+];
+
+/// Generate the schema for any schema-enabled type
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::combat::Combatant;
+/// use daggerheart_engine::schema::schema_for;
+///
+/// let schema = schema_for::<Combatant>();
+/// let json = serde_json::to_value(&schema).unwrap();
+/// assert_eq!(json["title"], "Combatant");
+/// ```
+pub fn schema_for<T: JsonSchema>() -> RootSchema {
+    schemars::schema_for!(T)
+}
+
+/// Look up a schema by one of the names in [`SCHEMA_NAMES`]
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::schema::schema_by_name;
+///
+/// assert!(schema_by_name("character_sheet").is_some());
+/// assert!(schema_by_name("dragon").is_none());
+/// ```
+pub fn schema_by_name(name: &str) -> Option<RootSchema> {
+    let schema = match name {
+        "combatant" => schema_for::<Combatant>(),
+        "character_progress" => schema_for::<CharacterProgress>(),
+        "character_sheet" => schema_for::<CharacterSheet>(),
+        "combat_encounter" => schema_for::<CombatEncounter>(),
+        "replay" => schema_for::<Replay>(),
+        "campaign" => schema_for::<Campaign>(),
+        "domain_card" => schema_for::<DomainCard>(),
+        "adversary" => schema_for::<Adversary>(),
+        "shop" => schema_for::<Shop>(),
+        "shop_item" => schema_for::<ShopItem>(),
+        "content_registry" => schema_for::<ContentRegistry>(),
+        _ => return None,
+    };
+    Some(schema)
+}
+
+/// Every published schema, paired with its name
+pub fn all_schemas() -> Vec<(&'static str, RootSchema)> {
+    SCHEMA_NAMES
+        .iter()
+        .filter_map(|&name| schema_by_name(name).map(|schema| (name, schema)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::{Ancestry, Class};
+    use crate::test_support::player;
+    use serde_json::Value;
+
+    fn required(schema: &RootSchema) -> Vec<String> {
+        schema
+            .schema
+            .object
+            .as_ref()
+            .map(|object| object.required.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    fn create_test_player() -> Combatant {
+        player("Grom", Class::Warrior, Ancestry::Orc)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_every_name_has_schema() {
+        assert_eq!(all_schemas().len(), SCHEMA_NAMES.len());
+    }
+
+    #[test]
+    fn test_defaulted_fields_are_optional() {
+        let required = required(&schema_for::<Combatant>());
+        assert!(required.contains(&"name".to_string()));
+        assert!(!required.contains(&"id".to_string()));
+        assert!(!required.contains(&"summon".to_string()));
+    }
+
+    #[test]
+    fn test_serialized_files_have_required_fields() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(create_test_player());
+        encounter.start();
+
+        let samples: Vec<(RootSchema, Value)> = vec![
+            (
+                schema_for::<Combatant>(),
+                serde_json::to_value(create_test_player()).unwrap(),
+            ),
+            (
+                schema_for::<CharacterSheet>(),
+                serde_json::to_value(CharacterSheet::new(create_test_player())).unwrap(),
+            ),
+            (
+                schema_for::<CombatEncounter>(),
+                serde_json::to_value(&encounter).unwrap(),
+            ),
+        ];
+
+        for (schema, value) in samples {
+            for field in required(&schema) {
+                assert!(value.get(&field).is_some(), "missing {}", field);
+            }
+        }
+    }
+
+    #[test]
+    fn test_combatant_id_is_integer() {
+        let json = serde_json::to_value(schema_for::<Combatant>()).unwrap();
+        assert_eq!(json["properties"]["id"]["type"], "integer");
+    }
+}
@@ -0,0 +1,349 @@
+//! Live session sync over WebSocket
+//!
+//! Enabled with the `realtime` feature. Clients connect to
+//! `GET /encounters/:name/live` and receive a snapshot of the encounter,
+//! then every `CombatEvent` as it happens - whether the change came from
+//! another WebSocket client or the REST endpoints. Everyone at the table
+//! sees the same state.
+//!
+//! Messages are JSON objects tagged with `type`:
+//!
+//! ```json
+//! {"type": "command", "command": {"SpendHope": {"amount": 1}}}
+//! {"type": "roll", "roller": "Grom", "modifier": 2}
+//! ```
+
+use super::{ApiResult, DualityRequest, ServerState, Shared};
+use crate::combat::events::CombatEvent;
+use crate::combat::replay::EncounterCommand;
+use crate::combat::simulation::CombatEncounter;
+use crate::core::dice::DualityResult;
+use crate::storage::{keys, Storage};
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, State};
+use axum::response::Response;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// Messages buffered per encounter before slow clients are resynced
+const CHANNEL_CAPACITY: usize = 64;
+
+/// A message from a client
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    /// Apply a command to the encounter (attack, spend Hope, next turn, ...)
+    Command { command: Box<EncounterCommand> },
+    /// Make a duality roll everyone can see
+    Roll {
+        roller: String,
+        #[serde(default)]
+        modifier: i8,
+        #[serde(default)]
+        advantage: bool,
+    },
+}
+
+/// A message to clients
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage {
+    /// Full encounter state, sent on connect and after a client falls behind
+    Snapshot { encounter: Box<CombatEncounter> },
+    /// Events appended to the encounter log
+    Events { events: Vec<CombatEvent> },
+    /// A duality roll made by someone at the table
+    Rolled {
+        roller: String,
+        result: DualityResult,
+    },
+    /// A message from this client was rejected (sent only to that client)
+    Error { message: String },
+}
+
+/// Broadcast channels, one per encounter with connected clients
+#[derive(Default)]
+pub(super) struct Hub {
+    channels: Mutex<HashMap<String, broadcast::Sender<ServerMessage>>>,
+}
+
+impl Hub {
+    /// Subscribe to an encounter's updates
+    pub(super) fn subscribe(&self, name: &str) -> broadcast::Receiver<ServerMessage> {
+        self.lock()
+            .entry(name.to_string())
+            .or_insert_with(|| broadcast::channel(CHANNEL_CAPACITY).0)
+            .subscribe()
+    }
+
+    /// Send a message to everyone watching an encounter
+    pub(super) fn publish(&self, name: &str, message: ServerMessage) {
+        let mut channels = self.lock();
+        if let Some(sender) = channels.get(name) {
+            if sender.send(message).is_err() {
+                // Last client disconnected
+                channels.remove(name);
+            }
+        }
+    }
+
+    /// Publish newly logged events, if there are any
+    pub(super) fn publish_events(&self, name: &str, events: &[CombatEvent]) {
+        if !events.is_empty() {
+            self.publish(
+                name,
+                ServerMessage::Events {
+                    events: events.to_vec(),
+                },
+            );
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, broadcast::Sender<ServerMessage>>> {
+        self.channels.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl<S: Storage> ServerState<S> {
+    /// Handle one client message, broadcasting its effects
+    ///
+    /// Returns the error to send back to the client if the message was
+    /// rejected.
+    fn handle(&self, name: &str, message: ClientMessage) -> Result<(), ServerMessage> {
+        match message {
+            ClientMessage::Command { command } => {
+                self.apply(name, &command)
+                    .map_err(|error| ServerMessage::Error {
+                        message: error.message,
+                    })?;
+            }
+            ClientMessage::Roll {
+                roller,
+                modifier,
+                advantage,
+            } => {
+                let result = DualityRequest {
+                    modifier,
+                    advantage,
+                }
+                .roll();
+                self.hub
+                    .publish(name, ServerMessage::Rolled { roller, result });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// `GET /encounters/:name/live`
+pub(super) async fn connect<S: Storage + Send + 'static>(
+    ws: WebSocketUpgrade,
+    State(state): Shared<S>,
+    Path(name): Path<String>,
+) -> ApiResult<Response> {
+    // Fail with a normal 404 before upgrading
+    let encounter = state.load(&keys::key(keys::ENCOUNTER, &name))?;
+    Ok(ws.on_upgrade(move |socket| session(socket, state, name, encounter)))
+}
+
+enum Next {
+    Incoming(Option<Result<Message, axum::Error>>),
+    Update(Result<ServerMessage, broadcast::error::RecvError>),
+}
+
+async fn session<S: Storage + Send + 'static>(
+    mut socket: WebSocket,
+    state: Arc<ServerState<S>>,
+    name: String,
+    encounter: CombatEncounter,
+) {
+    let mut updates = state.hub.subscribe(&name);
+    if send(
+        &mut socket,
+        &ServerMessage::Snapshot {
+            encounter: Box::new(encounter),
+        },
+    )
+    .await
+    .is_err()
+    {
+        return;
+    }
+
+    loop {
+        let next = tokio::select! {
+            incoming = socket.recv() => Next::Incoming(incoming),
+            update = updates.recv() => Next::Update(update),
+        };
+
+        let reply = match next {
+            Next::Incoming(Some(Ok(Message::Text(text)))) => {
+                match serde_json::from_str::<ClientMessage>(&text) {
+                    Ok(message) => state.handle(&name, message).err(),
+                    Err(error) => Some(ServerMessage::Error {
+                        message: error.to_string(),
+                    }),
+                }
+            }
+            Next::Incoming(Some(Ok(Message::Close(_)))) | Next::Incoming(None) => break,
+            Next::Incoming(Some(Ok(_))) => None,
+            Next::Incoming(Some(Err(_))) => break,
+            Next::Update(Ok(message)) => Some(message),
+            Next::Update(Err(broadcast::error::RecvError::Lagged(_))) => {
+                match state.load(&keys::key(keys::ENCOUNTER, &name)) {
+                    Ok(encounter) => Some(ServerMessage::Snapshot {
+                        encounter: Box::new(encounter),
+                    }),
+                    Err(_) => break,
+                }
+            }
+            Next::Update(Err(broadcast::error::RecvError::Closed)) => break,
+        };
+
+        if let Some(reply) = reply {
+            if send(&mut socket, &reply).await.is_err() {
+                break;
+            }
+        }
+    }
+}
+
+async fn send(socket: &mut WebSocket, message: &ServerMessage) -> Result<(), axum::Error> {
+    let text = serde_json::to_string(message).map_err(axum::Error::new)?;
+    socket.send(Message::Text(text)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::simulation::CombatantId;
+    use crate::storage::{MemoryStorage, Persist};
+
+    fn create_test_state() -> ServerState<MemoryStorage> {
+        let state = ServerState {
+            storage: Mutex::new(MemoryStorage::new()),
+            hub: Hub::default(),
+        };
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(
+            crate::combat::simulation::CombatantBuilder::adversary("Goblin")
+                .with_hp(4)
+                .with_evasion(10)
+                .build()
+                .unwrap(),
+        );
+        encounter
+            .save_to(&mut *state.lock(), "encounter.ambush")
+            .unwrap();
+        state
+    }
+
+    #[test]
+    fn test_client_message_format() {
+        let json = r#"{"type": "command", "command": {"SpendHope": {"amount": 1}}}"#;
+        assert_eq!(
+            serde_json::from_str::<ClientMessage>(json).unwrap(),
+            ClientMessage::Command {
+                command: Box::new(EncounterCommand::SpendHope {
+                    combatant: CombatantId(0),
+                    amount: 1
+                })
+            }
+        );
+
+        let json = r#"{"type": "roll", "roller": "Grom"}"#;
+        assert_eq!(
+            serde_json::from_str::<ClientMessage>(json).unwrap(),
+            ClientMessage::Roll {
+                roller: "Grom".to_string(),
+                modifier: 0,
+                advantage: false
+            }
+        );
+    }
+
+    #[test]
+    fn test_commands_broadcast_events() {
+        let state = create_test_state();
+        let mut first = state.hub.subscribe("ambush");
+        let mut second = state.hub.subscribe("ambush");
+
+        state
+            .handle(
+                "ambush",
+                ClientMessage::Command {
+                    command: Box::new(EncounterCommand::GainFear { amount: 2 }),
+                },
+            )
+            .unwrap();
+
+        for receiver in [&mut first, &mut second] {
+            match receiver.try_recv().unwrap() {
+                ServerMessage::Events { events } => {
+                    assert_eq!(events, vec![CombatEvent::FearGained { amount: 2 }]);
+                }
+                other => panic!("unexpected message: {:?}", other),
+            }
+        }
+    }
+
+    #[test]
+    fn test_rolls_broadcast() {
+        let state = create_test_state();
+        let mut receiver = state.hub.subscribe("ambush");
+
+        state
+            .handle(
+                "ambush",
+                ClientMessage::Roll {
+                    roller: "Grom".to_string(),
+                    modifier: 1,
+                    advantage: false,
+                },
+            )
+            .unwrap();
+
+        match receiver.try_recv().unwrap() {
+            ServerMessage::Rolled { roller, result } => {
+                assert_eq!(roller, "Grom");
+                assert_eq!(result.modifier, 1);
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_rejected_command_is_not_broadcast() {
+        let state = create_test_state();
+        let mut receiver = state.hub.subscribe("ambush");
+
+        let result = state.handle(
+            "ambush",
+            ClientMessage::Command {
+                command: Box::new(EncounterCommand::SpendFear { amount: 3 }),
+            },
+        );
+
+        assert!(matches!(result, Err(ServerMessage::Error { .. })));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_other_encounters_not_notified() {
+        let state = create_test_state();
+        let mut receiver = state.hub.subscribe("elsewhere");
+
+        state
+            .handle(
+                "ambush",
+                ClientMessage::Command {
+                    command: Box::new(EncounterCommand::GainFear { amount: 1 }),
+                },
+            )
+            .unwrap();
+
+        assert!(receiver.try_recv().is_err());
+    }
+}
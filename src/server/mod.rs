@@ -0,0 +1,603 @@
+//! REST API server
+//!
+//! Enabled with the `server` cargo feature. Exposes dice rolls, character
+//! creation and encounter commands over HTTP, so the engine can back a
+//! self-hosted group tool. Characters and encounters are kept in any
+//! [`Storage`] backend; `DirectoryStorage` gives a server whose state
+//! survives restarts.
+//!
+//! | Method | Path                             | Body                 |
+//! |--------|----------------------------------|----------------------|
+//! | POST   | `/roll/duality`                  | `DualityRequest`     |
+//! | POST   | `/roll/damage`                   | `DamageDice`         |
+//! | GET    | `/characters`                    |                      |
+//! | POST   | `/characters`                    | `CreateCharacter`    |
+//! | GET    | `/characters/:name`              |                      |
+//! | GET    | `/encounters`                    |                      |
+//! | POST   | `/encounters`                    | `CreateEncounter`    |
+//! | GET    | `/encounters/:name`              |                      |
+//! | POST   | `/encounters/:name/combatants`   | `AddCombatant`       |
+//! | POST   | `/encounters/:name/start`        |                      |
+//! | POST   | `/encounters/:name/next-turn`    |                      |
+//! | POST   | `/encounters/:name/commands`     | `EncounterCommand`   |
+//!
+//! Errors are returned as `{"error": "..."}` with a 4xx/5xx status.
+//!
+//! With the `realtime` feature, `GET /encounters/:name/live` upgrades to a
+//! WebSocket that streams the encounter's events; see [`live`].
+
+use crate::character::{Ancestry, Attributes, CharacterSheet, Class};
+use crate::combat::replay::EncounterCommand;
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantBuilder, CombatantId};
+use crate::core::dice::{DamageDice, DamageRoll, DualityResult, DualityRoll};
+use crate::error::EngineError;
+use crate::storage::{keys, Persist, Storage};
+use axum::extract::{Path, State};
+use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::sync::{Arc, Mutex};
+
+#[cfg(feature = "realtime")]
+pub mod live;
+
+/// Body of `POST /roll/duality`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DualityRequest {
+    #[serde(default)]
+    pub modifier: i8,
+    #[serde(default)]
+    pub advantage: bool,
+}
+
+/// Body of `POST /characters`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCharacter {
+    pub name: String,
+    pub class: Class,
+    pub ancestry: Ancestry,
+    #[serde(default = "default_level")]
+    pub level: u8,
+    pub attributes: Attributes,
+}
+
+fn default_level() -> u8 {
+    1
+}
+
+/// Body of `POST /encounters`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateEncounter {
+    pub name: String,
+    #[serde(default = "default_hope_max")]
+    pub hope_max: u8,
+}
+
+fn default_hope_max() -> u8 {
+    5
+}
+
+/// Body of `POST /encounters/:name/combatants`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AddCombatant {
+    /// A stored character, by name
+    Character(String),
+    /// A quick enemy stat line
+    Enemy {
+        name: String,
+        level: u8,
+        hp: u8,
+        evasion: u8,
+        #[serde(default)]
+        armor: u8,
+    },
+    /// A full combatant
+    Combatant(Box<Combatant>),
+}
+
+/// Response of `POST /encounters/:name/combatants`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddedCombatant {
+    pub id: CombatantId,
+}
+
+/// An error response
+#[derive(Debug)]
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<EngineError> for ApiError {
+    fn from(error: EngineError) -> Self {
+        let status = match error {
+            EngineError::UnknownCombatant { .. } => StatusCode::NOT_FOUND,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        Self::new(status, error.to_string())
+    }
+}
+
+impl From<io::Error> for ApiError {
+    fn from(error: io::Error) -> Self {
+        let status = match error.kind() {
+            io::ErrorKind::NotFound => StatusCode::NOT_FOUND,
+            io::ErrorKind::InvalidInput => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self::new(status, error.to_string())
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let body = Json(serde_json::json!({ "error": self.message }));
+        (self.status, body).into_response()
+    }
+}
+
+type ApiResult<T> = Result<T, ApiError>;
+
+/// Shared server state
+pub struct ServerState<S> {
+    storage: Mutex<S>,
+    #[cfg(feature = "realtime")]
+    hub: live::Hub,
+}
+
+impl<S: Storage> ServerState<S> {
+    fn load<T: Persist>(&self, key: &str) -> io::Result<T> {
+        T::load_from(&*self.lock(), key)
+    }
+
+    fn names(&self, prefix: &str) -> io::Result<Vec<String>> {
+        keys::names(&*self.lock(), prefix)
+    }
+
+    /// Save a new value, unless something is already stored under `key`
+    fn create<T: Persist>(&self, value: &T, key: &str, what: &str) -> ApiResult<()> {
+        let mut storage = self.lock();
+        if storage.exists(key) {
+            return Err(ApiError::new(
+                StatusCode::CONFLICT,
+                format!("{} already exists", what),
+            ));
+        }
+        value.save_to(&mut *storage, key)?;
+        Ok(())
+    }
+
+    /// Load a stored value, change it and save it back
+    ///
+    /// The storage stays locked throughout, so requests changing the same
+    /// value at once can't overwrite each other's changes. Nothing is
+    /// saved if `change` fails.
+    fn update<T: Persist, R>(
+        &self,
+        key: &str,
+        change: impl FnOnce(&mut T) -> ApiResult<R>,
+    ) -> ApiResult<(T, R)> {
+        let mut storage = self.lock();
+        let mut value = T::load_from(&*storage, key)?;
+        let result = change(&mut value)?;
+        value.save_to(&mut *storage, key)?;
+        Ok((value, result))
+    }
+
+    /// Apply a command to a stored encounter and save the result
+    fn apply(&self, name: &str, command: &EncounterCommand) -> ApiResult<CombatEncounter> {
+        let key = keys::key(keys::ENCOUNTER, name);
+        let (encounter, logged) = self.update(&key, |encounter: &mut CombatEncounter| {
+            let logged = encounter.events.len();
+            encounter.apply_command_with_rng(command, &mut rand::thread_rng())?;
+            Ok(logged)
+        })?;
+
+        #[cfg(feature = "realtime")]
+        self.hub.publish_events(name, &encounter.events[logged..]);
+        #[cfg(not(feature = "realtime"))]
+        let _ = logged;
+
+        Ok(encounter)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, S> {
+        self.storage.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+type Shared<S> = State<Arc<ServerState<S>>>;
+
+/// Build the API router over a storage backend
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::server;
+/// use daggerheart_engine::storage::MemoryStorage;
+///
+/// let app = server::router(MemoryStorage::new());
+/// ```
+pub fn router<S: Storage + Send + 'static>(storage: S) -> Router {
+    let state = Arc::new(ServerState {
+        storage: Mutex::new(storage),
+        #[cfg(feature = "realtime")]
+        hub: live::Hub::default(),
+    });
+
+    let router = Router::new()
+        .route("/roll/duality", post(roll_duality))
+        .route("/roll/damage", post(roll_damage))
+        .route(
+            "/characters",
+            get(list_characters::<S>).post(create_character::<S>),
+        )
+        .route("/characters/:name", get(get_character::<S>))
+        .route(
+            "/encounters",
+            get(list_encounters::<S>).post(create_encounter::<S>),
+        )
+        .route("/encounters/:name", get(get_encounter::<S>))
+        .route("/encounters/:name/combatants", post(add_combatant::<S>))
+        .route("/encounters/:name/start", post(start_encounter::<S>))
+        .route("/encounters/:name/next-turn", post(next_turn::<S>))
+        .route("/encounters/:name/commands", post(apply_command::<S>));
+
+    #[cfg(feature = "realtime")]
+    let router = router.route("/encounters/:name/live", get(live::connect::<S>));
+
+    router.with_state(state)
+}
+
+/// Serve the API on `addr` until the process exits
+///
+/// # Examples
+///
+/// ```no_run
+/// use daggerheart_engine::server;
+/// use daggerheart_engine::storage::DirectoryStorage;
+///
+/// # async fn run() -> std::io::Result<()> {
+/// server::serve("127.0.0.1:3000", DirectoryStorage::new("saves")?).await
+/// # }
+/// ```
+pub async fn serve<S: Storage + Send + 'static>(
+    addr: impl tokio::net::ToSocketAddrs,
+    storage: S,
+) -> io::Result<()> {
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, router(storage)).await
+}
+
+impl DualityRequest {
+    fn roll(&self) -> DualityResult {
+        let roll = DualityRoll::roll();
+        if self.advantage {
+            let mut result = roll.with_advantage();
+            result.total = (result.total as i16 + self.modifier as i16).max(0) as u16;
+            result.modifier = self.modifier;
+            result
+        } else {
+            roll.with_modifier(self.modifier)
+        }
+    }
+}
+
+async fn roll_duality(Json(request): Json<DualityRequest>) -> Json<DualityResult> {
+    Json(request.roll())
+}
+
+async fn roll_damage(Json(dice): Json<DamageDice>) -> Json<DamageRoll> {
+    Json(dice.roll())
+}
+
+async fn list_characters<S: Storage>(State(state): Shared<S>) -> ApiResult<Json<Vec<String>>> {
+    Ok(Json(state.names(keys::CHARACTER)?))
+}
+
+async fn create_character<S: Storage>(
+    State(state): Shared<S>,
+    Json(request): Json<CreateCharacter>,
+) -> ApiResult<(StatusCode, Json<CharacterSheet>)> {
+    request.attributes.validate()?;
+    let key = keys::key(keys::CHARACTER, &request.name);
+    let what = format!("Character {}", request.name);
+    let sheet = CharacterSheet::new(
+        CombatantBuilder::player(request.name, request.class, request.ancestry)
+            .with_level(request.level)
+            .with_attributes(request.attributes)
+            .build()?,
+    );
+    state.create(&sheet, &key, &what)?;
+    Ok((StatusCode::CREATED, Json(sheet)))
+}
+
+async fn get_character<S: Storage>(
+    State(state): Shared<S>,
+    Path(name): Path<String>,
+) -> ApiResult<Json<CharacterSheet>> {
+    Ok(Json(state.load(&keys::key(keys::CHARACTER, &name))?))
+}
+
+async fn list_encounters<S: Storage>(State(state): Shared<S>) -> ApiResult<Json<Vec<String>>> {
+    Ok(Json(state.names(keys::ENCOUNTER)?))
+}
+
+async fn create_encounter<S: Storage>(
+    State(state): Shared<S>,
+    Json(request): Json<CreateEncounter>,
+) -> ApiResult<(StatusCode, Json<CombatEncounter>)> {
+    let key = keys::key(keys::ENCOUNTER, &request.name);
+    let encounter = CombatEncounter::new(request.hope_max);
+    state.create(&encounter, &key, &format!("Encounter {}", request.name))?;
+    Ok((StatusCode::CREATED, Json(encounter)))
+}
+
+async fn get_encounter<S: Storage>(
+    State(state): Shared<S>,
+    Path(name): Path<String>,
+) -> ApiResult<Json<CombatEncounter>> {
+    Ok(Json(state.load(&keys::key(keys::ENCOUNTER, &name))?))
+}
+
+async fn add_combatant<S: Storage>(
+    State(state): Shared<S>,
+    Path(name): Path<String>,
+    Json(request): Json<AddCombatant>,
+) -> ApiResult<(StatusCode, Json<AddedCombatant>)> {
+    let combatant = match request {
+        AddCombatant::Character(character) => {
+            state
+                .load::<CharacterSheet>(&keys::key(keys::CHARACTER, &character))?
+                .combatant
+        }
+        AddCombatant::Enemy {
+            name,
+            level,
+            hp,
+            evasion,
+            armor,
+        } => CombatantBuilder::adversary(name)
+            .with_level(level)
+            .with_hp(hp)
+            .with_evasion(evasion)
+            .with_armor(armor)
+            .build()?,
+        AddCombatant::Combatant(combatant) => *combatant,
+    };
+
+    let encounter = state.apply(&name, &EncounterCommand::AddCombatant { combatant })?;
+    // New combatants are pushed onto the end of the list
+    let id = encounter.combatants[encounter.combatants.len() - 1].id;
+    Ok((StatusCode::CREATED, Json(AddedCombatant { id })))
+}
+
+async fn start_encounter<S: Storage>(
+    State(state): Shared<S>,
+    Path(name): Path<String>,
+) -> ApiResult<Json<CombatEncounter>> {
+    Ok(Json(state.apply(&name, &EncounterCommand::Start)?))
+}
+
+async fn next_turn<S: Storage>(
+    State(state): Shared<S>,
+    Path(name): Path<String>,
+) -> ApiResult<Json<CombatEncounter>> {
+    Ok(Json(state.apply(&name, &EncounterCommand::NextTurn)?))
+}
+
+async fn apply_command<S: Storage>(
+    State(state): Shared<S>,
+    Path(name): Path<String>,
+    Json(command): Json<EncounterCommand>,
+) -> ApiResult<Json<CombatEncounter>> {
+    Ok(Json(state.apply(&name, &command)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::combat::Countdown;
+    use crate::storage::MemoryStorage;
+    use axum::body::Body;
+    use axum::http::Request;
+    use serde_json::{json, Value};
+    use tower::ServiceExt;
+
+    async fn send(app: &Router, method: &str, uri: &str, body: Value) -> (StatusCode, Value) {
+        let request = Request::builder()
+            .method(method)
+            .uri(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let value = serde_json::from_slice(&bytes).unwrap_or(Value::Null);
+        (status, value)
+    }
+
+    async fn get(app: &Router, uri: &str) -> (StatusCode, Value) {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        (
+            status,
+            serde_json::from_slice(&bytes).unwrap_or(Value::Null),
+        )
+    }
+
+    fn grom() -> Value {
+        json!({
+            "name": "Grom",
+            "class": "Warrior",
+            "ancestry": "Orc",
+            "attributes": {
+                "agility": 2, "strength": 1, "finesse": 1,
+                "instinct": 0, "presence": 0, "knowledge": -1
+            }
+        })
+    }
+
+    #[test]
+    fn test_concurrent_commands_are_not_lost() {
+        let state = ServerState {
+            storage: Mutex::new(MemoryStorage::new()),
+            #[cfg(feature = "realtime")]
+            hub: live::Hub::default(),
+        };
+        let key = keys::key(keys::ENCOUNTER, "siege");
+        state
+            .create(&CombatEncounter::new(5), &key, "siege")
+            .unwrap();
+
+        std::thread::scope(|scope| {
+            for thread in 0..8 {
+                let state = &state;
+                scope.spawn(move || {
+                    for round in 0..25 {
+                        let countdown = Countdown::new(format!("Wave {thread}-{round}"), 3);
+                        let command = EncounterCommand::AddCountdown { countdown };
+                        state.apply("siege", &command).unwrap();
+                    }
+                });
+            }
+        });
+        let encounter: CombatEncounter = state.load(&key).unwrap();
+        assert_eq!(encounter.countdowns.len(), 200);
+    }
+
+    #[tokio::test]
+    async fn test_roll_duality() {
+        let app = router(MemoryStorage::new());
+        let (status, body) = send(&app, "POST", "/roll/duality", json!({ "modifier": 2 })).await;
+
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["modifier"], 2);
+        let total = body["total"].as_u64().unwrap();
+        assert!((4..=26).contains(&total));
+    }
+
+    #[tokio::test]
+    async fn test_roll_damage() {
+        let app = router(MemoryStorage::new());
+        let dice = serde_json::to_value(DamageDice::d6(2).with_bonus(3)).unwrap();
+        let (status, body) = send(&app, "POST", "/roll/damage", dice).await;
+
+        assert_eq!(status, StatusCode::OK);
+        let total = body["total"].as_u64().unwrap();
+        assert!((5..=15).contains(&total));
+    }
+
+    #[tokio::test]
+    async fn test_create_and_fetch_character() {
+        let app = router(MemoryStorage::new());
+
+        let (status, body) = send(&app, "POST", "/characters", grom()).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(body["combatant"]["name"], "Grom");
+
+        let (status, _) = send(&app, "POST", "/characters", grom()).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+
+        let (status, body) = get(&app, "/characters/Grom").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["progress"]["level"], 1);
+
+        let (_, body) = get(&app, "/characters").await;
+        assert_eq!(body, json!(["Grom"]));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_character_rejected() {
+        let app = router(MemoryStorage::new());
+        let mut character = grom();
+        character["attributes"]["agility"] = json!(3);
+
+        let (status, body) = send(&app, "POST", "/characters", character).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["error"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_missing_resources_are_not_found() {
+        let app = router(MemoryStorage::new());
+        assert_eq!(
+            get(&app, "/characters/Nobody").await.0,
+            StatusCode::NOT_FOUND
+        );
+        assert_eq!(
+            send(&app, "POST", "/encounters/nowhere/start", Value::Null)
+                .await
+                .0,
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[tokio::test]
+    async fn test_encounter_flow() {
+        let app = router(MemoryStorage::new());
+        send(&app, "POST", "/characters", grom()).await;
+
+        let (status, _) = send(&app, "POST", "/encounters", json!({ "name": "ambush" })).await;
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (_, hero) = send(
+            &app,
+            "POST",
+            "/encounters/ambush/combatants",
+            json!({ "character": "Grom" }),
+        )
+        .await;
+        let (_, goblin) = send(
+            &app,
+            "POST",
+            "/encounters/ambush/combatants",
+            json!({ "enemy": { "name": "Goblin", "level": 1, "hp": 4, "evasion": 10 } }),
+        )
+        .await;
+        assert_ne!(hero["id"], goblin["id"]);
+
+        let (status, body) = send(&app, "POST", "/encounters/ambush/start", Value::Null).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["round"], 1);
+
+        let command = json!({ "GainFear": { "amount": 2 } });
+        let (status, body) = send(&app, "POST", "/encounters/ambush/commands", command).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["fear"]["current"], 2);
+
+        let (_, body) = get(&app, "/encounters/ambush").await;
+        assert_eq!(body["fear"]["current"], 2);
+        assert_eq!(body["combatants"].as_array().unwrap().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_command_is_bad_request() {
+        let app = router(MemoryStorage::new());
+        send(&app, "POST", "/encounters", json!({ "name": "ambush" })).await;
+
+        let command = json!({ "SpendFear": { "amount": 3 } });
+        let (status, _) = send(&app, "POST", "/encounters/ambush/commands", command).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,302 @@
+//! Social conflict - winning over NPCs without a fight
+//!
+//! In a social encounter the party works on an NPC with Presence rolls
+//! against the NPC's Difficulty. Successes mark the NPC's resolve track;
+//! once it is full the NPC gives way and warms to the party. Rolls with
+//! Fear put the NPC under pressure instead, and when their pressure pool
+//! fills they sour on the party. Like any other action roll, each roll
+//! gives its PC Hope or the GM Fear.
+
+use crate::core::dice::{ControllingDie, DualityResult, DualityRoll, SuccessType};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use strum_macros::{Display, EnumIter};
+
+/// Resolve an NPC has unless given another amount
+pub const DEFAULT_RESOLVE: u8 = 4;
+
+/// Pressure an NPC can hold unless given another amount
+pub const DEFAULT_PRESSURE: u8 = 3;
+
+/// How an NPC feels about the party, from worst to best
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Default,
+    EnumIter,
+    Display,
+    Serialize,
+    Deserialize,
+)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum Attitude {
+    Hostile,
+    Unfriendly,
+    #[default]
+    Neutral,
+    Friendly,
+    Allied,
+}
+
+impl Attitude {
+    /// One step warmer, stopping at Allied
+    pub fn improve(self) -> Self {
+        match self {
+            Attitude::Hostile => Attitude::Unfriendly,
+            Attitude::Unfriendly => Attitude::Neutral,
+            Attitude::Neutral => Attitude::Friendly,
+            Attitude::Friendly | Attitude::Allied => Attitude::Allied,
+        }
+    }
+
+    /// One step colder, stopping at Hostile
+    pub fn worsen(self) -> Self {
+        match self {
+            Attitude::Allied => Attitude::Friendly,
+            Attitude::Friendly => Attitude::Neutral,
+            Attitude::Neutral => Attitude::Unfriendly,
+            Attitude::Unfriendly | Attitude::Hostile => Attitude::Hostile,
+        }
+    }
+}
+
+/// An NPC the party is trying to win over
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::core::dice::DualityRoll;
+/// use daggerheart_engine::social::{Attitude, SocialNpc};
+///
+/// let mut baron = SocialNpc::new("Baron Ashgrove", 13).with_resolve(3);
+///
+/// // A success with Hope marks two resolve and gives the PC a Hope
+/// let outcome = baron.resolve(DualityRoll::from_values(10, 4).with_modifier(1));
+/// assert_eq!(outcome.resolve_marked, 2);
+/// assert_eq!(outcome.hope_gained, 1);
+///
+/// // A success with Fear marks the last one, but the GM gains a Fear
+/// let outcome = baron.resolve(DualityRoll::from_values(6, 9).with_modifier(1));
+/// assert!(outcome.swayed);
+/// assert_eq!(outcome.fear_gained, 1);
+/// assert_eq!(baron.attitude, Attitude::Friendly);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SocialNpc {
+    pub name: String,
+    /// Difficulty of Presence rolls against the NPC
+    pub difficulty: u16,
+    #[serde(default)]
+    pub attitude: Attitude,
+    /// Segments of resolve to mark before the NPC gives way
+    pub resolve: u8,
+    pub resolve_marked: u8,
+    /// Pressure the NPC can take before their attitude worsens
+    pub pressure_maximum: u8,
+    pub pressure: u8,
+}
+
+/// What one roll did to an NPC
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SocialOutcome {
+    pub npc: String,
+    pub result: DualityResult,
+    pub difficulty: u16,
+    pub outcome: SuccessType,
+    /// Resolve marked by this roll
+    pub resolve_marked: u8,
+    /// Pressure the NPC took from this roll
+    pub pressure_gained: u8,
+    /// Whether this roll filled the resolve track
+    pub swayed: bool,
+    /// Whether the NPC's pressure boiled over, worsening their attitude
+    pub soured: bool,
+    /// The NPC's attitude after the roll
+    pub attitude: Attitude,
+    /// Hope the rolling PC gains
+    pub hope_gained: u8,
+    /// Fear the GM gains
+    pub fear_gained: u8,
+    /// Stress the rolling PC clears, from a critical success
+    pub stress_cleared: u8,
+}
+
+impl SocialNpc {
+    /// Create a Neutral NPC with the default resolve and pressure
+    pub fn new(name: impl Into<String>, difficulty: u16) -> Self {
+        Self {
+            name: name.into(),
+            difficulty,
+            attitude: Attitude::default(),
+            resolve: DEFAULT_RESOLVE,
+            resolve_marked: 0,
+            pressure_maximum: DEFAULT_PRESSURE,
+            pressure: 0,
+        }
+    }
+
+    /// Set the starting attitude
+    pub fn with_attitude(mut self, attitude: Attitude) -> Self {
+        self.attitude = attitude;
+        self
+    }
+
+    /// Set how much resolve must be marked to sway the NPC
+    pub fn with_resolve(mut self, resolve: u8) -> Self {
+        self.resolve = resolve.max(1);
+        self
+    }
+
+    /// Set how much pressure the NPC can take
+    pub fn with_pressure(mut self, maximum: u8) -> Self {
+        self.pressure_maximum = maximum.max(1);
+        self
+    }
+
+    /// Whether the resolve track is full
+    pub fn is_swayed(&self) -> bool {
+        self.resolve_marked >= self.resolve
+    }
+
+    /// Resolve left to mark
+    pub fn resolve_remaining(&self) -> u8 {
+        self.resolve.saturating_sub(self.resolve_marked)
+    }
+
+    /// Roll Presence against the NPC
+    pub fn persuade(&mut self, presence: i8) -> SocialOutcome {
+        let mut rng = rand::thread_rng();
+        self.persuade_with_rng(presence, &mut rng)
+    }
+
+    /// Roll Presence against the NPC with a specific RNG (for testing)
+    pub fn persuade_with_rng<R: Rng>(&mut self, presence: i8, rng: &mut R) -> SocialOutcome {
+        self.resolve(DualityRoll::roll_with_rng(rng).with_modifier(presence))
+    }
+
+    /// Apply an already rolled result to the NPC
+    ///
+    /// A success marks one resolve with Fear, two with Hope, and three on
+    /// a critical. A roll with Fear adds a pressure, or two on a failure;
+    /// when the pool fills, the NPC's attitude worsens and the pool
+    /// empties. Filling the resolve track improves their attitude once.
+    pub fn resolve(&mut self, result: DualityResult) -> SocialOutcome {
+        let outcome = result.success_type(self.difficulty);
+        let was_swayed = self.is_swayed();
+
+        let marks = match outcome {
+            SuccessType::Failure => 0,
+            SuccessType::SuccessWithFear => 1,
+            SuccessType::SuccessWithHope => 2,
+            SuccessType::CriticalSuccess => 3,
+        };
+        let resolve_marked = marks.min(self.resolve_remaining());
+        self.resolve_marked += resolve_marked;
+        let swayed = !was_swayed && self.is_swayed();
+        if swayed {
+            self.attitude = self.attitude.improve();
+        }
+
+        let with_fear = !result.is_critical && result.controlling == ControllingDie::Fear;
+        let pressure_gained = match (with_fear, outcome) {
+            (false, _) => 0,
+            (true, SuccessType::Failure) => 2,
+            (true, _) => 1,
+        };
+        self.pressure = self.pressure.saturating_add(pressure_gained);
+        let soured = self.pressure >= self.pressure_maximum;
+        if soured {
+            self.pressure = 0;
+            self.attitude = self.attitude.worsen();
+        }
+
+        SocialOutcome {
+            npc: self.name.clone(),
+            difficulty: self.difficulty,
+            outcome,
+            resolve_marked,
+            pressure_gained,
+            swayed,
+            soured,
+            attitude: self.attitude,
+            hope_gained: u8::from(!with_fear),
+            fear_gained: u8::from(with_fear),
+            stress_cleared: u8::from(result.is_critical),
+            result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attitude_steps_stop_at_the_ends() {
+        assert_eq!(Attitude::Allied.improve(), Attitude::Allied);
+        assert_eq!(Attitude::Hostile.worsen(), Attitude::Hostile);
+        assert_eq!(Attitude::Neutral.improve().worsen(), Attitude::Neutral);
+        assert!(Attitude::Friendly > Attitude::Unfriendly);
+    }
+
+    #[test]
+    fn test_failures_with_fear_sour_the_npc() {
+        let mut guard = SocialNpc::new("Gate Guard", 14).with_pressure(3);
+
+        let outcome = guard.resolve(DualityRoll::from_values(2, 5).with_modifier(0));
+        assert_eq!(outcome.outcome, SuccessType::Failure);
+        assert_eq!(outcome.pressure_gained, 2);
+        assert!(!outcome.soured);
+
+        let outcome = guard.resolve(DualityRoll::from_values(9, 10).with_modifier(0));
+        assert!(outcome.soured);
+        assert_eq!(guard.pressure, 0);
+        assert_eq!(guard.attitude, Attitude::Unfriendly);
+        assert_eq!(guard.resolve_marked, 1);
+    }
+
+    #[test]
+    fn test_failure_with_hope_still_gives_hope() {
+        let mut guard = SocialNpc::new("Gate Guard", 14);
+        let outcome = guard.resolve(DualityRoll::from_values(5, 2).with_modifier(0));
+        assert_eq!(outcome.outcome, SuccessType::Failure);
+        assert_eq!((outcome.hope_gained, outcome.fear_gained), (1, 0));
+        assert_eq!(outcome.pressure_gained, 0);
+    }
+
+    #[test]
+    fn test_sway_happens_once() {
+        let mut merchant = SocialNpc::new("Merchant", 10)
+            .with_resolve(2)
+            .with_attitude(Attitude::Unfriendly);
+        let outcome = merchant.resolve(DualityRoll::from_values(6, 6).with_modifier(0));
+        assert!(outcome.swayed);
+        assert_eq!(outcome.resolve_marked, 2);
+        assert_eq!(outcome.stress_cleared, 1);
+        assert_eq!(merchant.attitude, Attitude::Neutral);
+
+        let outcome = merchant.resolve(DualityRoll::from_values(11, 3).with_modifier(0));
+        assert!(!outcome.swayed);
+        assert_eq!(outcome.resolve_marked, 0);
+        assert_eq!(merchant.attitude, Attitude::Neutral);
+    }
+
+    #[test]
+    fn test_persuade_is_seeded() {
+        use rand::SeedableRng;
+
+        let mut a = SocialNpc::new("Sage", 12);
+        let mut b = a.clone();
+        let first = a.persuade_with_rng(2, &mut rand::rngs::StdRng::seed_from_u64(3));
+        let second = b.persuade_with_rng(2, &mut rand::rngs::StdRng::seed_from_u64(3));
+        assert_eq!(first, second);
+        assert_eq!(first.result.modifier, 2);
+    }
+}
@@ -0,0 +1,216 @@
+//! Autosave - saving interactive sessions without being asked
+//!
+//! An [`AutosavePolicy`] says when a session saves on its own: after every
+//! command that changed something, at most once every few seconds, or only
+//! when it ends. [`Autosave`] tracks unsaved changes against a policy and
+//! tells the session loop when to write, so rapid edits under an interval
+//! are saved together rather than one file write each. Every policy saves
+//! unsaved changes on quit.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+/// When an interactive session saves on its own
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum AutosavePolicy {
+    /// After each command that changed something
+    EveryCommand,
+    /// Changes are saved at most once every so many seconds
+    Interval { seconds: u32 },
+    /// Only when the session ends
+    #[default]
+    OnQuit,
+}
+
+impl fmt::Display for AutosavePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AutosavePolicy::EveryCommand => write!(f, "command"),
+            AutosavePolicy::Interval { seconds } => write!(f, "{}s", seconds),
+            AutosavePolicy::OnQuit => write!(f, "quit"),
+        }
+    }
+}
+
+impl FromStr for AutosavePolicy {
+    type Err = String;
+
+    /// Parse `command`, `quit`, or a number of seconds such as `30s`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::storage::AutosavePolicy;
+    ///
+    /// assert_eq!("command".parse(), Ok(AutosavePolicy::EveryCommand));
+    /// assert_eq!("30s".parse(), Ok(AutosavePolicy::Interval { seconds: 30 }));
+    /// assert_eq!("quit".parse(), Ok(AutosavePolicy::OnQuit));
+    /// ```
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        match s.as_str() {
+            "command" | "every-command" => Ok(AutosavePolicy::EveryCommand),
+            "quit" | "on-quit" => Ok(AutosavePolicy::OnQuit),
+            _ => match s.strip_suffix('s').unwrap_or(&s).parse() {
+                Ok(seconds) if seconds > 0 => Ok(AutosavePolicy::Interval { seconds }),
+                _ => Err(format!(
+                    "Unknown autosave policy: {}. Use command, quit, or seconds like 30s",
+                    s
+                )),
+            },
+        }
+    }
+}
+
+/// Unsaved changes in a session, checked against its policy
+///
+/// Times are passed in, so the session loop decides what "now" is.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::storage::{Autosave, AutosavePolicy};
+/// use std::time::{Duration, Instant};
+///
+/// let start = Instant::now();
+/// let mut autosave = Autosave::new(AutosavePolicy::Interval { seconds: 10 }, start);
+///
+/// // A change right after a save waits for the interval
+/// assert!(!autosave.changed(start + Duration::from_secs(1)));
+/// assert!(!autosave.is_due(start + Duration::from_secs(5)));
+/// assert!(autosave.is_due(start + Duration::from_secs(10)));
+///
+/// autosave.saved(start + Duration::from_secs(10));
+/// assert!(!autosave.on_quit());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Autosave {
+    policy: AutosavePolicy,
+    last_save: Instant,
+    dirty: bool,
+}
+
+impl Autosave {
+    /// Start tracking a session whose file was last written at `now`
+    pub fn new(policy: AutosavePolicy, now: Instant) -> Self {
+        Self {
+            policy,
+            last_save: now,
+            dirty: false,
+        }
+    }
+
+    /// The policy being followed
+    pub fn policy(&self) -> AutosavePolicy {
+        self.policy
+    }
+
+    /// Whether there are changes not yet saved
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Note a change, returning whether to save now
+    pub fn changed(&mut self, now: Instant) -> bool {
+        self.dirty = true;
+        match self.policy {
+            AutosavePolicy::EveryCommand => true,
+            _ => self.is_due(now),
+        }
+    }
+
+    /// Whether unsaved changes have waited out the interval
+    ///
+    /// Call this while the session is idle, so the last changes before a
+    /// pause are saved too.
+    pub fn is_due(&self, now: Instant) -> bool {
+        match self.policy {
+            AutosavePolicy::EveryCommand => self.dirty,
+            AutosavePolicy::Interval { seconds } => {
+                self.dirty && now.duration_since(self.last_save) >= interval(seconds)
+            }
+            AutosavePolicy::OnQuit => false,
+        }
+    }
+
+    /// How long the session can wait for input before the next save falls
+    /// due, if one is pending
+    pub fn wait(&self, now: Instant) -> Option<Duration> {
+        match self.policy {
+            AutosavePolicy::Interval { seconds } if self.dirty => {
+                let due = self.last_save + interval(seconds);
+                Some(due.saturating_duration_since(now))
+            }
+            _ => None,
+        }
+    }
+
+    /// Record a save at `now`
+    pub fn saved(&mut self, now: Instant) {
+        self.dirty = false;
+        self.last_save = now;
+    }
+
+    /// Whether to save as the session ends
+    pub fn on_quit(&self) -> bool {
+        self.dirty
+    }
+}
+
+fn interval(seconds: u32) -> Duration {
+    Duration::from_secs(u64::from(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_command_saves_each_change() {
+        let now = Instant::now();
+        let mut autosave = Autosave::new(AutosavePolicy::EveryCommand, now);
+        assert!(!autosave.is_due(now));
+        assert!(autosave.changed(now));
+        autosave.saved(now);
+        assert!(!autosave.on_quit());
+        assert_eq!(autosave.wait(now), None);
+    }
+
+    #[test]
+    fn test_intervals_batch_changes() {
+        let start = Instant::now();
+        let policy = AutosavePolicy::Interval { seconds: 30 };
+        let mut autosave = Autosave::new(policy, start);
+        assert_eq!(autosave.wait(start), None);
+
+        let later = start + Duration::from_secs(20);
+        assert!(!autosave.changed(later));
+        assert_eq!(autosave.wait(later), Some(Duration::from_secs(10)));
+        assert!(autosave.changed(start + Duration::from_secs(31)));
+    }
+
+    #[test]
+    fn test_on_quit_waits_for_the_end() {
+        let start = Instant::now();
+        let mut autosave = Autosave::new(AutosavePolicy::OnQuit, start);
+        assert!(!autosave.changed(start + Duration::from_secs(3600)));
+        assert!(!autosave.is_due(start + Duration::from_secs(7200)));
+        assert!(autosave.on_quit());
+    }
+
+    #[test]
+    fn test_policy_text_roundtrip() {
+        for policy in [
+            AutosavePolicy::EveryCommand,
+            AutosavePolicy::Interval { seconds: 45 },
+            AutosavePolicy::OnQuit,
+        ] {
+            assert_eq!(policy.to_string().parse(), Ok(policy));
+        }
+        assert!("0s".parse::<AutosavePolicy>().is_err());
+        assert!("sometimes".parse::<AutosavePolicy>().is_err());
+    }
+}
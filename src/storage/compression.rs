@@ -0,0 +1,200 @@
+//! Compression - smaller files for big saves
+//!
+//! Campaigns with a long roll log grow large. Saves whose name ends in
+//! `.gz` or `.zst` are compressed with gzip or zstd as they are written,
+//! and every load recognizes compressed bytes by their header, whatever
+//! the file is called. Gzip needs the `gzip` feature and zstd the `zstd`
+//! feature; without them those files are refused with an error naming
+//! the feature.
+
+use std::io;
+#[cfg(feature = "gzip")]
+use std::io::{Read, Write};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Every gzip stream starts with these bytes
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+/// Every zstd frame starts with these bytes
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// How a save file's bytes are compressed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    #[default]
+    None,
+    /// Requires the `gzip` feature
+    Gzip,
+    /// Requires the `zstd` feature
+    Zstd,
+}
+
+impl Compression {
+    /// Pick the compression from a file's last extension
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::storage::Compression;
+    ///
+    /// assert_eq!(Compression::from_path("witherwild.json.gz"), Compression::Gzip);
+    /// assert_eq!(Compression::from_path("witherwild.yaml.zst"), Compression::Zstd);
+    /// assert_eq!(Compression::from_path("witherwild.json"), Compression::None);
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        match path
+            .as_ref()
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .as_deref()
+        {
+            Some("gz") => Compression::Gzip,
+            Some("zst") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// Recognize compressed bytes by their header
+    pub fn detect(bytes: &[u8]) -> Self {
+        if bytes.starts_with(&GZIP_MAGIC) {
+            Compression::Gzip
+        } else if bytes.starts_with(&ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+
+    /// Conventional file extension, added after the format's
+    pub fn extension(&self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gz"),
+            Compression::Zstd => Some("zst"),
+        }
+    }
+
+    /// Whether this build can compress and decompress this way
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Compression::None => true,
+            Compression::Gzip => cfg!(feature = "gzip"),
+            Compression::Zstd => cfg!(feature = "zstd"),
+        }
+    }
+
+    /// Compress bytes for writing
+    pub fn compress(&self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(bytes.to_vec()),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::encode_all(bytes, 0),
+            #[allow(unreachable_patterns)]
+            _ => Err(self.unsupported()),
+        }
+    }
+
+    /// Decompress bytes that were read
+    pub fn decompress(&self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(bytes.to_vec()),
+            #[cfg(feature = "gzip")]
+            Compression::Gzip => {
+                let mut decompressed = Vec::new();
+                flate2::read::GzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+                Ok(decompressed)
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::decode_all(bytes),
+            #[allow(unreachable_patterns)]
+            _ => Err(self.unsupported()),
+        }
+    }
+
+    fn unsupported(&self) -> io::Error {
+        let feature = match self {
+            Compression::Gzip => "gzip",
+            _ => "zstd",
+        };
+        io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "{} support is not enabled (build with the `{}` feature)",
+                feature.to_uppercase(),
+                feature
+            ),
+        )
+    }
+}
+
+impl FromStr for Compression {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "none" => Ok(Compression::None),
+            "gzip" | "gz" => Ok(Compression::Gzip),
+            "zstd" | "zst" => Ok(Compression::Zstd),
+            _ => Err(format!(
+                "Unknown compression: {}. Use none, gzip, or zstd",
+                s
+            )),
+        }
+    }
+}
+
+/// Decompress whatever was read, if it was compressed
+pub(crate) fn decompressed(bytes: Vec<u8>) -> io::Result<Vec<u8>> {
+    match Compression::detect(&bytes) {
+        Compression::None => Ok(bytes),
+        compression => compression.decompress(&bytes),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_is_not_mistaken_for_compression() {
+        assert_eq!(Compression::detect(b"{\"level\": 1}"), Compression::None);
+        assert_eq!(Compression::detect(b""), Compression::None);
+        assert_eq!(decompressed(b"name: Grom".to_vec()).unwrap(), b"name: Grom");
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn test_gzip_unsupported_without_feature() {
+        let error = Compression::Gzip.compress(b"{}").unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::Unsupported);
+        assert!(error.to_string().contains("`gzip` feature"));
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_gzip_roundtrip() {
+        let text = "{\"roll_log\": []}".repeat(100);
+        let compressed = Compression::Gzip.compress(text.as_bytes()).unwrap();
+        assert!(compressed.len() < text.len());
+        assert_eq!(Compression::detect(&compressed), Compression::Gzip);
+        assert_eq!(decompressed(compressed).unwrap(), text.as_bytes());
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd_roundtrip() {
+        let text = "{\"roll_log\": []}".repeat(100);
+        let compressed = Compression::Zstd.compress(text.as_bytes()).unwrap();
+        assert!(compressed.len() < text.len());
+        assert_eq!(Compression::detect(&compressed), Compression::Zstd);
+        assert_eq!(decompressed(compressed).unwrap(), text.as_bytes());
+    }
+}
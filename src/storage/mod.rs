@@ -0,0 +1,1172 @@
+//! Persistence backends
+//!
+//! Game state is saved through two traits:
+//! - `Persist` turns a value into bytes and back, via any `io::Write` or
+//!   `io::Read`
+//! - `Storage` decides where those bytes live (memory, a directory, or a
+//!   custom backend such as browser local storage or a database)
+//!
+//! The `save_to_file`/`load_from_file` helpers on individual types are thin
+//! wrappers around [`save_file`] and [`load_file`], which report failures as
+//! `EngineError` with the file's path attached. Files are written with
+//! [`write_atomic`], so a crash mid-save leaves the old file rather than half
+//! of the new one, and [`save_file_with_backups`] keeps earlier versions as
+//! `.bak` files for [`restore_backup`]. Interactive sessions decide when
+//! to save with an [`AutosavePolicy`].
+//!
+//! Files named `.gz` or `.zst` are written compressed, and every load
+//! recognizes compressed bytes whatever the file is called; see
+//! [`Compression`]. These need the `gzip` and `zstd` features.
+//!
+//! JSON is always available. YAML and TOML are enabled with the `yaml` and
+//! `toml` cargo features, and compact MessagePack with the `msgpack` feature.
+//! The `sqlite` feature adds a relational campaign store in [`sqlite`].
+
+pub mod autosave;
+pub mod compression;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+pub use autosave::{Autosave, AutosavePolicy};
+pub use compression::Compression;
+
+use crate::error::EngineError;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+/// Text formats game data can be written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    #[default]
+    Json,
+    /// Requires the `yaml` feature
+    Yaml,
+    /// Requires the `toml` feature
+    Toml,
+}
+
+impl Format {
+    /// Guess the format from a file extension, defaulting to JSON
+    ///
+    /// A compression extension is looked past, so `grom.yaml.gz` is YAML.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::storage::Format;
+    ///
+    /// assert_eq!(Format::from_path("grom.yaml"), Format::Yaml);
+    /// assert_eq!(Format::from_path("grom.toml"), Format::Toml);
+    /// assert_eq!(Format::from_path("grom.json"), Format::Json);
+    /// assert_eq!(Format::from_path("grom"), Format::Json);
+    /// assert_eq!(Format::from_path("grom.toml.zst"), Format::Toml);
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        let path = match Compression::from_path(path) {
+            Compression::None => path,
+            _ => Path::new(path.file_stem().unwrap_or_default()),
+        };
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_lowercase())
+            .as_deref()
+        {
+            Some("yaml") | Some("yml") => Format::Yaml,
+            Some("toml") => Format::Toml,
+            _ => Format::Json,
+        }
+    }
+
+    /// Conventional file extension for this format
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Format::Json => "json",
+            Format::Yaml => "yaml",
+            Format::Toml => "toml",
+        }
+    }
+
+    /// Whether this build can read and write the format
+    pub fn is_supported(&self) -> bool {
+        match self {
+            Format::Json => true,
+            Format::Yaml => cfg!(feature = "yaml"),
+            Format::Toml => cfg!(feature = "toml"),
+        }
+    }
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(Format::Json),
+            "yaml" | "yml" => Ok(Format::Yaml),
+            "toml" => Ok(Format::Toml),
+            _ => Err(format!("Unknown format: {}. Use json, yaml, or toml", s)),
+        }
+    }
+}
+
+impl std::fmt::Display for Format {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.extension())
+    }
+}
+
+/// A key-value store for serialized game data
+pub trait Storage {
+    /// Store bytes under `key`, replacing any previous value
+    fn write(&mut self, key: &str, bytes: &[u8]) -> io::Result<()>;
+
+    /// Read the bytes stored under `key`
+    ///
+    /// Returns an `io::ErrorKind::NotFound` error if the key doesn't exist.
+    fn read(&self, key: &str) -> io::Result<Vec<u8>>;
+
+    /// Check whether `key` has a value
+    fn exists(&self, key: &str) -> bool;
+
+    /// Delete the value stored under `key`
+    fn remove(&mut self, key: &str) -> io::Result<()>;
+
+    /// List every stored key
+    fn keys(&self) -> io::Result<Vec<String>>;
+}
+
+/// A game type that can be saved and loaded
+///
+/// Values are stored as pretty-printed JSON.
+pub trait Persist: Serialize + DeserializeOwned {
+    /// Validate or upgrade a freshly loaded value
+    ///
+    /// Override this to migrate old save formats or reject unsupported ones.
+    fn after_load(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Serialize to any writer
+    fn write_to<W: Write>(&self, writer: W) -> io::Result<()> {
+        serde_json::to_writer_pretty(writer, self).map_err(io::Error::from)
+    }
+
+    /// Deserialize from any reader
+    fn read_from<R: Read>(reader: R) -> io::Result<Self> {
+        let mut value: Self = serde_json::from_reader(reader).map_err(io::Error::from)?;
+        value.after_load()?;
+        Ok(value)
+    }
+
+    /// Serialize to a string in the given format
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::CharacterProgress;
+    /// use daggerheart_engine::storage::{Format, Persist};
+    ///
+    /// let json = CharacterProgress::new().to_format(Format::Json).unwrap();
+    /// assert!(json.contains("\"level\": 1"));
+    /// ```
+    fn to_format(&self, format: Format) -> io::Result<String> {
+        match format {
+            Format::Json => serde_json::to_string_pretty(self).map_err(io::Error::from),
+            #[cfg(feature = "yaml")]
+            Format::Yaml => self.to_yaml(),
+            #[cfg(feature = "toml")]
+            Format::Toml => self.to_toml(),
+            #[allow(unreachable_patterns)]
+            _ => Err(unsupported(format)),
+        }
+    }
+
+    /// Deserialize from a string in the given format
+    fn from_format(text: &str, format: Format) -> io::Result<Self> {
+        match format {
+            Format::Json => Self::read_from(text.as_bytes()),
+            #[cfg(feature = "yaml")]
+            Format::Yaml => Self::from_yaml(text),
+            #[cfg(feature = "toml")]
+            Format::Toml => Self::from_toml(text),
+            #[allow(unreachable_patterns)]
+            _ => Err(unsupported(format)),
+        }
+    }
+
+    /// Serialize to YAML
+    #[cfg(feature = "yaml")]
+    fn to_yaml(&self) -> io::Result<String> {
+        serde_yaml::to_string(self).map_err(invalid_data)
+    }
+
+    /// Deserialize from YAML
+    #[cfg(feature = "yaml")]
+    fn from_yaml(text: &str) -> io::Result<Self> {
+        let mut value: Self = serde_yaml::from_str(text).map_err(invalid_data)?;
+        value.after_load()?;
+        Ok(value)
+    }
+
+    /// Serialize to TOML
+    #[cfg(feature = "toml")]
+    fn to_toml(&self) -> io::Result<String> {
+        toml::to_string_pretty(self).map_err(invalid_data)
+    }
+
+    /// Deserialize from TOML
+    #[cfg(feature = "toml")]
+    fn from_toml(text: &str) -> io::Result<Self> {
+        let mut value: Self = toml::from_str(text).map_err(invalid_data)?;
+        value.after_load()?;
+        Ok(value)
+    }
+
+    /// Serialize to compact MessagePack bytes
+    ///
+    /// Field names are kept, so saves stay readable by newer versions that
+    /// add defaulted fields.
+    #[cfg(feature = "msgpack")]
+    fn to_msgpack(&self) -> io::Result<Vec<u8>> {
+        rmp_serde::to_vec_named(self).map_err(invalid_data)
+    }
+
+    /// Deserialize from MessagePack bytes
+    #[cfg(feature = "msgpack")]
+    fn from_msgpack(bytes: &[u8]) -> io::Result<Self> {
+        let mut value: Self = rmp_serde::from_slice(bytes).map_err(invalid_data)?;
+        value.after_load()?;
+        Ok(value)
+    }
+
+    /// Save under `key` in a storage backend as MessagePack
+    #[cfg(feature = "msgpack")]
+    fn save_msgpack_to<S: Storage + ?Sized>(&self, storage: &mut S, key: &str) -> io::Result<()> {
+        storage.write(key, &self.to_msgpack()?)
+    }
+
+    /// Load a MessagePack value stored under `key`
+    #[cfg(feature = "msgpack")]
+    fn load_msgpack_from<S: Storage + ?Sized>(storage: &S, key: &str) -> io::Result<Self> {
+        Self::from_msgpack(&compression::decompressed(storage.read(key)?)?)
+    }
+
+    /// Save under `key` in a storage backend
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::CharacterProgress;
+    /// use daggerheart_engine::storage::{MemoryStorage, Persist};
+    ///
+    /// let mut storage = MemoryStorage::new();
+    /// let mut progress = CharacterProgress::new();
+    /// progress.add_experience(50);
+    ///
+    /// progress.save_to(&mut storage, "grom_progress").unwrap();
+    /// let loaded = CharacterProgress::load_from(&storage, "grom_progress").unwrap();
+    /// assert_eq!(loaded.experience, 50);
+    /// ```
+    fn save_to<S: Storage + ?Sized>(&self, storage: &mut S, key: &str) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        self.write_to(&mut bytes)?;
+        storage.write(key, &bytes)
+    }
+
+    /// Load the value stored under `key` in a storage backend, compressed
+    /// or not
+    fn load_from<S: Storage + ?Sized>(storage: &S, key: &str) -> io::Result<Self> {
+        let bytes = compression::decompressed(storage.read(key)?)?;
+        Self::read_from(bytes.as_slice())
+    }
+}
+
+/// Key naming for stores that hold several kinds of data
+///
+/// The REST server and GraphQL schema keep characters, encounters and so on
+/// side by side in one `Storage`, told apart by a key prefix.
+pub mod keys {
+    use super::Storage;
+    use std::io;
+
+    pub const CAMPAIGN: &str = "campaign.";
+    pub const CHARACTER: &str = "character.";
+    pub const ENCOUNTER: &str = "encounter.";
+    pub const CARD: &str = "card.";
+
+    /// Storage key for `name` under `prefix`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::storage::keys;
+    ///
+    /// assert_eq!(keys::key(keys::CHARACTER, "Grom"), "character.Grom");
+    /// ```
+    pub fn key(prefix: &str, name: &str) -> String {
+        format!("{}{}", prefix, name)
+    }
+
+    /// Names of everything stored under `prefix`
+    pub fn names<S: Storage + ?Sized>(storage: &S, prefix: &str) -> io::Result<Vec<String>> {
+        Ok(storage
+            .keys()?
+            .into_iter()
+            .filter_map(|key| key.strip_prefix(prefix).map(str::to_string))
+            .collect())
+    }
+}
+
+/// Save a value to a file on disk
+///
+/// The file is compressed if its name ends in `.gz` or `.zst`. Errors
+/// name the file and keep the underlying I/O or JSON error as their
+/// source.
+pub fn save_file<T: Persist>(value: &T, path: impl AsRef<Path>) -> Result<(), EngineError> {
+    let path = path.as_ref();
+    save_file_compressed(value, path, Format::Json, Compression::from_path(path))
+}
+
+/// Load a value from a file on disk
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::CharacterProgress;
+/// use daggerheart_engine::storage;
+/// use daggerheart_engine::EngineError;
+///
+/// let path = std::env::temp_dir().join("daggerheart_load_file_doc.json");
+/// std::fs::write(&path, "{ not json").unwrap();
+///
+/// let error = storage::load_file::<CharacterProgress>(&path).unwrap_err();
+/// assert_eq!(error.path(), Some(path.as_path()));
+/// assert!(matches!(
+///     error,
+///     EngineError::File { source, .. } if matches!(*source, EngineError::Serialization(_))
+/// ));
+/// # std::fs::remove_file(&path).ok();
+/// ```
+pub fn load_file<T: Persist>(path: impl AsRef<Path>) -> Result<T, EngineError> {
+    let path = path.as_ref();
+    in_file(path, || {
+        let bytes = compression::decompressed(std::fs::read(path)?)?;
+        let mut value: T = serde_json::from_slice(&bytes)?;
+        value.after_load()?;
+        Ok(value)
+    })
+}
+
+/// Save a value to a file on disk in a specific format, compressed if
+/// the file's name ends in `.gz` or `.zst`
+pub fn save_file_as<T: Persist>(
+    value: &T,
+    path: impl AsRef<Path>,
+    format: Format,
+) -> Result<(), EngineError> {
+    let path = path.as_ref();
+    save_file_compressed(value, path, format, Compression::from_path(path))
+}
+
+/// Save a value to a file on disk in a specific format and compression,
+/// whatever the file is called
+///
+/// Loading needs no such hint: [`load_file`] and [`load_file_as`]
+/// recognize compressed files by their contents.
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::CharacterProgress;
+/// use daggerheart_engine::storage::{self, Compression, Format};
+///
+/// let path = std::env::temp_dir().join("daggerheart_compressed_doc.json");
+/// let mut progress = CharacterProgress::new();
+/// progress.add_experience(50);
+///
+/// let compression = Compression::Gzip;
+/// if compression.is_supported() {
+///     storage::save_file_compressed(&progress, &path, Format::Json, compression).unwrap();
+///     let loaded: CharacterProgress = storage::load_file(&path).unwrap();
+///     assert_eq!(loaded.experience, 50);
+/// } else {
+///     assert!(storage::save_file_compressed(&progress, &path, Format::Json, compression).is_err());
+/// }
+/// # std::fs::remove_file(&path).ok();
+/// ```
+pub fn save_file_compressed<T: Persist>(
+    value: &T,
+    path: impl AsRef<Path>,
+    format: Format,
+    compression: Compression,
+) -> Result<(), EngineError> {
+    let path = path.as_ref();
+    in_file(path, || {
+        let bytes = match format {
+            Format::Json => serde_json::to_vec_pretty(value)?,
+            _ => value.to_format(format)?.into_bytes(),
+        };
+        Ok(write_atomic(path, &compression.compress(&bytes)?)?)
+    })
+}
+
+/// Save a value, keeping the `backups` versions it replaces as `.bak`
+/// files
+///
+/// The file being replaced becomes [`backup_path`]`(path, 1)`, the one
+/// before it moves to generation 2, and so on; anything older than
+/// `backups` generations is deleted. With `backups` at 0 this is
+/// [`save_file_as`].
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::character::CharacterProgress;
+/// use daggerheart_engine::storage::{self, Format};
+///
+/// let path = std::env::temp_dir().join("daggerheart_backups_doc.json");
+/// let mut progress = CharacterProgress::new();
+/// storage::save_file_with_backups(&progress, &path, Format::Json, 2).unwrap();
+///
+/// progress.add_experience(50);
+/// storage::save_file_with_backups(&progress, &path, Format::Json, 2).unwrap();
+/// assert!(storage::backup_path(&path, 1).is_file());
+///
+/// // Undo the last save
+/// storage::restore_backup(&path, 1).unwrap();
+/// let loaded: CharacterProgress = storage::load_file(&path).unwrap();
+/// assert_eq!(loaded.experience, 0);
+/// # std::fs::remove_file(&path).ok();
+/// # std::fs::remove_file(storage::backup_path(&path, 1)).ok();
+/// ```
+pub fn save_file_with_backups<T: Persist>(
+    value: &T,
+    path: impl AsRef<Path>,
+    format: Format,
+    backups: usize,
+) -> Result<(), EngineError> {
+    let path = path.as_ref();
+    in_file(path, || Ok(rotate_backups(path, backups)?))?;
+    save_file_as(value, path, format)
+}
+
+/// Put a backup generation back in place of the file it was taken from
+///
+/// The backup itself is left as it is, so restoring can be repeated.
+pub fn restore_backup(path: impl AsRef<Path>, generation: usize) -> Result<(), EngineError> {
+    let path = path.as_ref();
+    let backup = backup_path(path, generation);
+    let bytes = in_file(&backup, || Ok(std::fs::read(&backup)?))?;
+    in_file(path, || Ok(write_atomic(path, &bytes)?))
+}
+
+/// Where [`save_file_with_backups`] keeps a backup generation of `path`,
+/// counting from 1 for the most recent
+///
+/// # Examples
+///
+/// ```
+/// use daggerheart_engine::storage::backup_path;
+/// use std::path::Path;
+///
+/// assert_eq!(backup_path("saves/grom.json", 2), Path::new("saves/grom.json.2.bak"));
+/// ```
+pub fn backup_path(path: impl AsRef<Path>, generation: usize) -> PathBuf {
+    with_suffix(path.as_ref(), &format!(".{}.bak", generation))
+}
+
+/// Write a file so that a crash leaves either the old contents or the
+/// new, never a mix
+///
+/// The bytes go to a temporary file beside `path`, are flushed to disk,
+/// and the temporary file is then renamed over `path`.
+pub fn write_atomic(path: impl AsRef<Path>, bytes: &[u8]) -> io::Result<()> {
+    let path = path.as_ref();
+    let temp = with_suffix(path, ".tmp");
+    let written = File::create(&temp).and_then(|mut file| {
+        file.write_all(bytes)?;
+        file.sync_all()
+    });
+    let result = written.and_then(|_| std::fs::rename(&temp, path));
+    if result.is_err() {
+        std::fs::remove_file(&temp).ok();
+    }
+    result
+}
+
+/// Shift each backup of `path` a generation older and copy `path` in as
+/// the newest
+fn rotate_backups(path: &Path, backups: usize) -> io::Result<()> {
+    if backups == 0 || !path.is_file() {
+        return Ok(());
+    }
+    let oldest = backup_path(path, backups);
+    if oldest.is_file() {
+        std::fs::remove_file(&oldest)?;
+    }
+    for generation in (1..backups).rev() {
+        let backup = backup_path(path, generation);
+        if backup.is_file() {
+            std::fs::rename(&backup, backup_path(path, generation + 1))?;
+        }
+    }
+    std::fs::copy(path, backup_path(path, 1))?;
+    Ok(())
+}
+
+/// `path` with `suffix` added to its file name
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut name = path.file_name().map(OsString::from).unwrap_or_default();
+    name.push(suffix);
+    path.with_file_name(name)
+}
+
+/// Load a value from a file on disk in a specific format
+pub fn load_file_as<T: Persist>(path: impl AsRef<Path>, format: Format) -> Result<T, EngineError> {
+    let path = path.as_ref();
+    match format {
+        Format::Json => load_file(path),
+        _ => in_file(path, || {
+            let bytes = compression::decompressed(std::fs::read(path)?)?;
+            let text = String::from_utf8(bytes)
+                .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+            Ok(T::from_format(&text, format)?)
+        }),
+    }
+}
+
+/// Run a file operation, naming the file in any error
+fn in_file<T>(
+    path: &Path,
+    operation: impl FnOnce() -> Result<T, EngineError>,
+) -> Result<T, EngineError> {
+    operation().map_err(|error| error.in_file(path))
+}
+
+/// Storage that keeps everything in memory
+///
+/// Useful for tests, WASM builds, and servers that persist elsewhere.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MemoryStorage {
+    entries: BTreeMap<String, Vec<u8>>,
+}
+
+impl MemoryStorage {
+    /// Create an empty in-memory store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn write(&mut self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        self.entries.insert(key.to_string(), bytes.to_vec());
+        Ok(())
+    }
+
+    fn read(&self, key: &str) -> io::Result<Vec<u8>> {
+        self.entries.get(key).cloned().ok_or_else(|| not_found(key))
+    }
+
+    fn exists(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    fn remove(&mut self, key: &str) -> io::Result<()> {
+        self.entries
+            .remove(key)
+            .map(|_| ())
+            .ok_or_else(|| not_found(key))
+    }
+
+    fn keys(&self) -> io::Result<Vec<String>> {
+        Ok(self.entries.keys().cloned().collect())
+    }
+}
+
+/// Storage that keeps each key as a `<key>.json` file in a directory
+///
+/// Files can be written compressed with
+/// [`with_compression`](DirectoryStorage::with_compression); they keep
+/// their names, and compressed and plain files load alike.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DirectoryStorage {
+    root: PathBuf,
+    compression: Compression,
+}
+
+impl DirectoryStorage {
+    /// Use `root` as the store, creating it if needed
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use daggerheart_engine::storage::{DirectoryStorage, Storage};
+    ///
+    /// let storage = DirectoryStorage::new("saves").unwrap();
+    /// for key in storage.keys().unwrap() {
+    ///     println!("{}", key);
+    /// }
+    /// ```
+    pub fn new(root: impl Into<PathBuf>) -> io::Result<Self> {
+        let root = root.into();
+        std::fs::create_dir_all(&root)?;
+        Ok(Self {
+            root,
+            compression: Compression::None,
+        })
+    }
+
+    /// Compress everything written from now on
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Directory backing this store
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    fn path_for(&self, key: &str) -> io::Result<PathBuf> {
+        let valid = !key.is_empty()
+            && !key.starts_with('.')
+            && key
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.'));
+        if !valid {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid storage key: {:?}", key),
+            ));
+        }
+        Ok(self.root.join(format!("{}.json", key)))
+    }
+}
+
+impl Storage for DirectoryStorage {
+    fn write(&mut self, key: &str, bytes: &[u8]) -> io::Result<()> {
+        write_atomic(self.path_for(key)?, &self.compression.compress(bytes)?)
+    }
+
+    fn read(&self, key: &str) -> io::Result<Vec<u8>> {
+        compression::decompressed(std::fs::read(self.path_for(key)?)?)
+    }
+
+    fn exists(&self, key: &str) -> bool {
+        self.path_for(key).map(|p| p.is_file()).unwrap_or(false)
+    }
+
+    fn remove(&mut self, key: &str) -> io::Result<()> {
+        std::fs::remove_file(self.path_for(key)?)
+    }
+
+    fn keys(&self) -> io::Result<Vec<String>> {
+        let mut keys = Vec::new();
+        for entry in std::fs::read_dir(&self.root)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) == Some("json") {
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    keys.push(stem.to_string());
+                }
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+}
+
+fn unsupported(format: Format) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::Unsupported,
+        format!(
+            "{} support is not enabled (build with the `{}` feature)",
+            format.extension().to_uppercase(),
+            format.extension()
+        ),
+    )
+}
+
+#[cfg(any(
+    feature = "yaml",
+    feature = "toml",
+    feature = "msgpack",
+    feature = "sqlite"
+))]
+fn invalid_data<E: std::error::Error + Send + Sync + 'static>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, error)
+}
+
+fn not_found(key: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::NotFound,
+        format!("No stored value for key {:?}", key),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::CharacterProgress;
+    use crate::combat::simulation::{CombatEncounter, Combatant, CombatantBuilder};
+    use crate::test_support::{goblin, hero};
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("daggerheart_storage_{}", name))
+    }
+
+    #[test]
+    fn test_memory_storage_roundtrip() {
+        let mut storage = MemoryStorage::new();
+        let mut progress = CharacterProgress::new();
+        progress.add_card("blade_strike");
+
+        progress.save_to(&mut storage, "progress").unwrap();
+        let loaded = CharacterProgress::load_from(&storage, "progress").unwrap();
+
+        assert_eq!(loaded, progress);
+        assert!(storage.exists("progress"));
+        assert_eq!(storage.keys().unwrap(), vec!["progress".to_string()]);
+    }
+
+    #[test]
+    fn test_memory_storage_missing_key() {
+        let mut storage = MemoryStorage::new();
+
+        let err = CharacterProgress::load_from(&storage, "nope").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(storage.remove("nope").is_err());
+    }
+
+    #[test]
+    fn test_write_and_read_streams() {
+        let combatant = hero("Grom");
+
+        let mut buffer = Vec::new();
+        combatant.write_to(&mut buffer).unwrap();
+        let loaded = Combatant::read_from(buffer.as_slice()).unwrap();
+
+        assert_eq!(loaded, combatant);
+    }
+
+    #[test]
+    fn test_read_invalid_data() {
+        let err = CharacterProgress::read_from("not json".as_bytes()).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn test_encounter_load_runs_migration() {
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(goblin("Goblin"));
+        encounter.add_combatant(
+            CombatantBuilder::adversary("Orc")
+                .with_hp(6)
+                .with_evasion(10)
+                .build()
+                .unwrap(),
+        );
+
+        let mut value = serde_json::to_value(&encounter).unwrap();
+        let object = value.as_object_mut().unwrap();
+        object.remove("next_id");
+        for combatant in object["combatants"].as_array_mut().unwrap() {
+            combatant.as_object_mut().unwrap().remove("id");
+        }
+
+        let mut storage = MemoryStorage::new();
+        storage
+            .write("legacy", value.to_string().as_bytes())
+            .unwrap();
+        let loaded = CombatEncounter::load_from(&storage, "legacy").unwrap();
+
+        assert_eq!(loaded.combatants[1].id.0, 1);
+    }
+
+    #[test]
+    fn test_directory_storage() {
+        let root = temp_dir("directory");
+        std::fs::remove_dir_all(&root).ok();
+        let mut storage = DirectoryStorage::new(&root).unwrap();
+
+        let progress = CharacterProgress::new();
+        progress.save_to(&mut storage, "hero").unwrap();
+
+        assert!(root.join("hero.json").is_file());
+        assert_eq!(storage.keys().unwrap(), vec!["hero".to_string()]);
+        assert_eq!(
+            CharacterProgress::load_from(&storage, "hero").unwrap(),
+            progress
+        );
+
+        storage.remove("hero").unwrap();
+        assert!(!storage.exists("hero"));
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_directory_storage_rejects_path_keys() {
+        let root = temp_dir("keys");
+        let mut storage = DirectoryStorage::new(&root).unwrap();
+
+        assert!(storage.write("../escape", b"{}").is_err());
+        assert!(storage.write("a/b", b"{}").is_err());
+        assert!(storage.write("", b"{}").is_err());
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_format_parsing() {
+        assert_eq!("YAML".parse::<Format>(), Ok(Format::Yaml));
+        assert_eq!("yml".parse::<Format>(), Ok(Format::Yaml));
+        assert_eq!("toml".parse::<Format>(), Ok(Format::Toml));
+        assert!("xml".parse::<Format>().is_err());
+        assert_eq!(Format::from_path("a/b/hero.YML"), Format::Yaml);
+    }
+
+    #[test]
+    fn test_json_format_roundtrip() {
+        let progress = CharacterProgress::new();
+        let text = progress.to_format(Format::Json).unwrap();
+        let loaded = CharacterProgress::from_format(&text, Format::Json).unwrap();
+        assert_eq!(loaded, progress);
+    }
+
+    #[cfg(not(feature = "yaml"))]
+    #[test]
+    fn test_yaml_unsupported_without_feature() {
+        let err = CharacterProgress::new()
+            .to_format(Format::Yaml)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+        assert!(!Format::Yaml.is_supported());
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_yaml_roundtrip() {
+        use crate::character::CharacterSheet;
+
+        let sheet = CharacterSheet::new(hero("Grom"));
+
+        let yaml = sheet.to_yaml().unwrap();
+        assert!(yaml.contains("name: Grom"));
+        assert_eq!(CharacterSheet::from_yaml(&yaml).unwrap(), sheet);
+
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(goblin("Goblin"));
+        encounter.start();
+        let loaded = CombatEncounter::from_yaml(&encounter.to_yaml().unwrap()).unwrap();
+        assert_eq!(loaded.events, encounter.events);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_roundtrip() {
+        use crate::cards::DomainCard;
+        use crate::character::CharacterSheet;
+        use crate::items::Shop;
+
+        let sheet = CharacterSheet::new(hero("Grom"));
+        let toml = sheet.to_toml().unwrap();
+        assert!(toml.contains("name = \"Grom\""));
+        assert_eq!(CharacterSheet::from_toml(&toml).unwrap(), sheet);
+
+        let mut encounter = CombatEncounter::new(5);
+        encounter.add_combatant(goblin("Goblin"));
+        encounter.start();
+        let loaded = CombatEncounter::from_toml(&encounter.to_toml().unwrap()).unwrap();
+        assert_eq!(loaded.events, encounter.events);
+
+        let shop = Shop::new("General Store");
+        let loaded = Shop::from_toml(&shop.to_toml().unwrap()).unwrap();
+        assert_eq!(loaded.name, shop.name);
+
+        let card = DomainCard::new(
+            "blade_strike",
+            "Blade Strike",
+            crate::character::Domain::Blade,
+            1,
+            "A swift sword strike",
+            crate::cards::ActionCost::Major,
+        );
+        let text = card.to_toml().unwrap();
+        assert_eq!(DomainCard::from_toml(&text).unwrap(), card);
+    }
+
+    #[test]
+    fn test_file_helpers() {
+        let root = temp_dir("files");
+        std::fs::create_dir_all(&root).unwrap();
+        let path = root.join("progress.json");
+
+        let progress = CharacterProgress::new();
+        save_file(&progress, &path).unwrap();
+        let loaded: CharacterProgress = load_file(&path).unwrap();
+
+        assert_eq!(loaded, progress);
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_file_errors_name_the_file() {
+        let root = temp_dir("file_errors");
+        std::fs::create_dir_all(&root).unwrap();
+        let missing = root.join("missing.json");
+        let corrupt = root.join("corrupt.json");
+        std::fs::write(&corrupt, "{\"level\": \"high\"}").unwrap();
+
+        let error = load_file::<CharacterProgress>(&missing).unwrap_err();
+        assert_eq!(error.path(), Some(missing.as_path()));
+        assert_eq!(error.io_kind(), Some(io::ErrorKind::NotFound));
+
+        let error = load_file::<CharacterProgress>(&corrupt).unwrap_err();
+        assert!(error.to_string().contains("corrupt.json"));
+        match error {
+            EngineError::File { source, .. } => {
+                assert!(matches!(*source, EngineError::Serialization(_)))
+            }
+            other => panic!("unexpected error: {:?}", other),
+        }
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_atomic_writes_leave_no_temporary_file() {
+        let root = temp_dir("atomic");
+        std::fs::create_dir_all(&root).unwrap();
+        let path = root.join("progress.json");
+        std::fs::write(&path, "old").unwrap();
+
+        save_file(&CharacterProgress::new(), &path).unwrap();
+        let names: Vec<_> = std::fs::read_dir(&root)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["progress.json"]);
+        assert!(load_file::<CharacterProgress>(&path).is_ok());
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_backups_rotate_and_restore() {
+        let root = temp_dir("backups");
+        std::fs::create_dir_all(&root).unwrap();
+        let path = root.join("progress.json");
+
+        let mut progress = CharacterProgress::new();
+        for experience in [10, 20, 30, 40] {
+            progress.experience = experience;
+            save_file_with_backups(&progress, &path, Format::Json, 2).unwrap();
+        }
+        let experience = |path: &Path| load_file::<CharacterProgress>(path).unwrap().experience;
+        assert_eq!(experience(&path), 40);
+        assert_eq!(experience(&backup_path(&path, 1)), 30);
+        assert_eq!(experience(&backup_path(&path, 2)), 20);
+        assert!(!backup_path(&path, 3).exists());
+
+        restore_backup(&path, 2).unwrap();
+        assert_eq!(experience(&path), 20);
+        let error = restore_backup(&path, 3).unwrap_err();
+        assert_eq!(error.path(), Some(backup_path(&path, 3).as_path()));
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn test_compressed_names_need_the_feature() {
+        let root = temp_dir("no_gzip");
+        std::fs::create_dir_all(&root).unwrap();
+        let path = root.join("progress.json.gz");
+
+        let error = save_file(&CharacterProgress::new(), &path).unwrap_err();
+        assert_eq!(error.path(), Some(path.as_path()));
+        assert!(!path.exists());
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn test_compression_follows_the_file_name() {
+        let root = temp_dir("gzip");
+        std::fs::create_dir_all(&root).unwrap();
+        let mut progress = CharacterProgress::new();
+        progress.add_card("blade_strike");
+
+        let path = root.join("progress.json.gz");
+        save_file(&progress, &path).unwrap();
+        let bytes = std::fs::read(&path).unwrap();
+        assert_eq!(Compression::detect(&bytes), Compression::Gzip);
+        assert_eq!(load_file::<CharacterProgress>(&path).unwrap(), progress);
+
+        // Renamed, it still loads
+        let renamed = root.join("progress.json");
+        std::fs::rename(&path, &renamed).unwrap();
+        assert_eq!(load_file::<CharacterProgress>(&renamed).unwrap(), progress);
+
+        let mut storage = DirectoryStorage::new(root.join("store"))
+            .unwrap()
+            .with_compression(Compression::Gzip);
+        progress.save_to(&mut storage, "progress").unwrap();
+        assert_eq!(storage.keys().unwrap(), vec!["progress".to_string()]);
+        assert_eq!(
+            CharacterProgress::load_from(&storage, "progress").unwrap(),
+            progress
+        );
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[cfg(all(feature = "zstd", feature = "yaml"))]
+    #[test]
+    fn test_compressed_yaml_roundtrip() {
+        let root = temp_dir("zstd_yaml");
+        std::fs::create_dir_all(&root).unwrap();
+        let path = root.join("progress.yaml.zst");
+        let mut progress = CharacterProgress::new();
+        progress.add_experience(50);
+
+        assert_eq!(Format::from_path(&path), Format::Yaml);
+        save_file_as(&progress, &path, Format::from_path(&path)).unwrap();
+        let loaded: CharacterProgress = load_file_as(&path, Format::Yaml).unwrap();
+        assert_eq!(loaded, progress);
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[cfg(feature = "msgpack")]
+    mod msgpack {
+        use super::*;
+        use crate::campaign::Campaign;
+        use crate::cards::{ActionCost, DomainCard};
+        use crate::character::{CharacterSheet, Domain};
+        use crate::combat::adversary::{Adversary, AdversaryRole};
+        use crate::combat::replay::{EncounterCommand, Replay};
+        use crate::core::dice::DualityRoll;
+        use crate::items::{Shop, ShopItem};
+        use crate::test_support;
+        use std::fmt::Debug;
+
+        fn roundtrip<T: Persist>(value: &T) -> T {
+            T::from_msgpack(&value.to_msgpack().unwrap()).unwrap()
+        }
+
+        fn assert_roundtrip<T: Persist + PartialEq + Debug>(value: T) {
+            assert_eq!(roundtrip(&value), value);
+        }
+
+        fn create_test_encounter() -> CombatEncounter {
+            let mut encounter = test_support::create_test_encounter();
+            encounter.start();
+            encounter
+        }
+
+        #[test]
+        fn test_combatant() {
+            assert_roundtrip(hero("Grom"));
+        }
+
+        #[test]
+        fn test_character_progress() {
+            let mut progress = CharacterProgress::new();
+            progress.add_card("blade_strike");
+            assert_roundtrip(progress);
+        }
+
+        #[test]
+        fn test_character_sheet() {
+            assert_roundtrip(CharacterSheet::new(hero("Grom")));
+        }
+
+        #[test]
+        fn test_combat_encounter() {
+            let encounter = create_test_encounter();
+            let loaded = roundtrip(&encounter);
+            assert_eq!(loaded.combatants, encounter.combatants);
+            assert_eq!(loaded.turn_order, encounter.turn_order);
+            assert_eq!(loaded.events, encounter.events);
+        }
+
+        #[test]
+        fn test_replay() {
+            let mut replay = Replay::new(create_test_encounter(), 42);
+            replay.record(EncounterCommand::NextTurn);
+            let loaded = roundtrip(&replay);
+            assert_eq!(loaded.commands, replay.commands);
+            assert_eq!(loaded.seed, replay.seed);
+        }
+
+        #[test]
+        fn test_campaign() {
+            let mut campaign = Campaign::new("Test", 5);
+            campaign
+                .add_character(CharacterSheet::new(hero("Grom")))
+                .unwrap();
+            campaign.begin_encounter("Ambush", vec![]).unwrap();
+            campaign.log_roll(
+                "Grom",
+                "Smash",
+                DualityRoll::from_values(7, 3).with_modifier(2),
+            );
+
+            let loaded = roundtrip(&campaign);
+            assert_eq!(loaded.party, campaign.party);
+            assert_eq!(loaded.roll_log, campaign.roll_log);
+            assert!(loaded.encounter().is_some());
+        }
+
+        #[test]
+        fn test_shop() {
+            let mut shop = Shop::new("General Store");
+            shop.add_stock(ShopItem::new("rope", "Rope", 1, 2, 3));
+            let loaded = roundtrip(&shop);
+            assert_eq!(loaded.stock, shop.stock);
+        }
+
+        #[test]
+        fn test_adversary() {
+            assert_roundtrip(Adversary::new("Bandit", 2, AdversaryRole::Standard));
+        }
+
+        #[test]
+        fn test_domain_card() {
+            assert_roundtrip(DomainCard::new(
+                "blade_strike",
+                "Blade Strike",
+                Domain::Blade,
+                1,
+                "A swift sword strike",
+                ActionCost::Major,
+            ));
+        }
+
+        #[test]
+        fn test_smaller_than_json() {
+            let encounter = create_test_encounter();
+            let json = encounter.to_format(Format::Json).unwrap();
+            assert!(encounter.to_msgpack().unwrap().len() < json.len());
+        }
+
+        #[test]
+        fn test_memory_storage() {
+            let mut storage = MemoryStorage::new();
+            let encounter = create_test_encounter();
+
+            encounter.save_msgpack_to(&mut storage, "session").unwrap();
+            let loaded = CombatEncounter::load_msgpack_from(&storage, "session").unwrap();
+            assert_eq!(loaded.events, encounter.events);
+        }
+
+        #[test]
+        fn test_invalid_bytes() {
+            let err = CharacterProgress::from_msgpack(&[0xc1, 0x00]).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        }
+    }
+}
@@ -0,0 +1,553 @@
+//! SQLite-backed campaign store
+//!
+//! Long campaigns accumulate dozens of characters, NPCs and encounters.
+//! `CampaignDb` keeps them in relational tables so they can be queried
+//! (characters by class, encounters by date) instead of loading one large
+//! JSON file. Each row stores the full value as JSON alongside the columns
+//! used for lookups.
+
+use super::{invalid_data, Format, Persist};
+use crate::campaign::{Campaign, EncounterRecord, RollRecord};
+use crate::character::{CharacterSheet, Class};
+use crate::combat::adversary::Adversary;
+use crate::combat::simulation::CombatEncounter;
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS characters (
+    name TEXT PRIMARY KEY,
+    class TEXT NOT NULL,
+    ancestry TEXT NOT NULL,
+    level INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS adversaries (
+    name TEXT PRIMARY KEY,
+    tier INTEGER NOT NULL,
+    role TEXT NOT NULL,
+    data TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS encounters (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    played_at INTEGER NOT NULL,
+    player_victory INTEGER,
+    data TEXT NOT NULL
+);
+CREATE INDEX IF NOT EXISTS encounters_played_at ON encounters (played_at);
+CREATE TABLE IF NOT EXISTS rolls (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    roller TEXT NOT NULL,
+    description TEXT NOT NULL,
+    total INTEGER NOT NULL,
+    data TEXT NOT NULL
+);
+";
+
+/// A finished encounter as stored in the database
+#[derive(Debug, Clone)]
+pub struct StoredEncounter {
+    /// Row id, assigned on insert
+    pub id: i64,
+    /// Seconds since the Unix epoch
+    pub played_at: u64,
+    pub record: EncounterRecord,
+}
+
+/// Campaign data stored in an SQLite database
+pub struct CampaignDb {
+    conn: Connection,
+}
+
+impl CampaignDb {
+    /// Open (or create) a database file
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use daggerheart_engine::storage::sqlite::CampaignDb;
+    ///
+    /// let db = CampaignDb::open("campaign.db").unwrap();
+    /// ```
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::init(Connection::open(path).map_err(invalid_data)?)
+    }
+
+    /// Open a throwaway in-memory database
+    pub fn open_in_memory() -> io::Result<Self> {
+        Self::init(Connection::open_in_memory().map_err(invalid_data)?)
+    }
+
+    fn init(conn: Connection) -> io::Result<Self> {
+        conn.execute_batch(SCHEMA).map_err(invalid_data)?;
+        Ok(Self { conn })
+    }
+
+    /// Insert or replace a character, keyed by name
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::character::{Ancestry, Attributes, CharacterSheet, Class};
+    /// use daggerheart_engine::combat::CombatantBuilder;
+    /// use daggerheart_engine::storage::sqlite::CampaignDb;
+    ///
+    /// let db = CampaignDb::open_in_memory().unwrap();
+    /// db.save_character(&CharacterSheet::new(
+    ///     CombatantBuilder::player("Grom", Class::Warrior, Ancestry::Orc)
+    ///         .with_attributes(Attributes::from_array([2, 1, 1, 0, 0, -1]).unwrap())
+    ///         .build()
+    ///         .unwrap(),
+    /// ))
+    /// .unwrap();
+    ///
+    /// let warriors = db.characters_by_class(Class::Warrior).unwrap();
+    /// assert_eq!(warriors[0].name(), "Grom");
+    /// ```
+    pub fn save_character(&self, sheet: &CharacterSheet) -> io::Result<()> {
+        insert_character(&self.conn, sheet)
+    }
+
+    /// Load a character by name
+    pub fn load_character(&self, name: &str) -> io::Result<Option<CharacterSheet>> {
+        self.conn
+            .query_row(
+                "SELECT data FROM characters WHERE name = ?1",
+                [name],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .map_err(invalid_data)?
+            .map(|data| CharacterSheet::from_format(&data, Format::Json))
+            .transpose()
+    }
+
+    /// Delete a character, returning whether one was stored
+    pub fn remove_character(&self, name: &str) -> io::Result<bool> {
+        let removed = self
+            .conn
+            .execute("DELETE FROM characters WHERE name = ?1", [name])
+            .map_err(invalid_data)?;
+        Ok(removed > 0)
+    }
+
+    /// Every character, ordered by name
+    pub fn characters(&self) -> io::Result<Vec<CharacterSheet>> {
+        self.query_persisted("SELECT data FROM characters ORDER BY name", [])
+    }
+
+    /// Characters of one class, ordered by name
+    pub fn characters_by_class(&self, class: Class) -> io::Result<Vec<CharacterSheet>> {
+        self.query_persisted(
+            "SELECT data FROM characters WHERE class = ?1 ORDER BY name",
+            [class.to_string()],
+        )
+    }
+
+    /// Insert or replace an adversary stat block, keyed by name
+    pub fn save_adversary(&self, adversary: &Adversary) -> io::Result<()> {
+        self.conn
+            .execute(
+                "INSERT OR REPLACE INTO adversaries (name, tier, role, data)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    adversary.name,
+                    adversary.tier,
+                    adversary.role.to_string(),
+                    to_json(adversary)?,
+                ],
+            )
+            .map_err(invalid_data)?;
+        Ok(())
+    }
+
+    /// Load an adversary by name
+    pub fn load_adversary(&self, name: &str) -> io::Result<Option<Adversary>> {
+        self.conn
+            .query_row(
+                "SELECT data FROM adversaries WHERE name = ?1",
+                [name],
+                |row| row.get::<_, String>(0),
+            )
+            .optional()
+            .map_err(invalid_data)?
+            .map(|data| Adversary::from_format(&data, Format::Json))
+            .transpose()
+    }
+
+    /// Adversaries of one tier, ordered by name
+    pub fn adversaries_by_tier(&self, tier: u8) -> io::Result<Vec<Adversary>> {
+        self.query_persisted(
+            "SELECT data FROM adversaries WHERE tier = ?1 ORDER BY name",
+            [tier],
+        )
+    }
+
+    /// Store a finished encounter, timestamped now
+    pub fn record_encounter(&self, record: &EncounterRecord) -> io::Result<i64> {
+        self.record_encounter_at(record, now())
+    }
+
+    /// Store a finished encounter played at `played_at` (Unix seconds)
+    pub fn record_encounter_at(&self, record: &EncounterRecord, played_at: u64) -> io::Result<i64> {
+        insert_encounter(&self.conn, record, played_at)
+    }
+
+    /// Every stored encounter, oldest first
+    pub fn encounters(&self) -> io::Result<Vec<StoredEncounter>> {
+        self.encounters_between(0, u64::MAX)
+    }
+
+    /// Encounters played between `from` and `to` (inclusive), oldest first
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use daggerheart_engine::campaign::EncounterRecord;
+    /// use daggerheart_engine::combat::CombatEncounter;
+    /// use daggerheart_engine::storage::sqlite::CampaignDb;
+    ///
+    /// let db = CampaignDb::open_in_memory().unwrap();
+    /// let record = EncounterRecord {
+    ///     name: "Ambush".to_string(),
+    ///     encounter: CombatEncounter::new(5),
+    /// };
+    /// db.record_encounter_at(&record, 100).unwrap();
+    /// db.record_encounter_at(&record, 200).unwrap();
+    ///
+    /// assert_eq!(db.encounters_between(150, 250).unwrap().len(), 1);
+    /// ```
+    pub fn encounters_between(&self, from: u64, to: u64) -> io::Result<Vec<StoredEncounter>> {
+        let to = i64::try_from(to).unwrap_or(i64::MAX);
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT id, played_at, name, data FROM encounters
+                 WHERE played_at BETWEEN ?1 AND ?2 ORDER BY played_at, id",
+            )
+            .map_err(invalid_data)?;
+        let rows = statement
+            .query_map(
+                params![i64::try_from(from).unwrap_or(i64::MAX), to],
+                |row| {
+                    Ok((
+                        row.get::<_, i64>(0)?,
+                        row.get::<_, i64>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                    ))
+                },
+            )
+            .map_err(invalid_data)?;
+
+        rows.map(|row| {
+            let (id, played_at, name, data) = row.map_err(invalid_data)?;
+            Ok(StoredEncounter {
+                id,
+                played_at: played_at as u64,
+                record: EncounterRecord {
+                    name,
+                    encounter: CombatEncounter::from_format(&data, Format::Json)?,
+                },
+            })
+        })
+        .collect()
+    }
+
+    /// Append a duality roll to the log
+    pub fn log_roll(&self, record: &RollRecord) -> io::Result<()> {
+        insert_roll(&self.conn, record)
+    }
+
+    /// Rolls made by one character, in the order they were logged
+    pub fn rolls_by(&self, roller: &str) -> io::Result<Vec<RollRecord>> {
+        let mut statement = self
+            .conn
+            .prepare("SELECT data FROM rolls WHERE roller = ?1 ORDER BY id")
+            .map_err(invalid_data)?;
+        let rows = statement
+            .query_map([roller], data_column)
+            .map_err(invalid_data)?;
+        rows.map(|row| Ok(serde_json::from_str(&row.map_err(invalid_data)?)?))
+            .collect()
+    }
+
+    /// Copy a campaign's party, finished encounters and roll log into the
+    /// database in a single transaction
+    ///
+    /// Encounters are stamped with the import time.
+    pub fn import_campaign(&mut self, campaign: &Campaign) -> io::Result<()> {
+        let transaction = self.conn.transaction().map_err(invalid_data)?;
+        let played_at = now();
+        for sheet in &campaign.party {
+            insert_character(&transaction, sheet)?;
+        }
+        for record in &campaign.completed {
+            insert_encounter(&transaction, record, played_at)?;
+        }
+        for roll in &campaign.roll_log {
+            insert_roll(&transaction, roll)?;
+        }
+        transaction.commit().map_err(invalid_data)
+    }
+
+    fn query_persisted<T: Persist, P: rusqlite::Params>(
+        &self,
+        sql: &str,
+        params: P,
+    ) -> io::Result<Vec<T>> {
+        let mut statement = self.conn.prepare(sql).map_err(invalid_data)?;
+        let rows = statement
+            .query_map(params, data_column)
+            .map_err(invalid_data)?;
+        rows.map(|row| T::from_format(&row.map_err(invalid_data)?, Format::Json))
+            .collect()
+    }
+}
+
+fn insert_character(conn: &Connection, sheet: &CharacterSheet) -> io::Result<()> {
+    let combatant = &sheet.combatant;
+    conn.execute(
+        "INSERT OR REPLACE INTO characters (name, class, ancestry, level, data)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            combatant.name.as_str(),
+            combatant.class().map(|c| c.to_string()).unwrap_or_default(),
+            combatant
+                .ancestry()
+                .map(|a| a.to_string())
+                .unwrap_or_default(),
+            sheet.progress.level,
+            to_json(sheet)?,
+        ],
+    )
+    .map_err(invalid_data)?;
+    Ok(())
+}
+
+fn insert_encounter(
+    conn: &Connection,
+    record: &EncounterRecord,
+    played_at: u64,
+) -> io::Result<i64> {
+    conn.execute(
+        "INSERT INTO encounters (name, played_at, player_victory, data)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![
+            record.name,
+            i64::try_from(played_at).unwrap_or(i64::MAX),
+            record.player_victory(),
+            to_json(&record.encounter)?,
+        ],
+    )
+    .map_err(invalid_data)?;
+    Ok(conn.last_insert_rowid())
+}
+
+fn insert_roll(conn: &Connection, record: &RollRecord) -> io::Result<()> {
+    conn.execute(
+        "INSERT INTO rolls (roller, description, total, data) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            record.roller,
+            record.description,
+            record.result.total,
+            serde_json::to_string(record)?,
+        ],
+    )
+    .map_err(invalid_data)?;
+    Ok(())
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn data_column(row: &Row) -> rusqlite::Result<String> {
+    row.get(0)
+}
+
+fn to_json<T: Persist>(value: &T) -> io::Result<String> {
+    Ok(serde_json::to_string(value)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::character::Ancestry;
+    use crate::combat::adversary::AdversaryRole;
+    use crate::combat::simulation::CombatantBuilder;
+    use crate::core::dice::DualityRoll;
+    use crate::test_support::player;
+
+    fn create_test_sheet(name: &str, class: Class) -> CharacterSheet {
+        CharacterSheet::new(player(name, class, Ancestry::Human).build().unwrap())
+    }
+
+    fn create_test_record(name: &str) -> EncounterRecord {
+        EncounterRecord {
+            name: name.to_string(),
+            encounter: CombatEncounter::new(5),
+        }
+    }
+
+    #[test]
+    fn test_character_roundtrip() {
+        let db = CampaignDb::open_in_memory().unwrap();
+        let mut sheet = create_test_sheet("Grom", Class::Warrior);
+        sheet.progress.add_card("blade_strike");
+        db.save_character(&sheet).unwrap();
+
+        assert_eq!(db.load_character("Grom").unwrap(), Some(sheet));
+        assert_eq!(db.load_character("Nobody").unwrap(), None);
+    }
+
+    #[test]
+    fn test_save_character_replaces_by_name() {
+        let db = CampaignDb::open_in_memory().unwrap();
+        let mut sheet = create_test_sheet("Grom", Class::Warrior);
+        db.save_character(&sheet).unwrap();
+        sheet.progress.level = 2;
+        db.save_character(&sheet).unwrap();
+
+        let characters = db.characters().unwrap();
+        assert_eq!(characters.len(), 1);
+        assert_eq!(characters[0].progress.level, 2);
+    }
+
+    #[test]
+    fn test_characters_by_class() {
+        let db = CampaignDb::open_in_memory().unwrap();
+        db.save_character(&create_test_sheet("Zed", Class::Rogue))
+            .unwrap();
+        db.save_character(&create_test_sheet("Grom", Class::Warrior))
+            .unwrap();
+        db.save_character(&create_test_sheet("Ash", Class::Rogue))
+            .unwrap();
+
+        let rogues: Vec<String> = db
+            .characters_by_class(Class::Rogue)
+            .unwrap()
+            .iter()
+            .map(|sheet| sheet.name().to_string())
+            .collect();
+        assert_eq!(rogues, vec!["Ash", "Zed"]);
+        assert!(db.characters_by_class(Class::Wizard).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_remove_character() {
+        let db = CampaignDb::open_in_memory().unwrap();
+        db.save_character(&create_test_sheet("Grom", Class::Warrior))
+            .unwrap();
+
+        assert!(db.remove_character("Grom").unwrap());
+        assert!(!db.remove_character("Grom").unwrap());
+        assert!(db.characters().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_adversaries_by_tier() {
+        let db = CampaignDb::open_in_memory().unwrap();
+        let bandit = Adversary::new("Bandit", 1, AdversaryRole::Standard);
+        db.save_adversary(&bandit).unwrap();
+        db.save_adversary(&Adversary::new("Dragon", 3, AdversaryRole::Solo))
+            .unwrap();
+
+        assert_eq!(db.load_adversary("Bandit").unwrap(), Some(bandit));
+        assert_eq!(db.adversaries_by_tier(3).unwrap()[0].name, "Dragon");
+        assert!(db.adversaries_by_tier(2).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_encounters_ordered_by_date() {
+        let db = CampaignDb::open_in_memory().unwrap();
+        db.record_encounter_at(&create_test_record("Later"), 300)
+            .unwrap();
+        db.record_encounter_at(&create_test_record("First"), 100)
+            .unwrap();
+        db.record_encounter_at(&create_test_record("Middle"), 200)
+            .unwrap();
+
+        let names: Vec<String> = db
+            .encounters()
+            .unwrap()
+            .into_iter()
+            .map(|stored| stored.record.name)
+            .collect();
+        assert_eq!(names, vec!["First", "Middle", "Later"]);
+
+        let between = db.encounters_between(150, 300).unwrap();
+        assert_eq!(between.len(), 2);
+        assert_eq!(between[0].played_at, 200);
+    }
+
+    #[test]
+    fn test_rolls_by_roller() {
+        let db = CampaignDb::open_in_memory().unwrap();
+        let roll = |roller: &str, description: &str| RollRecord {
+            roller: roller.to_string(),
+            description: description.to_string(),
+            result: DualityRoll::from_values(7, 3).with_modifier(1),
+        };
+        db.log_roll(&roll("Grom", "Smash")).unwrap();
+        db.log_roll(&roll("Ash", "Sneak")).unwrap();
+        db.log_roll(&roll("Grom", "Parry")).unwrap();
+
+        let rolls = db.rolls_by("Grom").unwrap();
+        assert_eq!(rolls.len(), 2);
+        assert_eq!(rolls[1], roll("Grom", "Parry"));
+    }
+
+    #[test]
+    fn test_import_campaign() {
+        let mut campaign = Campaign::new("Test", 5);
+        campaign
+            .add_character(create_test_sheet("Grom", Class::Warrior))
+            .unwrap();
+        campaign
+            .begin_encounter(
+                "Ambush",
+                vec![CombatantBuilder::adversary("Goblin")
+                    .with_hp(4)
+                    .with_evasion(10)
+                    .build()
+                    .unwrap()],
+            )
+            .unwrap();
+        campaign.finish_encounter().unwrap();
+        campaign.log_roll(
+            "Grom",
+            "Smash",
+            DualityRoll::from_values(7, 3).with_modifier(2),
+        );
+
+        let mut db = CampaignDb::open_in_memory().unwrap();
+        db.import_campaign(&campaign).unwrap();
+
+        assert_eq!(db.characters().unwrap().len(), 1);
+        assert_eq!(db.encounters().unwrap()[0].record.name, "Ambush");
+        assert_eq!(db.rolls_by("Grom").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_file_database_persists() {
+        let path = std::env::temp_dir().join("daggerheart_campaign_test.db");
+        std::fs::remove_file(&path).ok();
+
+        {
+            let db = CampaignDb::open(&path).unwrap();
+            db.save_character(&create_test_sheet("Grom", Class::Warrior))
+                .unwrap();
+        }
+        let db = CampaignDb::open(&path).unwrap();
+        assert!(db.load_character("Grom").unwrap().is_some());
+
+        std::fs::remove_file(&path).ok();
+    }
+}
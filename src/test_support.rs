@@ -0,0 +1,34 @@
+//! Fixtures shared by the unit tests
+
+use crate::character::{Ancestry, Attributes, Class};
+use crate::combat::simulation::{CombatEncounter, Combatant, CombatantBuilder};
+
+/// A player with the standard modifier spread, ready for more options
+pub(crate) fn player(name: &str, class: Class, ancestry: Ancestry) -> CombatantBuilder {
+    CombatantBuilder::player(name, class, ancestry)
+        .with_attributes(Attributes::from_array(Attributes::STANDARD_MODIFIERS).unwrap())
+}
+
+/// A human Warrior with the standard modifier spread
+pub(crate) fn hero(name: &str) -> Combatant {
+    player(name, Class::Warrior, Ancestry::Human)
+        .build()
+        .unwrap()
+}
+
+/// A goblin with 4 HP and Evasion 10
+pub(crate) fn goblin(name: &str) -> Combatant {
+    CombatantBuilder::adversary(name)
+        .with_hp(4)
+        .with_evasion(10)
+        .build()
+        .unwrap()
+}
+
+/// A Warrior facing a goblin, not yet started
+pub(crate) fn create_test_encounter() -> CombatEncounter {
+    let mut encounter = CombatEncounter::new(5);
+    encounter.add_combatant(hero("Warrior"));
+    encounter.add_combatant(goblin("Goblin"));
+    encounter
+}